@@ -0,0 +1,96 @@
+//! Time and block-height source for escrow monitoring
+//!
+//! Deadlines (`expires_at`) and confirmation counts are evaluated against a
+//! [`ChainClock`] instead of calling `Utc::now()` directly, so that tests can
+//! substitute a simulated chain and advance time or mine blocks on demand.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use std::sync::Arc;
+
+/// Monero target block time in seconds (2 minutes)
+pub const MONERO_BLOCK_TIME_SECS: u64 = 120;
+
+/// Source of "now" and of the current chain height
+pub trait ChainClock: Send + Sync {
+    /// Current time (UTC)
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Current blockchain height, if this clock tracks the chain
+    ///
+    /// `None` means confirmations must be taken from the wallet RPC as-is.
+    fn block_height(&self) -> Option<u64>;
+
+    /// Current time as stored in the database (naive UTC)
+    fn now_naive(&self) -> NaiveDateTime {
+        self.now().naive_utc()
+    }
+
+    /// Confirmations of a transaction mined at `tx_height`
+    ///
+    /// Returns `None` if the clock has no height or the transaction is still
+    /// in the mempool (`tx_height == 0`).
+    fn confirmations_at(&self, tx_height: u64) -> Option<u64> {
+        let tip = self.block_height()?;
+        if tx_height == 0 {
+            return None;
+        }
+        Some(tip.saturating_sub(tx_height) + 1)
+    }
+}
+
+/// Shared clock handle, as held by the monitoring services
+pub type SharedClock = Arc<dyn ChainClock>;
+
+/// Wall clock, no chain height (production default)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    /// `SystemClock` as a [`SharedClock`]
+    pub fn shared() -> SharedClock {
+        Arc::new(SystemClock)
+    }
+}
+
+impl ChainClock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn block_height(&self) -> Option<u64> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedClock(DateTime<Utc>, Option<u64>);
+
+    impl ChainClock for FixedClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.0
+        }
+
+        fn block_height(&self) -> Option<u64> {
+            self.1
+        }
+    }
+
+    #[test]
+    fn test_system_clock_has_no_height() {
+        let clock = SystemClock;
+        assert!(clock.block_height().is_none());
+        assert!(clock.confirmations_at(100).is_none());
+    }
+
+    #[test]
+    fn test_confirmations_at() {
+        let clock = FixedClock(Utc::now(), Some(1_010));
+        assert_eq!(clock.confirmations_at(1_010), Some(1));
+        assert_eq!(clock.confirmations_at(1_001), Some(10));
+        // Mempool transactions report height 0
+        assert_eq!(clock.confirmations_at(0), None);
+    }
+}
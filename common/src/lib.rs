@@ -3,6 +3,7 @@
 //! This crate contains shared types, error definitions, and utilities
 //! used across the entire Monero Marketplace application.
 
+pub mod clock;
 pub mod error;
 pub mod types;
 pub mod utils;
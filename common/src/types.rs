@@ -1,5 +1,6 @@
 //! Common types for Monero Marketplace
 
+use crate::error::Error;
 use crate::MONERO_RPC_URL;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -19,19 +20,172 @@ pub type EscrowId = String;
 /// User ID type
 pub type UserId = String;
 
-/// Represents the state of an escrow process.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub enum EscrowState {
-    /// The escrow has been created but not yet funded.
+/// Status of an escrow as stored by the server (`escrows.status`).
+///
+/// Single source of truth for the escrow lifecycle: every status change must
+/// be allowed by [`EscrowStatus::can_transition_to`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EscrowStatus {
+    /// Escrow recorded, multisig wallet setup in progress
     Created,
-    /// The buyer has funded the escrow.
+    /// Multisig address ready, waiting for the buyer's deposit
     Funded,
-    /// The seller has released the funds to the buyer.
-    Released,
-    /// The funds have been refunded to the buyer.
+    /// Part of the amount received, waiting for a top-up
+    Underfunded,
+    /// Deposit detected in the multisig wallet
+    Active,
+    /// More than the amount received; the surplus goes back to the buyer on release
+    Overfunded,
+    /// Payout to the vendor broadcast, waiting for confirmations
+    Releasing,
+    /// Payout to the vendor confirmed
+    Completed,
+    /// Refund to the buyer broadcast, waiting for confirmations
+    Refunding,
+    /// Refund to the buyer confirmed
     Refunded,
-    /// The escrow is in a disputed state, requiring arbiter intervention.
+    /// Dispute opened, waiting for the arbiter
     Disputed,
+    /// Arbiter ruled for the buyer (refund follows)
+    ResolvedBuyer,
+    /// Arbiter ruled for the vendor (release follows)
+    ResolvedVendor,
+    /// Arbiter split the funds (split payout follows)
+    ResolvedSplit,
+    /// Abandoned before any deposit
+    Cancelled,
+    /// Deadline passed before any deposit
+    Expired,
+}
+
+impl EscrowStatus {
+    /// Every status, in lifecycle order
+    pub const ALL: [EscrowStatus; 15] = [
+        EscrowStatus::Created,
+        EscrowStatus::Funded,
+        EscrowStatus::Underfunded,
+        EscrowStatus::Active,
+        EscrowStatus::Overfunded,
+        EscrowStatus::Releasing,
+        EscrowStatus::Completed,
+        EscrowStatus::Refunding,
+        EscrowStatus::Refunded,
+        EscrowStatus::Disputed,
+        EscrowStatus::ResolvedBuyer,
+        EscrowStatus::ResolvedVendor,
+        EscrowStatus::ResolvedSplit,
+        EscrowStatus::Cancelled,
+        EscrowStatus::Expired,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EscrowStatus::Created => "created",
+            EscrowStatus::Funded => "funded",
+            EscrowStatus::Underfunded => "underfunded",
+            EscrowStatus::Active => "active",
+            EscrowStatus::Overfunded => "overfunded",
+            EscrowStatus::Releasing => "releasing",
+            EscrowStatus::Completed => "completed",
+            EscrowStatus::Refunding => "refunding",
+            EscrowStatus::Refunded => "refunded",
+            EscrowStatus::Disputed => "disputed",
+            EscrowStatus::ResolvedBuyer => "resolved_buyer",
+            EscrowStatus::ResolvedVendor => "resolved_vendor",
+            EscrowStatus::ResolvedSplit => "resolved_split",
+            EscrowStatus::Cancelled => "cancelled",
+            EscrowStatus::Expired => "expired",
+        }
+    }
+
+    /// Transition table of the escrow lifecycle
+    pub fn can_transition_to(&self, target: EscrowStatus) -> bool {
+        use EscrowStatus::*;
+        matches!(
+            (self, target),
+            // Setup: multisig ready, or deposit seen by the monitor right away
+            (Created, Funded)
+                | (Created, Underfunded)
+                | (Created, Active)
+                | (Created, Overfunded)
+                | (Created, Cancelled)
+                | (Created, Expired)
+                // Waiting for the deposit
+                | (Funded, Underfunded)
+                | (Funded, Active)
+                | (Funded, Overfunded)
+                | (Funded, Disputed)
+                | (Funded, Cancelled)
+                | (Funded, Expired)
+                // Partial deposit: top-ups until the amount is covered
+                | (Underfunded, Active)
+                | (Underfunded, Overfunded)
+                | (Underfunded, Cancelled)
+                | (Underfunded, Expired)
+                // Deposit received
+                | (Active, Releasing)
+                | (Active, Refunding)
+                | (Active, Disputed)
+                | (Overfunded, Releasing)
+                | (Overfunded, Refunding)
+                | (Overfunded, Disputed)
+                // Payouts waiting for confirmations
+                | (Releasing, Completed)
+                | (Refunding, Refunded)
+                // Dispute: arbiter ruling, or an air-gapped decision already broadcast
+                | (Disputed, ResolvedBuyer)
+                | (Disputed, ResolvedVendor)
+                | (Disputed, ResolvedSplit)
+                | (Disputed, Completed)
+                | (Disputed, Refunded)
+                // Rulings are carried out by a payout
+                | (ResolvedBuyer, Refunding)
+                | (ResolvedVendor, Releasing)
+                | (ResolvedSplit, Releasing)
+        )
+    }
+
+    /// Check the move to `target` against the transition table
+    pub fn transition_to(&self, target: EscrowStatus) -> Result<EscrowStatus, Error> {
+        if !self.can_transition_to(target) {
+            return Err(Error::InvalidState(format!(
+                "Illegal escrow transition: {} -> {}",
+                self, target
+            )));
+        }
+        Ok(target)
+    }
+
+    /// Statuses reachable in one step
+    pub fn next_statuses(&self) -> Vec<EscrowStatus> {
+        Self::ALL
+            .into_iter()
+            .filter(|target| self.can_transition_to(*target))
+            .collect()
+    }
+
+    /// Terminal statuses have no way out
+    pub fn is_terminal(&self) -> bool {
+        self.next_statuses().is_empty()
+    }
+}
+
+impl std::fmt::Display for EscrowStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for EscrowStatus {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|status| status.as_str() == s)
+            .ok_or_else(|| Error::InvalidState(format!("Unknown escrow status: {}", s)))
+    }
 }
 
 /// Contains all the necessary data for an escrow agreement.
@@ -80,12 +234,58 @@ pub struct EscrowDisputed {
     pub disputed_by: UserId,
 }
 
+/// Event triggered by any other status change (releasing, refunding, resolved_*...).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscrowStatusChanged {
+    pub escrow_id: EscrowId,
+    pub from: EscrowStatus,
+    pub to: EscrowStatus,
+}
+
+/// Escrow lifecycle event, as stored in the escrow event log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EscrowEvent {
+    Created(EscrowCreated),
+    Funded(EscrowFunded),
+    Released(EscrowReleased),
+    Refunded(EscrowRefunded),
+    Disputed(EscrowDisputed),
+    StatusChanged(EscrowStatusChanged),
+}
+
+impl EscrowEvent {
+    /// Escrow this event belongs to
+    pub fn escrow_id(&self) -> &str {
+        match self {
+            EscrowEvent::Created(e) => &e.escrow_id,
+            EscrowEvent::Funded(e) => &e.escrow_id,
+            EscrowEvent::Released(e) => &e.escrow_id,
+            EscrowEvent::Refunded(e) => &e.escrow_id,
+            EscrowEvent::Disputed(e) => &e.escrow_id,
+            EscrowEvent::StatusChanged(e) => &e.escrow_id,
+        }
+    }
+
+    /// Event type name (matches the serialized `type` tag)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EscrowEvent::Created(_) => "created",
+            EscrowEvent::Funded(_) => "funded",
+            EscrowEvent::Released(_) => "released",
+            EscrowEvent::Refunded(_) => "refunded",
+            EscrowEvent::Disputed(_) => "disputed",
+            EscrowEvent::StatusChanged(_) => "status_changed",
+        }
+    }
+}
+
 /// Complete escrow information with state management
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Escrow {
     pub id: EscrowId,
     pub data: EscrowData,
-    pub state: EscrowState,
+    pub state: EscrowStatus,
     pub created_at: u64,
     pub updated_at: u64,
     pub funding_tx_hash: Option<TxHash>,
@@ -187,17 +387,6 @@ pub struct EscrowTransaction {
     pub updated_at: u64,
 }
 
-/// Escrow status
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub enum EscrowStatus {
-    Created,
-    Funded,
-    Disputed,
-    Released,
-    Refunded,
-    Cancelled,
-}
-
 /// Tor status information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TorStatus {
@@ -350,37 +539,22 @@ pub struct TransactionInfo {
     pub fee: Amount,
 }
 
+/// Result of checking a transaction proof (`check_tx_proof`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxProofCheck {
+    /// Proof signature is valid for the transaction, address and message
+    pub good: bool,
+    /// Amount the address received in the transaction
+    pub received: Amount,
+    /// Transaction is still in the pool
+    pub in_pool: bool,
+    pub confirmations: u64,
+}
+
 // ============================================================================
 // ESCROW IMPLEMENTATIONS
 // ============================================================================
 
-impl EscrowState {
-    /// Check if a state transition is valid
-    pub fn can_transition_to(&self, new_state: &EscrowState) -> bool {
-        use EscrowState::*;
-        matches!(
-            (self, new_state),
-            (Created, Funded)
-                | (Funded, Released)
-                | (Funded, Refunded)
-                | (Funded, Disputed)
-                | (Disputed, Released)
-                | (Disputed, Refunded)
-        )
-    }
-
-    /// Get the next possible states from current state
-    pub fn next_possible_states(&self) -> Vec<EscrowState> {
-        use EscrowState::*;
-        match self {
-            Created => vec![Funded],
-            Funded => vec![Released, Refunded, Disputed],
-            Disputed => vec![Released, Refunded],
-            Released | Refunded => vec![], // Terminal states
-        }
-    }
-}
-
 impl Escrow {
     /// Create a new escrow
     pub fn new(id: EscrowId, data: EscrowData) -> Self {
@@ -392,7 +566,7 @@ impl Escrow {
         Self {
             id,
             data,
-            state: EscrowState::Created,
+            state: EscrowStatus::Created,
             created_at: now,
             updated_at: now,
             funding_tx_hash: None,
@@ -402,49 +576,6 @@ impl Escrow {
             disputed_by: None,
         }
     }
-
-    /// Transition to a new state if valid
-    pub fn transition_to(&mut self, new_state: EscrowState) -> Result<(), String> {
-        if !self.state.can_transition_to(&new_state) {
-            return Err(format!(
-                "Invalid state transition from {:?} to {:?}",
-                self.state, new_state
-            ));
-        }
-
-        self.state = new_state;
-        self.updated_at = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-
-        Ok(())
-    }
-
-    /// Check if escrow is in a terminal state
-    pub fn is_terminal(&self) -> bool {
-        matches!(self.state, EscrowState::Released | EscrowState::Refunded)
-    }
-
-    /// Check if escrow can be funded
-    pub fn can_be_funded(&self) -> bool {
-        matches!(self.state, EscrowState::Created)
-    }
-
-    /// Check if escrow can be released
-    pub fn can_be_released(&self) -> bool {
-        matches!(self.state, EscrowState::Funded | EscrowState::Disputed)
-    }
-
-    /// Check if escrow can be refunded
-    pub fn can_be_refunded(&self) -> bool {
-        matches!(self.state, EscrowState::Funded | EscrowState::Disputed)
-    }
-
-    /// Check if escrow can be disputed
-    pub fn can_be_disputed(&self) -> bool {
-        matches!(self.state, EscrowState::Funded)
-    }
 }
 
 // ============================================================================
@@ -505,3 +636,55 @@ impl Checkpoint {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escrow_status_round_trips_as_str() {
+        for status in EscrowStatus::ALL {
+            assert_eq!(status.as_str().parse::<EscrowStatus>().ok(), Some(status));
+            assert_eq!(
+                serde_json::to_value(status).ok(),
+                Some(serde_json::Value::String(status.as_str().to_string()))
+            );
+        }
+        assert!("paid".parse::<EscrowStatus>().is_err());
+    }
+
+    #[test]
+    fn test_escrow_status_transition_table() {
+        use EscrowStatus::*;
+
+        assert!(Funded.transition_to(Active).is_ok());
+        assert!(ResolvedBuyer.transition_to(Refunding).is_ok());
+        assert!(Created.transition_to(Releasing).is_err());
+        // Payouts need the deposit, or a ruling, first
+        assert!(Funded.transition_to(Releasing).is_err());
+        assert!(Funded.transition_to(Refunding).is_err());
+        assert!(Disputed.transition_to(Cancelled).is_err());
+        assert!(Underfunded.transition_to(Overfunded).is_ok());
+        assert!(Underfunded.transition_to(Releasing).is_err());
+        assert_eq!(Overfunded.next_statuses(), Active.next_statuses());
+
+        let terminal: Vec<EscrowStatus> = EscrowStatus::ALL
+            .into_iter()
+            .filter(EscrowStatus::is_terminal)
+            .collect();
+        assert_eq!(terminal, vec![Completed, Refunded, Cancelled, Expired]);
+
+        // Every non-terminal status is reachable from Created
+        let mut reached = vec![Created];
+        let mut i = 0;
+        while i < reached.len() {
+            for next in reached[i].next_statuses() {
+                if !reached.contains(&next) {
+                    reached.push(next);
+                }
+            }
+            i += 1;
+        }
+        assert_eq!(reached.len(), EscrowStatus::ALL.len());
+    }
+}
@@ -1,12 +1,31 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
+/// Format d'avis d'origine : la signature ne couvre que l'avis lui-même
+pub const REVIEW_FORMAT_V1: &str = "1.0";
+
+/// Format d'avis lié au vendeur, à l'escrow et à la marketplace
+pub const REVIEW_FORMAT_V2: &str = "2.0";
+
+/// Fichier de réputation non signé
+pub const REPUTATION_FORMAT_V1: &str = "1.0";
+
+/// Fichier de réputation signé par le vendeur
+pub const REPUTATION_FORMAT_V2: &str = "2.0";
+
+/// Longueur maximale d'un commentaire d'avis ou d'une réponse du vendeur
+pub const MAX_COMMENT_LENGTH: usize = 500;
+
 /// Avis signé cryptographiquement par un acheteur
 ///
 /// Chaque avis est une preuve vérifiable qu'une transaction réelle
 /// a eu lieu et que l'acheteur a émis cet avis.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignedReview {
+    /// Version du format de l'avis ("1.0" si absente)
+    #[serde(default = "default_review_format")]
+    pub format_version: String,
+
     /// Transaction hash Monero (preuve on-chain)
     pub txid: String,
 
@@ -23,21 +42,175 @@ pub struct SignedReview {
     /// Clé publique de l'acheteur (ed25519, base64)
     pub buyer_pubkey: String,
 
+    /// Clé publique du vendeur évalué (format 2.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor_pubkey: Option<String>,
+
+    /// Escrow de la commande évaluée (format 2.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escrow_id: Option<String>,
+
+    /// Domaine de la marketplace où la commande a eu lieu (format 2.0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub marketplace_domain: Option<String>,
+
+    /// Signature de la version remplacée, pour un avis modifié (format 2.0)
+    ///
+    /// Couverte par la signature : chaque version désigne la précédente.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<String>,
+
     /// Signature cryptographique de l'avis
-    /// Signature = sign(sha256(txid || rating || comment || timestamp))
+    /// Signature = sign(sha256(message)), voir [`SignedReview::signed_message`]
+    pub signature: String,
+
+    /// Preuve on-chain du paiement de l'escrow (hors signature de l'acheteur)
+    ///
+    /// Ajoutée par la marketplace après vérification, et vérifiable par
+    /// n'importe qui à partir de la preuve elle-même.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payment_proof: Option<PaymentProof>,
+
+    /// Versions antérieures de l'avis, de la plus ancienne à la plus récente
+    ///
+    /// Chacune est signée par l'acheteur : l'historique ne peut pas être
+    /// réécrit, seulement prolongé.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub revisions: Vec<ReviewRevision>,
+
+    /// Réponse publique du vendeur (hors signature de l'acheteur)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reply: Option<ReviewReply>,
+}
+
+/// Version antérieure d'un avis modifié
+///
+/// Seuls la note, le commentaire et la date changent d'une version à
+/// l'autre ; le reste (transaction, acheteur, contexte) est celui de l'avis.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewRevision {
+    /// Note de cette version
+    #[serde(deserialize_with = "validate_rating")]
+    pub rating: u8,
+
+    /// Commentaire de cette version
+    pub comment: Option<String>,
+
+    /// Date de cette version
+    pub timestamp: DateTime<Utc>,
+
+    /// Signature de la version qu'elle remplaçait
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supersedes: Option<String>,
+
+    /// Signature de l'acheteur sur cette version
+    pub signature: String,
+}
+
+/// Réponse publique d'un vendeur à un avis
+///
+/// Signée avec la clé de réputation du vendeur, voir
+/// [`ReviewReply::signed_message`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewReply {
+    /// Signature de la version de l'avis à laquelle le vendeur répond
+    pub in_reply_to: String,
+
+    /// Texte de la réponse (max 500 chars)
+    pub comment: String,
+
+    /// Date de la réponse
+    pub timestamp: DateTime<Utc>,
+
+    /// Signature du vendeur = sign(sha256(message))
+    pub signature: String,
+}
+
+/// Contexte auquel la signature d'un avis 2.0 lie l'avis
+///
+/// Un avis 1.0 peut être copié dans le fichier de n'importe quel vendeur ;
+/// un avis 2.0 ne vérifie que pour ce vendeur, cet escrow et cette marketplace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewBinding {
+    /// Clé publique du vendeur évalué
+    pub vendor_pubkey: String,
+
+    /// Escrow de la commande évaluée
+    pub escrow_id: String,
+
+    /// Domaine de la marketplace (ex: "market.example.onion")
+    pub marketplace_domain: String,
+}
+
+/// Preuve qu'une transaction Monero a payé l'adresse multisig d'un escrow
+///
+/// `signature` est la preuve renvoyée par `get_tx_proof` (OutProofV2 de
+/// l'acheteur, ou InProofV2 du destinataire). Les données de transaction
+/// (clés publiques de transaction et sorties) sont publiques et peuvent
+/// être comparées à celles de n'importe quel explorateur pour ce `txid`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentProof {
+    /// Transaction prouvée (hex)
+    pub txid: String,
+
+    /// Adresse payée : l'adresse multisig de l'escrow
+    pub address: String,
+
+    /// Message lié à la preuve, voir [`ReviewBinding::payment_proof_message`]
+    pub message: String,
+
+    /// Preuve `OutProofV2...` ou `InProofV2...`
     pub signature: String,
+
+    /// Clé publique de la transaction puis clés additionnelles (hex)
+    pub tx_pubkeys: Vec<String>,
+
+    /// Sorties de la transaction, dans l'ordre
+    pub outputs: Vec<TxOutput>,
+
+    /// Montant reçu par l'adresse (piconeros)
+    pub amount: u64,
+}
+
+/// Sortie RingCT d'une transaction Monero
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxOutput {
+    /// Clé publique de la sortie (hex)
+    pub key: String,
+
+    /// Montant chiffré, 8 octets (hex)
+    pub encrypted_amount: String,
+
+    /// Engagement de Pedersen du montant (hex)
+    pub commitment: String,
+}
+
+impl ReviewBinding {
+    /// Message de la preuve de paiement de cet escrow
+    ///
+    /// Une preuve générée pour un autre escrow ou une autre marketplace ne
+    /// vérifie pas avec ce message.
+    pub fn payment_proof_message(&self) -> String {
+        format!(
+            "review-payment|{}|{}",
+            self.marketplace_domain, self.escrow_id
+        )
+    }
 }
 
 /// Fichier de réputation complet d'un vendeur
 ///
 /// C'est le fichier portable qui peut être exporté vers IPFS
 /// et importé sur n'importe quelle marketplace.
+///
+/// En 2.0, le vendeur signe le fichier entier (avis, statistiques et
+/// date de génération) avec la clé `vendor_pubkey`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VendorReputation {
     /// Version du format (pour compatibilité future)
-    pub format_version: String,  // "1.0"
+    pub format_version: String,  // "1.0" ou "2.0"
 
-    /// Clé publique du vendeur
+    /// Clé publique du vendeur (ed25519, base64 en 2.0)
     pub vendor_pubkey: String,
 
     /// Date de génération du fichier
@@ -48,6 +221,19 @@ pub struct VendorReputation {
 
     /// Statistiques pré-calculées
     pub stats: ReputationStats,
+
+    /// Issue des escrows du vendeur (absente des fichiers plus anciens)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcomes: Option<EscrowOutcomes>,
+
+    /// Score pondéré, recalculable à partir du fichier seul
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<ReputationScore>,
+
+    /// Signature du vendeur sur le fichier (format 2.0)
+    /// Signature = sign(sha256(signed_payload)), voir [`VendorReputation::signed_payload`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
 }
 
 /// Statistiques de réputation pré-calculées
@@ -72,8 +258,85 @@ pub struct ReputationStats {
     pub newest_review: DateTime<Utc>,
 }
 
+/// Issue des escrows d'un vendeur
+///
+/// Comptée par la marketplace qui génère le fichier, puis couverte par la
+/// signature du vendeur comme le reste du fichier.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscrowOutcomes {
+    /// Escrows financés (dépôt reçu)
+    pub funded: u32,
+
+    /// Dont passés par un litige
+    pub disputed: u32,
+
+    /// Dont remboursés à l'acheteur
+    pub refunded: u32,
+}
+
+/// Paramètres du score pondéré
+///
+/// Tout est entier (millièmes, piconeros) : le score se recalcule au bit
+/// près en WASM à partir des paramètres joints au fichier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoringParams {
+    /// Demi-vie du poids d'un avis, en jours (0 : pas de décroissance)
+    pub half_life_days: u32,
+
+    /// Note a priori, en millièmes d'étoile
+    pub prior_rating_milli: u32,
+
+    /// Poids de la note a priori, en millièmes d'avis
+    pub prior_weight_milli: u32,
+
+    /// Montant payé qui donne un poids de 1 (piconeros)
+    pub reference_amount: u64,
+
+    /// Poids minimal, et poids d'un avis sans preuve de paiement (millièmes)
+    pub min_value_weight_milli: u32,
+
+    /// Poids maximal d'un avis selon le montant payé (millièmes)
+    pub max_value_weight_milli: u32,
+}
+
+impl Default for ScoringParams {
+    fn default() -> Self {
+        Self {
+            half_life_days: 180,
+            prior_rating_milli: 3500,
+            prior_weight_milli: 5000,
+            reference_amount: 1_000_000_000_000, // 1 XMR
+            min_value_weight_milli: 250,
+            max_value_weight_milli: 4000,
+        }
+    }
+}
+
+/// Score pondéré d'un vendeur, voir `reputation_crypto::scoring`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReputationScore {
+    /// Paramètres avec lesquels le score a été calculé
+    pub params: ScoringParams,
+
+    /// Note bayésienne pondérée, en millièmes d'étoile (0 à 5000)
+    pub score_milli: u32,
+
+    /// Somme des poids des avis, en millièmes (nombre d'avis effectif)
+    pub effective_reviews_milli: u64,
+
+    /// Part des escrows financés passés par un litige (points de base)
+    pub dispute_rate_bps: Option<u32>,
+
+    /// Part des escrows financés remboursés à l'acheteur (points de base)
+    pub refund_rate_bps: Option<u32>,
+}
+
 // Validation Helpers
 
+fn default_review_format() -> String {
+    REVIEW_FORMAT_V1.to_string()
+}
+
 fn validate_rating<'de, D>(deserializer: D) -> Result<u8, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -86,13 +349,146 @@ where
 }
 
 impl SignedReview {
+    /// Message canonique signé par l'acheteur
+    ///
+    /// - 1.0 : `txid|rating|comment|timestamp`
+    /// - 2.0 : `2.0|vendor_pubkey|escrow_id|marketplace_domain|txid|rating|timestamp|comment`
+    /// - version modifiée (2.0 seulement) : `revision|supersedes|` suivi du message 2.0
+    ///
+    /// En 2.0 le commentaire, seul champ libre, est placé en dernier pour
+    /// qu'un `|` dans le texte ne puisse pas déplacer les autres champs.
+    pub fn signed_message(&self) -> Result<String, String> {
+        let message = self.version_message()?;
+        match &self.supersedes {
+            None => Ok(message),
+            Some(_) if self.format_version != REVIEW_FORMAT_V2 => {
+                Err("Only 2.0 reviews can be amended".to_string())
+            }
+            Some(previous) if previous.is_empty() || previous.contains('|') => {
+                Err(format!("Invalid superseded signature: {:?}", previous))
+            }
+            Some(previous) => Ok(format!("revision|{}|{}", previous, message)),
+        }
+    }
+
+    /// Message d'une version, sans le lien vers la précédente
+    fn version_message(&self) -> Result<String, String> {
+        let comment = self.comment.as_deref().unwrap_or("");
+        match self.format_version.as_str() {
+            REVIEW_FORMAT_V1 => Ok(format!(
+                "{}|{}|{}|{}",
+                self.txid,
+                self.rating,
+                comment,
+                self.timestamp.to_rfc3339()
+            )),
+            REVIEW_FORMAT_V2 => {
+                let binding = self
+                    .binding()
+                    .ok_or_else(|| "Review 2.0 without vendor, escrow or domain".to_string())?;
+                for field in [
+                    &binding.vendor_pubkey,
+                    &binding.escrow_id,
+                    &binding.marketplace_domain,
+                ] {
+                    if field.is_empty() || field.contains('|') {
+                        return Err(format!("Invalid review binding field: {:?}", field));
+                    }
+                }
+                Ok(format!(
+                    "{}|{}|{}|{}|{}|{}|{}|{}",
+                    REVIEW_FORMAT_V2,
+                    binding.vendor_pubkey,
+                    binding.escrow_id,
+                    binding.marketplace_domain,
+                    self.txid,
+                    self.rating,
+                    self.timestamp.to_rfc3339(),
+                    comment
+                ))
+            }
+            other => Err(format!("Unsupported review format version: {}", other)),
+        }
+    }
+
+    /// Version courante de l'avis, telle qu'elle sera gardée dans l'historique
+    pub fn current_revision(&self) -> ReviewRevision {
+        ReviewRevision {
+            rating: self.rating,
+            comment: self.comment.clone(),
+            timestamp: self.timestamp,
+            supersedes: self.supersedes.clone(),
+            signature: self.signature.clone(),
+        }
+    }
+
+    /// L'avis tel qu'il était à une version antérieure
+    ///
+    /// Sans historique, réponse ni preuve de paiement : seulement ce que
+    /// l'acheteur a signé à ce moment-là.
+    pub fn at_revision(&self, revision: &ReviewRevision) -> SignedReview {
+        SignedReview {
+            rating: revision.rating,
+            comment: revision.comment.clone(),
+            timestamp: revision.timestamp,
+            supersedes: revision.supersedes.clone(),
+            signature: revision.signature.clone(),
+            payment_proof: None,
+            revisions: Vec::new(),
+            reply: None,
+            ..self.clone()
+        }
+    }
+
+    /// L'avis remplacé par la version `revision`, la version courante
+    /// passant dans l'historique
+    ///
+    /// Ne vérifie rien : la nouvelle version doit désigner la courante dans
+    /// `supersedes` et être signée par l'acheteur.
+    pub fn amended(&self, revision: ReviewRevision) -> SignedReview {
+        let mut revisions = self.revisions.clone();
+        revisions.push(self.current_revision());
+        SignedReview {
+            rating: revision.rating,
+            comment: revision.comment,
+            timestamp: revision.timestamp,
+            supersedes: revision.supersedes,
+            signature: revision.signature,
+            revisions,
+            ..self.clone()
+        }
+    }
+
+    /// Contexte lié par la signature (None pour un avis 1.0)
+    pub fn binding(&self) -> Option<ReviewBinding> {
+        if self.format_version != REVIEW_FORMAT_V2 {
+            return None;
+        }
+        Some(ReviewBinding {
+            vendor_pubkey: self.vendor_pubkey.clone()?,
+            escrow_id: self.escrow_id.clone()?,
+            marketplace_domain: self.marketplace_domain.clone()?,
+        })
+    }
+
+    /// Vérifie que l'avis peut figurer dans le fichier de ce vendeur
+    ///
+    /// Un avis 1.0 ne désigne aucun vendeur et est accepté partout.
+    pub fn is_for_vendor(&self, vendor_pubkey: &str) -> bool {
+        match self.format_version.as_str() {
+            REVIEW_FORMAT_V1 => true,
+            _ => self.vendor_pubkey.as_deref() == Some(vendor_pubkey),
+        }
+    }
+
     /// Valide la longueur du commentaire
     pub fn validate_comment(&self) -> Result<(), String> {
         if let Some(ref comment) = self.comment {
-            if comment.len() > 500 {
+            if comment.len() > MAX_COMMENT_LENGTH {
                 return Err(format!(
-                    "Comment too long: {} chars (max 500)",
-                    comment.len()
+                    "Comment too long: {} chars (max {})",
+                    comment.len(),
+                    MAX_COMMENT_LENGTH
                 ));
             }
         }
@@ -100,12 +496,41 @@ impl SignedReview {
     }
 }
 
+impl ReviewReply {
+    /// Message canonique signé par le vendeur
+    ///
+    /// `reply|in_reply_to|timestamp|comment` : la réponse désigne la version
+    /// de l'avis par sa signature, elle ne peut pas être déplacée sur un
+    /// autre avis. Le commentaire est en dernier, comme pour les avis 2.0.
+    pub fn signed_message(&self) -> Result<String, String> {
+        if self.in_reply_to.is_empty() || self.in_reply_to.contains('|') {
+            return Err(format!("Invalid reply target: {:?}", self.in_reply_to));
+        }
+        if self.comment.trim().is_empty() {
+            return Err("Reply cannot be empty".to_string());
+        }
+        if self.comment.len() > MAX_COMMENT_LENGTH {
+            return Err(format!(
+                "Reply too long: {} chars (max {})",
+                self.comment.len(),
+                MAX_COMMENT_LENGTH
+            ));
+        }
+        Ok(format!(
+            "reply|{}|{}|{}",
+            self.in_reply_to,
+            self.timestamp.to_rfc3339(),
+            self.comment
+        ))
+    }
+}
+
 impl VendorReputation {
     /// Crée une nouvelle réputation vide
     pub fn new(vendor_pubkey: String) -> Self {
         let now = Utc::now();
         Self {
-            format_version: "1.0".to_string(),
+            format_version: REPUTATION_FORMAT_V1.to_string(),
             vendor_pubkey,
             generated_at: now,
             reviews: Vec::new(),
@@ -116,7 +541,28 @@ impl VendorReputation {
                 oldest_review: now,
                 newest_review: now,
             },
+            outcomes: None,
+            score: None,
+            signature: None,
+        }
+    }
+
+    /// Octets signés par le vendeur : le fichier en JSON compact, sans signature
+    ///
+    /// La sérialisation serde_json suit l'ordre des champs et est stable,
+    /// le même fichier donne donc les mêmes octets en Rust et en WASM.
+    pub fn signed_payload(&self) -> Result<Vec<u8>, String> {
+        if self.format_version != REPUTATION_FORMAT_V2 {
+            return Err(format!(
+                "Reputation format {} is not signed",
+                self.format_version
+            ));
         }
+        let unsigned = VendorReputation {
+            signature: None,
+            ..self.clone()
+        };
+        serde_json::to_vec(&unsigned).map_err(|e| format!("Failed to serialize reputation: {}", e))
     }
 }
 
@@ -127,12 +573,20 @@ mod tests {
     #[test]
     fn test_review_serialization() {
         let review = SignedReview {
+            format_version: REVIEW_FORMAT_V1.to_string(),
             txid: "abc123def456".to_string(),
             rating: 5,
             comment: Some("Excellent product!".to_string()),
             timestamp: Utc::now(),
             buyer_pubkey: "pubkey_base64_encoded".to_string(),
+            vendor_pubkey: None,
+            escrow_id: None,
+            marketplace_domain: None,
+            supersedes: None,
             signature: "signature_base64_encoded".to_string(),
+            payment_proof: None,
+            revisions: Vec::new(),
+            reply: None,
         };
 
         // Serialize to JSON
@@ -145,6 +599,103 @@ mod tests {
         assert_eq!(parsed.txid, "abc123def456");
     }
 
+    #[test]
+    fn test_review_without_version_is_v1() {
+        let json = r#"{
+            "txid": "abc123",
+            "rating": 4,
+            "comment": "a | b",
+            "timestamp": "2025-10-21T00:00:00Z",
+            "buyer_pubkey": "pub",
+            "signature": "sig"
+        }"#;
+
+        let review: SignedReview = serde_json::from_str(json).unwrap();
+        assert_eq!(review.format_version, REVIEW_FORMAT_V1);
+        assert!(review.binding().is_none());
+        assert!(review.is_for_vendor("any_vendor"));
+        assert_eq!(
+            review.signed_message().unwrap(),
+            "abc123|4|a | b|2025-10-21T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_v2_message_binds_vendor_escrow_domain() {
+        let mut review: SignedReview = serde_json::from_str(
+            r#"{
+                "format_version": "2.0",
+                "txid": "abc123",
+                "rating": 4,
+                "comment": "a | b",
+                "timestamp": "2025-10-21T00:00:00Z",
+                "buyer_pubkey": "pub",
+                "vendor_pubkey": "vendor",
+                "escrow_id": "escrow",
+                "marketplace_domain": "market.onion",
+                "signature": "sig"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            review.signed_message().unwrap(),
+            "2.0|vendor|escrow|market.onion|abc123|4|2025-10-21T00:00:00+00:00|a | b"
+        );
+        assert!(review.is_for_vendor("vendor"));
+        assert!(!review.is_for_vendor("other_vendor"));
+
+        review.escrow_id = None;
+        assert!(review.signed_message().is_err());
+
+        review.escrow_id = Some("esc|row".to_string());
+        assert!(review.signed_message().is_err());
+
+        review.format_version = "3.0".to_string();
+        assert!(review.signed_message().is_err());
+    }
+
+    #[test]
+    fn test_revision_and_reply_messages() {
+        let mut review: SignedReview = serde_json::from_str(
+            r#"{
+                "format_version": "2.0",
+                "txid": "abc123",
+                "rating": 5,
+                "comment": null,
+                "timestamp": "2025-10-22T00:00:00Z",
+                "buyer_pubkey": "pub",
+                "vendor_pubkey": "vendor",
+                "escrow_id": "escrow",
+                "marketplace_domain": "market.onion",
+                "supersedes": "old_sig",
+                "signature": "sig"
+            }"#,
+        )
+        .unwrap();
+
+        // Un message de version modifiée ne commence jamais comme un avis
+        assert_eq!(
+            review.signed_message().unwrap(),
+            "revision|old_sig|2.0|vendor|escrow|market.onion|abc123|5|2025-10-22T00:00:00+00:00|"
+        );
+        review.format_version = REVIEW_FORMAT_V1.to_string();
+        assert!(review.signed_message().is_err());
+
+        let mut reply = ReviewReply {
+            in_reply_to: "sig".to_string(),
+            comment: "Thanks | see you".to_string(),
+            timestamp: "2025-10-23T00:00:00Z".parse().unwrap(),
+            signature: "vendor_sig".to_string(),
+        };
+        assert_eq!(
+            reply.signed_message().unwrap(),
+            "reply|sig|2025-10-23T00:00:00+00:00|Thanks | see you"
+        );
+        reply.comment = "x".repeat(MAX_COMMENT_LENGTH + 1);
+        assert!(reply.signed_message().is_err());
+    }
+
     #[test]
     fn test_invalid_rating_rejected() {
         let json = r#"{
@@ -163,12 +714,20 @@ mod tests {
     #[test]
     fn test_comment_validation() {
         let mut review = SignedReview {
+            format_version: REVIEW_FORMAT_V1.to_string(),
             txid: "abc".to_string(),
             rating: 5,
             comment: Some("x".repeat(501)),  // 501 chars
             timestamp: Utc::now(),
             buyer_pubkey: "pub".to_string(),
+            vendor_pubkey: None,
+            escrow_id: None,
+            marketplace_domain: None,
+            supersedes: None,
             signature: "sig".to_string(),
+            payment_proof: None,
+            revisions: Vec::new(),
+            reply: None,
         };
 
         assert!(review.validate_comment().is_err());
@@ -184,5 +743,23 @@ mod tests {
         assert_eq!(reputation.format_version, "1.0");
         assert_eq!(reputation.reviews.len(), 0);
         assert_eq!(reputation.stats.total_reviews, 0);
+        assert!(reputation.signature.is_none());
+    }
+
+    #[test]
+    fn test_signed_payload_excludes_signature() {
+        let mut reputation = VendorReputation::new("vendor_pubkey_123".to_string());
+        assert!(reputation.signed_payload().is_err());
+
+        reputation.format_version = REPUTATION_FORMAT_V2.to_string();
+        let unsigned = reputation.signed_payload().unwrap();
+
+        reputation.signature = Some("sig".to_string());
+        let json = serde_json::to_string(&reputation).unwrap();
+        let parsed: VendorReputation = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.signed_payload().unwrap(), unsigned);
+
+        reputation.stats.total_reviews = 1;
+        assert_ne!(reputation.signed_payload().unwrap(), unsigned);
     }
 }
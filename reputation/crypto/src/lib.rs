@@ -1 +1,4 @@
+pub mod payment_proof;
 pub mod reputation;
+pub mod revision;
+pub mod scoring;
@@ -0,0 +1,629 @@
+//! Vérification des preuves de paiement Monero (OutProofV2 / InProofV2)
+//!
+//! Reprend `check_tx_proof` de wallet2 : la preuve montre que son auteur
+//! connaît la clé secrète de la transaction (preuve d'envoi) ou la clé
+//! d'audit du destinataire (preuve de réception). Le secret partagé qu'elle
+//! révèle permet ensuite de retrouver les sorties destinées à l'adresse et
+//! d'en déchiffrer les montants.
+//!
+//! Aucun appel réseau : la même vérification tourne sur le serveur et en
+//! WASM dans le navigateur, à partir des données publiques de la transaction
+//! jointes à la preuve.
+
+use anyhow::{Context, Result};
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::Scalar;
+use sha3::{Digest, Keccak256};
+
+use reputation_common::types::{PaymentProof, SignedReview, TxOutput};
+
+/// Préfixe d'une preuve d'envoi (clé secrète de transaction)
+pub const OUT_PROOF_V2: &str = "OutProofV2";
+
+/// Préfixe d'une preuve de réception (clé d'audit du destinataire)
+pub const IN_PROOF_V2: &str = "InProofV2";
+
+/// Séparateur de domaine des preuves V2 (`config::HASH_KEY_TXPROOF_V2`)
+const TXPROOF_V2_DOMAIN: &[u8] = b"TXPROOF_V2";
+
+/// Générateur H des engagements de Pedersen (`rct::H`)
+const PEDERSEN_H: [u8; 32] = [
+    0x8b, 0x65, 0x59, 0x70, 0x15, 0x37, 0x99, 0xaf, 0x2a, 0xea, 0xdc, 0x9f, 0xf1, 0xad, 0xd0, 0xea,
+    0x6c, 0x72, 0x51, 0xd5, 0x41, 0x54, 0xcf, 0xa9, 0x2c, 0x17, 0x3a, 0x0d, 0xd3, 0x9c, 0x1f, 0x94,
+];
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Taille encodée d'un bloc de 0 à 8 octets en base58 Monero
+const BASE58_ENCODED_BLOCK_SIZES: [usize; 9] = [0, 2, 3, 5, 6, 7, 9, 10, 11];
+
+/// Longueur base58 d'une clé publique (32 octets) et d'une signature (64 octets)
+const ENCODED_KEY_LEN: usize = 44;
+const ENCODED_SIGNATURE_LEN: usize = 88;
+
+/// Clés publiques d'une adresse Monero
+struct Address {
+    spend_key: [u8; 32],
+    view_key: [u8; 32],
+    is_subaddress: bool,
+}
+
+/// Point de la courbe avec son encodage d'origine (haché tel quel)
+#[derive(Clone, Copy)]
+struct Key {
+    bytes: [u8; 32],
+    point: EdwardsPoint,
+}
+
+impl Key {
+    fn from_bytes(bytes: [u8; 32]) -> Result<Self> {
+        let point = CompressedEdwardsY(bytes)
+            .decompress()
+            .ok_or_else(|| anyhow::anyhow!("Invalid curve point"))?;
+        Ok(Self { bytes, point })
+    }
+
+    fn from_hex(hex_key: &str) -> Result<Self> {
+        Self::from_bytes(decode_hex_32(hex_key)?)
+    }
+}
+
+/// Une signature de la preuve : secret partagé D et signature (c, r)
+struct ProofPart {
+    shared_secret: Key,
+    c: Scalar,
+    r: Scalar,
+}
+
+/// Vérifie la preuve et renvoie le montant reçu par l'adresse (piconeros)
+///
+/// # Errors
+/// - Preuve, adresse ou données de transaction mal formées
+/// - Aucune signature de la preuve ne vérifie
+pub fn payment_proof_received(proof: &PaymentProof) -> Result<u64> {
+    let txid = decode_hex_32(&proof.txid).context("Invalid txid")?;
+    let address = parse_address(&proof.address)?;
+    let (is_out, parts) = parse_proof(&proof.signature)?;
+
+    let tx_pubkeys = proof
+        .tx_pubkeys
+        .iter()
+        .map(|key| Key::from_hex(key))
+        .collect::<Result<Vec<_>>>()
+        .context("Invalid transaction public key")?;
+    if tx_pubkeys.len() != parts.len() {
+        anyhow::bail!(
+            "Proof has {} signature(s) for {} transaction public key(s)",
+            parts.len(),
+            tx_pubkeys.len()
+        );
+    }
+
+    // Message signé : Hs(txid || message)
+    let mut hasher = Keccak256::new();
+    hasher.update(txid);
+    hasher.update(proof.message.as_bytes());
+    let prefix_hash: [u8; 32] = hasher.finalize().into();
+
+    let view_key = Key::from_bytes(address.view_key)?;
+    let spend_key = Key::from_bytes(address.spend_key)?;
+
+    // Dérivation 8·D de chaque signature valide
+    let derivations = parts
+        .iter()
+        .zip(&tx_pubkeys)
+        .map(|(part, tx_pubkey)| {
+            let good = if is_out {
+                let spend = address.is_subaddress.then_some(&spend_key);
+                check_tx_proof(&prefix_hash, tx_pubkey, &view_key, spend, part)
+            } else {
+                check_tx_proof(&prefix_hash, &view_key, tx_pubkey, None, part)
+            };
+            good.then(|| {
+                part.shared_secret
+                    .point
+                    .mul_by_cofactor()
+                    .compress()
+                    .to_bytes()
+            })
+        })
+        .collect::<Vec<_>>();
+    if derivations.iter().all(Option::is_none) {
+        anyhow::bail!("Invalid payment proof signature");
+    }
+
+    // Clé principale pour toutes les sorties, clé additionnelle i pour la sortie i
+    let (main, additional) = derivations.split_at(1);
+    let mut received = 0u64;
+    for (index, output) in proof.outputs.iter().enumerate() {
+        let candidates = [main[0], additional.get(index).copied().flatten()];
+        for derivation in candidates.into_iter().flatten() {
+            let shared = derivation_to_scalar(&derivation, index as u64);
+            if output_key(&shared, &spend_key.point) == decode_hex_32(&output.key)? {
+                received = received
+                    .checked_add(decode_amount(&shared, output)?)
+                    .ok_or_else(|| anyhow::anyhow!("Received amount overflow"))?;
+                break;
+            }
+        }
+    }
+
+    Ok(received)
+}
+
+/// Vérifie la preuve et le montant qu'elle annonce
+pub fn verify_payment_proof(proof: &PaymentProof) -> Result<bool> {
+    Ok(payment_proof_received(proof)? == proof.amount)
+}
+
+/// Vérifie la preuve de paiement jointe à un avis 2.0
+///
+/// La preuve doit porter sur le txid de l'avis, avec le message de son
+/// escrow. `Ok(false)` si l'avis n'a pas de preuve ou n'est pas lié.
+pub fn verify_review_payment(review: &SignedReview) -> Result<bool> {
+    let (Some(proof), Some(binding)) = (&review.payment_proof, review.binding()) else {
+        return Ok(false);
+    };
+    if !proof.txid.eq_ignore_ascii_case(&review.txid)
+        || proof.message != binding.payment_proof_message()
+    {
+        return Ok(false);
+    }
+    verify_payment_proof(proof)
+}
+
+/// `crypto::check_tx_proof` (version 2)
+///
+/// Vérifie que D = x·A pour le x tel que R = x·G (ou x·B pour une
+/// sous-adresse), sans révéler x.
+fn check_tx_proof(
+    prefix_hash: &[u8; 32],
+    r_key: &Key,
+    a_key: &Key,
+    b_key: Option<&Key>,
+    part: &ProofPart,
+) -> bool {
+    let d_key = &part.shared_secret;
+    if !d_key.point.is_torsion_free() {
+        return false;
+    }
+
+    // X = c·R + r·G (ou r·B), Y = c·D + r·A
+    let x = match b_key {
+        Some(b) => part.c * r_key.point + part.r * b.point,
+        None => EdwardsPoint::vartime_double_scalar_mul_basepoint(&part.c, &r_key.point, &part.r),
+    };
+    let y = part.c * d_key.point + part.r * a_key.point;
+
+    // c' = Hs(msg || D || X || Y || sep || R || A || B)
+    let mut hasher = Keccak256::new();
+    hasher.update(prefix_hash);
+    hasher.update(d_key.bytes);
+    hasher.update(x.compress().as_bytes());
+    hasher.update(y.compress().as_bytes());
+    hasher.update(Keccak256::digest(TXPROOF_V2_DOMAIN));
+    hasher.update(r_key.bytes);
+    hasher.update(a_key.bytes);
+    hasher.update(b_key.map(|b| b.bytes).unwrap_or([0u8; 32]));
+
+    hash_to_scalar(&hasher.finalize()) == part.c
+}
+
+/// Hs(dérivation || varint(index))
+fn derivation_to_scalar(derivation: &[u8; 32], index: u64) -> Scalar {
+    let mut data = derivation.to_vec();
+    let mut index = index;
+    while index >= 0x80 {
+        data.push((index as u8 & 0x7f) | 0x80);
+        index >>= 7;
+    }
+    data.push(index as u8);
+    hash_to_scalar(&Keccak256::digest(&data))
+}
+
+/// Clé publique de sortie : Hs(dérivation || index)·G + B
+fn output_key(shared: &Scalar, spend_key: &EdwardsPoint) -> [u8; 32] {
+    (EdwardsPoint::mul_base(shared) + spend_key)
+        .compress()
+        .to_bytes()
+}
+
+/// Déchiffre le montant d'une sortie (`ecdhDecode` v2)
+///
+/// Un montant dont l'engagement ne correspond pas compte pour zéro, comme
+/// dans wallet2.
+fn decode_amount(shared: &Scalar, output: &TxOutput) -> Result<u64> {
+    let encrypted: [u8; 8] = hex::decode(&output.encrypted_amount)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| anyhow::anyhow!("Invalid encrypted amount"))?;
+    let commitment = decode_hex_32(&output.commitment).context("Invalid commitment")?;
+
+    let mut hasher = Keccak256::new();
+    hasher.update(b"amount");
+    hasher.update(shared.as_bytes());
+    let amount_key = hasher.finalize();
+    let mut amount_bytes = encrypted;
+    for (byte, key) in amount_bytes.iter_mut().zip(amount_key.iter()) {
+        *byte ^= key;
+    }
+    let amount = u64::from_le_bytes(amount_bytes);
+
+    let mut hasher = Keccak256::new();
+    hasher.update(b"commitment_mask");
+    hasher.update(shared.as_bytes());
+    let mask = hash_to_scalar(&hasher.finalize());
+
+    let h = Key::from_bytes(PEDERSEN_H)?.point;
+    let expected = EdwardsPoint::mul_base(&mask) + Scalar::from(amount) * h;
+    if expected.compress().to_bytes() == commitment {
+        Ok(amount)
+    } else {
+        Ok(0)
+    }
+}
+
+fn hash_to_scalar(hash: &[u8]) -> Scalar {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&hash[..32]);
+    Scalar::from_bytes_mod_order(bytes)
+}
+
+fn decode_hex_32(value: &str) -> Result<[u8; 32]> {
+    hex::decode(value)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| anyhow::anyhow!("Expected 32 bytes of hex, got {:?}", value))
+}
+
+/// Découpe `OutProofV2...` / `InProofV2...` en signatures
+fn parse_proof(signature: &str) -> Result<(bool, Vec<ProofPart>)> {
+    let (is_out, body) = if let Some(body) = signature.strip_prefix(OUT_PROOF_V2) {
+        (true, body)
+    } else if let Some(body) = signature.strip_prefix(IN_PROOF_V2) {
+        (false, body)
+    } else {
+        anyhow::bail!(
+            "Unsupported proof, expected {} or {}",
+            OUT_PROOF_V2,
+            IN_PROOF_V2
+        );
+    };
+
+    let part_len = ENCODED_KEY_LEN + ENCODED_SIGNATURE_LEN;
+    if body.is_empty() || !body.is_ascii() || body.len() % part_len != 0 {
+        anyhow::bail!("Invalid proof length");
+    }
+
+    let parts = body
+        .as_bytes()
+        .chunks(part_len)
+        .map(|chunk| {
+            let (key, sig) = chunk.split_at(ENCODED_KEY_LEN);
+            let key: [u8; 32] = base58_decode(key)?
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("Invalid shared secret length"))?;
+            let sig = base58_decode(sig)?;
+            let scalar = |bytes: &[u8]| -> Result<Scalar> {
+                let bytes: [u8; 32] = bytes
+                    .try_into()
+                    .map_err(|_| anyhow::anyhow!("Invalid signature length"))?;
+                Option::from(Scalar::from_canonical_bytes(bytes))
+                    .ok_or_else(|| anyhow::anyhow!("Non-canonical signature scalar"))
+            };
+            Ok(ProofPart {
+                shared_secret: Key::from_bytes(key)?,
+                c: scalar(&sig[..32])?,
+                r: scalar(&sig[32..])?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok((is_out, parts))
+}
+
+/// Décode une adresse standard, sous-adresse ou intégrée (tous réseaux)
+fn parse_address(address: &str) -> Result<Address> {
+    let bytes = base58_decode(address.as_bytes()).context("Invalid address encoding")?;
+    // tag + clé de dépense + clé d'audit (+ payment id) + checksum
+    if bytes.len() != 69 && bytes.len() != 77 {
+        anyhow::bail!("Invalid address length");
+    }
+    let (data, checksum) = bytes.split_at(bytes.len() - 4);
+    if Keccak256::digest(data)[..4] != *checksum {
+        anyhow::bail!("Invalid address checksum");
+    }
+
+    let is_subaddress = match data[0] {
+        // mainnet, testnet, stagenet : standard et intégrée
+        18 | 19 | 53 | 54 | 24 | 25 => false,
+        42 | 63 | 36 => true,
+        tag => anyhow::bail!("Unknown address network tag {}", tag),
+    };
+
+    let mut spend_key = [0u8; 32];
+    let mut view_key = [0u8; 32];
+    spend_key.copy_from_slice(&data[1..33]);
+    view_key.copy_from_slice(&data[33..65]);
+    Ok(Address {
+        spend_key,
+        view_key,
+        is_subaddress,
+    })
+}
+
+/// Base58 Monero : blocs de 8 octets encodés sur 11 caractères
+fn base58_decode(encoded: &[u8]) -> Result<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(encoded.len() * 8 / 11 + 8);
+    for block in encoded.chunks(11) {
+        let size = BASE58_ENCODED_BLOCK_SIZES
+            .iter()
+            .position(|&len| len == block.len())
+            .ok_or_else(|| anyhow::anyhow!("Invalid base58 length"))?;
+
+        let mut value: u128 = 0;
+        for c in block {
+            let digit = BASE58_ALPHABET
+                .iter()
+                .position(|a| a == c)
+                .ok_or_else(|| anyhow::anyhow!("Invalid base58 character"))?;
+            value = value * 58 + digit as u128;
+        }
+        if value >> (8 * size) != 0 {
+            anyhow::bail!("Base58 block overflow");
+        }
+        decoded.extend_from_slice(&value.to_be_bytes()[16 - size..]);
+    }
+    Ok(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base58_encode(data: &[u8]) -> String {
+        let mut encoded = String::new();
+        for block in data.chunks(8) {
+            let mut value = block.iter().fold(0u128, |acc, &b| (acc << 8) | b as u128);
+            let mut chars = vec![b'1'; BASE58_ENCODED_BLOCK_SIZES[block.len()]];
+            for c in chars.iter_mut().rev() {
+                *c = BASE58_ALPHABET[(value % 58) as usize];
+                value /= 58;
+            }
+            encoded.push_str(std::str::from_utf8(&chars).unwrap());
+        }
+        encoded
+    }
+
+    fn scalar(seed: u8) -> Scalar {
+        hash_to_scalar(&Keccak256::digest([seed; 32]))
+    }
+
+    fn encode_address(tag: u8, spend: &EdwardsPoint, view: &EdwardsPoint) -> String {
+        let mut data = vec![tag];
+        data.extend_from_slice(spend.compress().as_bytes());
+        data.extend_from_slice(view.compress().as_bytes());
+        let checksum = Keccak256::digest(&data);
+        data.extend_from_slice(&checksum[..4]);
+        base58_encode(&data)
+    }
+
+    /// Équivalent de `crypto::generate_tx_proof` (version 2)
+    fn generate_tx_proof(
+        prefix_hash: &[u8; 32],
+        r_point: &EdwardsPoint,
+        a_point: &EdwardsPoint,
+        b_point: Option<&EdwardsPoint>,
+        d_point: &EdwardsPoint,
+        secret: &Scalar,
+    ) -> String {
+        let k = scalar(99);
+        let x = match b_point {
+            Some(b) => k * b,
+            None => EdwardsPoint::mul_base(&k),
+        };
+        let y = k * a_point;
+        let mut hasher = Keccak256::new();
+        hasher.update(prefix_hash);
+        hasher.update(d_point.compress().as_bytes());
+        hasher.update(x.compress().as_bytes());
+        hasher.update(y.compress().as_bytes());
+        hasher.update(Keccak256::digest(TXPROOF_V2_DOMAIN));
+        hasher.update(r_point.compress().as_bytes());
+        hasher.update(a_point.compress().as_bytes());
+        hasher.update(
+            b_point
+                .map(|b| b.compress().to_bytes())
+                .unwrap_or([0u8; 32]),
+        );
+        let c = hash_to_scalar(&hasher.finalize());
+        let r = k - c * secret;
+
+        let mut sig = c.to_bytes().to_vec();
+        sig.extend_from_slice(r.as_bytes());
+        format!(
+            "{}{}",
+            base58_encode(d_point.compress().as_bytes()),
+            base58_encode(&sig)
+        )
+    }
+
+    struct Payment {
+        proof: PaymentProof,
+        tx_secret: Scalar,
+        view_secret: Scalar,
+        tx_pubkey: EdwardsPoint,
+        view_key: EdwardsPoint,
+    }
+
+    /// Transaction de 2 sorties : `amount` vers l'adresse, le reste ailleurs
+    fn payment(amount: u64, message: &str) -> Payment {
+        let view_secret = scalar(1);
+        let spend_key = EdwardsPoint::mul_base(&scalar(2));
+        let view_key = EdwardsPoint::mul_base(&view_secret);
+        let tx_secret = scalar(3);
+        let tx_pubkey = EdwardsPoint::mul_base(&tx_secret);
+        let h = Key::from_bytes(PEDERSEN_H).unwrap().point;
+
+        let derivation = (tx_secret * view_key)
+            .mul_by_cofactor()
+            .compress()
+            .to_bytes();
+        let shared = derivation_to_scalar(&derivation, 1);
+        let mut hasher = Keccak256::new();
+        hasher.update(b"amount");
+        hasher.update(shared.as_bytes());
+        let amount_key = hasher.finalize();
+        let encrypted: Vec<u8> = amount
+            .to_le_bytes()
+            .iter()
+            .zip(amount_key.iter())
+            .map(|(a, k)| a ^ k)
+            .collect();
+        let mut hasher = Keccak256::new();
+        hasher.update(b"commitment_mask");
+        hasher.update(shared.as_bytes());
+        let mask = hash_to_scalar(&hasher.finalize());
+        let commitment = EdwardsPoint::mul_base(&mask) + Scalar::from(amount) * h;
+
+        let change = TxOutput {
+            key: hex::encode(EdwardsPoint::mul_base(&scalar(4)).compress().as_bytes()),
+            encrypted_amount: "0011223344556677".to_string(),
+            commitment: hex::encode(EdwardsPoint::mul_base(&scalar(5)).compress().as_bytes()),
+        };
+        let paid = TxOutput {
+            key: hex::encode(output_key(&shared, &spend_key)),
+            encrypted_amount: hex::encode(encrypted),
+            commitment: hex::encode(commitment.compress().as_bytes()),
+        };
+
+        let txid = [7u8; 32];
+        let mut hasher = Keccak256::new();
+        hasher.update(txid);
+        hasher.update(message.as_bytes());
+        let prefix_hash: [u8; 32] = hasher.finalize().into();
+        let signature = format!(
+            "{}{}",
+            OUT_PROOF_V2,
+            generate_tx_proof(
+                &prefix_hash,
+                &tx_pubkey,
+                &view_key,
+                None,
+                &(tx_secret * view_key),
+                &tx_secret
+            )
+        );
+
+        Payment {
+            proof: PaymentProof {
+                txid: hex::encode(txid),
+                address: encode_address(18, &spend_key, &view_key),
+                message: message.to_string(),
+                signature,
+                tx_pubkeys: vec![hex::encode(tx_pubkey.compress().as_bytes())],
+                outputs: vec![change, paid],
+                amount,
+            },
+            tx_secret,
+            view_secret,
+            tx_pubkey,
+            view_key,
+        }
+    }
+
+    #[test]
+    fn test_base58_address_checksum() {
+        // Adresse du Monero General Fund (mainnet)
+        let address = parse_address(
+            "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGv7SqSsaBYBb98uNbr2VBBEt7f2wfn3RVGQBEP3A",
+        )
+        .unwrap();
+        assert!(!address.is_subaddress);
+        assert!(parse_address(
+            "44AFFq5kSiGBoZ4NMDwYtN18obc8AemS33DBLWs3H7otXft3XjrpDtQGv7SqSsaBYBb98uNbr2VBBEt7f2wfn3RVGQBEP3B",
+        )
+        .is_err());
+
+        let data = [0u8, 1, 2, 250, 251, 252, 253, 254, 255, 9, 8];
+        assert_eq!(
+            base58_decode(base58_encode(&data).as_bytes()).unwrap(),
+            data
+        );
+    }
+
+    #[test]
+    fn test_out_proof() {
+        let payment = payment(1_500_000_000_000, "review-payment|market|escrow");
+        assert_eq!(
+            payment_proof_received(&payment.proof).unwrap(),
+            1_500_000_000_000
+        );
+        assert!(verify_payment_proof(&payment.proof).unwrap());
+
+        // Montant annoncé différent
+        let mut inflated = payment.proof.clone();
+        inflated.amount = 2_000_000_000_000;
+        assert!(!verify_payment_proof(&inflated).unwrap());
+
+        // Preuve pour un autre message ou une autre transaction
+        let mut other_message = payment.proof.clone();
+        other_message.message = "review-payment|market|other".to_string();
+        assert!(payment_proof_received(&other_message).is_err());
+        let mut other_tx = payment.proof.clone();
+        other_tx.txid = hex::encode([8u8; 32]);
+        assert!(payment_proof_received(&other_tx).is_err());
+    }
+
+    #[test]
+    fn test_out_proof_for_another_address() {
+        let payment = payment(1_000, "m");
+        let mut proof = payment.proof.clone();
+        proof.address = encode_address(
+            18,
+            &EdwardsPoint::mul_base(&scalar(6)),
+            &EdwardsPoint::mul_base(&scalar(7)),
+        );
+        assert!(payment_proof_received(&proof).is_err());
+    }
+
+    #[test]
+    fn test_in_proof() {
+        let payment = payment(42_000, "m");
+        let mut hasher = Keccak256::new();
+        hasher.update([7u8; 32]);
+        hasher.update(b"m");
+        let prefix_hash: [u8; 32] = hasher.finalize().into();
+
+        // Le destinataire prouve D = a·R avec sa clé d'audit
+        let shared_secret = payment.view_secret * payment.tx_pubkey;
+        assert_eq!(shared_secret, payment.tx_secret * payment.view_key);
+        let mut proof = payment.proof.clone();
+        proof.signature = format!(
+            "{}{}",
+            IN_PROOF_V2,
+            generate_tx_proof(
+                &prefix_hash,
+                &payment.view_key,
+                &payment.tx_pubkey,
+                None,
+                &shared_secret,
+                &payment.view_secret
+            )
+        );
+        assert!(verify_payment_proof(&proof).unwrap());
+    }
+
+    #[test]
+    fn test_malformed_proofs() {
+        let payment = payment(1, "m");
+        for signature in ["", "OutProofV1abc", "OutProofV2", "OutProofV2123"] {
+            let mut proof = payment.proof.clone();
+            proof.signature = signature.to_string();
+            assert!(payment_proof_received(&proof).is_err());
+        }
+        let mut proof = payment.proof.clone();
+        proof.tx_pubkeys.push(proof.tx_pubkeys[0].clone());
+        assert!(payment_proof_received(&proof).is_err());
+    }
+}
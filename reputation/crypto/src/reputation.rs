@@ -2,10 +2,17 @@ use anyhow::{Context, Result};
 use base64::Engine;
 use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use sha2::{Digest, Sha256};
-use reputation_common::types::{SignedReview, ReputationStats};
+use reputation_common::types::{
+    ReputationStats, ReviewBinding, SignedReview, VendorReputation, REPUTATION_FORMAT_V2,
+    REVIEW_FORMAT_V1, REVIEW_FORMAT_V2,
+};
 use chrono::Utc;
 
-/// Génère une signature cryptographique pour un avis
+use crate::revision::{verify_review_history, verify_review_reply};
+
+/// Génère une signature cryptographique pour un avis (format 1.0)
+///
+/// L'avis n'est lié à aucun vendeur : préférer [`sign_bound_review`].
 ///
 /// # Arguments
 /// * `txid` - Transaction hash Monero
@@ -38,48 +45,112 @@ pub fn sign_review(
     rating: u8,
     comment: Option<String>,
     buyer_signing_key: &SigningKey,
+) -> Result<SignedReview> {
+    sign(
+        REVIEW_FORMAT_V1,
+        txid,
+        rating,
+        comment,
+        None,
+        buyer_signing_key,
+    )
+}
+
+/// Génère un avis 2.0 lié au vendeur, à l'escrow et à la marketplace
+///
+/// La signature couvre le contexte : l'avis ne vérifie plus s'il est
+/// recopié dans le fichier de réputation d'un autre vendeur.
+///
+/// # Exemple
+/// ```no_run
+/// use ed25519_dalek::SigningKey;
+/// use reputation_common::types::ReviewBinding;
+/// use reputation_crypto::reputation::sign_bound_review;
+///
+/// let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+/// let review = sign_bound_review(
+///     "abc123".to_string(),
+///     5,
+///     None,
+///     ReviewBinding {
+///         vendor_pubkey: "vendor_pubkey".to_string(),
+///         escrow_id: "escrow_uuid".to_string(),
+///         marketplace_domain: "market.example.onion".to_string(),
+///     },
+///     &signing_key,
+/// ).unwrap();
+/// ```
+pub fn sign_bound_review(
+    txid: String,
+    rating: u8,
+    comment: Option<String>,
+    binding: ReviewBinding,
+    buyer_signing_key: &SigningKey,
+) -> Result<SignedReview> {
+    sign(
+        REVIEW_FORMAT_V2,
+        txid,
+        rating,
+        comment,
+        Some(binding),
+        buyer_signing_key,
+    )
+}
+
+fn sign(
+    format_version: &str,
+    txid: String,
+    rating: u8,
+    comment: Option<String>,
+    binding: Option<ReviewBinding>,
+    buyer_signing_key: &SigningKey,
 ) -> Result<SignedReview> {
     // Validate rating
     if !(1..=5).contains(&rating) {
         return Err(anyhow::anyhow!("Rating must be between 1 and 5"));
     }
 
-    let timestamp = Utc::now();
-
-    // 1. Construire le message à signer (format canonique)
-    let message = format!(
-        "{}|{}|{}|{}",
+    let buyer_pubkey_b64 = public_key_b64(buyer_signing_key);
+    let (vendor_pubkey, escrow_id, marketplace_domain) = match binding {
+        Some(b) => (
+            Some(b.vendor_pubkey),
+            Some(b.escrow_id),
+            Some(b.marketplace_domain),
+        ),
+        None => (None, None, None),
+    };
+
+    let mut review = SignedReview {
+        format_version: format_version.to_string(),
         txid,
         rating,
-        comment.as_deref().unwrap_or(""),
-        timestamp.to_rfc3339()
-    );
-
-    // 2. Hash du message (SHA-256)
-    let mut hasher = Sha256::new();
-    hasher.update(message.as_bytes());
-    let message_hash = hasher.finalize();
+        comment,
+        timestamp: Utc::now(),
+        buyer_pubkey: buyer_pubkey_b64,
+        vendor_pubkey,
+        escrow_id,
+        marketplace_domain,
+        supersedes: None,
+        signature: String::new(),
+        payment_proof: None,
+        revisions: Vec::new(),
+        reply: None,
+    };
 
-    // 3. Signer avec clé privée acheteur
-    let signature = buyer_signing_key.sign(&message_hash);
+    // 1. Construire le message à signer (format canonique)
+    let message = review.signed_message().map_err(anyhow::Error::msg)?;
 
-    // 4. Encoder en base64
-    let signature_b64 = base64::engine::general_purpose::STANDARD.encode(signature.to_bytes());
-    let verifying_key = buyer_signing_key.verifying_key();
-    let buyer_pubkey_b64 = base64::engine::general_purpose::STANDARD.encode(verifying_key.to_bytes());
+    // 2. Signer son hash SHA-256 avec la clé privée acheteur
+    review.signature = sign_message(message.as_bytes(), buyer_signing_key);
 
-    Ok(SignedReview {
-        txid,
-        rating,
-        comment,
-        timestamp,
-        buyer_pubkey: buyer_pubkey_b64,
-        signature: signature_b64,
-    })
+    Ok(review)
 }
 
 /// Vérifie la signature cryptographique d'un avis
 ///
+/// Les avis 1.0 et 2.0 sont acceptés. Pour un avis 2.0, vérifier aussi
+/// qu'il appartient bien au vendeur, voir [`verify_review_for_vendor`].
+///
 /// # Arguments
 /// * `review` - Avis à vérifier
 ///
@@ -97,10 +168,40 @@ pub fn sign_review(
 /// }
 /// ```
 pub fn verify_review_signature(review: &SignedReview) -> Result<bool> {
+    // 1. Reconstruire le message original (selon format_version)
+    let message = review.signed_message().map_err(anyhow::Error::msg)?;
+
+    // 2. Vérifier la signature de l'acheteur sur son hash
+    verify_message_signature(&review.buyer_pubkey, message.as_bytes(), &review.signature)
+}
+
+/// Signe `message` : ed25519 sur le SHA-256 du message, en base64
+///
+/// Même schéma que les avis ; sert aux fichiers de réputation et aux
+/// preuves de possession de clé demandées par la marketplace.
+pub fn sign_message(message: &[u8], signing_key: &SigningKey) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(message);
+    let message_hash = hasher.finalize();
+
+    let signature = signing_key.sign(&message_hash);
+    base64::engine::general_purpose::STANDARD.encode(signature.to_bytes())
+}
+
+/// Vérifie une signature produite par [`sign_message`]
+///
+/// # Returns
+/// * `Err` si la clé ou la signature est mal encodée
+/// * `Ok(false)` si la signature ne correspond pas
+pub fn verify_message_signature(
+    pubkey_b64: &str,
+    message: &[u8],
+    signature_b64: &str,
+) -> Result<bool> {
     // 1. Décoder la clé publique
     let pubkey_bytes = base64::engine::general_purpose::STANDARD
-        .decode(&review.buyer_pubkey)
-        .context("Invalid base64 in buyer_pubkey")?;
+        .decode(pubkey_b64)
+        .context("Invalid base64 in public key")?;
 
     if pubkey_bytes.len() != 32 {
         return Err(anyhow::anyhow!("Invalid public key length: expected 32 bytes"));
@@ -114,7 +215,7 @@ pub fn verify_review_signature(review: &SignedReview) -> Result<bool> {
 
     // 2. Décoder la signature
     let sig_bytes = base64::engine::general_purpose::STANDARD
-        .decode(&review.signature)
+        .decode(signature_b64)
         .context("Invalid base64 in signature")?;
 
     if sig_bytes.len() != 64 {
@@ -126,24 +227,66 @@ pub fn verify_review_signature(review: &SignedReview) -> Result<bool> {
 
     let signature = Signature::from_bytes(&sig_array);
 
-    // 3. Reconstruire le message original
-    let message = format!(
-        "{}|{}|{}|{}",
-        review.txid,
-        review.rating,
-        review.comment.as_deref().unwrap_or(""),
-        review.timestamp.to_rfc3339()
-    );
-
-    // 4. Hash du message
+    // 3. Hash du message
     let mut hasher = Sha256::new();
-    hasher.update(message.as_bytes());
+    hasher.update(message);
     let message_hash = hasher.finalize();
 
-    // 5. Vérifier la signature
+    // 4. Vérifier la signature
     Ok(verifying_key.verify(&message_hash, &signature).is_ok())
 }
 
+/// Clé publique (base64) correspondant à une clé de signature
+pub fn public_key_b64(signing_key: &SigningKey) -> String {
+    base64::engine::general_purpose::STANDARD.encode(signing_key.verifying_key().to_bytes())
+}
+
+/// Signe un fichier de réputation avec la clé du vendeur (format 2.0)
+///
+/// Le fichier doit déjà désigner cette clé dans `vendor_pubkey` : ses avis
+/// 2.0 sont liés à cette clé, la signer avec une autre n'a pas de sens.
+pub fn sign_reputation(
+    mut reputation: VendorReputation,
+    vendor_signing_key: &SigningKey,
+) -> Result<VendorReputation> {
+    if reputation.vendor_pubkey != public_key_b64(vendor_signing_key) {
+        return Err(anyhow::anyhow!("Reputation file is for another vendor key"));
+    }
+
+    reputation.format_version = REPUTATION_FORMAT_V2.to_string();
+    reputation.signature = None;
+    let payload = reputation.signed_payload().map_err(anyhow::Error::msg)?;
+    reputation.signature = Some(sign_message(&payload, vendor_signing_key));
+
+    Ok(reputation)
+}
+
+/// Vérifie la signature du vendeur sur un fichier de réputation 2.0
+///
+/// Ne vérifie pas les avis eux-mêmes : voir [`verify_review_for_vendor`].
+pub fn verify_reputation_signature(reputation: &VendorReputation) -> Result<bool> {
+    let signature = reputation
+        .signature
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("Reputation file is not signed"))?;
+    let payload = reputation.signed_payload().map_err(anyhow::Error::msg)?;
+
+    verify_message_signature(&reputation.vendor_pubkey, &payload, signature)
+}
+
+/// Vérifie la signature d'un avis et son appartenance au vendeur
+///
+/// Un avis 2.0 signé pour un autre vendeur est rejeté ; un avis 1.0 ne
+/// désigne aucun vendeur et n'est vérifié que sur sa signature. Les
+/// versions antérieures et la réponse du vendeur, s'il y en a, doivent
+/// vérifier aussi (voir [`crate::revision`]).
+pub fn verify_review_for_vendor(review: &SignedReview, vendor_pubkey: &str) -> Result<bool> {
+    Ok(review.is_for_vendor(vendor_pubkey)
+        && verify_review_signature(review)?
+        && verify_review_history(review)?
+        && verify_review_reply(review, vendor_pubkey)?)
+}
+
 /// Calcule les statistiques d'une liste d'avis
 ///
 /// # Arguments
@@ -245,6 +388,145 @@ mod tests {
         assert!(!verify_review_signature(&tampered).unwrap());
     }
 
+    fn bound_review(signing_key: &SigningKey) -> SignedReview {
+        sign_bound_review(
+            "abc123".to_string(),
+            4,
+            Some("Fast | discreet".to_string()),
+            ReviewBinding {
+                vendor_pubkey: "vendor_a".to_string(),
+                escrow_id: "escrow_1".to_string(),
+                marketplace_domain: "market.example.onion".to_string(),
+            },
+            signing_key,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_sign_and_verify_bound_review() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let review = bound_review(&signing_key);
+
+        assert_eq!(review.format_version, REVIEW_FORMAT_V2);
+        assert!(verify_review_signature(&review).unwrap());
+        assert!(verify_review_for_vendor(&review, "vendor_a").unwrap());
+        assert!(!verify_review_for_vendor(&review, "vendor_b").unwrap());
+    }
+
+    #[test]
+    fn test_rebound_review_fails_verification() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let review = bound_review(&signing_key);
+
+        let mut moved = review.clone();
+        moved.vendor_pubkey = Some("vendor_b".to_string());
+        assert!(!verify_review_signature(&moved).unwrap());
+
+        let mut moved = review.clone();
+        moved.escrow_id = Some("escrow_2".to_string());
+        assert!(!verify_review_signature(&moved).unwrap());
+
+        let mut moved = review.clone();
+        moved.marketplace_domain = Some("other.example.onion".to_string());
+        assert!(!verify_review_signature(&moved).unwrap());
+
+        // Rétrograder en 1.0 ne doit pas non plus permettre de la déplacer
+        let mut downgraded = review;
+        downgraded.format_version = REVIEW_FORMAT_V1.to_string();
+        assert!(!verify_review_signature(&downgraded).unwrap());
+    }
+
+    #[test]
+    fn test_v1_review_still_verifies() {
+        let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+        let review = sign_review("abc123".to_string(), 5, None, &signing_key).unwrap();
+
+        // Un avis 1.0 sérialisé sans format_version reste valide
+        let mut json = serde_json::to_value(&review).unwrap();
+        json.as_object_mut().unwrap().remove("format_version");
+        let parsed: SignedReview = serde_json::from_value(json).unwrap();
+
+        assert!(parsed.binding().is_none());
+        assert!(verify_review_signature(&parsed).unwrap());
+        assert!(verify_review_for_vendor(&parsed, "vendor_b").unwrap());
+    }
+
+    fn vendor_file(vendor_key: &SigningKey) -> VendorReputation {
+        let buyer_key = SigningKey::from_bytes(&[9u8; 32]);
+        let vendor_pubkey = public_key_b64(vendor_key);
+        let reviews = vec![sign_bound_review(
+            "tx1".to_string(),
+            5,
+            None,
+            ReviewBinding {
+                vendor_pubkey: vendor_pubkey.clone(),
+                escrow_id: "escrow_1".to_string(),
+                marketplace_domain: "market.example.onion".to_string(),
+            },
+            &buyer_key,
+        )
+        .unwrap()];
+
+        let mut reputation = VendorReputation::new(vendor_pubkey);
+        reputation.stats = calculate_stats(&reviews);
+        reputation.reviews = reviews;
+        reputation
+    }
+
+    #[test]
+    fn test_sign_and_verify_reputation_file() {
+        let vendor_key = SigningKey::from_bytes(&[1u8; 32]);
+        let signed = sign_reputation(vendor_file(&vendor_key), &vendor_key).unwrap();
+
+        assert_eq!(signed.format_version, REPUTATION_FORMAT_V2);
+        assert!(verify_reputation_signature(&signed).unwrap());
+
+        // Survit à un aller-retour JSON (export IPFS puis import)
+        let json = serde_json::to_vec_pretty(&signed).unwrap();
+        let parsed: VendorReputation = serde_json::from_slice(&json).unwrap();
+        assert!(verify_reputation_signature(&parsed).unwrap());
+    }
+
+    #[test]
+    fn test_tampered_reputation_file_fails_verification() {
+        let vendor_key = SigningKey::from_bytes(&[1u8; 32]);
+        let signed = sign_reputation(vendor_file(&vendor_key), &vendor_key).unwrap();
+
+        // Retirer un avis et recalculer les stats ne suffit pas
+        let mut pruned = signed.clone();
+        pruned.reviews.clear();
+        pruned.stats = calculate_stats(&pruned.reviews);
+        assert!(!verify_reputation_signature(&pruned).unwrap());
+
+        let mut backdated = signed.clone();
+        backdated.generated_at -= chrono::Duration::days(30);
+        assert!(!verify_reputation_signature(&backdated).unwrap());
+
+        // Un autre vendeur ne peut pas re-signer ce fichier
+        let other_key = SigningKey::from_bytes(&[2u8; 32]);
+        assert!(sign_reputation(signed.clone(), &other_key).is_err());
+
+        let mut unsigned = signed;
+        unsigned.signature = None;
+        assert!(verify_reputation_signature(&unsigned).is_err());
+    }
+
+    #[test]
+    fn test_sign_and_verify_message() {
+        let key = SigningKey::from_bytes(&[4u8; 32]);
+        let signature = sign_message(b"reputation-key|market|vendor", &key);
+        let pubkey = public_key_b64(&key);
+
+        assert!(
+            verify_message_signature(&pubkey, b"reputation-key|market|vendor", &signature).unwrap()
+        );
+        assert!(
+            !verify_message_signature(&pubkey, b"reputation-key|market|other", &signature).unwrap()
+        );
+        assert!(verify_message_signature("not base64!", b"x", &signature).is_err());
+    }
+
     #[test]
     fn test_invalid_rating_rejected() {
         let mut csprng = OsRng;
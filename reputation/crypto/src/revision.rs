@@ -0,0 +1,258 @@
+//! Modifications d'avis et réponses des vendeurs
+//!
+//! Un acheteur peut modifier son avis (2.0) : la nouvelle version signe la
+//! signature de celle qu'elle remplace, et l'ancienne est gardée dans
+//! `revisions`. L'historique forme une chaîne signée de bout en bout : une
+//! version ne peut être ni retirée ni réécrite sans casser la chaîne.
+//!
+//! Le vendeur peut répondre publiquement à un avis. Sa réponse, signée avec
+//! sa clé de réputation, désigne la version de l'avis à laquelle elle
+//! répond ; elle reste vérifiable après une modification de l'avis.
+
+use anyhow::Result;
+use chrono::Utc;
+use ed25519_dalek::SigningKey;
+use reputation_common::types::{ReviewReply, ReviewRevision, SignedReview, REVIEW_FORMAT_V2};
+
+use crate::reputation::{
+    public_key_b64, sign_message, verify_message_signature, verify_review_signature,
+};
+
+/// Modifie un avis : nouvelle version signée, l'ancienne passe dans l'historique
+///
+/// La preuve de paiement et la réponse du vendeur sont conservées : elles
+/// portent sur la transaction et sur une version désignée par sa signature.
+///
+/// # Arguments
+/// * `review` - Avis à modifier (version courante)
+/// * `rating` - Nouvelle note 1-5
+/// * `comment` - Nouveau commentaire
+/// * `buyer_signing_key` - Clé de l'acheteur qui a signé l'avis
+pub fn amend_review(
+    review: &SignedReview,
+    rating: u8,
+    comment: Option<String>,
+    buyer_signing_key: &SigningKey,
+) -> Result<SignedReview> {
+    if review.format_version != REVIEW_FORMAT_V2 {
+        anyhow::bail!("Only 2.0 reviews can be amended");
+    }
+    if !(1..=5).contains(&rating) {
+        anyhow::bail!("Rating must be between 1 and 5");
+    }
+    if public_key_b64(buyer_signing_key) != review.buyer_pubkey {
+        anyhow::bail!("Review was signed by another buyer key");
+    }
+
+    let mut amended = review.amended(ReviewRevision {
+        rating,
+        comment,
+        // Jamais avant la version remplacée, même si l'horloge recule
+        timestamp: Utc::now().max(review.timestamp),
+        supersedes: Some(review.signature.clone()),
+        signature: String::new(),
+    });
+    let message = amended.signed_message().map_err(anyhow::Error::msg)?;
+    amended.signature = sign_message(message.as_bytes(), buyer_signing_key);
+
+    Ok(amended)
+}
+
+/// Signe la réponse du vendeur à la version courante d'un avis
+pub fn sign_reply(
+    review: &SignedReview,
+    comment: String,
+    vendor_signing_key: &SigningKey,
+) -> Result<ReviewReply> {
+    let mut reply = ReviewReply {
+        in_reply_to: review.signature.clone(),
+        comment,
+        timestamp: Utc::now(),
+        signature: String::new(),
+    };
+    let message = reply.signed_message().map_err(anyhow::Error::msg)?;
+    reply.signature = sign_message(message.as_bytes(), vendor_signing_key);
+
+    Ok(reply)
+}
+
+/// Vérifie la chaîne des versions d'un avis
+///
+/// Chaque version antérieure doit être signée par l'acheteur, désigner
+/// celle qu'elle remplaçait et ne pas la précéder ; la version courante
+/// doit désigner la dernière. Ne vérifie pas la signature de la version
+/// courante elle-même : voir [`verify_review_signature`].
+///
+/// # Returns
+/// * `Err` si une version est mal formée (format, commentaire trop long)
+/// * `Ok(false)` si la chaîne est rompue ou qu'une signature ne correspond pas
+pub fn verify_review_history(review: &SignedReview) -> Result<bool> {
+    let mut previous: Option<&str> = None;
+    let mut previous_timestamp = None;
+
+    for revision in &review.revisions {
+        if revision.supersedes.as_deref() != previous {
+            return Ok(false);
+        }
+        if previous_timestamp.is_some_and(|t| revision.timestamp < t) {
+            return Ok(false);
+        }
+        let version = review.at_revision(revision);
+        version.validate_comment().map_err(anyhow::Error::msg)?;
+        if !verify_review_signature(&version)? {
+            return Ok(false);
+        }
+        previous = Some(&revision.signature);
+        previous_timestamp = Some(revision.timestamp);
+    }
+
+    Ok(review.supersedes.as_deref() == previous
+        && previous_timestamp.is_none_or(|t| review.timestamp >= t))
+}
+
+/// Vérifie la réponse du vendeur, s'il y en a une
+///
+/// La réponse doit être signée par `vendor_pubkey` et répondre à l'une des
+/// versions de l'avis (la courante, ou une antérieure si l'avis a été
+/// modifié depuis).
+pub fn verify_review_reply(review: &SignedReview, vendor_pubkey: &str) -> Result<bool> {
+    let Some(reply) = &review.reply else {
+        return Ok(true);
+    };
+
+    let answers_review = reply.in_reply_to == review.signature
+        || review
+            .revisions
+            .iter()
+            .any(|revision| revision.signature == reply.in_reply_to);
+    if !answers_review {
+        return Ok(false);
+    }
+
+    let message = reply.signed_message().map_err(anyhow::Error::msg)?;
+    verify_message_signature(vendor_pubkey, message.as_bytes(), &reply.signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reputation::{sign_bound_review, sign_review, verify_review_for_vendor};
+    use reputation_common::types::ReviewBinding;
+
+    fn keys() -> (SigningKey, SigningKey) {
+        (
+            SigningKey::from_bytes(&[41u8; 32]),
+            SigningKey::from_bytes(&[42u8; 32]),
+        )
+    }
+
+    fn review(buyer: &SigningKey, vendor: &SigningKey) -> SignedReview {
+        sign_bound_review(
+            "a".repeat(64),
+            2,
+            Some("Late delivery".to_string()),
+            ReviewBinding {
+                vendor_pubkey: public_key_b64(vendor),
+                escrow_id: "escrow".to_string(),
+                marketplace_domain: "market.onion".to_string(),
+            },
+            buyer,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_amendment_chain() {
+        let (buyer, vendor) = keys();
+        let vendor_pubkey = public_key_b64(&vendor);
+        let original = review(&buyer, &vendor);
+
+        let amended =
+            amend_review(&original, 4, Some("Arrived in the end".to_string()), &buyer).unwrap();
+        let amended = amend_review(&amended, 5, None, &buyer).unwrap();
+
+        assert_eq!(amended.revisions.len(), 2);
+        assert_eq!(amended.revisions[0].signature, original.signature);
+        assert_eq!(amended.revisions[0].supersedes, None);
+        assert_eq!(
+            amended.supersedes.as_deref(),
+            Some(amended.revisions[1].signature.as_str())
+        );
+        assert!(verify_review_signature(&amended).unwrap());
+        assert!(verify_review_history(&amended).unwrap());
+        assert!(verify_review_for_vendor(&amended, &vendor_pubkey).unwrap());
+
+        // Une version retirée ou réécrite casse la chaîne
+        let mut dropped = amended.clone();
+        dropped.revisions.remove(0);
+        assert!(!verify_review_history(&dropped).unwrap());
+
+        let mut rewritten = amended.clone();
+        rewritten.revisions[0].rating = 5;
+        assert!(!verify_review_history(&rewritten).unwrap());
+
+        // Historique effacé : la version courante désigne encore la précédente
+        let mut erased = amended.clone();
+        erased.revisions.clear();
+        assert!(!verify_review_for_vendor(&erased, &vendor_pubkey).unwrap());
+    }
+
+    #[test]
+    fn test_amendment_requires_buyer_key_and_v2() {
+        let (buyer, vendor) = keys();
+        let original = review(&buyer, &vendor);
+        assert!(amend_review(&original, 5, None, &vendor).is_err());
+        assert!(amend_review(&original, 0, None, &buyer).is_err());
+
+        let unbound = sign_review("b".repeat(64), 3, None, &buyer).unwrap();
+        assert!(amend_review(&unbound, 5, None, &buyer).is_err());
+
+        // Un lien vers une version précédente n'existe pas en 1.0
+        let mut linked = unbound.clone();
+        linked.supersedes = Some(original.signature.clone());
+        assert!(verify_review_signature(&linked).is_err());
+    }
+
+    #[test]
+    fn test_vendor_reply() {
+        let (buyer, vendor) = keys();
+        let vendor_pubkey = public_key_b64(&vendor);
+        let mut review = review(&buyer, &vendor);
+        assert!(verify_review_reply(&review, &vendor_pubkey).unwrap());
+
+        review.reply =
+            Some(sign_reply(&review, "Sorry, shipped again".to_string(), &vendor).unwrap());
+        assert!(verify_review_reply(&review, &vendor_pubkey).unwrap());
+        assert!(verify_review_for_vendor(&review, &vendor_pubkey).unwrap());
+
+        // Signée par l'acheteur, ou déplacée sur un autre avis : rejetée
+        let mut forged = review.clone();
+        forged.reply = Some(sign_reply(&review, "Great vendor".to_string(), &buyer).unwrap());
+        assert!(!verify_review_reply(&forged, &vendor_pubkey).unwrap());
+
+        let mut other = self::review(&buyer, &vendor);
+        other.reply = review.reply.clone();
+        assert!(!verify_review_reply(&other, &vendor_pubkey).unwrap());
+
+        // La réponse reste valide après une modification de l'avis
+        let amended = amend_review(&review, 4, None, &buyer).unwrap();
+        assert_eq!(amended.reply, review.reply);
+        assert!(verify_review_for_vendor(&amended, &vendor_pubkey).unwrap());
+    }
+
+    #[test]
+    fn test_history_survives_json() {
+        let (buyer, vendor) = keys();
+        let mut amended = amend_review(&review(&buyer, &vendor), 5, None, &buyer).unwrap();
+        amended.reply = Some(sign_reply(&amended, "Thanks".to_string(), &vendor).unwrap());
+
+        let json = serde_json::to_string(&amended).unwrap();
+        let parsed: SignedReview = serde_json::from_str(&json).unwrap();
+        assert!(verify_review_for_vendor(&parsed, &public_key_b64(&vendor)).unwrap());
+
+        // Un avis jamais modifié se sérialise comme avant
+        let original = review(&buyer, &vendor);
+        let json = serde_json::to_string(&original).unwrap();
+        assert!(!json.contains("revisions") && !json.contains("supersedes"));
+    }
+}
@@ -0,0 +1,313 @@
+//! Score pondéré d'un vendeur
+//!
+//! La moyenne simple de [`calculate_stats`](crate::reputation::calculate_stats)
+//! place un vendeur avec une seule vente à 5★ devant 300 ventes à 4,8★, et un
+//! avis vieux de trois ans y compte autant que celui d'hier. Le score :
+//!
+//! - pondère chaque avis par le montant payé (racine carrée du montant de sa
+//!   preuve de paiement, bornée) et par son âge (demi-vie) ;
+//! - part d'une note a priori qui pèse comme quelques avis, pour qu'un
+//!   vendeur avec peu de ventes ne soit pas classé sur un ou deux avis ;
+//! - donne à part les taux de litiges et de remboursements des escrows.
+//!
+//! Seuls les montants de preuves de paiement qui vérifient sont utilisés.
+//! Tout le calcul se fait en entiers : le même fichier donne le même score
+//! au bit près sur le serveur et dans le vérificateur WASM.
+
+use chrono::{DateTime, Utc};
+use reputation_common::types::{
+    EscrowOutcomes, ReputationScore, ScoringParams, SignedReview, VendorReputation,
+};
+
+use crate::payment_proof::verify_review_payment;
+
+/// Échelle des calculs intermédiaires (millionièmes)
+const SCALE: u128 = 1_000_000;
+
+/// Millièmes → millionièmes
+const MILLI: u128 = 1_000;
+
+/// Points de base dans 100 %
+const BPS: u64 = 10_000;
+
+/// Calcule le score d'une liste d'avis à la date `at`
+///
+/// # Arguments
+/// * `reviews` - Avis signés (les signatures ne sont pas vérifiées ici)
+/// * `outcomes` - Issue des escrows du vendeur, si connue
+/// * `params` - Paramètres du score, joints au résultat
+/// * `at` - Date de référence pour l'âge des avis
+pub fn calculate_score(
+    reviews: &[SignedReview],
+    outcomes: Option<&EscrowOutcomes>,
+    params: &ScoringParams,
+    at: DateTime<Utc>,
+) -> ReputationScore {
+    let prior_weight = u128::from(params.prior_weight_milli) * MILLI;
+    let mut total_weight = prior_weight;
+    let mut weighted_rating = prior_weight * u128::from(params.prior_rating_milli);
+
+    let mut reviews_weight = 0u128;
+    for review in reviews {
+        let weight = value_weight(review, params) * decay(review.timestamp, at, params) / SCALE;
+        reviews_weight += weight;
+        total_weight += weight;
+        weighted_rating += weight * u128::from(review.rating) * MILLI;
+    }
+
+    // Sans avis ni a priori, la note a priori elle-même
+    let score_milli = (weighted_rating + total_weight / 2)
+        .checked_div(total_weight)
+        .map_or(params.prior_rating_milli, |score| score as u32);
+
+    ReputationScore {
+        params: *params,
+        score_milli,
+        effective_reviews_milli: (reviews_weight / MILLI) as u64,
+        dispute_rate_bps: outcomes.and_then(|o| rate_bps(o.disputed, o.funded)),
+        refund_rate_bps: outcomes.and_then(|o| rate_bps(o.refunded, o.funded)),
+    }
+}
+
+/// Score d'un fichier de réputation, à sa date de génération
+pub fn reputation_score(reputation: &VendorReputation, params: &ScoringParams) -> ReputationScore {
+    calculate_score(
+        &reputation.reviews,
+        reputation.outcomes.as_ref(),
+        params,
+        reputation.generated_at,
+    )
+}
+
+/// Vérifie le score annoncé par un fichier en le recalculant
+///
+/// Recalculé avec les paramètres joints au score. Un fichier sans score
+/// (antérieur au score) est accepté.
+pub fn verify_reputation_score(reputation: &VendorReputation) -> bool {
+    match &reputation.score {
+        Some(score) => reputation_score(reputation, &score.params) == *score,
+        None => true,
+    }
+}
+
+/// Poids d'un avis selon le montant payé (millionièmes)
+///
+/// √(montant / référence), borné ; le poids minimal sans preuve valide.
+fn value_weight(review: &SignedReview, params: &ScoringParams) -> u128 {
+    match &review.payment_proof {
+        Some(proof) if verify_review_payment(review).unwrap_or(false) => {
+            amount_weight(proof.amount, params)
+        }
+        _ => u128::from(params.min_value_weight_milli) * MILLI,
+    }
+}
+
+/// Poids d'un montant payé (millionièmes)
+fn amount_weight(amount: u64, params: &ScoringParams) -> u128 {
+    let min = u128::from(params.min_value_weight_milli) * MILLI;
+    let max = (u128::from(params.max_value_weight_milli) * MILLI).max(min);
+    if params.reference_amount == 0 {
+        return max;
+    }
+
+    // √(a / r) × SCALE = √(a × SCALE² / r)
+    let ratio = u128::from(amount) * SCALE * SCALE / u128::from(params.reference_amount);
+    isqrt(ratio).clamp(min, max)
+}
+
+/// Décroissance d'un avis selon son âge (millionièmes)
+///
+/// Divisée par deux à chaque demi-vie, linéaire entre deux demi-vies.
+fn decay(timestamp: DateTime<Utc>, at: DateTime<Utc>, params: &ScoringParams) -> u128 {
+    let half_life = u128::from(params.half_life_days) * 86_400;
+    if half_life == 0 {
+        return SCALE;
+    }
+
+    let age = u128::try_from((at - timestamp).num_seconds()).unwrap_or(0);
+    let half_lives = age / half_life;
+    if half_lives >= 64 {
+        return 0;
+    }
+    let start = SCALE >> half_lives;
+    start - start * (age % half_life) / (2 * half_life)
+}
+
+/// `part / total` en points de base, `None` si `total` est nul
+fn rate_bps(part: u32, total: u32) -> Option<u32> {
+    if total == 0 {
+        return None;
+    }
+    Some((u64::from(part.min(total)) * BPS / u64::from(total)) as u32)
+}
+
+/// Racine carrée entière (arrondie vers le bas)
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // Newton depuis une borne supérieure
+    let mut x = 1u128 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reputation::sign_review;
+    use chrono::Duration;
+    use ed25519_dalek::SigningKey;
+
+    fn reviews(ratings: &[u8], age_days: i64, at: DateTime<Utc>) -> Vec<SignedReview> {
+        let key = SigningKey::from_bytes(&[4u8; 32]);
+        ratings
+            .iter()
+            .enumerate()
+            .map(|(i, &rating)| {
+                let mut review = sign_review(format!("tx{}", i), rating, None, &key).unwrap();
+                review.timestamp = at - Duration::days(age_days);
+                review
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_isqrt() {
+        for n in [0u128, 1, 2, 3, 4, 15, 16, 17, 1 << 40, u128::from(u64::MAX)] {
+            let r = isqrt(n);
+            assert!(r * r <= n && (r + 1) * (r + 1) > n, "isqrt({})", n);
+        }
+    }
+
+    #[test]
+    fn test_prior_outweighs_single_review() {
+        let at = Utc::now();
+        let params = ScoringParams::default();
+
+        let one_sale = calculate_score(&reviews(&[5], 1, at), None, &params, at);
+        let mut many = vec![5u8; 240];
+        many.extend([4u8; 60]); // 4.8★ en moyenne
+        let many_sales = calculate_score(&reviews(&many, 1, at), None, &params, at);
+
+        assert!(many_sales.score_milli > one_sale.score_milli);
+        assert!(one_sale.score_milli < 4000);
+        assert!((4700..4800).contains(&many_sales.score_milli));
+    }
+
+    #[test]
+    fn test_old_reviews_decay() {
+        let at = Utc::now();
+        let params = ScoringParams::default();
+
+        assert_eq!(decay(at, at, &params), SCALE);
+        assert_eq!(decay(at - Duration::days(180), at, &params), SCALE / 2);
+        assert_eq!(decay(at - Duration::days(270), at, &params), SCALE * 3 / 8);
+        assert_eq!(decay(at + Duration::days(1), at, &params), SCALE);
+
+        // Des 1★ anciens pèsent moins que des 5★ récents
+        let mut old = reviews(&[1; 10], 720, at);
+        old.extend(reviews(&[5; 10], 10, at));
+        let no_prior = ScoringParams {
+            prior_weight_milli: 0,
+            ..params
+        };
+        assert!(calculate_score(&old, None, &no_prior, at).score_milli > 4500);
+
+        // Sans décroissance, la moyenne reprend le dessus
+        let flat = ScoringParams {
+            half_life_days: 0,
+            prior_weight_milli: 0,
+            ..params
+        };
+        assert_eq!(calculate_score(&old, None, &flat, at).score_milli, 3000);
+    }
+
+    #[test]
+    fn test_empty_reviews_score_is_prior() {
+        let params = ScoringParams::default();
+        let score = calculate_score(&[], None, &params, Utc::now());
+        assert_eq!(score.score_milli, params.prior_rating_milli);
+        assert_eq!(score.effective_reviews_milli, 0);
+        assert_eq!(score.dispute_rate_bps, None);
+    }
+
+    #[test]
+    fn test_dispute_and_refund_rates() {
+        let outcomes = EscrowOutcomes {
+            funded: 40,
+            disputed: 3,
+            refunded: 1,
+        };
+        let score = calculate_score(&[], Some(&outcomes), &ScoringParams::default(), Utc::now());
+        assert_eq!(score.dispute_rate_bps, Some(750));
+        assert_eq!(score.refund_rate_bps, Some(250));
+
+        let none = EscrowOutcomes::default();
+        let score = calculate_score(&[], Some(&none), &ScoringParams::default(), Utc::now());
+        assert_eq!(score.dispute_rate_bps, None);
+    }
+
+    #[test]
+    fn test_amount_weight() {
+        let params = ScoringParams::default();
+        let xmr = params.reference_amount;
+        assert_eq!(amount_weight(xmr, &params), SCALE);
+        assert_eq!(amount_weight(4 * xmr, &params), 2 * SCALE);
+        assert_eq!(amount_weight(xmr / 4, &params), SCALE / 2);
+        // Bornes : 0,25 et 4
+        assert_eq!(amount_weight(xmr / 100, &params), SCALE / 4);
+        assert_eq!(amount_weight(100 * xmr, &params), 4 * SCALE);
+    }
+
+    #[test]
+    fn test_unverified_amount_gets_minimum_weight() {
+        use reputation_common::types::PaymentProof;
+
+        let at = Utc::now();
+        let params = ScoringParams::default();
+        let mut review = reviews(&[5], 0, at).remove(0);
+        assert_eq!(value_weight(&review, &params), 250 * MILLI);
+
+        // Montant annoncé sans preuve valide : ignoré
+        review.payment_proof = Some(PaymentProof {
+            txid: review.txid.clone(),
+            address: String::new(),
+            message: String::new(),
+            signature: String::new(),
+            tx_pubkeys: Vec::new(),
+            outputs: Vec::new(),
+            amount: 100 * params.reference_amount,
+        });
+        assert_eq!(value_weight(&review, &params), 250 * MILLI);
+    }
+
+    #[test]
+    fn test_verify_reputation_score() {
+        let at = Utc::now();
+        let mut reputation = VendorReputation::new("vendor".to_string());
+        reputation.generated_at = at;
+        reputation.reviews = reviews(&[5, 4, 3], 30, at);
+        assert!(verify_reputation_score(&reputation));
+
+        reputation.score = Some(reputation_score(&reputation, &ScoringParams::default()));
+        assert!(verify_reputation_score(&reputation));
+
+        // Paramètres joints au score : un score gonflé ne vérifie pas
+        let mut inflated = reputation.clone();
+        if let Some(score) = inflated.score.as_mut() {
+            score.score_milli += 1;
+        }
+        assert!(!verify_reputation_score(&inflated));
+
+        // Rejoué plus tard, le même fichier donne le même score
+        let json = serde_json::to_string(&reputation).unwrap();
+        let parsed: VendorReputation = serde_json::from_str(&json).unwrap();
+        assert!(verify_reputation_score(&parsed));
+    }
+}
@@ -327,6 +327,9 @@ async fn test_reputation_serialization() -> Result<()> {
         generated_at: Utc::now(),
         reviews,
         stats,
+        outcomes: None,
+        score: None,
+        signature: None,
     };
 
     // Serialize to JSON
@@ -388,6 +391,9 @@ async fn mock_submit_and_retrieve(
         generated_at: Utc::now(),
         reviews,
         stats,
+        outcomes: None,
+        score: None,
+        signature: None,
     };
 
     Ok(reputation)
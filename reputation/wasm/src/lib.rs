@@ -1,5 +1,7 @@
 use wasm_bindgen::prelude::*;
-use reputation_common::types::{SignedReview, VendorReputation};
+use reputation_common::types::{
+    PaymentProof, SignedReview, VendorReputation, REPUTATION_FORMAT_V1, REVIEW_FORMAT_V1,
+};
 use base64::Engine;
 use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 use sha2::{Digest, Sha256};
@@ -45,9 +47,39 @@ pub struct VerificationResult {
     /// Number of reviews with invalid signatures
     invalid_signatures: u32,
 
+    /// Number of 2.0 reviews signed for another vendor
+    foreign_reviews: u32,
+
+    /// Number of 1.0 reviews (not bound to a vendor, escrow or marketplace)
+    unbound_reviews: u32,
+
     /// Whether statistics match calculated values
     stats_match: bool,
 
+    /// Whether the file carries a vendor signature (format 2.0)
+    signed: bool,
+
+    /// Whether the vendor signature covers this exact file
+    file_signature_valid: bool,
+
+    /// Number of reviews whose on-chain payment proof verifies
+    verified_payments: u32,
+
+    /// Whether the weighted score (if any) matches the recalculated one
+    score_match: bool,
+
+    /// Number of reviews amended by their buyer
+    amended_reviews: u32,
+
+    /// Number of reviews whose revision history does not verify
+    invalid_revisions: u32,
+
+    /// Number of reviews with a vendor reply
+    vendor_replies: u32,
+
+    /// Number of vendor replies that do not verify against the vendor key
+    invalid_replies: u32,
+
     /// Error message if verification failed
     error_message: Option<String>,
 }
@@ -74,11 +106,61 @@ impl VerificationResult {
         self.invalid_signatures
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn foreign_reviews(&self) -> u32 {
+        self.foreign_reviews
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn unbound_reviews(&self) -> u32 {
+        self.unbound_reviews
+    }
+
     #[wasm_bindgen(getter)]
     pub fn stats_match(&self) -> bool {
         self.stats_match
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn signed(&self) -> bool {
+        self.signed
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn file_signature_valid(&self) -> bool {
+        self.file_signature_valid
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn verified_payments(&self) -> u32 {
+        self.verified_payments
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn score_match(&self) -> bool {
+        self.score_match
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn amended_reviews(&self) -> u32 {
+        self.amended_reviews
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn invalid_revisions(&self) -> u32 {
+        self.invalid_revisions
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn vendor_replies(&self) -> u32 {
+        self.vendor_replies
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn invalid_replies(&self) -> u32 {
+        self.invalid_replies
+    }
+
     #[wasm_bindgen(getter)]
     pub fn error_message(&self) -> Option<String> {
         self.error_message.clone()
@@ -96,8 +178,15 @@ impl VerificationResult {
 /// This is the main entry point for client-side verification.
 /// It verifies:
 /// 1. All signatures are valid
-/// 2. Statistics match calculated values
-/// 3. No tampering has occurred
+/// 2. Every 2.0 review was signed for this vendor
+/// 3. Statistics match calculated values
+/// 4. The vendor signature covers the whole file (format 2.0)
+/// 5. Amended reviews carry their full signed revision chain, and vendor
+///    replies are signed by the file's vendor key
+///
+/// 1.0 reviews are still accepted; they are counted in `unbound_reviews`
+/// since nothing ties them to this vendor. Unsigned 1.0 files are still
+/// accepted too, with `signed` false.
 ///
 /// # Arguments
 /// * `reputation_json` - JSON string of VendorReputation file
@@ -129,7 +218,17 @@ pub fn verify_reputation_file(reputation_json: &str) -> VerificationResult {
             total_reviews: 0,
             valid_signatures: 0,
             invalid_signatures: 0,
+            foreign_reviews: 0,
+            unbound_reviews: 0,
             stats_match: false,
+            signed: false,
+            file_signature_valid: false,
+            verified_payments: 0,
+            score_match: false,
+            amended_reviews: 0,
+            invalid_revisions: 0,
+            vendor_replies: 0,
+            invalid_replies: 0,
             error_message: Some(format!("Verification error: {}", e)),
         },
     }
@@ -144,33 +243,87 @@ fn verify_reputation_file_internal(reputation_json: &str) -> Result<Verification
     let total_reviews = reputation.reviews.len() as u32;
     let mut valid_signatures = 0u32;
     let mut invalid_signatures = 0u32;
-
-    // Verify each review signature
+    let mut foreign_reviews = 0u32;
+    let mut unbound_reviews = 0u32;
+    let mut verified_payments = 0u32;
+    let mut amended_reviews = 0u32;
+    let mut invalid_revisions = 0u32;
+    let mut vendor_replies = 0u32;
+    let mut invalid_replies = 0u32;
+
+    // Verify each review signature and who it was written for
     for review in &reputation.reviews {
         match verify_review_signature(review) {
             Ok(true) => valid_signatures += 1,
             Ok(false) => invalid_signatures += 1,
             Err(_) => invalid_signatures += 1,
         }
+        if review.format_version == REVIEW_FORMAT_V1 {
+            unbound_reviews += 1;
+        } else if !review.is_for_vendor(&reputation.vendor_pubkey) {
+            foreign_reviews += 1;
+        }
+        // A missing or failing payment proof does not invalidate the review
+        if reputation_crypto::payment_proof::verify_review_payment(review).unwrap_or(false) {
+            verified_payments += 1;
+        }
+        // Earlier versions and replies are checked like the review itself
+        if review.supersedes.is_some() || !review.revisions.is_empty() {
+            amended_reviews += 1;
+        }
+        if !reputation_crypto::revision::verify_review_history(review).unwrap_or(false) {
+            invalid_revisions += 1;
+        }
+        if review.reply.is_some() {
+            vendor_replies += 1;
+            if !reputation_crypto::revision::verify_review_reply(review, &reputation.vendor_pubkey)
+                .unwrap_or(false)
+            {
+                invalid_replies += 1;
+            }
+        }
     }
 
     // Verify statistics match
     let calculated_stats = calculate_stats(&reputation.reviews);
     let stats_match = verify_stats_match(&reputation.stats, &calculated_stats);
 
+    // Integer arithmetic: same bits as the server computed
+    let score_match = reputation_crypto::scoring::verify_reputation_score(&reputation);
+
+    // Verify the vendor signature over the whole file
+    let signed = reputation.format_version != REPUTATION_FORMAT_V1;
+    let file_signature_valid = signed && verify_reputation_signature(&reputation).unwrap_or(false);
+
     // Overall validation
-    let is_valid = invalid_signatures == 0 && stats_match;
+    let is_valid = invalid_signatures == 0
+        && foreign_reviews == 0
+        && invalid_revisions == 0
+        && invalid_replies == 0
+        && stats_match
+        && score_match
+        && (!signed || file_signature_valid);
 
     Ok(VerificationResult {
         is_valid,
         total_reviews,
         valid_signatures,
         invalid_signatures,
+        foreign_reviews,
+        unbound_reviews,
         stats_match,
+        signed,
+        file_signature_valid,
+        verified_payments,
+        score_match,
+        amended_reviews,
+        invalid_revisions,
+        vendor_replies,
+        invalid_replies,
         error_message: if !is_valid {
             Some(format!(
-                "{} invalid signature(s), stats_match={}",
-                invalid_signatures, stats_match
+                "{} invalid signature(s), {} review(s) for another vendor, {} broken revision history(ies), {} invalid reply(ies), stats_match={}, score_match={}, file_signature_valid={}",
+                invalid_signatures, foreign_reviews, invalid_revisions, invalid_replies, stats_match, score_match, file_signature_valid
             ))
         } else {
             None
@@ -180,20 +333,26 @@ fn verify_reputation_file_internal(reputation_json: &str) -> Result<Verification
 
 /// Verify a single review signature
 ///
+/// For an amended review, the whole revision history is checked too.
+///
 /// # Arguments
 /// * `review_json` - JSON string of SignedReview
 ///
 /// # Returns
-/// * `true` if signature is valid, `false` otherwise
+/// * `true` if signature (and history) is valid, `false` otherwise
 ///
 /// # Example (JavaScript)
 /// ```javascript
 /// const reviewJson = JSON.stringify({
+///   format_version: "2.0",
 ///   txid: "abc123",
 ///   rating: 5,
 ///   comment: "Great!",
 ///   timestamp: "2025-10-22T12:00:00Z",
 ///   buyer_pubkey: "base64_pubkey",
+///   vendor_pubkey: "vendor_pubkey",
+///   escrow_id: "escrow_uuid",
+///   marketplace_domain: "market.example.onion",
 ///   signature: "base64_signature"
 /// });
 ///
@@ -214,15 +373,144 @@ fn verify_single_review_internal(review_json: &str) -> Result<bool> {
     let review: SignedReview = serde_json::from_str(review_json)
         .map_err(|e| WasmError::ParseError(format!("Failed to parse review JSON: {}", e)))?;
 
-    verify_review_signature(&review)
+    Ok(verify_review_signature(&review)?
+        && reputation_crypto::revision::verify_review_history(&review)
+            .map_err(|e| WasmError::VerificationError(e.to_string()))?)
+}
+
+/// Verify the vendor reply attached to a review
+///
+/// # Arguments
+/// * `review_json` - JSON string of SignedReview (with its `reply`)
+/// * `vendor_pubkey` - Vendor reputation key (base64)
+///
+/// # Returns
+/// * `true` if the reply is signed by `vendor_pubkey` and answers one of the
+///   review's versions (also `true` when there is no reply)
+///
+/// # Example (JavaScript)
+/// ```javascript
+/// const review = reputation.reviews[0];
+/// const ok = verify_review_reply(JSON.stringify(review), reputation.vendor_pubkey);
+/// ```
+#[wasm_bindgen]
+pub fn verify_review_reply(review_json: &str, vendor_pubkey: &str) -> bool {
+    match verify_review_reply_internal(review_json, vendor_pubkey) {
+        Ok(valid) => valid,
+        Err(e) => {
+            web_sys::console::error_1(&format!("Reply verification error: {}", e).into());
+            false
+        }
+    }
+}
+
+fn verify_review_reply_internal(review_json: &str, vendor_pubkey: &str) -> Result<bool> {
+    let review: SignedReview = serde_json::from_str(review_json)
+        .map_err(|e| WasmError::ParseError(format!("Failed to parse review JSON: {}", e)))?;
+
+    reputation_crypto::revision::verify_review_reply(&review, vendor_pubkey)
+        .map_err(|e| WasmError::VerificationError(e.to_string()))
+}
+
+/// Recalculate the weighted score of a reputation file
+///
+/// Uses the parameters of the file's score when it has one, the default
+/// parameters otherwise. The result is bit-for-bit the server's.
+///
+/// # Arguments
+/// * `reputation_json` - JSON string of VendorReputation file
+///
+/// # Returns
+/// * ReputationScore as a JavaScript object (`score_milli`, `dispute_rate_bps`...),
+///   or `null` if the file does not parse
+///
+/// # Example (JavaScript)
+/// ```javascript
+/// const score = reputation_score(reputationJson);
+/// console.log(`${(score.score_milli / 1000).toFixed(2)} stars`);
+/// ```
+#[wasm_bindgen]
+pub fn reputation_score(reputation_json: &str) -> JsValue {
+    let reputation: VendorReputation = match serde_json::from_str(reputation_json) {
+        Ok(reputation) => reputation,
+        Err(e) => {
+            web_sys::console::error_1(&format!("Failed to parse reputation JSON: {}", e).into());
+            return JsValue::NULL;
+        }
+    };
+    let params = reputation
+        .score
+        .map(|score| score.params)
+        .unwrap_or_default();
+    let score = reputation_crypto::scoring::reputation_score(&reputation, &params);
+
+    serde_wasm_bindgen::to_value(&score).unwrap_or(JsValue::NULL)
+}
+
+/// Verify a Monero payment proof attached to a review
+///
+/// Re-runs the wallet's `check_tx_proof` from the proof string and the
+/// public transaction data it carries, without trusting the marketplace.
+///
+/// # Arguments
+/// * `proof_json` - JSON string of PaymentProof
+///
+/// # Returns
+/// * `true` if the proof verifies and the address received `amount`
+///
+/// # Example (JavaScript)
+/// ```javascript
+/// const review = reputation.reviews[0];
+/// const paid = verify_payment_proof(JSON.stringify(review.payment_proof));
+/// ```
+#[wasm_bindgen]
+pub fn verify_payment_proof(proof_json: &str) -> bool {
+    match verify_payment_proof_internal(proof_json) {
+        Ok(valid) => valid,
+        Err(e) => {
+            web_sys::console::error_1(&format!("Payment proof verification error: {}", e).into());
+            false
+        }
+    }
+}
+
+fn verify_payment_proof_internal(proof_json: &str) -> Result<bool> {
+    let proof: PaymentProof = serde_json::from_str(proof_json)
+        .map_err(|e| WasmError::ParseError(format!("Failed to parse payment proof JSON: {}", e)))?;
+
+    reputation_crypto::payment_proof::verify_payment_proof(&proof)
+        .map_err(|e| WasmError::VerificationError(e.to_string()))
 }
 
 /// Core signature verification logic (same as crypto crate)
 fn verify_review_signature(review: &SignedReview) -> Result<bool> {
+    // Reconstruct message (1.0 or 2.0, see SignedReview::signed_message)
+    let message = review
+        .signed_message()
+        .map_err(WasmError::VerificationError)?;
+
+    verify_message_signature(&review.buyer_pubkey, message.as_bytes(), &review.signature)
+}
+
+/// Vendor signature over a 2.0 reputation file (same as crypto crate)
+fn verify_reputation_signature(reputation: &VendorReputation) -> Result<bool> {
+    let signature = reputation
+        .signature
+        .as_deref()
+        .ok_or_else(|| WasmError::VerificationError("Reputation file is not signed".to_string()))?;
+    let payload = reputation
+        .signed_payload()
+        .map_err(WasmError::VerificationError)?;
+
+    verify_message_signature(&reputation.vendor_pubkey, &payload, signature)
+}
+
+/// Ed25519 signature over the SHA-256 of `message` (base64 key and signature)
+fn verify_message_signature(pubkey_b64: &str, message: &[u8], signature_b64: &str) -> Result<bool> {
     // 1. Decode public key
     let pubkey_bytes = base64::engine::general_purpose::STANDARD
-        .decode(&review.buyer_pubkey)
-        .map_err(|e| WasmError::VerificationError(format!("Invalid base64 in buyer_pubkey: {}", e)))?;
+        .decode(pubkey_b64)
+        .map_err(|e| WasmError::VerificationError(format!("Invalid base64 in public key: {}", e)))?;
 
     if pubkey_bytes.len() != 32 {
         return Err(WasmError::VerificationError("Invalid public key length: expected 32 bytes".to_string()));
@@ -236,7 +524,7 @@ fn verify_review_signature(review: &SignedReview) -> Result<bool> {
 
     // 2. Decode signature
     let sig_bytes = base64::engine::general_purpose::STANDARD
-        .decode(&review.signature)
+        .decode(signature_b64)
         .map_err(|e| WasmError::VerificationError(format!("Invalid base64 in signature: {}", e)))?;
 
     if sig_bytes.len() != 64 {
@@ -248,21 +536,12 @@ fn verify_review_signature(review: &SignedReview) -> Result<bool> {
 
     let signature = Signature::from_bytes(&sig_array);
 
-    // 3. Reconstruct message
-    let message = format!(
-        "{}|{}|{}|{}",
-        review.txid,
-        review.rating,
-        review.comment.as_deref().unwrap_or(""),
-        review.timestamp.to_rfc3339()
-    );
-
-    // 4. Hash message
+    // 3. Hash message
     let mut hasher = Sha256::new();
-    hasher.update(message.as_bytes());
+    hasher.update(message);
     let message_hash = hasher.finalize();
 
-    // 5. Verify signature
+    // 4. Verify signature
     Ok(verifying_key.verify(&message_hash, &signature).is_ok())
 }
 
@@ -0,0 +1,3 @@
+-- Revert per-party amounts on transactions
+ALTER TABLE transactions DROP COLUMN vendor_amount_xmr;
+ALTER TABLE transactions DROP COLUMN buyer_amount_xmr;
@@ -0,0 +1,4 @@
+-- Per-party amounts for split dispute settlements (one multisig transfer, two destinations)
+-- NULL for ordinary release/refund transactions
+ALTER TABLE transactions ADD COLUMN buyer_amount_xmr BIGINT DEFAULT NULL;
+ALTER TABLE transactions ADD COLUMN vendor_amount_xmr BIGINT DEFAULT NULL;
@@ -0,0 +1,2 @@
+-- Drop order_items table
+DROP TABLE IF EXISTS order_items;
@@ -0,0 +1,36 @@
+-- Line items for multi-item orders
+-- One order (and one escrow) per vendor per checkout; each cart line becomes an order_item.
+-- orders.listing_id is kept and points at the first item for older code paths.
+CREATE TABLE order_items (
+    id TEXT PRIMARY KEY NOT NULL,
+    order_id TEXT NOT NULL,
+    listing_id TEXT NOT NULL,
+    -- Listing title at checkout time (listings can be edited or deleted later)
+    title TEXT NOT NULL,
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    -- Unit price at checkout time, in atomic units (piconeros)
+    unit_price_xmr BIGINT NOT NULL CHECK (unit_price_xmr > 0),
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (listing_id) REFERENCES listings(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_order_items_order_id ON order_items(order_id);
+CREATE INDEX idx_order_items_listing_id ON order_items(listing_id);
+
+-- Backfill: existing orders become single-item orders
+-- (quantity recovered from total / price when it divides evenly)
+INSERT INTO order_items (id, order_id, listing_id, title, quantity, unit_price_xmr, created_at)
+SELECT
+    lower(hex(randomblob(16))),
+    o.id,
+    o.listing_id,
+    l.title,
+    CASE WHEN o.total_xmr % l.price_xmr = 0 AND o.total_xmr >= l.price_xmr
+         THEN o.total_xmr / l.price_xmr ELSE 1 END,
+    CASE WHEN o.total_xmr % l.price_xmr = 0 AND o.total_xmr >= l.price_xmr
+         THEN l.price_xmr ELSE o.total_xmr END,
+    o.created_at
+FROM orders o
+JOIN listings l ON l.id = o.listing_id
+WHERE o.total_xmr > 0;
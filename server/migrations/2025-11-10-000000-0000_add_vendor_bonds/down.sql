@@ -0,0 +1,3 @@
+-- Drop vendor bond tables
+DROP TABLE IF EXISTS vendor_bond_slashes;
+DROP TABLE IF EXISTS vendor_bonds;
@@ -0,0 +1,48 @@
+-- Vendor bonds (security deposits)
+-- A vendor locks XMR in a dedicated 2-of-3 multisig wallet (vendor, marketplace, arbiter).
+-- The bond unlocks listing limits, can be slashed by an arbiter into a dispute payout,
+-- and the remainder is paid back when the vendor retires.
+CREATE TABLE vendor_bonds (
+    id TEXT PRIMARY KEY NOT NULL,
+    vendor_id TEXT NOT NULL,
+    arbiter_id TEXT NOT NULL,
+    -- Bond amount in atomic units (piconeros)
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    -- Total slashed so far (paid out to buyers in disputes)
+    slashed_amount BIGINT NOT NULL DEFAULT 0 CHECK (slashed_amount >= 0 AND slashed_amount <= amount),
+    multisig_address TEXT,
+    status TEXT NOT NULL DEFAULT 'created'
+        CHECK (status IN ('created', 'awaiting_funding', 'active', 'slashed', 'released', 'cancelled')),
+    release_tx_hash TEXT,
+    funded_at TIMESTAMP,
+    released_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (vendor_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (arbiter_id) REFERENCES users(id)
+);
+
+CREATE INDEX idx_vendor_bonds_vendor_id ON vendor_bonds(vendor_id);
+CREATE INDEX idx_vendor_bonds_status ON vendor_bonds(status);
+
+-- At most one open bond per vendor
+CREATE UNIQUE INDEX idx_vendor_bonds_one_open_per_vendor ON vendor_bonds(vendor_id)
+    WHERE status IN ('created', 'awaiting_funding', 'active');
+
+-- Slashing history: each row is a payout from the bond wallet to a disputed escrow's buyer
+CREATE TABLE vendor_bond_slashes (
+    id TEXT PRIMARY KEY NOT NULL,
+    bond_id TEXT NOT NULL,
+    escrow_id TEXT NOT NULL,
+    arbiter_id TEXT NOT NULL,
+    amount BIGINT NOT NULL CHECK (amount > 0),
+    tx_hash TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (bond_id) REFERENCES vendor_bonds(id) ON DELETE CASCADE,
+    FOREIGN KEY (escrow_id) REFERENCES escrows(id),
+    FOREIGN KEY (arbiter_id) REFERENCES users(id)
+);
+
+CREATE INDEX idx_vendor_bond_slashes_bond_id ON vendor_bond_slashes(bond_id);
+CREATE INDEX idx_vendor_bond_slashes_escrow_id ON vendor_bond_slashes(escrow_id);
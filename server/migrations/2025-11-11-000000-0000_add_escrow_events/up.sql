@@ -0,0 +1,75 @@
+-- Escrow event log (append-only)
+-- Every escrow status change is recorded with who made it and why, so the
+-- history of an escrow can be replayed instead of trusting escrows.status alone.
+CREATE TABLE escrow_events (
+    id TEXT PRIMARY KEY NOT NULL,
+    escrow_id TEXT NOT NULL,
+    -- Position of the event in the escrow's history (1 = created)
+    sequence INTEGER NOT NULL CHECK (sequence > 0),
+    event_type TEXT NOT NULL
+        CHECK (event_type IN ('created', 'funded', 'released', 'refunded', 'disputed', 'status_changed')),
+    from_status TEXT,
+    to_status TEXT NOT NULL,
+    -- 'user:<uuid>' or 'system:<component>'
+    actor TEXT NOT NULL,
+    reason TEXT,
+    -- Serialized common::types::EscrowEvent (JSON)
+    payload TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (escrow_id) REFERENCES escrows(id),
+    UNIQUE (escrow_id, sequence)
+);
+
+CREATE INDEX idx_escrow_events_escrow_id ON escrow_events(escrow_id);
+
+CREATE TRIGGER escrow_events_no_update
+BEFORE UPDATE ON escrow_events
+BEGIN
+    SELECT RAISE(ABORT, 'escrow_events is append-only');
+END;
+
+CREATE TRIGGER escrow_events_no_delete
+BEFORE DELETE ON escrow_events
+BEGIN
+    SELECT RAISE(ABORT, 'escrow_events is append-only');
+END;
+
+-- Backfill existing escrows: a 'created' event, then their current status
+INSERT INTO escrow_events (id, escrow_id, sequence, event_type, from_status, to_status, actor, reason, payload, created_at)
+SELECT
+    lower(hex(randomblob(16))),
+    id,
+    1,
+    'created',
+    NULL,
+    'created',
+    'system:migration',
+    'Backfilled from escrows table',
+    json_object(
+        'type', 'created',
+        'escrow_id', id,
+        'data', json_object(
+            'buyer', buyer_id,
+            'seller', vendor_id,
+            'arbiter', arbiter_id,
+            'amount', amount,
+            'multisig_address', COALESCE(multisig_address, '')
+        )
+    ),
+    created_at
+FROM escrows;
+
+INSERT INTO escrow_events (id, escrow_id, sequence, event_type, from_status, to_status, actor, reason, payload, created_at)
+SELECT
+    lower(hex(randomblob(16))),
+    id,
+    2,
+    'status_changed',
+    'created',
+    status,
+    'system:migration',
+    'Backfilled from escrows table',
+    json_object('type', 'status_changed', 'escrow_id', id, 'from', 'created', 'to', status),
+    updated_at
+FROM escrows
+WHERE status != 'created';
@@ -0,0 +1,2 @@
+-- Revert the dispute ruling payout address
+ALTER TABLE escrows DROP COLUMN ruling_address;
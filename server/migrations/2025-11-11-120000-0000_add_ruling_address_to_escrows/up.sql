@@ -0,0 +1,3 @@
+-- Payout address chosen by the arbiter when ruling a dispute for one party
+-- Resolved escrows may only be paid out to this address
+ALTER TABLE escrows ADD COLUMN ruling_address TEXT DEFAULT NULL;
@@ -0,0 +1,2 @@
+-- Rollback: Remove funded_amount column
+ALTER TABLE escrows DROP COLUMN funded_amount;
@@ -0,0 +1,4 @@
+-- Track how much has actually landed on the escrow's multisig address
+-- Sum of incoming deposits (atomic units), so partial payments and top-ups
+-- can be shown and any surplus over `amount` returned to the buyer on release.
+ALTER TABLE escrows ADD COLUMN funded_amount BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,25 @@
+-- Rollback: Back to plaintext-only order messages
+-- Encrypted messages are kept: their envelope JSON goes in `message`, the
+-- server can't decrypt them.
+CREATE TABLE order_messages_old (
+    id TEXT PRIMARY KEY NOT NULL,
+    order_id TEXT NOT NULL,
+    sender_id TEXT NOT NULL,
+    message TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (sender_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+INSERT INTO order_messages_old (id, order_id, sender_id, message, created_at)
+SELECT id, order_id, sender_id, COALESCE(message, envelope), created_at
+FROM order_messages;
+
+DROP TABLE order_messages;
+ALTER TABLE order_messages_old RENAME TO order_messages;
+
+CREATE INDEX idx_order_messages_order_id ON order_messages(order_id);
+CREATE INDEX idx_order_messages_created_at ON order_messages(created_at ASC);
+CREATE INDEX idx_order_messages_sender_id ON order_messages(sender_id);
+
+DROP TABLE IF EXISTS user_encryption_keys;
@@ -0,0 +1,38 @@
+-- Per-user public keys for end-to-end encrypted order messages
+-- Raw uncompressed P-256 point, base64 (65 bytes decoded). The private key
+-- never leaves the user's browser.
+CREATE TABLE user_encryption_keys (
+    user_id TEXT PRIMARY KEY NOT NULL,
+    public_key TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+-- New order messages hold an encrypted envelope (JSON) instead of plaintext.
+-- Existing plaintext messages cannot be encrypted server-side: they are kept
+-- as-is in `message` and shown read-only. Exactly one of the two is set.
+
+-- SQLite can't drop NOT NULL on `message`, so the table is recreated
+CREATE TABLE order_messages_new (
+    id TEXT PRIMARY KEY NOT NULL,
+    order_id TEXT NOT NULL,
+    sender_id TEXT NOT NULL,
+    message TEXT,
+    envelope TEXT,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (order_id) REFERENCES orders(id) ON DELETE CASCADE,
+    FOREIGN KEY (sender_id) REFERENCES users(id) ON DELETE CASCADE,
+    CHECK ((message IS NULL) != (envelope IS NULL))
+);
+
+INSERT INTO order_messages_new (id, order_id, sender_id, message, envelope, created_at)
+SELECT id, order_id, sender_id, message, NULL, created_at
+FROM order_messages;
+
+DROP TABLE order_messages;
+ALTER TABLE order_messages_new RENAME TO order_messages;
+
+CREATE INDEX idx_order_messages_order_id ON order_messages(order_id);
+CREATE INDEX idx_order_messages_created_at ON order_messages(created_at ASC);
+CREATE INDEX idx_order_messages_sender_id ON order_messages(sender_id);
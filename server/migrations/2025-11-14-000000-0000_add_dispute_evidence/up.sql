@@ -0,0 +1,26 @@
+-- Evidence submitted by buyer or vendor for a disputed escrow
+-- Files are encrypted with the server field key before storage (IPFS or local
+-- blob directory). content_hash is the SHA-256 of the plaintext file, exported
+-- in the DisputeRequest so the offline arbiter can check the USB bundle.
+CREATE TABLE dispute_evidence (
+    id TEXT PRIMARY KEY NOT NULL,
+    escrow_id TEXT NOT NULL,
+    uploader_id TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('photo', 'tracking', 'message', 'document')),
+    description TEXT,
+    -- Name of the file in the evidence bundle (<party>-<n>.<ext>, e.g. buyer-1.png)
+    file_name TEXT NOT NULL,
+    mime_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes > 0),
+    -- SHA-256 of the plaintext file (hex)
+    content_hash TEXT NOT NULL,
+    storage_backend TEXT NOT NULL CHECK (storage_backend IN ('ipfs', 'local')),
+    -- IPFS CID or local blob name of the encrypted file
+    storage_ref TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (escrow_id) REFERENCES escrows(id),
+    FOREIGN KEY (uploader_id) REFERENCES users(id)
+);
+
+CREATE INDEX idx_dispute_evidence_escrow_id ON dispute_evidence(escrow_id);
+CREATE UNIQUE INDEX idx_dispute_evidence_file_name ON dispute_evidence(escrow_id, file_name);
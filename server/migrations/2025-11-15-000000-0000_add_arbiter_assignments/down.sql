@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_escrows_arbiter_status;
+DROP TABLE IF EXISTS arbiter_assignments;
@@ -0,0 +1,36 @@
+-- History of arbiters assigned to each escrow
+-- escrows.arbiter_id holds the current arbiter; each row here covers one
+-- assignment, open (ended_at NULL) while the arbiter is in charge.
+CREATE TABLE arbiter_assignments (
+    id TEXT PRIMARY KEY NOT NULL,
+    escrow_id TEXT NOT NULL,
+    arbiter_id TEXT NOT NULL,
+    -- Why the arbiter got the escrow
+    reason TEXT NOT NULL CHECK (reason IN ('initial', 'recusal', 'escalation', 'admin')),
+    assigned_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    ended_at TIMESTAMP,
+    -- Why the assignment ended
+    end_reason TEXT CHECK (end_reason IN ('recused', 'escalated', 'replaced')),
+    note TEXT,
+    FOREIGN KEY (escrow_id) REFERENCES escrows(id),
+    FOREIGN KEY (arbiter_id) REFERENCES users(id)
+);
+
+CREATE INDEX idx_arbiter_assignments_escrow_id ON arbiter_assignments(escrow_id);
+CREATE INDEX idx_arbiter_assignments_arbiter_id ON arbiter_assignments(arbiter_id);
+CREATE UNIQUE INDEX idx_arbiter_assignments_open ON arbiter_assignments(escrow_id)
+    WHERE ended_at IS NULL;
+
+-- Backfill existing escrows with their current arbiter
+INSERT INTO arbiter_assignments (id, escrow_id, arbiter_id, reason, assigned_at, note)
+SELECT
+    lower(hex(randomblob(16))),
+    id,
+    arbiter_id,
+    'initial',
+    created_at,
+    'Backfilled from escrows table'
+FROM escrows;
+
+-- Open-dispute load per arbiter
+CREATE INDEX idx_escrows_arbiter_status ON escrows(arbiter_id, status);
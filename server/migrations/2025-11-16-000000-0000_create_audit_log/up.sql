@@ -0,0 +1,45 @@
+-- Append-only, hash-chained audit trail of money movements and privileged actions
+-- Each entry stores the SHA3-256 of its own content and of the previous entry,
+-- so editing or removing a past entry breaks the chain. `sequence` is assigned
+-- by the writer (last + 1): two concurrent writers cannot fork the chain, the
+-- second insert fails on the primary key and is retried on the new head.
+CREATE TABLE audit_log (
+    sequence INTEGER PRIMARY KEY NOT NULL CHECK (sequence > 0),
+    event_type TEXT NOT NULL CHECK (event_type IN (
+        'release',
+        'refund',
+        'dispute_resolution',
+        'airgap_import',
+        'admin_action',
+        'wallet_address_change',
+        'bond_slash',
+        'bond_release'
+    )),
+    -- Escrow ID, user ID or other entity the event is about
+    entity_id TEXT NOT NULL,
+    -- 'user:<uuid>' or 'system:<component>' (as in escrow_events)
+    actor TEXT NOT NULL,
+    -- Event details (compact JSON)
+    data TEXT NOT NULL,
+    -- RFC 3339 UTC with microseconds, hashed as stored
+    recorded_at TEXT NOT NULL,
+    -- SHA3-256 of this entry (hex)
+    entry_hash TEXT NOT NULL UNIQUE,
+    -- entry_hash of sequence - 1 (NULL for the first entry)
+    previous_hash TEXT
+);
+
+CREATE INDEX idx_audit_log_entity ON audit_log(entity_id);
+CREATE INDEX idx_audit_log_event_type ON audit_log(event_type);
+
+CREATE TRIGGER audit_log_no_update
+BEFORE UPDATE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
+
+CREATE TRIGGER audit_log_no_delete
+BEFORE DELETE ON audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'audit_log is append-only');
+END;
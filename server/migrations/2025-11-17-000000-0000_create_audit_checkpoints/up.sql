@@ -0,0 +1,28 @@
+-- Signed checkpoints of the audit log head
+-- The marketplace signs (sequence, head_hash, created_at) with its Ed25519
+-- key and publishes the result; a later export that does not contain the
+-- same entry_hash at that sequence has been rewritten.
+CREATE TABLE audit_checkpoints (
+    -- audit_log.sequence of the signed head
+    sequence INTEGER PRIMARY KEY NOT NULL CHECK (sequence > 0),
+    -- audit_log.entry_hash at that sequence
+    head_hash TEXT NOT NULL,
+    -- RFC 3339 UTC, signed as stored
+    created_at TEXT NOT NULL,
+    -- Ed25519 public key (hex) of the signer
+    public_key TEXT NOT NULL,
+    -- Ed25519 signature (hex) of the checkpoint statement
+    signature TEXT NOT NULL
+);
+
+CREATE TRIGGER audit_checkpoints_no_update
+BEFORE UPDATE ON audit_checkpoints
+BEGIN
+    SELECT RAISE(ABORT, 'audit_checkpoints is append-only');
+END;
+
+CREATE TRIGGER audit_checkpoints_no_delete
+BEFORE DELETE ON audit_checkpoints
+BEGIN
+    SELECT RAISE(ABORT, 'audit_checkpoints is append-only');
+END;
@@ -0,0 +1,17 @@
+-- XMR exchange rates recorded by the price oracle
+-- One row per currency and refresh: the median of the sources that agreed,
+-- and which sources were used or rejected as outliers.
+CREATE TABLE price_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    -- ISO 4217 code, lowercase ('usd', 'eur', ...)
+    currency TEXT NOT NULL,
+    -- Fiat units per 1 XMR
+    rate DOUBLE NOT NULL CHECK (rate > 0),
+    -- Comma-separated names of the sources in the median
+    sources TEXT NOT NULL,
+    -- Comma-separated names of the sources rejected as outliers
+    rejected_sources TEXT NOT NULL DEFAULT '',
+    fetched_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_price_history_currency ON price_history(currency, id);
@@ -0,0 +1,10 @@
+-- Revert fiat-pegged listing prices
+DROP INDEX IF EXISTS idx_orders_quote_expires;
+ALTER TABLE orders DROP COLUMN quote_expires_at;
+
+ALTER TABLE order_items DROP COLUMN price_rate;
+ALTER TABLE order_items DROP COLUMN unit_price_fiat;
+ALTER TABLE order_items DROP COLUMN price_currency;
+
+ALTER TABLE listings DROP COLUMN price_fiat;
+ALTER TABLE listings DROP COLUMN price_currency;
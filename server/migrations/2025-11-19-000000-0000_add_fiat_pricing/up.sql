@@ -0,0 +1,19 @@
+-- Fiat-pegged listing prices, locked in at checkout
+--
+-- A listing may be priced in a fiat currency: price_fiat (minor units, e.g.
+-- cents) in price_currency (lowercase ISO 4217). Its price_xmr then only
+-- follows the current rate, for display and sorting.
+ALTER TABLE listings ADD COLUMN price_currency TEXT;
+ALTER TABLE listings ADD COLUMN price_fiat BIGINT CHECK (price_fiat IS NULL OR price_fiat > 0);
+
+-- At checkout the rate and the fiat unit price are copied onto the line
+-- item, next to the XMR unit price they produced
+ALTER TABLE order_items ADD COLUMN price_currency TEXT;
+ALTER TABLE order_items ADD COLUMN unit_price_fiat BIGINT;
+ALTER TABLE order_items ADD COLUMN price_rate DOUBLE;
+
+-- Until this time the XMR total of an order with fiat-priced items holds;
+-- an order still unfunded afterwards is requoted at the current rate
+ALTER TABLE orders ADD COLUMN quote_expires_at TIMESTAMP;
+
+CREATE INDEX idx_orders_quote_expires ON orders(status, quote_expires_at) WHERE quote_expires_at IS NOT NULL;
@@ -0,0 +1,6 @@
+-- Revert review binding
+DROP INDEX IF EXISTS idx_reviews_escrow;
+ALTER TABLE reviews DROP COLUMN marketplace_domain;
+ALTER TABLE reviews DROP COLUMN escrow_id;
+ALTER TABLE reviews DROP COLUMN vendor_pubkey;
+ALTER TABLE reviews DROP COLUMN format_version;
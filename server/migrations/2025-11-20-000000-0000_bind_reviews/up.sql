@@ -0,0 +1,13 @@
+-- Versioned reviews bound to vendor, escrow and marketplace
+--
+-- A 1.0 review signs txid|rating|comment|timestamp only. A 2.0 review also
+-- signs the vendor it is about, the escrow of the order and the marketplace
+-- domain, so it cannot be moved into another vendor's reputation file.
+-- Existing rows are 1.0 and keep the binding columns NULL.
+ALTER TABLE reviews ADD COLUMN format_version TEXT NOT NULL DEFAULT '1.0';
+ALTER TABLE reviews ADD COLUMN vendor_pubkey TEXT;
+ALTER TABLE reviews ADD COLUMN escrow_id TEXT REFERENCES escrows(id) ON DELETE SET NULL;
+ALTER TABLE reviews ADD COLUMN marketplace_domain TEXT;
+
+-- One review per escrow
+CREATE UNIQUE INDEX idx_reviews_escrow ON reviews(escrow_id) WHERE escrow_id IS NOT NULL;
@@ -0,0 +1,4 @@
+-- Revert vendor reputation keys and imports
+DROP INDEX IF EXISTS idx_imported_reputations_vendor;
+DROP TABLE IF EXISTS imported_reputations;
+DROP TABLE IF EXISTS vendor_reputation_keys;
@@ -0,0 +1,33 @@
+-- Vendor reputation keys and reputation imported from other marketplaces
+--
+-- A vendor signs their exported reputation file with an ed25519 key. The
+-- key is registered once, with a proof of possession, and never changes:
+-- 2.0 reviews are bound to it.
+CREATE TABLE vendor_reputation_keys (
+    vendor_id TEXT PRIMARY KEY NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    public_key TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- A signed reputation file fetched from IPFS. Every review and the vendor
+-- signature were verified at import; the file is kept as-is so visitors
+-- can verify it again.
+CREATE TABLE imported_reputations (
+    id TEXT PRIMARY KEY NOT NULL,
+    vendor_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    ipfs_cid TEXT NOT NULL,
+    vendor_pubkey TEXT NOT NULL,
+    -- Marketplace domains the 2.0 reviews were written on, comma-separated
+    source_domains TEXT NOT NULL,
+    total_reviews INTEGER NOT NULL,
+    -- 1.0 reviews in the file: signed by a buyer, but not bound to this vendor
+    unbound_reviews INTEGER NOT NULL,
+    average_rating DOUBLE NOT NULL,
+    generated_at TIMESTAMP NOT NULL,
+    reputation_json TEXT NOT NULL,
+    imported_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE(vendor_id, ipfs_cid)
+);
+
+CREATE INDEX idx_imported_reputations_vendor ON imported_reputations(vendor_id);
@@ -0,0 +1,2 @@
+-- Revert review payment proofs
+ALTER TABLE reviews DROP COLUMN payment_proof;
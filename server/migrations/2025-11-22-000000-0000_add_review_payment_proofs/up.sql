@@ -0,0 +1,6 @@
+-- On-chain payment proof of a review
+--
+-- JSON PaymentProof: the tx proof string checked by the wallet against the
+-- escrow multisig address, plus the public transaction data needed to check
+-- it again offline. Set together with `verified`.
+ALTER TABLE reviews ADD COLUMN payment_proof TEXT;
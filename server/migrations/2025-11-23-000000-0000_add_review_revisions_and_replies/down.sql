@@ -0,0 +1,4 @@
+-- Revert review amendments and vendor replies
+ALTER TABLE reviews DROP COLUMN reply;
+ALTER TABLE reviews DROP COLUMN revisions;
+ALTER TABLE reviews DROP COLUMN supersedes;
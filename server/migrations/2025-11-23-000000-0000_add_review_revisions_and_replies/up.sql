@@ -0,0 +1,10 @@
+-- Review amendments and vendor replies
+--
+-- `signature` and the other review columns hold the current version. An
+-- amended review keeps the signature of the version it replaced in
+-- `supersedes` and the earlier versions, oldest first, in `revisions`
+-- (JSON ReviewRevision array). `reply` is the vendor's signed reply (JSON
+-- ReviewReply).
+ALTER TABLE reviews ADD COLUMN supersedes TEXT;
+ALTER TABLE reviews ADD COLUMN revisions TEXT;
+ALTER TABLE reviews ADD COLUMN reply TEXT;
@@ -0,0 +1,94 @@
+//! Verify or export the hash-chained audit log
+//!
+//! Opens the encrypted database with the server's key and either recomputes
+//! the whole chain or writes the entries as JSON lines.
+//!
+//! # Usage
+//!
+//! ```bash
+//! # Check the chain (exit code 1 if it is broken)
+//! DB_ENCRYPTION_KEY=... cargo run --bin audit_log -- verify
+//!
+//! # Export entries after sequence 120 to a file (stdout without a path)
+//! DB_ENCRYPTION_KEY=... cargo run --bin audit_log -- export 120 audit.jsonl
+//! ```
+//!
+//! `DATABASE_URL` defaults to `marketplace.db`.
+
+use anyhow::{Context, Result};
+use dotenvy::dotenv;
+use server::db::create_pool;
+use server::models::audit_log::AuditEntry;
+use std::env;
+use std::io::Write;
+
+/// Entries read per query while exporting
+const EXPORT_BATCH: i64 = 1000;
+
+fn usage() -> ! {
+    eprintln!("Usage: audit_log verify");
+    eprintln!("       audit_log export [AFTER_SEQUENCE] [OUTPUT_FILE]");
+    std::process::exit(2);
+}
+
+fn main() -> Result<()> {
+    dotenv().ok();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    let database_url = env::var("DATABASE_URL").unwrap_or_else(|_| "marketplace.db".to_string());
+    let encryption_key = env::var("DB_ENCRYPTION_KEY")
+        .context("DB_ENCRYPTION_KEY not set (use the same value as the server)")?;
+
+    let pool = create_pool(&database_url, &encryption_key)?;
+    let mut conn = pool.get().context("Failed to open database")?;
+
+    match args.first().map(String::as_str) {
+        Some("verify") => {
+            let result = AuditEntry::verify_chain(&mut conn)?;
+            if result.is_valid() {
+                println!(
+                    "✅ Audit log intact: {} entries, head {}",
+                    result.entries_verified,
+                    result.head_hash.as_deref().unwrap_or("(empty)")
+                );
+                Ok(())
+            } else {
+                eprintln!(
+                    "❌ Audit log broken at sequence {}: {} ({} entries verified before it)",
+                    result.broken_at.unwrap_or_default(),
+                    result.error.as_deref().unwrap_or("unknown error"),
+                    result.entries_verified
+                );
+                std::process::exit(1);
+            }
+        }
+        Some("export") => {
+            let mut after: i64 = match args.get(1) {
+                Some(value) => value.parse().context("AFTER_SEQUENCE must be an integer")?,
+                None => 0,
+            };
+            let mut out: Box<dyn Write> = match args.get(2) {
+                Some(path) => Box::new(
+                    std::fs::File::create(path).context(format!("Failed to create {}", path))?,
+                ),
+                None => Box::new(std::io::stdout().lock()),
+            };
+
+            let mut exported = 0usize;
+            loop {
+                let entries = AuditEntry::list_after(&mut conn, after, EXPORT_BATCH)?;
+                let Some(last) = entries.last() else { break };
+                after = last.sequence;
+                for entry in &entries {
+                    serde_json::to_writer(&mut out, entry)?;
+                    writeln!(out)?;
+                }
+                exported += entries.len();
+            }
+            out.flush()?;
+            eprintln!("Exported {} audit log entries", exported);
+            Ok(())
+        }
+        _ => usage(),
+    }
+}
@@ -0,0 +1,204 @@
+//! Manage verifiable Shamir shares of the DB encryption key
+//!
+//! Every command reads from stdin and prints shares to stdout. The key only
+//! exists in memory (for `split` and `import-legacy`) or not at all
+//! (`verify`, `refresh`, `reshare`), and nothing is written to disk.
+//!
+//! # Usage
+//!
+//! ```bash
+//! # Split a key (64 hex chars on the first line) into 5 shares, 3 required
+//! cargo run --bin key_shares -- split --threshold 3 --shares 5
+//!
+//! # Check shares before relying on them (one per line, end with an empty line)
+//! cargo run --bin key_shares -- verify
+//!
+//! # New shares of the same key; the old ones stop combining with them
+//! cargo run --bin key_shares -- refresh
+//!
+//! # Move to a 4-of-7 set from any `threshold` current shares
+//! cargo run --bin key_shares -- reshare --threshold 4 --shares 7
+//!
+//! # Convert 3 shares written by split_key into verifiable shares
+//! cargo run --bin key_shares -- import-legacy --threshold 3 --shares 5
+//! ```
+//!
+//! Record the key fingerprint printed by `split`: it stays the same after
+//! `refresh` and `reshare`, and identifies the key the shares belong to.
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use server::crypto::shamir::reconstruct_key;
+use server::crypto::vss::{
+    refresh_shares, reshare, split_secret, verify_share_set, VerifiableShare,
+};
+use std::env;
+use std::io::{self, BufRead, Write};
+use zeroize::Zeroizing;
+
+const USAGE: &str = "Usage: key_shares <command> [--threshold K] [--shares N]
+
+Commands:
+  split          Split a hex key read from stdin (default 3-of-5)
+  verify         Check shares read from stdin
+  refresh        New shares of the same key, same threshold and holders
+  reshare        New k-of-n share set from the current shares
+  import-legacy  Convert 3 split_key shares into verifiable shares (default 3-of-5)";
+
+fn main() -> Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some(command) = args.first() else {
+        eprintln!("{}", USAGE);
+        std::process::exit(2);
+    };
+    let threshold = parse_option(&args, "--threshold")?;
+    let share_count = parse_option(&args, "--shares")?;
+
+    println!("\n╔════════════════════════════════════════════════════════════╗");
+    println!("║  Verifiable Key Shares (TM-002)                           ║");
+    println!("║  The key is never written to disk                         ║");
+    println!("╚════════════════════════════════════════════════════════════╝\n");
+
+    match command.as_str() {
+        "split" => {
+            let key = read_line("Enter 256-bit key (64 hex characters): ")?;
+            let key_bytes = Zeroizing::new(hex::decode(key.trim()).context("Key must be hex")?);
+            let shares =
+                split_secret(&key_bytes, threshold.unwrap_or(3), share_count.unwrap_or(5))?;
+            print_shares(&shares);
+        }
+        "verify" => {
+            let shares = read_verifiable_shares()?;
+            for share in &shares {
+                share.verify()?;
+                println!("✓ Share {} matches its commitments", share.index());
+            }
+            let commitments = verify_share_set(&shares)?;
+            println!(
+                "\n✅ {} shares of key {} ({} required)\n",
+                shares.len(),
+                commitments.fingerprint(),
+                commitments.threshold()
+            );
+        }
+        "refresh" => {
+            let shares = read_verifiable_shares()?;
+            print_shares(&refresh_shares(&shares)?);
+            println!("⚠️  Give every holder their new share and destroy ALL old shares:");
+            println!("   old shares do not combine with the new ones.");
+            println!("   Holders whose share was not entered are left out of the new set.\n");
+        }
+        "reshare" => {
+            let (Some(threshold), Some(share_count)) = (threshold, share_count) else {
+                anyhow::bail!("reshare requires --threshold and --shares\n\n{}", USAGE);
+            };
+            let shares = read_verifiable_shares()?;
+            print_shares(&reshare(&shares, threshold, share_count)?);
+            println!("⚠️  Destroy ALL old shares once the new ones are stored.\n");
+        }
+        "import-legacy" => {
+            let shares = read_legacy_shares()?;
+            let key_bytes = Zeroizing::new(
+                reconstruct_key(&shares).context("Failed to reconstruct key from legacy shares")?,
+            );
+            let shares =
+                split_secret(&key_bytes, threshold.unwrap_or(3), share_count.unwrap_or(5))?;
+            print_shares(&shares);
+            println!("⚠️  Destroy the split_key shares once the new ones are stored.\n");
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    }
+
+    Ok(())
+}
+
+/// Value of `--name N`, if given
+fn parse_option(args: &[String], name: &str) -> Result<Option<u8>> {
+    match args.iter().position(|arg| arg == name) {
+        None => Ok(None),
+        Some(i) => {
+            let value = args
+                .get(i + 1)
+                .context(format!("{} requires a value", name))?;
+            Ok(Some(
+                value
+                    .parse()
+                    .context(format!("Invalid {}: {}", name, value))?,
+            ))
+        }
+    }
+}
+
+fn read_line(prompt: &str) -> Result<Zeroizing<String>> {
+    eprint!("{}", prompt);
+    io::stderr().flush()?;
+    let mut input = Zeroizing::new(String::new());
+    io::stdin()
+        .read_line(&mut input)
+        .context("Failed to read input")?;
+    Ok(input)
+}
+
+/// Non-empty lines from stdin until EOF or an empty line
+fn read_share_lines() -> Result<Vec<Zeroizing<String>>> {
+    eprintln!("Enter shares, one per line (empty line to finish):");
+    let mut lines = Vec::new();
+    for line in io::stdin().lock().lines() {
+        let line = Zeroizing::new(line.context("Failed to read share")?);
+        if line.trim().is_empty() {
+            break;
+        }
+        lines.push(line);
+    }
+    Ok(lines)
+}
+
+fn read_verifiable_shares() -> Result<Vec<VerifiableShare>> {
+    read_share_lines()?
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            VerifiableShare::from_text(line).context(format!("Line {} is not a valid share", i + 1))
+        })
+        .collect()
+}
+
+fn read_legacy_shares() -> Result<Vec<Vec<u8>>> {
+    let shares = read_share_lines()?
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            BASE64
+                .decode(line.trim())
+                .context(format!("Line {} is not a base64 share", i + 1))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if shares.len() < 3 {
+        anyhow::bail!("3 split_key shares required, got {}", shares.len());
+    }
+    Ok(shares)
+}
+
+fn print_shares(shares: &[VerifiableShare]) {
+    let Some(first) = shares.first() else {
+        return;
+    };
+    let commitments = first.commitments();
+
+    println!(
+        "\n✅ {} shares, {} required (key fingerprint {})\n",
+        shares.len(),
+        commitments.threshold(),
+        commitments.fingerprint()
+    );
+    println!("═══════════════════════════════════════════════════════════\n");
+    for share in shares {
+        println!("📦 Share {}", share.index());
+        println!("   {}", share.to_text());
+        println!();
+    }
+    println!("═══════════════════════════════════════════════════════════\n");
+}
@@ -0,0 +1,135 @@
+//! Rotate the DB encryption key and re-split it into Shamir shares (3-of-5)
+//!
+//! Loads the current key the same way as the server (`DB_ENCRYPTION_KEY` or
+//! interactive reconstruction from 3 shares), generates a new 256-bit key,
+//! rewrites the database under it with `PRAGMA rekey` and prints 5 new
+//! verifiable shares (see `key_shares`). The field key is derived from the DB
+//! key, so field-encrypted columns and evidence blobs are re-encrypted by the
+//! server on its next start (see `services::key_rotation`), or right away
+//! with `--reencrypt-fields`.
+//! The shares are printed as soon as the rekey succeeds; a failed field
+//! re-encryption only prints a warning and leaves the rest to the server.
+//!
+//! # Usage
+//!
+//! ```bash
+//! # Stop the server and back up the database first
+//! cargo run --bin rotate_key
+//!
+//! # Also re-encrypt the database columns now (evidence is still done by the server)
+//! cargo run --bin rotate_key -- --reencrypt-fields
+//! ```
+//!
+//! `DATABASE_URL` defaults to `marketplace.db`.
+//!
+//! # After Rotation
+//!
+//! 1. Store the new shares and destroy the old ones
+//! 2. Start the server with the new key and, until it logs that previous
+//!    keys can be removed, `DB_PREVIOUS_ENCRYPTION_KEYS=<old key hex>`
+//! 3. Backups taken before the rotation still need the old key
+
+use anyhow::{Context, Result};
+use dotenvy::dotenv;
+use server::crypto::encryption::{generate_key, register_previous_key, validate_key};
+use server::crypto::shamir_startup::{get_db_encryption_key, is_shamir_mode_enabled};
+use server::crypto::vss::split_secret;
+use server::db::{create_pool, rekey_database};
+use server::services::key_rotation::reencrypt_database;
+use std::env;
+
+fn main() -> Result<()> {
+    dotenv().ok();
+
+    let reencrypt_fields = match env::args().nth(1).as_deref() {
+        None => false,
+        Some("--reencrypt-fields") => true,
+        Some(_) => {
+            eprintln!("Usage: rotate_key [--reencrypt-fields]");
+            std::process::exit(2);
+        }
+    };
+
+    println!("\n╔════════════════════════════════════════════════════════════╗");
+    println!("║  DB Encryption Key Rotation                               ║");
+    println!("║  Rekey the database and re-split the key (3-of-5)         ║");
+    println!("╚════════════════════════════════════════════════════════════╝\n");
+
+    let database_url = env::var("DATABASE_URL").unwrap_or_else(|_| "marketplace.db".to_string());
+    let shamir_mode = is_shamir_mode_enabled();
+    let current_key = get_db_encryption_key().context("Failed to load current DB key")?;
+    let current_field_key = hex::decode(&current_key).context("Current key is not hex")?;
+
+    let new_field_key = generate_key();
+    validate_key(&new_field_key).context("Generated key failed validation")?;
+    let new_key = hex::encode(&new_field_key);
+
+    // Split before the rekey: once the database is rekeyed, these shares are
+    // the only way back into it
+    let shares = split_secret(&new_field_key, 3, 5).context("Failed to split new key")?;
+
+    println!("🔄 Rekeying {} ...", database_url);
+    rekey_database(&database_url, &current_key, &new_key).context("Rotation failed")?;
+    println!("✅ Database now opens with the new key\n");
+
+    println!(
+        "Key fingerprint: {}\n",
+        shares[0].commitments().fingerprint()
+    );
+    println!("═══════════════════════════════════════════════════════════\n");
+    for share in &shares {
+        println!("📦 New share {}", share.index());
+        println!("   {}", share.to_text());
+        println!();
+    }
+    if !shamir_mode {
+        println!("📦 New DB_ENCRYPTION_KEY (development mode):");
+        println!("   {}\n", new_key);
+    }
+    println!("═══════════════════════════════════════════════════════════");
+
+    // The rekey is done: a failure here is left to the server's re-encryption
+    if reencrypt_fields {
+        println!();
+        if let Err(e) =
+            reencrypt_fields_now(&database_url, &new_key, &current_field_key, &new_field_key)
+        {
+            eprintln!("⚠️  Field re-encryption failed: {:#}", e);
+            eprintln!("   The server re-encrypts the remaining fields on its next start");
+        }
+    }
+
+    println!("\n⚠️  NEXT STEPS:\n");
+    println!("1. Replace every old share with the new one in the same location");
+    println!("   (old shares now reconstruct a key that no longer opens the database)");
+    println!("2. Start the server once with the retired key so remaining data is re-encrypted:");
+    println!(
+        "   $ export DB_PREVIOUS_ENCRYPTION_KEYS=\"{}\"",
+        current_key
+    );
+    println!("3. Remove DB_PREVIOUS_ENCRYPTION_KEYS once the server logs");
+    println!("   \"previous keys can be removed\"");
+    println!("4. Backups taken before now still need the OLD key\n");
+
+    Ok(())
+}
+
+/// Re-encrypt the field-encrypted columns under the new key
+fn reencrypt_fields_now(
+    database_url: &str,
+    new_key: &str,
+    current_field_key: &[u8],
+    new_field_key: &[u8],
+) -> Result<()> {
+    register_previous_key(current_field_key)?;
+    let pool = create_pool(database_url, new_key)?;
+    let mut conn = pool.get().context("Failed to open database")?;
+    let report = reencrypt_database(&mut conn, new_field_key)?;
+    for column in &report.columns {
+        println!(
+            "   {}.{}: {} re-encrypted, {} failed",
+            column.table, column.column, column.reencrypted, column.failed
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! Offline verification of an exported audit log
+//!
+//! Checks an export (JSON lines from `audit_log export` or the pages of
+//! `GET /admin/audit/export`) against the published checkpoints and the
+//! marketplace public key. Needs no database access.
+//!
+//! # Usage
+//!
+//! ```bash
+//! cargo run --bin verify_audit_export -- audit.jsonl audit-checkpoints.jsonl \
+//!     --public-key <HEX>
+//! ```
+//!
+//! Entries are JSON lines. Checkpoints are either JSON lines
+//! (`audit-checkpoints.jsonl`) or the JSON array returned by
+//! `GET /api/audit/checkpoints`. Exits with code 1 if anything is reported.
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use server::models::audit_checkpoint::AuditCheckpoint;
+use server::models::audit_log::AuditEntry;
+use server::services::audit_checkpoint::verify_export;
+use std::env;
+use std::path::Path;
+
+fn usage() -> ! {
+    eprintln!("Usage: verify_audit_export ENTRIES_FILE CHECKPOINTS_FILE --public-key HEX");
+    std::process::exit(2);
+}
+
+/// Read a JSON array or JSON lines
+fn read_records<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>> {
+    let content =
+        std::fs::read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+
+    if content.trim_start().starts_with('[') {
+        return serde_json::from_str(&content)
+            .context(format!("Invalid JSON array in {}", path.display()));
+    }
+
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str(line).context(format!(
+                "Invalid record at {}:{}",
+                path.display(),
+                index + 1
+            ))
+        })
+        .collect()
+}
+
+fn main() -> Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let mut files = Vec::new();
+    let mut public_key = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--public-key" => public_key = Some(iter.next().unwrap_or_else(|| usage()).clone()),
+            _ => files.push(arg.clone()),
+        }
+    }
+    let ([entries_path, checkpoints_path], Some(public_key)) = (files.as_slice(), public_key)
+    else {
+        usage()
+    };
+
+    let entries: Vec<AuditEntry> = read_records(Path::new(entries_path))?;
+    let checkpoints: Vec<AuditCheckpoint> = read_records(Path::new(checkpoints_path))?;
+
+    let report = verify_export(&entries, &checkpoints, public_key.trim());
+
+    println!(
+        "Checked {} entries (sequences {}..={}) against {} checkpoints, {} matched",
+        report.entries_checked,
+        report.first_sequence.unwrap_or_default(),
+        report.last_sequence.unwrap_or_default(),
+        checkpoints.len(),
+        report.checkpoints_matched
+    );
+
+    if report.is_clean() {
+        println!("✅ Export is consistent with every signed checkpoint");
+        return Ok(());
+    }
+
+    for finding in &report.findings {
+        eprintln!("❌ {}", finding);
+    }
+    std::process::exit(1);
+}
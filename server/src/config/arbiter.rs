@@ -0,0 +1,77 @@
+//! Arbiter pool configuration
+//!
+//! Escrows are assigned to the arbiter with the fewest open disputes. An
+//! arbiter at capacity is skipped, and a dispute that keeps timing out is
+//! only handed to a new arbiter a limited number of times before it is
+//! escalated to an admin.
+
+use serde::{Deserialize, Serialize};
+
+/// Arbiter assignment limits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArbiterPoolConfig {
+    /// Open disputes an arbiter may hold before being skipped (0 = no limit)
+    ///
+    /// Default: 20
+    pub max_open_disputes: u32,
+
+    /// Times a dispute is reassigned on timeout before admin escalation
+    ///
+    /// Default: 2
+    pub max_escalation_reassignments: u32,
+}
+
+impl Default for ArbiterPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_open_disputes: 20,
+            max_escalation_reassignments: 2,
+        }
+    }
+}
+
+impl ArbiterPoolConfig {
+    /// Create ArbiterPoolConfig from environment variables
+    ///
+    /// Reads configuration from:
+    /// - ARBITER_MAX_OPEN_DISPUTES
+    /// - ARBITER_MAX_ESCALATION_REASSIGNMENTS
+    ///
+    /// Falls back to defaults if not set.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_open_disputes: std::env::var("ARBITER_MAX_OPEN_DISPUTES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.max_open_disputes),
+            max_escalation_reassignments: std::env::var("ARBITER_MAX_ESCALATION_REASSIGNMENTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.max_escalation_reassignments),
+        }
+    }
+
+    /// Whether an arbiter with `open_disputes` can take another one
+    pub fn has_capacity(&self, open_disputes: i64) -> bool {
+        self.max_open_disputes == 0 || open_disputes < i64::from(self.max_open_disputes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capacity() {
+        let config = ArbiterPoolConfig::default();
+        assert!(config.has_capacity(19));
+        assert!(!config.has_capacity(20));
+
+        let unlimited = ArbiterPoolConfig {
+            max_open_disputes: 0,
+            ..ArbiterPoolConfig::default()
+        };
+        assert!(unlimited.has_capacity(10_000));
+    }
+}
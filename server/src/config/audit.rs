@@ -0,0 +1,74 @@
+//! Audit log checkpoint schedule and publication
+//!
+//! At each interval, if the audit log grew, its head hash is signed with the
+//! marketplace key and published so outsiders can later prove the log was
+//! not rewritten.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Checkpoint policy for the audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditCheckpointPolicy {
+    /// How often a checkpoint is taken (skipped when the log did not grow)
+    ///
+    /// Default: 3600 seconds (1 hour)
+    pub interval_secs: u64,
+
+    /// Directory where `audit-checkpoints.jsonl` is appended
+    ///
+    /// Default: none (checkpoints are only served by `GET /api/audit/checkpoints`)
+    pub publish_dir: Option<PathBuf>,
+}
+
+impl Default for AuditCheckpointPolicy {
+    fn default() -> Self {
+        Self {
+            interval_secs: 3600,
+            publish_dir: None,
+        }
+    }
+}
+
+impl AuditCheckpointPolicy {
+    /// Create AuditCheckpointPolicy from environment variables
+    ///
+    /// Reads configuration from:
+    /// - AUDIT_CHECKPOINT_INTERVAL_SECS
+    /// - AUDIT_CHECKPOINT_DIR
+    ///
+    /// Falls back to defaults if not set.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            interval_secs: std::env::var("AUDIT_CHECKPOINT_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(defaults.interval_secs),
+            publish_dir: std::env::var("AUDIT_CHECKPOINT_DIR")
+                .ok()
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+                .or(defaults.publish_dir),
+        }
+    }
+
+    /// Get checkpoint interval as Duration
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy() {
+        let policy = AuditCheckpointPolicy::default();
+        assert_eq!(policy.interval(), Duration::from_secs(3600));
+        assert!(policy.publish_dir.is_none());
+    }
+}
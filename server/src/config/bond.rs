@@ -0,0 +1,187 @@
+//! Vendor bond policy
+//!
+//! Maps the amount a vendor has locked in a bond to the number of active
+//! listings they may have. Vendors without a bond keep a small allowance so
+//! new sellers can start without collateral.
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// One step of the bond ladder
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BondTier {
+    /// Minimum remaining bond (atomic units) to reach this tier
+    pub min_bond_atomic: i64,
+    /// Active listing limit at this tier (None = unlimited)
+    pub max_active_listings: Option<u32>,
+}
+
+/// Bond requirements for listing limits
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BondPolicy {
+    /// Active listing limit for vendors without a funded bond
+    ///
+    /// Default: 5
+    pub unbonded_listing_limit: u32,
+
+    /// Tiers sorted by `min_bond_atomic` (ascending)
+    ///
+    /// Default: 1 XMR → 25 listings, 5 XMR → 100 listings, 10 XMR → unlimited
+    pub tiers: Vec<BondTier>,
+}
+
+impl Default for BondPolicy {
+    fn default() -> Self {
+        Self {
+            unbonded_listing_limit: 5,
+            tiers: vec![
+                BondTier {
+                    min_bond_atomic: 1_000_000_000_000, // 1 XMR
+                    max_active_listings: Some(25),
+                },
+                BondTier {
+                    min_bond_atomic: 5_000_000_000_000, // 5 XMR
+                    max_active_listings: Some(100),
+                },
+                BondTier {
+                    min_bond_atomic: 10_000_000_000_000, // 10 XMR
+                    max_active_listings: None,
+                },
+            ],
+        }
+    }
+}
+
+impl BondPolicy {
+    /// Create BondPolicy from environment variables
+    ///
+    /// Reads configuration from:
+    /// - BOND_UNBONDED_LISTING_LIMIT
+    /// - BOND_TIERS (`<atomic>:<limit>` pairs separated by commas,
+    ///   limit `unlimited` for no cap, e.g. `1000000000000:25,10000000000000:unlimited`)
+    ///
+    /// Falls back to defaults if not set or invalid.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        let unbonded_listing_limit = std::env::var("BOND_UNBONDED_LISTING_LIMIT")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(defaults.unbonded_listing_limit);
+
+        let tiers = match std::env::var("BOND_TIERS") {
+            Ok(raw) => Self::parse_tiers(&raw).unwrap_or_else(|| {
+                warn!("Invalid BOND_TIERS '{}', using default bond tiers", raw);
+                defaults.tiers
+            }),
+            Err(_) => defaults.tiers,
+        };
+
+        Self::new(unbonded_listing_limit, tiers)
+    }
+
+    /// Create a policy (tiers are sorted by minimum bond)
+    pub fn new(unbonded_listing_limit: u32, mut tiers: Vec<BondTier>) -> Self {
+        tiers.sort_by_key(|tier| tier.min_bond_atomic);
+        Self {
+            unbonded_listing_limit,
+            tiers,
+        }
+    }
+
+    /// Parse `<atomic>:<limit>,...` (None if any entry is malformed)
+    fn parse_tiers(raw: &str) -> Option<Vec<BondTier>> {
+        raw.split(',')
+            .map(|entry| {
+                let (amount, limit) = entry.trim().split_once(':')?;
+                let min_bond_atomic: i64 = amount.trim().parse().ok().filter(|a| *a > 0)?;
+                let max_active_listings = match limit.trim() {
+                    "unlimited" => None,
+                    n => Some(n.parse().ok()?),
+                };
+                Some(BondTier {
+                    min_bond_atomic,
+                    max_active_listings,
+                })
+            })
+            .collect()
+    }
+
+    /// Smallest bond accepted (first tier), None if bonds unlock nothing
+    pub fn minimum_bond(&self) -> Option<i64> {
+        self.tiers.first().map(|tier| tier.min_bond_atomic)
+    }
+
+    /// Highest tier reached with `bond_atomic` locked
+    pub fn tier_for(&self, bond_atomic: i64) -> Option<&BondTier> {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| bond_atomic >= tier.min_bond_atomic)
+    }
+
+    /// Next tier above `bond_atomic`, if any
+    pub fn next_tier(&self, bond_atomic: i64) -> Option<&BondTier> {
+        self.tiers
+            .iter()
+            .find(|tier| bond_atomic < tier.min_bond_atomic)
+    }
+
+    /// Active listing limit for a vendor with `bond_atomic` locked (None = unlimited)
+    pub fn listing_limit(&self, bond_atomic: i64) -> Option<u32> {
+        match self.tier_for(bond_atomic) {
+            Some(tier) => tier.max_active_listings,
+            None => Some(self.unbonded_listing_limit),
+        }
+    }
+
+    /// Whether a vendor with `active_listings` may activate one more
+    pub fn allows_new_listing(&self, bond_atomic: i64, active_listings: i64) -> bool {
+        self.listing_limit(bond_atomic)
+            .is_none_or(|limit| active_listings < limit as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_XMR: i64 = 1_000_000_000_000;
+
+    #[test]
+    fn test_listing_limit_by_tier() {
+        let policy = BondPolicy::default();
+
+        assert_eq!(policy.listing_limit(0), Some(5));
+        assert_eq!(policy.listing_limit(ONE_XMR - 1), Some(5));
+        assert_eq!(policy.listing_limit(ONE_XMR), Some(25));
+        assert_eq!(policy.listing_limit(7 * ONE_XMR), Some(100));
+        assert_eq!(policy.listing_limit(10 * ONE_XMR), None);
+
+        assert!(policy.allows_new_listing(0, 4));
+        assert!(!policy.allows_new_listing(0, 5));
+        assert!(policy.allows_new_listing(10 * ONE_XMR, 10_000));
+
+        assert_eq!(policy.minimum_bond(), Some(ONE_XMR));
+        assert_eq!(
+            policy.next_tier(2 * ONE_XMR).map(|t| t.min_bond_atomic),
+            Some(5 * ONE_XMR)
+        );
+        assert!(policy.next_tier(10 * ONE_XMR).is_none());
+    }
+
+    #[test]
+    fn test_parse_tiers() {
+        let tiers = BondPolicy::parse_tiers("5000:10, 1000:2 ,9000:unlimited").unwrap();
+        let policy = BondPolicy::new(1, tiers);
+
+        assert_eq!(policy.tiers[0].min_bond_atomic, 1000);
+        assert_eq!(policy.listing_limit(999), Some(1));
+        assert_eq!(policy.listing_limit(1000), Some(2));
+        assert_eq!(policy.listing_limit(9000), None);
+
+        assert!(BondPolicy::parse_tiers("1000").is_none());
+        assert!(BondPolicy::parse_tiers("0:5").is_none());
+        assert!(BondPolicy::parse_tiers("1000:lots").is_none());
+    }
+}
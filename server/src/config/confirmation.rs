@@ -0,0 +1,166 @@
+//! Confirmation policy per escrow amount
+//!
+//! Larger escrows are worth more to a double-spender, so they wait for more
+//! blocks before the deposit counts (vendor is told to ship) and before a
+//! release or refund is considered final.
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// One step of the confirmation ladder
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfirmationTier {
+    /// Minimum escrow amount (atomic units) for this tier
+    pub min_amount_atomic: u64,
+    /// Confirmations required at this tier
+    pub required_confirmations: u32,
+}
+
+/// Confirmations required before funding and payouts are trusted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmationPolicy {
+    /// Confirmations for escrows below the first tier
+    ///
+    /// Default: 10 (Monero's unlock window)
+    pub base_confirmations: u32,
+
+    /// Tiers sorted by `min_amount_atomic` (ascending)
+    ///
+    /// Default: 5 XMR → 15 confirmations, 20 XMR → 30 confirmations
+    pub tiers: Vec<ConfirmationTier>,
+}
+
+impl Default for ConfirmationPolicy {
+    fn default() -> Self {
+        Self {
+            base_confirmations: 10,
+            tiers: vec![
+                ConfirmationTier {
+                    min_amount_atomic: 5_000_000_000_000, // 5 XMR
+                    required_confirmations: 15,
+                },
+                ConfirmationTier {
+                    min_amount_atomic: 20_000_000_000_000, // 20 XMR
+                    required_confirmations: 30,
+                },
+            ],
+        }
+    }
+}
+
+impl ConfirmationPolicy {
+    /// Create ConfirmationPolicy from environment variables
+    ///
+    /// Reads configuration from:
+    /// - CONFIRMATIONS_BASE
+    /// - CONFIRMATION_TIERS (`<atomic>:<confirmations>` pairs separated by
+    ///   commas, e.g. `5000000000000:15,20000000000000:30`)
+    ///
+    /// Falls back to defaults if not set or invalid.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        let base_confirmations = std::env::var("CONFIRMATIONS_BASE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .filter(|n| *n > 0)
+            .unwrap_or(defaults.base_confirmations);
+
+        let tiers = match std::env::var("CONFIRMATION_TIERS") {
+            Ok(raw) => Self::parse_tiers(&raw).unwrap_or_else(|| {
+                warn!(
+                    "Invalid CONFIRMATION_TIERS '{}', using default confirmation tiers",
+                    raw
+                );
+                defaults.tiers
+            }),
+            Err(_) => defaults.tiers,
+        };
+
+        Self::new(base_confirmations, tiers)
+    }
+
+    /// Create a policy (tiers are sorted by minimum amount)
+    pub fn new(base_confirmations: u32, mut tiers: Vec<ConfirmationTier>) -> Self {
+        tiers.sort_by_key(|tier| tier.min_amount_atomic);
+        Self {
+            base_confirmations,
+            tiers,
+        }
+    }
+
+    /// Single requirement for every amount
+    pub fn flat(confirmations: u32) -> Self {
+        Self::new(confirmations, Vec::new())
+    }
+
+    /// Parse `<atomic>:<confirmations>,...` (None if any entry is malformed)
+    fn parse_tiers(raw: &str) -> Option<Vec<ConfirmationTier>> {
+        raw.split(',')
+            .map(|entry| {
+                let (amount, confirmations) = entry.trim().split_once(':')?;
+                let min_amount_atomic: u64 = amount.trim().parse().ok().filter(|a| *a > 0)?;
+                let required_confirmations: u32 =
+                    confirmations.trim().parse().ok().filter(|n| *n > 0)?;
+                Some(ConfirmationTier {
+                    min_amount_atomic,
+                    required_confirmations,
+                })
+            })
+            .collect()
+    }
+
+    /// Confirmations required for an escrow of `amount_atomic`
+    ///
+    /// Never below `base_confirmations`, even if a tier is configured lower.
+    pub fn required_for(&self, amount_atomic: u64) -> u32 {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| amount_atomic >= tier.min_amount_atomic)
+            .map_or(self.base_confirmations, |tier| {
+                tier.required_confirmations.max(self.base_confirmations)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_XMR: u64 = 1_000_000_000_000;
+
+    #[test]
+    fn test_required_for_by_tier() {
+        let policy = ConfirmationPolicy::default();
+
+        assert_eq!(policy.required_for(0), 10);
+        assert_eq!(policy.required_for(5 * ONE_XMR - 1), 10);
+        assert_eq!(policy.required_for(5 * ONE_XMR), 15);
+        assert_eq!(policy.required_for(20 * ONE_XMR), 30);
+        assert_eq!(policy.required_for(u64::MAX), 30);
+
+        assert_eq!(ConfirmationPolicy::flat(3).required_for(100 * ONE_XMR), 3);
+    }
+
+    #[test]
+    fn test_parse_tiers() {
+        let tiers = ConfirmationPolicy::parse_tiers("9000:20, 1000:12").unwrap();
+        let policy = ConfirmationPolicy::new(10, tiers);
+
+        assert_eq!(policy.tiers[0].min_amount_atomic, 1000);
+        assert_eq!(policy.required_for(999), 10);
+        assert_eq!(policy.required_for(1000), 12);
+        assert_eq!(policy.required_for(9000), 20);
+
+        // A tier below the base requirement cannot weaken it
+        let policy =
+            ConfirmationPolicy::new(10, ConfirmationPolicy::parse_tiers("1000:2").unwrap());
+        assert_eq!(policy.required_for(1000), 10);
+
+        assert!(ConfirmationPolicy::parse_tiers("1000").is_none());
+        assert!(ConfirmationPolicy::parse_tiers("0:5").is_none());
+        assert!(ConfirmationPolicy::parse_tiers("1000:0").is_none());
+        assert!(ConfirmationPolicy::parse_tiers("1000:many").is_none());
+    }
+}
@@ -1,5 +1,19 @@
 //! Configuration modules for the Monero Marketplace server
 
+pub mod arbiter;
+pub mod audit;
+pub mod bond;
+pub mod confirmation;
+pub mod price;
+pub mod reputation;
+pub mod retention;
 pub mod timeout;
 
+pub use arbiter::ArbiterPoolConfig;
+pub use audit::AuditCheckpointPolicy;
+pub use bond::BondPolicy;
+pub use confirmation::ConfirmationPolicy;
+pub use price::PriceOracleConfig;
+pub use reputation::ReviewPolicy;
+pub use retention::RetentionPolicy;
 pub use timeout::TimeoutConfig;
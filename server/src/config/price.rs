@@ -0,0 +1,221 @@
+//! Price oracle configuration
+//!
+//! XMR exchange rates are taken as the median of several sources, after
+//! dropping sources too far from the others. A rate older than `max_age_secs`
+//! is still served, but reported as stale.
+//!
+//! Fiat-priced listings are converted at checkout; the XMR amount holds for
+//! `quote_window_secs`, after which an unfunded order is requoted.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::warn;
+
+/// Known price source names
+pub const PRICE_SOURCE_NAMES: &[&str] = &["coingecko", "kraken", "manual"];
+
+/// Price oracle settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceOracleConfig {
+    /// Sources queried on each refresh
+    ///
+    /// Default: coingecko, kraken (use `manual` alone for offline operation)
+    pub sources: Vec<String>,
+
+    /// Fiat currencies to price XMR in (lowercase ISO 4217)
+    ///
+    /// Default: usd, eur
+    pub currencies: Vec<String>,
+
+    /// How often rates are refreshed
+    ///
+    /// Default: 300 seconds (5 minutes)
+    pub refresh_secs: u64,
+
+    /// Age after which a rate is reported as stale
+    ///
+    /// Default: 1800 seconds (30 minutes)
+    pub max_age_secs: u64,
+
+    /// Sources further than this from the median are rejected as outliers
+    ///
+    /// Default: 5.0 (percent)
+    pub max_deviation_pct: f64,
+
+    /// Sources that must agree for a new rate to be recorded
+    ///
+    /// Default: 1
+    pub min_sources: usize,
+
+    /// How long the XMR amount of a fiat-priced order holds before it is
+    /// requoted, if the escrow has received nothing
+    ///
+    /// Default: 1800 seconds (30 minutes)
+    pub quote_window_secs: u64,
+
+    /// JSON file of manual rates (`{"usd": 150.0}`), read by the `manual` source
+    ///
+    /// Default: none (only rates set through `/admin/prices` are used)
+    pub manual_rates_file: Option<PathBuf>,
+
+    /// Proxy for the HTTP sources
+    ///
+    /// Default: `socks5h://127.0.0.1:9050` (Tor); `none` connects directly
+    pub proxy: Option<String>,
+}
+
+impl Default for PriceOracleConfig {
+    fn default() -> Self {
+        Self {
+            sources: vec!["coingecko".to_string(), "kraken".to_string()],
+            currencies: vec!["usd".to_string(), "eur".to_string()],
+            refresh_secs: 300,
+            max_age_secs: 1800,
+            max_deviation_pct: 5.0,
+            min_sources: 1,
+            quote_window_secs: 1800,
+            manual_rates_file: None,
+            proxy: Some("socks5h://127.0.0.1:9050".to_string()),
+        }
+    }
+}
+
+impl PriceOracleConfig {
+    /// Create PriceOracleConfig from environment variables
+    ///
+    /// Reads configuration from:
+    /// - PRICE_SOURCES (comma-separated, e.g. `coingecko,kraken,manual`)
+    /// - PRICE_CURRENCIES (comma-separated, e.g. `usd,eur,gbp`)
+    /// - PRICE_REFRESH_SECS
+    /// - PRICE_MAX_AGE_SECS
+    /// - PRICE_MAX_DEVIATION_PCT
+    /// - PRICE_MIN_SOURCES
+    /// - PRICE_QUOTE_WINDOW_SECS
+    /// - PRICE_MANUAL_RATES_FILE
+    /// - PRICE_PROXY (`none` for direct connections)
+    ///
+    /// Falls back to defaults if not set or invalid.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+
+        let sources = match std::env::var("PRICE_SOURCES") {
+            Ok(raw) => Self::parse_sources(&raw).unwrap_or_else(|| {
+                warn!(
+                    "Invalid PRICE_SOURCES '{}', using default price sources",
+                    raw
+                );
+                defaults.sources
+            }),
+            Err(_) => defaults.sources,
+        };
+
+        let currencies = std::env::var("PRICE_CURRENCIES")
+            .ok()
+            .map(|raw| parse_list(&raw))
+            .filter(|currencies| {
+                !currencies.is_empty()
+                    && currencies
+                        .iter()
+                        .all(|c| c.len() == 3 && c.chars().all(|ch| ch.is_ascii_alphabetic()))
+            })
+            .unwrap_or(defaults.currencies);
+
+        Self {
+            sources,
+            currencies,
+            refresh_secs: std::env::var("PRICE_REFRESH_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(defaults.refresh_secs),
+            max_age_secs: std::env::var("PRICE_MAX_AGE_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(defaults.max_age_secs),
+            max_deviation_pct: std::env::var("PRICE_MAX_DEVIATION_PCT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|pct: &f64| pct.is_finite() && *pct > 0.0)
+                .unwrap_or(defaults.max_deviation_pct),
+            min_sources: std::env::var("PRICE_MIN_SOURCES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(defaults.min_sources),
+            quote_window_secs: std::env::var("PRICE_QUOTE_WINDOW_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|secs| *secs > 0)
+                .unwrap_or(defaults.quote_window_secs),
+            manual_rates_file: std::env::var("PRICE_MANUAL_RATES_FILE")
+                .ok()
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from)
+                .or(defaults.manual_rates_file),
+            proxy: match std::env::var("PRICE_PROXY") {
+                Ok(proxy) if proxy == "none" => None,
+                Ok(proxy) if !proxy.is_empty() => Some(proxy),
+                _ => defaults.proxy,
+            },
+        }
+    }
+
+    /// Parse a comma-separated source list (None if empty or a name is unknown)
+    fn parse_sources(raw: &str) -> Option<Vec<String>> {
+        let sources = parse_list(raw);
+        let known = sources
+            .iter()
+            .all(|source| PRICE_SOURCE_NAMES.contains(&source.as_str()));
+        (known && !sources.is_empty()).then_some(sources)
+    }
+
+    /// Whether the `manual` source is enabled
+    pub fn manual_enabled(&self) -> bool {
+        self.sources.iter().any(|source| source == "manual")
+    }
+
+    /// Get refresh interval as Duration
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_secs(self.refresh_secs)
+    }
+
+    /// Get quote window as Duration
+    pub fn quote_window(&self) -> Duration {
+        Duration::from_secs(self.quote_window_secs)
+    }
+}
+
+/// Lowercase, trimmed, non-empty entries of a comma-separated list
+fn parse_list(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(|entry| entry.trim().to_lowercase())
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config() {
+        let config = PriceOracleConfig::default();
+        assert_eq!(config.sources, vec!["coingecko", "kraken"]);
+        assert_eq!(config.currencies, vec!["usd", "eur"]);
+        assert_eq!(config.refresh_interval(), Duration::from_secs(300));
+        assert_eq!(config.quote_window(), Duration::from_secs(1800));
+        assert!(!config.manual_enabled());
+    }
+
+    #[test]
+    fn test_parse_sources() {
+        assert_eq!(
+            PriceOracleConfig::parse_sources(" Kraken, manual "),
+            Some(vec!["kraken".to_string(), "manual".to_string()])
+        );
+        assert_eq!(PriceOracleConfig::parse_sources("coingecko,binance"), None);
+        assert_eq!(PriceOracleConfig::parse_sources(" , "), None);
+    }
+}
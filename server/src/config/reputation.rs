@@ -0,0 +1,186 @@
+//! Review binding policy
+//!
+//! A 2.0 review signs the vendor it is about, the escrow of the order and
+//! the domain of the marketplace it was written on. The domain is the one
+//! buyers reach this marketplace at, so a review exported from here cannot
+//! pass for one written elsewhere.
+//!
+//! A review is marked verified once a transaction proof shows its escrow
+//! was funded on-chain; the transaction itself is read from the local daemon.
+//!
+//! Vendors are ranked by a score weighting reviews by the amount paid and
+//! their age, pulled towards a prior rating while they have few sales.
+//!
+//! Buyers may amend a 2.0 review for a while after posting it; earlier
+//! versions stay in the review's signed history.
+
+use anyhow::{bail, Result};
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use reputation_common::types::{ReviewBinding, ScoringParams};
+
+/// Which reviews are accepted and what they must be bound to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewPolicy {
+    /// Domain signed into 2.0 reviews (e.g. `market.example.onion`)
+    ///
+    /// Required at startup (`MARKETPLACE_DOMAIN`); `localhost` only in tests
+    pub marketplace_domain: String,
+
+    /// Whether 1.0 reviews (bound to nothing) are still accepted, submitted
+    /// or imported from another marketplace
+    ///
+    /// Default: false
+    pub accept_unbound_reviews: bool,
+
+    /// monerod RPC the transactions of payment proofs are read from
+    ///
+    /// Default: http://127.0.0.1:18081 (must be localhost)
+    pub daemon_rpc_url: String,
+
+    /// Parameters of the vendor score, signed into exported files
+    ///
+    /// Default: 180-day half-life, 3.5★ prior weighing as 5 reviews
+    pub scoring: ScoringParams,
+
+    /// How long after posting a buyer may amend their review, in hours
+    ///
+    /// Default: 168 (7 days); 0 disables amendments
+    pub amendment_window_hours: u32,
+}
+
+impl Default for ReviewPolicy {
+    fn default() -> Self {
+        Self {
+            marketplace_domain: "localhost".to_string(),
+            accept_unbound_reviews: false,
+            daemon_rpc_url: "http://127.0.0.1:18081".to_string(),
+            scoring: ScoringParams::default(),
+            amendment_window_hours: 168,
+        }
+    }
+}
+
+impl ReviewPolicy {
+    /// Create ReviewPolicy from environment variables
+    ///
+    /// Reads configuration from:
+    /// - MARKETPLACE_DOMAIN (required)
+    /// - REVIEW_ACCEPT_UNBOUND (`true`/`false`)
+    /// - MONERO_DAEMON_RPC_URL
+    /// - REPUTATION_HALF_LIFE_DAYS (0 disables decay)
+    /// - REPUTATION_PRIOR_RATING (stars, e.g. `3.5`)
+    /// - REPUTATION_PRIOR_WEIGHT (in reviews, e.g. `5`)
+    /// - REVIEW_AMENDMENT_WINDOW_HOURS
+    ///
+    /// Falls back to defaults for the others if not set.
+    ///
+    /// # Errors
+    /// Returns an error if MARKETPLACE_DOMAIN is not set: reviews signed for
+    /// a placeholder domain would verify on any other marketplace using it.
+    pub fn from_env() -> Result<Self> {
+        let marketplace_domain = std::env::var("MARKETPLACE_DOMAIN")
+            .ok()
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty());
+        let Some(marketplace_domain) = marketplace_domain else {
+            bail!("MARKETPLACE_DOMAIN must be set to the domain buyers reach this marketplace at");
+        };
+
+        let defaults = Self::default();
+        Ok(Self {
+            marketplace_domain,
+            accept_unbound_reviews: std::env::var("REVIEW_ACCEPT_UNBOUND")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.accept_unbound_reviews),
+            daemon_rpc_url: std::env::var("MONERO_DAEMON_RPC_URL")
+                .ok()
+                .map(|s| s.trim().trim_end_matches('/').to_string())
+                .filter(|s| !s.is_empty())
+                .unwrap_or(defaults.daemon_rpc_url),
+            scoring: ScoringParams {
+                half_life_days: std::env::var("REPUTATION_HALF_LIFE_DAYS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(defaults.scoring.half_life_days),
+                prior_rating_milli: std::env::var("REPUTATION_PRIOR_RATING")
+                    .ok()
+                    .and_then(|s| s.parse::<f64>().ok())
+                    .filter(|r| (1.0..=5.0).contains(r))
+                    .map(|r| (r * 1000.0).round() as u32)
+                    .unwrap_or(defaults.scoring.prior_rating_milli),
+                prior_weight_milli: std::env::var("REPUTATION_PRIOR_WEIGHT")
+                    .ok()
+                    .and_then(|s| s.parse::<f64>().ok())
+                    .filter(|w| (0.0..=1000.0).contains(w))
+                    .map(|w| (w * 1000.0).round() as u32)
+                    .unwrap_or(defaults.scoring.prior_weight_milli),
+                ..defaults.scoring
+            },
+            amendment_window_hours: std::env::var("REVIEW_AMENDMENT_WINDOW_HOURS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.amendment_window_hours),
+        })
+    }
+
+    /// Whether a review posted at `created_at` may still be amended at `now`
+    pub fn can_amend(&self, created_at: NaiveDateTime, now: NaiveDateTime) -> bool {
+        self.amendment_window_hours > 0
+            && now - created_at <= Duration::hours(i64::from(self.amendment_window_hours))
+    }
+
+    /// Binding a review of `escrow_id` for this vendor must carry
+    pub fn binding_for(&self, vendor_pubkey: &str, escrow_id: &str) -> ReviewBinding {
+        ReviewBinding {
+            vendor_pubkey: vendor_pubkey.to_string(),
+            escrow_id: escrow_id.to_string(),
+            marketplace_domain: self.marketplace_domain.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy() {
+        let policy = ReviewPolicy::default();
+        assert_eq!(policy.marketplace_domain, "localhost");
+        assert!(!policy.accept_unbound_reviews);
+        assert_eq!(policy.daemon_rpc_url, "http://127.0.0.1:18081");
+        assert_eq!(policy.scoring.half_life_days, 180);
+        assert_eq!(policy.scoring.prior_rating_milli, 3500);
+        assert_eq!(policy.scoring.prior_weight_milli, 5000);
+        assert_eq!(policy.amendment_window_hours, 168);
+    }
+
+    #[test]
+    fn test_amendment_window() {
+        let posted = chrono::Utc::now().naive_utc();
+        let policy = ReviewPolicy::default();
+        assert!(policy.can_amend(posted, posted + Duration::hours(168)));
+        assert!(!policy.can_amend(posted, posted + Duration::hours(169)));
+
+        let disabled = ReviewPolicy {
+            amendment_window_hours: 0,
+            ..ReviewPolicy::default()
+        };
+        assert!(!disabled.can_amend(posted, posted));
+    }
+
+    #[test]
+    fn test_binding_for() {
+        let policy = ReviewPolicy {
+            marketplace_domain: "market.example.onion".to_string(),
+            ..ReviewPolicy::default()
+        };
+        let binding = policy.binding_for("vendor", "escrow");
+        assert_eq!(binding.vendor_pubkey, "vendor");
+        assert_eq!(binding.escrow_id, "escrow");
+        assert_eq!(binding.marketplace_domain, "market.example.onion");
+    }
+}
@@ -0,0 +1,87 @@
+//! Retention of personal data attached to orders
+//!
+//! Shipping addresses are only needed until the order is settled. Once an
+//! order is completed or refunded, its encrypted address is kept for a
+//! grace period (late disputes, lost parcels) and then erased.
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Retention policy for order shipping data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Days the shipping address of a completed/refunded order is kept
+    ///
+    /// Default: 30 days
+    pub shipping_address_days: u32,
+
+    /// How often expired shipping addresses are purged
+    ///
+    /// Default: 3600 seconds (1 hour)
+    pub purge_interval_secs: u64,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            shipping_address_days: 30,
+            purge_interval_secs: 3600,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// Create RetentionPolicy from environment variables
+    ///
+    /// Reads configuration from:
+    /// - SHIPPING_ADDRESS_RETENTION_DAYS
+    /// - SHIPPING_ADDRESS_PURGE_INTERVAL_SECS
+    ///
+    /// Falls back to defaults if not set.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            shipping_address_days: std::env::var("SHIPPING_ADDRESS_RETENTION_DAYS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.shipping_address_days),
+            purge_interval_secs: std::env::var("SHIPPING_ADDRESS_PURGE_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.purge_interval_secs),
+        }
+    }
+
+    /// Orders closed before this time have their shipping data purged
+    pub fn shipping_cutoff(&self, now: NaiveDateTime) -> NaiveDateTime {
+        now - chrono::Duration::days(i64::from(self.shipping_address_days))
+    }
+
+    /// Get purge interval as Duration
+    pub fn purge_interval(&self) -> Duration {
+        Duration::from_secs(self.purge_interval_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy() {
+        let policy = RetentionPolicy::default();
+        assert_eq!(policy.shipping_address_days, 30);
+        assert_eq!(policy.purge_interval(), Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn test_shipping_cutoff() {
+        let policy = RetentionPolicy {
+            shipping_address_days: 7,
+            ..RetentionPolicy::default()
+        };
+        let now = chrono::Utc::now().naive_utc();
+        assert_eq!(now - policy.shipping_cutoff(now), chrono::Duration::days(7));
+    }
+}
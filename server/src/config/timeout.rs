@@ -3,6 +3,9 @@
 //! This module defines timeout policies for various escrow states and operations
 //! to prevent stuck transactions and improve system resilience.
 
+use chrono::NaiveDateTime;
+use monero_marketplace_common::clock::ChainClock;
+use monero_marketplace_common::types::EscrowStatus;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
@@ -38,6 +41,13 @@ pub struct TimeoutConfig {
     /// Default: 7 days
     pub dispute_resolution_timeout_secs: u64,
 
+    /// Time buyer and vendor have to submit dispute evidence
+    ///
+    /// Counted from the opening of the dispute. After this period the
+    /// evidence list is frozen so the arbiter reviews a fixed bundle.
+    /// Default: 72 hours
+    pub dispute_evidence_window_secs: u64,
+
     /// How often the TimeoutMonitor polls for expired escrows
     ///
     /// Lower values = faster detection, higher DB load.
@@ -58,6 +68,7 @@ impl Default for TimeoutConfig {
             funding_timeout_secs: 86400,                 // 24 hours
             transaction_confirmation_timeout_secs: 21600, // 6 hours
             dispute_resolution_timeout_secs: 604800,     // 7 days
+            dispute_evidence_window_secs: 259200,        // 72 hours
             poll_interval_secs: 60,                      // 1 minute
             warning_threshold_secs: 3600,                // 1 hour
         }
@@ -72,6 +83,7 @@ impl TimeoutConfig {
     /// - TIMEOUT_FUNDING_SECS
     /// - TIMEOUT_TX_CONFIRMATION_SECS
     /// - TIMEOUT_DISPUTE_RESOLUTION_SECS
+    /// - TIMEOUT_DISPUTE_EVIDENCE_SECS
     /// - TIMEOUT_POLL_INTERVAL_SECS
     /// - TIMEOUT_WARNING_THRESHOLD_SECS
     ///
@@ -96,6 +108,10 @@ impl TimeoutConfig {
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(604800),
+            dispute_evidence_window_secs: std::env::var("TIMEOUT_DISPUTE_EVIDENCE_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(259200),
             poll_interval_secs: std::env::var("TIMEOUT_POLL_INTERVAL_SECS")
                 .ok()
                 .and_then(|s| s.parse().ok())
@@ -111,20 +127,50 @@ impl TimeoutConfig {
     ///
     /// Returns the configured timeout for the given status, or None if
     /// the status doesn't have an associated timeout policy.
-    pub fn timeout_for_status(&self, status: &str) -> Option<Duration> {
+    pub fn timeout_for_status(&self, status: EscrowStatus) -> Option<Duration> {
         match status {
-            "created" => Some(Duration::from_secs(self.multisig_setup_timeout_secs)),
-            "funded" => Some(Duration::from_secs(self.funding_timeout_secs)),
-            "releasing" | "refunding" => {
+            EscrowStatus::Created => Some(Duration::from_secs(self.multisig_setup_timeout_secs)),
+            EscrowStatus::Funded | EscrowStatus::Underfunded => {
+                Some(Duration::from_secs(self.funding_timeout_secs))
+            }
+            EscrowStatus::Releasing | EscrowStatus::Refunding => {
                 Some(Duration::from_secs(self.transaction_confirmation_timeout_secs))
             }
-            "disputed" => Some(Duration::from_secs(self.dispute_resolution_timeout_secs)),
+            EscrowStatus::Disputed => {
+                Some(Duration::from_secs(self.dispute_resolution_timeout_secs))
+            }
+            // Funds received or ruling given: the next step is driven by a party or payout
+            EscrowStatus::Active
+            | EscrowStatus::Overfunded
+            | EscrowStatus::ResolvedBuyer
+            | EscrowStatus::ResolvedVendor
+            | EscrowStatus::ResolvedSplit => None,
             // Terminal states have no timeout
-            "completed" | "refunded" | "cancelled" | "expired" => None,
-            _ => None,
+            EscrowStatus::Completed
+            | EscrowStatus::Refunded
+            | EscrowStatus::Cancelled
+            | EscrowStatus::Expired => None,
         }
     }
 
+    /// Deadline (`expires_at`) for an escrow entering `status` now
+    ///
+    /// "Now" comes from `clock`, so a simulated chain can be used in tests.
+    /// Returns None for statuses without a timeout policy.
+    pub fn deadline_for_status(
+        &self,
+        status: EscrowStatus,
+        clock: &dyn ChainClock,
+    ) -> Option<NaiveDateTime> {
+        let timeout = chrono::Duration::from_std(self.timeout_for_status(status)?).ok()?;
+        Some(clock.now_naive() + timeout)
+    }
+
+    /// Time evidence can be submitted after a dispute opens
+    pub fn dispute_evidence_window(&self) -> Duration {
+        Duration::from_secs(self.dispute_evidence_window_secs)
+    }
+
     /// Get warning threshold as Duration
     pub fn warning_threshold(&self) -> Duration {
         Duration::from_secs(self.warning_threshold_secs)
@@ -147,6 +193,7 @@ mod tests {
         assert_eq!(config.funding_timeout_secs, 86400);
         assert_eq!(config.transaction_confirmation_timeout_secs, 21600);
         assert_eq!(config.dispute_resolution_timeout_secs, 604800);
+        assert_eq!(config.dispute_evidence_window_secs, 259200);
         assert_eq!(config.poll_interval_secs, 60);
         assert_eq!(config.warning_threshold_secs, 3600);
     }
@@ -157,31 +204,46 @@ mod tests {
 
         // Active states with timeouts
         assert_eq!(
-            config.timeout_for_status("created"),
+            config.timeout_for_status(EscrowStatus::Created),
             Some(Duration::from_secs(3600))
         );
         assert_eq!(
-            config.timeout_for_status("funded"),
+            config.timeout_for_status(EscrowStatus::Funded),
             Some(Duration::from_secs(86400))
         );
         assert_eq!(
-            config.timeout_for_status("releasing"),
+            config.timeout_for_status(EscrowStatus::Releasing),
             Some(Duration::from_secs(21600))
         );
         assert_eq!(
-            config.timeout_for_status("refunding"),
+            config.timeout_for_status(EscrowStatus::Refunding),
             Some(Duration::from_secs(21600))
         );
         assert_eq!(
-            config.timeout_for_status("disputed"),
+            config.timeout_for_status(EscrowStatus::Disputed),
             Some(Duration::from_secs(604800))
         );
 
         // Terminal states with no timeout
-        assert_eq!(config.timeout_for_status("completed"), None);
-        assert_eq!(config.timeout_for_status("refunded"), None);
-        assert_eq!(config.timeout_for_status("cancelled"), None);
-        assert_eq!(config.timeout_for_status("expired"), None);
+        assert_eq!(config.timeout_for_status(EscrowStatus::Completed), None);
+        assert_eq!(config.timeout_for_status(EscrowStatus::Refunded), None);
+        assert_eq!(config.timeout_for_status(EscrowStatus::Cancelled), None);
+        assert_eq!(config.timeout_for_status(EscrowStatus::Expired), None);
+    }
+
+    #[test]
+    fn test_deadline_for_status() {
+        use monero_marketplace_common::clock::SystemClock;
+
+        let config = TimeoutConfig::default();
+        let before = chrono::Utc::now().naive_utc();
+        let deadline = config
+            .deadline_for_status(EscrowStatus::Funded, &SystemClock)
+            .expect("funded has a timeout");
+        let remaining = (deadline - before).num_seconds();
+        assert!((86400..=86401).contains(&remaining));
+
+        assert!(config.deadline_for_status(EscrowStatus::Completed, &SystemClock).is_none());
     }
 
     #[test]
@@ -360,6 +360,19 @@ impl EscrowCoordinator {
             .ok_or_else(|| Error::EscrowNotFound(escrow_id.to_string()))
     }
 
+    /// Drop the coordination state of an escrow
+    ///
+    /// Used when a participant changes (arbiter reassignment) before the
+    /// multisig is finalized: all three wallets must register again.
+    /// Returns true if there was a coordination to drop.
+    pub async fn reset_coordination(&self, escrow_id: &str) -> bool {
+        let removed = self.coordinations.write().await.remove(escrow_id).is_some();
+        if removed {
+            info!("Coordination reset for escrow {}", escrow_id);
+        }
+        removed
+    }
+
     // ============================================================================
     // PRIVATE HELPER METHODS
     // ============================================================================
@@ -23,11 +23,23 @@
 //! - Never committed to version control
 //! - Rotated periodically (quarterly recommended)
 //! - Unique per environment (dev, staging, prod)
+//!
+//! # Key Rotation
+//!
+//! Ciphertexts carry the [`key_id`] of the key that produced them. After a
+//! rotation the previous key is registered with [`register_previous_key`]
+//! (from `DB_PREVIOUS_ENCRYPTION_KEYS`, see [`load_previous_keys_from_env`])
+//! so that data not yet re-encrypted stays readable until
+//! [`crate::services::key_rotation`] has moved every column to the new key.
+//! Ciphertexts written before key ids existed (no header) are still read.
 
 use aes_gcm::aead::{Aead, KeyInit, OsRng};
 use aes_gcm::{Aes256Gcm, Nonce};
 use anyhow::{Context, Result};
+use once_cell::sync::Lazy;
 use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::sync::RwLock;
 
 /// Size of AES-256-GCM encryption key in bytes (256 bits)
 pub const KEY_SIZE: usize = 32;
@@ -38,6 +50,21 @@ pub const NONCE_SIZE: usize = 12;
 /// Minimum encrypted data size (nonce + at least 1 byte plaintext + 16 byte auth tag)
 const MIN_ENCRYPTED_SIZE: usize = NONCE_SIZE + 1 + 16;
 
+/// Marker of the key-versioned format (followed by the 4-byte key id)
+const VERSIONED_TAG: [u8; 4] = [0xE5, b'K', b'V', 0x01];
+
+/// Size of the key id in the header
+pub const KEY_ID_SIZE: usize = 4;
+
+/// Size of the versioned header (tag + key id)
+const HEADER_SIZE: usize = VERSIONED_TAG.len() + KEY_ID_SIZE;
+
+/// Environment variable listing retired keys (comma-separated hex)
+pub const PREVIOUS_KEYS_ENV: &str = "DB_PREVIOUS_ENCRYPTION_KEYS";
+
+/// Keys rotated out but still accepted for decryption
+static PREVIOUS_KEYS: Lazy<RwLock<Vec<Vec<u8>>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
 /// Generate a cryptographically secure random 256-bit encryption key
 ///
 /// # Security
@@ -77,7 +104,8 @@ pub fn generate_key() -> Vec<u8> {
 ///
 /// # Returns
 ///
-/// Encrypted data with format: `[nonce (12 bytes)][ciphertext][auth tag (16 bytes)]`
+/// Encrypted data with format:
+/// `[tag (4 bytes)][key id (4 bytes)][nonce (12 bytes)][ciphertext][auth tag (16 bytes)]`
 ///
 /// # Security
 ///
@@ -106,27 +134,7 @@ pub fn encrypt_field(plaintext: &str, key: &[u8]) -> Result<Vec<u8>> {
         anyhow::bail!("Cannot encrypt empty plaintext - use Option<Vec<u8>> for nullable fields");
     }
 
-    // Create cipher
-    let cipher =
-        Aes256Gcm::new_from_slice(key).context("Failed to create AES-256-GCM cipher from key")?;
-
-    // Generate cryptographically secure random nonce
-    let mut nonce_bytes = [0u8; NONCE_SIZE];
-    OsRng.fill_bytes(&mut nonce_bytes);
-    #[allow(deprecated)]
-    let nonce = Nonce::from_slice(&nonce_bytes);
-
-    // Encrypt
-    let ciphertext = cipher
-        .encrypt(nonce, plaintext.as_bytes())
-        .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
-
-    // Prepend nonce to ciphertext for storage
-    let mut result = Vec::with_capacity(NONCE_SIZE + ciphertext.len());
-    result.extend_from_slice(&nonce_bytes);
-    result.extend_from_slice(&ciphertext);
-
-    Ok(result)
+    seal(plaintext.as_bytes(), key)
 }
 
 /// Decrypt ciphertext string using AES-256-GCM
@@ -134,7 +142,8 @@ pub fn encrypt_field(plaintext: &str, key: &[u8]) -> Result<Vec<u8>> {
 /// # Arguments
 ///
 /// * `ciphertext_with_nonce` - Data encrypted with `encrypt_field` (nonce prepended)
-/// * `key` - Same 32-byte encryption key used for encryption
+/// * `key` - Current 32-byte encryption key; data written under a key
+///   registered with [`register_previous_key`] is decrypted with that key
 ///
 /// # Returns
 ///
@@ -163,38 +172,221 @@ pub fn decrypt_field(ciphertext_with_nonce: &[u8], key: &[u8]) -> Result<String>
         );
     }
 
-    // Validate encrypted data size
-    if ciphertext_with_nonce.len() < MIN_ENCRYPTED_SIZE {
+    // Decrypt and verify authentication tag
+    let plaintext_bytes = open(ciphertext_with_nonce, key)?;
+
+    // Convert to UTF-8 string
+    String::from_utf8(plaintext_bytes).context("Decrypted data is not valid UTF-8")
+}
+
+/// Encrypt binary data (files) using AES-256-GCM
+///
+/// Same format as [`encrypt_field`] (nonce prepended), for content that is
+/// not UTF-8 text, e.g. dispute evidence files.
+///
+/// # Errors
+///
+/// Returns error if the key is not 32 bytes, data is empty or encryption fails
+pub fn encrypt_bytes(data: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    if key.len() != KEY_SIZE {
+        anyhow::bail!(
+            "Encryption key must be exactly {} bytes, got {}",
+            KEY_SIZE,
+            key.len()
+        );
+    }
+
+    if data.is_empty() {
+        anyhow::bail!("Cannot encrypt empty data");
+    }
+
+    seal(data, key)
+}
+
+/// Decrypt binary data produced by [`encrypt_bytes`]
+///
+/// # Errors
+///
+/// Returns error if the key is not 32 bytes, data is too short, or the
+/// authentication tag does not verify (wrong key or tampered data)
+pub fn decrypt_bytes(ciphertext_with_nonce: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    if key.len() != KEY_SIZE {
+        anyhow::bail!(
+            "Decryption key must be exactly {} bytes, got {}",
+            KEY_SIZE,
+            key.len()
+        );
+    }
+
+    open(ciphertext_with_nonce, key)
+}
+
+/// Short identifier of a key, stored in front of every ciphertext
+///
+/// First 4 bytes of SHA-256 of the key: enough to pick among the current and
+/// retired keys, useless for recovering the key.
+pub fn key_id(key: &[u8]) -> [u8; KEY_ID_SIZE] {
+    let digest = Sha256::digest(key);
+    let mut id = [0u8; KEY_ID_SIZE];
+    id.copy_from_slice(&digest[..KEY_ID_SIZE]);
+    id
+}
+
+/// Key id recorded in a ciphertext (None for the legacy format without header)
+pub fn ciphertext_key_id(data: &[u8]) -> Option<[u8; KEY_ID_SIZE]> {
+    if data.len() < HEADER_SIZE + MIN_ENCRYPTED_SIZE || data[..VERSIONED_TAG.len()] != VERSIONED_TAG
+    {
+        return None;
+    }
+    let mut id = [0u8; KEY_ID_SIZE];
+    id.copy_from_slice(&data[VERSIONED_TAG.len()..HEADER_SIZE]);
+    Some(id)
+}
+
+/// Whether `data` was encrypted under `key` in the current format
+pub fn is_encrypted_with(data: &[u8], key: &[u8]) -> bool {
+    ciphertext_key_id(data) == Some(key_id(key))
+}
+
+/// Accept a retired key for decryption
+///
+/// # Errors
+///
+/// Returns error if the key is not 32 bytes
+pub fn register_previous_key(key: &[u8]) -> Result<()> {
+    if key.len() != KEY_SIZE {
+        anyhow::bail!(
+            "Previous key must be exactly {} bytes, got {}",
+            KEY_SIZE,
+            key.len()
+        );
+    }
+
+    let mut keys = PREVIOUS_KEYS
+        .write()
+        .map_err(|_| anyhow::anyhow!("Previous key registry poisoned"))?;
+    if !keys.iter().any(|k| k == key) {
+        keys.push(key.to_vec());
+    }
+    Ok(())
+}
+
+/// Register the keys listed in `DB_PREVIOUS_ENCRYPTION_KEYS`, returning how many
+pub fn load_previous_keys_from_env() -> Result<usize> {
+    let Ok(value) = std::env::var(PREVIOUS_KEYS_ENV) else {
+        return Ok(0);
+    };
+
+    let mut count = 0;
+    for key_hex in value.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+        let key =
+            hex::decode(key_hex).context(format!("{} must be hex keys", PREVIOUS_KEYS_ENV))?;
+        register_previous_key(&key)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Whether any retired key is registered
+pub fn has_previous_keys() -> bool {
+    PREVIOUS_KEYS
+        .read()
+        .map(|keys| !keys.is_empty())
+        .unwrap_or(false)
+}
+
+/// Encrypt under `key` with the versioned header
+fn seal(data: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    let cipher =
+        Aes256Gcm::new_from_slice(key).context("Failed to create AES-256-GCM cipher from key")?;
+
+    // Generate cryptographically secure random nonce
+    let mut nonce_bytes = [0u8; NONCE_SIZE];
+    OsRng.fill_bytes(&mut nonce_bytes);
+    #[allow(deprecated)]
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    let ciphertext = cipher
+        .encrypt(nonce, data)
+        .map_err(|e| anyhow::anyhow!("Encryption failed: {}", e))?;
+
+    let mut result = Vec::with_capacity(HEADER_SIZE + NONCE_SIZE + ciphertext.len());
+    result.extend_from_slice(&VERSIONED_TAG);
+    result.extend_from_slice(&key_id(key));
+    result.extend_from_slice(&nonce_bytes);
+    result.extend_from_slice(&ciphertext);
+
+    Ok(result)
+}
+
+/// Decrypt either format, picking the key by id among `key` and the retired keys
+fn open(data: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    if data.len() < MIN_ENCRYPTED_SIZE {
         anyhow::bail!(
             "Encrypted data too short: expected at least {} bytes, got {}. Data may be corrupted.",
             MIN_ENCRYPTED_SIZE,
-            ciphertext_with_nonce.len()
+            data.len()
         );
     }
 
-    // Create cipher
+    let previous = PREVIOUS_KEYS
+        .read()
+        .map_err(|_| anyhow::anyhow!("Previous key registry poisoned"))?;
+    let keys = std::iter::once(key).chain(previous.iter().map(Vec::as_slice));
+
+    if let Some(id) = ciphertext_key_id(data) {
+        match keys.clone().find(|k| key_id(k) == id) {
+            Some(k) => {
+                if let Ok(plaintext) = open_raw(&data[HEADER_SIZE..], k) {
+                    return Ok(plaintext);
+                }
+            }
+            None => {
+                // A legacy nonce may start with the tag by chance
+                if let Ok(plaintext) = open_raw(data, key) {
+                    return Ok(plaintext);
+                }
+                anyhow::bail!(
+                    "Decryption failed: data was encrypted under key {}, which is not loaded \
+                     (set {} after a rotation)",
+                    hex::encode(id),
+                    PREVIOUS_KEYS_ENV
+                );
+            }
+        }
+    }
+
+    // Legacy format: nonce first, key unknown
+    let mut last_error = None;
+    for k in keys {
+        match open_raw(data, k) {
+            Ok(plaintext) => return Ok(plaintext),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| anyhow::anyhow!("Decryption failed")))
+}
+
+/// Decrypt `[nonce][ciphertext][auth tag]`
+fn open_raw(ciphertext_with_nonce: &[u8], key: &[u8]) -> Result<Vec<u8>> {
+    if ciphertext_with_nonce.len() < MIN_ENCRYPTED_SIZE {
+        anyhow::bail!("Encrypted data too short");
+    }
+
     let cipher =
         Aes256Gcm::new_from_slice(key).context("Failed to create AES-256-GCM cipher from key")?;
 
-    // Extract nonce
     #[allow(deprecated)]
     let nonce = Nonce::from_slice(&ciphertext_with_nonce[..NONCE_SIZE]);
 
-    // Extract ciphertext
-    let ciphertext = &ciphertext_with_nonce[NONCE_SIZE..];
-
-    // Decrypt and verify authentication tag
-    let plaintext_bytes = cipher
-        .decrypt(nonce, ciphertext)
+    cipher
+        .decrypt(nonce, &ciphertext_with_nonce[NONCE_SIZE..])
         .map_err(|e| {
             anyhow::anyhow!(
                 "Decryption failed: {}. This indicates wrong key, corrupted data, or tampered ciphertext.",
                 e
             )
-        })?;
-
-    // Convert to UTF-8 string
-    String::from_utf8(plaintext_bytes).context("Decrypted data is not valid UTF-8")
+        })
 }
 
 /// Validate encryption key format
@@ -409,11 +601,12 @@ mod tests {
         let encrypted = encrypt_field(plaintext, &key).expect("Encryption failed");
 
         // Verify structure
-        assert!(encrypted.len() >= NONCE_SIZE + plaintext.len() + 16);
+        assert!(encrypted.len() >= HEADER_SIZE + NONCE_SIZE + plaintext.len() + 16);
 
-        // First 12 bytes should be nonce
-        let nonce = &encrypted[..NONCE_SIZE];
-        assert_eq!(nonce.len(), NONCE_SIZE);
+        // Header carries the key id, nonce follows
+        assert_eq!(encrypted[..VERSIONED_TAG.len()], VERSIONED_TAG);
+        assert_eq!(ciphertext_key_id(&encrypted), Some(key_id(&key)));
+        assert!(is_encrypted_with(&encrypted, &key));
     }
 
     #[test]
@@ -437,4 +630,64 @@ mod tests {
 
         assert_eq!(plaintext, decrypted);
     }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let key = get_test_key();
+        let data = vec![0xFF, 0xD8, 0xFF, 0x00, 0x80, 0x7F]; // not UTF-8
+
+        let encrypted = encrypt_bytes(&data, &key).expect("Encryption failed");
+        let decrypted = decrypt_bytes(&encrypted, &key).expect("Decryption failed");
+        assert_eq!(data, decrypted);
+
+        let mut tampered = encrypted;
+        tampered[NONCE_SIZE] ^= 0x01;
+        assert!(decrypt_bytes(&tampered, &key).is_err());
+        assert!(encrypt_bytes(&[], &key).is_err());
+    }
+
+    /// Legacy ciphertext: nonce first, no header
+    fn legacy_encrypt(plaintext: &str, key: &[u8]) -> Vec<u8> {
+        let encrypted = encrypt_field(plaintext, key).expect("Encryption failed");
+        encrypted[HEADER_SIZE..].to_vec()
+    }
+
+    #[test]
+    fn test_legacy_format_still_decrypts() {
+        let key = get_test_key();
+        let legacy = legacy_encrypt("Old wallet info", &key);
+
+        assert_eq!(ciphertext_key_id(&legacy), None);
+        assert!(!is_encrypted_with(&legacy, &key));
+        assert_eq!(
+            decrypt_field(&legacy, &key).expect("Decryption failed"),
+            "Old wallet info"
+        );
+    }
+
+    #[test]
+    fn test_previous_key_decrypts_after_rotation() {
+        let old_key = generate_key();
+        let new_key = generate_key();
+        let encrypted = encrypt_field("Rotated secret", &old_key).expect("Encryption failed");
+        let legacy = legacy_encrypt("Legacy secret", &old_key);
+
+        let result = decrypt_field(&encrypted, &new_key);
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains(&hex::encode(key_id(&old_key))));
+
+        register_previous_key(&old_key).expect("Register failed");
+        assert!(has_previous_keys());
+        assert_eq!(
+            decrypt_field(&encrypted, &new_key).expect("Decryption failed"),
+            "Rotated secret"
+        );
+        assert_eq!(
+            decrypt_field(&legacy, &new_key).expect("Decryption failed"),
+            "Legacy secret"
+        );
+        assert!(!is_encrypted_with(&encrypted, &new_key));
+    }
 }
@@ -0,0 +1,289 @@
+//! End-to-end encrypted envelopes for order messages
+//!
+//! Message bodies are encrypted in the browser; the server only stores and
+//! relays the envelope and never holds a key able to open it.
+//!
+//! Checkout shipping addresses use the same envelope, encrypted to the
+//! vendors of the order (see [`MessageEnvelope::for_recipient`]).
+//!
+//! # Scheme (client side)
+//!
+//! - Every user registers a P-256 public key (raw uncompressed point, base64)
+//! - The sender draws a random AES-256 content key and encrypts the body with
+//!   AES-256-GCM
+//! - For each recipient, ECDH(ephemeral key, recipient key) → HKDF-SHA256 →
+//!   AES-256-GCM wrapping key, which encrypts the content key
+//! - The sender lists itself as a recipient to read its own messages back
+//!
+//! # Server side
+//!
+//! [`MessageEnvelope::validate`] only checks the envelope format and sizes:
+//! version, algorithm, base64 fields of the expected length and the
+//! recipient list. Whether the recipients are parties to the order is up to
+//! the caller.
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// Current envelope format version
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/// Algorithm identifier carried in every envelope
+pub const ENVELOPE_ALGORITHM: &str = "ECDH-P256+HKDF-SHA256+A256GCM";
+
+/// Size of a raw uncompressed P-256 public key (0x04 || X || Y)
+pub const PUBLIC_KEY_SIZE: usize = 65;
+
+/// Size of an AES-GCM nonce in bytes
+pub const NONCE_SIZE: usize = 12;
+
+/// Largest accepted message body: 2000 characters of up to 4 UTF-8 bytes
+pub const MAX_PLAINTEXT_SIZE: usize = 8000;
+
+/// Buyer, vendor and arbiter
+pub const MAX_RECIPIENTS: usize = 3;
+
+/// AES-GCM authentication tag size
+const TAG_SIZE: usize = 16;
+
+/// Wrapped AES-256 content key (32 bytes + tag)
+const WRAPPED_KEY_SIZE: usize = 32 + TAG_SIZE;
+
+/// Encrypted order message as sent by the client and stored in `order_messages`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MessageEnvelope {
+    pub version: u8,
+    pub algorithm: String,
+    /// One-time P-256 public key of the sender (base64)
+    pub ephemeral_public_key: String,
+    /// Nonce of the body encryption (base64)
+    pub nonce: String,
+    /// Encrypted body with its tag (base64)
+    pub ciphertext: String,
+    /// Content key wrapped for each reader
+    pub recipients: Vec<EnvelopeRecipient>,
+}
+
+/// Content key wrapped for one reader of the message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnvelopeRecipient {
+    pub user_id: String,
+    /// Nonce of the key wrapping (base64)
+    pub nonce: String,
+    /// Encrypted content key (base64)
+    pub wrapped_key: String,
+}
+
+impl MessageEnvelope {
+    /// Check the envelope format and sizes (contents are never inspected)
+    pub fn validate(&self) -> Result<()> {
+        self.validate_with_max_recipients(MAX_RECIPIENTS)
+    }
+
+    /// [`validate`](Self::validate) with a different recipient limit
+    ///
+    /// Shipping addresses are encrypted once for every vendor of a checkout.
+    pub fn validate_with_max_recipients(&self, max_recipients: usize) -> Result<()> {
+        if self.version != ENVELOPE_VERSION {
+            anyhow::bail!("Unsupported envelope version {}", self.version);
+        }
+        if self.algorithm != ENVELOPE_ALGORITHM {
+            anyhow::bail!("Unsupported envelope algorithm '{}'", self.algorithm);
+        }
+
+        validate_public_key(&self.ephemeral_public_key).context("Invalid ephemeral public key")?;
+        decode_exact(&self.nonce, NONCE_SIZE).context("Invalid envelope nonce")?;
+
+        let ciphertext = decode(&self.ciphertext).context("Invalid envelope ciphertext")?;
+        if ciphertext.len() <= TAG_SIZE {
+            anyhow::bail!("Envelope ciphertext is empty");
+        }
+        if ciphertext.len() > MAX_PLAINTEXT_SIZE + TAG_SIZE {
+            anyhow::bail!(
+                "Envelope ciphertext too large ({} bytes, max {})",
+                ciphertext.len(),
+                MAX_PLAINTEXT_SIZE + TAG_SIZE
+            );
+        }
+
+        if self.recipients.is_empty() || self.recipients.len() > max_recipients {
+            anyhow::bail!(
+                "Envelope must have between 1 and {} recipients (got {})",
+                max_recipients,
+                self.recipients.len()
+            );
+        }
+        for (index, recipient) in self.recipients.iter().enumerate() {
+            if self.recipients[..index]
+                .iter()
+                .any(|other| other.user_id == recipient.user_id)
+            {
+                anyhow::bail!("Duplicate envelope recipient {}", recipient.user_id);
+            }
+            decode_exact(&recipient.nonce, NONCE_SIZE)
+                .context(format!("Invalid nonce for recipient {}", recipient.user_id))?;
+            decode_exact(&recipient.wrapped_key, WRAPPED_KEY_SIZE)
+                .context(format!("Invalid wrapped key for recipient {}", recipient.user_id))?;
+        }
+
+        Ok(())
+    }
+
+    /// Users the content key is wrapped for
+    pub fn recipient_ids(&self) -> impl Iterator<Item = &str> {
+        self.recipients.iter().map(|r| r.user_id.as_str())
+    }
+
+    /// Whether `user_id` can decrypt the message
+    pub fn is_addressed_to(&self, user_id: &str) -> bool {
+        self.recipient_ids().any(|id| id == user_id)
+    }
+
+    /// Copy of the envelope readable only by `user_id`
+    ///
+    /// The ciphertext is unchanged; the content keys wrapped for the other
+    /// recipients are dropped. Returns None if `user_id` is not a recipient.
+    pub fn for_recipient(&self, user_id: &str) -> Option<MessageEnvelope> {
+        let recipient = self.recipients.iter().find(|r| r.user_id == user_id)?;
+        Some(MessageEnvelope {
+            recipients: vec![recipient.clone()],
+            ..self.clone()
+        })
+    }
+}
+
+/// Check that `public_key` is a base64 raw uncompressed P-256 point
+///
+/// Only the encoding is checked; the point itself is validated by the
+/// browser when importing the key.
+pub fn validate_public_key(public_key: &str) -> Result<()> {
+    let bytes = decode_exact(public_key, PUBLIC_KEY_SIZE)?;
+    if bytes[0] != 0x04 {
+        anyhow::bail!("Public key must be an uncompressed point (0x04 prefix)");
+    }
+    Ok(())
+}
+
+fn decode(value: &str) -> Result<Vec<u8>> {
+    BASE64_STANDARD
+        .decode(value)
+        .context("Not valid base64")
+}
+
+fn decode_exact(value: &str, size: usize) -> Result<Vec<u8>> {
+    let bytes = decode(value)?;
+    if bytes.len() != size {
+        anyhow::bail!("Expected {} bytes, got {}", size, bytes.len());
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn b64(bytes: &[u8]) -> String {
+        BASE64_STANDARD.encode(bytes)
+    }
+
+    fn public_key() -> String {
+        let mut key = vec![0x04];
+        key.extend_from_slice(&[7u8; 64]);
+        b64(&key)
+    }
+
+    fn recipient(user_id: &str) -> EnvelopeRecipient {
+        EnvelopeRecipient {
+            user_id: user_id.to_string(),
+            nonce: b64(&[1u8; NONCE_SIZE]),
+            wrapped_key: b64(&[2u8; WRAPPED_KEY_SIZE]),
+        }
+    }
+
+    fn envelope() -> MessageEnvelope {
+        MessageEnvelope {
+            version: ENVELOPE_VERSION,
+            algorithm: ENVELOPE_ALGORITHM.to_string(),
+            ephemeral_public_key: public_key(),
+            nonce: b64(&[3u8; NONCE_SIZE]),
+            ciphertext: b64(&[4u8; 42]),
+            recipients: vec![recipient("buyer"), recipient("vendor")],
+        }
+    }
+
+    #[test]
+    fn test_valid_envelope() {
+        let envelope = envelope();
+        assert!(envelope.validate().is_ok());
+        assert!(envelope.is_addressed_to("vendor"));
+        assert!(!envelope.is_addressed_to("arbiter"));
+    }
+
+    #[test]
+    fn test_rejects_bad_format() {
+        let mut bad = envelope();
+        bad.version = 2;
+        assert!(bad.validate().is_err());
+
+        let mut bad = envelope();
+        bad.nonce = b64(&[3u8; 8]);
+        assert!(bad.validate().is_err());
+
+        let mut bad = envelope();
+        bad.ciphertext = "not base64!".to_string();
+        assert!(bad.validate().is_err());
+
+        let mut bad = envelope();
+        bad.recipients[1].wrapped_key = b64(&[2u8; 32]);
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_size_and_recipient_limits() {
+        let mut bad = envelope();
+        bad.ciphertext = b64(&vec![0u8; MAX_PLAINTEXT_SIZE + TAG_SIZE + 1]);
+        assert!(bad.validate().is_err());
+
+        let mut bad = envelope();
+        bad.ciphertext = b64(&[0u8; TAG_SIZE]);
+        assert!(bad.validate().is_err());
+
+        let mut bad = envelope();
+        bad.recipients.push(recipient("buyer"));
+        assert!(bad.validate().is_err());
+
+        let mut bad = envelope();
+        bad.recipients = vec![];
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_for_recipient() {
+        let envelope = envelope();
+        let vendor_copy = envelope.for_recipient("vendor").expect("vendor is a recipient");
+        assert_eq!(vendor_copy.recipients, vec![recipient("vendor")]);
+        assert_eq!(vendor_copy.ciphertext, envelope.ciphertext);
+        assert!(vendor_copy.validate().is_ok());
+        assert!(envelope.for_recipient("arbiter").is_none());
+
+        assert!(envelope.validate_with_max_recipients(1).is_err());
+    }
+
+    #[test]
+    fn test_public_key_format() {
+        assert!(validate_public_key(&public_key()).is_ok());
+        assert!(validate_public_key(&b64(&[0x02; PUBLIC_KEY_SIZE])).is_err());
+        assert!(validate_public_key(&b64(&[0x04; 33])).is_err());
+    }
+
+    #[test]
+    fn test_rejects_unknown_fields() {
+        let mut value = serde_json::to_value(envelope()).expect("Serialization failed");
+        value["plaintext"] = serde_json::json!("leaked");
+        assert!(serde_json::from_value::<MessageEnvelope>(value).is_err());
+    }
+}
@@ -1,4 +1,7 @@
 pub mod encryption;
+pub mod message_envelope;
 pub mod shamir;
 pub mod shamir_startup;
+pub mod signing;
+pub mod vss;
 pub mod multisig_validation;
@@ -6,7 +6,8 @@
 //! # Security Model
 //!
 //! - Key NEVER stored on disk (only in memory during runtime)
-//! - Requires 3 of 5 shares to start server
+//! - Requires 3 of 5 shares to start server (or the threshold recorded in
+//!   verifiable `mkvss1:` shares, see [`super::vss`])
 //! - Shares stored in physically separated locations
 //! - Server seizure → attacker needs 3+ share locations
 //!
@@ -28,6 +29,7 @@ use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use std::io::{self, Write};
 
 use super::shamir::reconstruct_key;
+use super::vss::{reconstruct_secret, VerifiableShare};
 
 /// Interactively collect 3 Shamir shares and reconstruct DB encryption key
 ///
@@ -61,35 +63,18 @@ pub fn reconstruct_key_interactive() -> Result<String> {
     println!("   - DB encryption key is NOT stored on disk");
     println!("   - You must provide 3 of 5 shares to start the server\n");
 
-    println!("Enter 3 shares (base64 encoded):\n");
+    println!("Enter shares (base64, or mkvss1:... verifiable shares):\n");
 
-    // Read 3 shares from stdin
-    let mut shares = Vec::new();
-    for i in 1..=3 {
-        print!("  Share {}/3: ", i);
-        io::stdout().flush()?;
-
-        let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .context(format!("Failed to read share {}", i))?;
-        let input = input.trim();
-
-        // Decode share from base64
-        let share_bytes = BASE64
-            .decode(input)
-            .context(format!("Failed to decode share {} as base64. Expected format: AQHvR2xhc3Ntb3JwaGlzbQ==", i))?;
-
-        if share_bytes.len() != 33 {
-            anyhow::bail!(
-                "Share {} has invalid length: expected 33 bytes, got {}. \
-                 Shares must be generated by 'cargo run --bin split_key'",
-                i,
-                share_bytes.len()
-            );
-        }
+    let first = read_share(1, None)?;
+    if VerifiableShare::is_verifiable_share(&first) {
+        return reconstruct_verifiable_interactive(&first);
+    }
 
-        shares.push(share_bytes);
+    // Read 3 shares from stdin
+    let mut shares = vec![decode_legacy_share(1, &first)?];
+    for i in 2..=3 {
+        let input = read_share(i, Some(3))?;
+        shares.push(decode_legacy_share(i, &input)?);
     }
 
     println!("\n🔄 Reconstructing 256-bit DB encryption key...");
@@ -117,6 +102,82 @@ pub fn reconstruct_key_interactive() -> Result<String> {
     Ok(key_hex)
 }
 
+/// Prompt for share `i` (of `total`, when known) and read one line
+fn read_share(i: usize, total: Option<usize>) -> Result<String> {
+    match total {
+        Some(total) => print!("  Share {}/{}: ", i, total),
+        None => print!("  Share {}: ", i),
+    }
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .context(format!("Failed to read share {}", i))?;
+    Ok(input.trim().to_string())
+}
+
+/// Decode a base64 share written by `split_key`
+fn decode_legacy_share(i: usize, input: &str) -> Result<Vec<u8>> {
+    // Decode share from base64
+    let share_bytes = BASE64
+        .decode(input)
+        .context(format!("Failed to decode share {} as base64. Expected format: AQHvR2xhc3Ntb3JwaGlzbQ==", i))?;
+
+    if share_bytes.len() != 33 {
+        anyhow::bail!(
+            "Share {} has invalid length: expected 33 bytes, got {}. \
+             Shares must be generated by 'cargo run --bin split_key'",
+            i,
+            share_bytes.len()
+        );
+    }
+
+    Ok(share_bytes)
+}
+
+/// Collect verifiable shares, checking each one as it is entered
+///
+/// A corrupted share, or one from another share set, is rejected before
+/// the key is reconstructed.
+fn reconstruct_verifiable_interactive(first: &str) -> Result<String> {
+    let first = VerifiableShare::from_text(first).context("Share 1 is not a valid share")?;
+    first.verify().context("Share 1 failed verification")?;
+    let threshold = first.commitments().threshold() as usize;
+    println!(
+        "     ✓ verified (key fingerprint {}, {} shares required)",
+        first.commitments().fingerprint(),
+        threshold
+    );
+
+    let mut shares = vec![first];
+    for i in 2..=threshold {
+        let share = VerifiableShare::from_text(&read_share(i, Some(threshold))?)
+            .context(format!("Share {} is not a valid share", i))?;
+        share
+            .verify()
+            .context(format!("Share {} failed verification", i))?;
+        if share.commitments() != shares[0].commitments() {
+            anyhow::bail!(
+                "Share {} belongs to another key (fingerprint {})",
+                i,
+                share.commitments().fingerprint()
+            );
+        }
+        println!("     ✓ verified");
+        shares.push(share);
+    }
+
+    println!("\n🔄 Reconstructing 256-bit DB encryption key...");
+    let key_bytes = reconstruct_secret(&shares).context("Failed to reconstruct key")?;
+    let key_hex = hex::encode(key_bytes.as_slice());
+
+    println!("✅ Key reconstructed successfully ({} bytes)", key_bytes.len());
+    println!("🚀 Starting server with reconstructed encryption key...\n");
+
+    Ok(key_hex)
+}
+
 /// Check if Shamir mode is enabled (TM-002 mitigation active)
 ///
 /// # Returns
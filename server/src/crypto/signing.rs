@@ -0,0 +1,97 @@
+//! Marketplace Ed25519 signing key
+//!
+//! Signs statements the marketplace publishes for outside verification
+//! (audit log checkpoints). Only the public key leaves the server.
+//!
+//! # Key Management
+//!
+//! The 32-byte secret is read from `MARKETPLACE_SIGNING_KEY` (hex). Keep it
+//! out of `.env` in production, like the DB key. Rotating it means
+//! publishing the new public key: checkpoints stay verifiable with the key
+//! that signed them.
+
+use anyhow::{Context, Result};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+
+/// Environment variable holding the hex-encoded secret key
+pub const SIGNING_KEY_ENV: &str = "MARKETPLACE_SIGNING_KEY";
+
+/// Ed25519 key the marketplace signs published statements with
+pub struct MarketplaceSigner {
+    signing_key: SigningKey,
+}
+
+impl MarketplaceSigner {
+    /// Load the key from a 64-character hex secret
+    pub fn from_hex(secret_hex: &str) -> Result<Self> {
+        let bytes = hex::decode(secret_hex.trim()).context("Signing key is not valid hex")?;
+        let secret: [u8; 32] = bytes.try_into().map_err(|b: Vec<u8>| {
+            anyhow::anyhow!("Signing key must be 32 bytes, got {}", b.len())
+        })?;
+        Ok(Self {
+            signing_key: SigningKey::from_bytes(&secret),
+        })
+    }
+
+    /// Load the key from `MARKETPLACE_SIGNING_KEY`, None if unset
+    pub fn from_env() -> Result<Option<Self>> {
+        match std::env::var(SIGNING_KEY_ENV) {
+            Ok(secret_hex) => Self::from_hex(&secret_hex)
+                .context(format!("Invalid {}", SIGNING_KEY_ENV))
+                .map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Public key (hex) to publish for verifiers
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.signing_key.verifying_key().as_bytes())
+    }
+
+    /// Sign `data`, returning the hex-encoded 64-byte signature
+    pub fn sign_hex(&self, data: &[u8]) -> String {
+        hex::encode(self.signing_key.sign(data).to_bytes())
+    }
+}
+
+/// Check a hex signature made by [`MarketplaceSigner::sign_hex`]
+pub fn verify_hex_signature(public_key_hex: &str, data: &[u8], signature_hex: &str) -> Result<()> {
+    let key_bytes: [u8; 32] = hex::decode(public_key_hex)
+        .context("Public key is not valid hex")?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Public key must be 32 bytes"))?;
+    let verifying_key =
+        VerifyingKey::from_bytes(&key_bytes).context("Not a valid Ed25519 public key")?;
+
+    let signature_bytes: [u8; 64] = hex::decode(signature_hex)
+        .context("Signature is not valid hex")?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Signature must be 64 bytes"))?;
+
+    verifying_key
+        .verify(data, &Signature::from_bytes(&signature_bytes))
+        .map_err(|_| anyhow::anyhow!("Signature verification failed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+
+    #[test]
+    fn test_sign_and_verify() -> Result<()> {
+        let signer = MarketplaceSigner::from_hex(SECRET)?;
+        let signature = signer.sign_hex(b"head");
+
+        verify_hex_signature(&signer.public_key_hex(), b"head", &signature)?;
+        assert!(verify_hex_signature(&signer.public_key_hex(), b"other", &signature).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_malformed_key() {
+        assert!(MarketplaceSigner::from_hex("abcd").is_err());
+        assert!(MarketplaceSigner::from_hex("not hex").is_err());
+    }
+}
@@ -0,0 +1,603 @@
+//! Verifiable Shamir shares of the DB encryption key
+//!
+//! Pedersen verifiable secret sharing over Ristretto255. Every share carries
+//! the commitments to the dealing polynomials, so a corrupted or foreign
+//! share is rejected before any reconstruction is attempted.
+//!
+//! Share sets can be managed without ever computing the key:
+//! - [`refresh_shares`] adds a random polynomial with a zero constant term,
+//!   producing new shares of the same key (old and new shares do not mix)
+//! - [`reshare`] re-deals `threshold` shares into a new k-of-n set through
+//!   Lagrange-weighted sub-shares
+//!
+//! The commitment to the key itself is unchanged by both, so the
+//! [`Commitments::fingerprint`] recorded at split time identifies the key
+//! across every later share set.
+//!
+//! # Format
+//!
+//! The 256-bit key is shared as two 128-bit halves (each fits a scalar).
+//! A share is `mkvss1:` followed by base64 of
+//! `[version][threshold][index][values (2×32)][blinding (2×32)][commitments (2×threshold×32)]`.
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::Identity;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use sha2::{Digest, Sha256, Sha512};
+use std::fmt;
+use zeroize::{Zeroize, Zeroizing};
+
+/// Text prefix of a verifiable share
+pub const SHARE_PREFIX: &str = "mkvss1:";
+
+/// Binary format version
+const FORMAT_VERSION: u8 = 1;
+
+/// Size of the shared secret (DB key)
+pub const SECRET_SIZE: usize = 32;
+
+/// The secret is shared as two 128-bit halves
+const HALVES: usize = 2;
+const HALF_SIZE: usize = SECRET_SIZE / HALVES;
+
+/// Second Pedersen generator, with no known discrete log relative to the basepoint
+fn generator_h() -> RistrettoPoint {
+    let digest = Sha512::digest(b"monero-marketplace/vss/pedersen-h");
+    let mut bytes = [0u8; 64];
+    bytes.copy_from_slice(&digest);
+    RistrettoPoint::from_uniform_bytes(&bytes)
+}
+
+fn random_scalar() -> Scalar {
+    let mut bytes = Zeroizing::new([0u8; 64]);
+    OsRng.fill_bytes(&mut bytes[..]);
+    Scalar::from_bytes_mod_order_wide(&bytes)
+}
+
+/// Polynomial over the scalar field, lowest degree first
+struct Polynomial(Vec<Scalar>);
+
+impl Polynomial {
+    /// Random polynomial of `degree` with the given constant term
+    fn random(constant: Scalar, degree: usize) -> Self {
+        let mut coefficients = vec![constant];
+        coefficients.extend((0..degree).map(|_| random_scalar()));
+        Self(coefficients)
+    }
+
+    fn eval(&self, x: Scalar) -> Scalar {
+        self.0
+            .iter()
+            .rev()
+            .fold(Scalar::ZERO, |acc, coefficient| acc * x + coefficient)
+    }
+}
+
+impl Drop for Polynomial {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+/// Pedersen commitments to the coefficients of both halves
+#[derive(Clone, PartialEq, Eq)]
+pub struct Commitments {
+    threshold: u8,
+    /// `[half][degree]`: `a·G + b·H` for value coefficient `a` and blinding `b`
+    points: [Vec<RistrettoPoint>; HALVES],
+}
+
+impl Commitments {
+    /// Shares needed to reconstruct
+    pub fn threshold(&self) -> u8 {
+        self.threshold
+    }
+
+    /// Identifier of the shared key, stable across refresh and reshare
+    ///
+    /// Record it when the key is first split; any later share set must show
+    /// the same fingerprint.
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        for half in &self.points {
+            hasher.update(half[0].compress().as_bytes());
+        }
+        hex::encode(&hasher.finalize()[..8])
+    }
+
+    /// Commitment evaluated at `x`
+    fn eval(&self, half: usize, x: Scalar) -> RistrettoPoint {
+        self.points[half]
+            .iter()
+            .rev()
+            .fold(RistrettoPoint::identity(), |acc, point| acc * x + point)
+    }
+}
+
+impl fmt::Debug for Commitments {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Commitments")
+            .field("threshold", &self.threshold)
+            .field("fingerprint", &self.fingerprint())
+            .finish()
+    }
+}
+
+/// One holder's share, with the commitments it is checked against
+#[derive(Clone)]
+pub struct VerifiableShare {
+    index: u8,
+    values: [Scalar; HALVES],
+    blinding: [Scalar; HALVES],
+    commitments: Commitments,
+}
+
+impl Drop for VerifiableShare {
+    fn drop(&mut self) {
+        self.values.zeroize();
+        self.blinding.zeroize();
+    }
+}
+
+impl fmt::Debug for VerifiableShare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerifiableShare")
+            .field("index", &self.index)
+            .field("commitments", &self.commitments)
+            .finish_non_exhaustive()
+    }
+}
+
+impl VerifiableShare {
+    /// Evaluation point (1..=255), distinct within a share set
+    pub fn index(&self) -> u8 {
+        self.index
+    }
+
+    pub fn commitments(&self) -> &Commitments {
+        &self.commitments
+    }
+
+    /// Whether `text` looks like a verifiable share (rather than a `split_key` share)
+    pub fn is_verifiable_share(text: &str) -> bool {
+        text.trim().starts_with(SHARE_PREFIX)
+    }
+
+    /// Check the share against its commitments
+    pub fn verify(&self) -> Result<()> {
+        let g = RISTRETTO_BASEPOINT_POINT;
+        let h = generator_h();
+        let x = Scalar::from(self.index);
+        for half in 0..HALVES {
+            if self.values[half] * g + self.blinding[half] * h != self.commitments.eval(half, x) {
+                anyhow::bail!("Share {} does not match its commitments", self.index);
+            }
+        }
+        Ok(())
+    }
+
+    /// Encode as `mkvss1:<base64>`
+    pub fn to_text(&self) -> String {
+        let mut bytes =
+            Zeroizing::new(vec![FORMAT_VERSION, self.commitments.threshold, self.index]);
+        for scalar in self.values.iter().chain(&self.blinding) {
+            bytes.extend_from_slice(scalar.as_bytes());
+        }
+        for half in &self.commitments.points {
+            for point in half {
+                bytes.extend_from_slice(point.compress().as_bytes());
+            }
+        }
+        format!("{}{}", SHARE_PREFIX, BASE64.encode(bytes.as_slice()))
+    }
+
+    /// Decode a share written by [`VerifiableShare::to_text`]
+    pub fn from_text(text: &str) -> Result<Self> {
+        let encoded = text
+            .trim()
+            .strip_prefix(SHARE_PREFIX)
+            .context(format!("Share must start with {}", SHARE_PREFIX))?;
+        let bytes = Zeroizing::new(
+            BASE64
+                .decode(encoded)
+                .context("Share is not valid base64")?,
+        );
+
+        if bytes.len() < 3 || bytes[0] != FORMAT_VERSION {
+            anyhow::bail!("Unsupported share format");
+        }
+        let threshold = bytes[1];
+        let index = bytes[2];
+        if threshold < 2 || index == 0 {
+            anyhow::bail!("Invalid share header");
+        }
+        let expected = 3 + 4 * 32 + HALVES * threshold as usize * 32;
+        if bytes.len() != expected {
+            anyhow::bail!(
+                "Share has invalid length: expected {} bytes, got {}",
+                expected,
+                bytes.len()
+            );
+        }
+
+        let mut chunks = bytes[3..].chunks_exact(32);
+        let mut next_scalar = || -> Result<Scalar> {
+            let chunk: [u8; 32] = chunks.next().context("Truncated share")?.try_into()?;
+            Option::from(Scalar::from_canonical_bytes(chunk))
+                .context("Share holds an invalid scalar")
+        };
+        let values = [next_scalar()?, next_scalar()?];
+        let blinding = [next_scalar()?, next_scalar()?];
+
+        let points = bytes[3 + 4 * 32..]
+            .chunks_exact(32)
+            .map(|chunk| {
+                CompressedRistretto::from_slice(chunk)?
+                    .decompress()
+                    .context("Share holds an invalid commitment")
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let (lo, hi) = points.split_at(threshold as usize);
+
+        Ok(Self {
+            index,
+            values,
+            blinding,
+            commitments: Commitments {
+                threshold,
+                points: [lo.to_vec(), hi.to_vec()],
+            },
+        })
+    }
+}
+
+/// Deal shares at `indices` from the given polynomials
+fn deal(
+    values: &[Polynomial; HALVES],
+    blinding: &[Polynomial; HALVES],
+    indices: impl Iterator<Item = u8>,
+) -> Vec<VerifiableShare> {
+    let g = RISTRETTO_BASEPOINT_POINT;
+    let h = generator_h();
+    let commitments = Commitments {
+        threshold: values[0].0.len() as u8,
+        points: [0, 1].map(|half| {
+            values[half]
+                .0
+                .iter()
+                .zip(&blinding[half].0)
+                .map(|(a, b)| a * g + b * h)
+                .collect()
+        }),
+    };
+
+    indices
+        .map(|index| {
+            let x = Scalar::from(index);
+            VerifiableShare {
+                index,
+                values: [values[0].eval(x), values[1].eval(x)],
+                blinding: [blinding[0].eval(x), blinding[1].eval(x)],
+                commitments: commitments.clone(),
+            }
+        })
+        .collect()
+}
+
+fn check_parameters(threshold: u8, share_count: u8) -> Result<()> {
+    if threshold < 2 {
+        anyhow::bail!("Threshold must be at least 2, got {}", threshold);
+    }
+    if threshold > share_count {
+        anyhow::bail!(
+            "Threshold ({}) cannot exceed share count ({})",
+            threshold,
+            share_count
+        );
+    }
+    Ok(())
+}
+
+/// Split a 256-bit key into `share_count` verifiable shares, `threshold` required
+pub fn split_secret(secret: &[u8], threshold: u8, share_count: u8) -> Result<Vec<VerifiableShare>> {
+    if secret.len() != SECRET_SIZE {
+        anyhow::bail!(
+            "Secret must be exactly {} bytes, got {} bytes",
+            SECRET_SIZE,
+            secret.len()
+        );
+    }
+    check_parameters(threshold, share_count)?;
+
+    let degree = threshold as usize - 1;
+    let values = [0, 1].map(|half| {
+        let mut bytes = Zeroizing::new([0u8; 32]);
+        bytes[..HALF_SIZE].copy_from_slice(&secret[half * HALF_SIZE..(half + 1) * HALF_SIZE]);
+        Polynomial::random(Scalar::from_bytes_mod_order(*bytes), degree)
+    });
+    let blinding = [0, 1].map(|_| Polynomial::random(random_scalar(), degree));
+
+    Ok(deal(&values, &blinding, 1..=share_count))
+}
+
+/// Check that `shares` are valid, distinct and from the same set
+///
+/// Every share is checked against its commitments first; a share whose
+/// commitments differ from the others is reported by index. Returns the
+/// common commitments.
+pub fn verify_share_set(shares: &[VerifiableShare]) -> Result<&Commitments> {
+    let first = shares.first().context("At least one share is required")?;
+    for share in shares {
+        share.verify()?;
+    }
+
+    // The set is the one most shares agree on
+    let commitments = shares
+        .iter()
+        .map(|share| &share.commitments)
+        .max_by_key(|c| shares.iter().filter(|s| &s.commitments == *c).count())
+        .unwrap_or(&first.commitments);
+    for share in shares {
+        if &share.commitments == commitments {
+            continue;
+        }
+        if share.commitments.fingerprint() == commitments.fingerprint() {
+            anyhow::bail!(
+                "Share {} is from another share set of the same key (before or after a refresh)",
+                share.index
+            );
+        }
+        anyhow::bail!(
+            "Share {} belongs to another key (fingerprint {}, expected {})",
+            share.index,
+            share.commitments.fingerprint(),
+            commitments.fingerprint()
+        );
+    }
+
+    let mut indices: Vec<u8> = shares.iter().map(|s| s.index).collect();
+    indices.sort_unstable();
+    if indices.windows(2).any(|pair| pair[0] == pair[1]) {
+        anyhow::bail!("The same share was given more than once");
+    }
+    if shares.len() < commitments.threshold as usize {
+        anyhow::bail!(
+            "{} shares required, got {}",
+            commitments.threshold,
+            shares.len()
+        );
+    }
+    Ok(commitments)
+}
+
+/// Lagrange coefficients at 0 for the first `threshold` shares
+fn lagrange_at_zero(shares: &[VerifiableShare]) -> Vec<Scalar> {
+    shares
+        .iter()
+        .map(|share_i| {
+            let x_i = Scalar::from(share_i.index);
+            shares
+                .iter()
+                .filter(|share_j| share_j.index != share_i.index)
+                .fold(Scalar::ONE, |acc, share_j| {
+                    let x_j = Scalar::from(share_j.index);
+                    acc * x_j * (x_j - x_i).invert()
+                })
+        })
+        .collect()
+}
+
+/// Recover the key from verified shares (kept in memory only)
+pub fn reconstruct_secret(shares: &[VerifiableShare]) -> Result<Zeroizing<Vec<u8>>> {
+    let threshold = verify_share_set(shares)?.threshold as usize;
+    let shares = &shares[..threshold];
+    let lambdas = lagrange_at_zero(shares);
+
+    let mut secret = Zeroizing::new(Vec::with_capacity(SECRET_SIZE));
+    for half in 0..HALVES {
+        let mut value = shares
+            .iter()
+            .zip(&lambdas)
+            .fold(Scalar::ZERO, |acc, (share, lambda)| {
+                acc + lambda * share.values[half]
+            });
+        let bytes = Zeroizing::new(value.to_bytes());
+        value.zeroize();
+        if bytes[HALF_SIZE..].iter().any(|&b| b != 0) {
+            anyhow::bail!("Shares do not encode a 256-bit key");
+        }
+        secret.extend_from_slice(&bytes[..HALF_SIZE]);
+    }
+    Ok(secret)
+}
+
+/// New shares of the same key for the same holders
+///
+/// All shares that should stay usable must be given: shares left out no
+/// longer combine with the refreshed ones.
+pub fn refresh_shares(shares: &[VerifiableShare]) -> Result<Vec<VerifiableShare>> {
+    let commitments = verify_share_set(shares)?.clone();
+    let degree = commitments.threshold as usize - 1;
+    let g = RISTRETTO_BASEPOINT_POINT;
+    let h = generator_h();
+
+    let deltas = [0, 1].map(|_| Polynomial::random(Scalar::ZERO, degree));
+    let blinding_deltas = [0, 1].map(|_| Polynomial::random(Scalar::ZERO, degree));
+
+    let points = [0, 1].map(|half| {
+        commitments.points[half]
+            .iter()
+            .zip(deltas[half].0.iter().zip(&blinding_deltas[half].0))
+            .map(|(point, (a, b))| point + a * g + b * h)
+            .collect()
+    });
+    let refreshed = Commitments {
+        threshold: commitments.threshold,
+        points,
+    };
+
+    Ok(shares
+        .iter()
+        .map(|share| {
+            let x = Scalar::from(share.index);
+            VerifiableShare {
+                index: share.index,
+                values: [0, 1].map(|half| share.values[half] + deltas[half].eval(x)),
+                blinding: [0, 1].map(|half| share.blinding[half] + blinding_deltas[half].eval(x)),
+                commitments: refreshed.clone(),
+            }
+        })
+        .collect())
+}
+
+/// Re-deal the key into a new `new_threshold`-of-`new_count` set
+///
+/// Each of the first `threshold` shares is itself shared with a fresh
+/// polynomial; new shares are the Lagrange-weighted sums of those
+/// sub-shares, so the key is never assembled.
+pub fn reshare(
+    shares: &[VerifiableShare],
+    new_threshold: u8,
+    new_count: u8,
+) -> Result<Vec<VerifiableShare>> {
+    let threshold = verify_share_set(shares)?.threshold as usize;
+    check_parameters(new_threshold, new_count)?;
+
+    let shares = &shares[..threshold];
+    let lambdas = lagrange_at_zero(shares);
+    let degree = new_threshold as usize - 1;
+
+    let sub_shares: Vec<Vec<VerifiableShare>> = shares
+        .iter()
+        .map(|share| {
+            let values = [0, 1].map(|half| Polynomial::random(share.values[half], degree));
+            let blinding = [0, 1].map(|half| Polynomial::random(share.blinding[half], degree));
+            deal(&values, &blinding, 1..=new_count)
+        })
+        .collect();
+
+    let points = [0, 1].map(|half| {
+        (0..new_threshold as usize)
+            .map(|m| {
+                sub_shares
+                    .iter()
+                    .zip(&lambdas)
+                    .map(|(dealt, lambda)| lambda * dealt[0].commitments.points[half][m])
+                    .sum()
+            })
+            .collect()
+    });
+    let commitments = Commitments {
+        threshold: new_threshold,
+        points,
+    };
+
+    Ok((0..new_count as usize)
+        .map(|j| {
+            let combine = |pick: fn(&VerifiableShare) -> &[Scalar; HALVES]| {
+                [0, 1].map(|half| {
+                    sub_shares
+                        .iter()
+                        .zip(&lambdas)
+                        .fold(Scalar::ZERO, |acc, (dealt, lambda)| {
+                            acc + lambda * pick(&dealt[j])[half]
+                        })
+                })
+            };
+            VerifiableShare {
+                index: j as u8 + 1,
+                values: combine(|share| &share.values),
+                blinding: combine(|share| &share.blinding),
+                commitments: commitments.clone(),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [
+        0x9d, 0x61, 0xb1, 0x9d, 0xef, 0xfd, 0x5a, 0x60, 0xba, 0x84, 0x4a, 0xf4, 0x92, 0xec, 0x2c,
+        0xc4, 0x44, 0x49, 0xc5, 0x69, 0x7b, 0x32, 0x69, 0x19, 0x70, 0x3b, 0xac, 0x03, 0x1c, 0xae,
+        0x7f, 0x60,
+    ];
+
+    fn reparse(shares: &[VerifiableShare]) -> Vec<VerifiableShare> {
+        shares
+            .iter()
+            .map(|s| VerifiableShare::from_text(&s.to_text()).expect("valid share"))
+            .collect()
+    }
+
+    #[test]
+    fn test_split_and_reconstruct() -> Result<()> {
+        let shares = reparse(&split_secret(&KEY, 3, 5)?);
+        assert_eq!(reconstruct_secret(&shares[2..])?.as_slice(), KEY);
+        assert_eq!(reconstruct_secret(&shares[..3])?.as_slice(), KEY);
+        assert!(reconstruct_secret(&shares[..2]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bad_share_rejected_before_reconstruction() -> Result<()> {
+        let shares = split_secret(&KEY, 3, 5)?;
+        let mut tampered = shares[1].clone();
+        tampered.values[0] += Scalar::ONE;
+        let err = reconstruct_secret(&[shares[0].clone(), tampered, shares[2].clone()])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Share 2 does not match"), "{}", err);
+
+        let other = split_secret(&KEY, 3, 5)?;
+        let mixed = [shares[0].clone(), shares[2].clone(), other[3].clone()];
+        let err = verify_share_set(&mixed).unwrap_err().to_string();
+        assert!(err.contains("Share 4 belongs to another key"), "{}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn test_refresh_keeps_secret_and_fingerprint() -> Result<()> {
+        let shares = split_secret(&KEY, 3, 5)?;
+        let refreshed = refresh_shares(&shares)?;
+
+        assert_eq!(
+            refreshed[0].commitments().fingerprint(),
+            shares[0].commitments().fingerprint()
+        );
+        assert_eq!(reconstruct_secret(&refreshed[1..4])?.as_slice(), KEY);
+
+        let mixed = [
+            shares[0].clone(),
+            refreshed[1].clone(),
+            refreshed[2].clone(),
+        ];
+        let err = verify_share_set(&mixed).unwrap_err().to_string();
+        assert!(err.contains("another share set of the same key"), "{}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reshare_changes_threshold() -> Result<()> {
+        let shares = split_secret(&KEY, 3, 5)?;
+        let reshared = reshare(&shares[1..4], 2, 3)?;
+
+        assert_eq!(reshared.len(), 3);
+        assert_eq!(reshared[0].commitments().threshold(), 2);
+        assert_eq!(
+            reshared[0].commitments().fingerprint(),
+            shares[0].commitments().fingerprint()
+        );
+        assert_eq!(reconstruct_secret(&reshared[1..])?.as_slice(), KEY);
+
+        let grown = reshare(&reparse(&reshared[..2]), 4, 6)?;
+        assert_eq!(reconstruct_secret(&grown[2..])?.as_slice(), KEY);
+        Ok(())
+    }
+}
@@ -4,7 +4,10 @@ use diesel::r2d2::{self, ConnectionManager, CustomizeConnection};
 use diesel::sql_query;
 use uuid::Uuid;
 
-use crate::models::escrow::{Escrow, NewEscrow};
+use crate::models::arbiter_assignment::{ArbiterAssignment, AssignmentReason};
+use crate::models::audit_log::{AuditEntry, AuditEventType};
+use crate::models::escrow::{Escrow, EscrowStatus, NewEscrow};
+use crate::models::escrow_event::{EscrowActor, EscrowEventRecord};
 use crate::models::transaction::{NewTransaction, Transaction};
 use crate::schema::escrows;
 use monero_marketplace_common::types::MultisigInfo;
@@ -88,25 +91,72 @@ pub fn create_pool(database_url: &str, encryption_key: &str) -> Result<DbPool> {
     Ok(pool)
 }
 
+/// Change the SQLCipher key of a database with `PRAGMA rekey`
+///
+/// The server must be stopped: every page is rewritten under the new key.
+/// The database is switched out of WAL mode for the rewrite (SQLCipher does
+/// not rekey WAL databases) and back afterwards, then reopened with the new
+/// key to check it.
+pub fn rekey_database(database_url: &str, current_key: &str, new_key: &str) -> Result<()> {
+    if new_key.is_empty() || new_key.contains('\'') {
+        anyhow::bail!("New encryption key must be non-empty and must not contain quotes");
+    }
+
+    let mut conn = SqliteConnection::establish(database_url)
+        .context(format!("Failed to open database {}", database_url))?;
+    sql_query(format!("PRAGMA key = '{}';", current_key)).execute(&mut conn)?;
+    sql_query("SELECT count(*) FROM sqlite_master;")
+        .execute(&mut conn)
+        .context("Current encryption key does not open the database")?;
+
+    sql_query("PRAGMA journal_mode = DELETE;").execute(&mut conn)?;
+    sql_query(format!("PRAGMA rekey = '{}';", new_key))
+        .execute(&mut conn)
+        .context("PRAGMA rekey failed")?;
+    drop(conn);
+
+    let mut conn = SqliteConnection::establish(database_url)
+        .context(format!("Failed to reopen database {}", database_url))?;
+    sql_query(format!("PRAGMA key = '{}';", new_key)).execute(&mut conn)?;
+    sql_query("SELECT count(*) FROM sqlite_master;")
+        .execute(&mut conn)
+        .context("Database does not open with the new key after rekey")?;
+    sql_query("PRAGMA journal_mode = WAL;").execute(&mut conn)?;
+
+    Ok(())
+}
+
 pub async fn db_insert_escrow(pool: &DbPool, new_escrow: NewEscrow) -> Result<Escrow> {
     let mut conn = pool.get().context("Failed to get DB connection")?;
     let escrow_id = new_escrow.id.to_string();
     tokio::task::spawn_blocking(move || {
-        diesel::insert_into(escrows::table)
-            .values(&new_escrow)
-            .execute(&mut conn)
-            .map_err(|e| {
-                tracing::error!("Database insert error for escrow {}: {:?}", escrow_id, e);
-                anyhow::anyhow!("Failed to insert escrow: {}", e)
-            })?;
-
-        escrows::table
-            .filter(escrows::id.eq(escrow_id.clone()))
-            .first(&mut conn)
-            .map_err(|e| {
-                tracing::error!("Failed to retrieve escrow {} after insert: {:?}", escrow_id, e);
-                anyhow::anyhow!("Failed to retrieve created escrow: {}", e)
-            })
+        conn.transaction::<_, anyhow::Error, _>(|conn| {
+            diesel::insert_into(escrows::table)
+                .values(&new_escrow)
+                .execute(conn)
+                .map_err(|e| {
+                    tracing::error!("Database insert error for escrow {}: {:?}", escrow_id, e);
+                    anyhow::anyhow!("Failed to insert escrow: {}", e)
+                })?;
+
+            let escrow: Escrow = escrows::table
+                .filter(escrows::id.eq(escrow_id.clone()))
+                .first(conn)
+                .map_err(|e| {
+                    tracing::error!("Failed to retrieve escrow {} after insert: {:?}", escrow_id, e);
+                    anyhow::anyhow!("Failed to retrieve created escrow: {}", e)
+                })?;
+
+            EscrowEventRecord::record_created(conn, &escrow, &EscrowActor::user(&escrow.buyer_id))?;
+            ArbiterAssignment::start(
+                conn,
+                &escrow.id,
+                &escrow.arbiter_id,
+                AssignmentReason::Initial,
+                None,
+            )?;
+            Ok(escrow)
+        })
     })
     .await?
 }
@@ -135,17 +185,120 @@ pub async fn db_update_escrow_address(pool: &DbPool, escrow_id: Uuid, address: &
     Ok(())
 }
 
-pub async fn db_update_escrow_status(pool: &DbPool, escrow_id: Uuid, status: &str) -> Result<()> {
+/// Update escrow status, recorded in the event log as a system change
+///
+/// Prefer [`db_transition_escrow`] when the actor or reason is known.
+pub async fn db_update_escrow_status(
+    pool: &DbPool,
+    escrow_id: Uuid,
+    status: EscrowStatus,
+) -> Result<()> {
+    db_transition_escrow(pool, escrow_id, status, EscrowActor::System("server"), None).await?;
+    Ok(())
+}
+
+/// Update escrow status and append the matching event to the escrow event log
+///
+/// Rejects moves not allowed by the [`EscrowStatus`] transition table.
+pub async fn db_transition_escrow(
+    pool: &DbPool,
+    escrow_id: Uuid,
+    status: EscrowStatus,
+    actor: EscrowActor,
+    reason: Option<&str>,
+) -> Result<Escrow> {
     let mut conn = pool.get().context("Failed to get DB connection")?;
-    let status_clone = status.to_string();
-    let _ = tokio::task::spawn_blocking(move || {
-        diesel::update(escrows::table.filter(escrows::id.eq(escrow_id.to_string())))
-            .set(escrows::status.eq(status_clone))
-            .execute(&mut conn)
-            .context(format!("Failed to update escrow {} status", escrow_id))
+    let reason_clone = reason.map(str::to_string);
+    tokio::task::spawn_blocking(move || {
+        EscrowEventRecord::record_transition(
+            &mut conn,
+            &escrow_id.to_string(),
+            status,
+            &actor,
+            reason_clone.as_deref(),
+        )
+        .context(format!("Failed to update escrow {} status", escrow_id))
     })
-    .await?;
-    Ok(())
+    .await?
+}
+
+/// Update escrow status and append the matching audit entry in one transaction
+///
+/// Neither is written if the other fails, so a payout or ruling never lands
+/// without its audit entry.
+pub async fn db_transition_escrow_audited(
+    pool: &DbPool,
+    escrow_id: Uuid,
+    status: EscrowStatus,
+    actor: EscrowActor,
+    reason: Option<&str>,
+    event_type: AuditEventType,
+    data: serde_json::Value,
+) -> Result<Escrow> {
+    let mut conn = pool.get().context("Failed to get DB connection")?;
+    let reason_clone = reason.map(str::to_string);
+    tokio::task::spawn_blocking(move || {
+        let entity_id = escrow_id.to_string();
+        // BEGIN IMMEDIATE: takes the audit chain head lock up front
+        conn.immediate_transaction::<_, anyhow::Error, _>(|conn| {
+            let escrow = EscrowEventRecord::record_transition(
+                conn,
+                &entity_id,
+                status,
+                &actor,
+                reason_clone.as_deref(),
+            )?;
+            AuditEntry::append(conn, event_type, &entity_id, &actor, &data)?;
+            Ok(escrow)
+        })
+        .context(format!("Failed to update escrow {} status", escrow_id))
+    })
+    .await?
+}
+
+/// Record an arbiter's ruling for one party
+///
+/// Moves the disputed escrow to `status` (resolved_buyer / resolved_vendor),
+/// stores the address the payout must go to and appends the dispute
+/// resolution audit entry, in one transaction.
+pub async fn db_record_ruling(
+    pool: &DbPool,
+    escrow_id: Uuid,
+    status: EscrowStatus,
+    actor: EscrowActor,
+    reason: Option<&str>,
+    ruling_address: &str,
+    audit_data: serde_json::Value,
+) -> Result<Escrow> {
+    let mut conn = pool.get().context("Failed to get DB connection")?;
+    let reason_clone = reason.map(str::to_string);
+    let address = ruling_address.to_string();
+    tokio::task::spawn_blocking(move || {
+        let entity_id = escrow_id.to_string();
+        // BEGIN IMMEDIATE: takes the audit chain head lock up front
+        conn.immediate_transaction::<_, anyhow::Error, _>(|conn| {
+            EscrowEventRecord::record_transition(
+                conn,
+                &entity_id,
+                status,
+                &actor,
+                reason_clone.as_deref(),
+            )?;
+            diesel::update(escrows::table.filter(escrows::id.eq(&entity_id)))
+                .set(escrows::ruling_address.eq(Some(address)))
+                .execute(conn)?;
+            AuditEntry::append(
+                conn,
+                AuditEventType::DisputeResolution,
+                &entity_id,
+                &actor,
+                &audit_data,
+            )?;
+            Escrow::find_by_id(conn, entity_id.clone())
+        })
+        .context(format!("Failed to record ruling for escrow {}", escrow_id))
+    })
+    .await?
 }
 
 pub async fn db_update_escrow_transaction_hash(
@@ -168,6 +321,29 @@ pub async fn db_update_escrow_transaction_hash(
     Ok(())
 }
 
+/// Record the sum of deposits seen on the escrow's multisig address
+pub async fn db_update_escrow_funded_amount(
+    pool: &DbPool,
+    escrow_id: Uuid,
+    funded_amount: i64,
+) -> Result<()> {
+    let mut conn = pool.get().context("Failed to get DB connection")?;
+    tokio::task::spawn_blocking(move || {
+        diesel::update(escrows::table.filter(escrows::id.eq(escrow_id.to_string())))
+            .set((
+                escrows::funded_amount.eq(funded_amount),
+                escrows::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(&mut conn)
+            .context(format!(
+                "Failed to update escrow {} funded_amount",
+                escrow_id
+            ))
+    })
+    .await??;
+    Ok(())
+}
+
 pub async fn db_store_multisig_info(
     pool: &DbPool,
     escrow_id: Uuid,
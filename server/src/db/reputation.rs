@@ -6,12 +6,37 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, NaiveDateTime, Utc};
 use diesel::prelude::*;
+use serde::Serialize;
 use uuid::Uuid;
 
-use reputation_common::types::SignedReview;
+use monero_marketplace_common::types::EscrowStatus;
+use reputation_common::types::{
+    EscrowOutcomes, PaymentProof, ReputationScore, ReviewReply, ScoringParams, SignedReview,
+    VendorReputation, REPUTATION_FORMAT_V1,
+};
+use reputation_crypto::reputation::calculate_stats;
+use reputation_crypto::scoring::{calculate_score, reputation_score};
 
 use crate::db::DbPool;
-use crate::schema::reviews;
+use crate::schema::{
+    escrow_events, escrows, imported_reputations, reviews, vendor_reputation_keys,
+};
+
+/// Escrow statuses before the deposit is covered: not counted in outcomes
+const UNFUNDED_ESCROW_STATUSES: [EscrowStatus; 5] = [
+    EscrowStatus::Created,
+    EscrowStatus::Funded,
+    EscrowStatus::Underfunded,
+    EscrowStatus::Cancelled,
+    EscrowStatus::Expired,
+];
+
+/// Escrow statuses of a refund to the buyer (ordered or confirmed)
+const REFUND_ESCROW_STATUSES: [EscrowStatus; 3] = [
+    EscrowStatus::ResolvedBuyer,
+    EscrowStatus::Refunding,
+    EscrowStatus::Refunded,
+];
 
 /// Database model for a review
 ///
@@ -30,6 +55,63 @@ pub struct Review {
     pub timestamp: NaiveDateTime,
     pub verified: bool,
     pub created_at: NaiveDateTime,
+    pub format_version: String,
+    pub vendor_pubkey: Option<String>,
+    pub escrow_id: Option<String>,
+    pub marketplace_domain: Option<String>,
+    /// Verified `PaymentProof` (JSON), set with `verified`
+    pub payment_proof: Option<String>,
+    /// Signature of the version this one replaced, for an amended review
+    pub supersedes: Option<String>,
+    /// Earlier versions, oldest first (JSON `ReviewRevision` array)
+    pub revisions: Option<String>,
+    /// Vendor's signed reply (JSON `ReviewReply`)
+    pub reply: Option<String>,
+}
+
+impl From<Review> for SignedReview {
+    fn from(r: Review) -> Self {
+        SignedReview {
+            format_version: r.format_version,
+            txid: r.txid,
+            rating: r.rating as u8,
+            comment: r.comment,
+            timestamp: DateTime::from_naive_utc_and_offset(r.timestamp, Utc),
+            buyer_pubkey: r.buyer_pubkey,
+            vendor_pubkey: r.vendor_pubkey,
+            escrow_id: r.escrow_id,
+            marketplace_domain: r.marketplace_domain,
+            supersedes: r.supersedes,
+            signature: r.signature,
+            payment_proof: r
+                .payment_proof
+                .and_then(|proof| serde_json::from_str(&proof).ok()),
+            revisions: r
+                .revisions
+                .and_then(|revisions| serde_json::from_str(&revisions).ok())
+                .unwrap_or_default(),
+            reply: r.reply.and_then(|reply| serde_json::from_str(&reply).ok()),
+        }
+    }
+}
+
+/// Reputation file imported from another marketplace
+///
+/// Stored as verified at import time; `reputation_json` is the file itself.
+#[derive(Debug, Clone, Queryable, Insertable, Serialize)]
+#[diesel(table_name = imported_reputations)]
+pub struct ImportedReputation {
+    pub id: String,
+    pub vendor_id: String,
+    pub ipfs_cid: String,
+    pub vendor_pubkey: String,
+    pub source_domains: String,
+    pub total_reviews: i32,
+    pub unbound_reviews: i32,
+    pub average_rating: f64,
+    pub generated_at: NaiveDateTime,
+    pub reputation_json: String,
+    pub imported_at: NaiveDateTime,
 }
 
 /// Insert a cryptographically-signed review into the database
@@ -77,6 +159,16 @@ pub async fn db_insert_review(
         timestamp: review.timestamp.naive_utc(),
         verified: false, // On-chain verification happens separately
         created_at: Utc::now().naive_utc(),
+        format_version: review.format_version.clone(),
+        vendor_pubkey: review.vendor_pubkey.clone(),
+        escrow_id: review.escrow_id.clone(),
+        marketplace_domain: review.marketplace_domain.clone(),
+        // Only stored once checked against the chain
+        payment_proof: None,
+        // New reviews: amendments and replies come later
+        supersedes: None,
+        revisions: None,
+        reply: None,
     };
 
     tokio::task::spawn_blocking(move || {
@@ -125,17 +217,8 @@ pub async fn db_get_vendor_reviews(
             .context("Failed to load reviews from database")?;
 
         // Convert Review (DB model) → SignedReview (domain model)
-        let signed_reviews: Vec<SignedReview> = db_reviews
-            .into_iter()
-            .map(|r| SignedReview {
-                txid: r.txid,
-                rating: r.rating as u8,
-                comment: r.comment,
-                timestamp: DateTime::from_naive_utc_and_offset(r.timestamp, Utc),
-                buyer_pubkey: r.buyer_pubkey,
-                signature: r.signature,
-            })
-            .collect();
+        let signed_reviews: Vec<SignedReview> =
+            db_reviews.into_iter().map(SignedReview::from).collect();
 
         Ok(signed_reviews)
     })
@@ -165,17 +248,8 @@ pub async fn db_get_verified_vendor_reviews(
             .load::<Review>(&mut conn)
             .context("Failed to load verified reviews")?;
 
-        let signed_reviews: Vec<SignedReview> = db_reviews
-            .into_iter()
-            .map(|r| SignedReview {
-                txid: r.txid,
-                rating: r.rating as u8,
-                comment: r.comment,
-                timestamp: DateTime::from_naive_utc_and_offset(r.timestamp, Utc),
-                buyer_pubkey: r.buyer_pubkey,
-                signature: r.signature,
-            })
-            .collect();
+        let signed_reviews: Vec<SignedReview> =
+            db_reviews.into_iter().map(SignedReview::from).collect();
 
         Ok(signed_reviews)
     })
@@ -187,21 +261,30 @@ pub async fn db_get_verified_vendor_reviews(
 ///
 /// # Arguments
 /// * `pool` - Database connection pool
-/// * `txid` - Transaction hash to verify
+/// * `review_id` - UUID of the review
+/// * `proof` - Payment proof the transaction was checked with
 ///
 /// # Security
 /// - Only call this after confirming the transaction exists on-chain
-/// - Transaction must have sufficient confirmations (10+ recommended)
+/// - Transaction must have sufficient confirmations (see `ConfirmationPolicy`)
 ///
 /// # Returns
-/// Number of reviews updated (should be 1, or 0 if txid not found)
-pub async fn db_mark_review_verified(pool: &DbPool, txid: &str) -> Result<usize> {
+/// Number of reviews updated (should be 1, or 0 if the review is not found)
+pub async fn db_mark_review_verified(
+    pool: &DbPool,
+    review_id: &str,
+    proof: &PaymentProof,
+) -> Result<usize> {
     let mut conn = pool.get().context("Failed to get DB connection")?;
-    let txid_clone = txid.to_string();
+    let review_id_clone = review_id.to_string();
+    let proof_json = serde_json::to_string(proof).context("Failed to serialize payment proof")?;
 
     tokio::task::spawn_blocking(move || {
-        let updated = diesel::update(reviews::table.filter(reviews::txid.eq(txid_clone)))
-            .set(reviews::verified.eq(true))
+        let updated = diesel::update(reviews::table.filter(reviews::id.eq(review_id_clone)))
+            .set((
+                reviews::verified.eq(true),
+                reviews::payment_proof.eq(Some(proof_json)),
+            ))
             .execute(&mut conn)
             .context("Failed to mark review as verified")?;
 
@@ -211,6 +294,120 @@ pub async fn db_mark_review_verified(pool: &DbPool, txid: &str) -> Result<usize>
     .context("Database task panicked")?
 }
 
+/// Replace a review with its amended version
+///
+/// The amended review carries its earlier versions; its signature and
+/// history must already be verified by the caller. Only applies while the
+/// stored version is still the one `amended.supersedes` replaces, so two
+/// concurrent amendments cannot both succeed.
+///
+/// # Returns
+/// Number of reviews updated (1, or 0 if the review changed in between)
+pub async fn db_amend_review(
+    pool: &DbPool,
+    review_id: &str,
+    amended: &SignedReview,
+) -> Result<usize> {
+    let mut conn = pool.get().context("Failed to get DB connection")?;
+    let review_id_clone = review_id.to_string();
+    let previous = amended
+        .supersedes
+        .clone()
+        .context("Amended review does not name the version it replaces")?;
+    let revisions_json =
+        serde_json::to_string(&amended.revisions).context("Failed to serialize revisions")?;
+    let amended = amended.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let updated = diesel::update(
+            reviews::table
+                .filter(reviews::id.eq(review_id_clone))
+                .filter(reviews::signature.eq(previous)),
+        )
+        .set((
+            reviews::rating.eq(i32::from(amended.rating)),
+            reviews::comment.eq(amended.comment),
+            reviews::timestamp.eq(amended.timestamp.naive_utc()),
+            reviews::supersedes.eq(amended.supersedes),
+            reviews::signature.eq(amended.signature),
+            reviews::revisions.eq(Some(revisions_json)),
+        ))
+        .execute(&mut conn)
+        .context("Failed to amend review")?;
+
+        Ok(updated)
+    })
+    .await
+    .context("Database task panicked")?
+}
+
+/// Store the vendor's reply to a review, replacing any earlier reply
+///
+/// The reply signature must already be verified by the caller.
+///
+/// # Returns
+/// Number of reviews updated (should be 1, or 0 if the review is not found)
+pub async fn db_set_review_reply(
+    pool: &DbPool,
+    review_id: &str,
+    reply: &ReviewReply,
+) -> Result<usize> {
+    let mut conn = pool.get().context("Failed to get DB connection")?;
+    let review_id_clone = review_id.to_string();
+    let reply_json = serde_json::to_string(reply).context("Failed to serialize reply")?;
+
+    tokio::task::spawn_blocking(move || {
+        let updated = diesel::update(reviews::table.filter(reviews::id.eq(review_id_clone)))
+            .set(reviews::reply.eq(Some(reply_json)))
+            .execute(&mut conn)
+            .context("Failed to store review reply")?;
+
+        Ok(updated)
+    })
+    .await
+    .context("Database task panicked")?
+}
+
+/// Retrieve the review of a transaction written about a vendor, if any
+pub async fn db_get_vendor_review(
+    pool: &DbPool,
+    txid: &str,
+    vendor_id: &str,
+) -> Result<Option<Review>> {
+    let mut conn = pool.get().context("Failed to get DB connection")?;
+    let txid_clone = txid.to_string();
+    let vendor_clone = vendor_id.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        reviews::table
+            .filter(reviews::txid.eq(txid_clone))
+            .filter(reviews::vendor_id.eq(vendor_clone))
+            .first::<Review>(&mut conn)
+            .optional()
+            .context("Failed to load review")
+    })
+    .await
+    .context("Database task panicked")?
+}
+
+/// Retrieve the review a reviewer wrote for a transaction, if any
+pub async fn db_get_review(pool: &DbPool, txid: &str, reviewer_id: &str) -> Result<Option<Review>> {
+    let mut conn = pool.get().context("Failed to get DB connection")?;
+    let txid_clone = txid.to_string();
+    let reviewer_clone = reviewer_id.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        reviews::table
+            .filter(reviews::txid.eq(txid_clone))
+            .filter(reviews::reviewer_id.eq(reviewer_clone))
+            .first::<Review>(&mut conn)
+            .optional()
+            .context("Failed to load review")
+    })
+    .await
+    .context("Database task panicked")?
+}
+
 /// Check if a review already exists for a transaction
 ///
 /// # Arguments
@@ -242,50 +439,246 @@ pub async fn db_review_exists(pool: &DbPool, txid: &str, reviewer_id: &str) -> R
     .context("Database task panicked")?
 }
 
-/// Get reputation statistics for a vendor
+/// Check if an escrow already has a (2.0) review
 ///
-/// # Returns
-/// Tuple of (total_reviews, average_rating)
+/// # Use Case
+/// A bound review names its escrow: one review per escrow, whatever the txid
+pub async fn db_escrow_review_exists(pool: &DbPool, escrow_id: &str) -> Result<bool> {
+    let mut conn = pool.get().context("Failed to get DB connection")?;
+    let escrow_clone = escrow_id.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        let count: i64 = reviews::table
+            .filter(reviews::escrow_id.eq(escrow_clone))
+            .count()
+            .get_result(&mut conn)
+            .context("Failed to check escrow review existence")?;
+
+        Ok(count > 0)
+    })
+    .await
+    .context("Database task panicked")?
+}
+
+/// Build the (unsigned) reputation file of a vendor from their reviews
 ///
-/// # Performance
-/// - Uses database aggregation for efficiency
-/// - O(1) complexity with proper indexes
-pub async fn db_get_vendor_stats(pool: &DbPool, vendor_uuid: Uuid) -> Result<(i64, f64)> {
+/// `vendor_pubkey` is the vendor's registered reputation key, or their UUID
+/// while they have none. The file carries the vendor's escrow outcomes and
+/// their weighted score under `params`. The vendor signs the file
+/// themselves before export.
+pub async fn db_get_vendor_reputation(
+    pool: &DbPool,
+    vendor_uuid: Uuid,
+    params: &ScoringParams,
+) -> Result<VendorReputation> {
+    let reviews = db_get_vendor_reviews(pool, vendor_uuid).await?;
+    let vendor_pubkey = db_get_reputation_key(pool, &vendor_uuid.to_string())
+        .await?
+        .unwrap_or_else(|| vendor_uuid.to_string());
+    let outcomes = db_get_escrow_outcomes(pool, vendor_uuid).await?;
+    let stats = calculate_stats(&reviews);
+
+    let mut reputation = VendorReputation {
+        format_version: REPUTATION_FORMAT_V1.to_string(),
+        vendor_pubkey,
+        generated_at: Utc::now(),
+        reviews,
+        stats,
+        outcomes: Some(outcomes),
+        score: None,
+        signature: None,
+    };
+    reputation.score = Some(reputation_score(&reputation, params));
+
+    Ok(reputation)
+}
+
+/// Count the funded, disputed and refunded escrows of a vendor
+///
+/// An escrow counts once its deposit is covered. It counts as disputed
+/// if it ever went through `disputed`, as refunded once a refund to the
+/// buyer is ordered or confirmed.
+pub async fn db_get_escrow_outcomes(pool: &DbPool, vendor_uuid: Uuid) -> Result<EscrowOutcomes> {
     let mut conn = pool.get().context("Failed to get DB connection")?;
     let vendor_id_str = vendor_uuid.to_string();
 
     tokio::task::spawn_blocking(move || {
-        use diesel::dsl::count_star;
+        let statuses: Vec<String> = escrows::table
+            .filter(escrows::vendor_id.eq(&vendor_id_str))
+            .select(escrows::status)
+            .load(&mut conn)
+            .context("Failed to load vendor escrows")?;
 
-        // Get count of verified reviews
-        let total: i64 = reviews::table
-            .filter(reviews::vendor_id.eq(&vendor_id_str))
-            .filter(reviews::verified.eq(true))
-            .select(count_star())
+        let disputed: i64 = escrow_events::table
+            .inner_join(escrows::table)
+            .filter(escrows::vendor_id.eq(&vendor_id_str))
+            .filter(escrow_events::to_status.eq(EscrowStatus::Disputed.as_str()))
+            .select(diesel::dsl::count(escrow_events::escrow_id).aggregate_distinct())
             .first(&mut conn)
-            .context("Failed to count vendor reviews")?;
-
-        // Calculate average manually (Diesel's avg() returns Numeric which can't deserialize as f64 in SQLite)
-        let ratings: Vec<i32> = reviews::table
-            .filter(reviews::vendor_id.eq(&vendor_id_str))
-            .filter(reviews::verified.eq(true))
-            .select(reviews::rating)
-            .load(&mut conn)
-            .context("Failed to load ratings")?;
+            .context("Failed to count disputed escrows")?;
 
-        let average = if ratings.is_empty() {
-            0.0
-        } else {
-            let sum: i32 = ratings.iter().sum();
-            sum as f64 / ratings.len() as f64
+        let mut outcomes = EscrowOutcomes {
+            disputed: disputed as u32,
+            ..EscrowOutcomes::default()
         };
+        for status in statuses {
+            let Ok(status) = status.parse::<EscrowStatus>() else {
+                continue;
+            };
+            if UNFUNDED_ESCROW_STATUSES.contains(&status) {
+                continue;
+            }
+            outcomes.funded += 1;
+            if REFUND_ESCROW_STATUSES.contains(&status) {
+                outcomes.refunded += 1;
+            }
+        }
+
+        Ok(outcomes)
+    })
+    .await
+    .context("Database task panicked")?
+}
+
+/// Reputation key (ed25519, base64) registered by a vendor, if any
+pub async fn db_get_reputation_key(pool: &DbPool, vendor_id: &str) -> Result<Option<String>> {
+    let mut conn = pool.get().context("Failed to get DB connection")?;
+    let vendor_clone = vendor_id.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        vendor_reputation_keys::table
+            .filter(vendor_reputation_keys::vendor_id.eq(vendor_clone))
+            .select(vendor_reputation_keys::public_key)
+            .first::<String>(&mut conn)
+            .optional()
+            .context("Failed to load reputation key")
+    })
+    .await
+    .context("Database task panicked")?
+}
+
+/// Register the reputation key of a vendor
+///
+/// # Security
+/// - Caller MUST verify the proof of possession before calling this function
+/// - A key is registered once: 2.0 reviews are bound to it, so it cannot be
+///   replaced (PRIMARY KEY on vendor_id) nor shared (UNIQUE on public_key)
+pub async fn db_register_reputation_key(
+    pool: &DbPool,
+    vendor_id: &str,
+    public_key: &str,
+) -> Result<()> {
+    let mut conn = pool.get().context("Failed to get DB connection")?;
+    let vendor_clone = vendor_id.to_string();
+    let key_clone = public_key.to_string();
 
-        Ok((total, average))
+    tokio::task::spawn_blocking(move || {
+        diesel::insert_into(vendor_reputation_keys::table)
+            .values((
+                vendor_reputation_keys::vendor_id.eq(vendor_clone),
+                vendor_reputation_keys::public_key.eq(key_clone),
+                vendor_reputation_keys::created_at.eq(Utc::now().naive_utc()),
+            ))
+            .execute(&mut conn)
+            .context("Failed to register reputation key")?;
+        Ok(())
+    })
+    .await
+    .context("Database task panicked")?
+}
+
+/// Store an imported reputation file
+///
+/// Earlier imports of the vendor from the same marketplace(s) are replaced,
+/// so a refreshed export does not count the same reviews twice.
+pub async fn db_insert_imported_reputation(
+    pool: &DbPool,
+    imported: ImportedReputation,
+) -> Result<ImportedReputation> {
+    let mut conn = pool.get().context("Failed to get DB connection")?;
+
+    tokio::task::spawn_blocking(move || {
+        conn.transaction(|conn| {
+            diesel::delete(
+                imported_reputations::table
+                    .filter(imported_reputations::vendor_id.eq(&imported.vendor_id))
+                    .filter(imported_reputations::source_domains.eq(&imported.source_domains)),
+            )
+            .execute(conn)
+            .context("Failed to replace earlier import")?;
+
+            diesel::insert_into(imported_reputations::table)
+                .values(&imported)
+                .execute(conn)
+                .context("Failed to insert imported reputation")?;
+
+            imported_reputations::table
+                .filter(imported_reputations::id.eq(&imported.id))
+                .first::<ImportedReputation>(conn)
+                .context("Failed to retrieve imported reputation")
+        })
     })
     .await
     .context("Database task panicked")?
 }
 
+/// Reputation imported by a vendor, most recent file first
+pub async fn db_get_imported_reputations(
+    pool: &DbPool,
+    vendor_uuid: Uuid,
+) -> Result<Vec<ImportedReputation>> {
+    let mut conn = pool.get().context("Failed to get DB connection")?;
+    let vendor_id_str = vendor_uuid.to_string();
+
+    tokio::task::spawn_blocking(move || {
+        imported_reputations::table
+            .filter(imported_reputations::vendor_id.eq(vendor_id_str))
+            .order(imported_reputations::generated_at.desc())
+            .load::<ImportedReputation>(&mut conn)
+            .context("Failed to load imported reputations")
+    })
+    .await
+    .context("Database task panicked")?
+}
+
+/// Quick reputation statistics of a vendor
+#[derive(Debug, Clone, Serialize)]
+pub struct VendorStats {
+    /// Number of verified reviews
+    pub total_reviews: i64,
+
+    /// Plain average of the verified reviews
+    pub average_rating: f64,
+
+    /// Weighted score of the verified reviews, with dispute and refund rates
+    pub score: ReputationScore,
+}
+
+/// Get reputation statistics for a vendor
+///
+/// Only verified reviews count, for the plain average as for the score.
+pub async fn db_get_vendor_stats(
+    pool: &DbPool,
+    vendor_uuid: Uuid,
+    params: &ScoringParams,
+) -> Result<VendorStats> {
+    let reviews = db_get_verified_vendor_reviews(pool, vendor_uuid).await?;
+    let outcomes = db_get_escrow_outcomes(pool, vendor_uuid).await?;
+
+    let average_rating = if reviews.is_empty() {
+        0.0
+    } else {
+        let sum: u32 = reviews.iter().map(|r| u32::from(r.rating)).sum();
+        f64::from(sum) / reviews.len() as f64
+    };
+
+    Ok(VendorStats {
+        total_reviews: reviews.len() as i64,
+        average_rating,
+        score: calculate_score(&reviews, Some(&outcomes), params, Utc::now()),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     
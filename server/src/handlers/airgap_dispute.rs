@@ -17,12 +17,19 @@
 /// 3. `GET /api/escrow/:id/dispute/qr` - Generate QR code image (PNG data URI)
 use actix_session::Session;
 use actix_web::{get, post, web, HttpResponse, Responder};
+use diesel::prelude::*;
+use diesel::SqliteConnection;
 use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
 use uuid::Uuid;
 
-use crate::db::DbPool;
-use crate::models::escrow::Escrow;
-use crate::services::airgap::{ArbiterDecision, DisputeRequest};
+use crate::db::{db_create_transaction, DbPool};
+use crate::models::audit_log::{AuditEntry, AuditEventType};
+use crate::models::escrow::{Escrow, EscrowStatus};
+use crate::models::escrow_event::{EscrowActor, EscrowEventRecord};
+use crate::models::transaction::NewTransaction;
+use crate::services::airgap::{ArbiterDecision, ArbiterResolution, DisputeRequest};
+use crate::services::dispute_evidence::DisputeEvidenceService;
 
 /// Helper: Extract user_id from session
 fn get_user_id_from_session(session: &Session) -> actix_web::Result<Uuid> {
@@ -84,6 +91,7 @@ pub struct ImportDecisionRequest {
 pub async fn export_dispute(
     escrow_id: web::Path<Uuid>,
     pool: web::Data<DbPool>,
+    evidence_service: web::Data<DisputeEvidenceService>,
     session: Session,
 ) -> actix_web::Result<impl Responder> {
     let _user_id = get_user_id_from_session(&session)?;
@@ -109,7 +117,7 @@ pub async fn export_dispute(
     .ok_or_else(|| actix_web::error::ErrorNotFound("Escrow not found"))?;
 
     // Verify escrow is in dispute state
-    if escrow.status != "disputed" {
+    if escrow.status != EscrowStatus::Disputed.as_str() {
         return Err(actix_web::error::ErrorBadRequest(format!(
             "Escrow not in disputed state (current: {})",
             escrow.status
@@ -117,7 +125,7 @@ pub async fn export_dispute(
     }
 
     // Parse dispute details from multisig_state_json
-    let dispute_data: serde_json::Value = serde_json::from_str(&escrow.multisig_state_json.unwrap_or_else(|| "{}".to_string()))
+    let dispute_data: serde_json::Value = serde_json::from_str(escrow.multisig_state_json.as_deref().unwrap_or("{}"))
         .map_err(|e| actix_web::error::ErrorInternalServerError(format!("Failed to parse multisig state: {}", e)))?;
 
     // Extract dispute information (may be stored in different format)
@@ -150,6 +158,16 @@ pub async fn export_dispute(
         bytes
     });
 
+    // Evidence hashes (checked by the arbiter against the USB bundle)
+    let evidence = evidence_service
+        .evidence_hashes(&escrow)
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(format!("Failed to load evidence: {}", e)))?;
+    let evidence_deadline = evidence_service
+        .deadline(escrow_id)
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(format!("Failed to load dispute opening: {}", e)))?;
+
     // Create DisputeRequest
     let dispute_request = DisputeRequest {
         escrow_id,
@@ -161,7 +179,9 @@ pub async fn export_dispute(
         buyer_claim: buyer_claim.clone(),
         vendor_response: vendor_response.clone(),
         dispute_opened_at: escrow.updated_at.and_utc().timestamp(),
-        evidence_file_count: dispute_data["dispute"]["evidence_count"].as_u64().unwrap_or(0) as usize,
+        evidence_file_count: evidence.len(),
+        evidence_deadline: evidence_deadline.and_utc().timestamp(),
+        evidence,
         partial_tx_hex,
         nonce: nonce.clone(),
     };
@@ -185,13 +205,105 @@ pub async fn export_dispute(
             amount_xmr,
             buyer_claim,
             vendor_response,
-            evidence_count: dispute_data["evidence_count"].as_u64().unwrap_or(0) as usize,
+            evidence_count: dispute_request.evidence.len(),
         },
     };
 
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// Apply a verified arbiter decision to a disputed escrow
+///
+/// Checks the escrow is still disputed and that a split fits the escrow
+/// amount, stores the decision in `multisig_state_json` and moves the escrow
+/// to the status of the ruling, all in one transaction along with the audit
+/// entry for the import. The signature must be verified by the caller.
+pub fn record_arbiter_decision(
+    conn: &mut SqliteConnection,
+    escrow_id: Uuid,
+    decision: &ArbiterDecision,
+    imported_by: Uuid,
+) -> anyhow::Result<Escrow> {
+    use crate::schema::escrows::dsl::{escrows, id, multisig_state_json};
+
+    conn.transaction(|conn| {
+        let escrow = Escrow::find_by_id(conn, escrow_id.to_string())?;
+
+        if escrow.get_status()? != EscrowStatus::Disputed {
+            anyhow::bail!("Escrow not in disputed state (current: {})", escrow.status);
+        }
+
+        // Split amounts cannot exceed what the escrow holds
+        let split_amounts = match decision.decision {
+            ArbiterResolution::Split {
+                buyer_amount,
+                vendor_amount,
+            } => {
+                let total = buyer_amount.checked_add(vendor_amount);
+                if total.is_none_or(|t| t > escrow.amount.max(0) as u64) {
+                    anyhow::bail!(
+                        "Split amounts ({} + {}) exceed escrow amount {}",
+                        buyer_amount,
+                        vendor_amount,
+                        escrow.amount
+                    );
+                }
+                Some((buyer_amount as i64, vendor_amount as i64))
+            }
+            _ => None,
+        };
+
+        // Store the decision and signed transaction hex
+        let mut state_json: serde_json::Value =
+            serde_json::from_str(escrow.multisig_state_json.as_deref().unwrap_or("{}"))
+                .unwrap_or_else(|_| serde_json::json!({}));
+
+        state_json["arbiter_decision"] = serde_json::json!({
+            "resolution": decision.decision.as_str(),
+            "buyer_amount": split_amounts.map(|(buyer, _)| buyer),
+            "vendor_amount": split_amounts.map(|(_, vendor)| vendor),
+            "reason": decision.reason,
+            "decided_at": decision.decided_at,
+            "signed_tx_hex": decision.signed_tx_hex,
+        });
+
+        diesel::update(escrows.filter(id.eq(&escrow.id)))
+            .set(multisig_state_json.eq(serde_json::to_string(&state_json).ok()))
+            .execute(conn)?;
+
+        // The decision is signed with the arbiter key, so it is attributed to the escrow's arbiter
+        let arbiter = EscrowActor::user(&escrow.arbiter_id);
+        let new_status = decision.decision.escrow_status();
+        let updated = EscrowEventRecord::record_transition(
+            conn,
+            &escrow.id,
+            new_status,
+            &arbiter,
+            Some(&format!("Air-gap arbiter decision: {}", decision.reason)),
+        )?;
+
+        // Only a digest of the signed transaction goes into the audit log
+        let audit_data = serde_json::json!({
+            "resolution": decision.decision.as_str(),
+            "buyer_amount": split_amounts.map(|(buyer, _)| buyer),
+            "vendor_amount": split_amounts.map(|(_, vendor)| vendor),
+            "decided_at": decision.decided_at,
+            "new_status": new_status.as_str(),
+            "signed_tx_sha3": hex::encode(Sha3_256::digest(decision.signed_tx_hex.as_bytes())),
+            "imported_by": imported_by,
+        });
+        AuditEntry::append(
+            conn,
+            AuditEventType::AirgapImport,
+            &escrow.id,
+            &arbiter,
+            &audit_data,
+        )?;
+
+        Ok(updated)
+    })
+}
+
 /// POST /api/escrow/:escrow_id/dispute/import
 ///
 /// Import arbiter decision from QR code scan.
@@ -221,7 +333,7 @@ pub async fn import_arbiter_decision(
     pool: web::Data<DbPool>,
     session: Session,
 ) -> actix_web::Result<impl Responder> {
-    let _user_id = get_user_id_from_session(&session)?;
+    let user_id = get_user_id_from_session(&session)?;
     let escrow_id = escrow_id.into_inner();
 
     // Parse decision JSON
@@ -285,81 +397,38 @@ pub async fn import_arbiter_decision(
     .map_err(|e| actix_web::error::ErrorInternalServerError(format!("DB error: {}", e)))?
     .ok_or_else(|| actix_web::error::ErrorNotFound("Escrow not found"))?;
 
-    // Verify escrow is in dispute state
-    if escrow.status != "disputed" {
-        return Err(actix_web::error::ErrorBadRequest(format!(
-            "Escrow not in disputed state (current: {})",
-            escrow.status
-        )));
-    }
-
     // Submit signed transaction to Monero network
     // The signed_tx_hex from arbiter contains the final multisig signature
     tracing::info!(
-        "Arbiter decision imported for escrow {}: {:?} → reason: {}",
+        "Arbiter decision imported for escrow {} ({}): {:?} → reason: {}",
         escrow_id,
+        escrow.status,
         decision.decision,
         decision.reason
     );
 
-    // Determine final escrow status based on arbiter decision
-    let new_status = match decision.decision {
-        crate::services::airgap::ArbiterResolution::Buyer => "refunded", // Funds go to buyer
-        crate::services::airgap::ArbiterResolution::Vendor => "completed", // Funds go to vendor
+    let new_status = decision.decision.escrow_status();
+    let split_amounts = match decision.decision {
+        ArbiterResolution::Split {
+            buyer_amount,
+            vendor_amount,
+        } => Some((buyer_amount as i64, vendor_amount as i64)),
+        _ => None,
     };
 
-    // Clone decision data before moving into closure
-    let decision_resolution = decision.decision.clone();
-    let decision_reason = decision.reason.clone();
-    let decision_decided_at = decision.decided_at;
-    let signed_tx_hex = decision.signed_tx_hex.clone();
-
-    // Clone again for final response (will be moved into closure)
-    let decision_resolution_final = decision_resolution.clone();
-    let decision_reason_final = decision_reason.clone();
-    let signed_tx_hex_final = signed_tx_hex.clone();
-
-    // Update escrow status in database
-    let escrow_id_str = escrow_id.to_string();
-    let new_status_clone = new_status.to_string();
+    // Clone for final response (decision is moved into closure)
+    let decision_resolution_final = decision.decision.clone();
+    let decision_reason_final = decision.reason.clone();
+    let signed_tx_hex_final = decision.signed_tx_hex.clone();
 
     let mut conn = pool.get()
         .map_err(|e| actix_web::error::ErrorInternalServerError(format!("DB pool error: {}", e)))?;
 
-    let _updated_escrow = web::block(move || {
-        use diesel::prelude::*;
-        use crate::schema::escrows::dsl::*;
-
-        // Update escrow status and store signed transaction hex
-        let mut state_json: serde_json::Value = serde_json::from_str(
-            &escrow.multisig_state_json.unwrap_or_else(|| "{}".to_string())
-        ).unwrap_or_else(|_| serde_json::json!({}));
-
-        // Add arbiter decision to state
-        state_json["arbiter_decision"] = serde_json::json!({
-            "resolution": match decision_resolution {
-                crate::services::airgap::ArbiterResolution::Buyer => "buyer",
-                crate::services::airgap::ArbiterResolution::Vendor => "vendor",
-            },
-            "reason": decision_reason,
-            "decided_at": decision_decided_at,
-            "signed_tx_hex": signed_tx_hex,
-        });
-
-        diesel::update(escrows.filter(id.eq(escrow_id_str)))
-            .set((
-                status.eq(&new_status_clone),
-                multisig_state_json.eq(serde_json::to_string(&state_json).ok()),
-                updated_at.eq(chrono::Utc::now().naive_utc()),
-            ))
-            .execute(&mut conn)?;
-
-        escrows.filter(id.eq(escrow_id.to_string()))
-            .first::<Escrow>(&mut conn)
-    })
-    .await
-    .map_err(|e| actix_web::error::ErrorInternalServerError(format!("DB update error: {}", e)))?
-    .map_err(|e| actix_web::error::ErrorInternalServerError(format!("DB error: {}", e)))?;
+    // Status and split checks run inside the transaction that applies the decision
+    web::block(move || record_arbiter_decision(&mut conn, escrow_id, &decision, user_id))
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(format!("DB update error: {}", e)))?
+        .map_err(|e| actix_web::error::ErrorBadRequest(format!("Decision not applied: {}", e)))?;
 
     tracing::info!(
         "Escrow {} updated to status '{}' after arbiter decision",
@@ -367,16 +436,37 @@ pub async fn import_arbiter_decision(
         new_status
     );
 
+    // Record the split settlement with per-party amounts
+    // (tx_hash is set once the signed transaction is broadcast)
+    if let Some((buyer_amount, vendor_amount)) = split_amounts {
+        db_create_transaction(
+            &pool,
+            NewTransaction {
+                id: Uuid::new_v4().to_string(),
+                escrow_id: escrow_id.to_string(),
+                tx_hash: None,
+                amount_xmr: buyer_amount + vendor_amount,
+                confirmations: 0,
+                buyer_amount_xmr: Some(buyer_amount),
+                vendor_amount_xmr: Some(vendor_amount),
+            },
+        )
+        .await
+        .map_err(|e| {
+            actix_web::error::ErrorInternalServerError(format!(
+                "Failed to record split transaction: {}",
+                e
+            ))
+        })?;
+    }
+
     // Note: Actual transaction broadcast to Monero network would happen here
     // using monero-wallet-rpc's relay_tx or submit_transfer endpoints
     // For testnet alpha, the signed_tx_hex is stored but not automatically broadcast
     // to allow manual verification before network submission
 
     // Build final response using cloned values
-    let final_decision_str = match decision_resolution_final {
-        crate::services::airgap::ArbiterResolution::Buyer => "buyer",
-        crate::services::airgap::ArbiterResolution::Vendor => "vendor",
-    };
+    let final_decision_str = decision_resolution_final.as_str();
 
     Ok(HttpResponse::Ok().json(serde_json::json!({
         "status": "accepted",
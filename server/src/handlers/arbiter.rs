@@ -0,0 +1,207 @@
+//! Arbiter pool API handlers
+//!
+//! The escrow's arbiter can recuse themselves; admins see arbiter workloads,
+//! an escrow's assignment history, and can move an escrow to another arbiter.
+
+use actix_session::Session;
+use actix_web::{get, post, web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::handlers::auth::get_user_id_from_session;
+use crate::models::arbiter_assignment::{ArbiterAssignment, AssignmentReason};
+use crate::models::audit_log::AuditEventType;
+use crate::models::escrow_event::EscrowActor;
+use crate::services::arbiter_assignment::{ArbiterAssignmentService, Reassignment};
+use crate::services::audit::AuditLogger;
+
+/// Request body for an arbiter recusing from an escrow
+#[derive(Debug, Deserialize, Validate)]
+pub struct RecuseRequest {
+    #[validate(length(min = 3, max = 1000, message = "Reason must be 3-1000 characters"))]
+    pub reason: String,
+}
+
+/// Request body for an admin reassignment
+#[derive(Debug, Deserialize, Validate)]
+pub struct ReassignRequest {
+    #[validate(length(max = 1000, message = "Note must be at most 1000 characters"))]
+    pub note: Option<String>,
+}
+
+/// Response body for one assignment of an escrow
+#[derive(Debug, Serialize)]
+pub struct ArbiterAssignmentResponse {
+    pub arbiter_id: String,
+    pub reason: String,
+    pub assigned_at: String,
+    pub ended_at: Option<String>,
+    pub end_reason: Option<String>,
+    pub note: Option<String>,
+}
+
+impl From<ArbiterAssignment> for ArbiterAssignmentResponse {
+    fn from(assignment: ArbiterAssignment) -> Self {
+        Self {
+            arbiter_id: assignment.arbiter_id,
+            reason: assignment.reason,
+            assigned_at: assignment.assigned_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            ended_at: assignment
+                .ended_at
+                .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string()),
+            end_reason: assignment.end_reason,
+            note: assignment.note,
+        }
+    }
+}
+
+/// 400 response for a malformed escrow ID path segment
+fn invalid_escrow_id() -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({
+        "error": "Invalid escrow_id"
+    }))
+}
+
+/// 200 response describing a completed reassignment
+fn reassigned(reassignment: Reassignment) -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({
+        "success": true,
+        "escrow_id": reassignment.escrow.id,
+        "previous_arbiter_id": reassignment.previous_arbiter_id,
+        "arbiter_id": reassignment.escrow.arbiter_id,
+        "multisig_restarted": reassignment.multisig_restarted
+    }))
+}
+
+/// POST /api/escrow/{id}/arbiter/recuse - Current arbiter steps down
+///
+/// The escrow goes to the least loaded eligible arbiter. If the multisig was
+/// not set up yet, the setup restarts with the new arbiter.
+#[post("/escrow/{id}/arbiter/recuse")]
+pub async fn recuse(
+    service: web::Data<ArbiterAssignmentService>,
+    session: Session,
+    path: web::Path<String>,
+    req: web::Json<RecuseRequest>,
+) -> impl Responder {
+    if let Err(e) = req.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Validation error: {}", e)
+        }));
+    }
+
+    let arbiter_id = match get_user_id_from_session(&session) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let escrow_id = match path.into_inner().parse::<Uuid>() {
+        Ok(id) => id,
+        Err(_) => return invalid_escrow_id(),
+    };
+
+    match service.recuse(escrow_id, arbiter_id, &req.reason).await {
+        Ok(reassignment) => reassigned(reassignment),
+        Err(e) if e.to_string().contains("Only the escrow's current arbiter") => {
+            HttpResponse::Forbidden().json(serde_json::json!({
+                "error": e.to_string()
+            }))
+        }
+        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Failed to recuse: {}", e)
+        })),
+    }
+}
+
+/// POST /admin/escrow/{id}/arbiter/reassign - Move an escrow to another arbiter
+///
+/// Recorded in the audit log as an admin action.
+#[post("/escrow/{id}/arbiter/reassign")]
+pub async fn reassign(
+    service: web::Data<ArbiterAssignmentService>,
+    audit: web::Data<AuditLogger>,
+    session: Session,
+    path: web::Path<String>,
+    req: web::Json<ReassignRequest>,
+) -> impl Responder {
+    if let Err(e) = req.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Validation error: {}", e)
+        }));
+    }
+
+    let escrow_id = match path.into_inner().parse::<Uuid>() {
+        Ok(id) => id,
+        Err(_) => return invalid_escrow_id(),
+    };
+
+    let admin_id = match get_user_id_from_session(&session) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match service
+        .reassign(escrow_id, AssignmentReason::Admin, req.note.clone())
+        .await
+    {
+        Ok(reassignment) => {
+            audit
+                .record_or_log(
+                    AuditEventType::AdminAction,
+                    escrow_id,
+                    EscrowActor::user(admin_id),
+                    serde_json::json!({
+                        "action": "reassign_arbiter",
+                        "previous_arbiter_id": reassignment.previous_arbiter_id,
+                        "arbiter_id": reassignment.escrow.arbiter_id,
+                        "multisig_restarted": reassignment.multisig_restarted,
+                        "note": req.note,
+                    }),
+                )
+                .await;
+            reassigned(reassignment)
+        }
+        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Failed to reassign arbiter: {}", e)
+        })),
+    }
+}
+
+/// GET /admin/escrow/{id}/arbiter/history - Arbiters who held an escrow, oldest first
+#[get("/escrow/{id}/arbiter/history")]
+pub async fn get_history(
+    service: web::Data<ArbiterAssignmentService>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let escrow_id = match path.into_inner().parse::<Uuid>() {
+        Ok(id) => id,
+        Err(_) => return invalid_escrow_id(),
+    };
+
+    match service.history(escrow_id).await {
+        Ok(history) => HttpResponse::Ok().json(
+            history
+                .into_iter()
+                .map(ArbiterAssignmentResponse::from)
+                .collect::<Vec<_>>(),
+        ),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to load assignment history: {}", e)
+        })),
+    }
+}
+
+/// GET /admin/arbiters/workload - Open disputes and escrows per arbiter
+#[get("/arbiters/workload")]
+pub async fn get_workload(service: web::Data<ArbiterAssignmentService>) -> impl Responder {
+    match service.workloads().await {
+        Ok(workloads) => HttpResponse::Ok().json(serde_json::json!({
+            "max_open_disputes": service.config().max_open_disputes,
+            "arbiters": workloads
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to load arbiter workloads: {}", e)
+        })),
+    }
+}
@@ -0,0 +1,74 @@
+//! Audit log endpoints
+//!
+//! Admins verify the hash chain and page through the entries for export;
+//! the signed checkpoints are public so anyone can hold the marketplace to
+//! them.
+
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::services::audit::{AuditLogger, MAX_EXPORT_PAGE};
+
+/// Query parameters for exporting the audit log
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    /// Return entries after this sequence (0 = from the start)
+    #[serde(default)]
+    pub after: i64,
+    /// Page size, capped at [`MAX_EXPORT_PAGE`]
+    pub limit: Option<i64>,
+}
+
+/// GET /admin/audit/verify - Recompute the audit log hash chain
+///
+/// Responds 200 when the chain is intact and 409 with the first broken
+/// sequence otherwise.
+#[get("/audit/verify")]
+pub async fn verify_audit_log(audit: web::Data<AuditLogger>) -> impl Responder {
+    match audit.verify().await {
+        Ok(result) if result.is_valid() => HttpResponse::Ok().json(result),
+        Ok(result) => HttpResponse::Conflict().json(result),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to verify audit log: {}", e)
+        })),
+    }
+}
+
+/// GET /admin/audit/export?after=N&limit=M - Audit log entries, oldest first
+///
+/// `next_after` is the sequence to pass as `after` for the following page
+/// (None once the head is reached).
+#[get("/audit/export")]
+pub async fn export_audit_log(
+    audit: web::Data<AuditLogger>,
+    query: web::Query<ExportQuery>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(MAX_EXPORT_PAGE);
+    match audit.export(query.after, limit).await {
+        Ok(entries) => {
+            let next_after = (entries.len() as i64 >= limit.clamp(1, MAX_EXPORT_PAGE))
+                .then(|| entries.last().map(|entry| entry.sequence))
+                .flatten();
+            HttpResponse::Ok().json(serde_json::json!({
+                "entries": entries,
+                "next_after": next_after,
+            }))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to export audit log: {}", e)
+        })),
+    }
+}
+
+/// GET /api/audit/checkpoints - Signed audit log checkpoints, oldest first
+///
+/// Public: checkpoints reveal only sequence numbers and hashes.
+#[get("/audit/checkpoints")]
+pub async fn list_audit_checkpoints(audit: web::Data<AuditLogger>) -> impl Responder {
+    match audit.checkpoints().await {
+        Ok(checkpoints) => HttpResponse::Ok().json(checkpoints),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to load audit checkpoints: {}", e)
+        })),
+    }
+}
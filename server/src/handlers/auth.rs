@@ -22,7 +22,11 @@ use validator::Validate;
 
 use crate::db::DbPool;
 use crate::error::ApiError;
+use crate::crypto::message_envelope::validate_public_key;
 use crate::middleware::csrf::validate_csrf_token;
+use crate::models::audit_log::{AuditEntry, AuditEventType};
+use crate::models::encryption_key::UserEncryptionKey;
+use crate::models::escrow_event::EscrowActor;
 use crate::models::user::{NewUser, User};
 
 /// Helper function to check if request is from HTMX
@@ -51,6 +55,24 @@ fn htmx_success_redirect(location: &str) -> HttpResponse {
         .body("")
 }
 
+/// Authenticated user ID from the session
+///
+/// Shared by the handlers that work with `Uuid` user IDs; a missing or
+/// malformed `user_id` gets a 401 response.
+#[allow(clippy::result_large_err)]
+pub(crate) fn get_user_id_from_session(session: &Session) -> Result<Uuid, HttpResponse> {
+    session
+        .get::<String>("user_id")
+        .ok()
+        .flatten()
+        .and_then(|id| id.parse::<Uuid>().ok())
+        .ok_or_else(|| {
+            HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Not authenticated"
+            }))
+        })
+}
+
 #[derive(Debug, Validate, Deserialize)]
 pub struct RegisterRequest {
     #[validate(length(min = 3, max = 50))]
@@ -59,6 +81,8 @@ pub struct RegisterRequest {
     pub password: String,
     pub role: String,
     pub wallet_address: Option<String>,
+    /// P-256 public key generated in the browser for encrypted order messages
+    pub encryption_public_key: Option<String>,
     pub csrf_token: String,
 }
 
@@ -126,6 +150,21 @@ pub async fn register(
         }
     }
 
+    // If an encryption key is provided, validate its format
+    let encryption_public_key = req
+        .encryption_public_key
+        .clone()
+        .filter(|key| !key.is_empty());
+    if let Some(ref key) = encryption_public_key {
+        if validate_public_key(key).is_err() {
+            return if is_htmx {
+                Ok(htmx_error_response("Invalid encryption key. Please reload the page and try again."))
+            } else {
+                Err(ApiError::Internal("Invalid encryption public key".to_string()))
+            };
+        }
+    }
+
     let mut conn = pool.get().map_err(|e| ApiError::Internal(e.to_string()))?;
 
     // 1. Check if username exists (normalize to lowercase for case-insensitive comparison)
@@ -164,6 +203,13 @@ pub async fn register(
 
     let user = web::block(move || User::create(&mut conn, new_user)).await??;
 
+    // 4. Register the message encryption key
+    if let Some(public_key) = encryption_public_key {
+        let mut conn = pool.get().map_err(|e| ApiError::Internal(e.to_string()))?;
+        let user_id = user.id.clone();
+        web::block(move || UserEncryptionKey::upsert(&mut conn, &user_id, &public_key)).await??;
+    }
+
     info!(
         user_id = %user.id,
         username = %user.username,
@@ -485,13 +531,36 @@ pub async fn update_wallet_address(
     info!("DEBUG: Attempting to update wallet for user_id: {}", uid);
     info!("DEBUG: Wallet address to save: {}", wallet_addr);
 
-    let update_result = web::block(move || -> Result<usize, diesel::result::Error> {
-        let rows_affected = diesel::update(users::table.filter(users::id.eq(&uid)))
-            .set(users::wallet_address.eq(Some(&wallet_addr)))
-            .execute(&mut conn)?;
-
-        info!("DEBUG: Rows affected by UPDATE: {}", rows_affected);
-        Ok(rows_affected)
+    let update_result = web::block(move || -> Result<usize> {
+        conn.transaction::<_, anyhow::Error, _>(|conn| {
+            let previous_address: Option<String> = users::table
+                .filter(users::id.eq(&uid))
+                .select(users::wallet_address)
+                .first::<Option<String>>(conn)
+                .optional()?
+                .flatten();
+
+            let rows_affected = diesel::update(users::table.filter(users::id.eq(&uid)))
+                .set(users::wallet_address.eq(Some(&wallet_addr)))
+                .execute(conn)?;
+
+            info!("DEBUG: Rows affected by UPDATE: {}", rows_affected);
+
+            // Payout address changes are audited with the update itself
+            if rows_affected > 0 {
+                AuditEntry::append(
+                    conn,
+                    AuditEventType::WalletAddressChange,
+                    &uid,
+                    &EscrowActor::user(&uid),
+                    &serde_json::json!({
+                        "previous_address": previous_address,
+                        "new_address": wallet_addr,
+                    }),
+                )?;
+            }
+            Ok(rows_affected)
+        })
     }).await;
 
     match update_result {
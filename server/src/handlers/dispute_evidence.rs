@@ -0,0 +1,302 @@
+//! Dispute evidence API handlers
+//!
+//! Buyer and vendor upload evidence for a disputed escrow until the
+//! submission deadline; parties, the escrow's arbiter and admins can list
+//! and download it (decrypted, named as in the arbiter's USB bundle).
+
+use actix_multipart::Multipart;
+use actix_session::Session;
+use actix_web::{get, post, web, HttpResponse, Responder};
+use futures_util::TryStreamExt;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::db::{db_load_escrow, DbPool};
+use crate::handlers::auth::get_user_id_from_session;
+use crate::models::dispute_evidence::{DisputeEvidence, EvidenceKind};
+use crate::models::escrow::Escrow;
+use crate::models::user::User;
+use crate::services::dispute_evidence::{DisputeEvidenceService, MAX_FILE_SIZE};
+
+/// Longest accepted evidence description
+const MAX_DESCRIPTION_LEN: usize = 1000;
+
+/// Response body for one piece of evidence
+#[derive(Debug, Serialize)]
+pub struct DisputeEvidenceResponse {
+    pub id: String,
+    pub uploader_id: String,
+    pub kind: String,
+    pub description: Option<String>,
+    pub file_name: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub created_at: String,
+}
+
+impl From<DisputeEvidence> for DisputeEvidenceResponse {
+    fn from(evidence: DisputeEvidence) -> Self {
+        Self {
+            id: evidence.id,
+            uploader_id: evidence.uploader_id,
+            kind: evidence.kind,
+            description: evidence.description,
+            file_name: evidence.file_name,
+            mime_type: evidence.mime_type,
+            size_bytes: evidence.size_bytes,
+            sha256: evidence.content_hash,
+            created_at: evidence.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// Load the escrow if `user_id` may see its evidence
+///
+/// Buyer, vendor, the escrow's arbiter and admins have access.
+async fn load_escrow_for_viewer(
+    pool: &DbPool,
+    escrow_id: Uuid,
+    user_id: Uuid,
+) -> Result<Escrow, HttpResponse> {
+    let escrow = db_load_escrow(pool, escrow_id).await.map_err(|_| {
+        HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Escrow not found"
+        }))
+    })?;
+
+    let user = user_id.to_string();
+    if [&escrow.buyer_id, &escrow.vendor_id, &escrow.arbiter_id].contains(&&user) {
+        return Ok(escrow);
+    }
+
+    let pool = pool.clone();
+    let is_admin = web::block(move || -> anyhow::Result<bool> {
+        let mut conn = pool.get()?;
+        Ok(User::find_by_id(&mut conn, user)?.role == "admin")
+    })
+    .await;
+
+    match is_admin {
+        Ok(Ok(true)) => Ok(escrow),
+        _ => Err(HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Not authorized to view evidence for this escrow"
+        }))),
+    }
+}
+
+/// Read a small text field of a multipart form
+async fn read_text_field(field: &mut actix_multipart::Field) -> Result<String, HttpResponse> {
+    let mut data = Vec::new();
+    while let Some(chunk) = field.try_next().await.map_err(|e| {
+        tracing::error!("Stream reading error: {}", e);
+        HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Invalid multipart data"
+        }))
+    })? {
+        data.extend_from_slice(&chunk);
+        if data.len() > MAX_DESCRIPTION_LEN * 4 {
+            return Err(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("{} is too long", field.name())
+            })));
+        }
+    }
+
+    String::from_utf8(data).map_err(|_| {
+        HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("{} must be UTF-8 text", field.name())
+        }))
+    })
+}
+
+/// POST /api/escrow/{id}/dispute/evidence - Submit a piece of evidence
+///
+/// Multipart form with `file`, `kind` (photo, tracking, message, document)
+/// and an optional `description`. Buyer or vendor only, until the evidence
+/// deadline.
+#[post("/escrow/{id}/dispute/evidence")]
+pub async fn submit_evidence(
+    evidence_service: web::Data<DisputeEvidenceService>,
+    session: Session,
+    path: web::Path<Uuid>,
+    mut multipart: Multipart,
+) -> impl Responder {
+    let user_id = match get_user_id_from_session(&session) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let escrow_id = path.into_inner();
+
+    let mut file: Option<Vec<u8>> = None;
+    let mut kind: Option<EvidenceKind> = None;
+    let mut description: Option<String> = None;
+
+    loop {
+        let mut field = match multipart.try_next().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => {
+                tracing::error!("Multipart parsing error: {}", e);
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "Invalid multipart data"
+                }));
+            }
+        };
+
+        match field.name() {
+            "file" => {
+                let mut data = Vec::new();
+                loop {
+                    match field.try_next().await {
+                        Ok(Some(chunk)) => data.extend_from_slice(&chunk),
+                        Ok(None) => break,
+                        Err(e) => {
+                            tracing::error!("Stream reading error: {}", e);
+                            return HttpResponse::BadRequest().json(serde_json::json!({
+                                "error": "Failed to read file data"
+                            }));
+                        }
+                    }
+                    if data.len() > MAX_FILE_SIZE {
+                        return HttpResponse::BadRequest().json(serde_json::json!({
+                            "error": format!("File too large. Maximum size: {}MB", MAX_FILE_SIZE / 1024 / 1024)
+                        }));
+                    }
+                }
+                file = Some(data);
+            }
+            "kind" => match read_text_field(&mut field).await {
+                Ok(value) => match value.trim().parse::<EvidenceKind>() {
+                    Ok(parsed) => kind = Some(parsed),
+                    Err(e) => {
+                        return HttpResponse::BadRequest().json(serde_json::json!({
+                            "error": e.to_string()
+                        }))
+                    }
+                },
+                Err(response) => return response,
+            },
+            "description" => match read_text_field(&mut field).await {
+                Ok(value) => {
+                    let value = value.trim().to_string();
+                    if value.chars().count() > MAX_DESCRIPTION_LEN {
+                        return HttpResponse::BadRequest().json(serde_json::json!({
+                            "error": format!("Description must be at most {} characters", MAX_DESCRIPTION_LEN)
+                        }));
+                    }
+                    description = (!value.is_empty()).then_some(value);
+                }
+                Err(response) => return response,
+            },
+            _ => {}
+        }
+    }
+
+    let (Some(file), Some(kind)) = (file, kind) else {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Both file and kind are required"
+        }));
+    };
+
+    match evidence_service
+        .submit(escrow_id, user_id, kind, description, file)
+        .await
+    {
+        Ok(evidence) => HttpResponse::Created().json(DisputeEvidenceResponse::from(evidence)),
+        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Failed to submit evidence: {}", e)
+        })),
+    }
+}
+
+/// GET /api/escrow/{id}/dispute/evidence - List evidence and the deadline
+#[get("/escrow/{id}/dispute/evidence")]
+pub async fn list_evidence(
+    pool: web::Data<DbPool>,
+    evidence_service: web::Data<DisputeEvidenceService>,
+    session: Session,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_session(&session) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let escrow_id = path.into_inner();
+
+    if let Err(response) = load_escrow_for_viewer(&pool, escrow_id, user_id).await {
+        return response;
+    }
+
+    let deadline = evidence_service
+        .deadline(escrow_id)
+        .await
+        .ok()
+        .map(|d| d.format("%Y-%m-%d %H:%M:%S").to_string());
+
+    match evidence_service.list(escrow_id).await {
+        Ok(evidence) => HttpResponse::Ok().json(serde_json::json!({
+            "deadline": deadline,
+            "evidence": evidence
+                .into_iter()
+                .map(DisputeEvidenceResponse::from)
+                .collect::<Vec<_>>(),
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to load evidence: {}", e)
+        })),
+    }
+}
+
+/// GET /api/escrow/{id}/dispute/evidence/{evidence_id} - Download a file
+///
+/// The file is decrypted and checked against its recorded hash.
+#[get("/escrow/{id}/dispute/evidence/{evidence_id}")]
+pub async fn download_evidence(
+    pool: web::Data<DbPool>,
+    evidence_service: web::Data<DisputeEvidenceService>,
+    session: Session,
+    path: web::Path<(Uuid, Uuid)>,
+) -> impl Responder {
+    let user_id = match get_user_id_from_session(&session) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+    let (escrow_id, evidence_id) = path.into_inner();
+
+    let escrow = match load_escrow_for_viewer(&pool, escrow_id, user_id).await {
+        Ok(escrow) => escrow,
+        Err(response) => return response,
+    };
+
+    let lookup = web::block(move || -> anyhow::Result<DisputeEvidence> {
+        let mut conn = pool.get()?;
+        DisputeEvidence::find_by_id(&mut conn, &evidence_id.to_string())
+    })
+    .await;
+
+    let evidence = match lookup {
+        Ok(Ok(evidence)) if evidence.escrow_id == escrow.id => evidence,
+        _ => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Evidence not found"
+            }))
+        }
+    };
+
+    match evidence_service.read(&evidence).await {
+        Ok(data) => HttpResponse::Ok()
+            .content_type(evidence.mime_type.as_str())
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"", evidence.file_name),
+            ))
+            .insert_header(("X-Content-SHA256", evidence.content_hash))
+            .body(data),
+        Err(e) => {
+            tracing::error!("Failed to read evidence {}: {}", evidence.id, e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to read evidence"
+            }))
+        }
+    }
+}
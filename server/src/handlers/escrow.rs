@@ -594,15 +594,24 @@ pub async fn initiate_dispute(
 pub struct ResolveDisputeRequest {
     #[validate(custom = "validate_resolution")]
     pub resolution: String,
+    /// Winner's address (buyer's address for a split)
     #[validate(length(equal = 95))]
     pub recipient_address: String,
+    /// Split only: buyer's share of the escrow in percent (1-99)
+    #[serde(default)]
+    #[validate(range(min = 1, max = 99))]
+    pub buyer_share_percent: Option<u8>,
+    /// Split only: vendor's address
+    #[serde(default)]
+    #[validate(length(equal = 95))]
+    pub vendor_address: Option<String>,
 }
 
 /// Custom validator for resolution field
 fn validate_resolution(resolution: &str) -> Result<(), validator::ValidationError> {
-    if resolution != "buyer" && resolution != "vendor" {
+    if resolution != "buyer" && resolution != "vendor" && resolution != "split" {
         return Err(validator::ValidationError::new(
-            "resolution must be 'buyer' or 'vendor'",
+            "resolution must be 'buyer', 'vendor' or 'split'",
         ));
     }
     Ok(())
@@ -615,6 +624,8 @@ fn validate_resolution(resolution: &str) -> Result<(), validator::ValidationErro
 /// 2. Update escrow status based on resolution:
 ///    - "buyer" -> status: resolved_buyer (arbiter can then call refund)
 ///    - "vendor" -> status: resolved_vendor (arbiter can then call release)
+///    - "split" -> status: resolved_split, one transfer paying
+///      `buyer_share_percent` to `recipient_address` and the rest to `vendor_address`
 /// 3. Notify both parties via WebSocket
 ///
 /// # Endpoint
@@ -686,20 +697,46 @@ pub async fn resolve_dispute(
     }
 
     // Resolve dispute via orchestrator
-    match escrow_orchestrator
-        .resolve_dispute(
-            escrow_id,
-            user_id,
-            &payload.resolution,
-            payload.recipient_address.clone(),
-        )
-        .await
-    {
+    let result = if payload.resolution == "split" {
+        let (buyer_share_percent, vendor_address) =
+            match (payload.buyer_share_percent, payload.vendor_address.clone()) {
+                (Some(percent), Some(address)) => (percent, address),
+                _ => {
+                    return HttpResponse::BadRequest().json(serde_json::json!({
+                        "error": "Split resolution requires buyer_share_percent and vendor_address"
+                    }));
+                }
+            };
+        escrow_orchestrator
+            .resolve_dispute_split(
+                escrow_id,
+                user_id,
+                buyer_share_percent,
+                payload.recipient_address.clone(),
+                vendor_address,
+            )
+            .await
+    } else {
+        escrow_orchestrator
+            .resolve_dispute(
+                escrow_id,
+                user_id,
+                &payload.resolution,
+                payload.recipient_address.clone(),
+            )
+            .await
+    };
+
+    match result {
         Ok(tx_hash) => HttpResponse::Ok().json(serde_json::json!({
             "success": true,
             "resolution": &payload.resolution,
             "tx_hash": tx_hash,
-            "message": format!("Dispute resolved in favor of {}, funds transferred", &payload.resolution)
+            "message": if payload.resolution == "split" {
+                "Dispute resolved with a split settlement, funds transferred".to_string()
+            } else {
+                format!("Dispute resolved in favor of {}, funds transferred", &payload.resolution)
+            }
         })),
         Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
             "error": format!("Failed to resolve dispute: {}", e)
@@ -9,13 +9,18 @@ use serde::{Deserialize, Serialize};
 use tera::{Context, Tera};
 use tracing::{error, info, warn};
 
+use crate::config::ReviewPolicy;
+use crate::crypto::message_envelope::MessageEnvelope;
 use crate::db::DbPool;
 use crate::middleware::csrf::get_csrf_token;
 use crate::models::escrow::Escrow;
 use crate::models::listing::Listing;
 use crate::models::order::Order;
+use crate::models::order_item::OrderItem;
 use crate::models::cart::Cart;
 use crate::models::user::User;
+use crate::services::fiat_pricing::FiatPricing;
+use crate::services::price_conversion::PriceOracle;
 
 
 /// GET /new-home - New V2 Homepage
@@ -262,6 +267,7 @@ pub async fn show_listings(
 pub async fn show_listing(
     tera: web::Data<Tera>,
     pool: web::Data<DbPool>,
+    oracle: web::Data<PriceOracle>,
     session: Session,
     listing_id: web::Path<String>,
 ) -> impl Responder {
@@ -351,6 +357,31 @@ pub async fn show_listing(
     ctx.insert("vendor", &vendor);
     ctx.insert("price_display", &listing.price_as_xmr());
     ctx.insert("images", &images);
+
+    // Fiat equivalents, with when the rate was last refreshed
+    let fiat_prices: Vec<serde_json::Value> = match oracle.rates().await {
+        Ok(rates) => rates
+            .iter()
+            .map(|rate| {
+                serde_json::json!({
+                    "currency": rate.currency.to_uppercase(),
+                    "amount": rate.convert_atomic(listing.price_xmr),
+                    "fetched_at": rate.fetched_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+                    "stale": rate.stale,
+                })
+            })
+            .collect(),
+        Err(e) => {
+            warn!("Failed to load exchange rates: {}", e);
+            Vec::new()
+        }
+    };
+    ctx.insert("fiat_prices", &fiat_prices);
+    // Fiat peg: the XMR price follows the rate until checkout locks it in
+    ctx.insert(
+        "fiat_price",
+        &listing.fiat_price().map(|price| price.display()),
+    );
     ctx.insert("is_owner", &is_owner);
 
     // Add CSRF token for order creation
@@ -978,6 +1009,7 @@ pub async fn show_order(
     session: Session,
     order_id: web::Path<String>,
     encryption_key: web::Data<Vec<u8>>,
+    pricing: web::Data<FiatPricing>,
 ) -> impl Responder {
     use crate::models::listing::Listing;
     use crate::models::order::Order;
@@ -1067,6 +1099,21 @@ pub async fn show_order(
         }
     };
 
+    // Fetch line items
+    let mut conn_items = match pool.get() {
+        Ok(c) => c,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+    let items_order_id = order.id.clone();
+    let items = match web::block(move || OrderItem::find_by_order(&mut conn_items, &items_order_id)).await {
+        Ok(Ok(items)) => items,
+        _ => {
+            error!("Failed to fetch items for order");
+            return HttpResponse::InternalServerError().body("Failed to load order details");
+        }
+    };
+    let quantity: i32 = items.iter().map(|item| item.quantity).sum();
+
     // Fetch buyer details
     let mut conn3 = match pool.get() {
         Ok(c) => c,
@@ -1097,8 +1144,21 @@ pub async fn show_order(
     use crate::crypto::encryption::decrypt_field;
     use base64::{Engine as _, engine::general_purpose};
 
-    let decrypted_shipping_address = if order.vendor_id == user_id {
-        // Vendor viewing order - decrypt the address
+    // New orders hold an envelope encrypted to the vendor's public key: it is
+    // handed to the vendor's browser as-is and decrypted there
+    let shipping_envelope = if order.vendor_id == user_id {
+        order
+            .shipping_address
+            .as_deref()
+            .and_then(|raw| serde_json::from_str::<MessageEnvelope>(raw).ok())
+    } else {
+        None
+    };
+
+    let decrypted_shipping_address = if shipping_envelope.is_some() {
+        None
+    } else if order.vendor_id == user_id {
+        // Vendor viewing legacy order - decrypt the server-encrypted address
         match &order.shipping_address {
             Some(encrypted_base64) => {
                 // Decode base64 to bytes
@@ -1139,7 +1199,12 @@ pub async fn show_order(
         "total_xmr": order.total_xmr,
         "total_price_xmr": format!("{:.12}", order.total_xmr as f64 / 1_000_000_000_000.0),
         "unit_price_xmr": format!("{:.12}", listing.price_xmr as f64 / 1_000_000_000_000.0),
-        "quantity": 1, // TODO: Add quantity field to Order model
+        "quantity": quantity.max(1),
+        "items": order_items_for_template(&items),
+        "quote_expires_at": order
+            .quote_expires_at
+            .map(|at| at.format("%Y-%m-%d %H:%M UTC").to_string()),
+        "quote_expired": order.is_quote_expired(pricing.now()),
         "buyer_username": buyer_username,
         "vendor_username": vendor_username,
         "created_at": order.created_at,
@@ -1149,6 +1214,8 @@ pub async fn show_order(
         "completed_at": None::<String>,
         "shipping_address": decrypted_shipping_address,
         "shipping_notes": order.shipping_notes,
+        "shipping_envelope": shipping_envelope
+            .map(|envelope| serde_json::to_string(&envelope).unwrap_or_default()),
     });
 
     ctx.insert("order", &order_data);
@@ -1168,6 +1235,23 @@ pub async fn show_order(
     }
 }
 
+/// Line items formatted for the order and escrow templates
+fn order_items_for_template(items: &[OrderItem]) -> Vec<serde_json::Value> {
+    items
+        .iter()
+        .map(|item| {
+            serde_json::json!({
+                "listing_id": item.listing_id,
+                "title": item.title,
+                "quantity": item.quantity,
+                "unit_price_xmr": format!("{:.12}", item.unit_price_as_xmr()),
+                "total_price_xmr": format!("{:.12}", item.total_as_xmr()),
+                "unit_price_fiat": item.fiat_price().map(|price| price.display()),
+            })
+        })
+        .collect()
+}
+
 /// GET /escrow/{id} - Escrow detail page
 pub async fn show_escrow(
     tera: web::Data<Tera>,
@@ -1236,6 +1320,19 @@ pub async fn show_escrow(
         }
     };
 
+    // Line items covered by this escrow
+    let mut conn_items = match pool.get() {
+        Ok(c) => c,
+        Err(_) => return HttpResponse::InternalServerError().body("Database error"),
+    };
+    let items_order_id = escrow.order_id.clone();
+    let items = web::block(move || OrderItem::find_by_order(&mut conn_items, &items_order_id))
+        .await
+        .ok()
+        .and_then(|r| r.ok())
+        .unwrap_or_default();
+    ctx.insert("items", &order_items_for_template(&items));
+
     ctx.insert("escrow", &escrow);
 
     match tera.render("escrow/show.html", &ctx) {
@@ -1260,11 +1357,13 @@ pub async fn show_escrow(
 pub async fn vendor_profile(
     tera: web::Data<Tera>,
     pool: web::Data<DbPool>,
+    policy: web::Data<ReviewPolicy>,
     session: Session,
     vendor_id: web::Path<String>,
 ) -> impl Responder {
-    use crate::db::reputation::db_get_vendor_reviews;
-    use reputation_common::types::VendorReputation;
+    use crate::db::reputation::{
+        db_get_imported_reputations, db_get_reputation_key, db_get_vendor_reputation,
+    };
     use uuid::Uuid;
 
     let mut ctx = Context::new();
@@ -1284,6 +1383,7 @@ pub async fn vendor_profile(
     } else {
         ctx.insert("logged_in", &false);
     }
+    ctx.insert("csrf_token", &get_csrf_token(&session));
 
     // Validate vendor_id format
     let vendor_uuid = match Uuid::parse_str(&vendor_id.into_inner()) {
@@ -1294,8 +1394,8 @@ pub async fn vendor_profile(
         }
     };
 
-    // Fetch vendor reviews from database
-    let reviews = match db_get_vendor_reviews(&pool, vendor_uuid).await {
+    // Fetch vendor reviews and stats from database
+    let reputation = match db_get_vendor_reputation(&pool, vendor_uuid, &policy.scoring).await {
         Ok(r) => r,
         Err(e) => {
             error!("Database error fetching reviews: {}", e);
@@ -1303,17 +1403,13 @@ pub async fn vendor_profile(
         }
     };
 
-    // Calculate stats from reviews using reputation-crypto
-    use reputation_crypto::reputation::calculate_stats;
-    let stats = calculate_stats(&reviews);
-
-    // Build VendorReputation structure
-    let reputation = VendorReputation {
-        format_version: "1.0".to_string(),
-        vendor_pubkey: vendor_uuid.to_string(),
-        generated_at: chrono::Utc::now(),
-        reviews,
-        stats,
+    // Reputation imported from other marketplaces, shown apart from native reviews
+    let imported_reputations = match db_get_imported_reputations(&pool, vendor_uuid).await {
+        Ok(r) => r,
+        Err(e) => {
+            error!("Database error fetching imported reputation: {}", e);
+            return HttpResponse::InternalServerError().body("Database error");
+        }
     };
 
     // Serialize reputation to JSON for WASM verification
@@ -1328,12 +1424,22 @@ pub async fn vendor_profile(
     ctx.insert("vendor_id", &vendor_uuid.to_string());
     ctx.insert("reputation", &reputation);
     ctx.insert("reputation_json", &reputation_json);
-
-    // Check if current user is the vendor (for IPFS export button)
-    if let Ok(Some(user_id)) = session.get::<String>("user_id") {
-        ctx.insert("is_vendor", &(user_id == vendor_uuid.to_string()));
-    } else {
-        ctx.insert("is_vendor", &false);
+    ctx.insert("imported_reputations", &imported_reputations);
+
+    // Check if current user is the vendor (for IPFS export and import)
+    let is_vendor = matches!(
+        session.get::<String>("user_id"),
+        Ok(Some(user_id)) if user_id == vendor_uuid.to_string()
+    );
+    ctx.insert("is_vendor", &is_vendor);
+    if is_vendor {
+        match db_get_reputation_key(&pool, &vendor_uuid.to_string()).await {
+            Ok(key) => ctx.insert("reputation_key", &key),
+            Err(e) => {
+                error!("Database error fetching reputation key: {}", e);
+                return HttpResponse::InternalServerError().body("Database error");
+            }
+        }
     }
 
     match tera.render("reputation/vendor_profile.html", &ctx) {
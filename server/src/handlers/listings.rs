@@ -13,11 +13,14 @@ use uuid::Uuid;
 use validator::Validate;
 use infer;
 
+use crate::config::BondPolicy;
 use crate::db::DbPool;
 use crate::ipfs::client::IpfsClient;
-use crate::models::listing::{Listing, ListingStatus, NewListing, UpdateListing};
+use crate::models::listing::{FiatPrice, Listing, ListingStatus, NewListing, UpdateListing};
 use crate::models::order::Order;
+use crate::models::vendor_bond::{VendorBond, VendorBondStatus};
 use crate::schema::{listings, orders};
+use crate::services::price_conversion::PriceOracle;
 use chrono::{Datelike, Timelike, Utc};
 
 /// Request body for creating a new listing
@@ -33,8 +36,16 @@ pub struct CreateListingRequest {
     ))]
     pub description: String,
 
+    /// Required unless the listing is priced in fiat
     #[validate(range(min = 1, message = "Price must be positive"))]
-    pub price_xmr: i64,
+    pub price_xmr: Option<i64>,
+
+    /// Currency of a fiat price (lowercase ISO 4217), with `price_fiat`
+    pub price_currency: Option<String>,
+
+    /// Fiat price in minor units (cents); `price_xmr` then follows the rate
+    #[validate(range(min = 1, message = "Fiat price must be positive"))]
+    pub price_fiat: Option<i64>,
 
     #[validate(range(min = 0, message = "Stock cannot be negative"))]
     pub stock: i32,
@@ -52,9 +63,15 @@ pub struct UpdateListingRequest {
     #[validate(length(min = 10, max = 5000))]
     pub description: Option<String>,
 
+    /// A new XMR price without `price_fiat` also drops the fiat price
     #[validate(range(min = 1))]
     pub price_xmr: Option<i64>,
 
+    pub price_currency: Option<String>,
+
+    #[validate(range(min = 1))]
+    pub price_fiat: Option<i64>,
+
     #[validate(range(min = 0))]
     pub stock: Option<i32>,
 
@@ -79,6 +96,10 @@ pub struct ListingResponse {
     pub updated_at: String,
     pub images: Vec<String>, // IPFS CIDs for images
     pub category: String,
+    /// Fiat price the listing is pegged to (minor units), if any
+    pub price_fiat: Option<i64>,
+    pub price_currency: Option<String>,
+    pub price_fiat_display: Option<String>,
 }
 
 impl From<Listing> for ListingResponse {
@@ -102,6 +123,9 @@ impl From<Listing> for ListingResponse {
             updated_at: listing.updated_at.to_string(),
             images,
             category: listing.category.clone(),
+            price_fiat_display: listing.fiat_price().map(|price| price.display()),
+            price_fiat: listing.price_fiat,
+            price_currency: listing.price_currency.clone(),
         }
     }
 }
@@ -122,6 +146,75 @@ fn get_user_id_from_session(session: &Session) -> Result<String, HttpResponse> {
         })
 }
 
+/// Error prefix when a vendor is at the listing limit of its bond tier
+const LISTING_LIMIT_ERROR: &str = "Listing limit reached";
+
+/// Check that the vendor may have one more active listing under the bond policy
+fn check_listing_limit(
+    conn: &mut SqliteConnection,
+    vendor_id: &str,
+    policy: &BondPolicy,
+) -> anyhow::Result<()> {
+    let active_listings = listings::table
+        .filter(listings::vendor_id.eq(vendor_id))
+        .filter(listings::status.eq(ListingStatus::Active.as_str()))
+        .count()
+        .get_result::<i64>(conn)
+        .context("Failed to count active listings")?;
+    let bond_atomic = VendorBond::effective_amount_for_vendor(conn, vendor_id)?;
+
+    if !policy.allows_new_listing(bond_atomic, active_listings) {
+        return Err(anyhow::anyhow!(
+            "{}: {} active listings allowed with your current bond. Post a larger vendor bond to list more.",
+            LISTING_LIMIT_ERROR,
+            policy.listing_limit(bond_atomic).unwrap_or_default()
+        ));
+    }
+    Ok(())
+}
+
+/// Price of a listing from a create/update request
+///
+/// A fiat price is converted at the current rate, which must not be stale.
+/// Returns None when the request sets no price.
+async fn resolve_price(
+    oracle: &PriceOracle,
+    price_xmr: Option<i64>,
+    price_currency: Option<&str>,
+    price_fiat: Option<i64>,
+) -> Result<Option<(i64, Option<FiatPrice>)>, HttpResponse> {
+    let (currency, amount) = match (price_currency, price_fiat) {
+        (None, None) => return Ok(price_xmr.map(|price_xmr| (price_xmr, None))),
+        (Some(currency), Some(amount)) if amount > 0 => (currency.to_lowercase(), amount),
+        _ => {
+            return Err(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "A fiat price needs both price_currency and a positive price_fiat"
+            })))
+        }
+    };
+    if !oracle.currencies().contains(&currency) {
+        return Err(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Currency {} is not priced by this marketplace", currency)
+        })));
+    }
+
+    let rate = match oracle.rate(&currency).await {
+        Ok(rate) if !rate.stale => rate,
+        _ => {
+            return Err(HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": format!("No current XMR/{} rate, try again later", currency.to_uppercase())
+            })))
+        }
+    };
+    let fiat_price = FiatPrice { currency, amount };
+    match fiat_price.to_atomic(rate.rate) {
+        Ok(price_xmr) => Ok(Some((price_xmr, Some(fiat_price)))),
+        Err(e) => Err(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Invalid fiat price: {}", e)
+        }))),
+    }
+}
+
 /// Upload images to IPFS and return CIDs
 ///
 /// # Arguments
@@ -211,12 +304,29 @@ async fn upload_images_to_ipfs(
 
 
 
+/// Insert a listing and peg it to its fiat price, if any
+fn create_with_price(
+    conn: &mut SqliteConnection,
+    new_listing: NewListing,
+    fiat_price: Option<FiatPrice>,
+) -> anyhow::Result<Listing> {
+    conn.transaction(|conn| {
+        let listing = Listing::create(conn, new_listing)?;
+        match fiat_price {
+            Some(fiat_price) => Listing::set_fiat_price(conn, listing.id, Some(fiat_price)),
+            None => Ok(listing),
+        }
+    })
+}
+
 /// POST /api/listings - Create a new listing (JSON)
 ///
 /// Requires authentication and vendor role.
 #[post("/listings")]
 pub async fn create_listing(
     pool: web::Data<DbPool>,
+    bond_policy: web::Data<BondPolicy>,
+    oracle: web::Data<PriceOracle>,
     session: Session,
     req: web::Json<CreateListingRequest>,
 ) -> impl Responder {
@@ -233,13 +343,30 @@ pub async fn create_listing(
         Err(response) => return response,
     };
 
+    let (price_xmr, fiat_price) = match resolve_price(
+        &oracle,
+        req.price_xmr,
+        req.price_currency.as_deref(),
+        req.price_fiat,
+    )
+    .await
+    {
+        Ok(Some(price)) => price,
+        Ok(None) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Validation error: price_xmr or a fiat price is required"
+            }))
+        }
+        Err(response) => return response,
+    };
+
     // Create listing
     let new_listing = NewListing {
         id: Uuid::new_v4().to_string(),
         vendor_id: user_id,
         title: req.title.clone(),
         description: req.description.clone(),
-        price_xmr: req.price_xmr,
+        price_xmr,
         stock: req.stock,
         status: ListingStatus::Active.as_str().to_string(),
         images_ipfs_cids: Some("[]".to_string()), // Default to empty JSON array
@@ -255,7 +382,11 @@ pub async fn create_listing(
         }
     };
 
-    let listing_result = web::block(move || Listing::create(&mut conn, new_listing)).await;
+    let listing_result = web::block(move || {
+        check_listing_limit(&mut conn, &new_listing.vendor_id, &bond_policy)?;
+        create_with_price(&mut conn, new_listing, fiat_price)
+    })
+    .await;
 
     match listing_result {
         Ok(Ok(listing)) => {
@@ -264,6 +395,11 @@ pub async fn create_listing(
                 .insert_header(("HX-Redirect", format!("/listings/{}", listing.id)))
                 .json(ListingResponse::from(listing))
         }
+        Ok(Err(e)) if e.to_string().starts_with(LISTING_LIMIT_ERROR) => {
+            HttpResponse::Forbidden().json(serde_json::json!({
+                "error": e.to_string()
+            }))
+        }
         Ok(Err(e)) => HttpResponse::InternalServerError().json(serde_json::json!({
             "error": format!("Failed to create listing: {}", e)
         })),
@@ -279,6 +415,8 @@ pub async fn create_listing(
 #[post("/listings/with-images")]
 pub async fn create_listing_with_images(
     pool: web::Data<DbPool>,
+    bond_policy: web::Data<BondPolicy>,
+    oracle: web::Data<PriceOracle>,
     session: Session,
     mut multipart: Multipart,
     ipfs_client: web::Data<IpfsClient>,
@@ -289,10 +427,39 @@ pub async fn create_listing_with_images(
         Err(response) => return response,
     };
 
+    // Check the bond listing limit before uploading anything to IPFS
+    let limit_pool = pool.clone();
+    let limit_vendor_id = user_id.clone();
+    let limit_result = web::block(move || {
+        let mut conn = limit_pool.get().context("Database connection failed")?;
+        check_listing_limit(&mut conn, &limit_vendor_id, &bond_policy)
+    })
+    .await;
+    match limit_result {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) if e.to_string().starts_with(LISTING_LIMIT_ERROR) => {
+            return HttpResponse::Forbidden().json(serde_json::json!({
+                "error": e.to_string()
+            }));
+        }
+        Ok(Err(e)) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to check listing limit: {}", e)
+            }));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Async task failed: {}", e)
+            }));
+        }
+    }
+
     // Parse multipart form data
     let mut title = String::new();
     let mut description = String::new();
     let mut price_xmr: i64 = 0;
+    let mut price_currency: Option<String> = None;
+    let mut price_fiat: Option<i64> = None;
     let mut stock: i32 = 0;
     let mut category = String::from("other"); // Default category
     let mut image_files = Vec::new();
@@ -343,6 +510,10 @@ pub async fn create_listing_with_images(
                         "title" => title = value,
                         "description" => description = value,
                         "price_xmr" => price_xmr = value.parse().unwrap_or(0),
+                        "price_currency" if !value.is_empty() => price_currency = Some(value),
+                        "price_fiat" if !value.is_empty() => {
+                            price_fiat = Some(value.parse().unwrap_or(0))
+                        }
                         "stock" => stock = value.parse().unwrap_or(0),
                         "category" => category = value,
                         _ => {}
@@ -364,11 +535,22 @@ pub async fn create_listing_with_images(
             "error": "Description must be between 10-5000 characters"
         }));
     }
-    if price_xmr < 1 {
-        return HttpResponse::BadRequest().json(serde_json::json!({
-            "error": "Price must be positive"
-        }));
-    }
+    let (price_xmr, fiat_price) = match resolve_price(
+        &oracle,
+        Some(price_xmr).filter(|price| *price > 0),
+        price_currency.as_deref(),
+        price_fiat,
+    )
+    .await
+    {
+        Ok(Some(price)) => price,
+        Ok(None) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Price must be positive"
+            }))
+        }
+        Err(response) => return response,
+    };
 
     // Upload images to IPFS
     let mut image_cids = Vec::new();
@@ -421,7 +603,8 @@ pub async fn create_listing_with_images(
         }
     };
 
-    let listing_result = web::block(move || Listing::create(&mut conn, new_listing)).await;
+    let listing_result =
+        web::block(move || create_with_price(&mut conn, new_listing, fiat_price)).await;
 
     match listing_result {
         Ok(Ok(listing)) => {
@@ -806,6 +989,8 @@ pub async fn search_listings(
 #[put("/listings/{id}")]
 pub async fn update_listing(
     pool: web::Data<DbPool>,
+    bond_policy: web::Data<BondPolicy>,
+    oracle: web::Data<PriceOracle>,
     session: Session,
     id: web::Path<String>,
     req: web::Json<UpdateListingRequest>,
@@ -825,11 +1010,23 @@ pub async fn update_listing(
 
     let listing_id = id.into_inner();
 
+    let price = match resolve_price(
+        &oracle,
+        req.price_xmr,
+        req.price_currency.as_deref(),
+        req.price_fiat,
+    )
+    .await
+    {
+        Ok(price) => price,
+        Err(response) => return response,
+    };
+
     // Build update data from request
     let update_data = UpdateListing {
         title: req.title.clone(),
         description: req.description.clone(),
-        price_xmr: req.price_xmr,
+        price_xmr: price.as_ref().map(|(price_xmr, _)| *price_xmr),
         stock: req.stock,
         status: req.status.clone(),
         category: req.category.clone(),
@@ -845,8 +1042,21 @@ pub async fn update_listing(
             return Err(anyhow::anyhow!("Permission denied"));
         }
 
-        // 3. Perform the update
-            Listing::update(&mut conn, listing_id, update_data)
+        // 3. Reactivating a listing counts against the bond listing limit
+        let reactivating = update_data.status.as_deref() == Some(ListingStatus::Active.as_str())
+            && existing_listing.status != ListingStatus::Active.as_str();
+        if reactivating {
+            check_listing_limit(&mut conn, &user_id, &bond_policy)?;
+        }
+
+        // 4. Perform the update (a new price also sets or drops the fiat peg)
+            conn.transaction(|conn| {
+                let listing = Listing::update(conn, listing_id.clone(), update_data)?;
+                match price {
+                    Some((_, fiat_price)) => Listing::set_fiat_price(conn, listing_id, fiat_price),
+                    None => Ok(listing),
+                }
+            })
         })
         .await;
 
@@ -859,6 +1069,10 @@ pub async fn update_listing(
                 HttpResponse::Forbidden().json(serde_json::json!({
                     "error": "You can only update your own listings"
                 }))
+            } else if e.to_string().starts_with(LISTING_LIMIT_ERROR) {
+                HttpResponse::Forbidden().json(serde_json::json!({
+                    "error": e.to_string()
+                }))
             } else {
                 HttpResponse::InternalServerError().json(serde_json::json!({
                     "error": format!("Failed to update listing: {}", e)
@@ -932,6 +1146,50 @@ pub struct VendorDashboardStats {
     pub revenue_this_month_xmr: String,
     pub total_sales: i64,
     pub sales_this_month: i64,
+    pub bond: VendorBondStats,
+}
+
+/// Vendor bond part of the dashboard stats
+#[derive(Debug, Serialize)]
+pub struct VendorBondStats {
+    /// Status of the latest bond ("none" if the vendor never posted one)
+    pub status: String,
+    pub bond_id: Option<String>,
+    pub amount_xmr: String,
+    pub remaining_xmr: String,
+    pub slashed_xmr: String,
+    /// Deposit address while the bond awaits funding
+    pub multisig_address: Option<String>,
+    /// Active listing limit (None = unlimited)
+    pub listing_limit: Option<u32>,
+    /// Bond needed for the next tier, if any
+    pub next_tier_bond_xmr: Option<String>,
+    pub next_tier_listing_limit: Option<u32>,
+}
+
+impl VendorBondStats {
+    fn new(bond: Option<&VendorBond>, policy: &BondPolicy) -> Self {
+        let xmr = |atomic: i64| format!("{:.12}", atomic as f64 / 1_000_000_000_000.0);
+        let effective = bond
+            .filter(|b| b.status == VendorBondStatus::Active.as_str())
+            .map(|b| b.remaining())
+            .unwrap_or(0);
+        let next_tier = policy.next_tier(effective);
+
+        Self {
+            status: bond.map_or_else(|| "none".to_string(), |b| b.status.clone()),
+            bond_id: bond.map(|b| b.id.clone()),
+            amount_xmr: xmr(bond.map_or(0, |b| b.amount)),
+            remaining_xmr: xmr(bond.map_or(0, |b| b.remaining())),
+            slashed_xmr: xmr(bond.map_or(0, |b| b.slashed_amount)),
+            multisig_address: bond
+                .filter(|b| b.status == VendorBondStatus::AwaitingFunding.as_str())
+                .and_then(|b| b.multisig_address.clone()),
+            listing_limit: policy.listing_limit(effective),
+            next_tier_bond_xmr: next_tier.map(|tier| xmr(tier.min_bond_atomic)),
+            next_tier_listing_limit: next_tier.and_then(|tier| tier.max_active_listings),
+        }
+    }
 }
 
 /// GET /api/vendor/dashboard/stats - Get vendor dashboard statistics
@@ -940,6 +1198,7 @@ pub struct VendorDashboardStats {
 #[get("/vendor/dashboard/stats")]
 pub async fn get_vendor_dashboard_stats(
     pool: web::Data<DbPool>,
+    bond_policy: web::Data<BondPolicy>,
     session: Session,
 ) -> impl Responder {
     // Get authenticated user
@@ -1008,6 +1267,10 @@ pub async fn get_vendor_dashboard_stats(
 
         let sales_this_month = this_month_orders.len() as i64;
 
+        // Vendor bond and the listing limit it unlocks
+        let bond = VendorBond::find_latest_by_vendor(&mut conn, &user_id)?;
+        let bond = VendorBondStats::new(bond.as_ref(), &bond_policy);
+
         Ok(VendorDashboardStats {
             active_listings,
             pending_orders,
@@ -1015,6 +1278,7 @@ pub async fn get_vendor_dashboard_stats(
             revenue_this_month_xmr,
             total_sales,
             sales_this_month,
+            bond,
         })
     })
     .await;
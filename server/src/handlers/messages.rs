@@ -1,23 +1,45 @@
 //! Order message API handlers
 //!
 //! REST API endpoints for vendor-buyer communication within orders.
+//!
+//! Message bodies are end-to-end encrypted by the clients: the server only
+//! checks the envelope format and that it is addressed to the order parties.
 
 use actix_session::Session;
 use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder};
+use diesel::SqliteConnection;
 use serde::{Deserialize, Serialize};
-use validator::Validate;
 
+use crate::crypto::message_envelope::MessageEnvelope;
 use crate::db::DbPool;
 use crate::middleware::csrf::validate_csrf_token;
+use crate::models::encryption_key::UserEncryptionKey;
+use crate::models::escrow::Escrow;
 use crate::models::message::{NewOrderMessage, OrderMessage, OrderMessageWithSender};
 use crate::models::order::Order;
 use crate::models::user::User;
 
 /// Request body for sending a message
-#[derive(Debug, Deserialize, Validate)]
+#[derive(Debug, Deserialize)]
 pub struct SendMessageRequest {
-    #[validate(length(min = 1, max = 2000, message = "Message must be between 1 and 2000 characters"))]
-    pub message: String,
+    /// Body encrypted client-side to the counterparty (and optionally the arbiter)
+    pub envelope: MessageEnvelope,
+}
+
+/// Public key of one order party (None until the user registers one)
+#[derive(Debug, Serialize)]
+pub struct PartyKey {
+    pub user_id: String,
+    pub public_key: Option<String>,
+}
+
+/// Keys needed to encrypt a message for an order
+#[derive(Debug, Serialize)]
+pub struct MessageKeysResponse {
+    pub buyer: PartyKey,
+    pub vendor: PartyKey,
+    /// Present once an escrow (and so an arbiter) is attached to the order
+    pub arbiter: Option<PartyKey>,
 }
 
 /// Response for message list
@@ -43,10 +65,20 @@ fn get_user_id_from_session(session: &Session) -> Result<String, HttpResponse> {
         })
 }
 
+/// Arbiter of the escrow attached to `order`, if any
+fn order_arbiter(conn: &mut SqliteConnection, order: &Order) -> Option<String> {
+    let escrow_id = order.escrow_id.clone()?;
+    Escrow::find_by_id(conn, escrow_id)
+        .ok()
+        .map(|escrow| escrow.arbiter_id)
+}
+
 /// GET /api/orders/{order_id}/messages - Get all messages for an order
 ///
-/// Returns all messages for the specified order.
-/// Only buyer or vendor can access messages for their own orders.
+/// Returns the encrypted envelopes of the order's messages.
+/// Buyer and vendor see every message, including the read-only plaintext of
+/// messages sent before end-to-end encryption; the escrow arbiter only sees
+/// the messages that were encrypted to them.
 ///
 /// Requires authentication.
 #[get("/orders/{order_id}/messages")]
@@ -84,7 +116,9 @@ pub async fn get_messages(
         }
     };
 
-    if order.buyer_id != user_id && order.vendor_id != user_id {
+    let is_party = order.buyer_id == user_id || order.vendor_id == user_id;
+    let is_arbiter = !is_party && order_arbiter(&mut conn, &order).as_deref() == Some(&user_id);
+    if !is_party && !is_arbiter {
         return HttpResponse::Forbidden().json(serde_json::json!({
             "error": "You don't have permission to view these messages"
         }));
@@ -105,6 +139,22 @@ pub async fn get_messages(
     let messages_with_sender: Vec<OrderMessageWithSender> = messages
         .into_iter()
         .filter_map(|msg| {
+            let envelope = match msg.envelope() {
+                Ok(envelope) => envelope,
+                Err(e) => {
+                    tracing::error!("Skipping message: {:?}", e);
+                    return None;
+                }
+            };
+            // Legacy plaintext messages were only ever shared between buyer and vendor
+            let visible = match &envelope {
+                Some(envelope) => !is_arbiter || envelope.is_addressed_to(&user_id),
+                None => !is_arbiter,
+            };
+            if !visible {
+                return None;
+            }
+
             // Get sender username
             let sender = User::find_by_id(&mut conn, msg.sender_id.clone()).ok()?;
 
@@ -113,7 +163,8 @@ pub async fn get_messages(
                 order_id: msg.order_id.clone(),
                 sender_id: msg.sender_id.clone(),
                 sender_username: sender.username.clone(),
-                message: msg.message.clone(),
+                envelope,
+                legacy_message: msg.message.clone(),
                 created_at: msg.created_at,
                 is_current_user: msg.sender_id == user_id,
             })
@@ -128,8 +179,10 @@ pub async fn get_messages(
 
 /// POST /api/orders/{order_id}/messages - Send a new message
 ///
-/// Sends a new message in the order chat.
-/// Only buyer or vendor can send messages for their own orders.
+/// Sends a new encrypted message in the order chat.
+/// Only buyer or vendor can send messages for their own orders. The envelope
+/// must be well-formed, addressed to the counterparty and to no one outside
+/// the order (buyer, vendor, escrow arbiter).
 ///
 /// Requires authentication and CSRF protection.
 #[post("/orders/{order_id}/messages")]
@@ -155,10 +208,10 @@ pub async fn send_message(
         }));
     }
 
-    // Validate input
-    if let Err(e) = req.validate() {
+    // Validate envelope format and size (contents are never inspected)
+    if let Err(e) = req.envelope.validate() {
         return HttpResponse::BadRequest().json(serde_json::json!({
-            "error": format!("Validation error: {}", e)
+            "error": format!("Invalid message envelope: {:#}", e)
         }));
     }
 
@@ -195,12 +248,37 @@ pub async fn send_message(
         }));
     }
 
+    // Readers: the counterparty must be one, nobody outside the order may be
+    let counterparty = if order.buyer_id == user_id {
+        &order.vendor_id
+    } else {
+        &order.buyer_id
+    };
+    if !req.envelope.is_addressed_to(counterparty) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Message must be encrypted to the other party of the order"
+        }));
+    }
+    let arbiter_id = order_arbiter(&mut conn, &order);
+    if let Some(outsider) = req.envelope.recipient_ids().find(|id| {
+        *id != order.buyer_id && *id != order.vendor_id && Some(*id) != arbiter_id.as_deref()
+    }) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Recipient {} is not a party to this order", outsider)
+        }));
+    }
+
     // Create new message
-    let new_message = NewOrderMessage::new(
-        order_id.clone(),
-        user_id.clone(),
-        req.message.clone(),
-    );
+    let new_message = match NewOrderMessage::new(order_id.clone(), user_id.clone(), &req.envelope)
+    {
+        Ok(msg) => msg,
+        Err(e) => {
+            tracing::error!("Failed to build message: {:?}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to send message"
+            }));
+        }
+    };
 
     let message = match OrderMessage::create(new_message, &mut conn) {
         Ok(msg) => msg,
@@ -228,10 +306,85 @@ pub async fn send_message(
         order_id: message.order_id.clone(),
         sender_id: message.sender_id.clone(),
         sender_username: sender.username.clone(),
-        message: message.message.clone(),
+        envelope: Some(req.into_inner().envelope),
+        legacy_message: None,
         created_at: message.created_at,
         is_current_user: true,
     };
 
     HttpResponse::Ok().json(message_with_sender)
 }
+
+/// GET /api/orders/{order_id}/message-keys - Public keys of the order parties
+///
+/// Used by the client to encrypt a message before sending it.
+/// Only buyer, vendor or the escrow arbiter can fetch the keys.
+///
+/// Requires authentication.
+#[get("/orders/{order_id}/message-keys")]
+pub async fn get_message_keys(
+    pool: web::Data<DbPool>,
+    session: Session,
+    path: web::Path<String>,
+) -> impl Responder {
+    let order_id = path.into_inner();
+
+    let user_id = match get_user_id_from_session(&session) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(e) => {
+            tracing::error!("Database connection error: {:?}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database connection failed"
+            }));
+        }
+    };
+
+    let order = match Order::find_by_id(&mut conn, order_id) {
+        Ok(order) => order,
+        Err(_) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Order not found"
+            }));
+        }
+    };
+
+    let arbiter_id = order_arbiter(&mut conn, &order);
+    if order.buyer_id != user_id
+        && order.vendor_id != user_id
+        && arbiter_id.as_deref() != Some(user_id.as_str())
+    {
+        return HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "You don't have permission to view these keys"
+        }));
+    }
+
+    let mut party_ids = vec![order.buyer_id.as_str(), order.vendor_id.as_str()];
+    party_ids.extend(arbiter_id.as_deref());
+    let keys = match UserEncryptionKey::find_by_user_ids(&mut conn, &party_ids) {
+        Ok(keys) => keys,
+        Err(e) => {
+            tracing::error!("Failed to load encryption keys: {:?}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to load encryption keys"
+            }));
+        }
+    };
+    let party_key = |party_id: &str| PartyKey {
+        user_id: party_id.to_string(),
+        public_key: keys
+            .iter()
+            .find(|key| key.user_id == party_id)
+            .map(|key| key.public_key.clone()),
+    };
+
+    HttpResponse::Ok().json(MessageKeysResponse {
+        buyer: party_key(&order.buyer_id),
+        vendor: party_key(&order.vendor_id),
+        arbiter: arbiter_id.as_deref().map(party_key),
+    })
+}
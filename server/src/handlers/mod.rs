@@ -1,6 +1,9 @@
 pub mod airgap_dispute;
+pub mod arbiter;
+pub mod audit;
 pub mod auth;
 pub mod cart;
+pub mod dispute_evidence;
 pub mod escrow;
 pub mod frontend;
 pub mod listings;
@@ -9,6 +12,8 @@ pub mod monitoring;
 pub mod multisig_challenge;
 pub mod noncustodial;
 pub mod orders;
+pub mod prices;
 pub mod reputation;
 pub mod reputation_ipfs;
 pub mod user;
+pub mod vendor_bond;
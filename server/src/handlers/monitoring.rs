@@ -7,7 +7,8 @@ use serde::Serialize;
 use uuid::Uuid;
 
 use crate::db::DbPool;
-use crate::models::escrow::Escrow;
+use crate::models::escrow::{terminal_statuses, Escrow, EscrowStatus};
+use crate::models::escrow_event::{replay, EscrowEventRecord, EscrowReplay};
 
 /// Response structure for escrow health check
 #[derive(Debug, Serialize)]
@@ -64,10 +65,7 @@ pub async fn get_escrow_health(pool: web::Data<DbPool>) -> impl Responder {
         use diesel::prelude::*;
 
         escrows
-            .filter(status.ne("completed"))
-            .filter(status.ne("refunded"))
-            .filter(status.ne("cancelled"))
-            .filter(status.ne("expired"))
+            .filter(status.ne_all(terminal_statuses()))
             .load::<Escrow>(&mut conn)
     })
     .await
@@ -118,11 +116,14 @@ pub async fn get_escrow_health(pool: web::Data<DbPool>) -> impl Responder {
     const WARNING_THRESHOLD_SECS: i64 = 3600; // 1 hour
     for escrow in &active_escrows {
         if escrow.is_expiring_soon(WARNING_THRESHOLD_SECS) {
-            let action_required = match escrow.status.as_str() {
-                "created" => "Complete multisig setup",
-                "funded" => "Buyer: deposit funds to escrow address",
-                "releasing" | "refunding" => "Wait for blockchain confirmation",
-                "disputed" => "Arbiter: resolve dispute",
+            let action_required = match escrow.get_status() {
+                Ok(EscrowStatus::Created) => "Complete multisig setup",
+                Ok(EscrowStatus::Funded) => "Buyer: deposit funds to escrow address",
+                Ok(EscrowStatus::Underfunded) => "Buyer: top up the partial deposit to the escrow address",
+                Ok(EscrowStatus::Releasing | EscrowStatus::Refunding) => {
+                    "Wait for blockchain confirmation"
+                }
+                Ok(EscrowStatus::Disputed) => "Arbiter: resolve dispute",
                 _ => "No action required",
             };
 
@@ -232,3 +233,111 @@ pub async fn get_escrow_status(
 
     HttpResponse::Ok().json(response)
 }
+
+/// One entry of an escrow timeline
+#[derive(Debug, Serialize)]
+pub struct EscrowTimelineEntry {
+    pub sequence: i32,
+    pub event_type: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub actor: String,
+    pub reason: Option<String>,
+    pub event: serde_json::Value,
+    pub created_at: String,
+}
+
+/// Response structure for an escrow timeline
+#[derive(Debug, Serialize)]
+pub struct EscrowTimelineResponse {
+    pub escrow_id: String,
+    /// Status stored in the escrows table
+    pub current_status: String,
+    /// State rebuilt from the event log (None if the log cannot be replayed)
+    pub replayed: Option<EscrowReplay>,
+    pub replay_error: Option<String>,
+    /// True when the replayed status matches the stored status
+    pub consistent: bool,
+    pub events: Vec<EscrowTimelineEntry>,
+}
+
+/// GET /admin/escrows/{id}/timeline - Event history of an escrow
+///
+/// Returns every recorded status change (who, when, why) and the state
+/// replayed from those events, flagged if it disagrees with `escrows.status`.
+#[get("/escrows/{id}/timeline")]
+pub async fn get_escrow_timeline(
+    pool: web::Data<DbPool>,
+    escrow_id: web::Path<String>,
+) -> impl Responder {
+    let escrow_id_str = escrow_id.into_inner();
+
+    // Validate UUID format
+    if Uuid::parse_str(&escrow_id_str).is_err() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Invalid escrow ID format (must be UUID)"
+        }));
+    }
+
+    // Get DB connection
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to get DB connection: {}", e)
+            }));
+        }
+    };
+
+    // Load escrow and its events
+    let (escrow, records) = match tokio::task::spawn_blocking(move || {
+        let escrow = Escrow::find_by_id(&mut conn, escrow_id_str)?;
+        let records = EscrowEventRecord::find_by_escrow(&mut conn, &escrow.id)?;
+        Ok::<_, anyhow::Error>((escrow, records))
+    })
+    .await
+    {
+        Ok(Ok(loaded)) => loaded,
+        Ok(Err(e)) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": format!("Escrow not found: {}", e)
+            }));
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Task join error: {}", e)
+            }));
+        }
+    };
+
+    let (replayed, replay_error) = match replay(&records) {
+        Ok(state) => (Some(state), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    let consistent = replayed
+        .as_ref()
+        .is_some_and(|state| state.status.as_str() == escrow.status);
+
+    let events = records
+        .into_iter()
+        .map(|record| EscrowTimelineEntry {
+            event: serde_json::from_str(&record.payload).unwrap_or(serde_json::Value::Null),
+            sequence: record.sequence,
+            event_type: record.event_type,
+            from_status: record.from_status,
+            to_status: record.to_status,
+            actor: record.actor,
+            reason: record.reason,
+            created_at: record.created_at.to_string(),
+        })
+        .collect();
+
+    HttpResponse::Ok().json(EscrowTimelineResponse {
+        escrow_id: escrow.id,
+        current_status: escrow.status,
+        replayed,
+        replay_error,
+        consistent,
+        events,
+    })
+}
@@ -435,8 +435,8 @@ pub async fn funds_received_notification(
     db: web::Data<crate::db::DbPool>,
 ) -> impl Responder {
     use tracing::{info, error};
-    use diesel::prelude::*;
-    use crate::schema::escrows;
+    use crate::models::escrow::EscrowStatus;
+    use crate::models::escrow_event::{EscrowActor, EscrowEventRecord};
 
     info!("💰 Funds received notification for escrow {}: {} atomic units",
         req.escrow_id, req.balance);
@@ -450,13 +450,14 @@ pub async fn funds_received_notification(
         let mut conn = db_clone.get()
             .map_err(|e| anyhow::anyhow!("Failed to get DB connection: {}", e))?;
 
-        diesel::update(escrows::table.filter(escrows::id.eq(&escrow_id)))
-            .set((
-                escrows::status.eq("funded"),
-                escrows::updated_at.eq(chrono::Utc::now().naive_utc()),
-            ))
-            .execute(&mut conn)
-            .map_err(|e| anyhow::anyhow!("Failed to update escrow status: {}", e))?;
+        EscrowEventRecord::record_transition(
+            &mut conn,
+            &escrow_id,
+            EscrowStatus::Funded,
+            &EscrowActor::System("noncustodial_client"),
+            Some(&format!("Client reported {} atomic units received", balance)),
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to update escrow status: {}", e))?;
 
         Ok::<(), anyhow::Error>(())
     })
@@ -5,21 +5,25 @@
 use actix::Addr;
 use actix_session::Session;
 use actix_web::{get, post, put, web, HttpRequest, HttpResponse, Responder};
-use diesel::Connection;
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::Validate;
 
-use base64::Engine;
-use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
-use crate::crypto::encryption::encrypt_field;
+use crate::crypto::message_envelope::MessageEnvelope;
 use crate::db::{DbPool, db_load_escrow};
 use crate::middleware::csrf::validate_csrf_token;
 use crate::models::cart::Cart;
-use crate::models::listing::Listing;
-use crate::models::order::{NewOrder, Order, OrderStatus};
+use crate::models::encryption_key::UserEncryptionKey;
+use crate::models::listing::{Listing, INSUFFICIENT_STOCK_ERROR};
+use crate::models::order::{
+    CheckoutLine, CheckoutQuote, Order, OrderStatus, LISTING_UNAVAILABLE_ERROR, OWN_LISTING_ERROR,
+    SHIPPING_RECIPIENT_ERROR,
+};
+use crate::models::order_item::OrderItem;
 use crate::models::user::User;
 use crate::services::escrow::EscrowOrchestrator;
+use crate::services::fiat_pricing::FiatPricing;
 use crate::websocket::{NotifyUser, WebSocketServer, WsEvent};
 
 /// Request body for creating a new order
@@ -31,11 +35,8 @@ pub struct CreateOrderRequest {
     #[validate(range(min = 1, message = "Quantity must be at least 1"))]
     pub quantity: i32,
 
-    #[validate(length(min = 10, max = 500, message = "Shipping address must be between 10 and 500 characters"))]
-    pub shipping_address: String,
-
-    #[validate(length(max = 200, message = "Shipping notes must be 200 characters or less"))]
-    pub shipping_notes: Option<String>,
+    /// Shipping address and notes encrypted to the vendor's public key
+    pub shipping_address: MessageEnvelope,
 }
 
 /// Request body for creating a new order from cart
@@ -43,11 +44,23 @@ pub struct CreateOrderRequest {
 pub struct CreateOrderFromCartRequest {
     pub checkout_mode: String, // "cart" | "listing"
 
-    #[validate(length(min = 10, max = 500, message = "Shipping address must be between 10 and 500 characters"))]
-    pub shipping_address: String,
+    /// Shipping address and notes encrypted to the public key of every
+    /// vendor in the checkout
+    pub shipping_address: MessageEnvelope,
+}
 
-    #[validate(length(max = 200, message = "Shipping notes must be 200 characters or less"))]
-    pub shipping_notes: Option<String>,
+/// Query for the vendor keys of the current checkout
+#[derive(Debug, Deserialize)]
+pub struct ShippingKeysQuery {
+    pub checkout_mode: String, // "cart" | "listing"
+}
+
+/// Public key a shipping address must be encrypted to
+#[derive(Debug, Serialize)]
+pub struct VendorShippingKey {
+    pub user_id: String,
+    /// None if the vendor has not registered a key yet
+    pub public_key: Option<String>,
 }
 
 /// Request body for updating order status
@@ -69,6 +82,51 @@ pub struct OrderResponse {
     pub total_display: String, // XMR with formatting
     pub created_at: String,
     pub updated_at: String,
+    /// Until when `total_xmr` holds (orders with fiat-priced items)
+    pub quote_expires_at: Option<String>,
+    /// Line items (only filled by endpoints that load them)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<OrderItemResponse>,
+}
+
+/// Order line item in API responses
+#[derive(Debug, Serialize)]
+pub struct OrderItemResponse {
+    pub listing_id: String,
+    pub title: String,
+    pub quantity: i32,
+    pub unit_price_xmr: i64,
+    pub total_xmr: i64,
+    /// Fiat unit price (minor units) and the rate it was converted at
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit_price_fiat: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_rate: Option<f64>,
+}
+
+impl From<OrderItem> for OrderItemResponse {
+    fn from(item: OrderItem) -> Self {
+        Self {
+            total_xmr: item.total_price(),
+            unit_price_fiat: item.unit_price_fiat,
+            price_currency: item.price_currency,
+            price_rate: item.price_rate,
+            listing_id: item.listing_id,
+            title: item.title,
+            quantity: item.quantity,
+            unit_price_xmr: item.unit_price_xmr,
+        }
+    }
+}
+
+impl OrderResponse {
+    /// Attach line items to the response
+    pub fn with_items(mut self, items: Vec<OrderItem>) -> Self {
+        self.items = items.into_iter().map(OrderItemResponse::from).collect();
+        self
+    }
 }
 
 impl From<Order> for OrderResponse {
@@ -84,6 +142,8 @@ impl From<Order> for OrderResponse {
             total_display: format!("{:.12} XMR", order.total_as_xmr()),
             created_at: order.created_at.to_string(),
             updated_at: order.updated_at.to_string(),
+            quote_expires_at: order.quote_expires_at.map(|at| at.to_string()),
+            items: Vec::new(),
         }
     }
 }
@@ -104,20 +164,36 @@ fn get_user_id_from_session(session: &Session) -> Result<String, HttpResponse> {
         })
 }
 
-/// POST /api/orders/create - Create a new order from cart
+/// Current rates for fiat-priced listings
+///
+/// Without rates, checkouts of XMR-priced listings still go through; the
+/// fiat-priced ones fail in [`Order::create_checkout_quoted`].
+async fn checkout_quote(pricing: &FiatPricing) -> CheckoutQuote {
+    pricing.checkout_quote().await.unwrap_or_else(|e| {
+        tracing::warn!("No exchange rates for checkout: {:#}", e);
+        CheckoutQuote {
+            issued_at: pricing.now(),
+            ..Default::default()
+        }
+    })
+}
+
+/// POST /api/orders/create - Create orders from cart
 ///
-/// Creates a new order from the buyer's cart with encrypted shipping address.
-/// This is the main checkout endpoint used by the frontend.
+/// Creates one order per vendor from the buyer's cart, each with its line
+/// items and the encrypted shipping address, so each vendor's items are
+/// covered by a single escrow. Stock is decreased for every item in the
+/// same DB transaction. This is the main checkout endpoint used by the frontend.
 ///
 /// Requires authentication as a buyer.
 #[post("/orders/create")]
 pub async fn create_order_from_cart(
     pool: web::Data<DbPool>,
+    pricing: web::Data<FiatPricing>,
     session: Session,
     http_req: HttpRequest,
     req: web::Json<CreateOrderFromCartRequest>,
     websocket: web::Data<Addr<WebSocketServer>>,
-    encryption_key: web::Data<Vec<u8>>,
 ) -> impl Responder {
     // SECURITY: Validate CSRF token
     let csrf_token = http_req
@@ -172,7 +248,7 @@ pub async fn create_order_from_cart(
     };
 
     // Handle different checkout modes
-    let (vendor_id, listing_id, total_xmr, _quantity) = if req.checkout_mode == "listing" {
+    let (lines, total_xmr) = if req.checkout_mode == "listing" {
         // Single listing mode (Buy Now)
         let listing_id_from_session = match session.get::<String>("checkout_listing_id") {
             Ok(Some(id)) => id,
@@ -217,7 +293,11 @@ pub async fn create_order_from_cart(
         // Clear listing_id from session after retrieving
         let _ = session.remove("checkout_listing_id");
 
-        (listing.vendor_id.clone(), listing.id.clone(), listing.price_xmr, 1)
+        let line = CheckoutLine {
+            listing_id: listing.id.clone(),
+            quantity: 1,
+        };
+        (vec![line], listing.price_xmr)
     } else {
         // Cart mode (existing logic)
         let cart = match session.get::<Cart>("cart") {
@@ -235,26 +315,24 @@ pub async fn create_order_from_cart(
             }));
         }
 
-        // For now, only support single-vendor carts
-        // TODO: Support multi-vendor carts with separate orders per vendor
-        let vendor_id = cart.items[0].vendor_id.clone();
-        if !cart.items.iter().all(|item| &item.vendor_id == &vendor_id) {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": "Multi-vendor carts not yet supported. Please checkout items from one vendor at a time."
-            }));
-        }
-
         // Prevent self-purchasing
-        if vendor_id == buyer_id {
+        if cart.items.iter().any(|item| item.vendor_id == buyer_id) {
             return HttpResponse::BadRequest().json(serde_json::json!({
                 "error": "Cannot purchase your own listings"
             }));
         }
 
-        let total_xmr = cart.total_price();
-        let listing_id = cart.items[0].listing_id.clone();
-
-        (vendor_id, listing_id, total_xmr, cart.total_quantity())
+        // Multi-vendor carts are split into one order (and one escrow) per vendor
+        let lines = cart
+            .items
+            .iter()
+            .map(|item| CheckoutLine {
+                listing_id: item.listing_id.clone(),
+                quantity: item.quantity,
+            })
+            .collect();
+
+        (lines, cart.total_price())
     };
 
     // SECURITY: Validate total is positive and reasonable
@@ -272,46 +350,40 @@ pub async fn create_order_from_cart(
         }));
     }
 
-    // SECURITY: Field-level AES-256-GCM encryption for shipping address
-    // Only the vendor can decrypt this address using the same encryption key
-    // Database is also encrypted at rest with SQLCipher (defense in depth)
-    let encrypted_address = match encrypt_field(&req.shipping_address, &encryption_key) {
-        Ok(encrypted_bytes) => BASE64_STANDARD.encode(&encrypted_bytes),
-        Err(e) => {
-            tracing::error!("Failed to encrypt shipping address: {}", e);
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to encrypt shipping address"
-            }));
-        }
-    };
-
-    // Create the order (conn already acquired at line 163)
-    let new_order = NewOrder {
-        id: Uuid::new_v4().to_string(),
-        buyer_id: buyer_id.clone(),
-        vendor_id: vendor_id.clone(),
-        listing_id: listing_id.clone(), // From tuple (cart or listing mode)
-        escrow_id: None, // Set when escrow is initialized
-        status: OrderStatus::Pending.as_str().to_string(),
-        total_xmr,
-        shipping_address: Some(encrypted_address),
-        shipping_notes: req.shipping_notes.clone(),
-    };
+    // SECURITY: The shipping address is encrypted in the browser to the
+    // vendors' public keys; the server only checks the envelope format and
+    // stores each vendor a copy only they can open
+    if let Err(e) = req.shipping_address.validate_with_max_recipients(lines.len()) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Invalid encrypted shipping address: {}", e)
+        }));
+    }
 
-    let order = match Order::create(&mut conn, new_order) {
-        Ok(order) => order,
+    // Create one order per vendor with its line items, decreasing stock for
+    // every item in the same transaction (conn already acquired above).
+    // Fiat-priced items are converted at the current rate, locked until the
+    // quote expires.
+    let quote = checkout_quote(&pricing).await;
+    let created = match Order::create_checkout_quoted(
+        &mut conn,
+        &buyer_id,
+        &lines,
+        Some(&req.shipping_address),
+        &quote,
+    ) {
+        Ok(created) => created,
         Err(e) => {
-            tracing::error!("Failed to create order: {}", e);
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to create order"
-            }))
+            tracing::error!("Failed to create orders from checkout: {:?}", e);
+            return checkout_error_response(&e);
         }
     };
 
-    tracing::info!(
-        "Order created successfully: id={}, buyer={}, vendor={}, total={} piconeros, mode={}",
-        order.id, order.buyer_id, order.vendor_id, order.total_xmr, req.checkout_mode
-    );
+    for (order, items) in &created {
+        tracing::info!(
+            "Order created successfully: id={}, buyer={}, vendor={}, items={}, total={} piconeros, mode={}",
+            order.id, order.buyer_id, order.vendor_id, items.len(), order.total_xmr, req.checkout_mode
+        );
+    }
 
     // Clear the cart in session ONLY if this was a cart checkout (not Buy Now)
     if req.checkout_mode == "cart" {
@@ -324,42 +396,47 @@ pub async fn create_order_from_cart(
         }
     }
 
-    // Send WebSocket notification to vendor
-    let vendor_uuid = match Uuid::parse_str(&order.vendor_id) {
-        Ok(uuid) => uuid,
-        Err(_) => {
-            tracing::error!("Invalid vendor UUID: {}", order.vendor_id);
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Internal error"
-            }));
-        }
-    };
+    // Send WebSocket notification to each vendor
+    for (order, _) in &created {
+        let (vendor_uuid, order_uuid) =
+            match (Uuid::parse_str(&order.vendor_id), Uuid::parse_str(&order.id)) {
+                (Ok(vendor_uuid), Ok(order_uuid)) => (vendor_uuid, order_uuid),
+                _ => {
+                    tracing::error!("Invalid vendor/order UUID: {} / {}", order.vendor_id, order.id);
+                    return HttpResponse::InternalServerError().json(serde_json::json!({
+                        "error": "Internal error"
+                    }));
+                }
+            };
 
-    let order_uuid = match Uuid::parse_str(&order.id) {
-        Ok(uuid) => uuid,
-        Err(_) => {
-            tracing::error!("Invalid order UUID: {}", order.id);
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Internal error"
-            }));
-        }
-    };
+        websocket.do_send(NotifyUser {
+            user_id: vendor_uuid,
+            event: WsEvent::OrderStatusChanged {
+                order_id: order_uuid,
+                new_status: "pending".to_string(),
+            },
+        });
 
-    websocket.do_send(NotifyUser {
-        user_id: vendor_uuid,
-        event: WsEvent::OrderStatusChanged {
-            order_id: order_uuid,
-            new_status: "pending".to_string(),
-        },
-    });
+        tracing::info!("Sent order notification to vendor {}", vendor_uuid);
+    }
 
-    tracing::info!("Sent order notification to vendor {}", vendor_uuid);
+    let orders: Vec<OrderResponse> = created
+        .into_iter()
+        .map(|(order, items)| OrderResponse::from(order).with_items(items))
+        .collect();
 
     HttpResponse::Created().json(serde_json::json!({
         "success": true,
-        "order_id": order.id,
-        "total_xmr": order.total_xmr,
-        "message": "Order created successfully"
+        // First order, for single-vendor checkout flows
+        "order_id": orders[0].id,
+        "order_ids": orders.iter().map(|o| o.id.clone()).collect::<Vec<_>>(),
+        "total_xmr": orders.iter().map(|o| o.total_xmr).sum::<i64>(),
+        "orders": orders,
+        "message": if orders.len() > 1 {
+            format!("{} orders created (one per vendor)", orders.len())
+        } else {
+            "Order created successfully".to_string()
+        }
     }))
 }
 
@@ -372,11 +449,11 @@ pub async fn create_order_from_cart(
 #[post("/orders")]
 pub async fn create_order(
     pool: web::Data<DbPool>,
+    pricing: web::Data<FiatPricing>,
     session: Session,
     http_req: HttpRequest,
     req: web::Json<CreateOrderRequest>,
     websocket: web::Data<Addr<WebSocketServer>>,
-    encryption_key: web::Data<Vec<u8>>,
 ) -> impl Responder {
     // SECURITY: Validate CSRF token
     let csrf_token = http_req
@@ -486,49 +563,36 @@ pub async fn create_order(
         }));
     }
 
-    // SECURITY: Field-level AES-256-GCM encryption for shipping address
-    let encrypted_address = match encrypt_field(&req.shipping_address, &encryption_key) {
-        Ok(encrypted_bytes) => BASE64_STANDARD.encode(&encrypted_bytes),
-        Err(e) => {
-            tracing::error!("Failed to encrypt shipping address: {}", e);
-            return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Failed to encrypt shipping address"
-            }));
-        }
-    };
+    // SECURITY: Shipping address must be encrypted to the vendor only
+    if let Err(e) = req.shipping_address.validate_with_max_recipients(1) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Invalid encrypted shipping address: {}", e)
+        }));
+    }
+    if !req.shipping_address.is_addressed_to(&listing.vendor_id) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Shipping address must be encrypted to the vendor's public key"
+        }));
+    }
 
     // SECURITY: Use database transaction to atomically create order and reserve stock
     // This prevents race conditions where multiple buyers could order the same stock
-    let order_result = conn.transaction::<Order, diesel::result::Error, _>(|conn| {
-        // First, decrease stock atomically
-        // This will fail if stock is insufficient (race condition protection)
-        Listing::decrease_stock(conn, req.listing_id.clone(), req.quantity)
-            .map_err(|e| {
-                tracing::error!("Failed to decrease stock: {}", e);
-                diesel::result::Error::RollbackTransaction
-            })?;
-
-        // Then create the order
-        let new_order = NewOrder {
-            id: Uuid::new_v4().to_string(),
-            buyer_id: buyer_id.clone(),
-            vendor_id: listing.vendor_id.clone(),
-            listing_id: req.listing_id.clone(),
-            escrow_id: None, // Set when escrow is created
-            status: OrderStatus::Pending.as_str().to_string(),
-            total_xmr,
-            shipping_address: Some(encrypted_address),
-            shipping_notes: req.shipping_notes.clone(),
-        };
-
-        Order::create(conn, new_order).map_err(|e| {
-            tracing::error!("Failed to create order: {}", e);
-            diesel::result::Error::RollbackTransaction
-        })
-    });
+    let lines = [CheckoutLine {
+        listing_id: req.listing_id.clone(),
+        quantity: req.quantity,
+    }];
+    let quote = checkout_quote(&pricing).await;
+    let order_result = Order::create_checkout_quoted(
+        &mut conn,
+        &buyer_id,
+        &lines,
+        Some(&req.shipping_address),
+        &quote,
+    )
+    .and_then(|mut created| created.pop().context("Checkout created no order"));
 
     match order_result {
-        Ok(order) => {
+        Ok((order, items)) => {
             tracing::info!(
                 "Order created successfully: id={}, buyer={}, vendor={}, total={} piconeros",
                 order.id, order.buyer_id, order.vendor_id, order.total_xmr
@@ -565,7 +629,12 @@ pub async fn create_order(
             
             tracing::info!("Sent order notification to vendor {}", vendor_uuid);
             
-            HttpResponse::Created().json(OrderResponse::from(order))
+            HttpResponse::Created().json(OrderResponse::from(order).with_items(items))
+        }
+        Err(e) if e.to_string().starts_with("No current XMR/") => {
+            HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "error": format!("{}, try again later", e)
+            }))
         }
         Err(e) => {
             tracing::error!("Transaction failed: {:?}", e);
@@ -576,6 +645,117 @@ pub async fn create_order(
     }
 }
 
+/// Client response for a failed checkout
+///
+/// Errors the buyer can act on get a specific 400; anything else is an
+/// internal error whose details stay in the logs.
+fn checkout_error_response(e: &anyhow::Error) -> HttpResponse {
+    let message = e.to_string();
+    let error = if message.starts_with(INSUFFICIENT_STOCK_ERROR) {
+        "Not enough stock left for one of the items"
+    } else if message.starts_with(LISTING_UNAVAILABLE_ERROR) {
+        "One of the listings is no longer available for purchase"
+    } else if message == OWN_LISTING_ERROR {
+        "Cannot purchase your own listing"
+    } else if message.starts_with(SHIPPING_RECIPIENT_ERROR) {
+        "Shipping address is not encrypted for every vendor of the order"
+    } else if message.starts_with("No current XMR/") {
+        return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "error": format!("{}, try again later", message)
+        }));
+    } else {
+        return HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": "Failed to create order"
+        }));
+    };
+
+    HttpResponse::BadRequest().json(serde_json::json!({ "error": error }))
+}
+
+/// GET /api/orders/shipping-keys - Vendor public keys for the current checkout
+///
+/// The buyer encrypts the shipping address to every key returned here before
+/// calling `POST /api/orders/create`. Vendors are read from the session (cart
+/// or Buy Now listing), in checkout order.
+#[get("/orders/shipping-keys")]
+pub async fn get_shipping_keys(
+    pool: web::Data<DbPool>,
+    session: Session,
+    query: web::Query<ShippingKeysQuery>,
+) -> impl Responder {
+    if let Err(response) = get_user_id_from_session(&session) {
+        return response;
+    }
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database connection failed"
+            }))
+        }
+    };
+
+    let vendor_ids: Vec<String> = if query.checkout_mode == "listing" {
+        let listing_id = match session.get::<String>("checkout_listing_id") {
+            Ok(Some(id)) => id,
+            _ => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "No listing selected for checkout"
+                }))
+            }
+        };
+        match Listing::find_by_id(&mut conn, listing_id) {
+            Ok(listing) => vec![listing.vendor_id],
+            Err(_) => {
+                return HttpResponse::NotFound().json(serde_json::json!({
+                    "error": "Listing not found"
+                }))
+            }
+        }
+    } else {
+        let cart = match session.get::<Cart>("cart") {
+            Ok(Some(cart)) if !cart.is_empty() => cart,
+            _ => {
+                return HttpResponse::BadRequest().json(serde_json::json!({
+                    "error": "Cart is empty"
+                }))
+            }
+        };
+        let mut vendor_ids: Vec<String> = Vec::new();
+        for item in cart.items {
+            if !vendor_ids.contains(&item.vendor_id) {
+                vendor_ids.push(item.vendor_id);
+            }
+        }
+        vendor_ids
+    };
+
+    let id_refs: Vec<&str> = vendor_ids.iter().map(String::as_str).collect();
+    let keys = match UserEncryptionKey::find_by_user_ids(&mut conn, &id_refs) {
+        Ok(keys) => keys,
+        Err(e) => {
+            tracing::error!("Failed to load vendor encryption keys: {:?}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to load encryption keys"
+            }));
+        }
+    };
+
+    let vendors: Vec<VendorShippingKey> = vendor_ids
+        .into_iter()
+        .map(|vendor_id| VendorShippingKey {
+            public_key: keys
+                .iter()
+                .find(|key| key.user_id == vendor_id)
+                .map(|key| key.public_key.clone()),
+            user_id: vendor_id,
+        })
+        .collect();
+
+    HttpResponse::Ok().json(serde_json::json!({ "vendors": vendors }))
+}
+
 /// GET /api/orders/pending-count - Get count of pending orders for vendor
 #[get("/orders/pending-count")]
 pub async fn get_pending_count(pool: web::Data<DbPool>, session: Session) -> impl Responder {
@@ -712,7 +892,16 @@ pub async fn get_order(
         }));
     }
 
-    HttpResponse::Ok().json(OrderResponse::from(order))
+    let items = match order.items(&mut conn) {
+        Ok(items) => items,
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to fetch order items: {}", e)
+            }))
+        }
+    };
+
+    HttpResponse::Ok().json(OrderResponse::from(order).with_items(items))
 }
 
 /// POST /api/orders/{id}/ship - Mark order as shipped
@@ -959,6 +1148,7 @@ pub async fn complete_order(
 pub async fn init_escrow(
     pool: web::Data<DbPool>,
     escrow_orchestrator: web::Data<EscrowOrchestrator>,
+    pricing: web::Data<FiatPricing>,
     session: Session,
     http_req: HttpRequest,
     id: web::Path<String>,
@@ -1021,6 +1211,20 @@ pub async fn init_escrow(
         }));
     }
 
+    // The escrow waits for the current price of fiat-priced items
+    let order = if order.is_quote_expired(pricing.now()) {
+        match pricing.requote(&order.id).await {
+            Ok((order, _)) => order,
+            Err(e) => {
+                return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                    "error": format!("Order price expired and could not be requoted: {}", e)
+                }))
+            }
+        }
+    } else {
+        order
+    };
+
     // Parse UUIDs
     let order_uuid = match Uuid::parse_str(&order.id) {
         Ok(uuid) => uuid,
@@ -1098,6 +1302,77 @@ pub async fn init_escrow(
     }
 }
 
+/// POST /api/orders/{id}/requote - Convert fiat-priced items at the current rate
+///
+/// For a pending order whose escrow has received nothing yet. Locks the new
+/// total for another quote window; the escrow then waits for that amount.
+///
+/// Requires authentication as the buyer.
+#[post("/orders/{id}/requote")]
+pub async fn requote_order(
+    pool: web::Data<DbPool>,
+    pricing: web::Data<FiatPricing>,
+    session: Session,
+    http_req: HttpRequest,
+    id: web::Path<String>,
+) -> impl Responder {
+    let csrf_token = http_req
+        .headers()
+        .get("X-CSRF-Token")
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("");
+
+    if !validate_csrf_token(&session, csrf_token) {
+        return HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Invalid or missing CSRF token"
+        }));
+    }
+
+    let user_id = match get_user_id_from_session(&session) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let mut conn = match pool.get() {
+        Ok(conn) => conn,
+        Err(_) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database connection failed"
+            }))
+        }
+    };
+
+    let order_id = id.into_inner();
+    match Order::find_by_id(&mut conn, order_id.clone()) {
+        Ok(order) if order.buyer_id == user_id => {}
+        Ok(_) => {
+            return HttpResponse::Forbidden().json(serde_json::json!({
+                "error": "Only the buyer can requote an order"
+            }))
+        }
+        Err(_) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Order not found"
+            }))
+        }
+    }
+    drop(conn);
+
+    match pricing.requote(&order_id).await {
+        Ok((order, items)) => {
+            tracing::info!(
+                "Order {} requoted by buyer: {} piconeros",
+                order.id,
+                order.total_xmr
+            );
+            HttpResponse::Ok().json(OrderResponse::from(order).with_items(items))
+        }
+        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Failed to requote order: {}", e)
+        })),
+    }
+}
+
 /// PUT /api/orders/{id}/cancel - Cancel an order
 ///
 /// Buyer can cancel in pending or funded status.
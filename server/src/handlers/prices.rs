@@ -0,0 +1,120 @@
+//! XMR exchange rate endpoints
+//!
+//! Current rates and their history are public; admins can set the manual
+//! rate used when the marketplace runs without reachable price APIs.
+
+use actix_session::Session;
+use actix_web::{get, put, web, HttpResponse, Responder};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::models::audit_log::AuditEventType;
+use crate::models::escrow_event::EscrowActor;
+use crate::models::price_history::MAX_HISTORY_PAGE;
+use crate::services::audit::AuditLogger;
+use crate::services::price_conversion::PriceOracle;
+
+/// Query parameters for the rate history
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    /// Number of records, capped at [`MAX_HISTORY_PAGE`]
+    pub limit: Option<i64>,
+}
+
+/// Request body for setting a manual rate
+#[derive(Debug, Deserialize)]
+pub struct ManualRateRequest {
+    /// Fiat units per 1 XMR; null clears the manual rate
+    pub rate: Option<f64>,
+}
+
+/// GET /api/prices - Current XMR rate in every configured currency
+///
+/// Each rate carries its `fetched_at`, `age_secs` and a `stale` flag set
+/// once it is older than the configured maximum age.
+#[get("/prices")]
+pub async fn get_prices(oracle: web::Data<PriceOracle>) -> impl Responder {
+    match oracle.rates().await {
+        Ok(rates) => HttpResponse::Ok().json(serde_json::json!({
+            "rates": rates,
+            "max_age_secs": oracle.config().max_age_secs,
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to load rates: {}", e)
+        })),
+    }
+}
+
+/// GET /api/prices/{currency}/history?limit=N - Recorded rates, newest first
+#[get("/prices/{currency}/history")]
+pub async fn get_price_history(
+    oracle: web::Data<PriceOracle>,
+    path: web::Path<String>,
+    query: web::Query<HistoryQuery>,
+) -> impl Responder {
+    let currency = path.into_inner().to_lowercase();
+    if !oracle.currencies().contains(&currency) {
+        return HttpResponse::NotFound().json(serde_json::json!({
+            "error": format!("Currency {} is not priced by this marketplace", currency)
+        }));
+    }
+
+    match oracle
+        .history(&currency, query.limit.unwrap_or(MAX_HISTORY_PAGE))
+        .await
+    {
+        Ok(records) => HttpResponse::Ok().json(records),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to load price history: {}", e)
+        })),
+    }
+}
+
+/// PUT /admin/prices/{currency}/manual - Set or clear the manual rate
+///
+/// Rates are refreshed right away; recorded in the audit log as an admin
+/// action.
+#[put("/prices/{currency}/manual")]
+pub async fn set_manual_rate(
+    oracle: web::Data<PriceOracle>,
+    audit: web::Data<AuditLogger>,
+    session: Session,
+    path: web::Path<String>,
+    req: web::Json<ManualRateRequest>,
+) -> impl Responder {
+    let admin_id = match session
+        .get::<String>("user_id")
+        .ok()
+        .flatten()
+        .and_then(|id| id.parse::<Uuid>().ok())
+    {
+        Some(id) => id,
+        None => {
+            return HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Not authenticated"
+            }))
+        }
+    };
+
+    let currency = path.into_inner().to_lowercase();
+    match oracle.set_manual_rate(&currency, req.rate).await {
+        Ok(records) => {
+            audit
+                .record_or_log(
+                    AuditEventType::AdminAction,
+                    format!("price:{}", currency),
+                    EscrowActor::user(admin_id),
+                    serde_json::json!({
+                        "action": "set_manual_rate",
+                        "currency": currency,
+                        "rate": req.rate,
+                    }),
+                )
+                .await;
+            HttpResponse::Ok().json(serde_json::json!({ "recorded": records }))
+        }
+        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Failed to set manual rate: {}", e)
+        })),
+    }
+}
@@ -15,13 +15,21 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
-use reputation_common::types::{SignedReview, VendorReputation};
-use reputation_crypto::reputation::{calculate_stats, verify_review_signature};
+use reputation_common::types::{
+    ReputationScore, ReviewReply, ReviewRevision, SignedReview, VendorReputation,
+};
+use reputation_crypto::reputation::{verify_message_signature, verify_review_signature};
+use reputation_crypto::revision::{verify_review_history, verify_review_reply};
 
+use crate::config::ReviewPolicy;
 use crate::db::reputation::{
-    db_get_vendor_reviews, db_get_vendor_stats, db_insert_review, db_review_exists,
+    db_amend_review, db_escrow_review_exists, db_get_reputation_key, db_get_review,
+    db_get_vendor_reputation, db_get_vendor_review, db_get_vendor_stats, db_insert_review,
+    db_register_reputation_key, db_review_exists, db_set_review_reply,
 };
-use crate::db::DbPool;
+use crate::db::{db_load_escrow, DbPool};
+use crate::models::escrow::EscrowStatus;
+use crate::services::payment_proof::PaymentProofService;
 
 /// Maximum comment length (defense in depth)
 const MAX_COMMENT_LENGTH: usize = 500;
@@ -50,6 +58,14 @@ fn hash_txid_for_logging(txid: &str) -> String {
     format!("{:x}", result)[..16].to_string() // First 16 chars of hex for brevity
 }
 
+/// Message a vendor signs to prove they hold their reputation key
+///
+/// Names the marketplace and the vendor account, so the proof cannot be
+/// replayed to register the key for someone else or elsewhere.
+pub fn reputation_key_message(marketplace_domain: &str, vendor_id: &str) -> String {
+    format!("reputation-key|{}|{}", marketplace_domain, vendor_id)
+}
+
 // ============================================================================
 // Request/Response Types
 // ============================================================================
@@ -79,12 +95,46 @@ pub struct ReputationResponse {
     pub reputation: VendorReputation,
 }
 
+/// Request to register a vendor reputation key
+#[derive(Debug, Deserialize)]
+pub struct RegisterKeyRequest {
+    /// Ed25519 public key (base64)
+    pub public_key: String,
+
+    /// Signature of [`reputation_key_message`] by that key (base64)
+    pub signature: String,
+}
+
+/// Request to verify the payment behind a review
+#[derive(Debug, Default, Deserialize)]
+pub struct VerifyPaymentRequest {
+    /// Tx proof from the buyer's wallet (`get_tx_proof`); generated by the
+    /// escrow wallet when absent
+    #[serde(default)]
+    pub proof: Option<String>,
+}
+
+/// Request to amend a review
+#[derive(Debug, Deserialize)]
+pub struct AmendReviewRequest {
+    /// New version signed by the buyer, superseding the current one
+    pub revision: ReviewRevision,
+}
+
+/// Request to reply to a review
+#[derive(Debug, Deserialize)]
+pub struct ReplyRequest {
+    /// Reply signed with the vendor's reputation key
+    pub reply: ReviewReply,
+}
+
 /// Quick stats response (without full review list)
 #[derive(Debug, Serialize)]
 pub struct QuickStatsResponse {
     pub vendor_id: String,
     pub total_reviews: i64,
     pub average_rating: f64,
+    pub score: ReputationScore,
 }
 
 // ============================================================================
@@ -98,6 +148,9 @@ pub struct QuickStatsResponse {
 /// # Security
 /// - Session authentication required (SameSite=Strict cookies provide CSRF protection)
 /// - Signature verification (ed25519)
+/// - 2.0 reviews: signed for this vendor and marketplace, about an escrow
+///   between this buyer and this vendor that has no review yet
+/// - 1.0 reviews only while `REVIEW_ACCEPT_UNBOUND` is on
 /// - Duplicate detection (same txid + reviewer)
 /// - Rate limiting: 10 requests/hour per user
 ///
@@ -105,14 +158,18 @@ pub struct QuickStatsResponse {
 /// ```json
 /// {
 ///   "review": {
+///     "format_version": "2.0",
 ///     "txid": "monero_transaction_hash",
 ///     "rating": 5,
 ///     "comment": "Excellent service!",
 ///     "timestamp": "2025-10-22T12:00:00Z",
 ///     "buyer_pubkey": "base64_encoded_pubkey",
+///     "vendor_pubkey": "vendor_uuid",
+///     "escrow_id": "escrow_uuid",
+///     "marketplace_domain": "market.example.onion",
 ///     "signature": "base64_encoded_signature"
 ///   },
-///   "vendor_id": "uuid"
+///   "vendor_id": "vendor_uuid"
 /// }
 /// ```
 ///
@@ -131,6 +188,7 @@ pub struct QuickStatsResponse {
 /// - 500: Database error
 pub async fn submit_review(
     pool: web::Data<DbPool>,
+    policy: web::Data<ReviewPolicy>,
     session: Session,
     req: web::Json<SubmitReviewRequest>,
 ) -> impl Responder {
@@ -168,7 +226,7 @@ pub async fn submit_review(
     };
 
     // 4. Validate review fields
-    if let Err(e) = validate_review_input(&req.review) {
+    if let Err(e) = validate_new_review(&req.review) {
         tracing::warn!(
             reviewer_id = %reviewer_id,
             vendor_id = %vendor_uuid,
@@ -218,7 +276,25 @@ pub async fn submit_review(
         }
     }
 
-    // 6. Check for duplicate review
+    // 6. Check what the review is bound to
+    if let Err(e) =
+        check_review_binding(&pool, &policy, &req.review, &reviewer_id, &vendor_uuid).await
+    {
+        tracing::warn!(
+            txid_hash = %hash_txid_for_logging(&req.review.txid),
+            reviewer_id = %reviewer_id,
+            vendor_id = %vendor_uuid,
+            error = %e,
+            "Review binding rejected"
+        );
+        return HttpResponse::BadRequest().json(SubmitReviewResponse {
+            status: "error".to_string(),
+            message: format!("Review binding error: {}", e),
+            review_id: None,
+        });
+    }
+
+    // 7. Check for duplicate review
     match db_review_exists(&pool, &req.review.txid, &reviewer_id).await {
         Ok(true) => {
             tracing::warn!(
@@ -245,11 +321,10 @@ pub async fn submit_review(
         }
     }
 
-    // 7. TODO: Verify transaction exists on blockchain
-    // This will be implemented when blockchain_monitor integration is complete
-    // For now, we trust the signature verification
+    // 8. On-chain verification happens separately: the review stays
+    // unverified until POST /api/reviews/{txid}/payment-proof
 
-    // 8. Store review in database
+    // 9. Store review in database
     match db_insert_review(&pool, &req.review, &reviewer_id, &req.vendor_id).await {
         Ok(created_review) => {
             tracing::info!(
@@ -320,6 +395,7 @@ pub async fn submit_review(
 /// - 500: Database error
 pub async fn get_vendor_reputation(
     pool: web::Data<DbPool>,
+    policy: web::Data<ReviewPolicy>,
     vendor_id: web::Path<String>,
 ) -> impl Responder {
     // 1. Validate vendor_id format
@@ -332,8 +408,8 @@ pub async fn get_vendor_reputation(
         }
     };
 
-    // 2. Load all reviews from database
-    let reviews = match db_get_vendor_reviews(&pool, vendor_uuid).await {
+    // 2. Load all reviews and build the reputation file
+    let reputation = match db_get_vendor_reputation(&pool, vendor_uuid, &policy.scoring).await {
         Ok(r) => r,
         Err(e) => {
             tracing::error!(
@@ -348,24 +424,12 @@ pub async fn get_vendor_reputation(
     };
 
     // 3. Return 404 if no reviews found
-    if reviews.is_empty() {
+    if reputation.reviews.is_empty() {
         return HttpResponse::NotFound().json(serde_json::json!({
             "error": "No reviews found for this vendor"
         }));
     }
 
-    // 4. Calculate statistics
-    let stats = calculate_stats(&reviews);
-
-    // 5. Build reputation file
-    let reputation = VendorReputation {
-        format_version: "1.0".to_string(),
-        vendor_pubkey: vendor_uuid.to_string(),
-        generated_at: chrono::Utc::now(),
-        reviews,
-        stats,
-    };
-
     tracing::debug!(
         vendor_id = %vendor_uuid,
         total_reviews = reputation.stats.total_reviews,
@@ -379,21 +443,446 @@ pub async fn get_vendor_reputation(
     })
 }
 
+/// POST /api/reputation/key
+///
+/// Register the ed25519 key a vendor signs their reputation file with.
+/// 2.0 reviews of the vendor are bound to this key, so it is registered
+/// once and cannot be replaced.
+///
+/// # Security
+/// - Session authentication required, vendor accounts only
+/// - Proof of possession: `signature` is the key's signature of
+///   `reputation-key|<marketplace domain>|<vendor id>` (see
+///   `reputation_crypto::reputation::sign_message`)
+///
+/// # Request Body
+/// ```json
+/// {
+///   "public_key": "base64_encoded_pubkey",
+///   "signature": "base64_encoded_signature"
+/// }
+/// ```
+///
+/// # Errors
+/// - 401: Not authenticated
+/// - 403: Not a vendor
+/// - 400: Invalid key or proof
+/// - 409: A key is already registered, or the key belongs to another vendor
+pub async fn register_reputation_key(
+    pool: web::Data<DbPool>,
+    policy: web::Data<ReviewPolicy>,
+    session: Session,
+    req: web::Json<RegisterKeyRequest>,
+) -> impl Responder {
+    let vendor_id = match session.get::<String>("user_id") {
+        Ok(Some(id)) => id,
+        _ => {
+            return HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Authentication required"
+            }));
+        }
+    };
+    if session.get::<String>("role").ok().flatten().as_deref() != Some("vendor") {
+        return HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Only vendors can register a reputation key"
+        }));
+    }
+
+    // Proof of possession
+    let message = reputation_key_message(&policy.marketplace_domain, &vendor_id);
+    match verify_message_signature(&req.public_key, message.as_bytes(), &req.signature) {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Signature does not prove possession of the key"
+            }));
+        }
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid key or signature: {}", e)
+            }));
+        }
+    }
+
+    match db_get_reputation_key(&pool, &vendor_id).await {
+        Ok(Some(existing)) if existing == req.public_key => {
+            return HttpResponse::Ok().json(serde_json::json!({
+                "status": "success",
+                "public_key": existing
+            }));
+        }
+        Ok(Some(_)) => {
+            return HttpResponse::Conflict().json(serde_json::json!({
+                "error": "A different reputation key is already registered"
+            }));
+        }
+        Ok(None) => {}
+        Err(e) => {
+            tracing::error!(error = %e, vendor_id = %vendor_id, "Error loading reputation key");
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    }
+
+    // Only a UNIQUE violation is expected here: the key is another vendor's
+    if let Err(e) = db_register_reputation_key(&pool, &vendor_id, &req.public_key).await {
+        tracing::warn!(error = %e, vendor_id = %vendor_id, "Reputation key registration failed");
+        return HttpResponse::Conflict().json(serde_json::json!({
+            "error": "Reputation key is already registered"
+        }));
+    }
+
+    tracing::info!(vendor_id = %vendor_id, "Reputation key registered");
+    HttpResponse::Created().json(serde_json::json!({
+        "status": "success",
+        "public_key": req.public_key
+    }))
+}
+
+/// POST /api/reviews/{txid}/payment-proof
+///
+/// Verify on-chain that the transaction of a 2.0 review paid its escrow's
+/// multisig address at least the escrow amount, then mark the review
+/// verified and store the proof with it (see
+/// `services::payment_proof::PaymentProofService`).
+///
+/// # Security
+/// - Session authentication required, the reviewer only
+/// - The proof signs `review-payment|<marketplace domain>|<escrow id>`
+///
+/// # Request Body
+/// ```json
+/// {
+///   "proof": "OutProofV2..."
+/// }
+/// ```
+///
+/// # Response (200 OK)
+/// ```json
+/// {
+///   "status": "success",
+///   "payment_proof": { "txid": "...", "address": "...", "amount": 1000000000000, ... }
+/// }
+/// ```
+///
+/// # Errors
+/// - 401: Not authenticated
+/// - 400: No such review, 1.0 review, invalid or insufficient payment
+pub async fn verify_review_payment(
+    service: web::Data<PaymentProofService>,
+    session: Session,
+    txid: web::Path<String>,
+    req: Option<web::Json<VerifyPaymentRequest>>,
+) -> impl Responder {
+    let reviewer_id = match session.get::<String>("user_id") {
+        Ok(Some(id)) => id,
+        _ => {
+            return HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Authentication required"
+            }));
+        }
+    };
+    let proof = req
+        .map(|req| req.into_inner().proof)
+        .unwrap_or_default()
+        .map(|proof| proof.trim().to_string())
+        .filter(|proof| !proof.is_empty());
+
+    match service
+        .verify_review_payment(&txid, &reviewer_id, proof)
+        .await
+    {
+        Ok(payment_proof) => {
+            tracing::info!(
+                txid_hash = %hash_txid_for_logging(&txid),
+                reviewer_id = %reviewer_id,
+                "Review payment verified"
+            );
+            HttpResponse::Ok().json(serde_json::json!({
+                "status": "success",
+                "payment_proof": payment_proof
+            }))
+        }
+        Err(e) => {
+            tracing::warn!(
+                txid_hash = %hash_txid_for_logging(&txid),
+                reviewer_id = %reviewer_id,
+                error = %e,
+                "Review payment verification failed"
+            );
+            HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Payment verification failed: {:#}", e)
+            }))
+        }
+    }
+}
+
+/// POST /api/reviews/{txid}/amend
+///
+/// Replace the buyer's review with a new version. The current version is
+/// kept in the review's history; the new one signs its signature
+/// (`supersedes`), so the history cannot be rewritten. Verification and the
+/// payment proof carry over: they are about the transaction.
+///
+/// # Security
+/// - Session authentication required, the reviewer only
+/// - 2.0 reviews only, within `REVIEW_AMENDMENT_WINDOW_HOURS` of posting
+/// - Signed by the review's buyer key, dated no earlier than the current version
+///
+/// # Request Body
+/// ```json
+/// {
+///   "revision": {
+///     "rating": 4,
+///     "comment": "Arrived in the end",
+///     "timestamp": "2025-10-23T12:00:00Z",
+///     "supersedes": "base64_signature_of_current_version",
+///     "signature": "base64_encoded_signature"
+///   }
+/// }
+/// ```
+///
+/// # Errors
+/// - 401: Not authenticated
+/// - 404: No review of this transaction by this user
+/// - 403: Amendment window closed
+/// - 400: Invalid signature or fields, 1.0 review
+/// - 409: Not an amendment of the current version
+pub async fn amend_review(
+    pool: web::Data<DbPool>,
+    policy: web::Data<ReviewPolicy>,
+    session: Session,
+    txid: web::Path<String>,
+    req: web::Json<AmendReviewRequest>,
+) -> impl Responder {
+    let reviewer_id = match session.get::<String>("user_id") {
+        Ok(Some(id)) => id,
+        _ => {
+            return HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Authentication required"
+            }));
+        }
+    };
+
+    let stored = match db_get_review(&pool, &txid, &reviewer_id).await {
+        Ok(Some(review)) => review,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Review not found"
+            }));
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Database error loading review");
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    };
+    if !policy.can_amend(stored.created_at, chrono::Utc::now().naive_utc()) {
+        return HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "This review can no longer be amended"
+        }));
+    }
+
+    let review_id = stored.id.clone();
+    let current = SignedReview::from(stored);
+    if req.revision.supersedes.as_deref() != Some(current.signature.as_str()) {
+        return HttpResponse::Conflict().json(serde_json::json!({
+            "error": "Only the current version of the review can be amended"
+        }));
+    }
+    let amended = current.amended(req.into_inner().revision);
+    if let Err(e) = check_amendment(&amended) {
+        tracing::warn!(
+            txid_hash = %hash_txid_for_logging(&txid),
+            reviewer_id = %reviewer_id,
+            error = %e,
+            "Review amendment rejected"
+        );
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Invalid amendment: {}", e)
+        }));
+    }
+
+    match db_amend_review(&pool, &review_id, &amended).await {
+        Ok(1) => {
+            tracing::info!(
+                review_id = %review_id,
+                txid_hash = %hash_txid_for_logging(&txid),
+                revisions = amended.revisions.len(),
+                "Review amended"
+            );
+            HttpResponse::Ok().json(serde_json::json!({
+                "status": "success",
+                "review": amended
+            }))
+        }
+        Ok(_) => HttpResponse::Conflict().json(serde_json::json!({
+            "error": "Only the current version of the review can be amended"
+        })),
+        Err(e) => {
+            tracing::error!(error = %e, review_id = %review_id, "Database error amending review");
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to store review"
+            }))
+        }
+    }
+}
+
+/// POST /api/reviews/{txid}/reply
+///
+/// Post the vendor's public reply to a review. The reply signs the
+/// signature of the version it answers, with the vendor's reputation key;
+/// it is exported with the review and checked by the WASM verifier.
+///
+/// One reply per version: once the buyer amends the review, the vendor may
+/// reply again, replacing the earlier reply.
+///
+/// # Security
+/// - Session authentication required, the reviewed vendor only
+/// - Signed by the vendor's registered reputation key
+///
+/// # Request Body
+/// ```json
+/// {
+///   "reply": {
+///     "in_reply_to": "base64_signature_of_current_version",
+///     "comment": "Sorry for the delay, reshipped",
+///     "timestamp": "2025-10-23T12:00:00Z",
+///     "signature": "base64_encoded_signature"
+///   }
+/// }
+/// ```
+///
+/// # Errors
+/// - 401: Not authenticated
+/// - 404: No review of this transaction about this vendor
+/// - 400: No reputation key, invalid signature or reply
+/// - 409: Not a reply to the current version, or it already has one
+pub async fn reply_to_review(
+    pool: web::Data<DbPool>,
+    session: Session,
+    txid: web::Path<String>,
+    req: web::Json<ReplyRequest>,
+) -> impl Responder {
+    let vendor_id = match session.get::<String>("user_id") {
+        Ok(Some(id)) => id,
+        _ => {
+            return HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Authentication required"
+            }));
+        }
+    };
+
+    let stored = match db_get_vendor_review(&pool, &txid, &vendor_id).await {
+        Ok(Some(review)) => review,
+        Ok(None) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Review not found"
+            }));
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Database error loading review");
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    };
+    let vendor_key = match db_get_reputation_key(&pool, &vendor_id).await {
+        Ok(Some(key)) => key,
+        Ok(None) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Register a reputation key before replying"
+            }));
+        }
+        Err(e) => {
+            tracing::error!(error = %e, vendor_id = %vendor_id, "Error loading reputation key");
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    };
+
+    let review_id = stored.id.clone();
+    let mut review = SignedReview::from(stored);
+    let reply = req.into_inner().reply;
+    if reply.in_reply_to != review.signature {
+        return HttpResponse::Conflict().json(serde_json::json!({
+            "error": "Replies must answer the current version of the review"
+        }));
+    }
+    if review
+        .reply
+        .as_ref()
+        .is_some_and(|existing| existing.in_reply_to == review.signature)
+    {
+        return HttpResponse::Conflict().json(serde_json::json!({
+            "error": "This version of the review already has a reply"
+        }));
+    }
+
+    review.reply = Some(reply.clone());
+    match verify_review_reply(&review, &vendor_key) {
+        Ok(true) => {}
+        Ok(false) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Reply signature verification failed"
+            }));
+        }
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Invalid reply: {}", e)
+            }));
+        }
+    }
+
+    match db_set_review_reply(&pool, &review_id, &reply).await {
+        Ok(_) => {
+            tracing::info!(
+                review_id = %review_id,
+                txid_hash = %hash_txid_for_logging(&txid),
+                vendor_id = %vendor_id,
+                "Vendor replied to review"
+            );
+            HttpResponse::Created().json(serde_json::json!({
+                "status": "success",
+                "reply": reply
+            }))
+        }
+        Err(e) => {
+            tracing::error!(error = %e, review_id = %review_id, "Database error storing reply");
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to store reply"
+            }))
+        }
+    }
+}
+
 /// GET /api/reputation/{vendor_id}/stats
 ///
 /// Retrieve quick statistics for a vendor without full review list.
-/// Optimized for performance (uses database aggregation).
+/// The score weights verified reviews by amount paid and age; rates are in
+/// basis points of funded escrows (`null` before the first one).
 ///
 /// # Response (200 OK)
 /// ```json
 /// {
 ///   "vendor_id": "uuid",
 ///   "total_reviews": 42,
-///   "average_rating": 4.7
+///   "average_rating": 4.7,
+///   "score": {
+///     "params": { "half_life_days": 180, ... },
+///     "score_milli": 4612,
+///     "effective_reviews_milli": 28400,
+///     "dispute_rate_bps": 250,
+///     "refund_rate_bps": 100
+///   }
 /// }
 /// ```
 pub async fn get_vendor_stats(
     pool: web::Data<DbPool>,
+    policy: web::Data<ReviewPolicy>,
     vendor_id: web::Path<String>,
 ) -> impl Responder {
     let vendor_uuid = match Uuid::parse_str(vendor_id.as_str()) {
@@ -405,11 +894,12 @@ pub async fn get_vendor_stats(
         }
     };
 
-    match db_get_vendor_stats(&pool, vendor_uuid).await {
-        Ok((total, average)) => HttpResponse::Ok().json(QuickStatsResponse {
+    match db_get_vendor_stats(&pool, vendor_uuid, &policy.scoring).await {
+        Ok(stats) => HttpResponse::Ok().json(QuickStatsResponse {
             vendor_id: vendor_uuid.to_string(),
-            total_reviews: total,
-            average_rating: average,
+            total_reviews: stats.total_reviews,
+            average_rating: stats.average_rating,
+            score: stats.score,
         }),
         Err(e) => {
             tracing::error!(
@@ -428,6 +918,86 @@ pub async fn get_vendor_stats(
 // Validation Helpers
 // ============================================================================
 
+/// Check that a review belongs to the vendor, escrow and marketplace
+///
+/// A 2.0 review must be signed for this vendor's reputation key and this
+/// marketplace, about a settled escrow (payout to the vendor or refund to
+/// the buyer confirmed) between the reviewer (as buyer) and the vendor that
+/// has no review yet. A 1.0 review names none of these and is only accepted while
+/// the policy allows unbound reviews.
+pub async fn check_review_binding(
+    pool: &DbPool,
+    policy: &ReviewPolicy,
+    review: &SignedReview,
+    reviewer_id: &str,
+    vendor_uuid: &Uuid,
+) -> Result<()> {
+    let Some(binding) = review.binding() else {
+        if policy.accept_unbound_reviews {
+            return Ok(());
+        }
+        anyhow::bail!("Reviews must use format 2.0 (vendor, escrow and marketplace domain)");
+    };
+
+    let Some(vendor_key) = db_get_reputation_key(pool, &vendor_uuid.to_string()).await? else {
+        anyhow::bail!("Vendor has no reputation key yet, reviews cannot be bound to it");
+    };
+    if binding.vendor_pubkey != vendor_key {
+        anyhow::bail!("Review is signed for another vendor");
+    }
+    if binding.marketplace_domain != policy.marketplace_domain {
+        anyhow::bail!(
+            "Review is signed for marketplace '{}', expected '{}'",
+            binding.marketplace_domain,
+            policy.marketplace_domain
+        );
+    }
+
+    let escrow_uuid =
+        Uuid::parse_str(&binding.escrow_id).map_err(|_| anyhow::anyhow!("Invalid escrow ID"))?;
+    let escrow = db_load_escrow(pool, escrow_uuid)
+        .await
+        .map_err(|_| anyhow::anyhow!("Escrow not found"))?;
+    if escrow.buyer_id != reviewer_id || escrow.vendor_id != vendor_uuid.to_string() {
+        anyhow::bail!("Escrow is not between this buyer and this vendor");
+    }
+    if !matches!(
+        escrow.get_status()?,
+        EscrowStatus::Completed | EscrowStatus::Refunded
+    ) {
+        anyhow::bail!("Escrow is not settled yet (status: {})", escrow.status);
+    }
+    if db_escrow_review_exists(pool, &binding.escrow_id).await? {
+        anyhow::bail!("Escrow has already been reviewed");
+    }
+
+    Ok(())
+}
+
+/// Validate a newly submitted review
+///
+/// A new review is a first version: earlier versions come from amendments
+/// and replies from the vendor, both through their own endpoints.
+fn validate_new_review(review: &SignedReview) -> Result<()> {
+    validate_review_input(review)?;
+    if review.supersedes.is_some() || !review.revisions.is_empty() || review.reply.is_some() {
+        anyhow::bail!("New reviews cannot carry earlier versions or a vendor reply");
+    }
+    Ok(())
+}
+
+/// Check an amended review: its new version and its history
+fn check_amendment(amended: &SignedReview) -> Result<()> {
+    validate_review_input(amended)?;
+    if !verify_review_signature(amended)? {
+        anyhow::bail!("Cryptographic signature verification failed");
+    }
+    if !verify_review_history(amended)? {
+        anyhow::bail!("New version is dated before the version it replaces");
+    }
+    Ok(())
+}
+
 /// Validate review input fields (defense in depth)
 ///
 /// Even though SignedReview has validation, we double-check here
@@ -478,6 +1048,9 @@ fn validate_review_input(review: &SignedReview) -> Result<()> {
         anyhow::bail!("Signature cannot be empty");
     }
 
+    // Validate format version and binding fields
+    review.signed_message().map_err(anyhow::Error::msg)?;
+
     Ok(())
 }
 
@@ -485,27 +1058,44 @@ fn validate_review_input(review: &SignedReview) -> Result<()> {
 mod tests {
     use super::*;
     use chrono::Utc;
+    use reputation_common::types::{REVIEW_FORMAT_V1, REVIEW_FORMAT_V2};
 
     #[test]
     fn test_validate_review_rating_bounds() {
         let review = SignedReview {
+            format_version: REVIEW_FORMAT_V1.to_string(),
             txid: "a".repeat(64),
             rating: 0,
             comment: None,
             timestamp: Utc::now(),
             buyer_pubkey: "valid_pubkey".to_string(),
+            vendor_pubkey: None,
+            escrow_id: None,
+            marketplace_domain: None,
+            supersedes: None,
             signature: "valid_sig".to_string(),
+            payment_proof: None,
+            revisions: Vec::new(),
+            reply: None,
         };
 
         assert!(validate_review_input(&review).is_err());
 
         let review = SignedReview {
+            format_version: REVIEW_FORMAT_V1.to_string(),
             txid: "a".repeat(64),
             rating: 6,
             comment: None,
             timestamp: Utc::now(),
             buyer_pubkey: "valid_pubkey".to_string(),
+            vendor_pubkey: None,
+            escrow_id: None,
+            marketplace_domain: None,
+            supersedes: None,
             signature: "valid_sig".to_string(),
+            payment_proof: None,
+            revisions: Vec::new(),
+            reply: None,
         };
 
         assert!(validate_review_input(&review).is_err());
@@ -514,12 +1104,20 @@ mod tests {
     #[test]
     fn test_validate_review_comment_length() {
         let review = SignedReview {
+            format_version: REVIEW_FORMAT_V1.to_string(),
             txid: "a".repeat(64),
             rating: 5,
             comment: Some("x".repeat(501)),
             timestamp: Utc::now(),
             buyer_pubkey: "valid_pubkey".to_string(),
+            vendor_pubkey: None,
+            escrow_id: None,
+            marketplace_domain: None,
+            supersedes: None,
             signature: "valid_sig".to_string(),
+            payment_proof: None,
+            revisions: Vec::new(),
+            reply: None,
         };
 
         assert!(validate_review_input(&review).is_err());
@@ -528,14 +1126,75 @@ mod tests {
     #[test]
     fn test_validate_review_txid_length() {
         let review = SignedReview {
+            format_version: REVIEW_FORMAT_V1.to_string(),
             txid: "short".to_string(),
             rating: 5,
             comment: None,
             timestamp: Utc::now(),
             buyer_pubkey: "valid_pubkey".to_string(),
+            vendor_pubkey: None,
+            escrow_id: None,
+            marketplace_domain: None,
+            supersedes: None,
+            signature: "valid_sig".to_string(),
+            payment_proof: None,
+            revisions: Vec::new(),
+            reply: None,
+        };
+
+        assert!(validate_review_input(&review).is_err());
+    }
+
+    #[test]
+    fn test_validate_review_binding_fields() {
+        let mut review = SignedReview {
+            format_version: REVIEW_FORMAT_V2.to_string(),
+            txid: "a".repeat(64),
+            rating: 5,
+            comment: None,
+            timestamp: Utc::now(),
+            buyer_pubkey: "valid_pubkey".to_string(),
+            vendor_pubkey: Some(Uuid::new_v4().to_string()),
+            escrow_id: None,
+            marketplace_domain: Some("localhost".to_string()),
+            supersedes: None,
             signature: "valid_sig".to_string(),
+            payment_proof: None,
+            revisions: Vec::new(),
+            reply: None,
         };
 
         assert!(validate_review_input(&review).is_err());
+
+        review.escrow_id = Some(Uuid::new_v4().to_string());
+        assert!(validate_review_input(&review).is_ok());
+
+        review.format_version = "9.9".to_string();
+        assert!(validate_review_input(&review).is_err());
+    }
+
+    #[test]
+    fn test_new_review_has_no_history() {
+        let mut review = SignedReview {
+            format_version: REVIEW_FORMAT_V2.to_string(),
+            txid: "a".repeat(64),
+            rating: 5,
+            comment: None,
+            timestamp: Utc::now(),
+            buyer_pubkey: "valid_pubkey".to_string(),
+            vendor_pubkey: Some(Uuid::new_v4().to_string()),
+            escrow_id: Some(Uuid::new_v4().to_string()),
+            marketplace_domain: Some("localhost".to_string()),
+            supersedes: None,
+            signature: "valid_sig".to_string(),
+            payment_proof: None,
+            revisions: Vec::new(),
+            reply: None,
+        };
+        assert!(validate_new_review(&review).is_ok());
+
+        review.supersedes = Some("earlier_sig".to_string());
+        assert!(validate_review_input(&review).is_ok());
+        assert!(validate_new_review(&review).is_err());
     }
 }
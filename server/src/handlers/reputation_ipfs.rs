@@ -1,24 +1,63 @@
-//! IPFS export handler for reputation system
+//! IPFS export and import handlers for reputation system
 //!
-//! This module provides the endpoint to export vendor reputation to IPFS.
+//! A vendor exports their reputation as a 2.0 file signed with their
+//! reputation key: the server prepares the file, the vendor signs it
+//! client-side and the server checks it against the database before
+//! uploading it to IPFS.
+//!
+//! On another marketplace, the same vendor (same registered key) imports the
+//! file by CID. Every review and the file signature are verified before the
+//! file is stored; imported reputation is kept apart from native reviews.
 
 use actix_session::Session;
 use actix_web::{web, HttpResponse, Responder};
+use anyhow::Result;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use reputation_common::types::VendorReputation;
-use reputation_crypto::reputation::calculate_stats;
+use reputation_common::types::{VendorReputation, REPUTATION_FORMAT_V2, REVIEW_FORMAT_V1};
+use reputation_crypto::reputation::{
+    calculate_stats, verify_reputation_signature, verify_review_for_vendor,
+};
+use reputation_crypto::scoring::verify_reputation_score;
 
-use crate::db::reputation::db_get_vendor_reviews;
+use crate::config::ReviewPolicy;
+use crate::db::reputation::{
+    db_get_reputation_key, db_get_vendor_reputation, db_insert_imported_reputation,
+    ImportedReputation,
+};
 use crate::db::DbPool;
 use crate::ipfs::client::IpfsClient;
 
+/// Maximum size of an imported reputation file
+const MAX_IMPORT_SIZE: usize = 5 * 1024 * 1024;
+
+/// Request to prepare a reputation export
+#[derive(Debug, Deserialize)]
+pub struct PrepareExportRequest {
+    /// Vendor UUID to export
+    pub vendor_id: String,
+}
+
+/// Unsigned reputation file, ready to be signed by the vendor
+#[derive(Debug, Serialize)]
+pub struct PrepareExportResponse {
+    pub reputation: VendorReputation,
+
+    /// Bytes to sign with the reputation key (base64), see
+    /// `VendorReputation::signed_payload`
+    pub signing_payload: String,
+}
+
 /// Request to export reputation to IPFS
 #[derive(Debug, Deserialize)]
 pub struct ExportRequest {
     /// Vendor UUID to export
     pub vendor_id: String,
+
+    /// Prepared file, signed by the vendor
+    pub reputation: VendorReputation,
 }
 
 /// Response after exporting to IPFS
@@ -31,19 +70,251 @@ pub struct ExportResponse {
     pub gateway_url: String,
 }
 
+/// Request to import reputation from IPFS
+#[derive(Debug, Deserialize)]
+pub struct ImportRequest {
+    /// CID of a signed reputation file
+    pub cid: String,
+}
+
+/// What a verified reputation file contains
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportSummary {
+    /// Marketplaces the 2.0 reviews were written on, sorted
+    pub source_domains: Vec<String>,
+
+    /// 1.0 reviews, bound to no vendor nor marketplace
+    pub unbound_reviews: u32,
+}
+
+/// Check that a string looks like an IPFS CID (v0 base58 or v1 base32)
+pub fn is_valid_cid(cid: &str) -> bool {
+    (46..=128).contains(&cid.len()) && cid.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Verify a reputation file before importing it for `vendor_key`
+///
+/// The file must be a 2.0 file signed by the vendor's registered key, every
+/// review must verify and belong to that vendor, and its stats and score
+/// must be the ones of its reviews. Reviews written on this marketplace are
+/// native reviews: importing them would count them twice. 1.0 reviews are
+/// only imported while the policy accepts unbound reviews.
+pub fn verify_imported_reputation(
+    reputation: &VendorReputation,
+    vendor_key: &str,
+    policy: &ReviewPolicy,
+) -> Result<ImportSummary> {
+    if reputation.format_version != REPUTATION_FORMAT_V2 {
+        anyhow::bail!(
+            "Reputation file format {} is not signed, expected {}",
+            reputation.format_version,
+            REPUTATION_FORMAT_V2
+        );
+    }
+    if reputation.vendor_pubkey != vendor_key {
+        anyhow::bail!("Reputation file belongs to another vendor key");
+    }
+    if !verify_reputation_signature(reputation)? {
+        anyhow::bail!("Invalid reputation file signature");
+    }
+    if reputation.reviews.is_empty() {
+        anyhow::bail!("Reputation file has no reviews");
+    }
+
+    let mut source_domains = Vec::new();
+    let mut unbound_reviews = 0;
+    for review in &reputation.reviews {
+        if !verify_review_for_vendor(review, vendor_key)? {
+            anyhow::bail!("Invalid review signature or review of another vendor");
+        }
+        match review.binding() {
+            Some(binding) if binding.marketplace_domain == policy.marketplace_domain => {
+                anyhow::bail!("Reputation file contains reviews written on this marketplace");
+            }
+            Some(binding) => source_domains.push(binding.marketplace_domain),
+            None if review.format_version == REVIEW_FORMAT_V1 => {
+                if !policy.accept_unbound_reviews {
+                    anyhow::bail!(
+                        "Reputation file contains 1.0 reviews, bound to no vendor nor marketplace"
+                    );
+                }
+                unbound_reviews += 1;
+            }
+            None => anyhow::bail!("Unsupported review format {}", review.format_version),
+        }
+    }
+    source_domains.sort();
+    source_domains.dedup();
+
+    let stats = calculate_stats(&reputation.reviews);
+    if reputation.stats.total_reviews != stats.total_reviews
+        || reputation.stats.rating_distribution != stats.rating_distribution
+        || (reputation.stats.average_rating - stats.average_rating).abs() >= 0.01
+    {
+        anyhow::bail!("Reputation stats do not match its reviews");
+    }
+    if !verify_reputation_score(reputation) {
+        anyhow::bail!("Reputation score does not match its reviews");
+    }
+
+    Ok(ImportSummary {
+        source_domains,
+        unbound_reviews,
+    })
+}
+
+/// Authenticated vendor exporting their own reputation, or an error response
+fn authorize_export(session: &Session, vendor_id: &str) -> Result<Uuid, Box<HttpResponse>> {
+    // 1. Authentication check
+    let user_id = match session.get::<String>("user_id") {
+        Ok(Some(id)) => id,
+        Ok(None) => {
+            tracing::warn!("Unauthenticated IPFS export attempt");
+            return Err(Box::new(HttpResponse::Unauthorized().json(
+                serde_json::json!({
+                    "error": "Authentication required"
+                }),
+            )));
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Session error during IPFS export");
+            return Err(Box::new(HttpResponse::InternalServerError().json(
+                serde_json::json!({
+                    "error": "Session error"
+                }),
+            )));
+        }
+    };
+
+    // 2. Validate vendor_id format
+    let vendor_uuid = Uuid::parse_str(vendor_id).map_err(|_| {
+        HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Invalid vendor ID format"
+        }))
+    })?;
+
+    // 3. Authorization check: Only vendor can export their own reputation
+    if user_id != vendor_id {
+        tracing::warn!(
+            user_id = %user_id,
+            vendor_id = %vendor_uuid,
+            "Unauthorized IPFS export attempt"
+        );
+        return Err(Box::new(HttpResponse::Forbidden().json(
+            serde_json::json!({
+                "error": "You can only export your own reputation"
+            }),
+        )));
+    }
+
+    Ok(vendor_uuid)
+}
+
+/// POST /api/reputation/export/prepare
+///
+/// Build the reputation file of the vendor for signing: format 2.0, for
+/// their registered reputation key, with `signing_payload` the exact bytes
+/// to sign.
+///
+/// # Security
+/// - Session authentication required (SameSite=Strict cookies provide CSRF protection)
+/// - Authorization: Only vendor can export their own reputation
+///
+/// # Request Body
+/// ```json
+/// {
+///   "vendor_id": "uuid"
+/// }
+/// ```
+///
+/// # Errors
+/// - 401: Not authenticated
+/// - 403: Not authorized (not your reputation)
+/// - 400: Invalid vendor ID, no reputation key, no reviews found
+/// - 500: Database error
+pub async fn prepare_export(
+    pool: web::Data<DbPool>,
+    policy: web::Data<ReviewPolicy>,
+    session: Session,
+    req: web::Json<PrepareExportRequest>,
+) -> impl Responder {
+    let vendor_uuid = match authorize_export(&session, &req.vendor_id) {
+        Ok(uuid) => uuid,
+        Err(response) => return *response,
+    };
+
+    let vendor_key = match db_get_reputation_key(&pool, &req.vendor_id).await {
+        Ok(Some(key)) => key,
+        Ok(None) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Register a reputation key before exporting"
+            }));
+        }
+        Err(e) => {
+            tracing::error!(vendor_id = %vendor_uuid, error = %e, "Error loading reputation key");
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to load reputation data"
+            }));
+        }
+    };
+
+    let mut reputation = match db_get_vendor_reputation(&pool, vendor_uuid, &policy.scoring).await {
+        Ok(r) => r,
+        Err(e) => {
+            tracing::error!(
+                vendor_id = %vendor_uuid,
+                error = %e,
+                "Error loading vendor reviews for IPFS export"
+            );
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to load reputation data"
+            }));
+        }
+    };
+
+    if reputation.reviews.is_empty() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "No reviews found to export"
+        }));
+    }
+
+    reputation.format_version = REPUTATION_FORMAT_V2.to_string();
+    reputation.vendor_pubkey = vendor_key;
+    let signing_payload = match reputation.signed_payload() {
+        Ok(payload) => base64::engine::general_purpose::STANDARD.encode(payload),
+        Err(e) => {
+            tracing::error!(error = %e, vendor_id = %vendor_uuid, "Failed to build signing payload");
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Serialization error"
+            }));
+        }
+    };
+
+    HttpResponse::Ok().json(PrepareExportResponse {
+        reputation,
+        signing_payload,
+    })
+}
+
 /// POST /api/reputation/export
 ///
-/// Export vendor reputation to IPFS for portable storage.
+/// Export a signed vendor reputation file to IPFS for portable storage.
 /// Only the vendor themselves can export their own reputation.
 ///
+/// The file comes from `/api/reputation/export/prepare`, signed by the
+/// vendor. It must carry exactly the vendor's current reviews: a review
+/// received since preparing it means preparing again.
+///
 /// # Security
 /// - Session authentication required (SameSite=Strict cookies provide CSRF protection)
 /// - Authorization: Only vendor can export their own reputation
+/// - The file signature must verify against the vendor's registered key
 ///
 /// # Request Body
 /// ```json
 /// {
-///   "vendor_id": "uuid"
+///   "vendor_id": "uuid",
+///   "reputation": { "format_version": "2.0", ..., "signature": "base64" }
 /// }
 /// ```
 ///
@@ -61,55 +332,51 @@ pub struct ExportResponse {
 /// # Errors
 /// - 401: Not authenticated
 /// - 403: Not authorized (not your reputation)
-/// - 400: Invalid vendor ID, no reviews found
+/// - 400: Invalid vendor ID, no reputation key, invalid signature, stats or score
+/// - 409: Reviews or escrow outcomes changed since the file was prepared
 /// - 500: Database error, IPFS error
 pub async fn export_to_ipfs(
     pool: web::Data<DbPool>,
+    policy: web::Data<ReviewPolicy>,
     ipfs: web::Data<IpfsClient>,
     session: Session,
     req: web::Json<ExportRequest>,
 ) -> impl Responder {
-    // 1. Authentication check
-    let user_id = match session.get::<String>("user_id") {
-        Ok(Some(id)) => id,
+    let vendor_uuid = match authorize_export(&session, &req.vendor_id) {
+        Ok(uuid) => uuid,
+        Err(response) => return *response,
+    };
+    let reputation = &req.reputation;
+
+    // 4. The file is signed by the vendor's registered key
+    let vendor_key = match db_get_reputation_key(&pool, &req.vendor_id).await {
+        Ok(Some(key)) => key,
         Ok(None) => {
-            tracing::warn!("Unauthenticated IPFS export attempt");
-            return HttpResponse::Unauthorized().json(serde_json::json!({
-                "error": "Authentication required"
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Register a reputation key before exporting"
             }));
         }
         Err(e) => {
-            tracing::error!(error = %e, "Session error during IPFS export");
+            tracing::error!(vendor_id = %vendor_uuid, error = %e, "Error loading reputation key");
             return HttpResponse::InternalServerError().json(serde_json::json!({
-                "error": "Session error"
-            }));
-        }
-    };
-
-    // 2. Validate vendor_id format
-    let vendor_uuid = match Uuid::parse_str(&req.vendor_id) {
-        Ok(uuid) => uuid,
-        Err(_) => {
-            return HttpResponse::BadRequest().json(serde_json::json!({
-                "error": "Invalid vendor ID format"
+                "error": "Failed to load reputation data"
             }));
         }
     };
-
-    // 4. Authorization check: Only vendor can export their own reputation
-    if user_id != req.vendor_id {
-        tracing::warn!(
-            user_id = %user_id,
-            vendor_id = %vendor_uuid,
-            "Unauthorized IPFS export attempt"
-        );
-        return HttpResponse::Forbidden().json(serde_json::json!({
-            "error": "You can only export your own reputation"
+    if reputation.format_version != REPUTATION_FORMAT_V2 || reputation.vendor_pubkey != vendor_key {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Reputation file must be a 2.0 file for your reputation key"
+        }));
+    }
+    if !matches!(verify_reputation_signature(reputation), Ok(true)) {
+        tracing::warn!(vendor_id = %vendor_uuid, "Invalid reputation file signature on export");
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Invalid reputation file signature"
         }));
     }
 
     // 5. Load all reviews from database
-    let reviews = match db_get_vendor_reviews(&pool, vendor_uuid).await {
+    let current = match db_get_vendor_reputation(&pool, vendor_uuid, &policy.scoring).await {
         Ok(r) => r,
         Err(e) => {
             tracing::error!(
@@ -124,26 +391,38 @@ pub async fn export_to_ipfs(
     };
 
     // 6. Validate that vendor has reviews
-    if reviews.is_empty() {
+    if current.reviews.is_empty() {
         return HttpResponse::BadRequest().json(serde_json::json!({
             "error": "No reviews found to export"
         }));
     }
 
-    // 7. Calculate statistics
-    let stats = calculate_stats(&reviews);
-
-    // 8. Build reputation file
-    let reputation = VendorReputation {
-        format_version: "1.0".to_string(),
-        vendor_pubkey: vendor_uuid.to_string(),
-        generated_at: chrono::Utc::now(),
-        reviews,
-        stats,
-    };
+    // 7. The signed file carries the vendor's reviews and escrow outcomes,
+    // their statistics and their score under this marketplace's parameters
+    let same_reviews = serde_json::to_value(&reputation.reviews).ok()
+        == serde_json::to_value(&current.reviews).ok();
+    if !same_reviews || reputation.outcomes != current.outcomes {
+        return HttpResponse::Conflict().json(serde_json::json!({
+            "error": "Reviews or escrow outcomes changed since the file was prepared, prepare it again"
+        }));
+    }
+    if reputation.stats.total_reviews != current.stats.total_reviews
+        || reputation.stats.rating_distribution != current.stats.rating_distribution
+        || (reputation.stats.average_rating - current.stats.average_rating).abs() >= 0.01
+    {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Reputation stats do not match the reviews"
+        }));
+    }
+    let same_params = reputation.score.map(|s| s.params) == Some(policy.scoring);
+    if !same_params || !verify_reputation_score(reputation) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Reputation score does not match the reviews"
+        }));
+    }
 
-    // 9. Serialize to JSON
-    let json_bytes = match serde_json::to_vec_pretty(&reputation) {
+    // 8. Serialize to JSON
+    let json_bytes = match serde_json::to_vec_pretty(reputation) {
         Ok(bytes) => bytes,
         Err(e) => {
             tracing::error!(
@@ -159,8 +438,11 @@ pub async fn export_to_ipfs(
 
     let file_size = json_bytes.len();
 
-    // 10. Upload to IPFS
-    let ipfs_hash = match ipfs.add(json_bytes, "reputation.json", "application/json").await {
+    // 9. Upload to IPFS
+    let ipfs_hash = match ipfs
+        .add(json_bytes, "reputation.json", "application/json")
+        .await
+    {
         Ok(hash) => hash,
         Err(e) => {
             tracing::error!(
@@ -176,7 +458,7 @@ pub async fn export_to_ipfs(
         }
     };
 
-    // 11. Build gateway URL
+    // 10. Build gateway URL
     let gateway_url = format!("http://127.0.0.1:8080/ipfs/{}", ipfs_hash);
 
     tracing::info!(
@@ -195,3 +477,222 @@ pub async fn export_to_ipfs(
         gateway_url,
     })
 }
+
+/// POST /api/reputation/import
+///
+/// Import a signed reputation file exported from another marketplace. The
+/// file must be signed by the reputation key the vendor registered here;
+/// see [`verify_imported_reputation`] for what else is checked.
+///
+/// Importing again from the same marketplace(s) replaces the earlier file.
+///
+/// # Security
+/// - Session authentication required (SameSite=Strict cookies provide CSRF protection)
+/// - Authorization: the file is imported for the session's vendor only
+///
+/// # Request Body
+/// ```json
+/// {
+///   "cid": "Qm..."
+/// }
+/// ```
+///
+/// # Errors
+/// - 401: Not authenticated
+/// - 400: Invalid CID, no reputation key, file too large or not verifiable
+/// - 502: File could not be fetched from IPFS
+/// - 500: Database error
+pub async fn import_from_ipfs(
+    pool: web::Data<DbPool>,
+    ipfs: web::Data<IpfsClient>,
+    policy: web::Data<ReviewPolicy>,
+    session: Session,
+    req: web::Json<ImportRequest>,
+) -> impl Responder {
+    // 1. Authentication check
+    let vendor_id = match session.get::<String>("user_id") {
+        Ok(Some(id)) => id,
+        _ => {
+            tracing::warn!("Unauthenticated reputation import attempt");
+            return HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Authentication required"
+            }));
+        }
+    };
+
+    // 2. Validate CID
+    let cid = req.cid.trim();
+    if !is_valid_cid(cid) {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Invalid CID"
+        }));
+    }
+
+    // 3. The file must be for the vendor's registered key
+    let vendor_key = match db_get_reputation_key(&pool, &vendor_id).await {
+        Ok(Some(key)) => key,
+        Ok(None) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Register your reputation key before importing"
+            }));
+        }
+        Err(e) => {
+            tracing::error!(vendor_id = %vendor_id, error = %e, "Error loading reputation key");
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }));
+        }
+    };
+
+    // 4. Fetch and parse the file
+    let bytes = match ipfs.cat(cid).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!(error = %e, cid = %cid, "Reputation file download failed");
+            return HttpResponse::BadGateway().json(serde_json::json!({
+                "error": "Could not fetch the reputation file from IPFS"
+            }));
+        }
+    };
+    if bytes.len() > MAX_IMPORT_SIZE {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "Reputation file is too large"
+        }));
+    }
+    let reputation: VendorReputation = match serde_json::from_slice(&bytes) {
+        Ok(r) => r,
+        Err(e) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Not a reputation file: {}", e)
+            }));
+        }
+    };
+
+    // 5. Verify every review and the file signature
+    let summary = match verify_imported_reputation(&reputation, &vendor_key, &policy) {
+        Ok(summary) => summary,
+        Err(e) => {
+            tracing::warn!(vendor_id = %vendor_id, cid = %cid, error = %e, "Reputation import rejected");
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": format!("Reputation file rejected: {}", e)
+            }));
+        }
+    };
+
+    // 6. Store it
+    let imported = ImportedReputation {
+        id: Uuid::new_v4().to_string(),
+        vendor_id: vendor_id.clone(),
+        ipfs_cid: cid.to_string(),
+        vendor_pubkey: vendor_key,
+        source_domains: summary.source_domains.join(","),
+        total_reviews: reputation.stats.total_reviews as i32,
+        unbound_reviews: summary.unbound_reviews as i32,
+        average_rating: f64::from(reputation.stats.average_rating),
+        generated_at: reputation.generated_at.naive_utc(),
+        reputation_json: String::from_utf8_lossy(&bytes).into_owned(),
+        imported_at: chrono::Utc::now().naive_utc(),
+    };
+    match db_insert_imported_reputation(&pool, imported).await {
+        Ok(imported) => {
+            tracing::info!(
+                vendor_id = %vendor_id,
+                cid = %cid,
+                total_reviews = imported.total_reviews,
+                "Reputation imported from IPFS"
+            );
+            HttpResponse::Created().json(serde_json::json!({
+                "status": "success",
+                "imported": imported
+            }))
+        }
+        Err(e) => {
+            tracing::error!(vendor_id = %vendor_id, error = %e, "Failed to store imported reputation");
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database error"
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+    use reputation_common::types::ReviewBinding;
+    use reputation_crypto::reputation::{
+        public_key_b64, sign_bound_review, sign_reputation, sign_review,
+    };
+
+    fn signed_file(vendor_key: &SigningKey, domain: &str) -> VendorReputation {
+        let buyer_key = SigningKey::from_bytes(&[9u8; 32]);
+        let binding = ReviewBinding {
+            vendor_pubkey: public_key_b64(vendor_key),
+            escrow_id: Uuid::new_v4().to_string(),
+            marketplace_domain: domain.to_string(),
+        };
+        let reviews = vec![
+            sign_bound_review("a".repeat(64), 5, None, binding, &buyer_key).unwrap(),
+            sign_review("b".repeat(64), 3, None, &buyer_key).unwrap(),
+        ];
+        let mut reputation = VendorReputation::new(public_key_b64(vendor_key));
+        reputation.stats = calculate_stats(&reviews);
+        reputation.reviews = reviews;
+        sign_reputation(reputation, vendor_key).unwrap()
+    }
+
+    #[test]
+    fn test_verify_imported_reputation() {
+        let vendor_key = SigningKey::from_bytes(&[7u8; 32]);
+        let policy = ReviewPolicy {
+            accept_unbound_reviews: true,
+            ..ReviewPolicy::default()
+        };
+        let file = signed_file(&vendor_key, "other.example.onion");
+
+        let summary =
+            verify_imported_reputation(&file, &public_key_b64(&vendor_key), &policy).unwrap();
+        assert_eq!(
+            summary.source_domains,
+            vec!["other.example.onion".to_string()]
+        );
+        assert_eq!(summary.unbound_reviews, 1);
+
+        // Another vendor's key
+        let other = public_key_b64(&SigningKey::from_bytes(&[8u8; 32]));
+        assert!(verify_imported_reputation(&file, &other, &policy).is_err());
+
+        // 1.0 reviews while unbound reviews are refused
+        assert!(verify_imported_reputation(
+            &file,
+            &public_key_b64(&vendor_key),
+            &ReviewPolicy::default()
+        )
+        .is_err());
+
+        // Tampered stats
+        let mut tampered = file.clone();
+        tampered.stats.average_rating = 5.0;
+        assert!(
+            verify_imported_reputation(&tampered, &public_key_b64(&vendor_key), &policy).is_err()
+        );
+    }
+
+    #[test]
+    fn test_reject_reviews_from_this_marketplace() {
+        let vendor_key = SigningKey::from_bytes(&[7u8; 32]);
+        let policy = ReviewPolicy::default();
+        let file = signed_file(&vendor_key, &policy.marketplace_domain);
+
+        assert!(verify_imported_reputation(&file, &public_key_b64(&vendor_key), &policy).is_err());
+    }
+
+    #[test]
+    fn test_is_valid_cid() {
+        assert!(is_valid_cid(
+            "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG"
+        ));
+        assert!(!is_valid_cid("Qm../../etc/passwd"));
+        assert!(!is_valid_cid(""));
+    }
+}
@@ -1,11 +1,13 @@
 //! User API handlers for profile and escrow management
 
 use actix_session::Session;
-use actix_web::{web, HttpResponse, Responder};
-use serde::Serialize;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 
 use crate::db::DbPool;
+use crate::middleware::csrf::validate_csrf_token;
+use crate::models::encryption_key::UserEncryptionKey;
 use crate::models::escrow::Escrow;
 
 /// Response struct for user escrow list
@@ -101,3 +103,98 @@ pub async fn get_user_escrows(
 
     HttpResponse::Ok().json(all_escrows)
 }
+
+/// Request body for registering a message encryption key
+#[derive(Debug, Deserialize)]
+pub struct EncryptionKeyRequest {
+    /// Raw uncompressed P-256 public key, base64
+    pub public_key: String,
+}
+
+/// GET /api/user/encryption-key - Public key registered by the authenticated user
+///
+/// Lets the client check that its locally stored private key still matches.
+pub async fn get_encryption_key(pool: web::Data<DbPool>, session: Session) -> impl Responder {
+    let user_id = match session.get::<String>("user_id") {
+        Ok(Some(uid)) => uid,
+        _ => {
+            return HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Not authenticated"
+            }));
+        }
+    };
+
+    let mut conn = match pool.get() {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database connection failed"
+            }));
+        }
+    };
+
+    match UserEncryptionKey::find_by_user_id(&mut conn, &user_id) {
+        Ok(Some(key)) => HttpResponse::Ok().json(key),
+        Ok(None) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "No encryption key registered"
+        })),
+        Err(e) => {
+            error!("Failed to load encryption key: {:?}", e);
+            HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Failed to load encryption key"
+            }))
+        }
+    }
+}
+
+/// PUT /api/user/encryption-key - Register or rotate the message encryption key
+///
+/// Only the public key is sent; the private key stays in the browser.
+/// Requires authentication and CSRF protection.
+pub async fn set_encryption_key(
+    pool: web::Data<DbPool>,
+    session: Session,
+    http_req: HttpRequest,
+    req: web::Json<EncryptionKeyRequest>,
+) -> impl Responder {
+    let csrf_token = http_req
+        .headers()
+        .get("X-CSRF-Token")
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("");
+    if !validate_csrf_token(&session, csrf_token) {
+        return HttpResponse::Forbidden().json(serde_json::json!({
+            "error": "Invalid or missing CSRF token"
+        }));
+    }
+
+    let user_id = match session.get::<String>("user_id") {
+        Ok(Some(uid)) => uid,
+        _ => {
+            return HttpResponse::Unauthorized().json(serde_json::json!({
+                "error": "Not authenticated"
+            }));
+        }
+    };
+
+    let mut conn = match pool.get() {
+        Ok(c) => c,
+        Err(e) => {
+            error!("Database connection error: {}", e);
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": "Database connection failed"
+            }));
+        }
+    };
+
+    match UserEncryptionKey::upsert(&mut conn, &user_id, &req.public_key) {
+        Ok(key) => {
+            info!("Encryption key registered for user {}", user_id);
+            HttpResponse::Ok().json(key)
+        }
+        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("{:#}", e)
+        })),
+    }
+}
@@ -0,0 +1,306 @@
+//! Vendor bond API handlers
+//!
+//! Vendors post a bond to raise their listing limit and get the remainder
+//! back when they retire. Arbiters slash bonds into dispute payouts.
+
+use actix_session::Session;
+use actix_web::{get, post, web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::db::DbPool;
+use crate::handlers::auth::get_user_id_from_session;
+use crate::models::vendor_bond::{VendorBond, VendorBondSlash};
+use crate::services::vendor_bond::VendorBondService;
+
+/// Request body for opening a vendor bond
+#[derive(Debug, Deserialize, Validate)]
+pub struct OpenBondRequest {
+    /// Bond amount in atomic units (piconeros)
+    #[validate(range(min = 1, message = "Bond amount must be positive"))]
+    pub amount_atomic: i64,
+}
+
+/// Request body for retiring (bond release)
+#[derive(Debug, Deserialize, Validate)]
+pub struct RetireRequest {
+    /// Address receiving the remaining bond (checked by the service, which
+    /// also accepts integrated addresses)
+    pub payout_address: String,
+}
+
+/// Request body for slashing a vendor bond (arbiter only)
+#[derive(Debug, Deserialize, Validate)]
+pub struct SlashBondRequest {
+    /// Amount taken from the bond, in atomic units
+    #[validate(range(min = 1, message = "Slash amount must be positive"))]
+    pub amount_atomic: i64,
+    #[validate(length(min = 3, max = 1000, message = "Reason must be 3-1000 characters"))]
+    pub reason: String,
+}
+
+/// Response body for a vendor bond
+#[derive(Debug, Serialize)]
+pub struct VendorBondResponse {
+    pub id: String,
+    pub status: String,
+    pub amount_atomic: i64,
+    pub remaining_atomic: i64,
+    pub slashed_atomic: i64,
+    pub multisig_address: Option<String>,
+    pub release_tx_hash: Option<String>,
+    pub created_at: String,
+    pub funded_at: Option<String>,
+    pub released_at: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub slashes: Vec<VendorBondSlashResponse>,
+}
+
+/// Response body for one slash of a bond
+#[derive(Debug, Serialize)]
+pub struct VendorBondSlashResponse {
+    pub escrow_id: String,
+    pub amount_atomic: i64,
+    pub tx_hash: String,
+    pub reason: String,
+    pub created_at: String,
+}
+
+impl From<VendorBond> for VendorBondResponse {
+    fn from(bond: VendorBond) -> Self {
+        Self {
+            remaining_atomic: bond.remaining(),
+            id: bond.id,
+            status: bond.status,
+            amount_atomic: bond.amount,
+            slashed_atomic: bond.slashed_amount,
+            multisig_address: bond.multisig_address,
+            release_tx_hash: bond.release_tx_hash,
+            created_at: bond.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            funded_at: bond.funded_at.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string()),
+            released_at: bond.released_at.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string()),
+            slashes: Vec::new(),
+        }
+    }
+}
+
+impl From<VendorBondSlash> for VendorBondSlashResponse {
+    fn from(slash: VendorBondSlash) -> Self {
+        Self {
+            escrow_id: slash.escrow_id,
+            amount_atomic: slash.amount,
+            tx_hash: slash.tx_hash,
+            reason: slash.reason,
+            created_at: slash.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// POST /api/vendor/bond - Open a vendor bond
+///
+/// Requires authentication and vendor role. Returns the bond with the
+/// multisig address the vendor must fund.
+#[post("/vendor/bond")]
+pub async fn open_bond(
+    bond_service: web::Data<VendorBondService>,
+    session: Session,
+    req: web::Json<OpenBondRequest>,
+) -> impl Responder {
+    if let Err(e) = req.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Validation error: {}", e)
+        }));
+    }
+
+    let vendor_id = match get_user_id_from_session(&session) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match bond_service.open_bond(vendor_id, req.amount_atomic).await {
+        Ok(bond) => HttpResponse::Created().json(VendorBondResponse::from(bond)),
+        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Failed to open vendor bond: {}", e)
+        })),
+    }
+}
+
+/// GET /api/vendor/bond - Latest bond of the authenticated vendor, with slashes
+#[get("/vendor/bond")]
+pub async fn get_bond(pool: web::Data<DbPool>, session: Session) -> impl Responder {
+    let vendor_id = match get_user_id_from_session(&session) {
+        Ok(id) => id.to_string(),
+        Err(response) => return response,
+    };
+
+    let bond_result = web::block(move || -> anyhow::Result<Option<VendorBondResponse>> {
+        let mut conn = pool.get()?;
+        let Some(bond) = VendorBond::find_latest_by_vendor(&mut conn, &vendor_id)? else {
+            return Ok(None);
+        };
+        let slashes = bond.slashes(&mut conn)?;
+
+        let mut response = VendorBondResponse::from(bond);
+        response.slashes = slashes.into_iter().map(VendorBondSlashResponse::from).collect();
+        Ok(Some(response))
+    })
+    .await;
+
+    match bond_result {
+        Ok(Ok(Some(bond))) => HttpResponse::Ok().json(bond),
+        Ok(Ok(None)) => HttpResponse::NotFound().json(serde_json::json!({
+            "error": "No vendor bond"
+        })),
+        Ok(Err(e)) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to load vendor bond: {}", e)
+        })),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Async task failed: {}", e)
+        })),
+    }
+}
+
+/// POST /api/vendor/bond/check - Check the bond deposit and activate the bond
+#[post("/vendor/bond/check")]
+pub async fn check_bond_funding(
+    pool: web::Data<DbPool>,
+    bond_service: web::Data<VendorBondService>,
+    session: Session,
+) -> impl Responder {
+    let vendor_id = match get_user_id_from_session(&session) {
+        Ok(id) => id.to_string(),
+        Err(response) => return response,
+    };
+
+    let bond_result = web::block(move || -> anyhow::Result<Option<VendorBond>> {
+        let mut conn = pool.get()?;
+        VendorBond::find_open_by_vendor(&mut conn, &vendor_id)
+    })
+    .await;
+
+    let bond_id = match bond_result {
+        Ok(Ok(Some(bond))) => match bond.id.parse::<Uuid>() {
+            Ok(id) => id,
+            Err(_) => {
+                return HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "Invalid vendor bond id"
+                }))
+            }
+        },
+        Ok(Ok(None)) => {
+            return HttpResponse::NotFound().json(serde_json::json!({
+                "error": "No open vendor bond"
+            }))
+        }
+        Ok(Err(e)) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Failed to load vendor bond: {}", e)
+            }))
+        }
+        Err(e) => {
+            return HttpResponse::InternalServerError().json(serde_json::json!({
+                "error": format!("Async task failed: {}", e)
+            }))
+        }
+    };
+
+    match bond_service.refresh_funding(bond_id).await {
+        Ok(bond) => HttpResponse::Ok().json(VendorBondResponse::from(bond)),
+        Err(e) => HttpResponse::InternalServerError().json(serde_json::json!({
+            "error": format!("Failed to check vendor bond funding: {}", e)
+        })),
+    }
+}
+
+/// POST /api/vendor/bond/retire - Retire and get the remaining bond back
+///
+/// Deactivates all listings. Refused while the vendor has open orders.
+#[post("/vendor/bond/retire")]
+pub async fn retire_vendor(
+    bond_service: web::Data<VendorBondService>,
+    session: Session,
+    req: web::Json<RetireRequest>,
+) -> impl Responder {
+    if let Err(e) = req.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Validation error: {}", e)
+        }));
+    }
+
+    let vendor_id = match get_user_id_from_session(&session) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    match bond_service
+        .retire_vendor(vendor_id, req.payout_address.clone())
+        .await
+    {
+        Ok(tx_hash) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "tx_hash": tx_hash,
+            "message": "Vendor bond released, listings deactivated"
+        })),
+        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Failed to retire: {}", e)
+        })),
+    }
+}
+
+/// POST /api/escrow/{id}/slash-bond - Slash the vendor's bond into the dispute payout
+///
+/// Only the escrow's assigned arbiter can slash, while the escrow is disputed
+/// or being resolved in the buyer's favour. The slashed amount goes to the
+/// buyer's registered wallet address.
+#[post("/escrow/{id}/slash-bond")]
+pub async fn slash_bond(
+    bond_service: web::Data<VendorBondService>,
+    session: Session,
+    path: web::Path<String>,
+    req: web::Json<SlashBondRequest>,
+) -> impl Responder {
+    if let Err(e) = req.validate() {
+        return HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Validation error: {}", e)
+        }));
+    }
+
+    let arbiter_id = match get_user_id_from_session(&session) {
+        Ok(id) => id,
+        Err(response) => return response,
+    };
+
+    let escrow_id = match path.into_inner().parse::<Uuid>() {
+        Ok(id) => id,
+        Err(_) => {
+            return HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Invalid escrow_id"
+            }))
+        }
+    };
+
+    match bond_service
+        .slash_bond(
+            escrow_id,
+            arbiter_id,
+            req.amount_atomic,
+            req.reason.clone(),
+        )
+        .await
+    {
+        Ok(tx_hash) => HttpResponse::Ok().json(serde_json::json!({
+            "success": true,
+            "tx_hash": tx_hash,
+            "message": "Vendor bond slashed into dispute payout"
+        })),
+        Err(e) if e.to_string().contains("Only assigned arbiter") => {
+            HttpResponse::Forbidden().json(serde_json::json!({
+                "error": e.to_string()
+            }))
+        }
+        Err(e) => HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!("Failed to slash vendor bond: {}", e)
+        })),
+    }
+}
@@ -9,7 +9,7 @@ use actix_web_actors::ws;
 use anyhow::{Context, Result};
 use monero_marketplace_common::types::MoneroConfig;
 use server::db::create_pool;
-use server::handlers::{auth, cart, escrow, frontend, listings, messages, monitoring, multisig_challenge, noncustodial, orders, reputation, reputation_ipfs, user};
+use server::handlers::{arbiter, audit, auth, cart, dispute_evidence, escrow, frontend, listings, messages, monitoring, multisig_challenge, noncustodial, orders, prices, reputation, reputation_ipfs, user, vendor_bond};
 use server::middleware::{
     admin_auth::AdminAuth,
     // rate_limit::{global_rate_limiter, protected_rate_limiter}, // Temporarily disabled for testing
@@ -130,6 +130,9 @@ async fn main() -> Result<()> {
 
     // 6. Initialize Wallet Manager with persistence and automatic recovery
     let encryption_key = hex::decode(&db_encryption_key).context("Failed to hex decode DB_ENCRYPTION_KEY")?;
+    // Keys retired by `rotate_key`, readable until the re-encryption job below finishes
+    let previous_key_count = server::crypto::encryption::load_previous_keys_from_env()
+        .context("Failed to load previous encryption keys")?;
     let wallet_manager = {
         // Configure 6 RPC instances with failover (2 per role)
         // Pattern: index % 3 determines role (0=Buyer, 1=Vendor, 2=Arbiter)
@@ -303,13 +306,32 @@ async fn main() -> Result<()> {
         timeout_config.funding_timeout_secs,
         timeout_config.transaction_confirmation_timeout_secs
     );
+    let dispute_evidence_window = timeout_config.dispute_evidence_window();
 
-    let timeout_monitor = Arc::new(TimeoutMonitor::new_with_persistence(
-        pool.clone(),
-        websocket_server.clone(),
-        timeout_config,
-        encryption_key.clone(),
-    ));
+    // 9a. Arbiter pool (workload balancing, recusal, reassignment on dispute timeout)
+    use server::config::ArbiterPoolConfig;
+    use server::services::arbiter_assignment::ArbiterAssignmentService;
+    let arbiter_pool_config = ArbiterPoolConfig::from_env();
+    info!(
+        "ArbiterPoolConfig loaded: max_open_disputes={}, max_escalation_reassignments={}",
+        arbiter_pool_config.max_open_disputes, arbiter_pool_config.max_escalation_reassignments
+    );
+    let arbiter_assignment_service = Arc::new(
+        ArbiterAssignmentService::new(pool.clone(), arbiter_pool_config.clone())
+            .with_websocket(websocket_server.clone())
+            .with_coordinator(escrow_coordinator.clone()),
+    );
+
+    let timeout_monitor = Arc::new(
+        TimeoutMonitor::new_with_persistence(
+            pool.clone(),
+            websocket_server.clone(),
+            timeout_config,
+            encryption_key.clone(),
+        )
+        .with_wallet_manager(wallet_manager.clone()) // Refunds partial deposits on funding timeout
+        .with_arbiter_assignment(arbiter_assignment_service.clone()), // Reassigns timed-out disputes
+    );
 
     // Spawn TimeoutMonitor in background
     let timeout_monitor_handle = timeout_monitor.clone();
@@ -318,6 +340,38 @@ async fn main() -> Result<()> {
     });
     info!("TimeoutMonitor background service started");
 
+    // Purge shipping addresses of settled orders after the retention period
+    use server::config::RetentionPolicy;
+    use server::models::order::Order;
+
+    let retention_policy = RetentionPolicy::from_env();
+    info!(
+        "RetentionPolicy loaded: shipping_address={} days after completion/refund",
+        retention_policy.shipping_address_days
+    );
+    let retention_pool = pool.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(retention_policy.purge_interval());
+        loop {
+            interval.tick().await;
+
+            let cutoff = retention_policy.shipping_cutoff(chrono::Utc::now().naive_utc());
+            let pool = retention_pool.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                let mut conn = pool.get().context("Failed to get DB connection")?;
+                Order::purge_shipping_data(&mut conn, cutoff)
+            })
+            .await;
+
+            match result {
+                Ok(Ok(0)) => {}
+                Ok(Ok(purged)) => info!("Purged shipping data of {} settled orders", purged),
+                Ok(Err(e)) => tracing::error!("Failed to purge shipping data: {:#}", e),
+                Err(e) => tracing::error!("Shipping data purge task failed: {}", e),
+            }
+        }
+    });
+
     // 🚀 [PHASE 2] Initialize WalletSessionManager for persistent wallet sessions
     use server::services::wallet_session_manager::WalletSessionManager;
     let wallet_pool = wallet_manager.lock().await.wallet_pool()
@@ -329,13 +383,23 @@ async fn main() -> Result<()> {
     );
 
     // Initialize and start BlockchainMonitor for automatic payment detection
+    use server::config::ConfirmationPolicy;
     use server::services::blockchain_monitor::{BlockchainMonitor, MonitorConfig};
+    let confirmation_policy = ConfirmationPolicy::from_env();
+    info!(
+        "ConfirmationPolicy loaded: base_confirmations={}, tiers={}",
+        confirmation_policy.base_confirmations,
+        confirmation_policy.tiers.len()
+    );
     let blockchain_monitor = Arc::new(BlockchainMonitor::new(
         wallet_manager.clone(),
         wallet_session_manager.clone(),  // 🚀 [PHASE 2] Pass session manager
         pool.clone(),
         websocket_server.clone(),
-        MonitorConfig::default(), // poll_interval: 30s, required_confirmations: 10
+        MonitorConfig {
+            confirmations: confirmation_policy.clone(),
+            ..MonitorConfig::default() // poll_interval: 30s
+        },
     ));
 
     let blockchain_monitor_handle = blockchain_monitor.clone();
@@ -385,9 +449,121 @@ async fn main() -> Result<()> {
         pool.clone(),
         websocket_server.clone(),
         encryption_key.clone(),
-    ));
+    )
+    .with_confirmation_policy(confirmation_policy.clone())
+    .with_arbiter_pool(arbiter_pool_config.clone()));
     info!("✅ EscrowOrchestrator initialized with WalletSessionManager - [PHASE 2]");
 
+    // 13. Vendor bonds (listing limits, slashing, release on retirement)
+    use server::config::BondPolicy;
+    use server::services::vendor_bond::VendorBondService;
+    let bond_policy = BondPolicy::from_env();
+    info!(
+        "BondPolicy loaded: unbonded_listing_limit={}, tiers={}",
+        bond_policy.unbonded_listing_limit,
+        bond_policy.tiers.len()
+    );
+    let vendor_bond_service = Arc::new(VendorBondService::new(
+        wallet_manager.clone(),
+        pool.clone(),
+        websocket_server.clone(),
+        bond_policy.clone(),
+    ));
+
+    // 14. Dispute evidence (encrypted at rest, local blobs or IPFS)
+    use server::services::dispute_evidence::{DisputeEvidenceService, EvidenceStore};
+    let evidence_store = EvidenceStore::from_env(ipfs_client.clone());
+    info!("Dispute evidence storage: {}", evidence_store.backend());
+    let dispute_evidence_service = Arc::new(DisputeEvidenceService::new(
+        pool.clone(),
+        evidence_store,
+        encryption_key.clone(),
+        dispute_evidence_window,
+    ));
+
+    // Re-encrypt fields and evidence left under a previous key
+    if previous_key_count > 0 {
+        use server::services::key_rotation::KeyRotationService;
+        info!(
+            "{} previous encryption key(s) loaded, re-encrypting data under the current key",
+            previous_key_count
+        );
+        let key_rotation = KeyRotationService::new(pool.clone(), encryption_key.clone())
+            .with_evidence(dispute_evidence_service.clone());
+        tokio::spawn(async move {
+            if let Err(e) = key_rotation.run().await {
+                tracing::error!("Key rotation re-encryption failed: {:#}", e);
+            }
+        });
+    }
+
+    // 15. Hash-chained audit log (admin actions, wallet address changes; payouts via the orchestrator)
+    use server::services::audit::AuditLogger;
+    let audit_logger = AuditLogger::new(pool.clone());
+
+    // 16. Signed audit checkpoints (head hash signed with the marketplace key)
+    use server::config::AuditCheckpointPolicy;
+    use server::crypto::signing::{MarketplaceSigner, SIGNING_KEY_ENV};
+    use server::services::audit_checkpoint::AuditCheckpointer;
+    match MarketplaceSigner::from_env().context("Failed to load marketplace signing key")? {
+        Some(signer) => {
+            let checkpoint_policy = AuditCheckpointPolicy::from_env();
+            info!(
+                "Audit checkpoints every {}s, signed by {}",
+                checkpoint_policy.interval_secs,
+                signer.public_key_hex()
+            );
+            let checkpointer = Arc::new(AuditCheckpointer::new(
+                pool.clone(),
+                signer,
+                checkpoint_policy,
+            ));
+            tokio::spawn(checkpointer.start());
+        }
+        None => tracing::warn!("{} not set - audit checkpoints disabled", SIGNING_KEY_ENV),
+    }
+
+    // 17. Price oracle (median of several sources, history in price_history)
+    use server::config::PriceOracleConfig;
+    use server::services::price_conversion::PriceOracle;
+    let price_config = PriceOracleConfig::from_env();
+    info!(
+        "Price oracle: {} in {}, refreshed every {}s",
+        price_config.sources.join(", "),
+        price_config.currencies.join(", "),
+        price_config.refresh_secs
+    );
+    let price_oracle = Arc::new(
+        PriceOracle::new(pool.clone(), price_config).context("Failed to initialize price oracle")?,
+    );
+    tokio::spawn(price_oracle.clone().start());
+
+    // 18. Fiat-pegged prices (listing repricing, requote of expired order quotes)
+    use server::services::fiat_pricing::FiatPricing;
+    let fiat_pricing = Arc::new(FiatPricing::new(pool.clone(), price_oracle.clone()));
+    tokio::spawn(fiat_pricing.clone().start());
+
+    // 19. Review binding (2.0 reviews signed for vendor, escrow and marketplace domain)
+    use server::config::ReviewPolicy;
+    let review_policy = ReviewPolicy::from_env().context("Failed to load review policy")?;
+    info!(
+        "Reviews bound to {}, unbound 1.0 reviews {}",
+        review_policy.marketplace_domain,
+        if review_policy.accept_unbound_reviews { "accepted" } else { "rejected" }
+    );
+
+    // 20. Review payment proofs (tx proofs against the escrow multisig address)
+    use server::services::payment_proof::PaymentProofService;
+    let payment_proof_service = Arc::new(
+        PaymentProofService::new(
+            pool.clone(),
+            wallet_manager.clone(),
+            review_policy.clone(),
+            confirmation_policy.clone(),
+        )
+        .context("Failed to initialize review payment proofs")?,
+    );
+
     info!("Starting HTTP server on http://127.0.0.1:8080");
 
     // 12. Start HTTP server
@@ -418,6 +594,15 @@ async fn main() -> Result<()> {
             // Shared app state
             .app_data(web::Data::new(pool.clone()))
             .app_data(web::Data::from(escrow_orchestrator.clone()))
+            .app_data(web::Data::from(vendor_bond_service.clone()))
+            .app_data(web::Data::from(dispute_evidence_service.clone()))
+            .app_data(web::Data::from(arbiter_assignment_service.clone()))
+            .app_data(web::Data::new(audit_logger.clone()))
+            .app_data(web::Data::from(price_oracle.clone()))
+            .app_data(web::Data::from(fiat_pricing.clone()))
+            .app_data(web::Data::from(payment_proof_service.clone()))
+            .app_data(web::Data::new(bond_policy.clone()))
+            .app_data(web::Data::new(review_policy.clone()))
             .app_data(web::Data::from(escrow_coordinator.clone()))
             .app_data(web::Data::new(websocket_server.clone()))
             .app_data(web::Data::new(tera.clone()))
@@ -497,16 +682,24 @@ async fn main() -> Result<()> {
                     .service(listings::update_listing)
                     .service(listings::delete_listing)
                     .service(listings::get_vendor_dashboard_stats)
+                    // Vendor bonds
+                    .service(vendor_bond::open_bond)
+                    .service(vendor_bond::get_bond)
+                    .service(vendor_bond::check_bond_funding)
+                    .service(vendor_bond::retire_vendor)
+                    .service(vendor_bond::slash_bond)
                     .service(listings::upload_listing_images)
                     .service(listings::get_listing_image)
                     .service(listings::remove_listing_image)
                     // Orders
                     .service(orders::create_order_from_cart)
                     .service(orders::create_order)
+                    .service(orders::get_shipping_keys)
                     .service(orders::get_pending_count)
                     .service(orders::list_orders)
                     .service(orders::get_order)
                     .service(orders::init_escrow)
+                    .service(orders::requote_order)
                     .service(orders::ship_order)
                     .service(orders::complete_order)
                     .service(orders::cancel_order)
@@ -514,6 +707,7 @@ async fn main() -> Result<()> {
                     // Order Messages (Chat)
                     .service(messages::get_messages)
                     .service(messages::send_message)
+                    .service(messages::get_message_keys)
                     // Escrow
                     .route("/escrow/{id}", web::get().to(escrow::get_escrow))
                     .service(escrow::get_escrow_status)
@@ -536,6 +730,10 @@ async fn main() -> Result<()> {
                         "/escrow/{id}/dispute",
                         web::post().to(escrow::initiate_dispute),
                     )
+                    .service(dispute_evidence::submit_evidence)
+                    .service(dispute_evidence::list_evidence)
+                    .service(dispute_evidence::download_evidence)
+                    .service(arbiter::recuse)
                     // NON-CUSTODIAL: Get multisig address for escrow
                     .route(
                         "/escrow/{id}/multisig-address",
@@ -573,7 +771,24 @@ async fn main() -> Result<()> {
                     .service(multisig_challenge::submit_multisig_info_with_signature)
                     .service(multisig_challenge::cleanup_expired_challenges)
                     // Reputation System
+                    // Audit checkpoints (public)
+                    .service(audit::list_audit_checkpoints)
+                    // Exchange rates (public)
+                    .service(prices::get_prices)
+                    .service(prices::get_price_history)
                     .route("/reviews", web::post().to(reputation::submit_review))
+                    .route(
+                        "/reviews/{txid}/payment-proof",
+                        web::post().to(reputation::verify_review_payment),
+                    )
+                    .route(
+                        "/reviews/{txid}/amend",
+                        web::post().to(reputation::amend_review),
+                    )
+                    .route(
+                        "/reviews/{txid}/reply",
+                        web::post().to(reputation::reply_to_review),
+                    )
                     .route(
                         "/reputation/{vendor_id}",
                         web::get().to(reputation::get_vendor_reputation),
@@ -586,6 +801,18 @@ async fn main() -> Result<()> {
                         "/reputation/export",
                         web::post().to(reputation_ipfs::export_to_ipfs),
                     )
+                    .route(
+                        "/reputation/export/prepare",
+                        web::post().to(reputation_ipfs::prepare_export),
+                    )
+                    .route(
+                        "/reputation/import",
+                        web::post().to(reputation_ipfs::import_from_ipfs),
+                    )
+                    .route(
+                        "/reputation/key",
+                        web::post().to(reputation::register_reputation_key),
+                    )
                     // Cart endpoints
                     .route("/cart/add", web::post().to(cart::add_to_cart))
                     .route("/cart/remove", web::post().to(cart::remove_from_cart))
@@ -594,14 +821,29 @@ async fn main() -> Result<()> {
                     .route("/cart", web::get().to(cart::get_cart))
                     .route("/cart/count", web::get().to(cart::get_cart_count))
                     // User endpoints
-                    .route("/user/escrows", web::get().to(user::get_user_escrows)),
+                    .route("/user/escrows", web::get().to(user::get_user_escrows))
+                    .route(
+                        "/user/encryption-key",
+                        web::get().to(user::get_encryption_key),
+                    )
+                    .route(
+                        "/user/encryption-key",
+                        web::put().to(user::set_encryption_key),
+                    ),
             )
             // Admin-only endpoints (requires admin role)
             .service(
                 web::scope("/admin")
                     .wrap(AdminAuth)
                     .service(monitoring::get_escrow_health)
-                    .service(monitoring::get_escrow_status),
+                    .service(monitoring::get_escrow_status)
+                    .service(monitoring::get_escrow_timeline)
+                    .service(arbiter::get_workload)
+                    .service(arbiter::get_history)
+                    .service(arbiter::reassign)
+                    .service(audit::verify_audit_log)
+                    .service(audit::export_audit_log)
+                    .service(prices::set_manual_rate),
             )
     })
     .bind(("127.0.0.1", 8080))
@@ -0,0 +1,150 @@
+//! Arbiter assignment history
+//!
+//! `escrows.arbiter_id` is the arbiter currently in charge; this table keeps
+//! who held each escrow before, and why they were replaced. Assignments are
+//! changed through [`crate::services::arbiter_assignment`].
+
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::arbiter_assignments;
+
+/// Why an arbiter was given an escrow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AssignmentReason {
+    /// Picked when the escrow was created
+    Initial,
+    /// Previous arbiter recused themselves
+    Recusal,
+    /// Previous arbiter let the dispute time out
+    Escalation,
+    /// Reassigned by an admin
+    Admin,
+}
+
+impl AssignmentReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssignmentReason::Initial => "initial",
+            AssignmentReason::Recusal => "recusal",
+            AssignmentReason::Escalation => "escalation",
+            AssignmentReason::Admin => "admin",
+        }
+    }
+
+    /// `end_reason` recorded on the assignment being replaced
+    pub fn end_reason(&self) -> &'static str {
+        match self {
+            AssignmentReason::Recusal => "recused",
+            AssignmentReason::Escalation => "escalated",
+            AssignmentReason::Initial | AssignmentReason::Admin => "replaced",
+        }
+    }
+}
+
+/// Arbiter assignment database model
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[diesel(table_name = arbiter_assignments)]
+pub struct ArbiterAssignment {
+    pub id: String,
+    pub escrow_id: String,
+    pub arbiter_id: String,
+    pub reason: String,
+    pub assigned_at: NaiveDateTime,
+    /// None while the arbiter is in charge
+    pub ended_at: Option<NaiveDateTime>,
+    /// "recused", "escalated" or "replaced"
+    pub end_reason: Option<String>,
+    pub note: Option<String>,
+}
+
+/// New arbiter assignment for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = arbiter_assignments)]
+pub struct NewArbiterAssignment {
+    pub id: String,
+    pub escrow_id: String,
+    pub arbiter_id: String,
+    pub reason: String,
+    pub note: Option<String>,
+}
+
+impl ArbiterAssignment {
+    /// Record `arbiter_id` taking charge of an escrow
+    pub fn start(
+        conn: &mut SqliteConnection,
+        escrow_id: &str,
+        arbiter_id: &str,
+        reason: AssignmentReason,
+        note: Option<&str>,
+    ) -> Result<Self> {
+        let new_assignment = NewArbiterAssignment {
+            id: Uuid::new_v4().to_string(),
+            escrow_id: escrow_id.to_string(),
+            arbiter_id: arbiter_id.to_string(),
+            reason: reason.as_str().to_string(),
+            note: note.map(str::to_string),
+        };
+
+        diesel::insert_into(arbiter_assignments::table)
+            .values(&new_assignment)
+            .execute(conn)
+            .context("Failed to insert arbiter assignment")?;
+
+        arbiter_assignments::table
+            .find(&new_assignment.id)
+            .first(conn)
+            .context("Failed to retrieve created arbiter assignment")
+    }
+
+    /// Close the open assignment of an escrow, if any
+    pub fn end_open(
+        conn: &mut SqliteConnection,
+        escrow_id: &str,
+        end_reason: &str,
+        note: Option<&str>,
+    ) -> Result<usize> {
+        diesel::update(
+            arbiter_assignments::table
+                .filter(arbiter_assignments::escrow_id.eq(escrow_id))
+                .filter(arbiter_assignments::ended_at.is_null()),
+        )
+        .set((
+            arbiter_assignments::ended_at.eq(diesel::dsl::now),
+            arbiter_assignments::end_reason.eq(end_reason),
+            arbiter_assignments::note.eq(note),
+        ))
+        .execute(conn)
+        .context(format!("Failed to end arbiter assignment for escrow {}", escrow_id))
+    }
+
+    /// Assignment history of an escrow, oldest first
+    pub fn find_by_escrow(conn: &mut SqliteConnection, escrow_id: &str) -> Result<Vec<Self>> {
+        arbiter_assignments::table
+            .filter(arbiter_assignments::escrow_id.eq(escrow_id))
+            .order((
+                arbiter_assignments::assigned_at.asc(),
+                arbiter_assignments::ended_at.is_null().asc(),
+            ))
+            .load(conn)
+            .context(format!("Failed to load arbiter assignments for escrow {}", escrow_id))
+    }
+
+    /// Times an escrow was handed to a new arbiter for `reason`
+    pub fn count_by_reason(
+        conn: &mut SqliteConnection,
+        escrow_id: &str,
+        reason: AssignmentReason,
+    ) -> Result<i64> {
+        arbiter_assignments::table
+            .filter(arbiter_assignments::escrow_id.eq(escrow_id))
+            .filter(arbiter_assignments::reason.eq(reason.as_str()))
+            .count()
+            .get_result(conn)
+            .context("Failed to count arbiter assignments")
+    }
+}
@@ -0,0 +1,85 @@
+//! Signed checkpoints of the audit log head
+//!
+//! A checkpoint commits the marketplace, under its Ed25519 key, to the
+//! `entry_hash` of the audit log at a given sequence. Once published, any
+//! later copy of the log must contain that hash at that sequence; see
+//! [`crate::services::audit_checkpoint::verify_export`].
+
+use anyhow::{Context, Result};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::signing::verify_hex_signature;
+use crate::schema::audit_checkpoints;
+
+/// Domain separation prefix of the signed statement
+const STATEMENT_PREFIX: &str = "monero-marketplace/audit-checkpoint/v1";
+
+/// Stored (and published) audit checkpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Queryable, Insertable)]
+#[diesel(table_name = audit_checkpoints)]
+pub struct AuditCheckpoint {
+    /// `audit_log.sequence` of the signed head
+    pub sequence: i64,
+    /// `audit_log.entry_hash` at `sequence`
+    pub head_hash: String,
+    /// RFC 3339 UTC, signed as stored
+    pub created_at: String,
+    /// Signer's Ed25519 public key (hex)
+    pub public_key: String,
+    /// Ed25519 signature (hex) of [`AuditCheckpoint::statement`]
+    pub signature: String,
+}
+
+impl AuditCheckpoint {
+    /// Bytes signed for a checkpoint
+    pub fn statement(sequence: i64, head_hash: &str, created_at: &str) -> Vec<u8> {
+        format!(
+            "{}\nsequence={}\nhead_hash={}\ncreated_at={}",
+            STATEMENT_PREFIX, sequence, head_hash, created_at
+        )
+        .into_bytes()
+    }
+
+    /// Check the signature against `public_key_hex` (the published marketplace key)
+    pub fn verify_signature(&self, public_key_hex: &str) -> Result<()> {
+        if !self.public_key.eq_ignore_ascii_case(public_key_hex) {
+            anyhow::bail!(
+                "Signed by {} instead of {}",
+                self.public_key,
+                public_key_hex
+            );
+        }
+        verify_hex_signature(
+            public_key_hex,
+            &Self::statement(self.sequence, &self.head_hash, &self.created_at),
+            &self.signature,
+        )
+    }
+
+    /// Store a new checkpoint
+    pub fn insert(conn: &mut SqliteConnection, checkpoint: &AuditCheckpoint) -> Result<()> {
+        diesel::insert_into(audit_checkpoints::table)
+            .values(checkpoint)
+            .execute(conn)
+            .context("Failed to insert audit checkpoint")?;
+        Ok(())
+    }
+
+    /// Most recent checkpoint, if any
+    pub fn latest(conn: &mut SqliteConnection) -> Result<Option<Self>> {
+        audit_checkpoints::table
+            .order(audit_checkpoints::sequence.desc())
+            .first(conn)
+            .optional()
+            .context("Failed to load latest audit checkpoint")
+    }
+
+    /// All checkpoints, oldest first
+    pub fn list(conn: &mut SqliteConnection) -> Result<Vec<Self>> {
+        audit_checkpoints::table
+            .order(audit_checkpoints::sequence.asc())
+            .load(conn)
+            .context("Failed to load audit checkpoints")
+    }
+}
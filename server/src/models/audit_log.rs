@@ -0,0 +1,343 @@
+//! Hash-chained audit log
+//!
+//! Append-only record of releases, refunds, dispute resolutions, air-gap
+//! imports, admin actions and wallet address changes. Each entry carries the
+//! SHA3-256 of its content and of the previous entry; [`AuditEntry::verify_chain`]
+//! recomputes the chain and reports the first entry that does not match.
+//! Update and delete are rejected by database triggers.
+
+use anyhow::{Context, Result};
+use chrono::{SecondsFormat, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::fmt;
+
+use crate::models::escrow_event::EscrowActor;
+use crate::schema::audit_log;
+
+/// Kind of audited action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditEventType {
+    /// Escrow funds sent to the vendor
+    Release,
+    /// Escrow funds sent back to the buyer
+    Refund,
+    /// Arbiter ruling on a dispute (buyer, vendor or split)
+    DisputeResolution,
+    /// Signed decision of the offline arbiter imported
+    AirgapImport,
+    /// Privileged action from the admin scope
+    AdminAction,
+    /// User payout address set or changed
+    WalletAddressChange,
+    /// Part of a vendor bond sent to the buyer of a disputed escrow
+    BondSlash,
+    /// Remaining vendor bond paid back to a retiring vendor
+    BondRelease,
+}
+
+impl AuditEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditEventType::Release => "release",
+            AuditEventType::Refund => "refund",
+            AuditEventType::DisputeResolution => "dispute_resolution",
+            AuditEventType::AirgapImport => "airgap_import",
+            AuditEventType::AdminAction => "admin_action",
+            AuditEventType::WalletAddressChange => "wallet_address_change",
+            AuditEventType::BondSlash => "bond_slash",
+            AuditEventType::BondRelease => "bond_release",
+        }
+    }
+}
+
+impl fmt::Display for AuditEventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Stored audit log entry
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[diesel(table_name = audit_log, primary_key(sequence))]
+pub struct AuditEntry {
+    /// Position in the chain, starting at 1
+    pub sequence: i64,
+    pub event_type: String,
+    pub entity_id: String,
+    /// `user:<uuid>` or `system:<component>`
+    pub actor: String,
+    /// Event details (compact JSON)
+    pub data: String,
+    /// RFC 3339 UTC, hashed as stored
+    pub recorded_at: String,
+    pub entry_hash: String,
+    /// `entry_hash` of the previous entry (None for the first one)
+    pub previous_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = audit_log)]
+struct NewAuditEntry {
+    sequence: i64,
+    event_type: String,
+    entity_id: String,
+    actor: String,
+    data: String,
+    recorded_at: String,
+    entry_hash: String,
+    previous_hash: Option<String>,
+}
+
+/// Result of checking the whole chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainVerification {
+    /// Entries checked before the first break (all of them if valid)
+    pub entries_verified: usize,
+    /// Hash of the last valid entry
+    pub head_hash: Option<String>,
+    /// Sequence of the first entry that breaks the chain
+    pub broken_at: Option<i64>,
+    /// Why `broken_at` does not match
+    pub error: Option<String>,
+}
+
+impl ChainVerification {
+    pub fn is_valid(&self) -> bool {
+        self.broken_at.is_none()
+    }
+}
+
+/// SHA3-256 over the entry fields, each prefixed with its length
+pub fn compute_entry_hash(
+    sequence: i64,
+    event_type: &str,
+    entity_id: &str,
+    actor: &str,
+    data: &str,
+    recorded_at: &str,
+    previous_hash: Option<&str>,
+) -> String {
+    let mut hasher = Sha3_256::new();
+    hasher.update(sequence.to_be_bytes());
+    for field in [
+        event_type,
+        entity_id,
+        actor,
+        data,
+        recorded_at,
+        previous_hash.unwrap_or(""),
+    ] {
+        hasher.update((field.len() as u64).to_be_bytes());
+        hasher.update(field.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+impl AuditEntry {
+    /// Append an entry at the head of the chain
+    ///
+    /// Runs in a transaction (a savepoint when the caller already has one),
+    /// so an audited change and its entry are committed together.
+    pub fn append(
+        conn: &mut SqliteConnection,
+        event_type: AuditEventType,
+        entity_id: &str,
+        actor: &EscrowActor,
+        data: &serde_json::Value,
+    ) -> Result<Self> {
+        conn.transaction::<_, anyhow::Error, _>(|conn| {
+            let head = Self::head(conn)?;
+            let sequence = head.as_ref().map_or(1, |entry| entry.sequence + 1);
+            let previous_hash = head.map(|entry| entry.entry_hash);
+
+            let event_type = event_type.as_str().to_string();
+            let actor = actor.to_string();
+            let data = serde_json::to_string(data).context("Failed to serialize audit data")?;
+            let recorded_at = Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true);
+            let entry_hash = compute_entry_hash(
+                sequence,
+                &event_type,
+                entity_id,
+                &actor,
+                &data,
+                &recorded_at,
+                previous_hash.as_deref(),
+            );
+
+            let new_entry = NewAuditEntry {
+                sequence,
+                event_type,
+                entity_id: entity_id.to_string(),
+                actor,
+                data,
+                recorded_at,
+                entry_hash,
+                previous_hash,
+            };
+
+            diesel::insert_into(audit_log::table)
+                .values(&new_entry)
+                .execute(conn)
+                .context("Failed to insert audit log entry")?;
+
+            audit_log::table
+                .find(sequence)
+                .first(conn)
+                .context("Failed to retrieve audit log entry")
+        })
+    }
+
+    /// Latest entry, if any
+    pub fn head(conn: &mut SqliteConnection) -> Result<Option<Self>> {
+        audit_log::table
+            .order(audit_log::sequence.desc())
+            .first(conn)
+            .optional()
+            .context("Failed to load audit log head")
+    }
+
+    /// Entry at `sequence`, if any
+    pub fn find_by_sequence(conn: &mut SqliteConnection, sequence: i64) -> Result<Option<Self>> {
+        audit_log::table
+            .find(sequence)
+            .first(conn)
+            .optional()
+            .context(format!("Failed to load audit log entry {}", sequence))
+    }
+
+    /// Entries about one entity, oldest first
+    pub fn find_by_entity(conn: &mut SqliteConnection, entity_id: &str) -> Result<Vec<Self>> {
+        audit_log::table
+            .filter(audit_log::entity_id.eq(entity_id))
+            .order(audit_log::sequence.asc())
+            .load(conn)
+            .context(format!("Failed to load audit log for {}", entity_id))
+    }
+
+    /// Up to `limit` entries after `after_sequence`, oldest first
+    pub fn list_after(
+        conn: &mut SqliteConnection,
+        after_sequence: i64,
+        limit: i64,
+    ) -> Result<Vec<Self>> {
+        audit_log::table
+            .filter(audit_log::sequence.gt(after_sequence))
+            .order(audit_log::sequence.asc())
+            .limit(limit)
+            .load(conn)
+            .context("Failed to load audit log entries")
+    }
+
+    /// Recompute the chain from the first entry
+    pub fn verify_chain(conn: &mut SqliteConnection) -> Result<ChainVerification> {
+        let entries: Vec<Self> = audit_log::table
+            .order(audit_log::sequence.asc())
+            .load(conn)
+            .context("Failed to load audit log")?;
+        Ok(verify_entries(&entries))
+    }
+
+    /// Hash this entry should have
+    pub fn expected_hash(&self) -> String {
+        compute_entry_hash(
+            self.sequence,
+            &self.event_type,
+            &self.entity_id,
+            &self.actor,
+            &self.data,
+            &self.recorded_at,
+            self.previous_hash.as_deref(),
+        )
+    }
+}
+
+/// Check that `entries` (oldest first, starting at sequence 1) form an intact chain
+pub fn verify_entries(entries: &[AuditEntry]) -> ChainVerification {
+    let mut head_hash: Option<String> = None;
+
+    for (index, entry) in entries.iter().enumerate() {
+        let error = if entry.sequence != index as i64 + 1 {
+            Some(format!("expected sequence {}", index + 1))
+        } else if entry.previous_hash != head_hash {
+            Some("previous_hash does not match the preceding entry".to_string())
+        } else if entry.expected_hash() != entry.entry_hash {
+            Some("entry_hash does not match the entry content".to_string())
+        } else {
+            None
+        };
+
+        if let Some(error) = error {
+            return ChainVerification {
+                entries_verified: index,
+                head_hash,
+                broken_at: Some(entry.sequence),
+                error: Some(error),
+            };
+        }
+        head_hash = Some(entry.entry_hash.clone());
+    }
+
+    ChainVerification {
+        entries_verified: entries.len(),
+        head_hash,
+        broken_at: None,
+        error: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(len: i64) -> Vec<AuditEntry> {
+        let mut entries: Vec<AuditEntry> = Vec::new();
+        for sequence in 1..=len {
+            let previous_hash = entries.last().map(|e| e.entry_hash.clone());
+            let mut entry = AuditEntry {
+                sequence,
+                event_type: "release".to_string(),
+                entity_id: format!("escrow-{}", sequence),
+                actor: "system:test".to_string(),
+                data: "{}".to_string(),
+                recorded_at: "2025-11-16T00:00:00.000000Z".to_string(),
+                entry_hash: String::new(),
+                previous_hash,
+            };
+            entry.entry_hash = entry.expected_hash();
+            entries.push(entry);
+        }
+        entries
+    }
+
+    #[test]
+    fn test_intact_chain_verifies() {
+        let entries = chain(3);
+        let result = verify_entries(&entries);
+        assert!(result.is_valid());
+        assert_eq!(result.entries_verified, 3);
+        assert_eq!(result.head_hash.as_deref(), Some(entries[2].entry_hash.as_str()));
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let mut edited = chain(3);
+        edited[1].data = r#"{"amount":1}"#.to_string();
+        assert_eq!(verify_entries(&edited).broken_at, Some(2));
+
+        let mut removed = chain(3);
+        removed.remove(1);
+        let result = verify_entries(&removed);
+        assert_eq!(result.broken_at, Some(3));
+        assert_eq!(result.entries_verified, 1);
+    }
+
+    #[test]
+    fn test_field_boundaries_are_hashed() {
+        let a = compute_entry_hash(1, "release", "ab", "c", "{}", "t", None);
+        let b = compute_entry_hash(1, "release", "a", "bc", "{}", "t", None);
+        assert_ne!(a, b);
+    }
+}
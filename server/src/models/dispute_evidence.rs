@@ -0,0 +1,187 @@
+//! Dispute evidence model
+//!
+//! Files (photos, tracking info, message excerpts) submitted by the buyer or
+//! vendor of a disputed escrow. Only metadata lives here; the encrypted file
+//! is held by [`crate::services::dispute_evidence::EvidenceStore`].
+
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::schema::dispute_evidence;
+
+/// What a piece of evidence shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EvidenceKind {
+    /// Photo of the item or parcel
+    Photo,
+    /// Carrier tracking information or receipt
+    Tracking,
+    /// Excerpt of the order conversation
+    Message,
+    /// Any other document (invoice, customs form...)
+    Document,
+}
+
+impl EvidenceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EvidenceKind::Photo => "photo",
+            EvidenceKind::Tracking => "tracking",
+            EvidenceKind::Message => "message",
+            EvidenceKind::Document => "document",
+        }
+    }
+}
+
+impl FromStr for EvidenceKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "photo" => Ok(EvidenceKind::Photo),
+            "tracking" => Ok(EvidenceKind::Tracking),
+            "message" => Ok(EvidenceKind::Message),
+            "document" => Ok(EvidenceKind::Document),
+            _ => anyhow::bail!("Invalid evidence kind: {}", s),
+        }
+    }
+}
+
+/// Dispute evidence database model
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[diesel(table_name = dispute_evidence)]
+pub struct DisputeEvidence {
+    pub id: String,
+    pub escrow_id: String,
+    pub uploader_id: String,
+    pub kind: String,
+    pub description: Option<String>,
+    /// Name of the file in the arbiter's evidence bundle
+    pub file_name: String,
+    pub mime_type: String,
+    /// Size of the plaintext file in bytes
+    pub size_bytes: i64,
+    /// SHA-256 of the plaintext file (hex)
+    pub content_hash: String,
+    /// `ipfs` or `local`
+    pub storage_backend: String,
+    /// IPFS CID or local blob name of the encrypted file
+    #[serde(skip_serializing)]
+    pub storage_ref: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// New dispute evidence for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = dispute_evidence)]
+pub struct NewDisputeEvidence {
+    pub id: String,
+    pub escrow_id: String,
+    pub uploader_id: String,
+    pub kind: String,
+    pub description: Option<String>,
+    pub file_name: String,
+    pub mime_type: String,
+    pub size_bytes: i64,
+    pub content_hash: String,
+    pub storage_backend: String,
+    pub storage_ref: String,
+}
+
+impl DisputeEvidence {
+    /// Insert a new piece of evidence
+    pub fn create(conn: &mut SqliteConnection, new_evidence: NewDisputeEvidence) -> Result<Self> {
+        diesel::insert_into(dispute_evidence::table)
+            .values(&new_evidence)
+            .execute(conn)
+            .context("Failed to insert dispute evidence")?;
+
+        dispute_evidence::table
+            .find(&new_evidence.id)
+            .first(conn)
+            .context("Failed to retrieve created dispute evidence")
+    }
+
+    /// Find evidence by ID
+    pub fn find_by_id(conn: &mut SqliteConnection, evidence_id: &str) -> Result<Self> {
+        dispute_evidence::table
+            .find(evidence_id)
+            .first(conn)
+            .context(format!("Dispute evidence {} not found", evidence_id))
+    }
+
+    /// Evidence of an escrow, oldest first
+    pub fn find_by_escrow(conn: &mut SqliteConnection, escrow_id: &str) -> Result<Vec<Self>> {
+        dispute_evidence::table
+            .filter(dispute_evidence::escrow_id.eq(escrow_id))
+            .order((dispute_evidence::created_at.asc(), dispute_evidence::id.asc()))
+            .load(conn)
+            .context(format!("Failed to load evidence for escrow {}", escrow_id))
+    }
+
+    /// Evidence kept in `storage_backend`
+    pub fn find_by_backend(
+        conn: &mut SqliteConnection,
+        storage_backend: &str,
+    ) -> Result<Vec<Self>> {
+        dispute_evidence::table
+            .filter(dispute_evidence::storage_backend.eq(storage_backend))
+            .order(dispute_evidence::id.asc())
+            .load(conn)
+            .context("Failed to load dispute evidence")
+    }
+
+    /// Point evidence to a new blob (after re-encryption)
+    pub fn update_storage_ref(
+        conn: &mut SqliteConnection,
+        evidence_id: &str,
+        storage_ref: &str,
+    ) -> Result<()> {
+        diesel::update(dispute_evidence::table.find(evidence_id))
+            .set(dispute_evidence::storage_ref.eq(storage_ref))
+            .execute(conn)
+            .context(format!("Failed to update evidence {}", evidence_id))?;
+        Ok(())
+    }
+
+    /// Number of files `uploader_id` submitted for an escrow
+    pub fn count_by_uploader(
+        conn: &mut SqliteConnection,
+        escrow_id: &str,
+        uploader_id: &str,
+    ) -> Result<i64> {
+        dispute_evidence::table
+            .filter(dispute_evidence::escrow_id.eq(escrow_id))
+            .filter(dispute_evidence::uploader_id.eq(uploader_id))
+            .count()
+            .get_result(conn)
+            .context("Failed to count dispute evidence")
+    }
+
+    /// Get parsed kind enum
+    pub fn get_kind(&self) -> Result<EvidenceKind> {
+        self.kind.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evidence_kind_conversion() {
+        for kind in [
+            EvidenceKind::Photo,
+            EvidenceKind::Tracking,
+            EvidenceKind::Message,
+            EvidenceKind::Document,
+        ] {
+            assert_eq!(kind.as_str().parse::<EvidenceKind>().ok(), Some(kind));
+        }
+        assert!("video".parse::<EvidenceKind>().is_err());
+    }
+}
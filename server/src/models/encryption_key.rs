@@ -0,0 +1,86 @@
+//! Public encryption keys registered by users
+//!
+//! Used by clients to encrypt order messages to the other parties; see
+//! [`crate::crypto::message_envelope`].
+
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::message_envelope::validate_public_key;
+use crate::schema::user_encryption_keys;
+
+/// A user's current public key
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[diesel(table_name = user_encryption_keys, primary_key(user_id))]
+pub struct UserEncryptionKey {
+    pub user_id: String,
+    /// Raw uncompressed P-256 point, base64
+    pub public_key: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = user_encryption_keys)]
+struct NewUserEncryptionKey<'a> {
+    user_id: &'a str,
+    public_key: &'a str,
+}
+
+impl UserEncryptionKey {
+    /// Register or replace the public key of `user_id`
+    ///
+    /// Messages already encrypted to the previous key stay readable only
+    /// with the previous private key.
+    pub fn upsert(
+        conn: &mut SqliteConnection,
+        user_id: &str,
+        public_key: &str,
+    ) -> Result<UserEncryptionKey> {
+        validate_public_key(public_key).context("Invalid encryption public key")?;
+
+        diesel::insert_into(user_encryption_keys::table)
+            .values(&NewUserEncryptionKey {
+                user_id,
+                public_key,
+            })
+            .on_conflict(user_encryption_keys::user_id)
+            .do_update()
+            .set((
+                user_encryption_keys::public_key.eq(public_key),
+                user_encryption_keys::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn)
+            .context(format!("Failed to store encryption key for user {}", user_id))?;
+
+        user_encryption_keys::table
+            .find(user_id)
+            .first(conn)
+            .context("Failed to retrieve stored encryption key")
+    }
+
+    /// Public key of `user_id`, if one was registered
+    pub fn find_by_user_id(
+        conn: &mut SqliteConnection,
+        user_id: &str,
+    ) -> Result<Option<UserEncryptionKey>> {
+        user_encryption_keys::table
+            .find(user_id)
+            .first(conn)
+            .optional()
+            .context(format!("Failed to load encryption key for user {}", user_id))
+    }
+
+    /// Public keys of several users (users without a key are left out)
+    pub fn find_by_user_ids(
+        conn: &mut SqliteConnection,
+        user_ids: &[&str],
+    ) -> Result<Vec<UserEncryptionKey>> {
+        user_encryption_keys::table
+            .filter(user_encryption_keys::user_id.eq_any(user_ids))
+            .load(conn)
+            .context("Failed to load encryption keys")
+    }
+}
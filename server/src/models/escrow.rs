@@ -5,8 +5,21 @@ use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::models::arbiter_assignment::{ArbiterAssignment, AssignmentReason};
+use crate::models::escrow_event::{EscrowActor, EscrowEventRecord};
 use crate::schema::escrows;
 
+pub use monero_marketplace_common::types::EscrowStatus;
+
+/// `escrows.status` values an escrow never leaves
+pub fn terminal_statuses() -> Vec<&'static str> {
+    EscrowStatus::ALL
+        .into_iter()
+        .filter(EscrowStatus::is_terminal)
+        .map(|status| status.as_str())
+        .collect()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Queryable)]
 #[diesel(table_name = escrows)]
 pub struct Escrow {
@@ -34,6 +47,10 @@ pub struct Escrow {
     pub buyer_temp_wallet_id: Option<String>,
     pub vendor_temp_wallet_id: Option<String>,
     pub arbiter_temp_wallet_id: Option<String>,
+    /// Payout address of the arbiter's ruling (resolved_buyer / resolved_vendor)
+    pub ruling_address: Option<String>,
+    /// Sum of deposits received on the multisig address (atomic units)
+    pub funded_amount: i64,
 }
 
 #[derive(Insertable)]
@@ -50,16 +67,31 @@ pub struct NewEscrow {
 
 impl Escrow {
     /// Create a new escrow in the database
+    ///
+    /// The `created` event is appended to the escrow event log, attributed to the buyer,
+    /// and the arbiter's initial assignment is recorded.
     pub fn create(conn: &mut SqliteConnection, new_escrow: NewEscrow) -> Result<Escrow> {
-        diesel::insert_into(escrows::table)
-            .values(&new_escrow)
-            .execute(conn)
-            .context("Failed to insert escrow")?;
+        conn.transaction::<_, anyhow::Error, _>(|conn| {
+            diesel::insert_into(escrows::table)
+                .values(&new_escrow)
+                .execute(conn)
+                .context("Failed to insert escrow")?;
 
-        escrows::table
-            .filter(escrows::id.eq(new_escrow.id))
-            .first(conn)
-            .context("Failed to retrieve created escrow")
+            let escrow: Escrow = escrows::table
+                .filter(escrows::id.eq(&new_escrow.id))
+                .first(conn)
+                .context("Failed to retrieve created escrow")?;
+
+            EscrowEventRecord::record_created(conn, &escrow, &EscrowActor::user(&escrow.buyer_id))?;
+            ArbiterAssignment::start(
+                conn,
+                &escrow.id,
+                &escrow.arbiter_id,
+                AssignmentReason::Initial,
+                None,
+            )?;
+            Ok(escrow)
+        })
     }
 
     /// Find escrow by ID
@@ -95,21 +127,37 @@ impl Escrow {
     }
 
     /// Update escrow status
+    ///
+    /// Fails if the transition table does not allow the move. Recorded in the
+    /// escrow event log as a system change; use
+    /// [`EscrowEventRecord::record_transition`] when the actor or reason is known.
     pub fn update_status(
         conn: &mut SqliteConnection,
         escrow_id: String,
-        new_status: &str,
+        new_status: EscrowStatus,
     ) -> Result<()> {
-        diesel::update(escrows::table.filter(escrows::id.eq(escrow_id.clone())))
-            .set((
-                escrows::status.eq(new_status),
-                escrows::updated_at.eq(diesel::dsl::now),
-            ))
-            .execute(conn)
-            .context(format!("Failed to update status for escrow {}", escrow_id))?;
+        EscrowEventRecord::record_transition(
+            conn,
+            &escrow_id,
+            new_status,
+            &EscrowActor::System("server"),
+            None,
+        )?;
         Ok(())
     }
 
+    /// Get parsed status enum
+    pub fn get_status(&self) -> Result<EscrowStatus> {
+        self.status
+            .parse::<EscrowStatus>()
+            .context(format!("Escrow {} has an invalid status", self.id))
+    }
+
+    /// Amount received beyond `amount`, owed back to the buyer
+    pub fn surplus(&self) -> i64 {
+        (self.funded_amount - self.amount).max(0)
+    }
+
     /// Update multisig address
     pub fn update_multisig_address(
         conn: &mut SqliteConnection,
@@ -129,6 +177,53 @@ impl Escrow {
         Ok(())
     }
 
+    /// Whether the multisig wallet is still to be set up
+    ///
+    /// Such an escrow can change arbiter and simply run the setup again.
+    pub fn is_multisig_unstarted(&self) -> bool {
+        self.status == EscrowStatus::Created.as_str() && self.multisig_address.is_none()
+    }
+
+    /// Hand an escrow to another arbiter
+    ///
+    /// With `restart_multisig`, the wallet infos and multisig state collected
+    /// so far are discarded so that setup runs again with the new arbiter's
+    /// wallet (only valid while [`Escrow::is_multisig_unstarted`]).
+    pub fn update_arbiter(
+        conn: &mut SqliteConnection,
+        escrow_id: &str,
+        arbiter_id: &str,
+        restart_multisig: bool,
+    ) -> Result<()> {
+        let target = escrows::table.filter(escrows::id.eq(escrow_id));
+        let result = if restart_multisig {
+            diesel::update(target)
+                .set((
+                    escrows::arbiter_id.eq(arbiter_id),
+                    escrows::buyer_wallet_info.eq(None::<Vec<u8>>),
+                    escrows::vendor_wallet_info.eq(None::<Vec<u8>>),
+                    escrows::arbiter_wallet_info.eq(None::<Vec<u8>>),
+                    escrows::buyer_temp_wallet_id.eq(None::<String>),
+                    escrows::vendor_temp_wallet_id.eq(None::<String>),
+                    escrows::arbiter_temp_wallet_id.eq(None::<String>),
+                    escrows::multisig_phase.eq("not_started"),
+                    escrows::multisig_state_json.eq(None::<String>),
+                    escrows::updated_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)
+        } else {
+            diesel::update(target)
+                .set((
+                    escrows::arbiter_id.eq(arbiter_id),
+                    escrows::updated_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)
+        };
+
+        result.context(format!("Failed to update arbiter for escrow {}", escrow_id))?;
+        Ok(())
+    }
+
     /// Store encrypted wallet info for a party
     pub fn store_wallet_info(
         conn: &mut SqliteConnection,
@@ -271,8 +366,13 @@ impl Escrow {
     /// Returns true if expires_at is set and is in the past.
     /// Returns false if expires_at is None (terminal states) or in the future.
     pub fn is_expired(&self) -> bool {
+        self.is_expired_at(chrono::Utc::now().naive_utc())
+    }
+
+    /// Same as [`Escrow::is_expired`], evaluated at `now` (see `ChainClock`)
+    pub fn is_expired_at(&self, now: NaiveDateTime) -> bool {
         if let Some(expires_at) = self.expires_at {
-            expires_at < chrono::Utc::now().naive_utc()
+            expires_at < now
         } else {
             false
         }
@@ -284,8 +384,12 @@ impl Escrow {
     /// Returns Some(0) if already expired.
     /// Returns Some(n) with seconds remaining otherwise.
     pub fn seconds_until_expiration(&self) -> Option<i64> {
+        self.seconds_until_expiration_at(chrono::Utc::now().naive_utc())
+    }
+
+    /// Same as [`Escrow::seconds_until_expiration`], evaluated at `now`
+    pub fn seconds_until_expiration_at(&self, now: NaiveDateTime) -> Option<i64> {
         self.expires_at.map(|expires_at| {
-            let now = chrono::Utc::now().naive_utc();
             let duration = expires_at.signed_duration_since(now);
             duration.num_seconds().max(0)
         })
@@ -298,7 +402,12 @@ impl Escrow {
     ///
     /// Returns true if expiration is within the threshold but not yet expired.
     pub fn is_expiring_soon(&self, warning_threshold_secs: i64) -> bool {
-        if let Some(secs_remaining) = self.seconds_until_expiration() {
+        self.is_expiring_soon_at(chrono::Utc::now().naive_utc(), warning_threshold_secs)
+    }
+
+    /// Same as [`Escrow::is_expiring_soon`], evaluated at `now`
+    pub fn is_expiring_soon_at(&self, now: NaiveDateTime, warning_threshold_secs: i64) -> bool {
+        if let Some(secs_remaining) = self.seconds_until_expiration_at(now) {
             secs_remaining > 0 && secs_remaining <= warning_threshold_secs
         } else {
             false
@@ -314,15 +423,15 @@ impl Escrow {
     ///
     /// Used by TimeoutMonitor to find escrows needing timeout handling.
     pub fn find_expired(conn: &mut SqliteConnection) -> Result<Vec<Escrow>> {
-        let now = chrono::Utc::now().naive_utc();
+        Self::find_expired_at(conn, chrono::Utc::now().naive_utc())
+    }
 
+    /// Same as [`Escrow::find_expired`] with `now` supplied by the caller
+    pub fn find_expired_at(conn: &mut SqliteConnection, now: NaiveDateTime) -> Result<Vec<Escrow>> {
         escrows::table
             .filter(escrows::expires_at.is_not_null())
             .filter(escrows::expires_at.lt(now))
-            .filter(escrows::status.ne("completed"))
-            .filter(escrows::status.ne("refunded"))
-            .filter(escrows::status.ne("cancelled"))
-            .filter(escrows::status.ne("expired"))
+            .filter(escrows::status.ne_all(terminal_statuses()))
             .load(conn)
             .context("Failed to load expired escrows")
     }
@@ -339,17 +448,22 @@ impl Escrow {
         conn: &mut SqliteConnection,
         warning_threshold_secs: i64,
     ) -> Result<Vec<Escrow>> {
-        let now = chrono::Utc::now().naive_utc();
+        Self::find_expiring_soon_at(conn, chrono::Utc::now().naive_utc(), warning_threshold_secs)
+    }
+
+    /// Same as [`Escrow::find_expiring_soon`] with `now` supplied by the caller
+    pub fn find_expiring_soon_at(
+        conn: &mut SqliteConnection,
+        now: NaiveDateTime,
+        warning_threshold_secs: i64,
+    ) -> Result<Vec<Escrow>> {
         let warning_time = now + chrono::Duration::seconds(warning_threshold_secs);
 
         escrows::table
             .filter(escrows::expires_at.is_not_null())
             .filter(escrows::expires_at.gt(now))
             .filter(escrows::expires_at.le(warning_time))
-            .filter(escrows::status.ne("completed"))
-            .filter(escrows::status.ne("refunded"))
-            .filter(escrows::status.ne("cancelled"))
-            .filter(escrows::status.ne("expired"))
+            .filter(escrows::status.ne_all(terminal_statuses()))
             .load(conn)
             .context("Failed to load expiring escrows")
     }
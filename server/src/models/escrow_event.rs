@@ -0,0 +1,472 @@
+//! Escrow event log (append-only history of escrow status changes)
+//!
+//! Status changes go through [`EscrowEventRecord::record_transition`], which
+//! checks the [`EscrowStatus`] transition table, updates `escrows.status` and
+//! appends the matching [`EscrowEvent`] in the same transaction. [`replay`] rebuilds the escrow state from the log alone,
+//! so it can be compared with what the `escrows` table says.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use monero_marketplace_common::types::{
+    EscrowCreated, EscrowData, EscrowDisputed, EscrowEvent, EscrowFunded, EscrowRefunded,
+    EscrowReleased, EscrowStatus, EscrowStatusChanged,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+use crate::models::escrow::Escrow;
+use crate::schema::{escrow_events, escrows};
+
+/// Who made an escrow status change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EscrowActor {
+    /// Authenticated user (buyer, vendor, arbiter or admin)
+    User(String),
+    /// Server component acting on its own (monitors, setup...)
+    System(&'static str),
+}
+
+impl EscrowActor {
+    pub fn user(user_id: impl ToString) -> Self {
+        EscrowActor::User(user_id.to_string())
+    }
+}
+
+impl fmt::Display for EscrowActor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EscrowActor::User(id) => write!(f, "user:{}", id),
+            EscrowActor::System(component) => write!(f, "system:{}", component),
+        }
+    }
+}
+
+/// Stored escrow event
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[diesel(table_name = escrow_events)]
+pub struct EscrowEventRecord {
+    pub id: String,
+    pub escrow_id: String,
+    /// Position in the escrow's history, starting at 1
+    pub sequence: i32,
+    pub event_type: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    /// `user:<uuid>` or `system:<component>`
+    pub actor: String,
+    pub reason: Option<String>,
+    /// Serialized [`EscrowEvent`]
+    pub payload: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// New escrow event for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = escrow_events)]
+pub struct NewEscrowEventRecord {
+    pub id: String,
+    pub escrow_id: String,
+    pub sequence: i32,
+    pub event_type: String,
+    pub from_status: Option<String>,
+    pub to_status: String,
+    pub actor: String,
+    pub reason: Option<String>,
+    pub payload: String,
+}
+
+impl EscrowEventRecord {
+    /// Decode the stored event
+    pub fn event(&self) -> Result<EscrowEvent> {
+        serde_json::from_str(&self.payload).context(format!(
+            "Invalid payload for escrow event {} (escrow {})",
+            self.sequence, self.escrow_id
+        ))
+    }
+
+    /// Events of an escrow, oldest first
+    pub fn find_by_escrow(conn: &mut SqliteConnection, escrow_id: &str) -> Result<Vec<Self>> {
+        escrow_events::table
+            .filter(escrow_events::escrow_id.eq(escrow_id))
+            .order(escrow_events::sequence.asc())
+            .load(conn)
+            .context(format!("Failed to load events for escrow {}", escrow_id))
+    }
+
+    /// Most recent move of an escrow to `status`, if any
+    ///
+    /// Gives e.g. the time a dispute was opened and the reason given.
+    pub fn last_transition_to(
+        conn: &mut SqliteConnection,
+        escrow_id: &str,
+        status: EscrowStatus,
+    ) -> Result<Option<Self>> {
+        escrow_events::table
+            .filter(escrow_events::escrow_id.eq(escrow_id))
+            .filter(escrow_events::to_status.eq(status.as_str()))
+            .order(escrow_events::sequence.desc())
+            .first(conn)
+            .optional()
+            .context(format!("Failed to load events for escrow {}", escrow_id))
+    }
+
+    /// Record the creation of a freshly inserted escrow
+    pub fn record_created(
+        conn: &mut SqliteConnection,
+        escrow: &Escrow,
+        actor: &EscrowActor,
+    ) -> Result<EscrowEventRecord> {
+        let status = escrow.get_status()?;
+        let event = EscrowEvent::Created(EscrowCreated {
+            escrow_id: escrow.id.clone(),
+            data: EscrowData {
+                buyer: escrow.buyer_id.clone(),
+                seller: escrow.vendor_id.clone(),
+                arbiter: escrow.arbiter_id.clone(),
+                amount: escrow.amount.max(0) as u64,
+                multisig_address: escrow.multisig_address.clone().unwrap_or_default(),
+            },
+        });
+        Self::append(conn, &escrow.id, None, status, actor, None, &event)
+    }
+
+    /// Change the status of an escrow and append the event, atomically
+    ///
+    /// Moves not allowed by [`EscrowStatus::can_transition_to`] are rejected
+    /// and leave the escrow untouched. Setting the status it already has is a
+    /// no-op (no event).
+    pub fn record_transition(
+        conn: &mut SqliteConnection,
+        escrow_id: &str,
+        new_status: EscrowStatus,
+        actor: &EscrowActor,
+        reason: Option<&str>,
+    ) -> Result<Escrow> {
+        conn.transaction::<_, anyhow::Error, _>(|conn| {
+            let escrow = Escrow::find_by_id(conn, escrow_id.to_string())?;
+            let current_status = escrow.get_status()?;
+            if current_status == new_status {
+                return Ok(escrow);
+            }
+            current_status
+                .transition_to(new_status)
+                .context(format!("Escrow {} status not updated", escrow_id))?;
+
+            diesel::update(escrows::table.filter(escrows::id.eq(escrow_id)))
+                .set((
+                    escrows::status.eq(new_status.as_str()),
+                    escrows::updated_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)
+                .context(format!("Failed to update status for escrow {}", escrow_id))?;
+
+            let event = event_for_transition(&escrow, new_status, actor, reason)?;
+            Self::append(
+                conn,
+                escrow_id,
+                Some(current_status),
+                new_status,
+                actor,
+                reason,
+                &event,
+            )?;
+
+            Escrow::find_by_id(conn, escrow_id.to_string())
+        })
+    }
+
+    fn append(
+        conn: &mut SqliteConnection,
+        escrow_id: &str,
+        from_status: Option<EscrowStatus>,
+        to_status: EscrowStatus,
+        actor: &EscrowActor,
+        reason: Option<&str>,
+        event: &EscrowEvent,
+    ) -> Result<EscrowEventRecord> {
+        let last_sequence: Option<i32> = escrow_events::table
+            .filter(escrow_events::escrow_id.eq(escrow_id))
+            .select(diesel::dsl::max(escrow_events::sequence))
+            .first(conn)
+            .context(format!("Failed to read event sequence for escrow {}", escrow_id))?;
+
+        let new_record = NewEscrowEventRecord {
+            id: Uuid::new_v4().to_string(),
+            escrow_id: escrow_id.to_string(),
+            sequence: last_sequence.unwrap_or(0) + 1,
+            event_type: event.kind().to_string(),
+            from_status: from_status.map(|status| status.as_str().to_string()),
+            to_status: to_status.as_str().to_string(),
+            actor: actor.to_string(),
+            reason: reason.map(str::to_string),
+            payload: serde_json::to_string(event).context("Failed to serialize escrow event")?,
+        };
+
+        diesel::insert_into(escrow_events::table)
+            .values(&new_record)
+            .execute(conn)
+            .context(format!("Failed to append event for escrow {}", escrow_id))?;
+
+        escrow_events::table
+            .filter(escrow_events::id.eq(&new_record.id))
+            .first(conn)
+            .context("Failed to retrieve appended escrow event")
+    }
+}
+
+/// Event describing a move of `escrow` to `new_status`
+///
+/// Statuses with a dedicated event type (active, completed, refunded,
+/// disputed) use it; everything else is a plain status change.
+pub fn event_for_transition(
+    escrow: &Escrow,
+    new_status: EscrowStatus,
+    actor: &EscrowActor,
+    reason: Option<&str>,
+) -> Result<EscrowEvent> {
+    let escrow_id = escrow.id.clone();
+    // Funding is detected by balance, the deposit tx hash is not known here
+    let tx_hash = escrow.transaction_hash.clone().unwrap_or_default();
+
+    Ok(match new_status {
+        EscrowStatus::Active => EscrowEvent::Funded(EscrowFunded { escrow_id, tx_hash }),
+        EscrowStatus::Completed => EscrowEvent::Released(EscrowReleased { escrow_id, tx_hash }),
+        EscrowStatus::Refunded => EscrowEvent::Refunded(EscrowRefunded { escrow_id, tx_hash }),
+        EscrowStatus::Disputed => EscrowEvent::Disputed(EscrowDisputed {
+            escrow_id,
+            reason: reason.unwrap_or_default().to_string(),
+            disputed_by: match actor {
+                EscrowActor::User(id) => id.clone(),
+                EscrowActor::System(_) => actor.to_string(),
+            },
+        }),
+        _ => EscrowEvent::StatusChanged(EscrowStatusChanged {
+            escrow_id,
+            from: escrow.get_status()?,
+            to: new_status,
+        }),
+    })
+}
+
+/// Escrow status after applying `event`
+pub fn status_after(event: &EscrowEvent) -> EscrowStatus {
+    match event {
+        EscrowEvent::Created(_) => EscrowStatus::Created,
+        EscrowEvent::Funded(_) => EscrowStatus::Active,
+        EscrowEvent::Released(_) => EscrowStatus::Completed,
+        EscrowEvent::Refunded(_) => EscrowStatus::Refunded,
+        EscrowEvent::Disputed(_) => EscrowStatus::Disputed,
+        EscrowEvent::StatusChanged(e) => e.to,
+    }
+}
+
+/// Escrow state rebuilt from its event log
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct EscrowReplay {
+    pub escrow_id: String,
+    pub status: EscrowStatus,
+    pub buyer_id: String,
+    pub vendor_id: String,
+    pub arbiter_id: String,
+    pub amount: u64,
+    pub funding_tx_hash: Option<String>,
+    pub release_tx_hash: Option<String>,
+    pub refund_tx_hash: Option<String>,
+    pub dispute_reason: Option<String>,
+    pub disputed_by: Option<String>,
+    pub last_actor: String,
+    pub last_event_at: NaiveDateTime,
+    pub event_count: usize,
+}
+
+/// Rebuild the state of an escrow from its events (oldest first)
+///
+/// Fails if the log does not start with a `created` event, mixes escrows
+/// or has a gap in its sequence numbers.
+pub fn replay(records: &[EscrowEventRecord]) -> Result<EscrowReplay> {
+    let first = records
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("Escrow event log is empty"))?;
+    let EscrowEvent::Created(created) = first.event()? else {
+        anyhow::bail!(
+            "Event log for escrow {} does not start with a created event",
+            first.escrow_id
+        );
+    };
+
+    let mut state = EscrowReplay {
+        escrow_id: created.escrow_id,
+        status: first
+            .to_status
+            .parse()
+            .context(format!("Invalid status in created event of escrow {}", first.escrow_id))?,
+        buyer_id: created.data.buyer,
+        vendor_id: created.data.seller,
+        arbiter_id: created.data.arbiter,
+        amount: created.data.amount,
+        funding_tx_hash: None,
+        release_tx_hash: None,
+        refund_tx_hash: None,
+        dispute_reason: None,
+        disputed_by: None,
+        last_actor: first.actor.clone(),
+        last_event_at: first.created_at,
+        event_count: 1,
+    };
+
+    for (index, record) in records.iter().enumerate().skip(1) {
+        if record.escrow_id != state.escrow_id {
+            anyhow::bail!(
+                "Event {} belongs to escrow {}, not {}",
+                record.id,
+                record.escrow_id,
+                state.escrow_id
+            );
+        }
+        let expected_sequence = index as i32 + first.sequence;
+        if record.sequence != expected_sequence {
+            anyhow::bail!(
+                "Event log for escrow {} has a gap at sequence {}",
+                state.escrow_id,
+                expected_sequence
+            );
+        }
+
+        let event = record.event()?;
+        let non_empty = |tx_hash: &str| Some(tx_hash.to_string()).filter(|h| !h.is_empty());
+        match &event {
+            EscrowEvent::Created(_) => {
+                anyhow::bail!("Escrow {} created twice", state.escrow_id);
+            }
+            EscrowEvent::Funded(e) => state.funding_tx_hash = non_empty(&e.tx_hash),
+            EscrowEvent::Released(e) => state.release_tx_hash = non_empty(&e.tx_hash),
+            EscrowEvent::Refunded(e) => state.refund_tx_hash = non_empty(&e.tx_hash),
+            EscrowEvent::Disputed(e) => {
+                state.dispute_reason = Some(e.reason.clone());
+                state.disputed_by = Some(e.disputed_by.clone());
+            }
+            EscrowEvent::StatusChanged(_) => {}
+        }
+
+        state.status = status_after(&event);
+        state.last_actor = record.actor.clone();
+        state.last_event_at = record.created_at;
+        state.event_count += 1;
+    }
+
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(sequence: i32, event: EscrowEvent, actor: &str) -> EscrowEventRecord {
+        EscrowEventRecord {
+            id: Uuid::new_v4().to_string(),
+            escrow_id: event.escrow_id().to_string(),
+            sequence,
+            event_type: event.kind().to_string(),
+            from_status: None,
+            to_status: status_after(&event).as_str().to_string(),
+            actor: actor.to_string(),
+            reason: None,
+            payload: serde_json::to_string(&event).unwrap(),
+            created_at: NaiveDateTime::default(),
+        }
+    }
+
+    fn created(escrow_id: &str) -> EscrowEvent {
+        EscrowEvent::Created(EscrowCreated {
+            escrow_id: escrow_id.to_string(),
+            data: EscrowData {
+                buyer: "buyer".to_string(),
+                seller: "vendor".to_string(),
+                arbiter: "arbiter".to_string(),
+                amount: 1_000_000_000_000,
+                multisig_address: String::new(),
+            },
+        })
+    }
+
+    fn status_changed(escrow_id: &str, from: &str, to: &str) -> EscrowEvent {
+        EscrowEvent::StatusChanged(EscrowStatusChanged {
+            escrow_id: escrow_id.to_string(),
+            from: from.parse().unwrap(),
+            to: to.parse().unwrap(),
+        })
+    }
+
+    #[test]
+    fn test_replay_dispute_then_refund() {
+        let id = "escrow-1";
+        let records = vec![
+            record(1, created(id), "user:buyer"),
+            record(2, status_changed(id, "created", "funded"), "system:escrow_orchestrator"),
+            record(
+                3,
+                EscrowEvent::Funded(EscrowFunded {
+                    escrow_id: id.to_string(),
+                    tx_hash: String::new(),
+                }),
+                "system:blockchain_monitor",
+            ),
+            record(
+                4,
+                EscrowEvent::Disputed(EscrowDisputed {
+                    escrow_id: id.to_string(),
+                    reason: "Never shipped".to_string(),
+                    disputed_by: "buyer".to_string(),
+                }),
+                "user:buyer",
+            ),
+            record(5, status_changed(id, "disputed", "resolved_buyer"), "user:arbiter"),
+            record(
+                6,
+                EscrowEvent::Refunded(EscrowRefunded {
+                    escrow_id: id.to_string(),
+                    tx_hash: "abc123".to_string(),
+                }),
+                "system:blockchain_monitor",
+            ),
+        ];
+
+        let state = replay(&records).unwrap();
+        assert_eq!(state.status, EscrowStatus::Refunded);
+        assert_eq!(state.vendor_id, "vendor");
+        assert_eq!(state.funding_tx_hash, None);
+        assert_eq!(state.refund_tx_hash.as_deref(), Some("abc123"));
+        assert_eq!(state.dispute_reason.as_deref(), Some("Never shipped"));
+        assert_eq!(state.disputed_by.as_deref(), Some("buyer"));
+        assert_eq!(state.last_actor, "system:blockchain_monitor");
+        assert_eq!(state.event_count, 6);
+    }
+
+    #[test]
+    fn test_replay_rejects_broken_logs() {
+        let id = "escrow-1";
+        assert!(replay(&[]).is_err());
+        assert!(replay(&[record(1, status_changed(id, "created", "funded"), "system:x")]).is_err());
+
+        let gap = vec![
+            record(1, created(id), "user:buyer"),
+            record(3, status_changed(id, "created", "funded"), "system:x"),
+        ];
+        assert!(replay(&gap).is_err());
+
+        let mixed = vec![
+            record(1, created(id), "user:buyer"),
+            record(2, status_changed("escrow-2", "created", "funded"), "system:x"),
+        ];
+        assert!(replay(&mixed).is_err());
+    }
+
+    #[test]
+    fn test_event_payload_is_tagged() {
+        let json = serde_json::to_value(status_changed("e", "funded", "releasing")).unwrap();
+        assert_eq!(json["type"], "status_changed");
+        assert_eq!(json["to"], "releasing");
+    }
+}
@@ -1,6 +1,8 @@
 //! Listing model and related database operations
 //!
 //! Represents a product or service listed for sale on the marketplace.
+//! A listing is priced in XMR, or pegged to a fiat price that is converted
+//! at the current rate when it is bought (see [`FiatPrice`]).
 
 use anyhow::{Context, Result};
 use chrono::NaiveDateTime;
@@ -10,6 +12,9 @@ use std::str::FromStr;
 
 use crate::schema::listings;
 
+/// Error prefix when a listing has less stock than requested
+pub const INSUFFICIENT_STOCK_ERROR: &str = "Insufficient stock";
+
 /// Listing status enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -49,6 +54,57 @@ impl FromStr for ListingStatus {
     }
 }
 
+/// Fiat minor units (cents) per unit of currency
+pub const FIAT_MINOR_PER_UNIT: i64 = 100;
+
+/// Fiat price a listing is pegged to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FiatPrice {
+    /// Lowercase ISO 4217 code
+    pub currency: String,
+    /// Amount in minor units (cents): 12.50 USD = 1250
+    pub amount: i64,
+}
+
+impl FiatPrice {
+    /// Price in atomic units at `rate` (fiat units per XMR), rounded up
+    pub fn to_atomic(&self, rate: f64) -> Result<i64> {
+        fiat_to_atomic(self.amount, rate)
+    }
+
+    /// Formatted price, e.g. `12.50 USD`
+    pub fn display(&self) -> String {
+        format_fiat(self.amount, &self.currency)
+    }
+}
+
+/// Convert a fiat amount in minor units to atomic units at `rate` (fiat
+/// units per XMR), rounding up to the next piconero
+pub fn fiat_to_atomic(amount: i64, rate: f64) -> Result<i64> {
+    if amount <= 0 {
+        anyhow::bail!("Fiat amount must be positive");
+    }
+    if !rate.is_finite() || rate <= 0.0 {
+        anyhow::bail!("Invalid exchange rate: {}", rate);
+    }
+    let atomic = (amount as f64 / FIAT_MINOR_PER_UNIT as f64 / rate * 1_000_000_000_000.0).ceil();
+    if atomic >= i64::MAX as f64 {
+        anyhow::bail!("Converted price exceeds maximum value");
+    }
+    Ok(atomic as i64)
+}
+
+/// Format a fiat amount in minor units, e.g. `12.50 USD`
+pub fn format_fiat(amount: i64, currency: &str) -> String {
+    format!(
+        "{}{}.{:02} {}",
+        if amount < 0 { "-" } else { "" },
+        amount.abs() / FIAT_MINOR_PER_UNIT,
+        amount.abs() % FIAT_MINOR_PER_UNIT,
+        currency.to_uppercase()
+    )
+}
+
 /// Listing database model
 #[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
 #[diesel(table_name = listings)]
@@ -66,6 +122,11 @@ pub struct Listing {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub category: String,
+    /// Currency of the fiat peg (lowercase ISO 4217); `price_xmr` then only
+    /// follows the current rate
+    pub price_currency: Option<String>,
+    /// Fiat price in minor units (cents), set together with `price_currency`
+    pub price_fiat: Option<i64>,
 }
 
 /// New listing for insertion
@@ -221,6 +282,57 @@ impl Listing {
         Self::find_by_id(conn, listing_id)
     }
 
+    /// Peg the listing to a fiat price, or back to its XMR price with `None`
+    ///
+    /// The caller sets a matching `price_xmr` (see [`Listing::reprice`]).
+    pub fn set_fiat_price(
+        conn: &mut SqliteConnection,
+        listing_id: String,
+        fiat_price: Option<FiatPrice>,
+    ) -> Result<Listing> {
+        let (currency, amount) = match fiat_price {
+            Some(price) => {
+                if price.amount <= 0 {
+                    anyhow::bail!("Fiat price must be positive");
+                }
+                (Some(price.currency.to_lowercase()), Some(price.amount))
+            }
+            None => (None, None),
+        };
+
+        diesel::update(listings::table.filter(listings::id.eq(listing_id.clone())))
+            .set((
+                listings::price_currency.eq(currency),
+                listings::price_fiat.eq(amount),
+            ))
+            .execute(conn)
+            .context("Failed to set fiat price")?;
+
+        Self::find_by_id(conn, listing_id)
+    }
+
+    /// Set the XMR price of a fiat-pegged listing to follow the current rate
+    pub fn reprice(conn: &mut SqliteConnection, listing_id: &str, price_xmr: i64) -> Result<()> {
+        diesel::update(
+            listings::table
+                .filter(listings::id.eq(listing_id))
+                .filter(listings::price_fiat.is_not_null()),
+        )
+        .set(listings::price_xmr.eq(price_xmr))
+        .execute(conn)
+        .context("Failed to reprice listing")?;
+        Ok(())
+    }
+
+    /// Listings pegged to a fiat price that can still be bought
+    pub fn find_fiat_priced(conn: &mut SqliteConnection) -> Result<Vec<Listing>> {
+        listings::table
+            .filter(listings::price_fiat.is_not_null())
+            .filter(listings::status.ne(ListingStatus::Deleted.as_str()))
+            .load(conn)
+            .context("Failed to load fiat-priced listings")
+    }
+
     /// Decrease listing stock by quantity
     ///
     /// # Arguments
@@ -245,7 +357,8 @@ impl Listing {
 
         if listing.stock < quantity {
             anyhow::bail!(
-                "Insufficient stock: available={}, requested={}",
+                "{}: available={}, requested={}",
+                INSUFFICIENT_STOCK_ERROR,
                 listing.stock,
                 quantity
             );
@@ -321,6 +434,17 @@ impl Listing {
         self.price_xmr as f64 / 1_000_000_000_000.0
     }
 
+    /// Fiat price the listing is pegged to, if any
+    pub fn fiat_price(&self) -> Option<FiatPrice> {
+        match (&self.price_currency, self.price_fiat) {
+            (Some(currency), Some(amount)) => Some(FiatPrice {
+                currency: currency.clone(),
+                amount,
+            }),
+            _ => None,
+        }
+    }
+
     /// Get parsed status enum
     pub fn get_status(&self) -> Result<ListingStatus> {
         self.status.parse::<ListingStatus>()
@@ -371,8 +495,28 @@ mod tests {
             updated_at: chrono::Utc::now().naive_utc(),
             images_ipfs_cids: None,
             category: "other".to_string(),
+            price_currency: None,
+            price_fiat: None,
         };
 
         assert_eq!(listing.price_as_xmr(), 1.5);
+        assert!(listing.fiat_price().is_none());
+    }
+
+    #[test]
+    fn test_fiat_to_atomic() {
+        // 150.00 USD at 150 USD/XMR = 1 XMR
+        assert_eq!(fiat_to_atomic(15_000, 150.0).unwrap(), 1_000_000_000_000);
+        // 10.00 USD at 3 USD/XMR = 3.333... XMR, rounded up
+        assert_eq!(fiat_to_atomic(1_000, 3.0).unwrap(), 3_333_333_333_334);
+        assert!(fiat_to_atomic(0, 150.0).is_err());
+        assert!(fiat_to_atomic(100, 0.0).is_err());
+        assert!(fiat_to_atomic(100, f64::NAN).is_err());
+
+        let price = FiatPrice {
+            currency: "eur".to_string(),
+            amount: 1_205,
+        };
+        assert_eq!(price.display(), "12.05 EUR");
     }
 }
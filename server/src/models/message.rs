@@ -1,11 +1,15 @@
 // ! Order message model for vendor-buyer communication
 //!
 //! Represents a message sent between buyer and vendor for a specific order.
+//! Only the encrypted envelope is stored; see [`crate::crypto::message_envelope`].
+//! Messages sent before end-to-end encryption keep their plaintext and are
+//! shown read-only.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::crypto::message_envelope::MessageEnvelope;
 use crate::schema::order_messages;
 
 /// Order message database model
@@ -15,7 +19,10 @@ pub struct OrderMessage {
     pub id: String,
     pub order_id: String,
     pub sender_id: String,
-    pub message: String,
+    /// Plaintext of a legacy message (None for encrypted messages)
+    pub message: Option<String>,
+    /// Serialized [`MessageEnvelope`] (ciphertext only, None for legacy messages)
+    pub envelope: Option<String>,
     pub created_at: i32, // Unix timestamp
 }
 
@@ -26,22 +33,23 @@ pub struct NewOrderMessage {
     pub id: String,
     pub order_id: String,
     pub sender_id: String,
-    pub message: String,
+    pub envelope: String,
     pub created_at: i32,
 }
 
 impl NewOrderMessage {
-    /// Create a new order message
-    pub fn new(order_id: String, sender_id: String, message: String) -> Self {
+    /// Create a new order message from an already validated envelope
+    pub fn new(order_id: String, sender_id: String, envelope: &MessageEnvelope) -> Result<Self> {
         let now = chrono::Utc::now().timestamp() as i32;
 
-        Self {
+        Ok(Self {
             id: uuid::Uuid::new_v4().to_string(),
             order_id,
             sender_id,
-            message,
+            envelope: serde_json::to_string(envelope)
+                .context("Failed to serialize message envelope")?,
             created_at: now,
-        }
+        })
     }
 }
 
@@ -52,12 +60,25 @@ pub struct OrderMessageWithSender {
     pub order_id: String,
     pub sender_id: String,
     pub sender_username: String,
-    pub message: String,
+    pub envelope: Option<MessageEnvelope>,
+    /// Plaintext of a message sent before end-to-end encryption (read-only)
+    pub legacy_message: Option<String>,
     pub created_at: i32,
     pub is_current_user: bool,
 }
 
 impl OrderMessage {
+    /// Decode the stored envelope (None for a legacy plaintext message)
+    pub fn envelope(&self) -> Result<Option<MessageEnvelope>> {
+        self.envelope
+            .as_deref()
+            .map(|envelope| {
+                serde_json::from_str(envelope)
+                    .context(format!("Invalid envelope for message {}", self.id))
+            })
+            .transpose()
+    }
+
     /// Get all messages for an order
     pub fn get_by_order_id(
         order_id: &str,
@@ -1,9 +1,18 @@
+pub mod arbiter_assignment;
+pub mod audit_checkpoint;
+pub mod audit_log;
 pub mod cart;
+pub mod dispute_evidence;
+pub mod encryption_key;
 pub mod escrow;
+pub mod escrow_event;
 pub mod listing;
 pub mod message;
 pub mod multisig_state;
 pub mod order;
+pub mod order_item;
+pub mod price_history;
 pub mod transaction;
 pub mod user;
+pub mod vendor_bond;
 pub mod wallet_rpc_config;
@@ -18,10 +18,13 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::models::escrow::EscrowStatus;
+
 /// Multisig setup phase states
 ///
 /// Represents the current stage of multisig wallet initialization.
 /// Each phase corresponds to specific Monero RPC calls required.
+/// This is wallet setup progress only, see [`MultisigPhase::escrow_status`].
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum MultisigPhase {
@@ -104,6 +107,21 @@ impl MultisigPhase {
         serde_json::from_str(s).context("Failed to deserialize MultisigPhase")
     }
 
+    /// Escrow status during this phase
+    ///
+    /// Every phase, `Ready` included, is a sub-state of `EscrowStatus::Created`:
+    /// the escrow only moves to `Funded` once the orchestrator records the
+    /// finished setup.
+    pub fn escrow_status(&self) -> EscrowStatus {
+        match self {
+            Self::NotStarted
+            | Self::Preparing { .. }
+            | Self::Exchanging { .. }
+            | Self::Ready { .. }
+            | Self::Failed { .. } => EscrowStatus::Created,
+        }
+    }
+
     /// Check if phase allows state transitions
     pub fn is_terminal(&self) -> bool {
         matches!(self, Self::Ready { .. } | Self::Failed { .. })
@@ -315,6 +333,29 @@ mod tests {
         .is_terminal());
     }
 
+    #[test]
+    fn test_phases_are_created_escrows() {
+        let phases = [
+            MultisigPhase::NotStarted,
+            MultisigPhase::Preparing { completed: vec![] },
+            MultisigPhase::Exchanging {
+                round: 1,
+                infos: HashMap::new(),
+            },
+            MultisigPhase::Ready {
+                address: "4xxx".to_string(),
+                finalized_at: 123456,
+            },
+            MultisigPhase::Failed {
+                reason: "timeout".to_string(),
+                failed_at: 123456,
+            },
+        ];
+        for phase in phases {
+            assert_eq!(phase.escrow_status(), EscrowStatus::Created);
+        }
+    }
+
     #[test]
     fn test_status_description() {
         let phase = MultisigPhase::Exchanging {
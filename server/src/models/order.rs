@@ -6,9 +6,23 @@ use anyhow::{Context, Result};
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::str::FromStr;
 
-use crate::schema::orders;
+use crate::crypto::message_envelope::MessageEnvelope;
+use crate::models::escrow::{Escrow, EscrowStatus};
+use crate::models::listing::Listing;
+use crate::models::order_item::{NewOrderItem, OrderItem};
+use crate::schema::{escrows, orders};
+
+/// Error prefix when a checkout line is not an active listing
+pub const LISTING_UNAVAILABLE_ERROR: &str = "Listing is not available for purchase";
+
+/// Error when a buyer checks out one of their own listings
+pub const OWN_LISTING_ERROR: &str = "Cannot purchase your own listing";
+
+/// Error prefix when the shipping envelope has no copy for a vendor
+pub const SHIPPING_RECIPIENT_ERROR: &str = "Shipping address is not encrypted for vendor";
 
 /// Order status enum tracking the lifecycle of an order
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -104,10 +118,15 @@ pub struct Order {
     pub total_xmr: i64,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
-    /// Shipping address (encrypted in DB via SQLCipher)
+    /// Shipping address and notes: a [`MessageEnvelope`] (JSON) only the
+    /// vendor can open; legacy orders hold a server-encrypted address
     pub shipping_address: Option<String>,
-    /// Optional shipping notes from buyer (e.g., delivery instructions)
+    /// Plaintext shipping notes of legacy orders (new orders carry them
+    /// inside the envelope)
     pub shipping_notes: Option<String>,
+    /// Until when `total_xmr` holds, for orders with fiat-priced items;
+    /// an order unfunded by then is requoted at the current rate
+    pub quote_expires_at: Option<NaiveDateTime>,
 }
 
 /// New order for insertion
@@ -125,7 +144,343 @@ pub struct NewOrder {
     pub shipping_notes: Option<String>,
 }
 
+/// One line of a checkout (cart line or Buy Now)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckoutLine {
+    pub listing_id: String,
+    pub quantity: i32,
+}
+
+/// Exchange rates fiat-priced listings are converted at
+///
+/// Only fresh rates belong here: a fiat-priced listing whose currency has
+/// no rate cannot be bought.
+#[derive(Debug, Clone, Default)]
+pub struct CheckoutQuote {
+    /// Lowercase currency code → fiat units per XMR
+    pub rates: HashMap<String, f64>,
+    /// How long the converted XMR amounts hold if the order is not funded
+    pub window: chrono::Duration,
+    /// When the rates were taken; the quote expires `window` after it
+    pub issued_at: NaiveDateTime,
+}
+
+impl CheckoutQuote {
+    /// Atomic unit price of a listing under this quote, with the rate used
+    /// (None for listings priced in XMR)
+    fn unit_price(&self, listing: &Listing) -> Result<(i64, Option<f64>)> {
+        let Some(fiat_price) = listing.fiat_price() else {
+            return Ok((listing.price_xmr, None));
+        };
+        let rate = *self.rates.get(&fiat_price.currency).context(format!(
+            "No current XMR/{} rate to price listing {}",
+            fiat_price.currency.to_uppercase(),
+            listing.id
+        ))?;
+        Ok((fiat_price.to_atomic(rate)?, Some(rate)))
+    }
+
+    fn expires_at(&self) -> NaiveDateTime {
+        self.issued_at + self.window
+    }
+}
+
+/// Checkout line with its listing and quoted unit price
+struct QuotedLine {
+    listing: Listing,
+    quantity: i32,
+    unit_price_xmr: i64,
+    /// Rate the fiat price was converted at (None if priced in XMR)
+    rate: Option<f64>,
+}
+
 impl Order {
+    /// Create the orders of a checkout: one order per vendor, with line items
+    ///
+    /// Lines from the same vendor share one order, so one escrow covers them
+    /// all. Prices and titles are read from the listings (not from the cart),
+    /// and the stock of every listing is decreased. Everything runs in a
+    /// single DB transaction: if one listing is inactive or out of stock,
+    /// no order is created and no stock is touched.
+    ///
+    /// # Arguments
+    ///
+    /// * `conn` - Database connection
+    /// * `buyer_id` - Buyer user ID
+    /// * `lines` - Checkout lines (duplicate listings are merged)
+    /// * `shipping` - Shipping address envelope, encrypted to every vendor of
+    ///   the checkout. Each order stores a copy readable only by its vendor;
+    ///   the checkout fails if a vendor is not a recipient.
+    ///
+    /// # Returns
+    ///
+    /// Created orders with their line items, in order of first appearance
+    /// of each vendor in `lines`
+    pub fn create_checkout(
+        conn: &mut SqliteConnection,
+        buyer_id: &str,
+        lines: &[CheckoutLine],
+        shipping: Option<&MessageEnvelope>,
+    ) -> Result<Vec<(Order, Vec<OrderItem>)>> {
+        Self::create_checkout_quoted(conn, buyer_id, lines, shipping, &CheckoutQuote::default())
+    }
+
+    /// [`Order::create_checkout`] with fiat-priced listings converted at the
+    /// rates of `quote`
+    ///
+    /// Each fiat-priced item keeps its fiat price and the rate used, and its
+    /// order gets a `quote_expires_at` of `quote.window` after
+    /// `quote.issued_at`. Fails if
+    /// a fiat-priced listing has no rate in `quote`.
+    pub fn create_checkout_quoted(
+        conn: &mut SqliteConnection,
+        buyer_id: &str,
+        lines: &[CheckoutLine],
+        shipping: Option<&MessageEnvelope>,
+        quote: &CheckoutQuote,
+    ) -> Result<Vec<(Order, Vec<OrderItem>)>> {
+        if lines.is_empty() {
+            anyhow::bail!("Checkout has no items");
+        }
+
+        // Merge duplicate listings, keep first-appearance order
+        let mut merged: Vec<CheckoutLine> = Vec::new();
+        for line in lines {
+            if line.quantity <= 0 {
+                anyhow::bail!("Invalid quantity {} for listing {}", line.quantity, line.listing_id);
+            }
+            match merged.iter_mut().find(|l| l.listing_id == line.listing_id) {
+                Some(existing) => {
+                    existing.quantity = existing
+                        .quantity
+                        .checked_add(line.quantity)
+                        .context("Quantity overflow")?;
+                }
+                None => merged.push(line.clone()),
+            }
+        }
+
+        conn.transaction::<_, anyhow::Error, _>(|conn| {
+            // Reserve stock and group lines by vendor
+            let mut by_vendor: Vec<(String, Vec<QuotedLine>)> = Vec::new();
+            for line in &merged {
+                let listing = Listing::find_by_id(conn, line.listing_id.clone())?;
+
+                if listing.status != "active" {
+                    anyhow::bail!("{}: {}", LISTING_UNAVAILABLE_ERROR, listing.id);
+                }
+                if listing.vendor_id == buyer_id {
+                    anyhow::bail!(OWN_LISTING_ERROR);
+                }
+
+                // Fails (and rolls back everything) if stock is insufficient
+                Listing::decrease_stock(conn, listing.id.clone(), line.quantity)?;
+
+                let (unit_price_xmr, rate) = quote.unit_price(&listing)?;
+                let quoted = QuotedLine {
+                    listing,
+                    quantity: line.quantity,
+                    unit_price_xmr,
+                    rate,
+                };
+                match by_vendor
+                    .iter_mut()
+                    .find(|(vendor, _)| *vendor == quoted.listing.vendor_id)
+                {
+                    Some((_, group)) => group.push(quoted),
+                    None => by_vendor.push((quoted.listing.vendor_id.clone(), vec![quoted])),
+                }
+            }
+
+            let mut created = Vec::with_capacity(by_vendor.len());
+            for (vendor_id, group) in by_vendor {
+                let mut total_xmr: i64 = 0;
+                for line in &group {
+                    let line_total = line
+                        .unit_price_xmr
+                        .checked_mul(line.quantity as i64)
+                        .context("Order total overflow")?;
+                    total_xmr = total_xmr
+                        .checked_add(line_total)
+                        .context("Order total overflow")?;
+                }
+                if total_xmr <= 0 {
+                    anyhow::bail!("Invalid order total");
+                }
+
+                let shipping_address = match shipping {
+                    Some(envelope) => {
+                        let vendor_copy = envelope.for_recipient(&vendor_id).context(format!(
+                            "{} {}",
+                            SHIPPING_RECIPIENT_ERROR, vendor_id
+                        ))?;
+                        Some(
+                            serde_json::to_string(&vendor_copy)
+                                .context("Failed to serialize shipping envelope")?,
+                        )
+                    }
+                    None => None,
+                };
+
+                let order_id = uuid::Uuid::new_v4().to_string();
+                let order = Self::create(
+                    conn,
+                    NewOrder {
+                        id: order_id.clone(),
+                        buyer_id: buyer_id.to_string(),
+                        vendor_id,
+                        // First item, for code paths that still read a single listing
+                        listing_id: group[0].listing.id.clone(),
+                        escrow_id: None, // Set when escrow is initialized
+                        status: OrderStatus::Pending.as_str().to_string(),
+                        total_xmr,
+                        shipping_address,
+                        shipping_notes: None,
+                    },
+                )?;
+
+                let order = if group.iter().any(|line| line.rate.is_some()) {
+                    Self::set_quote_expiry(conn, &order_id, total_xmr, quote.expires_at())?
+                } else {
+                    order
+                };
+
+                let new_items: Vec<NewOrderItem> = group
+                    .iter()
+                    .map(|line| {
+                        let fiat_price = line.rate.and(line.listing.fiat_price());
+                        NewOrderItem {
+                            id: uuid::Uuid::new_v4().to_string(),
+                            order_id: order_id.clone(),
+                            listing_id: line.listing.id.clone(),
+                            title: line.listing.title.clone(),
+                            quantity: line.quantity,
+                            unit_price_xmr: line.unit_price_xmr,
+                            price_currency: fiat_price.as_ref().map(|p| p.currency.clone()),
+                            unit_price_fiat: fiat_price.as_ref().map(|p| p.amount),
+                            price_rate: line.rate,
+                        }
+                    })
+                    .collect();
+                OrderItem::create_many(conn, &new_items)?;
+
+                let items = OrderItem::find_by_order(conn, &order_id)?;
+                created.push((order, items));
+            }
+
+            Ok(created)
+        })
+    }
+
+    /// Convert the fiat-priced items of a pending order again at the rates
+    /// of `quote`, and restart its quote window
+    ///
+    /// The new total is also the amount its escrow waits for. Refused once
+    /// the escrow has received anything: a deposit locks the quote.
+    pub fn requote(
+        conn: &mut SqliteConnection,
+        order_id: &str,
+        quote: &CheckoutQuote,
+    ) -> Result<(Order, Vec<OrderItem>)> {
+        conn.transaction::<_, anyhow::Error, _>(|conn| {
+            let order = Self::find_by_id(conn, order_id.to_string())?;
+            if order.status != OrderStatus::Pending.as_str() {
+                anyhow::bail!("Order {} is {}, its price is final", order.id, order.status);
+            }
+            let items = order.items(conn)?;
+            if !items.iter().any(|item| item.fiat_price().is_some()) {
+                anyhow::bail!("Order {} has no fiat-priced items", order.id);
+            }
+
+            let escrow = match &order.escrow_id {
+                Some(escrow_id) => Some(Escrow::find_by_id(conn, escrow_id.clone())?),
+                None => None,
+            };
+            if let Some(escrow) = &escrow {
+                let awaiting_deposit = escrow.status == EscrowStatus::Created.as_str()
+                    || escrow.status == EscrowStatus::Funded.as_str();
+                if !awaiting_deposit || escrow.funded_amount > 0 {
+                    anyhow::bail!(
+                        "Escrow {} already received a deposit, the quote of order {} is locked",
+                        escrow.id,
+                        order.id
+                    );
+                }
+            }
+
+            let mut total_xmr: i64 = 0;
+            for item in &items {
+                let unit_price_xmr = match item.fiat_price() {
+                    Some(fiat_price) => {
+                        let rate = *quote.rates.get(&fiat_price.currency).context(format!(
+                            "No current XMR/{} rate to requote order {}",
+                            fiat_price.currency.to_uppercase(),
+                            order.id
+                        ))?;
+                        let unit_price_xmr = fiat_price.to_atomic(rate)?;
+                        OrderItem::set_quote(conn, &item.id, unit_price_xmr, rate)?;
+                        unit_price_xmr
+                    }
+                    None => item.unit_price_xmr,
+                };
+                total_xmr = unit_price_xmr
+                    .checked_mul(item.quantity as i64)
+                    .and_then(|line_total| total_xmr.checked_add(line_total))
+                    .context("Order total overflow")?;
+            }
+
+            if let Some(escrow) = &escrow {
+                diesel::update(escrows::table.filter(escrows::id.eq(&escrow.id)))
+                    .set((
+                        escrows::amount.eq(total_xmr),
+                        escrows::updated_at.eq(diesel::dsl::now),
+                    ))
+                    .execute(conn)
+                    .context("Failed to update escrow amount")?;
+            }
+
+            let order = Self::set_quote_expiry(conn, &order.id, total_xmr, quote.expires_at())?;
+            let items = order.items(conn)?;
+            Ok((order, items))
+        })
+    }
+
+    /// Pending orders whose quote expired before `now`
+    pub fn find_expired_quotes(
+        conn: &mut SqliteConnection,
+        now: NaiveDateTime,
+    ) -> Result<Vec<Order>> {
+        orders::table
+            .filter(orders::status.eq(OrderStatus::Pending.as_str()))
+            .filter(orders::quote_expires_at.lt(now))
+            .order(orders::quote_expires_at.asc())
+            .load(conn)
+            .context("Failed to load orders with expired quotes")
+    }
+
+    fn set_quote_expiry(
+        conn: &mut SqliteConnection,
+        order_id: &str,
+        total_xmr: i64,
+        expires_at: NaiveDateTime,
+    ) -> Result<Order> {
+        diesel::update(orders::table.filter(orders::id.eq(order_id)))
+            .set((
+                orders::total_xmr.eq(total_xmr),
+                orders::quote_expires_at.eq(expires_at),
+                orders::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn)
+            .context("Failed to set order quote")?;
+
+        Self::find_by_id(conn, order_id.to_string())
+    }
+
+    /// Line items of this order
+    pub fn items(&self, conn: &mut SqliteConnection) -> Result<Vec<OrderItem>> {
+        OrderItem::find_by_order(conn, &self.id)
+    }
+
     /// Create a new order in the database
     ///
     /// # Arguments
@@ -253,7 +608,10 @@ impl Order {
         }
 
         diesel::update(orders::table.filter(orders::id.eq(order_id.clone())))
-            .set(orders::status.eq(new_status.as_str()))
+            .set((
+                orders::status.eq(new_status.as_str()),
+                orders::updated_at.eq(diesel::dsl::now),
+            ))
             .execute(conn)
             .context("Failed to update order status")?;
 
@@ -293,11 +651,50 @@ impl Order {
             .context(format!("Order with escrow ID {} not found", escrow_id))
     }
 
+    /// Erase the shipping data of orders closed before `closed_before`
+    ///
+    /// Completed and refunded orders no longer need the address; their
+    /// `updated_at` is the time they reached that status.
+    ///
+    /// # Returns
+    ///
+    /// Number of orders purged
+    pub fn purge_shipping_data(
+        conn: &mut SqliteConnection,
+        closed_before: NaiveDateTime,
+    ) -> Result<usize> {
+        diesel::update(
+            orders::table
+                .filter(orders::status.eq_any([
+                    OrderStatus::Completed.as_str(),
+                    OrderStatus::Refunded.as_str(),
+                ]))
+                .filter(orders::updated_at.lt(closed_before))
+                .filter(
+                    orders::shipping_address
+                        .is_not_null()
+                        .or(orders::shipping_notes.is_not_null()),
+                ),
+        )
+        .set((
+            orders::shipping_address.eq(None::<String>),
+            orders::shipping_notes.eq(None::<String>),
+        ))
+        .execute(conn)
+        .context("Failed to purge shipping data")
+    }
+
     /// Convert total from atomic units to XMR
     pub fn total_as_xmr(&self) -> f64 {
         self.total_xmr as f64 / 1_000_000_000_000.0
     }
 
+    /// Whether the quoted XMR total of this order no longer holds at `now`
+    /// (see `ChainClock`)
+    pub fn is_quote_expired(&self, now: NaiveDateTime) -> bool {
+        self.quote_expires_at.is_some_and(|expires_at| expires_at < now)
+    }
+
     /// Get parsed status enum
     pub fn get_status(&self) -> Result<OrderStatus> {
         self.status.parse::<OrderStatus>()
@@ -388,6 +785,9 @@ mod tests {
             total_xmr: 2_500_000_000_000, // 2.5 XMR
             created_at: chrono::Utc::now().naive_utc(),
             updated_at: chrono::Utc::now().naive_utc(),
+            shipping_address: None,
+            shipping_notes: None,
+            quote_expires_at: None,
         };
 
         assert_eq!(order.total_as_xmr(), 2.5);
@@ -406,6 +806,9 @@ mod tests {
             total_xmr: 1_000_000_000_000,
             created_at: chrono::Utc::now().naive_utc(),
             updated_at: chrono::Utc::now().naive_utc(),
+            shipping_address: None,
+            shipping_notes: None,
+            quote_expires_at: None,
         };
         assert!(shipped_order.can_confirm_receipt());
 
@@ -443,6 +846,9 @@ mod tests {
             total_xmr: 1_000_000_000_000,
             created_at: chrono::Utc::now().naive_utc(),
             updated_at: chrono::Utc::now().naive_utc(),
+            shipping_address: None,
+            shipping_notes: None,
+            quote_expires_at: None,
         };
 
         // Only funded orders can be marked as shipped
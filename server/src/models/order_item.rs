@@ -0,0 +1,112 @@
+//! Order line item model
+//!
+//! An order groups every cart line from one vendor at checkout, so a single
+//! escrow (one 2-of-3 multisig wallet) covers all of them. Title and unit
+//! price are copied from the listing at checkout time; for a fiat-priced
+//! listing, so are its fiat price and the rate that converted it.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::models::listing::FiatPrice;
+use crate::schema::order_items;
+
+/// Order line item database model
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[diesel(table_name = order_items)]
+pub struct OrderItem {
+    pub id: String,
+    pub order_id: String,
+    pub listing_id: String,
+    /// Listing title at checkout time
+    pub title: String,
+    pub quantity: i32,
+    /// Unit price at checkout time, in atomic units (piconeros)
+    pub unit_price_xmr: i64,
+    pub created_at: NaiveDateTime,
+    /// Currency of the listing's fiat price at checkout time
+    pub price_currency: Option<String>,
+    /// Fiat unit price at checkout time, in minor units (cents)
+    pub unit_price_fiat: Option<i64>,
+    /// Rate (fiat units per XMR) `unit_price_xmr` was converted at
+    pub price_rate: Option<f64>,
+}
+
+/// New order line item for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = order_items)]
+pub struct NewOrderItem {
+    pub id: String,
+    pub order_id: String,
+    pub listing_id: String,
+    pub title: String,
+    pub quantity: i32,
+    pub unit_price_xmr: i64,
+    pub price_currency: Option<String>,
+    pub unit_price_fiat: Option<i64>,
+    pub price_rate: Option<f64>,
+}
+
+impl OrderItem {
+    /// Insert line items (call inside the checkout transaction)
+    pub fn create_many(conn: &mut SqliteConnection, items: &[NewOrderItem]) -> Result<usize> {
+        diesel::insert_into(order_items::table)
+            .values(items)
+            .execute(conn)
+            .context("Failed to insert order items")
+    }
+
+    /// Find all line items of an order (sorted by title)
+    pub fn find_by_order(conn: &mut SqliteConnection, order_id: &str) -> Result<Vec<OrderItem>> {
+        order_items::table
+            .filter(order_items::order_id.eq(order_id))
+            .order((order_items::title.asc(), order_items::id.asc()))
+            .load(conn)
+            .context(format!("Failed to load items for order {}", order_id))
+    }
+
+    /// Lock a new rate onto a fiat-priced item (call inside a transaction)
+    pub fn set_quote(
+        conn: &mut SqliteConnection,
+        item_id: &str,
+        unit_price_xmr: i64,
+        rate: f64,
+    ) -> Result<()> {
+        diesel::update(order_items::table.filter(order_items::id.eq(item_id)))
+            .set((
+                order_items::unit_price_xmr.eq(unit_price_xmr),
+                order_items::price_rate.eq(rate),
+            ))
+            .execute(conn)
+            .context(format!("Failed to requote order item {}", item_id))?;
+        Ok(())
+    }
+
+    /// Fiat price of the item at checkout, if its listing was fiat-priced
+    pub fn fiat_price(&self) -> Option<FiatPrice> {
+        match (&self.price_currency, self.unit_price_fiat) {
+            (Some(currency), Some(amount)) => Some(FiatPrice {
+                currency: currency.clone(),
+                amount,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Line total (quantity * unit price)
+    pub fn total_price(&self) -> i64 {
+        self.unit_price_xmr.saturating_mul(self.quantity as i64)
+    }
+
+    /// Unit price in XMR (for display)
+    pub fn unit_price_as_xmr(&self) -> f64 {
+        self.unit_price_xmr as f64 / 1_000_000_000_000.0
+    }
+
+    /// Line total in XMR (for display)
+    pub fn total_as_xmr(&self) -> f64 {
+        self.total_price() as f64 / 1_000_000_000_000.0
+    }
+}
@@ -0,0 +1,106 @@
+//! Exchange rates recorded by the price oracle
+//!
+//! Each refresh of [`crate::services::price_conversion::PriceOracle`] adds
+//! one row per currency; the latest row is the rate in use, and its
+//! `fetched_at` is what listing pages show as "last refreshed".
+
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::schema::price_history;
+
+/// Most rows returned by [`PriceRecord::list`]
+pub const MAX_HISTORY_PAGE: i64 = 500;
+
+/// Recorded XMR exchange rate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Queryable, Identifiable)]
+#[diesel(table_name = price_history)]
+pub struct PriceRecord {
+    pub id: i64,
+    /// Lowercase ISO 4217 code
+    pub currency: String,
+    /// Fiat units per 1 XMR
+    pub rate: f64,
+    /// Comma-separated names of the sources in the median
+    pub sources: String,
+    /// Comma-separated names of the sources rejected as outliers
+    pub rejected_sources: String,
+    pub fetched_at: NaiveDateTime,
+}
+
+/// New exchange rate for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = price_history)]
+pub struct NewPriceRecord {
+    pub currency: String,
+    pub rate: f64,
+    pub sources: String,
+    pub rejected_sources: String,
+    pub fetched_at: NaiveDateTime,
+}
+
+impl PriceRecord {
+    /// Record an aggregated rate
+    pub fn insert(
+        conn: &mut SqliteConnection,
+        currency: &str,
+        rate: f64,
+        sources: &[String],
+        rejected_sources: &[String],
+    ) -> Result<Self> {
+        let new_record = NewPriceRecord {
+            currency: currency.to_lowercase(),
+            rate,
+            sources: sources.join(","),
+            rejected_sources: rejected_sources.join(","),
+            fetched_at: chrono::Utc::now().naive_utc(),
+        };
+
+        diesel::insert_into(price_history::table)
+            .values(&new_record)
+            .execute(conn)
+            .context("Failed to insert price record")?;
+
+        price_history::table
+            .filter(price_history::currency.eq(&new_record.currency))
+            .order(price_history::id.desc())
+            .first(conn)
+            .context("Failed to retrieve created price record")
+    }
+
+    /// Rate currently in use for `currency`
+    pub fn latest(conn: &mut SqliteConnection, currency: &str) -> Result<Option<Self>> {
+        price_history::table
+            .filter(price_history::currency.eq(currency.to_lowercase()))
+            .order(price_history::id.desc())
+            .first(conn)
+            .optional()
+            .context("Failed to load latest price record")
+    }
+
+    /// Rates recorded for `currency`, newest first
+    pub fn list(conn: &mut SqliteConnection, currency: &str, limit: i64) -> Result<Vec<Self>> {
+        price_history::table
+            .filter(price_history::currency.eq(currency.to_lowercase()))
+            .order(price_history::id.desc())
+            .limit(limit.clamp(1, MAX_HISTORY_PAGE))
+            .load(conn)
+            .context("Failed to load price history")
+    }
+
+    /// Names of the sources in the median
+    pub fn source_names(&self) -> Vec<&str> {
+        split_names(&self.sources)
+    }
+
+    /// Names of the sources rejected as outliers
+    pub fn rejected_source_names(&self) -> Vec<&str> {
+        split_names(&self.rejected_sources)
+    }
+}
+
+fn split_names(names: &str) -> Vec<&str> {
+    names.split(',').filter(|name| !name.is_empty()).collect()
+}
@@ -32,6 +32,10 @@ pub struct Transaction {
     pub confirmations: i32,
     /// Transaction creation timestamp
     pub created_at: NaiveDateTime,
+    /// Buyer's share of a split dispute settlement (None otherwise)
+    pub buyer_amount_xmr: Option<i64>,
+    /// Vendor's share of a split dispute settlement (None otherwise)
+    pub vendor_amount_xmr: Option<i64>,
 }
 
 /// New transaction for insertion
@@ -43,6 +47,8 @@ pub struct NewTransaction {
     pub tx_hash: Option<String>,
     pub amount_xmr: i64,
     pub confirmations: i32,
+    pub buyer_amount_xmr: Option<i64>,
+    pub vendor_amount_xmr: Option<i64>,
 }
 
 impl Transaction {
@@ -279,6 +285,11 @@ impl Transaction {
         Ok(total)
     }
 
+    /// Whether this transaction settles a dispute with a buyer/vendor split
+    pub fn is_split(&self) -> bool {
+        self.buyer_amount_xmr.is_some() && self.vendor_amount_xmr.is_some()
+    }
+
     /// Check if transaction is fully confirmed (10+ confirmations)
     pub fn is_confirmed(&self) -> bool {
         self.confirmations >= 10
@@ -297,6 +308,7 @@ impl Transaction {
     /// - Amount is negative or zero
     /// - Confirmations is negative
     /// - tx_hash is invalid format (if present)
+    /// - Split amounts are incomplete, not positive or exceed the total
     pub fn validate(&self) -> Result<()> {
         if self.amount_xmr <= 0 {
             anyhow::bail!(
@@ -312,6 +324,28 @@ impl Transaction {
             );
         }
 
+        match (self.buyer_amount_xmr, self.vendor_amount_xmr) {
+            (None, None) => {}
+            (Some(buyer), Some(vendor)) => {
+                if buyer <= 0 || vendor <= 0 {
+                    anyhow::bail!(
+                        "Split amounts must be positive, got buyer={} vendor={}",
+                        buyer,
+                        vendor
+                    );
+                }
+                if buyer.saturating_add(vendor) > self.amount_xmr {
+                    anyhow::bail!(
+                        "Split amounts exceed transaction amount: {} + {} > {}",
+                        buyer,
+                        vendor,
+                        self.amount_xmr
+                    );
+                }
+            }
+            _ => anyhow::bail!("Split transaction must set both buyer and vendor amounts"),
+        }
+
         if let Some(ref hash) = self.tx_hash {
             if hash.len() != 64 {
                 anyhow::bail!(
@@ -342,6 +376,8 @@ mod tests {
             amount_xmr: 2_500_000_000_000, // 2.5 XMR
             confirmations: 10,
             created_at: chrono::Utc::now().naive_utc(),
+            buyer_amount_xmr: None,
+            vendor_amount_xmr: None,
         };
 
         assert_eq!(transaction.amount_as_xmr(), 2.5);
@@ -356,6 +392,8 @@ mod tests {
             amount_xmr: 1_000_000_000_000,
             confirmations: 5,
             created_at: chrono::Utc::now().naive_utc(),
+            buyer_amount_xmr: None,
+            vendor_amount_xmr: None,
         };
 
         assert!(!transaction.is_confirmed());
@@ -376,6 +414,8 @@ mod tests {
             amount_xmr: 1_000_000_000_000,
             confirmations: 0,
             created_at: chrono::Utc::now().naive_utc(),
+            buyer_amount_xmr: None,
+            vendor_amount_xmr: None,
         };
 
         assert!(transaction.validate().is_ok());
@@ -390,6 +430,8 @@ mod tests {
             amount_xmr: -1_000_000_000_000,
             confirmations: 0,
             created_at: chrono::Utc::now().naive_utc(),
+            buyer_amount_xmr: None,
+            vendor_amount_xmr: None,
         };
 
         let result = transaction.validate();
@@ -406,6 +448,8 @@ mod tests {
             amount_xmr: 0,
             confirmations: 0,
             created_at: chrono::Utc::now().naive_utc(),
+            buyer_amount_xmr: None,
+            vendor_amount_xmr: None,
         };
 
         let result = transaction.validate();
@@ -422,6 +466,8 @@ mod tests {
             amount_xmr: 1_000_000_000_000,
             confirmations: -5,
             created_at: chrono::Utc::now().naive_utc(),
+            buyer_amount_xmr: None,
+            vendor_amount_xmr: None,
         };
 
         let result = transaction.validate();
@@ -441,6 +487,8 @@ mod tests {
             amount_xmr: 1_000_000_000_000,
             confirmations: 10,
             created_at: chrono::Utc::now().naive_utc(),
+            buyer_amount_xmr: None,
+            vendor_amount_xmr: None,
         };
 
         assert!(transaction.validate().is_ok());
@@ -455,6 +503,8 @@ mod tests {
             amount_xmr: 1_000_000_000_000,
             confirmations: 10,
             created_at: chrono::Utc::now().naive_utc(),
+            buyer_amount_xmr: None,
+            vendor_amount_xmr: None,
         };
 
         let result = transaction.validate();
@@ -474,6 +524,8 @@ mod tests {
             amount_xmr: 1_000_000_000_000,
             confirmations: 10,
             created_at: chrono::Utc::now().naive_utc(),
+            buyer_amount_xmr: None,
+            vendor_amount_xmr: None,
         };
 
         let result = transaction.validate();
@@ -483,4 +535,33 @@ mod tests {
             .to_string()
             .contains("hexadecimal characters"));
     }
+
+    #[test]
+    fn test_validation_split_amounts() {
+        let mut transaction = Transaction {
+            id: "test-id".to_string(),
+            escrow_id: "escrow-id".to_string(),
+            tx_hash: None,
+            amount_xmr: 1_000_000_000_000,
+            confirmations: 0,
+            created_at: chrono::Utc::now().naive_utc(),
+            buyer_amount_xmr: Some(600_000_000_000),
+            vendor_amount_xmr: Some(400_000_000_000),
+        };
+        assert!(transaction.is_split());
+        assert!(transaction.validate().is_ok());
+
+        // Shares larger than the total
+        transaction.vendor_amount_xmr = Some(500_000_000_000);
+        assert!(transaction
+            .validate()
+            .unwrap_err()
+            .to_string()
+            .contains("exceed"));
+
+        // Only one side set
+        transaction.vendor_amount_xmr = None;
+        assert!(!transaction.is_split());
+        assert!(transaction.validate().is_err());
+    }
 }
@@ -0,0 +1,313 @@
+//! Vendor bond (security deposit) model
+//!
+//! A bond is XMR locked by a vendor in its own 2-of-3 multisig wallet
+//! (vendor, marketplace, arbiter). Only the part not yet slashed counts
+//! towards the listing limits of [`crate::config::BondPolicy`].
+
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::schema::{escrows, vendor_bond_slashes, vendor_bonds};
+
+/// Vendor bond status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VendorBondStatus {
+    /// Bond record created, multisig setup in progress
+    Created,
+    /// Multisig address ready, waiting for the vendor's deposit
+    AwaitingFunding,
+    /// Deposit confirmed, bond counts towards listing limits
+    Active,
+    /// Fully slashed (nothing left to release)
+    Slashed,
+    /// Remainder paid back to the vendor after retirement
+    Released,
+    /// Setup failed or abandoned before funding
+    Cancelled,
+}
+
+impl VendorBondStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VendorBondStatus::Created => "created",
+            VendorBondStatus::AwaitingFunding => "awaiting_funding",
+            VendorBondStatus::Active => "active",
+            VendorBondStatus::Slashed => "slashed",
+            VendorBondStatus::Released => "released",
+            VendorBondStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// Statuses of a bond that is still open (at most one per vendor)
+const OPEN_STATUSES: [&str; 3] = ["created", "awaiting_funding", "active"];
+
+/// Vendor bond database model
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[diesel(table_name = vendor_bonds)]
+pub struct VendorBond {
+    pub id: String,
+    pub vendor_id: String,
+    pub arbiter_id: String,
+    /// Bond amount in atomic units (piconeros)
+    pub amount: i64,
+    /// Total slashed into dispute payouts
+    pub slashed_amount: i64,
+    pub multisig_address: Option<String>,
+    pub status: String,
+    pub release_tx_hash: Option<String>,
+    pub funded_at: Option<NaiveDateTime>,
+    pub released_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// New vendor bond for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = vendor_bonds)]
+pub struct NewVendorBond {
+    pub id: String,
+    pub vendor_id: String,
+    pub arbiter_id: String,
+    pub amount: i64,
+    pub status: String,
+}
+
+/// Slash of a vendor bond into a dispute payout
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Identifiable)]
+#[diesel(table_name = vendor_bond_slashes)]
+pub struct VendorBondSlash {
+    pub id: String,
+    pub bond_id: String,
+    pub escrow_id: String,
+    pub arbiter_id: String,
+    pub amount: i64,
+    pub tx_hash: String,
+    pub reason: String,
+    pub created_at: NaiveDateTime,
+}
+
+/// New bond slash for insertion
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = vendor_bond_slashes)]
+pub struct NewVendorBondSlash {
+    pub id: String,
+    pub bond_id: String,
+    pub escrow_id: String,
+    pub arbiter_id: String,
+    pub amount: i64,
+    pub tx_hash: String,
+    pub reason: String,
+}
+
+impl VendorBond {
+    /// Create a new bond in the database
+    pub fn create(conn: &mut SqliteConnection, new_bond: NewVendorBond) -> Result<VendorBond> {
+        diesel::insert_into(vendor_bonds::table)
+            .values(&new_bond)
+            .execute(conn)
+            .context("Failed to insert vendor bond")?;
+
+        Self::find_by_id(conn, &new_bond.id)
+    }
+
+    /// Find bond by ID
+    pub fn find_by_id(conn: &mut SqliteConnection, bond_id: &str) -> Result<VendorBond> {
+        vendor_bonds::table
+            .filter(vendor_bonds::id.eq(bond_id))
+            .first(conn)
+            .context(format!("Vendor bond with ID {} not found", bond_id))
+    }
+
+    /// Open bond of a vendor (created, awaiting funding or active)
+    pub fn find_open_by_vendor(
+        conn: &mut SqliteConnection,
+        vendor_id: &str,
+    ) -> Result<Option<VendorBond>> {
+        vendor_bonds::table
+            .filter(vendor_bonds::vendor_id.eq(vendor_id))
+            .filter(vendor_bonds::status.eq_any(OPEN_STATUSES))
+            .first(conn)
+            .optional()
+            .context(format!("Failed to load open bond for vendor {}", vendor_id))
+    }
+
+    /// Most recent bond of a vendor, whatever its status
+    pub fn find_latest_by_vendor(
+        conn: &mut SqliteConnection,
+        vendor_id: &str,
+    ) -> Result<Option<VendorBond>> {
+        vendor_bonds::table
+            .filter(vendor_bonds::vendor_id.eq(vendor_id))
+            .order((vendor_bonds::created_at.desc(), vendor_bonds::id.desc()))
+            .first(conn)
+            .optional()
+            .context(format!("Failed to load bonds for vendor {}", vendor_id))
+    }
+
+    /// Bond amount (atomic units) counting towards listing limits
+    ///
+    /// Only an active bond counts, minus what has been slashed.
+    pub fn effective_amount_for_vendor(conn: &mut SqliteConnection, vendor_id: &str) -> Result<i64> {
+        Ok(Self::find_open_by_vendor(conn, vendor_id)?
+            .filter(|bond| bond.status == VendorBondStatus::Active.as_str())
+            .map(|bond| bond.remaining())
+            .unwrap_or(0))
+    }
+
+    /// Update bond status
+    pub fn update_status(
+        conn: &mut SqliteConnection,
+        bond_id: &str,
+        new_status: VendorBondStatus,
+    ) -> Result<()> {
+        diesel::update(vendor_bonds::table.filter(vendor_bonds::id.eq(bond_id)))
+            .set((
+                vendor_bonds::status.eq(new_status.as_str()),
+                vendor_bonds::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn)
+            .context(format!("Failed to update status for vendor bond {}", bond_id))?;
+        Ok(())
+    }
+
+    /// Store the multisig address and wait for the deposit
+    pub fn set_multisig_address(
+        conn: &mut SqliteConnection,
+        bond_id: &str,
+        address: &str,
+    ) -> Result<()> {
+        diesel::update(vendor_bonds::table.filter(vendor_bonds::id.eq(bond_id)))
+            .set((
+                vendor_bonds::multisig_address.eq(address),
+                vendor_bonds::status.eq(VendorBondStatus::AwaitingFunding.as_str()),
+                vendor_bonds::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn)
+            .context(format!(
+                "Failed to update multisig address for vendor bond {}",
+                bond_id
+            ))?;
+        Ok(())
+    }
+
+    /// Mark the deposit as received (bond becomes active)
+    pub fn mark_funded(conn: &mut SqliteConnection, bond_id: &str) -> Result<()> {
+        diesel::update(vendor_bonds::table.filter(vendor_bonds::id.eq(bond_id)))
+            .set((
+                vendor_bonds::status.eq(VendorBondStatus::Active.as_str()),
+                vendor_bonds::funded_at.eq(diesel::dsl::now),
+                vendor_bonds::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn)
+            .context(format!("Failed to mark vendor bond {} as funded", bond_id))?;
+        Ok(())
+    }
+
+    /// Record a broadcast slash and reduce the remaining bond
+    ///
+    /// The bond becomes `slashed` once nothing is left. The slashes of one
+    /// escrow never add up to more than the escrow amount.
+    pub fn record_slash(
+        conn: &mut SqliteConnection,
+        new_slash: NewVendorBondSlash,
+    ) -> Result<VendorBond> {
+        conn.transaction::<_, anyhow::Error, _>(|conn| {
+            let bond = Self::find_by_id(conn, &new_slash.bond_id)?;
+            if new_slash.amount <= 0 || new_slash.amount > bond.remaining() {
+                return Err(anyhow::anyhow!(
+                    "Slash amount {} exceeds remaining bond {}",
+                    new_slash.amount,
+                    bond.remaining()
+                ));
+            }
+            let slashable = Self::slashable_for_escrow(conn, &new_slash.escrow_id)?;
+            if new_slash.amount > slashable {
+                return Err(anyhow::anyhow!(
+                    "Slash amount {} exceeds what is left to slash for escrow {} ({})",
+                    new_slash.amount,
+                    new_slash.escrow_id,
+                    slashable
+                ));
+            }
+
+            diesel::insert_into(vendor_bond_slashes::table)
+                .values(&new_slash)
+                .execute(conn)
+                .context("Failed to insert vendor bond slash")?;
+
+            let slashed_amount = bond.slashed_amount + new_slash.amount;
+            let status = if slashed_amount == bond.amount {
+                VendorBondStatus::Slashed.as_str()
+            } else {
+                bond.status.as_str()
+            };
+            diesel::update(vendor_bonds::table.filter(vendor_bonds::id.eq(&bond.id)))
+                .set((
+                    vendor_bonds::slashed_amount.eq(slashed_amount),
+                    vendor_bonds::status.eq(status),
+                    vendor_bonds::updated_at.eq(diesel::dsl::now),
+                ))
+                .execute(conn)
+                .context(format!("Failed to update slashed amount for vendor bond {}", bond.id))?;
+
+            Self::find_by_id(conn, &bond.id)
+        })
+    }
+
+    /// Mark the remainder as paid back to the vendor
+    pub fn mark_released(conn: &mut SqliteConnection, bond_id: &str, tx_hash: &str) -> Result<()> {
+        diesel::update(vendor_bonds::table.filter(vendor_bonds::id.eq(bond_id)))
+            .set((
+                vendor_bonds::status.eq(VendorBondStatus::Released.as_str()),
+                vendor_bonds::release_tx_hash.eq(tx_hash),
+                vendor_bonds::released_at.eq(diesel::dsl::now),
+                vendor_bonds::updated_at.eq(diesel::dsl::now),
+            ))
+            .execute(conn)
+            .context(format!("Failed to mark vendor bond {} as released", bond_id))?;
+        Ok(())
+    }
+
+    /// Amount that may still be slashed for an escrow: its amount minus the
+    /// slashes already recorded for it, from any bond
+    pub fn slashable_for_escrow(conn: &mut SqliteConnection, escrow_id: &str) -> Result<i64> {
+        let escrow_amount: i64 = escrows::table
+            .filter(escrows::id.eq(escrow_id))
+            .select(escrows::amount)
+            .first(conn)
+            .context(format!("Escrow with ID {} not found", escrow_id))?;
+        let slashed: Vec<i64> = vendor_bond_slashes::table
+            .filter(vendor_bond_slashes::escrow_id.eq(escrow_id))
+            .select(vendor_bond_slashes::amount)
+            .load(conn)
+            .context(format!("Failed to load slashes for escrow {}", escrow_id))?;
+        Ok((escrow_amount - slashed.iter().sum::<i64>()).max(0))
+    }
+
+    /// Slashes of this bond (oldest first)
+    pub fn slashes(&self, conn: &mut SqliteConnection) -> Result<Vec<VendorBondSlash>> {
+        vendor_bond_slashes::table
+            .filter(vendor_bond_slashes::bond_id.eq(&self.id))
+            .order(vendor_bond_slashes::created_at.asc())
+            .load(conn)
+            .context(format!("Failed to load slashes for vendor bond {}", self.id))
+    }
+
+    /// Amount not yet slashed (atomic units)
+    pub fn remaining(&self) -> i64 {
+        self.amount - self.slashed_amount
+    }
+
+    /// Bond amount in XMR (for display)
+    pub fn amount_as_xmr(&self) -> f64 {
+        self.amount as f64 / 1_000_000_000_000.0
+    }
+
+    /// Remaining bond in XMR (for display)
+    pub fn remaining_as_xmr(&self) -> f64 {
+        self.remaining() as f64 / 1_000_000_000_000.0
+    }
+}
@@ -27,6 +27,7 @@ use tracing::{debug, error, info, warn};
 
 use crate::crypto::encryption::{decrypt_field, encrypt_field};
 use crate::db::DbPool;
+use crate::models::escrow::EscrowStatus;
 use crate::models::multisig_state::{MultisigPhase, MultisigSnapshot};
 use crate::schema::escrows;
 
@@ -223,7 +224,12 @@ impl MultisigStateRepository {
         let mut conn = self.pool.get()?;
 
         let rows: Vec<(String, Option<String>)> = escrows::table
-            .filter(escrows::status.eq_any(&["created", "funded", "releasing", "refunding"]))
+            .filter(escrows::status.eq_any([
+                EscrowStatus::Created.as_str(),
+                EscrowStatus::Funded.as_str(),
+                EscrowStatus::Releasing.as_str(),
+                EscrowStatus::Refunding.as_str(),
+            ]))
             .filter(escrows::multisig_phase.ne_all(&["ready", "failed"]))
             .filter(escrows::multisig_state_json.is_not_null())
             .select((escrows::id, escrows::multisig_state_json))
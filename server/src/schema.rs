@@ -1,5 +1,73 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    arbiter_assignments (id) {
+        id -> Text,
+        escrow_id -> Text,
+        arbiter_id -> Text,
+        reason -> Text,
+        assigned_at -> Timestamp,
+        ended_at -> Nullable<Timestamp>,
+        end_reason -> Nullable<Text>,
+        note -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    audit_checkpoints (sequence) {
+        sequence -> BigInt,
+        head_hash -> Text,
+        created_at -> Text,
+        public_key -> Text,
+        signature -> Text,
+    }
+}
+
+diesel::table! {
+    audit_log (sequence) {
+        sequence -> BigInt,
+        event_type -> Text,
+        entity_id -> Text,
+        actor -> Text,
+        data -> Text,
+        recorded_at -> Text,
+        entry_hash -> Text,
+        previous_hash -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    dispute_evidence (id) {
+        id -> Text,
+        escrow_id -> Text,
+        uploader_id -> Text,
+        kind -> Text,
+        description -> Nullable<Text>,
+        file_name -> Text,
+        mime_type -> Text,
+        size_bytes -> BigInt,
+        content_hash -> Text,
+        storage_backend -> Text,
+        storage_ref -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    escrow_events (id) {
+        id -> Text,
+        escrow_id -> Text,
+        sequence -> Integer,
+        event_type -> Text,
+        from_status -> Nullable<Text>,
+        to_status -> Text,
+        actor -> Text,
+        reason -> Nullable<Text>,
+        payload -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     escrows (id) {
         id -> Text,
@@ -25,6 +93,24 @@ diesel::table! {
         buyer_temp_wallet_id -> Nullable<Text>,
         vendor_temp_wallet_id -> Nullable<Text>,
         arbiter_temp_wallet_id -> Nullable<Text>,
+        ruling_address -> Nullable<Text>,
+        funded_amount -> BigInt,
+    }
+}
+
+diesel::table! {
+    imported_reputations (id) {
+        id -> Text,
+        vendor_id -> Text,
+        ipfs_cid -> Text,
+        vendor_pubkey -> Text,
+        source_domains -> Text,
+        total_reviews -> Integer,
+        unbound_reviews -> Integer,
+        average_rating -> Double,
+        generated_at -> Timestamp,
+        reputation_json -> Text,
+        imported_at -> Timestamp,
     }
 }
 
@@ -41,6 +127,23 @@ diesel::table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         category -> Text,
+        price_currency -> Nullable<Text>,
+        price_fiat -> Nullable<BigInt>,
+    }
+}
+
+diesel::table! {
+    order_items (id) {
+        id -> Text,
+        order_id -> Text,
+        listing_id -> Text,
+        title -> Text,
+        quantity -> Integer,
+        unit_price_xmr -> BigInt,
+        created_at -> Timestamp,
+        price_currency -> Nullable<Text>,
+        unit_price_fiat -> Nullable<BigInt>,
+        price_rate -> Nullable<Double>,
     }
 }
 
@@ -49,7 +152,8 @@ diesel::table! {
         id -> Text,
         order_id -> Text,
         sender_id -> Text,
-        message -> Text,
+        message -> Nullable<Text>,
+        envelope -> Nullable<Text>,
         created_at -> Integer,
     }
 }
@@ -67,6 +171,18 @@ diesel::table! {
         updated_at -> Timestamp,
         shipping_address -> Nullable<Text>,
         shipping_notes -> Nullable<Text>,
+        quote_expires_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    price_history (id) {
+        id -> BigInt,
+        currency -> Text,
+        rate -> Double,
+        sources -> Text,
+        rejected_sources -> Text,
+        fetched_at -> Timestamp,
     }
 }
 
@@ -83,6 +199,14 @@ diesel::table! {
         timestamp -> Timestamp,
         verified -> Bool,
         created_at -> Timestamp,
+        format_version -> Text,
+        vendor_pubkey -> Nullable<Text>,
+        escrow_id -> Nullable<Text>,
+        marketplace_domain -> Nullable<Text>,
+        payment_proof -> Nullable<Text>,
+        supersedes -> Nullable<Text>,
+        revisions -> Nullable<Text>,
+        reply -> Nullable<Text>,
     }
 }
 
@@ -94,6 +218,17 @@ diesel::table! {
         amount_xmr -> BigInt,
         confirmations -> Integer,
         created_at -> Timestamp,
+        buyer_amount_xmr -> Nullable<BigInt>,
+        vendor_amount_xmr -> Nullable<BigInt>,
+    }
+}
+
+diesel::table! {
+    user_encryption_keys (user_id) {
+        user_id -> Text,
+        public_key -> Text,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -110,6 +245,44 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    vendor_bond_slashes (id) {
+        id -> Text,
+        bond_id -> Text,
+        escrow_id -> Text,
+        arbiter_id -> Text,
+        amount -> BigInt,
+        tx_hash -> Text,
+        reason -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    vendor_bonds (id) {
+        id -> Text,
+        vendor_id -> Text,
+        arbiter_id -> Text,
+        amount -> BigInt,
+        slashed_amount -> BigInt,
+        multisig_address -> Nullable<Text>,
+        status -> Text,
+        release_tx_hash -> Nullable<Text>,
+        funded_at -> Nullable<Timestamp>,
+        released_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    vendor_reputation_keys (vendor_id) {
+        vendor_id -> Text,
+        public_key -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     wallet_address_history (id) {
         id -> Text,
@@ -137,23 +310,48 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(arbiter_assignments -> escrows (escrow_id));
+diesel::joinable!(arbiter_assignments -> users (arbiter_id));
+diesel::joinable!(dispute_evidence -> escrows (escrow_id));
+diesel::joinable!(dispute_evidence -> users (uploader_id));
+diesel::joinable!(escrow_events -> escrows (escrow_id));
 diesel::joinable!(escrows -> orders (order_id));
+diesel::joinable!(imported_reputations -> users (vendor_id));
 diesel::joinable!(listings -> users (vendor_id));
+diesel::joinable!(order_items -> listings (listing_id));
+diesel::joinable!(order_items -> orders (order_id));
 diesel::joinable!(order_messages -> orders (order_id));
 diesel::joinable!(order_messages -> users (sender_id));
 diesel::joinable!(orders -> listings (listing_id));
+diesel::joinable!(reviews -> escrows (escrow_id));
 diesel::joinable!(transactions -> escrows (escrow_id));
+diesel::joinable!(user_encryption_keys -> users (user_id));
+diesel::joinable!(vendor_bond_slashes -> escrows (escrow_id));
+diesel::joinable!(vendor_bond_slashes -> vendor_bonds (bond_id));
+diesel::joinable!(vendor_reputation_keys -> users (vendor_id));
 diesel::joinable!(wallet_address_history -> users (user_id));
 diesel::joinable!(wallet_rpc_configs -> escrows (escrow_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    arbiter_assignments,
+    audit_checkpoints,
+    audit_log,
+    dispute_evidence,
+    escrow_events,
     escrows,
+    imported_reputations,
     listings,
+    order_items,
     order_messages,
     orders,
+    price_history,
     reviews,
     transactions,
+    user_encryption_keys,
     users,
+    vendor_bond_slashes,
+    vendor_bonds,
+    vendor_reputation_keys,
     wallet_address_history,
     wallet_rpc_configs,
 );
@@ -40,8 +40,9 @@
 /// 1. Server detects dispute
 /// 2. Server exports `DisputeRequest` struct as QR code
 /// 3. Arbiter scans QR on offline laptop
-/// 4. Arbiter reviews evidence from USB readonly
-/// 5. Arbiter makes decision (release to buyer OR vendor)
+/// 4. Arbiter checks the USB evidence bundle against the hashes in the QR,
+///    then reviews it (readonly)
+/// 5. Arbiter makes decision (release to buyer OR vendor, or split between both)
 /// 6. Arbiter signs transaction offline
 /// 7. Arbiter exports `ArbiterSignature` as QR code
 /// 8. Server scans QR, imports signature, finalizes transaction
@@ -53,8 +54,11 @@
 /// - `base64` for binary encoding
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use uuid::Uuid;
 
+use crate::models::escrow::EscrowStatus;
+
 /// Dispute request exported from server to offline arbiter
 ///
 /// This struct is serialized to JSON and encoded as a QR code.
@@ -85,6 +89,14 @@ pub struct DisputeRequest {
     /// Evidence file count (on USB)
     pub evidence_file_count: usize,
 
+    /// SHA-256 of each evidence file, checked against the USB bundle
+    #[serde(default)]
+    pub evidence: Vec<EvidenceHash>,
+
+    /// Time after which no more evidence was accepted (Unix timestamp)
+    #[serde(default)]
+    pub evidence_deadline: i64,
+
     /// Multisig transaction data (partially signed by buyer/vendor)
     pub partial_tx_hex: String,
 
@@ -92,6 +104,25 @@ pub struct DisputeRequest {
     pub nonce: String,
 }
 
+/// One evidence file as listed in a [`DisputeRequest`]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EvidenceHash {
+    /// File name in the USB bundle
+    pub file_name: String,
+
+    /// SHA-256 of the file (hex)
+    pub sha256: String,
+
+    /// File size in bytes
+    pub size_bytes: u64,
+
+    /// "photo", "tracking", "message" or "document"
+    pub kind: String,
+
+    /// "buyer" or "vendor"
+    pub submitted_by: String,
+}
+
 /// Arbiter's decision + signature exported from offline laptop to server
 ///
 /// This struct is serialized to JSON and encoded as a QR code.
@@ -104,7 +135,7 @@ pub struct ArbiterDecision {
     /// Nonce from DisputeRequest (prevents replay)
     pub nonce: String,
 
-    /// Decision: "buyer" or "vendor"
+    /// Decision: "buyer", "vendor" or {"split": {...}}
     pub decision: ArbiterResolution,
 
     /// Human-readable reason for decision
@@ -115,7 +146,8 @@ pub struct ArbiterDecision {
 
     /// Ed25519 signature of decision (proof arbiter approved it)
     ///
-    /// Signature covers: BLAKE2b(escrow_id || nonce || decision || signed_tx_hex)
+    /// Signature covers: BLAKE2b(escrow_id || nonce || decision || signed_tx_hex),
+    /// where decision is "buyer", "vendor" or "split:<buyer_amount>:<vendor_amount>"
     pub decision_signature: String,
 
     /// Timestamp when decision was made (Unix timestamp)
@@ -131,6 +163,50 @@ pub enum ArbiterResolution {
 
     /// Release funds to vendor (vendor was right)
     Vendor,
+
+    /// Split settlement: one multisig transfer with two destinations
+    ///
+    /// Amounts are in atomic units, before the network fee (which is
+    /// shared pro rata when the transfer is built).
+    Split {
+        buyer_amount: u64,
+        vendor_amount: u64,
+    },
+}
+
+impl ArbiterResolution {
+    /// Short name stored in escrow state and sent in notifications
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArbiterResolution::Buyer => "buyer",
+            ArbiterResolution::Vendor => "vendor",
+            ArbiterResolution::Split { .. } => "split",
+        }
+    }
+
+    /// Bytes bound into the decision signature
+    ///
+    /// For splits the amounts are included, so a signed 60/40 decision
+    /// cannot be replayed as a different split.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        match self {
+            ArbiterResolution::Split {
+                buyer_amount,
+                vendor_amount,
+            } => format!("split:{}:{}", buyer_amount, vendor_amount).into_bytes(),
+            other => other.as_str().as_bytes().to_vec(),
+        }
+    }
+
+    /// Escrow status once the decision is imported
+    ///
+    /// Buyer rulings refund the escrow; vendor and split rulings complete it.
+    pub fn escrow_status(&self) -> EscrowStatus {
+        match self {
+            ArbiterResolution::Buyer => EscrowStatus::Refunded,
+            ArbiterResolution::Vendor | ArbiterResolution::Split { .. } => EscrowStatus::Completed,
+        }
+    }
 }
 
 impl DisputeRequest {
@@ -202,6 +278,55 @@ impl DisputeRequest {
             anyhow::bail!("Invalid partial_tx_hex: must be hexadecimal");
         }
 
+        // Check evidence list matches the announced count
+        if self.evidence.len() != self.evidence_file_count {
+            anyhow::bail!(
+                "Invalid evidence: {} files announced, {} hashes listed",
+                self.evidence_file_count,
+                self.evidence.len()
+            );
+        }
+
+        for file in &self.evidence {
+            if file.file_name.is_empty()
+                || file.file_name.starts_with('.')
+                || file.file_name.contains(['/', '\\'])
+            {
+                anyhow::bail!("Invalid evidence file name: {:?}", file.file_name);
+            }
+            if file.sha256.len() != 64 || !file.sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+                anyhow::bail!("Invalid evidence hash for {}: expected 64 hex chars", file.file_name);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check an evidence bundle (USB directory) against the hash list
+    ///
+    /// Fails if a listed file is missing, differs from its hash, or if the
+    /// directory holds files that are not listed.
+    pub fn verify_evidence_bundle(&self, dir: &Path) -> Result<()> {
+        use sha2::{Digest, Sha256};
+
+        for file in &self.evidence {
+            let data = std::fs::read(dir.join(&file.file_name))
+                .context(format!("Evidence file missing from bundle: {}", file.file_name))?;
+
+            if data.len() as u64 != file.size_bytes
+                || !hex::encode(Sha256::digest(&data)).eq_ignore_ascii_case(&file.sha256)
+            {
+                anyhow::bail!("Evidence file does not match its hash: {}", file.file_name);
+            }
+        }
+
+        for entry in std::fs::read_dir(dir).context("Failed to read evidence bundle")? {
+            let name = entry?.file_name().to_string_lossy().into_owned();
+            if !self.evidence.iter().any(|file| file.file_name == name) {
+                anyhow::bail!("Unexpected file in evidence bundle: {}", name);
+            }
+        }
+
         Ok(())
     }
 }
@@ -259,6 +384,17 @@ impl ArbiterDecision {
             anyhow::bail!("Invalid signed_tx_hex: must be hexadecimal");
         }
 
+        // A split must pay both parties (otherwise it is a plain buyer/vendor decision)
+        if let ArbiterResolution::Split {
+            buyer_amount,
+            vendor_amount,
+        } = self.decision
+        {
+            if buyer_amount == 0 || vendor_amount == 0 {
+                anyhow::bail!("Invalid split: both buyer and vendor amounts must be non-zero");
+            }
+        }
+
         // Check decision_signature is hex and correct length (128 hex chars = 64 bytes)
         if self.decision_signature.len() != 128 {
             anyhow::bail!("Invalid decision_signature: expected 128 hex chars (64 bytes)");
@@ -300,11 +436,7 @@ impl ArbiterDecision {
         let mut hasher = Blake2b512::new();
         hasher.update(self.escrow_id.as_bytes());
         hasher.update(self.nonce.as_bytes());
-        let decision_bytes: &[u8] = match self.decision {
-            ArbiterResolution::Buyer => b"buyer",
-            ArbiterResolution::Vendor => b"vendor",
-        };
-        hasher.update(decision_bytes);
+        hasher.update(self.decision.signing_bytes());
         hasher.update(self.signed_tx_hex.as_bytes());
         let message = hasher.finalize();
 
@@ -371,7 +503,9 @@ mod tests {
             buyer_claim: "Item not received".to_string(),
             vendor_response: Some("Shipped on 2025-10-20".to_string()),
             dispute_opened_at: 1698765432,
-            evidence_file_count: 3,
+            evidence_file_count: 0,
+            evidence: Vec::new(),
+            evidence_deadline: 1698765432 + 259200,
             partial_tx_hex: "abc123def456".to_string(),
             nonce: hex::encode(&random_bytes_32()),
         };
@@ -400,6 +534,8 @@ mod tests {
             vendor_response: None,
             dispute_opened_at: chrono::Utc::now().timestamp(),
             evidence_file_count: 0,
+            evidence: Vec::new(),
+            evidence_deadline: chrono::Utc::now().timestamp() + 259200,
             partial_tx_hex: "abc123".to_string(),
             nonce: hex::encode(&random_bytes_32()),
         };
@@ -420,10 +556,43 @@ mod tests {
         // Short nonce should fail
         request.nonce = "abc".to_string();
         assert!(request.validate().is_err());
+        request.nonce = hex::encode(&random_bytes_32());
+
+        // Evidence count must match the hash list
+        request.evidence_file_count = 1;
+        assert!(request.validate().is_err());
+
+        // Evidence file names cannot escape the bundle directory
+        request.evidence.push(EvidenceHash {
+            file_name: "../photo.jpg".to_string(),
+            sha256: "ab".repeat(32),
+            size_bytes: 10,
+            kind: "photo".to_string(),
+            submitted_by: "buyer".to_string(),
+        });
+        assert!(request.validate().is_err());
+        request.evidence[0].file_name = "photo.jpg".to_string();
+        assert!(request.validate().is_ok());
 
         Ok(())
     }
 
+    #[test]
+    fn test_old_dispute_request_without_evidence_list() -> Result<()> {
+        let json = format!(
+            r#"{{"escrow_id":"{}","buyer_id":"{}","vendor_id":"{}","amount":1,
+               "buyer_claim":"x","vendor_response":null,"dispute_opened_at":0,
+               "evidence_file_count":0,"partial_tx_hex":"ab","nonce":"n"}}"#,
+            Uuid::new_v4(),
+            Uuid::new_v4(),
+            Uuid::new_v4()
+        );
+        let request = DisputeRequest::from_json(&json)?;
+        assert!(request.evidence.is_empty());
+        assert_eq!(request.evidence_deadline, 0);
+        Ok(())
+    }
+
     #[test]
     fn test_arbiter_decision_validation() -> Result<()> {
         let mut decision = ArbiterDecision {
@@ -496,4 +665,93 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_split_resolution_serialization() -> Result<()> {
+        // Existing decisions keep their plain string form
+        assert_eq!(serde_json::to_string(&ArbiterResolution::Buyer)?, "\"buyer\"");
+
+        let split = ArbiterResolution::Split {
+            buyer_amount: 600_000_000_000,
+            vendor_amount: 400_000_000_000,
+        };
+        let json = serde_json::to_string(&split)?;
+        assert_eq!(
+            json,
+            r#"{"split":{"buyer_amount":600000000000,"vendor_amount":400000000000}}"#
+        );
+        assert_eq!(serde_json::from_str::<ArbiterResolution>(&json)?, split);
+        assert_eq!(split.as_str(), "split");
+        assert_eq!(split.signing_bytes(), b"split:600000000000:400000000000".to_vec());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_decision_validation() -> Result<()> {
+        let mut decision = ArbiterDecision {
+            escrow_id: Uuid::new_v4(),
+            nonce: hex::encode(&random_bytes_32()),
+            decision: ArbiterResolution::Split {
+                buyer_amount: 600_000_000_000,
+                vendor_amount: 400_000_000_000,
+            },
+            reason: "Half of the order arrived damaged".to_string(),
+            signed_tx_hex: "def789abc123".to_string(),
+            decision_signature: hex::encode(&random_bytes_64()),
+            decided_at: chrono::Utc::now().timestamp(),
+        };
+        assert!(decision.validate().is_ok());
+
+        decision.decision = ArbiterResolution::Split {
+            buyer_amount: 1_000_000_000_000,
+            vendor_amount: 0,
+        };
+        assert!(decision.validate().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_signature_binds_amounts() -> Result<()> {
+        use ed25519_dalek::{Signer, SigningKey};
+        use blake2::{Blake2b512, Digest};
+
+        let signing_key = SigningKey::from_bytes(&random_bytes_32());
+        let pubkey_hex = hex::encode(signing_key.verifying_key().as_bytes());
+
+        let escrow_id = Uuid::new_v4();
+        let nonce = hex::encode(&random_bytes_32());
+        let signed_tx_hex = "abc123def456".to_string();
+
+        let mut hasher = Blake2b512::new();
+        hasher.update(escrow_id.as_bytes());
+        hasher.update(nonce.as_bytes());
+        hasher.update(b"split:600000000000:400000000000");
+        hasher.update(signed_tx_hex.as_bytes());
+        let signature = signing_key.sign(&hasher.finalize());
+
+        let mut decision = ArbiterDecision {
+            escrow_id,
+            nonce,
+            decision: ArbiterResolution::Split {
+                buyer_amount: 600_000_000_000,
+                vendor_amount: 400_000_000_000,
+            },
+            reason: "Partial delivery".to_string(),
+            signed_tx_hex,
+            decision_signature: hex::encode(signature.to_bytes()),
+            decided_at: chrono::Utc::now().timestamp(),
+        };
+        assert!(decision.verify_signature(&pubkey_hex).is_ok());
+
+        // Same signature, tampered split
+        decision.decision = ArbiterResolution::Split {
+            buyer_amount: 400_000_000_000,
+            vendor_amount: 600_000_000_000,
+        };
+        assert!(decision.verify_signature(&pubkey_hex).is_err());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,392 @@
+//! Arbiter pool: assignment, workload balancing and recusal
+//!
+//! Every user with the `arbiter` role is in the pool. A new escrow goes to
+//! the arbiter with the fewest open disputes (then fewest open escrows),
+//! skipping arbiters at capacity and any arbiter who has traded with the
+//! buyer or the vendor.
+//!
+//! An escrow changes arbiter when its arbiter recuses, when an admin moves
+//! it, or when the dispute times out ([`crate::services::timeout_monitor`]).
+//! The new arbiter never is one who already held the escrow. If the
+//! multisig was not set up yet, the setup restarts with the new arbiter's
+//! wallet; afterwards the new arbiter takes over the review while the
+//! marketplace's arbiter key still signs the ruling (see
+//! [`crate::services::airgap`]).
+
+use actix::Addr;
+use anyhow::{Context, Result};
+use diesel::prelude::*;
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::ArbiterPoolConfig;
+use crate::coordination::EscrowCoordinator;
+use crate::db::DbPool;
+use crate::models::arbiter_assignment::{ArbiterAssignment, AssignmentReason};
+use crate::models::escrow::{terminal_statuses, Escrow, EscrowStatus};
+use crate::models::user::User;
+use crate::schema::{escrows, orders};
+use crate::websocket::{NotifyUser, WebSocketServer, WsEvent};
+
+/// Current load of one arbiter
+#[derive(Debug, Clone, Serialize)]
+pub struct ArbiterWorkload {
+    pub arbiter_id: String,
+    pub username: String,
+    /// Escrows of this arbiter in `disputed`
+    pub open_disputes: i64,
+    /// Escrows of this arbiter not yet in a terminal status
+    pub open_escrows: i64,
+}
+
+/// Outcome of moving an escrow to another arbiter
+#[derive(Debug, Clone)]
+pub struct Reassignment {
+    /// Escrow with its new arbiter
+    pub escrow: Escrow,
+    pub previous_arbiter_id: String,
+    /// Multisig setup discarded and to be run again
+    pub multisig_restarted: bool,
+}
+
+/// Load of every arbiter in the pool
+pub fn arbiter_workloads(conn: &mut SqliteConnection) -> Result<Vec<ArbiterWorkload>> {
+    let terminal = terminal_statuses();
+    User::find_by_role(conn, "arbiter")?
+        .into_iter()
+        .map(|arbiter| {
+            let open_disputes: i64 = escrows::table
+                .filter(escrows::arbiter_id.eq(&arbiter.id))
+                .filter(escrows::status.eq(EscrowStatus::Disputed.as_str()))
+                .count()
+                .get_result(conn)
+                .context("Failed to count open disputes")?;
+            let open_escrows: i64 = escrows::table
+                .filter(escrows::arbiter_id.eq(&arbiter.id))
+                .filter(escrows::status.ne_all(&terminal))
+                .count()
+                .get_result(conn)
+                .context("Failed to count open escrows")?;
+
+            Ok(ArbiterWorkload {
+                arbiter_id: arbiter.id,
+                username: arbiter.username,
+                open_disputes,
+                open_escrows,
+            })
+        })
+        .collect()
+}
+
+/// Whether `arbiter_id` bought from or sold to any of `party_ids`
+pub fn has_traded_with(
+    conn: &mut SqliteConnection,
+    arbiter_id: &str,
+    party_ids: &[&str],
+) -> Result<bool> {
+    if party_ids.contains(&arbiter_id) {
+        return Ok(true);
+    }
+
+    let trades: i64 = orders::table
+        .filter(
+            orders::buyer_id
+                .eq(arbiter_id)
+                .and(orders::vendor_id.eq_any(party_ids))
+                .or(orders::vendor_id
+                    .eq(arbiter_id)
+                    .and(orders::buyer_id.eq_any(party_ids))),
+        )
+        .count()
+        .get_result(conn)
+        .context("Failed to check arbiter trade history")?;
+    Ok(trades > 0)
+}
+
+/// Pick the least loaded eligible arbiter for an escrow between `buyer_id` and `vendor_id`
+///
+/// Arbiters in `excluded`, at capacity, or who traded with either party are skipped.
+pub fn select_arbiter(
+    conn: &mut SqliteConnection,
+    config: &ArbiterPoolConfig,
+    buyer_id: &str,
+    vendor_id: &str,
+    excluded: &[String],
+) -> Result<ArbiterWorkload> {
+    let mut candidates = Vec::new();
+    for workload in arbiter_workloads(conn)? {
+        if excluded.contains(&workload.arbiter_id) || !config.has_capacity(workload.open_disputes)
+        {
+            continue;
+        }
+        if has_traded_with(conn, &workload.arbiter_id, &[buyer_id, vendor_id])? {
+            continue;
+        }
+        candidates.push(workload);
+    }
+
+    candidates
+        .into_iter()
+        .min_by(|a, b| {
+            (a.open_disputes, a.open_escrows, &a.arbiter_id).cmp(&(
+                b.open_disputes,
+                b.open_escrows,
+                &b.arbiter_id,
+            ))
+        })
+        .ok_or_else(|| anyhow::anyhow!("No eligible arbiter available"))
+}
+
+/// Move an escrow to a new arbiter, recording why
+///
+/// Runs in one transaction: the open assignment is closed, `escrows.arbiter_id`
+/// updated (restarting the multisig setup if it had not completed) and the
+/// new assignment opened.
+pub fn reassign_arbiter(
+    conn: &mut SqliteConnection,
+    config: &ArbiterPoolConfig,
+    escrow_id: &str,
+    reason: AssignmentReason,
+    note: Option<&str>,
+) -> Result<Reassignment> {
+    conn.transaction::<_, anyhow::Error, _>(|conn| {
+        let escrow = Escrow::find_by_id(conn, escrow_id.to_string())?;
+        if escrow.get_status()?.is_terminal() {
+            anyhow::bail!("Escrow {} is closed ({})", escrow_id, escrow.status);
+        }
+
+        // Nobody gets an escrow back once they held it
+        let mut excluded: Vec<String> = ArbiterAssignment::find_by_escrow(conn, escrow_id)?
+            .into_iter()
+            .map(|assignment| assignment.arbiter_id)
+            .collect();
+        excluded.push(escrow.arbiter_id.clone());
+
+        let selected =
+            select_arbiter(conn, config, &escrow.buyer_id, &escrow.vendor_id, &excluded)?;
+        let restart = escrow.is_multisig_unstarted();
+
+        ArbiterAssignment::end_open(conn, escrow_id, reason.end_reason(), note)?;
+        Escrow::update_arbiter(conn, escrow_id, &selected.arbiter_id, restart)?;
+        ArbiterAssignment::start(conn, escrow_id, &selected.arbiter_id, reason, note)?;
+
+        Ok(Reassignment {
+            escrow: Escrow::find_by_id(conn, escrow_id.to_string())?,
+            previous_arbiter_id: escrow.arbiter_id,
+            multisig_restarted: restart,
+        })
+    })
+}
+
+/// Assigns arbiters from the pool and handles recusals and reassignments
+pub struct ArbiterAssignmentService {
+    db: DbPool,
+    config: ArbiterPoolConfig,
+    /// Notifies the parties and both arbiters of a reassignment
+    websocket: Option<Addr<WebSocketServer>>,
+    /// Coordination state to drop when the multisig setup restarts
+    coordinator: Option<Arc<EscrowCoordinator>>,
+}
+
+impl ArbiterAssignmentService {
+    /// Create a new ArbiterAssignmentService
+    ///
+    /// # Arguments
+    /// * `db` - Database connection pool
+    /// * `config` - Capacity and escalation limits
+    pub fn new(db: DbPool, config: ArbiterPoolConfig) -> Self {
+        Self {
+            db,
+            config,
+            websocket: None,
+            coordinator: None,
+        }
+    }
+
+    /// Send `ArbiterReassigned` notifications through `websocket`
+    pub fn with_websocket(mut self, websocket: Addr<WebSocketServer>) -> Self {
+        self.websocket = Some(websocket);
+        self
+    }
+
+    /// Reset non-custodial coordination when a multisig setup restarts
+    pub fn with_coordinator(mut self, coordinator: Arc<EscrowCoordinator>) -> Self {
+        self.coordinator = Some(coordinator);
+        self
+    }
+
+    /// Capacity and escalation limits in use
+    pub fn config(&self) -> &ArbiterPoolConfig {
+        &self.config
+    }
+
+    /// Arbiter for a new escrow between `buyer_id` and `vendor_id`
+    pub async fn pick_for_new_escrow(&self, buyer_id: Uuid, vendor_id: Uuid) -> Result<String> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let config = self.config.clone();
+        let selected = tokio::task::spawn_blocking(move || {
+            select_arbiter(
+                &mut conn,
+                &config,
+                &buyer_id.to_string(),
+                &vendor_id.to_string(),
+                &[],
+            )
+        })
+        .await
+        .context("Task join error")??;
+
+        info!(
+            "Selected arbiter {} ({}) with {} open disputes",
+            selected.username, selected.arbiter_id, selected.open_disputes
+        );
+        Ok(selected.arbiter_id)
+    }
+
+    /// Load of every arbiter in the pool
+    pub async fn workloads(&self) -> Result<Vec<ArbiterWorkload>> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        tokio::task::spawn_blocking(move || arbiter_workloads(&mut conn))
+            .await
+            .context("Task join error")?
+    }
+
+    /// Assignment history of an escrow, oldest first
+    pub async fn history(&self, escrow_id: Uuid) -> Result<Vec<ArbiterAssignment>> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        tokio::task::spawn_blocking(move || {
+            ArbiterAssignment::find_by_escrow(&mut conn, &escrow_id.to_string())
+        })
+        .await
+        .context("Task join error")?
+    }
+
+    /// Current arbiter steps down from an escrow, which goes to another arbiter
+    pub async fn recuse(
+        &self,
+        escrow_id: Uuid,
+        arbiter_id: Uuid,
+        reason: &str,
+    ) -> Result<Reassignment> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let escrow = tokio::task::spawn_blocking(move || {
+            Escrow::find_by_id(&mut conn, escrow_id.to_string())
+        })
+        .await
+        .context("Task join error")??;
+
+        if escrow.arbiter_id != arbiter_id.to_string() {
+            anyhow::bail!("Only the escrow's current arbiter can recuse");
+        }
+
+        self.reassign(escrow_id, AssignmentReason::Recusal, Some(reason.to_string()))
+            .await
+    }
+
+    /// Hand a timed-out dispute to another arbiter
+    ///
+    /// Fails once the dispute was escalated `max_escalation_reassignments`
+    /// times, leaving it to an admin.
+    pub async fn escalate(&self, escrow_id: Uuid) -> Result<Reassignment> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let escalations = tokio::task::spawn_blocking(move || {
+            ArbiterAssignment::count_by_reason(
+                &mut conn,
+                &escrow_id.to_string(),
+                AssignmentReason::Escalation,
+            )
+        })
+        .await
+        .context("Task join error")??;
+
+        if escalations >= i64::from(self.config.max_escalation_reassignments) {
+            anyhow::bail!(
+                "Dispute already reassigned {} times on timeout",
+                escalations
+            );
+        }
+
+        self.reassign(
+            escrow_id,
+            AssignmentReason::Escalation,
+            Some("Dispute resolution timeout".to_string()),
+        )
+        .await
+    }
+
+    /// Move an escrow to the least loaded eligible arbiter
+    pub async fn reassign(
+        &self,
+        escrow_id: Uuid,
+        reason: AssignmentReason,
+        note: Option<String>,
+    ) -> Result<Reassignment> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let config = self.config.clone();
+        let reassignment = tokio::task::spawn_blocking(move || {
+            reassign_arbiter(
+                &mut conn,
+                &config,
+                &escrow_id.to_string(),
+                reason,
+                note.as_deref(),
+            )
+        })
+        .await
+        .context("Task join error")??;
+
+        info!(
+            "Escrow {} reassigned from arbiter {} to {} ({}, multisig restarted: {})",
+            escrow_id,
+            reassignment.previous_arbiter_id,
+            reassignment.escrow.arbiter_id,
+            reason.as_str(),
+            reassignment.multisig_restarted
+        );
+
+        if reassignment.multisig_restarted {
+            if let Some(coordinator) = &self.coordinator {
+                coordinator.reset_coordination(&reassignment.escrow.id).await;
+            }
+        }
+
+        self.notify(escrow_id, &reassignment, reason);
+        Ok(reassignment)
+    }
+
+    /// Tell the parties and both arbiters about a reassignment
+    fn notify(&self, escrow_id: Uuid, reassignment: &Reassignment, reason: AssignmentReason) {
+        let Some(websocket) = &self.websocket else {
+            return;
+        };
+
+        let escrow = &reassignment.escrow;
+        let ids = [
+            &escrow.buyer_id,
+            &escrow.vendor_id,
+            &reassignment.previous_arbiter_id,
+            &escrow.arbiter_id,
+        ]
+        .map(|id| id.parse::<Uuid>());
+
+        let [Ok(buyer_id), Ok(vendor_id), Ok(previous_arbiter_id), Ok(arbiter_id)] = ids else {
+            warn!("Escrow {} has invalid party ids, reassignment not notified", escrow_id);
+            return;
+        };
+
+        for user_id in [buyer_id, vendor_id, previous_arbiter_id, arbiter_id] {
+            websocket.do_send(NotifyUser {
+                user_id,
+                event: WsEvent::ArbiterReassigned {
+                    escrow_id,
+                    previous_arbiter_id,
+                    arbiter_id,
+                    reason: reason.as_str().to_string(),
+                    multisig_restarted: reassignment.multisig_restarted,
+                },
+            });
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! Audit trail service
+//!
+//! Async front of [`crate::models::audit_log`] used by the orchestrator and
+//! handlers. Appends take the write lock up front and are retried if the
+//! database stays busy.
+
+use anyhow::{Context, Result};
+use tracing::{error, warn};
+
+use crate::db::DbPool;
+use crate::models::audit_checkpoint::AuditCheckpoint;
+use crate::models::audit_log::{AuditEntry, AuditEventType, ChainVerification};
+use crate::models::escrow_event::EscrowActor;
+
+/// Attempts at appending before giving up on a busy database
+const APPEND_ATTEMPTS: usize = 3;
+
+/// Largest page returned by [`AuditLogger::export`]
+pub const MAX_EXPORT_PAGE: i64 = 1000;
+
+/// Writes and checks the hash-chained audit log
+#[derive(Clone)]
+pub struct AuditLogger {
+    db: DbPool,
+}
+
+impl AuditLogger {
+    pub fn new(db: DbPool) -> Self {
+        Self { db }
+    }
+
+    /// Append an entry to the chain
+    pub async fn record(
+        &self,
+        event_type: AuditEventType,
+        entity_id: impl ToString,
+        actor: EscrowActor,
+        data: serde_json::Value,
+    ) -> Result<AuditEntry> {
+        let entity_id = entity_id.to_string();
+        let mut attempt = 1;
+        loop {
+            let mut conn = self.db.get().context("Failed to get DB connection")?;
+            let (entity, who, details) = (entity_id.clone(), actor.clone(), data.clone());
+            // BEGIN IMMEDIATE: concurrent writers wait for the lock instead of
+            // reading the same head
+            let result = tokio::task::spawn_blocking(move || {
+                conn.immediate_transaction(|conn| {
+                    AuditEntry::append(conn, event_type, &entity, &who, &details)
+                })
+            })
+            .await
+            .context("Task join error")?;
+
+            match result {
+                Ok(entry) => return Ok(entry),
+                Err(e) if attempt < APPEND_ATTEMPTS => {
+                    warn!(
+                        "Audit append for {} {} failed (attempt {}), retrying: {:#}",
+                        event_type, entity_id, attempt, e
+                    );
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Append an entry, logging instead of failing
+    ///
+    /// For actions that already took effect (an arbiter reassignment), where
+    /// reporting an error to the caller would suggest they did not.
+    pub async fn record_or_log(
+        &self,
+        event_type: AuditEventType,
+        entity_id: impl ToString,
+        actor: EscrowActor,
+        data: serde_json::Value,
+    ) {
+        let entity_id = entity_id.to_string();
+        if let Err(e) = self
+            .record(event_type, &entity_id, actor, data.clone())
+            .await
+        {
+            error!(
+                "AUDIT ENTRY LOST for {} {}: {} ({:#})",
+                event_type, entity_id, data, e
+            );
+        }
+    }
+
+    /// Recompute the whole chain
+    pub async fn verify(&self) -> Result<ChainVerification> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        tokio::task::spawn_blocking(move || AuditEntry::verify_chain(&mut conn))
+            .await
+            .context("Task join error")?
+    }
+
+    /// Entries after `after_sequence`, oldest first, at most [`MAX_EXPORT_PAGE`]
+    pub async fn export(&self, after_sequence: i64, limit: i64) -> Result<Vec<AuditEntry>> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let limit = limit.clamp(1, MAX_EXPORT_PAGE);
+        tokio::task::spawn_blocking(move || {
+            AuditEntry::list_after(&mut conn, after_sequence, limit)
+        })
+        .await
+        .context("Task join error")?
+    }
+
+    /// Signed checkpoints of the chain head, oldest first
+    pub async fn checkpoints(&self) -> Result<Vec<AuditCheckpoint>> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        tokio::task::spawn_blocking(move || AuditCheckpoint::list(&mut conn))
+            .await
+            .context("Task join error")?
+    }
+}
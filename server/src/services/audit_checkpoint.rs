@@ -0,0 +1,310 @@
+//! Signed audit log checkpoints and offline verification of exports
+//!
+//! [`AuditCheckpointer`] periodically signs the head of the audit log with
+//! the marketplace key, stores the checkpoint and appends it to a published
+//! file. [`verify_export`] is what an external auditor runs (through the
+//! `verify_audit_export` binary): given an exported log, the published
+//! checkpoints and the marketplace public key, it reports gaps, altered
+//! entries and any divergence from what was signed.
+
+use anyhow::{Context, Result};
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Write;
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::config::AuditCheckpointPolicy;
+use crate::crypto::signing::MarketplaceSigner;
+use crate::db::DbPool;
+use crate::models::audit_checkpoint::AuditCheckpoint;
+use crate::models::audit_log::AuditEntry;
+
+/// File the checkpoints are appended to in the publish directory
+pub const CHECKPOINT_FILE: &str = "audit-checkpoints.jsonl";
+
+/// Signs and publishes audit log checkpoints
+pub struct AuditCheckpointer {
+    db: DbPool,
+    signer: MarketplaceSigner,
+    policy: AuditCheckpointPolicy,
+}
+
+impl AuditCheckpointer {
+    pub fn new(db: DbPool, signer: MarketplaceSigner, policy: AuditCheckpointPolicy) -> Self {
+        Self { db, signer, policy }
+    }
+
+    /// Public key verifiers need
+    pub fn public_key_hex(&self) -> String {
+        self.signer.public_key_hex()
+    }
+
+    /// Take checkpoints every `interval_secs`
+    pub async fn start(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(self.policy.interval());
+        loop {
+            interval.tick().await;
+            match self.checkpoint().await {
+                Ok(Some(checkpoint)) => info!(
+                    "Audit checkpoint signed at sequence {} ({})",
+                    checkpoint.sequence, checkpoint.head_hash
+                ),
+                Ok(None) => {}
+                Err(e) => error!("Audit checkpoint failed: {:#}", e),
+            }
+        }
+    }
+
+    /// Sign the current head if the log grew since the last checkpoint
+    ///
+    /// Refuses to sign when the chain does not verify or no longer contains
+    /// the hash of the previous checkpoint.
+    pub async fn checkpoint(&self) -> Result<Option<AuditCheckpoint>> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let (head, latest) = tokio::task::spawn_blocking(move || -> Result<_> {
+            let verification = AuditEntry::verify_chain(&mut conn)?;
+            if let Some(sequence) = verification.broken_at {
+                anyhow::bail!(
+                    "Audit log broken at sequence {}: {}",
+                    sequence,
+                    verification.error.unwrap_or_default()
+                );
+            }
+
+            let latest = AuditCheckpoint::latest(&mut conn)?;
+            if let Some(checkpoint) = &latest {
+                let signed = AuditEntry::find_by_sequence(&mut conn, checkpoint.sequence)?;
+                if signed.map(|entry| entry.entry_hash).as_ref() != Some(&checkpoint.head_hash) {
+                    anyhow::bail!(
+                        "Audit log no longer matches checkpoint at sequence {}",
+                        checkpoint.sequence
+                    );
+                }
+            }
+
+            let head = verification
+                .head_hash
+                .map(|hash| (verification.entries_verified as i64, hash));
+            Ok((head, latest))
+        })
+        .await
+        .context("Task join error")??;
+
+        let Some((sequence, head_hash)) = head else {
+            return Ok(None);
+        };
+        if latest.is_some_and(|checkpoint| checkpoint.sequence >= sequence) {
+            return Ok(None);
+        }
+
+        let created_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+        let checkpoint = AuditCheckpoint {
+            signature: self.signer.sign_hex(&AuditCheckpoint::statement(
+                sequence,
+                &head_hash,
+                &created_at,
+            )),
+            sequence,
+            head_hash,
+            created_at,
+            public_key: self.signer.public_key_hex(),
+        };
+
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let stored = checkpoint.clone();
+        tokio::task::spawn_blocking(move || AuditCheckpoint::insert(&mut conn, &stored))
+            .await
+            .context("Task join error")??;
+
+        self.publish(&checkpoint)?;
+        Ok(Some(checkpoint))
+    }
+
+    /// Append the checkpoint to the published file, if configured
+    fn publish(&self, checkpoint: &AuditCheckpoint) -> Result<()> {
+        let Some(dir) = &self.policy.publish_dir else {
+            return Ok(());
+        };
+
+        std::fs::create_dir_all(dir).context(format!("Failed to create {}", dir.display()))?;
+        let path = dir.join(CHECKPOINT_FILE);
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .context(format!("Failed to open {}", path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(checkpoint)?)
+            .context(format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Problem found in an exported audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AuditFinding {
+    /// Sequences `from..=to` are missing from the export
+    Gap { from: i64, to: i64 },
+    /// The same sequence appears more than once
+    Duplicate { sequence: i64 },
+    /// `previous_hash` does not match the entry before it
+    BrokenLink { sequence: i64 },
+    /// `entry_hash` does not match the entry content
+    AlteredEntry { sequence: i64 },
+    /// Checkpoint not signed by the marketplace key
+    BadCheckpointSignature { sequence: i64, reason: String },
+    /// The export has another hash at a signed sequence (history rewritten)
+    CheckpointMismatch { sequence: i64 },
+    /// The export stops before a signed sequence (entries dropped from the end)
+    Truncated { sequence: i64 },
+}
+
+impl fmt::Display for AuditFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditFinding::Gap { from, to } => write!(f, "entries {}..={} missing", from, to),
+            AuditFinding::Duplicate { sequence } => {
+                write!(f, "entry {} appears more than once", sequence)
+            }
+            AuditFinding::BrokenLink { sequence } => {
+                write!(f, "entry {} does not link to the entry before it", sequence)
+            }
+            AuditFinding::AlteredEntry { sequence } => {
+                write!(f, "entry {} content does not match its hash", sequence)
+            }
+            AuditFinding::BadCheckpointSignature { sequence, reason } => {
+                write!(
+                    f,
+                    "checkpoint {} has an invalid signature: {}",
+                    sequence, reason
+                )
+            }
+            AuditFinding::CheckpointMismatch { sequence } => write!(
+                f,
+                "entry {} differs from the signed checkpoint (log rewritten)",
+                sequence
+            ),
+            AuditFinding::Truncated { sequence } => write!(
+                f,
+                "export ends before signed checkpoint {} (entries removed)",
+                sequence
+            ),
+        }
+    }
+}
+
+/// Outcome of [`verify_export`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportReport {
+    pub entries_checked: usize,
+    pub first_sequence: Option<i64>,
+    pub last_sequence: Option<i64>,
+    /// Checkpoints whose signature and head hash both match the export
+    pub checkpoints_matched: usize,
+    pub findings: Vec<AuditFinding>,
+}
+
+impl ExportReport {
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
+
+/// Check an exported audit log against the published checkpoints
+///
+/// An export may start after sequence 1 if a valid checkpoint signs the
+/// hash its first entry links to.
+pub fn verify_export(
+    entries: &[AuditEntry],
+    checkpoints: &[AuditCheckpoint],
+    public_key_hex: &str,
+) -> ExportReport {
+    let mut findings = Vec::new();
+
+    let mut by_sequence: BTreeMap<i64, &AuditEntry> = BTreeMap::new();
+    for entry in entries {
+        if by_sequence.insert(entry.sequence, entry).is_some() {
+            findings.push(AuditFinding::Duplicate {
+                sequence: entry.sequence,
+            });
+        }
+    }
+
+    let mut signed: BTreeMap<i64, &AuditCheckpoint> = BTreeMap::new();
+    for checkpoint in checkpoints {
+        match checkpoint.verify_signature(public_key_hex) {
+            Ok(()) => {
+                signed.insert(checkpoint.sequence, checkpoint);
+            }
+            Err(e) => findings.push(AuditFinding::BadCheckpointSignature {
+                sequence: checkpoint.sequence,
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    let first_sequence = by_sequence.keys().next().copied();
+    let last_sequence = by_sequence.keys().next_back().copied();
+
+    let mut previous: Option<&AuditEntry> = None;
+    for (&sequence, entry) in &by_sequence {
+        if entry.expected_hash() != entry.entry_hash {
+            findings.push(AuditFinding::AlteredEntry { sequence });
+        }
+
+        match previous {
+            Some(prev) if prev.sequence + 1 == sequence => {
+                if entry.previous_hash.as_deref() != Some(prev.entry_hash.as_str()) {
+                    findings.push(AuditFinding::BrokenLink { sequence });
+                }
+            }
+            Some(prev) => findings.push(AuditFinding::Gap {
+                from: prev.sequence + 1,
+                to: sequence - 1,
+            }),
+            None if sequence == 1 => {
+                if entry.previous_hash.is_some() {
+                    findings.push(AuditFinding::BrokenLink { sequence });
+                }
+            }
+            None => match signed.get(&(sequence - 1)) {
+                // Partial export anchored on a signed head
+                Some(anchor) => {
+                    if entry.previous_hash.as_deref() != Some(anchor.head_hash.as_str()) {
+                        findings.push(AuditFinding::CheckpointMismatch {
+                            sequence: sequence - 1,
+                        });
+                    }
+                }
+                None => findings.push(AuditFinding::Gap {
+                    from: 1,
+                    to: sequence - 1,
+                }),
+            },
+        }
+        previous = Some(entry);
+    }
+
+    let mut checkpoints_matched = 0;
+    for (&sequence, checkpoint) in &signed {
+        match by_sequence.get(&sequence) {
+            Some(entry) if entry.entry_hash == checkpoint.head_hash => checkpoints_matched += 1,
+            Some(_) => findings.push(AuditFinding::CheckpointMismatch { sequence }),
+            None if last_sequence.is_none_or(|last| sequence > last) => {
+                findings.push(AuditFinding::Truncated { sequence })
+            }
+            // Before the export or inside a gap already reported
+            None => {}
+        }
+    }
+
+    ExportReport {
+        entries_checked: by_sequence.len(),
+        first_sequence,
+        last_sequence,
+        checkpoints_matched,
+        findings,
+    }
+}
@@ -7,6 +7,9 @@
 
 use actix::Addr;
 use anyhow::{Context, Result};
+use monero_marketplace_common::clock::{SharedClock, SystemClock};
+use monero_marketplace_common::types::TransactionInfo;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
@@ -14,7 +17,10 @@ use tokio::time::interval;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
-use crate::db::{db_load_escrow, db_update_escrow_status, DbPool};
+use crate::config::ConfirmationPolicy;
+use crate::db::{db_load_escrow, db_transition_escrow, db_update_escrow_funded_amount, DbPool};
+use crate::models::escrow::EscrowStatus;
+use crate::models::escrow_event::EscrowActor;
 use crate::models::order::{Order, OrderStatus};
 use crate::wallet_manager::WalletManager;
 use crate::websocket::WebSocketServer;
@@ -26,8 +32,9 @@ use crate::wallet_pool::WalletRole;
 pub struct MonitorConfig {
     /// How often to check for transaction updates (in seconds)
     pub poll_interval_secs: u64,
-    /// Number of confirmations required to consider a transaction settled
-    pub required_confirmations: u32,
+    /// Confirmations required, by escrow amount, before a deposit counts
+    /// and before a release/refund transaction is considered settled
+    pub confirmations: ConfirmationPolicy,
     /// Maximum number of blocks to scan in a single poll
     pub max_blocks_per_poll: u64,
 }
@@ -36,7 +43,7 @@ impl Default for MonitorConfig {
     fn default() -> Self {
         Self {
             poll_interval_secs: 30,
-            required_confirmations: 10,
+            confirmations: ConfirmationPolicy::default(),
             max_blocks_per_poll: 100,
         }
     }
@@ -50,6 +57,9 @@ pub struct BlockchainMonitor {
     #[allow(dead_code)]
     websocket: Addr<WebSocketServer>,
     config: MonitorConfig,
+    clock: SharedClock,
+    /// Last confirmation count sent per tx hash, so each poll only notifies changes
+    reported_confirmations: std::sync::Mutex<HashMap<String, u32>>,
 }
 
 impl BlockchainMonitor {
@@ -62,8 +72,10 @@ impl BlockchainMonitor {
         config: MonitorConfig,
     ) -> Self {
         info!(
-            "BlockchainMonitor initialized with poll_interval={}s, required_confirmations={}",
-            config.poll_interval_secs, config.required_confirmations
+            "BlockchainMonitor initialized with poll_interval={}s, base_confirmations={}, confirmation_tiers={}",
+            config.poll_interval_secs,
+            config.confirmations.base_confirmations,
+            config.confirmations.tiers.len()
         );
         Self {
             wallet_manager,
@@ -71,9 +83,20 @@ impl BlockchainMonitor {
             db,
             websocket,
             config,
+            clock: SystemClock::shared(),
+            reported_confirmations: std::sync::Mutex::new(HashMap::new()),
         }
     }
 
+    /// Count confirmations against `clock` instead of the wallet RPC
+    ///
+    /// Only clocks that track the chain height change anything; with the
+    /// default `SystemClock` the RPC `confirmations` field is used.
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Start monitoring in background
     ///
     /// This spawns a background task that periodically checks for:
@@ -95,7 +118,10 @@ impl BlockchainMonitor {
     }
 
     /// Poll all active escrows for transaction updates
-    async fn poll_escrows(&self) -> Result<()> {
+    ///
+    /// Called on each tick of `start_monitoring`; tests call it directly
+    /// after mining blocks on a simulated chain.
+    pub async fn poll_escrows(&self) -> Result<()> {
         // Get all escrows in 'funded' state (waiting for buyer to deposit)
         let funded_escrows = self.get_funded_escrows().await?;
 
@@ -146,6 +172,7 @@ impl BlockchainMonitor {
         // Escrow must have a multisig address
         let multisig_address = escrow
             .multisig_address
+            .as_deref()
             .ok_or_else(|| anyhow::anyhow!("Escrow {} has no multisig address", escrow_id))?;
 
         info!(
@@ -166,29 +193,62 @@ impl BlockchainMonitor {
             .await
             .context("Failed to get buyer wallet from session")?;
 
-        // Note: No explicit refresh() needed - Monero RPC auto-refreshes on balance queries
+        // Note: No explicit refresh() needed - Monero RPC auto-refreshes on transfer queries
         // The wallet is persistent in the session, so it stays synced with blockchain
 
-        // Get balance (instant - wallet already open!)
-        let (total_balance, unlocked_balance) = buyer_wallet.rpc().get_balance().await
-            .map_err(|e| anyhow::anyhow!("Failed to get wallet balance: {:?}", e))?;
+        // Incoming transfers (instant - wallet already open!)
+        let deposits = buyer_wallet.rpc().get_incoming_transfers().await
+            .map_err(|e| anyhow::anyhow!("Failed to get incoming transfers: {:?}", e))?;
+
+        // Only deposits with enough confirmations for this amount count
+        let required = self.config.confirmations.required_for(escrow.amount as u64);
+        let mut received: u64 = 0;
+        let mut confirmed: u64 = 0;
+        for deposit in &deposits {
+            let confirmations = self.confirmations_of(deposit);
+            received += deposit.amount;
+            if confirmations >= required {
+                confirmed += deposit.amount;
+            }
+            self.report_confirmations(escrow_id, &deposit.tx_hash, confirmations, required);
+        }
 
         info!(
-            "Escrow {} wallet balance: total={}, unlocked={}, expected={}",
-            escrow_id, total_balance, unlocked_balance, escrow.amount
+            "Escrow {} deposits: received={}, confirmed={} ({} confirmations required), expected={}",
+            escrow_id, received, confirmed, required, escrow.amount
         );
 
-        // Check if funds have arrived (use unlocked balance for safety)
-        if unlocked_balance >= escrow.amount as u64 {
+        // Top-ups are summed: remember everything that landed on the address
+        let expected = escrow.amount as u64;
+        let funded_amount_changed = received as i64 != escrow.funded_amount;
+        if funded_amount_changed {
+            db_update_escrow_funded_amount(&self.db, escrow_id, received as i64)
+                .await
+                .context("Failed to update escrow funded amount")?;
+        }
+
+        // Check if funds have arrived with enough confirmations
+        if confirmed >= expected {
+            let funded_status = if received > expected {
+                EscrowStatus::Overfunded
+            } else {
+                EscrowStatus::Active
+            };
             info!(
-                "Escrow {} is now funded! Updating status to 'active'",
-                escrow_id
+                "Escrow {} is now funded! Updating status to '{}'",
+                escrow_id, funded_status
             );
 
-            // Update escrow status to "active" (funds received, ready for transaction)
-            db_update_escrow_status(&self.db, escrow_id, "active")
-                .await
-                .context("Failed to update escrow status to active")?;
+            // Update escrow status to "active"/"overfunded" (funds received, ready for transaction)
+            db_transition_escrow(
+                &self.db,
+                escrow_id,
+                funded_status,
+                EscrowActor::System("blockchain_monitor"),
+                Some(&format!("Deposit confirmed ({} confirmations)", required)),
+            )
+            .await
+                .context("Failed to update escrow status to funded")?;
 
             // Update associated order status to "funded"
             let order_id = escrow.order_id.clone();
@@ -228,14 +288,71 @@ impl BlockchainMonitor {
             use crate::websocket::WsEvent;
             self.websocket.do_send(WsEvent::EscrowStatusChanged {
                 escrow_id,
-                new_status: "active".to_string(),
+                new_status: funded_status,
             });
 
+            if funded_status == EscrowStatus::Overfunded {
+                self.notify_buyer(
+                    &escrow.buyer_id,
+                    WsEvent::EscrowOverfunded {
+                        escrow_id,
+                        received_atomic: received as i64,
+                        expected_atomic: escrow.amount,
+                        surplus_atomic: (received - expected) as i64,
+                    },
+                );
+            }
+
+            for deposit in &deposits {
+                self.forget_confirmations(&deposit.tx_hash);
+            }
+
             info!("Escrow {} funding complete and parties notified", escrow_id);
+        } else if received > 0 && received < expected {
+            // Partial deposit: wait for a top-up and tell the buyer what is missing
+            let missing = expected - received;
+            info!(
+                "Escrow {} underfunded: {}/{} atomic units received, {} missing",
+                escrow_id, received, expected, missing
+            );
+
+            if escrow.get_status()? != EscrowStatus::Underfunded {
+                db_transition_escrow(
+                    &self.db,
+                    escrow_id,
+                    EscrowStatus::Underfunded,
+                    EscrowActor::System("blockchain_monitor"),
+                    Some(&format!(
+                        "Partial deposit: {} of {} atomic units",
+                        received, expected
+                    )),
+                )
+                .await
+                .context("Failed to update escrow status to underfunded")?;
+
+                use crate::websocket::WsEvent;
+                self.websocket.do_send(WsEvent::EscrowStatusChanged {
+                    escrow_id,
+                    new_status: EscrowStatus::Underfunded,
+                });
+            }
+
+            if funded_amount_changed {
+                use crate::websocket::WsEvent;
+                self.notify_buyer(
+                    &escrow.buyer_id,
+                    WsEvent::EscrowUnderfunded {
+                        escrow_id,
+                        received_atomic: received as i64,
+                        expected_atomic: escrow.amount,
+                        missing_atomic: missing as i64,
+                    },
+                );
+            }
         } else {
             info!(
-                "Escrow {} still waiting for funds: {}/{} atomic units",
-                escrow_id, unlocked_balance, escrow.amount
+                "Escrow {} still waiting for funds: {}/{} atomic units confirmed",
+                escrow_id, confirmed, escrow.amount
             );
         }
 
@@ -270,7 +387,11 @@ impl BlockchainMonitor {
         };
 
         // Only monitor transactions in releasing or refunding status
-        if !matches!(escrow.status.as_str(), "releasing" | "refunding") {
+        let current_status = escrow.get_status()?;
+        if !matches!(
+            current_status,
+            EscrowStatus::Releasing | EscrowStatus::Refunding
+        ) {
             return Ok(());
         }
 
@@ -310,7 +431,8 @@ impl BlockchainMonitor {
             }
         };
 
-        let confirmations = transfer_info.confirmations as u32;
+        let confirmations = self.confirmations_of(&transfer_info);
+        let required = self.config.confirmations.required_for(escrow.amount as u64);
 
         info!(
             "🚀 [PHASE 2] Transaction query completed instantly (wallet persistent in session)"
@@ -320,21 +442,23 @@ impl BlockchainMonitor {
             "Transaction {} has {} confirmations (required: {})",
             &tx_hash[..10],
             confirmations,
-            self.config.required_confirmations
+            required
         );
 
         // Check if transaction has enough confirmations
-        if confirmations >= self.config.required_confirmations {
+        if confirmations < required {
+            self.report_confirmations(escrow_id, tx_hash, confirmations, required);
+        } else {
             // Determine final status based on current status
-            let final_status = match escrow.status.as_str() {
-                "releasing" => {
+            let final_status = match current_status {
+                EscrowStatus::Releasing => {
                     // Transaction completed successfully → Trigger review invitation
                     self.trigger_review_invitation(escrow_id, tx_hash)
                         .await
                         .context("Failed to trigger review invitation")?;
-                    "completed"
+                    EscrowStatus::Completed
                 }
-                "refunding" => "refunded",
+                EscrowStatus::Refunding => EscrowStatus::Refunded,
                 _ => {
                     warn!(
                         "Unexpected escrow status {} for confirmation check",
@@ -352,16 +476,25 @@ impl BlockchainMonitor {
             );
 
             // Update escrow to final status
-            db_update_escrow_status(&self.db, escrow_id, final_status)
-                .await
+            db_transition_escrow(
+                &self.db,
+                escrow_id,
+                final_status,
+                EscrowActor::System("blockchain_monitor"),
+                Some(&format!("Transaction confirmed ({} confirmations)", confirmations)),
+            )
+            .await
                 .context("Failed to update escrow to final status")?;
 
             // Notify all parties via WebSocket
             use crate::websocket::WsEvent;
             self.websocket.do_send(WsEvent::TransactionConfirmed {
+                escrow_id,
                 tx_hash: tx_hash.clone(),
                 confirmations,
+                required_confirmations: required,
             });
+            self.forget_confirmations(tx_hash);
 
             info!(
                 "Escrow {} finalized with status '{}' (tx: {})",
@@ -386,6 +519,51 @@ impl BlockchainMonitor {
         Ok(())
     }
 
+    /// Confirmations of `tx`, counted against the clock when it tracks the chain height
+    fn confirmations_of(&self, tx: &TransactionInfo) -> u32 {
+        self.clock
+            .confirmations_at(tx.block_height)
+            .unwrap_or(tx.confirmations) as u32
+    }
+
+    /// Send "x of y confirmations" progress for `tx_hash` if it changed since the last poll
+    fn report_confirmations(&self, escrow_id: Uuid, tx_hash: &str, confirmations: u32, required: u32) {
+        let mut reported = self
+            .reported_confirmations
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if reported.get(tx_hash) == Some(&confirmations) {
+            return;
+        }
+        reported.insert(tx_hash.to_string(), confirmations);
+
+        use crate::websocket::WsEvent;
+        self.websocket.do_send(WsEvent::TransactionConfirmed {
+            escrow_id,
+            tx_hash: tx_hash.to_string(),
+            confirmations: confirmations.min(required),
+            required_confirmations: required,
+        });
+    }
+
+    /// Send `event` to the buyer only
+    fn notify_buyer(&self, buyer_id: &str, event: crate::websocket::WsEvent) {
+        match Uuid::parse_str(buyer_id) {
+            Ok(user_id) => self
+                .websocket
+                .do_send(crate::websocket::NotifyUser { user_id, event }),
+            Err(e) => warn!("Invalid buyer_id {}: {}", buyer_id, e),
+        }
+    }
+
+    /// Stop tracking progress of a settled transaction
+    fn forget_confirmations(&self, tx_hash: &str) {
+        self.reported_confirmations
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(tx_hash);
+    }
+
     /// Trigger review invitation to buyer after escrow transaction completion
     ///
     /// This method is automatically called when a transaction reaches the required
@@ -443,13 +621,15 @@ impl BlockchainMonitor {
             use crate::schema::escrows::dsl::*;
             use diesel::prelude::*;
 
-            // Monitor escrows in "created" or "funded" status that have a multisig address
+            // Monitor escrows in "created", "funded" or "underfunded" status that have a multisig address
             // "created" = multisig setup complete, waiting for payment
             // "funded" = payment detected but not yet confirmed (legacy status)
+            // "underfunded" = partial deposit, waiting for a top-up
             escrows
                 .filter(
-                    status.eq("created")
-                    .or(status.eq("funded"))
+                    status.eq(EscrowStatus::Created.as_str())
+                    .or(status.eq(EscrowStatus::Funded.as_str()))
+                    .or(status.eq(EscrowStatus::Underfunded.as_str()))
                 )
                 .filter(multisig_address.is_not_null())
                 .select(id)
@@ -472,9 +652,9 @@ impl BlockchainMonitor {
             escrows
                 .filter(
                     status
-                        .eq("releasing")
-                        .or(status.eq("refunding"))
-                        .or(status.eq("active")),
+                        .eq(EscrowStatus::Releasing.as_str())
+                        .or(status.eq(EscrowStatus::Refunding.as_str()))
+                        .or(status.eq(EscrowStatus::Active.as_str())),
                 )
                 .select(id)
                 .load::<String>(&mut conn)
@@ -494,7 +674,7 @@ mod tests {
     fn test_monitor_config_default() {
         let config = MonitorConfig::default();
         assert_eq!(config.poll_interval_secs, 30);
-        assert_eq!(config.required_confirmations, 10);
+        assert_eq!(config.confirmations.required_for(0), 10);
         assert_eq!(config.max_blocks_per_poll, 100);
     }
 
@@ -502,11 +682,11 @@ mod tests {
     fn test_monitor_config_custom() {
         let config = MonitorConfig {
             poll_interval_secs: 60,
-            required_confirmations: 20,
+            confirmations: ConfirmationPolicy::flat(20),
             max_blocks_per_poll: 200,
         };
         assert_eq!(config.poll_interval_secs, 60);
-        assert_eq!(config.required_confirmations, 20);
+        assert_eq!(config.confirmations.required_for(100_000_000_000_000), 20);
         assert_eq!(config.max_blocks_per_poll, 200);
     }
 }
@@ -0,0 +1,426 @@
+//! Dispute evidence service
+//!
+//! Buyer and vendor of a disputed escrow upload files (photos, tracking
+//! receipts, message excerpts) for the arbiter. Files are encrypted with the
+//! server field key and kept in an [`EvidenceStore`]; the database keeps the
+//! metadata and the SHA-256 of the plaintext.
+//!
+//! The hash list is embedded in the air-gapped
+//! [`DisputeRequest`](crate::services::airgap::DisputeRequest), so the
+//! offline arbiter can check the USB bundle written by [`DisputeEvidenceService::write_bundle`]
+//! against the QR code before reviewing it.
+//!
+//! Evidence is frozen once the submission window (counted from the moment
+//! the escrow entered `disputed`) has passed, or the dispute is resolved.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::crypto::encryption::{decrypt_bytes, encrypt_bytes, is_encrypted_with};
+use crate::db::{db_load_escrow, DbPool};
+use crate::ipfs::client::IpfsClient;
+use crate::models::dispute_evidence::{DisputeEvidence, EvidenceKind, NewDisputeEvidence};
+use crate::models::escrow::{Escrow, EscrowStatus};
+use crate::models::escrow_event::EscrowEventRecord;
+use crate::services::airgap::EvidenceHash;
+use monero_marketplace_common::clock::{SharedClock, SystemClock};
+
+/// Files each party may submit per dispute
+///
+/// Kept low so that the hash list of both parties still fits in the
+/// DisputeRequest QR code.
+pub const MAX_FILES_PER_PARTY: i64 = 5;
+
+/// Largest accepted evidence file (plaintext)
+pub const MAX_FILE_SIZE: usize = 10 * 1024 * 1024; // 10MB
+
+/// Binary formats accepted as evidence (anything else must be UTF-8 text)
+const ALLOWED_MIME_TYPES: [&str; 4] = ["image/jpeg", "image/png", "image/gif", "application/pdf"];
+
+/// Where encrypted evidence files are kept
+#[derive(Clone)]
+pub enum EvidenceStore {
+    /// IPFS node (content is encrypted before upload)
+    Ipfs(IpfsClient),
+    /// Directory of encrypted blobs on the server
+    Local(PathBuf),
+}
+
+impl EvidenceStore {
+    /// Store selected by `EVIDENCE_STORAGE` (`ipfs` or `local`, default `local`)
+    ///
+    /// Local blobs go to `EVIDENCE_STORAGE_DIR` (default `./data/evidence`).
+    pub fn from_env(ipfs_client: IpfsClient) -> Self {
+        match std::env::var("EVIDENCE_STORAGE").as_deref() {
+            Ok("ipfs") => EvidenceStore::Ipfs(ipfs_client),
+            _ => EvidenceStore::Local(
+                std::env::var("EVIDENCE_STORAGE_DIR")
+                    .unwrap_or_else(|_| "./data/evidence".to_string())
+                    .into(),
+            ),
+        }
+    }
+
+    /// Value stored in `dispute_evidence.storage_backend`
+    pub fn backend(&self) -> &'static str {
+        match self {
+            EvidenceStore::Ipfs(_) => "ipfs",
+            EvidenceStore::Local(_) => "local",
+        }
+    }
+
+    /// Store an encrypted blob, returning its reference (CID or blob name)
+    pub async fn put(&self, evidence_id: &str, encrypted: Vec<u8>) -> Result<String> {
+        match self {
+            EvidenceStore::Ipfs(client) => client
+                .add(encrypted, &format!("{}.enc", evidence_id), "application/octet-stream")
+                .await
+                .context("Failed to upload evidence to IPFS"),
+            EvidenceStore::Local(dir) => {
+                let blob_name = format!("{}.enc", evidence_id);
+                tokio::fs::create_dir_all(dir)
+                    .await
+                    .context(format!("Failed to create evidence directory {}", dir.display()))?;
+                tokio::fs::write(dir.join(&blob_name), encrypted)
+                    .await
+                    .context("Failed to write evidence blob")?;
+                Ok(blob_name)
+            }
+        }
+    }
+
+    /// Fetch an encrypted blob by reference
+    pub async fn get(&self, storage_ref: &str) -> Result<Vec<u8>> {
+        match self {
+            EvidenceStore::Ipfs(client) => client
+                .cat(storage_ref)
+                .await
+                .context("Failed to fetch evidence from IPFS"),
+            EvidenceStore::Local(dir) => {
+                if storage_ref.contains(['/', '\\']) || storage_ref.starts_with('.') {
+                    anyhow::bail!("Invalid evidence blob name: {}", storage_ref);
+                }
+                tokio::fs::read(dir.join(storage_ref))
+                    .await
+                    .context(format!("Failed to read evidence blob {}", storage_ref))
+            }
+        }
+    }
+}
+
+/// Accepts, stores and serves dispute evidence
+pub struct DisputeEvidenceService {
+    db: DbPool,
+    store: EvidenceStore,
+    /// Key encrypting evidence at rest (same as the DB field key)
+    encryption_key: Vec<u8>,
+    /// How long after the dispute opened evidence is accepted
+    evidence_window: chrono::Duration,
+    clock: SharedClock,
+}
+
+impl DisputeEvidenceService {
+    /// Create a new DisputeEvidenceService
+    ///
+    /// # Arguments
+    /// * `db` - Database connection pool
+    /// * `store` - Storage for the encrypted files
+    /// * `encryption_key` - 32-byte key encrypting files at rest
+    /// * `evidence_window` - Submission window from the dispute opening
+    pub fn new(
+        db: DbPool,
+        store: EvidenceStore,
+        encryption_key: Vec<u8>,
+        evidence_window: std::time::Duration,
+    ) -> Self {
+        Self {
+            db,
+            store,
+            encryption_key,
+            evidence_window: chrono::Duration::from_std(evidence_window)
+                .unwrap_or_else(|_| chrono::Duration::days(3)),
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Evaluate the submission deadline against `clock` instead of the system time
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Time after which evidence for `escrow_id` is frozen
+    ///
+    /// Counted from the escrow's last move to `disputed`.
+    pub async fn deadline(&self, escrow_id: Uuid) -> Result<NaiveDateTime> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let window = self.evidence_window;
+        tokio::task::spawn_blocking(move || -> Result<NaiveDateTime> {
+            let opened = EscrowEventRecord::last_transition_to(
+                &mut conn,
+                &escrow_id.to_string(),
+                EscrowStatus::Disputed,
+            )?
+            .context(format!("Escrow {} was never disputed", escrow_id))?;
+            Ok(opened.created_at + window)
+        })
+        .await?
+    }
+
+    /// Submit a file as evidence for a disputed escrow
+    ///
+    /// Only the buyer and the vendor may submit, while the escrow is
+    /// `disputed` and the deadline has not passed.
+    pub async fn submit(
+        &self,
+        escrow_id: Uuid,
+        uploader_id: Uuid,
+        kind: EvidenceKind,
+        description: Option<String>,
+        data: Vec<u8>,
+    ) -> Result<DisputeEvidence> {
+        if data.is_empty() {
+            anyhow::bail!("Evidence file is empty");
+        }
+        if data.len() > MAX_FILE_SIZE {
+            anyhow::bail!(
+                "Evidence file too large. Maximum size: {}MB",
+                MAX_FILE_SIZE / 1024 / 1024
+            );
+        }
+
+        let escrow = db_load_escrow(&self.db, escrow_id).await?;
+        let uploader = uploader_id.to_string();
+        let party = if escrow.buyer_id == uploader {
+            "buyer"
+        } else if escrow.vendor_id == uploader {
+            "vendor"
+        } else {
+            anyhow::bail!("Only the buyer or the vendor can submit evidence");
+        };
+        if escrow.status != EscrowStatus::Disputed.as_str() {
+            anyhow::bail!(
+                "Escrow not in disputed state (current: {})",
+                escrow.status
+            );
+        }
+
+        let deadline = self.deadline(escrow_id).await?;
+        if self.clock.now_naive() > deadline {
+            anyhow::bail!("Evidence submission closed at {}", deadline);
+        }
+
+        let (mime_type, extension) = detect_file_type(&data)?;
+        let content_hash = hex::encode(Sha256::digest(&data));
+        let size_bytes = data.len() as i64;
+
+        let evidence_id = Uuid::new_v4().to_string();
+        let encrypted = encrypt_bytes(&data, &self.encryption_key)?;
+
+        // Check the limit before storing anything
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let (escrow_key, uploader_key) = (escrow.id.clone(), uploader.clone());
+        let submitted = tokio::task::spawn_blocking(move || {
+            DisputeEvidence::count_by_uploader(&mut conn, &escrow_key, &uploader_key)
+        })
+        .await??;
+        if submitted >= MAX_FILES_PER_PARTY {
+            anyhow::bail!(
+                "Maximum {} evidence files per party",
+                MAX_FILES_PER_PARTY
+            );
+        }
+
+        let storage_ref = self.store.put(&evidence_id, encrypted).await?;
+
+        let new_evidence = NewDisputeEvidence {
+            // Short names keep the hash list small enough for the QR code
+            file_name: format!("{}-{}.{}", party, submitted + 1, extension),
+            id: evidence_id,
+            escrow_id: escrow.id.clone(),
+            uploader_id: uploader,
+            kind: kind.as_str().to_string(),
+            description,
+            mime_type: mime_type.to_string(),
+            size_bytes,
+            content_hash,
+            storage_backend: self.store.backend().to_string(),
+            storage_ref,
+        };
+
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let evidence = tokio::task::spawn_blocking(move || {
+            DisputeEvidence::create(&mut conn, new_evidence)
+        })
+        .await??;
+
+        info!(
+            escrow_id = %escrow_id,
+            evidence_id = %evidence.id,
+            kind = %evidence.kind,
+            "Dispute evidence submitted"
+        );
+        Ok(evidence)
+    }
+
+    /// Evidence of an escrow, oldest first
+    pub async fn list(&self, escrow_id: Uuid) -> Result<Vec<DisputeEvidence>> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        tokio::task::spawn_blocking(move || {
+            DisputeEvidence::find_by_escrow(&mut conn, &escrow_id.to_string())
+        })
+        .await?
+    }
+
+    /// Hash list for the air-gapped dispute export
+    pub async fn evidence_hashes(&self, escrow: &Escrow) -> Result<Vec<EvidenceHash>> {
+        let escrow_id = escrow.id.parse::<Uuid>().context("Invalid escrow id")?;
+        let evidence = self.list(escrow_id).await?;
+        Ok(evidence
+            .into_iter()
+            .map(|e| EvidenceHash {
+                submitted_by: if e.uploader_id == escrow.buyer_id {
+                    "buyer".to_string()
+                } else {
+                    "vendor".to_string()
+                },
+                file_name: e.file_name,
+                sha256: e.content_hash,
+                size_bytes: e.size_bytes as u64,
+                kind: e.kind,
+            })
+            .collect())
+    }
+
+    /// Decrypt a piece of evidence, checking it against the recorded hash
+    pub async fn read(&self, evidence: &DisputeEvidence) -> Result<Vec<u8>> {
+        if evidence.storage_backend != self.store.backend() {
+            anyhow::bail!(
+                "Evidence {} is stored in {}, server uses {}",
+                evidence.id,
+                evidence.storage_backend,
+                self.store.backend()
+            );
+        }
+
+        let encrypted = self.store.get(&evidence.storage_ref).await?;
+        let data = decrypt_bytes(&encrypted, &self.encryption_key)
+            .context(format!("Failed to decrypt evidence {}", evidence.id))?;
+
+        if hex::encode(Sha256::digest(&data)) != evidence.content_hash {
+            anyhow::bail!("Evidence {} does not match its recorded hash", evidence.id);
+        }
+        Ok(data)
+    }
+
+    /// Re-encrypt blobs written under a previous key (after a key rotation)
+    ///
+    /// Local blobs are overwritten in place; IPFS blobs are uploaded again
+    /// and the evidence points to the new CID. Returns the number of blobs
+    /// re-encrypted and the number that could not be read.
+    pub async fn reencrypt_all(&self) -> Result<(usize, usize)> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let backend = self.store.backend();
+        let evidence = tokio::task::spawn_blocking(move || {
+            DisputeEvidence::find_by_backend(&mut conn, backend)
+        })
+        .await??;
+
+        let (mut reencrypted, mut failed) = (0, 0);
+        for item in evidence {
+            let encrypted = match self.store.get(&item.storage_ref).await {
+                Ok(encrypted) if is_encrypted_with(&encrypted, &self.encryption_key) => continue,
+                Ok(encrypted) => encrypted,
+                Err(e) => {
+                    warn!("Cannot read evidence {}: {:#}", item.id, e);
+                    failed += 1;
+                    continue;
+                }
+            };
+            let data = match decrypt_bytes(&encrypted, &self.encryption_key) {
+                Ok(data) => data,
+                Err(e) => {
+                    warn!("Cannot decrypt evidence {}: {}", item.id, e);
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            let storage_ref = self
+                .store
+                .put(&item.id, encrypt_bytes(&data, &self.encryption_key)?)
+                .await?;
+            if storage_ref != item.storage_ref {
+                let mut conn = self.db.get().context("Failed to get DB connection")?;
+                let evidence_id = item.id.clone();
+                tokio::task::spawn_blocking(move || {
+                    DisputeEvidence::update_storage_ref(&mut conn, &evidence_id, &storage_ref)
+                })
+                .await??;
+            }
+            reencrypted += 1;
+        }
+        Ok((reencrypted, failed))
+    }
+
+    /// Write the decrypted evidence bundle of an escrow to `dir` (USB stick)
+    ///
+    /// Files are named as in the DisputeRequest hash list. Returns the
+    /// number of files written.
+    pub async fn write_bundle(&self, escrow_id: Uuid, dir: &Path) -> Result<usize> {
+        let evidence = self.list(escrow_id).await?;
+        tokio::fs::create_dir_all(dir)
+            .await
+            .context(format!("Failed to create bundle directory {}", dir.display()))?;
+
+        for item in &evidence {
+            let data = self.read(item).await?;
+            tokio::fs::write(dir.join(&item.file_name), data)
+                .await
+                .context(format!("Failed to write {}", item.file_name))?;
+        }
+        Ok(evidence.len())
+    }
+}
+
+/// MIME type and file extension of an evidence file
+///
+/// Images and PDFs are recognised by their magic bytes; anything else must
+/// be valid UTF-8 and is stored as plain text.
+fn detect_file_type(data: &[u8]) -> Result<(&'static str, &'static str)> {
+    if let Some(kind) = infer::get(data) {
+        if ALLOWED_MIME_TYPES.contains(&kind.mime_type()) {
+            return Ok((kind.mime_type(), kind.extension()));
+        }
+        anyhow::bail!("Unsupported evidence format: {}", kind.mime_type());
+    }
+
+    if std::str::from_utf8(data).is_ok() {
+        return Ok(("text/plain", "txt"));
+    }
+    anyhow::bail!("Unsupported evidence format: only JPEG, PNG, GIF, PDF and text are accepted")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_file_type() {
+        let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 0];
+        assert_eq!(detect_file_type(&png).ok(), Some(("image/png", "png")));
+        assert_eq!(
+            detect_file_type(b"Tracking: RR123456789CH").ok(),
+            Some(("text/plain", "txt"))
+        );
+
+        // Executables are recognised and refused
+        assert!(detect_file_type(b"MZ\x90\x00\x03\x00\x00\x00").is_err());
+        // Unknown binary data is refused
+        assert!(detect_file_type(&[0xff, 0xfe, 0x00, 0x81]).is_err());
+    }
+}
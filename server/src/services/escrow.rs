@@ -6,18 +6,27 @@ use std::sync::Arc;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use crate::config::{ArbiterPoolConfig, ConfirmationPolicy};
 use crate::crypto::encryption::encrypt_field;
 use crate::db::{
-    db_count_multisig_infos, db_insert_escrow, db_load_escrow, db_store_multisig_info,
-    db_update_escrow_address, db_update_escrow_status, DbPool,
+    db_count_multisig_infos, db_create_transaction, db_insert_escrow, db_load_escrow,
+    db_record_ruling, db_store_multisig_info, db_transition_escrow, db_transition_escrow_audited,
+    db_update_escrow_address, DbPool,
 };
-use crate::models::escrow::{Escrow, NewEscrow};
+use crate::models::audit_log::AuditEventType;
+use crate::models::escrow::{Escrow, EscrowStatus, NewEscrow};
+use crate::models::escrow_event::EscrowActor;
+use crate::models::transaction::NewTransaction;
 use crate::models::user::User;
+use crate::services::arbiter_assignment::select_arbiter;
 use crate::wallet_manager::WalletManager;
 use crate::websocket::{WebSocketServer, WsEvent};
 use monero_marketplace_common::types::TransferDestination;
 use tokio::sync::Mutex;
 
+/// Error prefix when the requester may not pay out the escrow in its status
+pub const PAYOUT_NOT_ALLOWED_ERROR: &str = "Payout not allowed";
+
 /// Manages escrow operations and state transitions
 pub struct EscrowOrchestrator {
     /// Monero wallet manager for blockchain operations
@@ -30,6 +39,10 @@ pub struct EscrowOrchestrator {
     websocket: Addr<WebSocketServer>,
     /// Encryption key for sensitive data
     encryption_key: Vec<u8>,
+    /// Confirmations required per escrow amount (reported with payout transactions)
+    confirmation_policy: ConfirmationPolicy,
+    /// Capacity limit applied when picking an arbiter
+    arbiter_pool: ArbiterPoolConfig,
 }
 
 impl EscrowOrchestrator {
@@ -47,9 +60,26 @@ impl EscrowOrchestrator {
             db,
             websocket,
             encryption_key,
+            confirmation_policy: ConfirmationPolicy::default(),
+            arbiter_pool: ArbiterPoolConfig::default(),
         }
     }
 
+    /// Use `policy` instead of the default confirmation tiers
+    ///
+    /// Must match the policy given to `BlockchainMonitor` so the "x of y"
+    /// shown when a payout is broadcast agrees with the monitor's updates.
+    pub fn with_confirmation_policy(mut self, policy: ConfirmationPolicy) -> Self {
+        self.confirmation_policy = policy;
+        self
+    }
+
+    /// Use `config` instead of the default arbiter capacity limit
+    pub fn with_arbiter_pool(mut self, config: ArbiterPoolConfig) -> Self {
+        self.arbiter_pool = config;
+        self
+    }
+
     // ========================================================================
     // NON-CUSTODIAL: Client Wallet Registration
     // ========================================================================
@@ -210,8 +240,8 @@ impl EscrowOrchestrator {
             order_id, buyer_id, vendor_id, amount_atomic
         );
 
-        // 1. Assign arbiter using round-robin from available arbiters
-        let arbiter_id = self.assign_arbiter().await?;
+        // 1. Assign arbiter from the pool by workload
+        let arbiter_id = self.assign_arbiter(buyer_id, vendor_id).await?;
         info!("✅ Assigned arbiter {} to escrow", arbiter_id);
 
         // 2. Create escrow in DB
@@ -712,59 +742,68 @@ impl EscrowOrchestrator {
             .context("Failed to update escrow with multisig address")?;
 
         // Update status to 'funded' (ready to receive funds)
-        db_update_escrow_status(&self.db, escrow_id, "funded")
-            .await
-            .context("Failed to update escrow status")?;
+        db_transition_escrow(
+            &self.db,
+            escrow_id,
+            EscrowStatus::Funded,
+            EscrowActor::System("escrow_orchestrator"),
+            Some("Multisig setup complete"),
+        )
+        .await
+        .context("Failed to update escrow status")?;
 
         // Notify all parties of status change
         self.websocket.do_send(WsEvent::EscrowStatusChanged {
             escrow_id,
-            new_status: "funded".to_string(),
+            new_status: EscrowStatus::Funded,
         });
 
         info!("Multisig setup complete for escrow {}", escrow_id);
         Ok(())
     }
 
-    /// Assign an arbiter using round-robin selection
-    async fn assign_arbiter(&self) -> Result<String> {
+    /// Assign the least loaded arbiter who has not traded with either party
+    async fn assign_arbiter(&self, buyer_id: Uuid, vendor_id: Uuid) -> Result<String> {
         // Get connection from pool
         let mut conn = self.db.get().context("Failed to get DB connection")?;
-
-        // Find all users with 'arbiter' role
-        let arbiters =
-            tokio::task::spawn_blocking(move || User::find_by_role(&mut conn, "arbiter"))
-                .await
-                .context("Task join error")??;
-
-        if arbiters.is_empty() {
-            return Err(anyhow::anyhow!("No arbiters available in the system"));
-        }
-
-        // Simple round-robin: pick first arbiter
-        // In production, track arbiter workload and balance assignments
-        let selected_arbiter = &arbiters[0];
+        let arbiter_pool = self.arbiter_pool.clone();
+
+        let selected_arbiter = tokio::task::spawn_blocking(move || {
+            select_arbiter(
+                &mut conn,
+                &arbiter_pool,
+                &buyer_id.to_string(),
+                &vendor_id.to_string(),
+                &[],
+            )
+        })
+        .await
+        .context("Task join error")??;
 
         info!(
-            "Selected arbiter: {} ({})",
-            selected_arbiter.username, selected_arbiter.id
+            "Selected arbiter: {} ({}, {} open disputes)",
+            selected_arbiter.username, selected_arbiter.arbiter_id, selected_arbiter.open_disputes
         );
-        Ok(selected_arbiter.id.clone())
+        Ok(selected_arbiter.arbiter_id)
     }
 
     /// Release funds to vendor (buyer approves)
     ///
     /// # Flow
-    /// 1. Validate buyer is requester
+    /// 1. Validate buyer is requester and the escrow is active or overfunded
+    ///    (or the assigned arbiter carries out a ruling for the vendor, to its
+    ///    address)
     /// 2. Create multisig transaction to send funds to vendor_address
+    ///    (plus any overpayment back to the buyer's registered wallet)
     /// 3. Sign with buyer's wallet (first signature)
     /// 4. Get arbiter to sign (second signature - 2-of-3 threshold met)
     /// 5. Submit fully signed transaction to network
     /// 6. Update escrow status to "released"
+    ///    and append the payout to the audit log, in one transaction
     ///
     /// # Arguments
     /// * `escrow_id` - The escrow to release
-    /// * `requester_id` - Must be buyer
+    /// * `requester_id` - Must be buyer (arbiter for a `resolved_vendor` escrow)
     /// * `vendor_address` - Monero address to send funds to
     ///
     /// # Important
@@ -780,32 +819,26 @@ impl EscrowOrchestrator {
     ) -> Result<String> {
         let escrow = db_load_escrow(&self.db, escrow_id).await?;
 
-        // Only buyer can release funds
-        if requester_id.to_string() != escrow.buyer_id {
-            return Err(anyhow::anyhow!("Only buyer can release funds"));
-        }
-
-        // Validate escrow is in funded/active state (active = funds received and confirmed)
-        if escrow.status != "funded" && escrow.status != "active" {
-            return Err(anyhow::anyhow!(
-                "Escrow must be in 'funded' or 'active' state to release funds (current: {})",
+        // Buyer releases once the deposit is in; a ruling for the vendor is
+        // carried out by the assigned arbiter
+        match escrow.get_status()? {
+            EscrowStatus::Active | EscrowStatus::Overfunded
+                if requester_id.to_string() == escrow.buyer_id => {}
+            EscrowStatus::Active | EscrowStatus::Overfunded => {
+                anyhow::bail!("{}: only the buyer can release funds", PAYOUT_NOT_ALLOWED_ERROR)
+            }
+            EscrowStatus::ResolvedVendor => {
+                check_ruling_payout(&escrow, requester_id, &vendor_address)?
+            }
+            _ => anyhow::bail!(
+                "{}: escrow must be active to release funds (current: {})",
+                PAYOUT_NOT_ALLOWED_ERROR,
                 escrow.status
-            ));
+            ),
         }
 
         // Validate vendor address format (mainnet: 4/8, testnet: 9/A/B, 95-106 chars)
-        let first_char = vendor_address.chars().next().unwrap_or('0');
-        let len = vendor_address.len();
-        if len < 95 || len > 106 {
-            return Err(anyhow::anyhow!(
-                "Invalid Monero address length: {} (must be 95-106 characters)", len
-            ));
-        }
-        if first_char != '4' && first_char != '8' && first_char != '9' && first_char != 'A' && first_char != 'B' {
-            return Err(anyhow::anyhow!(
-                "Invalid Monero address format: must start with 4/8 (mainnet) or 9/A/B (testnet)"
-            ));
-        }
+        validate_payout_address(&vendor_address)?;
 
         info!(
             "Releasing funds from escrow {} to vendor address {}",
@@ -822,10 +855,18 @@ impl EscrowOrchestrator {
             )
         })?;
 
-        let destinations = vec![TransferDestination {
+        let mut destinations = vec![TransferDestination {
             address: vendor_address.clone(),
             amount: amount_u64,
         }];
+        // Overpayment goes back to the buyer in the same transfer
+        if let Some(surplus) = self.surplus_destination(&escrow).await? {
+            info!(
+                "Returning surplus of {} atomic units to buyer for escrow {}",
+                surplus.amount, escrow_id
+            );
+            destinations.push(surplus);
+        }
 
         // Use WalletManager to release funds through multisig flow
         let mut wallet_manager = self.wallet_manager.lock().await;
@@ -839,16 +880,34 @@ impl EscrowOrchestrator {
         crate::db::db_update_escrow_transaction_hash(&self.db, escrow_id, &tx_hash).await?;
 
         // Update escrow status to 'releasing' (will become 'completed' after confirmations)
-        db_update_escrow_status(&self.db, escrow_id, "releasing").await?;
+        db_transition_escrow_audited(
+            &self.db,
+            escrow_id,
+            EscrowStatus::Releasing,
+            EscrowActor::user(requester_id),
+            Some("Release to vendor"),
+            AuditEventType::Release,
+            serde_json::json!({
+                "tx_hash": tx_hash,
+                "amount": escrow.amount,
+                "surplus": escrow.surplus(),
+                "vendor_address": vendor_address,
+            }),
+        )
+        .await?;
 
         // Notify parties via WebSocket
         self.websocket.do_send(WsEvent::TransactionConfirmed {
+            escrow_id,
             tx_hash: tx_hash.clone(),
             confirmations: 0,
+            required_confirmations: self
+                .confirmation_policy
+                .required_for(escrow.amount as u64),
         });
         self.websocket.do_send(WsEvent::EscrowStatusChanged {
             escrow_id,
-            new_status: "releasing".to_string(),
+            new_status: EscrowStatus::Releasing,
         });
 
         info!(
@@ -861,16 +920,19 @@ impl EscrowOrchestrator {
     /// Refund funds to buyer (vendor or arbiter approves)
     ///
     /// # Flow
-    /// 1. Validate requester is vendor or arbiter
+    /// 1. Validate vendor is requester and the escrow is active or overfunded
+    ///    (or the assigned arbiter carries out a ruling for the buyer, to its
+    ///    address)
     /// 2. Create multisig transaction to send funds back to buyer_address
     /// 3. Sign with vendor's wallet (first signature)
     /// 4. Get arbiter to sign (second signature - 2-of-3 threshold met)
     /// 5. Submit fully signed transaction to network
     /// 6. Update escrow status to "refunded"
+    ///    and append the payout to the audit log, in one transaction
     ///
     /// # Arguments
     /// * `escrow_id` - The escrow to refund
-    /// * `requester_id` - Must be vendor or arbiter
+    /// * `requester_id` - Must be vendor (arbiter for a `resolved_buyer` escrow)
     /// * `buyer_address` - Monero address to refund to
     pub async fn refund_funds(
         &self,
@@ -880,36 +942,26 @@ impl EscrowOrchestrator {
     ) -> Result<String> {
         let escrow = db_load_escrow(&self.db, escrow_id).await?;
 
-        // Vendor or arbiter can initiate refund
-        if requester_id.to_string() != escrow.vendor_id
-            && requester_id.to_string() != escrow.arbiter_id
-        {
-            return Err(anyhow::anyhow!(
-                "Only vendor or arbiter can initiate refund"
-            ));
-        }
-
-        // Validate escrow is in funded/active state (active = funds received and confirmed)
-        if escrow.status != "funded" && escrow.status != "active" {
-            return Err(anyhow::anyhow!(
-                "Escrow must be in 'funded' or 'active' state to refund (current: {})",
+        // Vendor refunds once the deposit is in; a ruling for the buyer is
+        // carried out by the assigned arbiter
+        match escrow.get_status()? {
+            EscrowStatus::Active | EscrowStatus::Overfunded
+                if requester_id.to_string() == escrow.vendor_id => {}
+            EscrowStatus::Active | EscrowStatus::Overfunded => {
+                anyhow::bail!("{}: only the vendor can refund", PAYOUT_NOT_ALLOWED_ERROR)
+            }
+            EscrowStatus::ResolvedBuyer => {
+                check_ruling_payout(&escrow, requester_id, &buyer_address)?
+            }
+            _ => anyhow::bail!(
+                "{}: escrow must be active to refund (current: {})",
+                PAYOUT_NOT_ALLOWED_ERROR,
                 escrow.status
-            ));
+            ),
         }
 
         // Validate buyer address format (mainnet: 4/8, testnet: 9/A/B, 95-106 chars)
-        let first_char = buyer_address.chars().next().unwrap_or('0');
-        let len = buyer_address.len();
-        if len < 95 || len > 106 {
-            return Err(anyhow::anyhow!(
-                "Invalid Monero address length: {} (must be 95-106 characters)", len
-            ));
-        }
-        if first_char != '4' && first_char != '8' && first_char != '9' && first_char != 'A' && first_char != 'B' {
-            return Err(anyhow::anyhow!(
-                "Invalid Monero address format: must start with 4/8 (mainnet) or 9/A/B (testnet)"
-            ));
-        }
+        validate_payout_address(&buyer_address)?;
 
         info!(
             "Refunding funds from escrow {} to buyer address {}",
@@ -926,9 +978,10 @@ impl EscrowOrchestrator {
             )
         })?;
 
+        // The buyer gets back everything they sent, including any overpayment
         let destinations = vec![TransferDestination {
             address: buyer_address.clone(),
-            amount: amount_u64,
+            amount: amount_u64 + escrow.surplus() as u64,
         }];
 
         // Use WalletManager to refund funds through multisig flow
@@ -944,16 +997,33 @@ impl EscrowOrchestrator {
         crate::db::db_update_escrow_transaction_hash(&self.db, escrow_id, &tx_hash).await?;
 
         // Update escrow status to 'refunding' (will become 'refunded' after confirmations)
-        db_update_escrow_status(&self.db, escrow_id, "refunding").await?;
+        db_transition_escrow_audited(
+            &self.db,
+            escrow_id,
+            EscrowStatus::Refunding,
+            EscrowActor::user(requester_id),
+            Some("Refund to buyer"),
+            AuditEventType::Refund,
+            serde_json::json!({
+                "tx_hash": tx_hash,
+                "amount": escrow.amount + escrow.surplus(),
+                "buyer_address": buyer_address,
+            }),
+        )
+        .await?;
 
         // Notify parties via WebSocket
         self.websocket.do_send(WsEvent::TransactionConfirmed {
+            escrow_id,
             tx_hash: tx_hash.clone(),
             confirmations: 0,
+            required_confirmations: self
+                .confirmation_policy
+                .required_for(escrow.amount as u64),
         });
         self.websocket.do_send(WsEvent::EscrowStatusChanged {
             escrow_id,
-            new_status: "refunding".to_string(),
+            new_status: EscrowStatus::Refunding,
         });
 
         info!(
@@ -979,12 +1049,19 @@ impl EscrowOrchestrator {
             return Err(anyhow::anyhow!("Only buyer or vendor can initiate dispute"));
         }
 
-        db_update_escrow_status(&self.db, escrow_id, "disputed").await?;
+        db_transition_escrow(
+            &self.db,
+            escrow_id,
+            EscrowStatus::Disputed,
+            EscrowActor::user(requester_id),
+            Some(&reason),
+        )
+        .await?;
 
         // Notify arbiter
         self.websocket.do_send(WsEvent::EscrowStatusChanged {
             escrow_id,
-            new_status: "disputed".to_string(),
+            new_status: EscrowStatus::Disputed,
         });
 
         info!(
@@ -1006,7 +1083,7 @@ impl EscrowOrchestrator {
         let escrow = db_load_escrow(&self.db, escrow_id).await?;
 
         // 1. Verify escrow is in disputed state
-        if escrow.status != "disputed" {
+        if escrow.get_status()? != EscrowStatus::Disputed {
             return Err(anyhow::anyhow!(
                 "Escrow not in disputed state (current: {})",
                 escrow.status
@@ -1025,15 +1102,27 @@ impl EscrowOrchestrator {
             ));
         }
 
-        // 4. Update escrow status based on resolution
+        // 4. Update escrow status based on resolution, with the payout address
+        validate_payout_address(&recipient_address)?;
         let new_status = match resolution {
-            "buyer" => "resolved_buyer",
-            "vendor" => "resolved_vendor",
+            "buyer" => EscrowStatus::ResolvedBuyer,
+            "vendor" => EscrowStatus::ResolvedVendor,
             _ => anyhow::bail!("Invalid resolution after validation: {}", resolution),
         };
-        db_update_escrow_status(&self.db, escrow_id, new_status)
-            .await
-            .context("Failed to update escrow status after resolution")?;
+        db_record_ruling(
+            &self.db,
+            escrow_id,
+            new_status,
+            EscrowActor::user(arbiter_id),
+            Some(&format!("Dispute resolved in favour of {}", resolution)),
+            &recipient_address,
+            serde_json::json!({
+                "resolution": resolution,
+                "recipient_address": recipient_address,
+            }),
+        )
+        .await
+        .context("Failed to update escrow status after resolution")?;
 
         // 5. Notify all parties via WebSocket
         self.websocket.do_send(WsEvent::DisputeResolved {
@@ -1069,6 +1158,202 @@ impl EscrowOrchestrator {
         Ok(tx_hash)
     }
 
+    /// Arbiter resolves dispute with a split settlement
+    ///
+    /// # Flow
+    /// 1. Validate escrow is disputed and requester is the assigned arbiter
+    /// 2. Split the escrow amount (`buyer_share_percent` to buyer, rest to vendor);
+    ///    any overpayment is added to the buyer's share
+    /// 3. Build ONE multisig transfer with two destinations (buyer + vendor)
+    /// 4. Record the transaction with per-party amounts
+    /// 5. Update escrow status to "releasing" (completed after confirmations)
+    ///
+    /// The recorded amounts are the requested shares; the network fee is
+    /// taken pro rata from both destinations when the transfer is built.
+    ///
+    /// # Arguments
+    /// * `escrow_id` - The disputed escrow
+    /// * `arbiter_id` - Must be the assigned arbiter
+    /// * `buyer_share_percent` - Buyer's share (1-99), the vendor gets the rest
+    /// * `buyer_address` - Monero address for the buyer's share
+    /// * `vendor_address` - Monero address for the vendor's share
+    pub async fn resolve_dispute_split(
+        &self,
+        escrow_id: Uuid,
+        arbiter_id: Uuid,
+        buyer_share_percent: u8,
+        buyer_address: String,
+        vendor_address: String,
+    ) -> Result<String> {
+        let escrow = db_load_escrow(&self.db, escrow_id).await?;
+
+        // 1. Verify escrow is in disputed state
+        if escrow.get_status()? != EscrowStatus::Disputed {
+            return Err(anyhow::anyhow!(
+                "Escrow not in disputed state (current: {})",
+                escrow.status
+            ));
+        }
+
+        // 2. Verify requester is the assigned arbiter
+        if arbiter_id.to_string() != escrow.arbiter_id {
+            return Err(anyhow::anyhow!("Only assigned arbiter can resolve dispute"));
+        }
+
+        // 3. Validate addresses and compute shares
+        validate_payout_address(&buyer_address)?;
+        validate_payout_address(&vendor_address)?;
+
+        let amount_u64 = u64::try_from(escrow.amount).map_err(|_| {
+            anyhow::anyhow!(
+                "Invalid escrow amount: {}. Amount must be positive.",
+                escrow.amount
+            )
+        })?;
+        let (buyer_share, vendor_amount) = split_escrow_amount(amount_u64, buyer_share_percent)?;
+        // Overpayment is not part of the dispute: it goes back to the buyer
+        let buyer_amount = buyer_share + escrow.surplus() as u64;
+
+        db_transition_escrow_audited(
+            &self.db,
+            escrow_id,
+            EscrowStatus::ResolvedSplit,
+            EscrowActor::user(arbiter_id),
+            Some(&format!(
+                "Dispute split: {}% to buyer",
+                buyer_share_percent
+            )),
+            AuditEventType::DisputeResolution,
+            serde_json::json!({
+                "resolution": "split",
+                "buyer_share_percent": buyer_share_percent,
+                "buyer_amount": buyer_amount,
+                "vendor_amount": vendor_amount,
+                "buyer_address": buyer_address,
+                "vendor_address": vendor_address,
+            }),
+        )
+        .await
+        .context("Failed to update escrow status after resolution")?;
+
+        // 4. Notify all parties via WebSocket
+        self.websocket.do_send(WsEvent::DisputeResolved {
+            escrow_id,
+            resolution: "split".to_string(),
+            decided_by: arbiter_id,
+        });
+
+        info!(
+            "Dispute resolved for escrow {} with split {}% buyer / {}% vendor by arbiter {}",
+            escrow_id,
+            buyer_share_percent,
+            100 - buyer_share_percent,
+            arbiter_id
+        );
+
+        // 5. Single transfer paying both parties
+        let destinations = vec![
+            TransferDestination {
+                address: buyer_address,
+                amount: buyer_amount,
+            },
+            TransferDestination {
+                address: vendor_address,
+                amount: vendor_amount,
+            },
+        ];
+
+        let mut wallet_manager = self.wallet_manager.lock().await;
+        let tx_hash = wallet_manager
+            .release_funds(escrow_id, destinations)
+            .await?;
+        drop(wallet_manager);
+
+        info!("Split transaction submitted to network: tx_hash={}", tx_hash);
+
+        // Update escrow with transaction hash (for blockchain monitoring)
+        crate::db::db_update_escrow_transaction_hash(&self.db, escrow_id, &tx_hash).await?;
+
+        db_create_transaction(
+            &self.db,
+            NewTransaction {
+                id: Uuid::new_v4().to_string(),
+                escrow_id: escrow_id.to_string(),
+                tx_hash: Some(tx_hash.clone()),
+                amount_xmr: (buyer_amount + vendor_amount) as i64,
+                confirmations: 0,
+                buyer_amount_xmr: Some(buyer_amount as i64),
+                vendor_amount_xmr: Some(vendor_amount as i64),
+            },
+        )
+        .await
+        .context("Failed to record split transaction")?;
+
+        // Update escrow status to 'releasing' (will become 'completed' after confirmations)
+        db_transition_escrow(
+            &self.db,
+            escrow_id,
+            EscrowStatus::Releasing,
+            EscrowActor::user(arbiter_id),
+            Some("Split payout"),
+        )
+        .await?;
+
+        self.websocket.do_send(WsEvent::TransactionConfirmed {
+            escrow_id,
+            tx_hash: tx_hash.clone(),
+            confirmations: 0,
+            required_confirmations: self
+                .confirmation_policy
+                .required_for(escrow.amount as u64),
+        });
+        self.websocket.do_send(WsEvent::EscrowStatusChanged {
+            escrow_id,
+            new_status: EscrowStatus::Releasing,
+        });
+
+        info!(
+            "Dispute resolution complete for escrow {}: split via tx {}",
+            escrow_id, tx_hash
+        );
+
+        Ok(tx_hash)
+    }
+
+    /// Transfer returning an overpayment to the buyer's registered wallet
+    ///
+    /// None if the escrow was not overfunded. Fails when there is a surplus
+    /// but the buyer has no `wallet_address` to send it to.
+    async fn surplus_destination(&self, escrow: &Escrow) -> Result<Option<TransferDestination>> {
+        let surplus = escrow.surplus();
+        if surplus == 0 {
+            return Ok(None);
+        }
+
+        let buyer_id = escrow.buyer_id.clone();
+        let db_clone = self.db.clone();
+        let buyer = tokio::task::spawn_blocking(move || {
+            let mut conn = db_clone.get().context("Failed to get DB connection")?;
+            User::find_by_id(&mut conn, buyer_id)
+        })
+        .await
+        .context("Database task panicked")??;
+
+        let address = buyer.wallet_address.ok_or_else(|| {
+            anyhow::anyhow!(
+                "Escrow {} is overfunded by {} atomic units but the buyer has no registered wallet address for the surplus",
+                escrow.id,
+                surplus
+            )
+        })?;
+        validate_payout_address(&address)?;
+
+        Ok(Some(TransferDestination {
+            address,
+            amount: surplus as u64,
+        }))
+    }
+
     /// Sync multisig wallets and get current balance (LAZY SYNC PATTERN)
     ///
     /// This method implements the lazy sync pattern to check escrow balance
@@ -1147,3 +1432,96 @@ impl EscrowOrchestrator {
         Ok(())
     }
 }
+
+/// Validate a payout address (mainnet: 4/8, testnet: 9/A/B, 95-106 chars)
+pub(crate) fn validate_payout_address(address: &str) -> Result<()> {
+    let first_char = address.chars().next().unwrap_or('0');
+    let len = address.len();
+    if !(95..=106).contains(&len) {
+        return Err(anyhow::anyhow!(
+            "Invalid Monero address length: {} (must be 95-106 characters)", len
+        ));
+    }
+    if !matches!(first_char, '4' | '8' | '9' | 'A' | 'B') {
+        return Err(anyhow::anyhow!(
+            "Invalid Monero address format: must start with 4/8 (mainnet) or 9/A/B (testnet)"
+        ));
+    }
+    Ok(())
+}
+
+/// Check that a resolved escrow is paid out as ruled
+///
+/// Only the assigned arbiter carries out a ruling, and only to the address
+/// recorded with it.
+fn check_ruling_payout(escrow: &Escrow, requester_id: Uuid, address: &str) -> Result<()> {
+    if requester_id.to_string() != escrow.arbiter_id {
+        anyhow::bail!(
+            "{}: only the assigned arbiter can carry out a ruling",
+            PAYOUT_NOT_ALLOWED_ERROR
+        );
+    }
+    if escrow.ruling_address.as_deref() != Some(address) {
+        anyhow::bail!(
+            "{}: payout address differs from the arbiter's ruling",
+            PAYOUT_NOT_ALLOWED_ERROR
+        );
+    }
+    Ok(())
+}
+
+/// Split an escrow amount between buyer and vendor
+///
+/// Returns `(buyer_amount, vendor_amount)`; the vendor gets the rounding
+/// remainder so the two always add up to `amount`.
+///
+/// # Errors
+/// `buyer_share_percent` outside 1-99 (use a plain buyer/vendor resolution
+/// for 0 or 100), or a share that rounds down to zero.
+pub fn split_escrow_amount(amount: u64, buyer_share_percent: u8) -> Result<(u64, u64)> {
+    if !(1..=99).contains(&buyer_share_percent) {
+        return Err(anyhow::anyhow!(
+            "Invalid split: buyer share must be between 1 and 99 percent (got {})",
+            buyer_share_percent
+        ));
+    }
+
+    let buyer_amount = (amount as u128 * buyer_share_percent as u128 / 100) as u64;
+    let vendor_amount = amount - buyer_amount;
+    if buyer_amount == 0 || vendor_amount == 0 {
+        return Err(anyhow::anyhow!(
+            "Invalid split: escrow amount {} too small to split",
+            amount
+        ));
+    }
+
+    Ok((buyer_amount, vendor_amount))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_escrow_amount() -> Result<()> {
+        assert_eq!(
+            split_escrow_amount(1_000_000_000_000, 60)?,
+            (600_000_000_000, 400_000_000_000)
+        );
+        // Rounding remainder goes to the vendor
+        assert_eq!(split_escrow_amount(1_001, 50)?, (500, 501));
+
+        assert!(split_escrow_amount(1_000, 0).is_err());
+        assert!(split_escrow_amount(1_000, 100).is_err());
+        assert!(split_escrow_amount(1, 50).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_payout_address() {
+        assert!(validate_payout_address(&format!("9{}", "a".repeat(94))).is_ok());
+        assert!(validate_payout_address(&format!("4{}", "a".repeat(105))).is_ok());
+        assert!(validate_payout_address("9tooshort").is_err());
+        assert!(validate_payout_address(&format!("1{}", "a".repeat(94))).is_err());
+    }
+}
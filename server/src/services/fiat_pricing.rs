@@ -0,0 +1,154 @@
+//! Fiat-Pegged Listing Prices
+//!
+//! A listing priced in fiat is converted to XMR at the current rate of the
+//! [`PriceOracle`] when it is bought. The XMR amount and the rate are locked
+//! into the order (and the amount into its escrow) for the quote window; an
+//! order whose escrow has received nothing when the window ends is requoted
+//! at the rate of that moment, so a late deposit pays the current price.
+//!
+//! Stale rates are never used: without a fresh rate, fiat-priced listings
+//! cannot be bought and expired quotes stay as they are until one arrives.
+
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use monero_marketplace_common::clock::{SharedClock, SystemClock};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::db::DbPool;
+use crate::models::listing::Listing;
+use crate::models::order::{CheckoutQuote, Order};
+use crate::models::order_item::OrderItem;
+use crate::services::price_conversion::PriceOracle;
+
+/// Converts fiat-priced listings and keeps order quotes current
+pub struct FiatPricing {
+    db: DbPool,
+    oracle: Arc<PriceOracle>,
+    clock: SharedClock,
+}
+
+impl FiatPricing {
+    pub fn new(db: DbPool, oracle: Arc<PriceOracle>) -> Self {
+        Self {
+            db,
+            oracle,
+            clock: SystemClock::shared(),
+        }
+    }
+
+    /// Issue and expire quotes against `clock` instead of the system time
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Current time of the pricing clock, for [`Order::is_quote_expired`]
+    pub fn now(&self) -> NaiveDateTime {
+        self.clock.now_naive()
+    }
+
+    /// Reprice listings and requote expired orders every `refresh_secs`
+    pub async fn start(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(self.oracle.config().refresh_interval());
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.reprice_listings().await {
+                error!("Fiat listing repricing failed: {:#}", e);
+            }
+            match self.requote_expired().await {
+                Ok(requoted) if !requoted.is_empty() => {
+                    info!("Requoted {} order(s) with expired quotes", requoted.len())
+                }
+                Ok(_) => {}
+                Err(e) => error!("Requoting expired orders failed: {:#}", e),
+            }
+        }
+    }
+
+    /// Fresh rates of every configured currency, with the quote window
+    pub async fn checkout_quote(&self) -> Result<CheckoutQuote> {
+        let rates = self
+            .oracle
+            .rates()
+            .await?
+            .into_iter()
+            .filter(|rate| !rate.stale)
+            .map(|rate| (rate.currency, rate.rate))
+            .collect();
+        let window = chrono::Duration::from_std(self.oracle.config().quote_window())
+            .context("Invalid quote window")?;
+        Ok(CheckoutQuote {
+            rates,
+            window,
+            issued_at: self.now(),
+        })
+    }
+
+    /// Convert the fiat-priced items of a pending order at the current rates
+    pub async fn requote(&self, order_id: &str) -> Result<(Order, Vec<OrderItem>)> {
+        let quote = self.checkout_quote().await?;
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let order_id = order_id.to_string();
+        tokio::task::spawn_blocking(move || Order::requote(&mut conn, &order_id, &quote))
+            .await
+            .context("Task join error")?
+    }
+
+    /// Requote every pending order whose quote expired
+    ///
+    /// Orders that cannot be requoted (deposit received, no fresh rate) are
+    /// skipped and logged.
+    pub async fn requote_expired(&self) -> Result<Vec<Order>> {
+        let quote = self.checkout_quote().await?;
+        let now = self.now();
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        tokio::task::spawn_blocking(move || {
+            let expired = Order::find_expired_quotes(&mut conn, now)?;
+            let mut requoted = Vec::with_capacity(expired.len());
+            for order in expired {
+                match Order::requote(&mut conn, &order.id, &quote) {
+                    Ok((order, _)) => {
+                        info!(
+                            "Order {} requoted: {} piconeros until {:?}",
+                            order.id, order.total_xmr, order.quote_expires_at
+                        );
+                        requoted.push(order);
+                    }
+                    Err(e) => warn!("Order {} not requoted: {:#}", order.id, e),
+                }
+            }
+            Ok(requoted)
+        })
+        .await
+        .context("Task join error")?
+    }
+
+    /// Set `price_xmr` of fiat-priced listings from the current rates
+    ///
+    /// Listing pages, carts and searches keep working in XMR; the price that
+    /// is charged is still converted again at checkout.
+    pub async fn reprice_listings(&self) -> Result<usize> {
+        let quote = self.checkout_quote().await?;
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        tokio::task::spawn_blocking(move || {
+            let mut repriced = 0;
+            for listing in Listing::find_fiat_priced(&mut conn)? {
+                let Some(fiat_price) = listing.fiat_price() else {
+                    continue;
+                };
+                let Some(rate) = quote.rates.get(&fiat_price.currency) else {
+                    continue;
+                };
+                let price_xmr = fiat_price.to_atomic(*rate)?;
+                if price_xmr != listing.price_xmr {
+                    Listing::reprice(&mut conn, &listing.id, price_xmr)?;
+                    repriced += 1;
+                }
+            }
+            Ok(repriced)
+        })
+        .await
+        .context("Task join error")?
+    }
+}
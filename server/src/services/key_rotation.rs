@@ -0,0 +1,309 @@
+//! Re-encryption of field-encrypted data after a key rotation
+//!
+//! The field key is derived from the SQLCipher key, so `rotate_key` changing
+//! the database key also retires the field key. The server is then started
+//! with the old key in `DB_PREVIOUS_ENCRYPTION_KEYS`: reads keep working
+//! through [`crate::crypto::encryption::register_previous_key`] while
+//! [`KeyRotationService`] rewrites every column in [`ENCRYPTED_COLUMNS`] and
+//! the dispute evidence blobs under the new key, in small batches, without
+//! stopping the server.
+//!
+//! Rows are updated only if the column still holds the value that was read,
+//! so a concurrent write (already under the new key) is never overwritten.
+
+use anyhow::{Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use diesel::prelude::*;
+use diesel::sql_query;
+use diesel::sql_types::{BigInt, Binary, Text};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::crypto::encryption::{decrypt_bytes, encrypt_bytes, is_encrypted_with};
+use crate::db::DbPool;
+use crate::services::dispute_evidence::DisputeEvidenceService;
+
+/// Rows re-encrypted per transaction
+const BATCH_SIZE: i64 = 200;
+
+/// How a column stores its ciphertext
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnEncoding {
+    /// BLOB holding the ciphertext
+    Binary,
+    /// TEXT holding base64 ciphertext; other values (plain JSON) are skipped
+    Base64Text,
+}
+
+/// Column encrypted with the server field key
+#[derive(Debug, Clone, Copy)]
+pub struct EncryptedColumn {
+    pub table: &'static str,
+    pub column: &'static str,
+    pub encoding: ColumnEncoding,
+}
+
+/// Every column holding data encrypted with the field key
+pub const ENCRYPTED_COLUMNS: &[EncryptedColumn] = &[
+    EncryptedColumn {
+        table: "escrows",
+        column: "buyer_wallet_info",
+        encoding: ColumnEncoding::Binary,
+    },
+    EncryptedColumn {
+        table: "escrows",
+        column: "vendor_wallet_info",
+        encoding: ColumnEncoding::Binary,
+    },
+    EncryptedColumn {
+        table: "escrows",
+        column: "arbiter_wallet_info",
+        encoding: ColumnEncoding::Binary,
+    },
+    EncryptedColumn {
+        table: "escrows",
+        column: "multisig_state_json",
+        encoding: ColumnEncoding::Base64Text,
+    },
+    EncryptedColumn {
+        table: "wallet_rpc_configs",
+        column: "rpc_url_encrypted",
+        encoding: ColumnEncoding::Binary,
+    },
+    EncryptedColumn {
+        table: "wallet_rpc_configs",
+        column: "rpc_user_encrypted",
+        encoding: ColumnEncoding::Binary,
+    },
+    EncryptedColumn {
+        table: "wallet_rpc_configs",
+        column: "rpc_password_encrypted",
+        encoding: ColumnEncoding::Binary,
+    },
+    // Legacy server-encrypted addresses; envelopes (JSON) are skipped
+    EncryptedColumn {
+        table: "orders",
+        column: "shipping_address",
+        encoding: ColumnEncoding::Base64Text,
+    },
+];
+
+/// Outcome for one column
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ColumnReport {
+    pub table: &'static str,
+    pub column: &'static str,
+    /// Non-null values examined
+    pub scanned: usize,
+    /// Values rewritten under the current key
+    pub reencrypted: usize,
+    /// Values that could not be decrypted with any loaded key
+    pub failed: usize,
+}
+
+/// Outcome of a re-encryption pass
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReencryptionReport {
+    pub columns: Vec<ColumnReport>,
+    pub evidence_reencrypted: usize,
+    pub evidence_failed: usize,
+}
+
+impl ReencryptionReport {
+    pub fn reencrypted(&self) -> usize {
+        self.columns.iter().map(|c| c.reencrypted).sum::<usize>() + self.evidence_reencrypted
+    }
+
+    pub fn failed(&self) -> usize {
+        self.columns.iter().map(|c| c.failed).sum::<usize>() + self.evidence_failed
+    }
+}
+
+/// Column value as read, compared again on update
+enum StoredValue {
+    Binary(Vec<u8>),
+    Text(String),
+}
+
+#[derive(QueryableByName)]
+struct BinaryRow {
+    #[diesel(sql_type = BigInt)]
+    row_id: i64,
+    #[diesel(sql_type = Binary)]
+    value: Vec<u8>,
+}
+
+#[derive(QueryableByName)]
+struct TextRow {
+    #[diesel(sql_type = BigInt)]
+    row_id: i64,
+    #[diesel(sql_type = Text)]
+    value: String,
+}
+
+/// Re-encrypt every column of [`ENCRYPTED_COLUMNS`] under `key`
+///
+/// Values already under `key` are left alone, so the pass can be repeated
+/// (or interrupted) safely.
+pub fn reencrypt_database(conn: &mut SqliteConnection, key: &[u8]) -> Result<ReencryptionReport> {
+    let mut report = ReencryptionReport::default();
+    for column in ENCRYPTED_COLUMNS {
+        let column_report = reencrypt_column(conn, column, key).context(format!(
+            "Failed to re-encrypt {}.{}",
+            column.table, column.column
+        ))?;
+        report.columns.push(column_report);
+    }
+    Ok(report)
+}
+
+/// Rewrite one column in batches ordered by rowid
+fn reencrypt_column(
+    conn: &mut SqliteConnection,
+    column: &EncryptedColumn,
+    key: &[u8],
+) -> Result<ColumnReport> {
+    let mut report = ColumnReport {
+        table: column.table,
+        column: column.column,
+        ..Default::default()
+    };
+
+    let select = format!(
+        "SELECT rowid AS row_id, {col} AS value FROM {table} \
+         WHERE rowid > ? AND {col} IS NOT NULL ORDER BY rowid LIMIT ?",
+        col = column.column,
+        table = column.table
+    );
+    let update = format!(
+        "UPDATE {table} SET {col} = ? WHERE rowid = ? AND {col} = ?",
+        col = column.column,
+        table = column.table
+    );
+
+    let mut after = 0i64;
+    loop {
+        let last = conn.transaction::<_, anyhow::Error, _>(|conn| {
+            let rows: Vec<(i64, StoredValue)> = match column.encoding {
+                ColumnEncoding::Binary => sql_query(&select)
+                    .bind::<BigInt, _>(after)
+                    .bind::<BigInt, _>(BATCH_SIZE)
+                    .load::<BinaryRow>(conn)?
+                    .into_iter()
+                    .map(|row| (row.row_id, StoredValue::Binary(row.value)))
+                    .collect(),
+                ColumnEncoding::Base64Text => sql_query(&select)
+                    .bind::<BigInt, _>(after)
+                    .bind::<BigInt, _>(BATCH_SIZE)
+                    .load::<TextRow>(conn)?
+                    .into_iter()
+                    .map(|row| (row.row_id, StoredValue::Text(row.value)))
+                    .collect(),
+            };
+
+            for (row_id, stored) in &rows {
+                let ciphertext = match stored {
+                    StoredValue::Binary(bytes) => bytes.clone(),
+                    StoredValue::Text(text) => match BASE64.decode(text) {
+                        Ok(bytes) => bytes,
+                        // Plain JSON, not ciphertext
+                        Err(_) => continue,
+                    },
+                };
+
+                report.scanned += 1;
+                if is_encrypted_with(&ciphertext, key) {
+                    continue;
+                }
+
+                let reencrypted = match decrypt_bytes(&ciphertext, key) {
+                    Ok(plaintext) => encrypt_bytes(&plaintext, key)?,
+                    Err(e) => {
+                        warn!(
+                            "Cannot decrypt {}.{} row {}: {}",
+                            column.table, column.column, row_id, e
+                        );
+                        report.failed += 1;
+                        continue;
+                    }
+                };
+
+                report.reencrypted += match stored {
+                    StoredValue::Binary(bytes) => sql_query(&update)
+                        .bind::<Binary, _>(&reencrypted)
+                        .bind::<BigInt, _>(row_id)
+                        .bind::<Binary, _>(bytes)
+                        .execute(conn)?,
+                    StoredValue::Text(text) => sql_query(&update)
+                        .bind::<Text, _>(BASE64.encode(&reencrypted))
+                        .bind::<BigInt, _>(row_id)
+                        .bind::<Text, _>(text)
+                        .execute(conn)?,
+                };
+            }
+
+            Ok(rows.last().map(|(row_id, _)| *row_id))
+        })?;
+
+        match last {
+            Some(row_id) => after = row_id,
+            None => break,
+        }
+    }
+
+    Ok(report)
+}
+
+/// Online re-encryption job run after a key rotation
+pub struct KeyRotationService {
+    db: DbPool,
+    /// Current field key
+    encryption_key: Vec<u8>,
+    evidence: Option<Arc<DisputeEvidenceService>>,
+}
+
+impl KeyRotationService {
+    pub fn new(db: DbPool, encryption_key: Vec<u8>) -> Self {
+        Self {
+            db,
+            encryption_key,
+            evidence: None,
+        }
+    }
+
+    /// Also re-encrypt dispute evidence blobs
+    pub fn with_evidence(mut self, evidence: Arc<DisputeEvidenceService>) -> Self {
+        self.evidence = Some(evidence);
+        self
+    }
+
+    /// Move all encrypted data to the current key
+    pub async fn run(&self) -> Result<ReencryptionReport> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let key = self.encryption_key.clone();
+        let mut report = tokio::task::spawn_blocking(move || reencrypt_database(&mut conn, &key))
+            .await
+            .context("Task join error")??;
+
+        if let Some(evidence) = &self.evidence {
+            let (reencrypted, failed) = evidence.reencrypt_all().await?;
+            report.evidence_reencrypted = reencrypted;
+            report.evidence_failed = failed;
+        }
+
+        if report.failed() == 0 {
+            info!(
+                "Key rotation: {} values re-encrypted, previous keys can be removed",
+                report.reencrypted()
+            );
+        } else {
+            warn!(
+                "Key rotation: {} values re-encrypted, {} could not be decrypted - keep the previous keys",
+                report.reencrypted(),
+                report.failed()
+            );
+        }
+        Ok(report)
+    }
+}
@@ -1,6 +1,14 @@
 pub mod airgap;
+pub mod arbiter_assignment;
+pub mod audit;
+pub mod audit_checkpoint;
 pub mod blockchain_monitor;
+pub mod dispute_evidence;
 pub mod escrow;
+pub mod fiat_pricing;
+pub mod key_rotation;
+pub mod payment_proof;
 pub mod price_conversion;
 pub mod timeout_monitor;
+pub mod vendor_bond;
 pub mod wallet_session_manager;
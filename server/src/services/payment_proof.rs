@@ -0,0 +1,374 @@
+//! On-chain verification of review payments
+//!
+//! A 2.0 review names its escrow. The review is marked verified once a
+//! transaction proof (`get_tx_proof` / `check_tx_proof`) shows its txid paid
+//! the escrow's multisig address at least the escrow amount, with enough
+//! confirmations. The proof is stored with the review together with the
+//! public data of the transaction (tx public keys, output keys, encrypted
+//! amounts and commitments), so anyone can check it again offline, e.g. with
+//! `reputation-wasm` in the browser.
+//!
+//! # Proofs
+//! The buyer may send the proof from their own wallet (`OutProofV2`, signed
+//! with the transaction secret key). Without one, the escrow's wallet proves
+//! receipt itself (`InProofV2`, multisig participants share the view key).
+//! Either way the proof signs `ReviewBinding::payment_proof_message`.
+
+use anyhow::{Context, Result};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::info;
+use uuid::Uuid;
+
+use monero_marketplace_wallet::validation::validate_localhost_strict;
+use reputation_common::types::{PaymentProof, SignedReview, TxOutput};
+use reputation_crypto::payment_proof::payment_proof_received;
+
+use crate::config::{ConfirmationPolicy, ReviewPolicy};
+use crate::db::reputation::{db_get_review, db_mark_review_verified};
+use crate::db::{db_load_escrow, DbPool};
+use crate::wallet_manager::WalletManager;
+
+/// First RingCT type with 8-byte encrypted amounts (Bulletproofs 2)
+const MIN_COMPACT_RCT_TYPE: u64 = 4;
+
+/// Verifies review payments and stores their proofs
+pub struct PaymentProofService {
+    /// Database connection pool
+    db: DbPool,
+    /// Monero wallet manager (escrow wallets check and generate proofs)
+    wallet_manager: Arc<Mutex<WalletManager>>,
+    /// Marketplace domain and daemon RPC URL
+    policy: ReviewPolicy,
+    /// Confirmations required for the escrow amount
+    confirmations: ConfirmationPolicy,
+    /// HTTP client for the daemon RPC
+    http: reqwest::Client,
+}
+
+impl PaymentProofService {
+    /// Create a new PaymentProofService
+    ///
+    /// # Errors
+    /// - Daemon RPC URL is not on localhost
+    pub fn new(
+        db: DbPool,
+        wallet_manager: Arc<Mutex<WalletManager>>,
+        policy: ReviewPolicy,
+        confirmations: ConfirmationPolicy,
+    ) -> Result<Self> {
+        validate_localhost_strict(&policy.daemon_rpc_url)
+            .context("Daemon RPC must be on localhost")?;
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .context("Failed to build HTTP client")?;
+
+        Ok(Self {
+            db,
+            wallet_manager,
+            policy,
+            confirmations,
+            http,
+        })
+    }
+
+    /// Verify the payment of the review `reviewer_id` wrote for `txid`
+    ///
+    /// Uses `signature` (a tx proof string) if given, otherwise has the
+    /// escrow wallet generate one. Returns the stored proof.
+    ///
+    /// # Errors
+    /// - Review not found, already verified, or not bound to an escrow (1.0)
+    /// - Proof invalid, transaction unconfirmed or paying less than the escrow
+    /// - Wallet or daemon RPC unreachable
+    pub async fn verify_review_payment(
+        &self,
+        txid: &str,
+        reviewer_id: &str,
+        signature: Option<String>,
+    ) -> Result<PaymentProof> {
+        let review = db_get_review(&self.db, txid, reviewer_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Review not found"))?;
+        if review.verified {
+            anyhow::bail!("Review payment is already verified");
+        }
+        let review_id = review.id.clone();
+        let binding = SignedReview::from(review)
+            .binding()
+            .ok_or_else(|| anyhow::anyhow!("Only 2.0 reviews name the escrow that was paid"))?;
+
+        let escrow_uuid = Uuid::parse_str(&binding.escrow_id)
+            .map_err(|_| anyhow::anyhow!("Invalid escrow ID"))?;
+        let escrow = db_load_escrow(&self.db, escrow_uuid).await?;
+        let address = escrow
+            .multisig_address
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Escrow has no multisig address"))?;
+        let wallet_id = escrow
+            .arbiter_temp_wallet_id
+            .as_deref()
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .ok_or_else(|| anyhow::anyhow!("Escrow has no wallet to check the proof with"))?;
+        let amount = u64::try_from(escrow.amount).context("Invalid escrow amount")?;
+        let message = binding.payment_proof_message();
+
+        let (signature, check) = {
+            let wallet_manager = self.wallet_manager.lock().await;
+            let signature = match signature {
+                Some(signature) => signature,
+                None => wallet_manager
+                    .get_tx_proof(wallet_id, txid, &address, &message)
+                    .await
+                    .context("Failed to generate payment proof")?,
+            };
+            let check = wallet_manager
+                .check_tx_proof(wallet_id, txid, &address, &message, &signature)
+                .await
+                .context("Failed to check payment proof")?;
+            (signature, check)
+        };
+
+        if !check.good {
+            anyhow::bail!("Payment proof does not verify");
+        }
+        let required = self.confirmations.required_for(amount);
+        if check.in_pool || check.confirmations < u64::from(required) {
+            anyhow::bail!(
+                "Payment has {} confirmation(s), {} required",
+                check.confirmations,
+                required
+            );
+        }
+        if check.received < amount {
+            anyhow::bail!(
+                "Payment of {} atomic units is below the escrow amount of {}",
+                check.received,
+                amount
+            );
+        }
+
+        // Public data of the transaction, for offline verification
+        let tx = self.fetch_transaction(txid).await?;
+        let mut proof = payment_proof_from_tx(&tx, txid, &address, &message, &signature)?;
+        proof.amount = check.received;
+        let received = payment_proof_received(&proof).context("Payment proof does not verify")?;
+        if received != check.received {
+            anyhow::bail!(
+                "Payment proof shows {} atomic units, wallet reported {}",
+                received,
+                check.received
+            );
+        }
+
+        db_mark_review_verified(&self.db, &review_id, &proof).await?;
+        info!(
+            "Review {} payment verified ({} atomic units, {} confirmations)",
+            review_id, check.received, check.confirmations
+        );
+
+        Ok(proof)
+    }
+
+    /// Fetch a transaction from the daemon (`/get_transactions`, decoded)
+    async fn fetch_transaction(&self, txid: &str) -> Result<serde_json::Value> {
+        let response: serde_json::Value = self
+            .http
+            .post(format!("{}/get_transactions", self.policy.daemon_rpc_url))
+            .json(&serde_json::json!({
+                "txs_hashes": [txid],
+                "decode_as_json": true,
+            }))
+            .send()
+            .await
+            .context("Failed to reach daemon RPC")?
+            .json()
+            .await
+            .context("Invalid daemon RPC response")?;
+
+        let as_json = response["txs"][0]["as_json"]
+            .as_str()
+            .ok_or_else(|| anyhow::anyhow!("Transaction not found by the daemon"))?;
+        serde_json::from_str(as_json).context("Invalid transaction JSON")
+    }
+}
+
+/// Build a payment proof from a decoded transaction (daemon `as_json`)
+///
+/// `amount` is left at 0 for the caller to fill in.
+///
+/// # Errors
+/// - No tx public key in `extra`
+/// - Pre-Bulletproofs-2 RingCT (amounts are not 8-byte encrypted)
+pub fn payment_proof_from_tx(
+    tx: &serde_json::Value,
+    txid: &str,
+    address: &str,
+    message: &str,
+    signature: &str,
+) -> Result<PaymentProof> {
+    let extra = tx["extra"]
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("Transaction has no extra field"))?
+        .iter()
+        .map(|byte| byte.as_u64().and_then(|b| u8::try_from(b).ok()))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or_else(|| anyhow::anyhow!("Invalid transaction extra"))?;
+    let tx_pubkeys = parse_tx_pubkeys(&extra)?;
+
+    let rct = &tx["rct_signatures"];
+    if rct["type"].as_u64().unwrap_or(0) < MIN_COMPACT_RCT_TYPE {
+        anyhow::bail!("Unsupported RingCT type for payment proofs");
+    }
+    let vout = tx["vout"]
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("Transaction has no outputs"))?;
+    let outputs = vout
+        .iter()
+        .enumerate()
+        .map(|(index, output)| {
+            let target = &output["target"];
+            let key = target["tagged_key"]["key"]
+                .as_str()
+                .or_else(|| target["key"].as_str());
+            Some(TxOutput {
+                key: key?.to_string(),
+                encrypted_amount: rct["ecdhInfo"][index]["amount"].as_str()?.to_string(),
+                commitment: rct["outPk"][index].as_str()?.to_string(),
+            })
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| anyhow::anyhow!("Invalid transaction output"))?;
+
+    Ok(PaymentProof {
+        txid: txid.to_string(),
+        address: address.to_string(),
+        message: message.to_string(),
+        signature: signature.to_string(),
+        tx_pubkeys,
+        outputs,
+        amount: 0,
+    })
+}
+
+/// Main then additional tx public keys from `extra` (hex)
+fn parse_tx_pubkeys(extra: &[u8]) -> Result<Vec<String>> {
+    let mut main = None;
+    let mut additional = Vec::new();
+    let mut i = 0;
+    while i < extra.len() {
+        match extra[i] {
+            // Padding runs to the end
+            0x00 => break,
+            // Tx public key
+            0x01 => {
+                let key = extra
+                    .get(i + 1..i + 33)
+                    .context("Truncated tx public key")?;
+                main.get_or_insert_with(|| hex::encode(key));
+                i += 33;
+            }
+            // Nonce (payment id), one length byte
+            0x02 => {
+                let len = *extra.get(i + 1).context("Truncated nonce")? as usize;
+                i += 2 + len;
+            }
+            // Additional tx public keys (one per output)
+            0x04 => {
+                let (count, len) = read_varint(&extra[i + 1..])?;
+                i += 1 + len;
+                for _ in 0..count {
+                    let key = extra.get(i..i + 32).context("Truncated additional key")?;
+                    additional.push(hex::encode(key));
+                    i += 32;
+                }
+            }
+            // Unknown field: nothing after it can be parsed reliably
+            _ => break,
+        }
+    }
+
+    let main = main.ok_or_else(|| anyhow::anyhow!("Transaction has no public key"))?;
+    Ok(std::iter::once(main).chain(additional).collect())
+}
+
+/// LEB128 varint, returns (value, bytes read)
+fn read_varint(data: &[u8]) -> Result<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, byte) in data.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+    anyhow::bail!("Invalid varint")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> Vec<u8> {
+        vec![byte; 32]
+    }
+
+    #[test]
+    fn test_parse_tx_pubkeys() -> Result<()> {
+        let mut extra = vec![0x01];
+        extra.extend(key(1));
+        extra.extend([0x02, 0x09, 0x01]);
+        extra.extend([0u8; 8]);
+        extra.extend([0x04, 0x02]);
+        extra.extend(key(2));
+        extra.extend(key(3));
+
+        let keys = parse_tx_pubkeys(&extra)?;
+        assert_eq!(
+            keys,
+            vec![
+                hex::encode(key(1)),
+                hex::encode(key(2)),
+                hex::encode(key(3))
+            ]
+        );
+
+        // Truncated key, missing key
+        assert!(parse_tx_pubkeys(&extra[..20]).is_err());
+        assert!(parse_tx_pubkeys(&[0x02, 0x00]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_payment_proof_from_tx() -> Result<()> {
+        let mut extra = vec![1u8];
+        extra.extend(key(7));
+        let tx = serde_json::json!({
+            "version": 2,
+            "vout": [
+                { "amount": 0, "target": { "tagged_key": { "key": hex::encode(key(8)), "view_tag": "ab" } } },
+                { "amount": 0, "target": { "key": hex::encode(key(9)) } }
+            ],
+            "extra": extra,
+            "rct_signatures": {
+                "type": 6,
+                "ecdhInfo": [{ "amount": "0011223344556677" }, { "amount": "8899aabbccddeeff" }],
+                "outPk": [hex::encode(key(10)), hex::encode(key(11))]
+            }
+        });
+
+        let proof = payment_proof_from_tx(&tx, "txid", "address", "message", "OutProofV2")?;
+        assert_eq!(proof.tx_pubkeys, vec![hex::encode(key(7))]);
+        assert_eq!(proof.outputs.len(), 2);
+        assert_eq!(proof.outputs[0].key, hex::encode(key(8)));
+        assert_eq!(proof.outputs[1].encrypted_amount, "8899aabbccddeeff");
+        assert_eq!(proof.outputs[1].commitment, hex::encode(key(11)));
+
+        // RingCT before Bulletproofs 2
+        let mut old = tx.clone();
+        old["rct_signatures"]["type"] = serde_json::json!(3);
+        assert!(payment_proof_from_tx(&old, "txid", "address", "message", "OutProofV2").is_err());
+        Ok(())
+    }
+}
@@ -1,197 +1,573 @@
-//! XMR/USD Price Conversion Service
+//! XMR Exchange Rates from Several Price Sources
 //!
-//! Fetches real-time Monero to USD exchange rate from CoinGecko API via Tor proxy.
-//! Implements caching with 5-minute TTL to reduce API calls and improve performance.
+//! [`PriceOracle`] queries every configured [`PriceSource`] (CoinGecko and
+//! Kraken via Tor, or manual rates for offline use), rejects sources too far
+//! from the median and records the median of the others in `price_history`.
+//!
+//! There is no hard-coded fallback rate: when no source answers, the last
+//! recorded rate keeps being served with its age, flagged as stale once it
+//! is older than `max_age_secs`.
 
 use anyhow::{Context, Result};
+use chrono::{NaiveDateTime, Utc};
+use futures_util::future::{join_all, BoxFuture};
 use reqwest::Proxy;
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
-use std::time::{Duration, Instant};
-use tracing::{debug, info, warn};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+use crate::config::PriceOracleConfig;
+use crate::db::DbPool;
+use crate::models::price_history::PriceRecord;
+
+/// Atomic units (piconeros) per XMR
+pub const ATOMIC_PER_XMR: f64 = 1_000_000_000_000.0;
+
+/// Rates from one source: lowercase currency code → fiat units per XMR
+pub type SourceRates = HashMap<String, f64>;
+
+/// Where XMR exchange rates come from
+pub trait PriceSource: Send + Sync {
+    /// Name recorded in the price history
+    fn name(&self) -> &'static str;
+
+    /// XMR rates for `currencies` (lowercase ISO 4217)
+    ///
+    /// Currencies the source does not quote are left out of the result.
+    fn fetch<'a>(&'a self, currencies: &'a [String]) -> BoxFuture<'a, Result<SourceRates>>;
+}
 
-/// Cache entry for XMR/USD rate with timestamp
-#[derive(Clone, Debug)]
-struct CachedRate {
-    rate: f64,
-    timestamp: Instant,
+/// HTTP client for the price APIs (through Tor unless configured otherwise)
+fn http_client(proxy: Option<&str>) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30)) // Tor can be slow
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; rv:102.0) Gecko/20100101 Firefox/102.0");
+    if let Some(proxy) = proxy {
+        builder =
+            builder.proxy(Proxy::all(proxy).context("Failed to configure proxy for price APIs")?);
+    }
+    builder.build().context("Failed to build HTTP client")
 }
 
-/// Global cache for XMR/USD rate (thread-safe)
-static RATE_CACHE: Mutex<Option<CachedRate>> = Mutex::new(None);
+/// GET `url` and return the body of a successful response
+async fn get_text(client: &reqwest::Client, url: &str) -> Result<String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .context(format!("Failed to send request to {}", url))?;
+
+    if !response.status().is_success() {
+        anyhow::bail!("{} returned non-success status: {}", url, response.status());
+    }
 
-/// Cache TTL duration (5 minutes)
-const CACHE_TTL: Duration = Duration::from_secs(300);
+    response
+        .text()
+        .await
+        .context("Failed to read response body")
+}
 
-/// Fallback static rate if API is unreachable (150 USD/XMR)
-const FALLBACK_RATE: f64 = 150.0;
+/// CoinGecko simple price API (free tier, no API key required)
+pub struct CoinGeckoSource {
+    client: reqwest::Client,
+}
 
-/// CoinGecko API response structure
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize)]
 struct CoinGeckoResponse {
-    monero: MoneroPrice,
+    monero: HashMap<String, f64>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
-struct MoneroPrice {
-    usd: f64,
+impl CoinGeckoSource {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+
+    /// Parse `{"monero": {"usd": 150.1, "eur": 139.8}}`
+    fn parse(body: &str) -> Result<SourceRates> {
+        let response: CoinGeckoResponse =
+            serde_json::from_str(body).context("Failed to parse CoinGecko API response")?;
+        Ok(response
+            .monero
+            .into_iter()
+            .map(|(currency, rate)| (currency.to_lowercase(), rate))
+            .collect())
+    }
 }
 
-/// Fetch XMR/USD exchange rate from CoinGecko API via Tor
-///
-/// Returns cached rate if available and fresh (<5 min old), otherwise fetches from API.
-/// Falls back to static rate (150 USD/XMR) if API call fails.
-///
-/// # Returns
-/// - `Ok(rate)`: Exchange rate as f64 (e.g., 155.43 means 1 XMR = $155.43 USD)
-///
-/// # Errors
-/// - Never returns error, always provides fallback rate
-pub async fn get_xmr_usd_rate() -> f64 {
-    // Check cache first
-    if let Some(cached_rate) = get_cached_rate() {
-        debug!("Using cached XMR/USD rate: ${:.2}", cached_rate);
-        return cached_rate;
-    }
-
-    // Fetch from API
-    match fetch_rate_from_api().await {
-        Ok(rate) => {
-            info!("Fetched fresh XMR/USD rate from CoinGecko: ${:.2}", rate);
-            cache_rate(rate);
-            rate
-        }
-        Err(e) => {
-            warn!(
-                "Failed to fetch XMR/USD rate from CoinGecko ({}), using fallback: ${:.2}",
-                e, FALLBACK_RATE
+impl PriceSource for CoinGeckoSource {
+    fn name(&self) -> &'static str {
+        "coingecko"
+    }
+
+    fn fetch<'a>(&'a self, currencies: &'a [String]) -> BoxFuture<'a, Result<SourceRates>> {
+        Box::pin(async move {
+            let url = format!(
+                "https://api.coingecko.com/api/v3/simple/price?ids=monero&vs_currencies={}",
+                currencies.join(",")
             );
-            FALLBACK_RATE
-        }
+            debug!("Fetching XMR rates from CoinGecko...");
+            Self::parse(&get_text(&self.client, &url).await?)
+        })
     }
 }
 
-/// Get cached rate if available and not expired
-fn get_cached_rate() -> Option<f64> {
-    let cache = RATE_CACHE.lock().ok()?;
+/// Kraken public ticker (last trade price)
+pub struct KrakenSource {
+    client: reqwest::Client,
+}
 
-    if let Some(cached) = cache.as_ref() {
-        let age = cached.timestamp.elapsed();
-        if age < CACHE_TTL {
-            debug!("Cache hit (age: {:?})", age);
-            return Some(cached.rate);
-        } else {
-            debug!("Cache expired (age: {:?})", age);
-        }
-    }
+#[derive(Deserialize)]
+struct KrakenResponse {
+    error: Vec<String>,
+    #[serde(default)]
+    result: HashMap<String, KrakenTicker>,
+}
 
-    None
+#[derive(Deserialize)]
+struct KrakenTicker {
+    /// Last trade closed: [price, lot volume]
+    c: Vec<String>,
 }
 
-/// Cache the rate with current timestamp
-fn cache_rate(rate: f64) {
-    if let Ok(mut cache) = RATE_CACHE.lock() {
-        *cache = Some(CachedRate {
-            rate,
-            timestamp: Instant::now(),
-        });
-        debug!("Cached XMR/USD rate: ${:.2}", rate);
+impl KrakenSource {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+
+    /// Parse the ticker of one XMR pair (named e.g. `XXMRZUSD` in the result)
+    fn parse(body: &str) -> Result<f64> {
+        let response: KrakenResponse =
+            serde_json::from_str(body).context("Failed to parse Kraken API response")?;
+        if !response.error.is_empty() {
+            anyhow::bail!("Kraken API error: {}", response.error.join(", "));
+        }
+        let ticker = response
+            .result
+            .values()
+            .next()
+            .context("Kraken API returned no ticker")?;
+        ticker
+            .c
+            .first()
+            .context("Kraken ticker has no last trade")?
+            .parse()
+            .context("Invalid Kraken last trade price")
     }
 }
 
-/// Fetch XMR/USD rate from CoinGecko API via Tor proxy
-async fn fetch_rate_from_api() -> Result<f64> {
-    // Configure Tor SOCKS5 proxy
-    let proxy = Proxy::all("socks5h://127.0.0.1:9050")
-        .context("Failed to configure Tor proxy for price API")?;
+impl PriceSource for KrakenSource {
+    fn name(&self) -> &'static str {
+        "kraken"
+    }
 
-    // Build HTTP client with Tor proxy and timeout
-    let client = reqwest::Client::builder()
-        .proxy(proxy)
-        .timeout(Duration::from_secs(30)) // Tor can be slow
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; rv:102.0) Gecko/20100101 Firefox/102.0")
-        .build()
-        .context("Failed to build HTTP client")?;
+    /// One request per pair, so an unlisted currency does not fail the others
+    fn fetch<'a>(&'a self, currencies: &'a [String]) -> BoxFuture<'a, Result<SourceRates>> {
+        Box::pin(async move {
+            let mut rates = SourceRates::new();
+            for currency in currencies {
+                let url = format!(
+                    "https://api.kraken.com/0/public/Ticker?pair=XMR{}",
+                    currency.to_uppercase()
+                );
+                debug!("Fetching XMR/{} from Kraken...", currency.to_uppercase());
+                match get_text(&self.client, &url)
+                    .await
+                    .and_then(|body| Self::parse(&body))
+                {
+                    Ok(rate) => {
+                        rates.insert(currency.clone(), rate);
+                    }
+                    Err(e) => debug!("Kraken has no XMR/{} rate: {:#}", currency, e),
+                }
+            }
+            if rates.is_empty() {
+                anyhow::bail!("Kraken returned no rate for {}", currencies.join(", "));
+            }
+            Ok(rates)
+        })
+    }
+}
 
-    // CoinGecko API endpoint (free tier, no API key required)
-    let url = "https://api.coingecko.com/api/v3/simple/price?ids=monero&vs_currencies=usd";
+/// Rates set by an admin or read from a local JSON file, for offline use
+///
+/// Admin rates take precedence over the file, which is re-read on every
+/// refresh.
+pub struct ManualSource {
+    file: Option<PathBuf>,
+    rates: RwLock<SourceRates>,
+}
 
-    debug!("Fetching XMR/USD rate from CoinGecko via Tor...");
+impl ManualSource {
+    pub fn new(file: Option<PathBuf>) -> Self {
+        Self {
+            file,
+            rates: RwLock::new(SourceRates::new()),
+        }
+    }
 
-    // Make HTTP request
-    let response = client
-        .get(url)
-        .send()
-        .await
-        .context("Failed to send request to CoinGecko API")?;
+    /// Set the admin rate for `currency`, or clear it with `None`
+    pub fn set_rate(&self, currency: &str, rate: Option<f64>) -> Result<()> {
+        let mut rates = self
+            .rates
+            .write()
+            .map_err(|_| anyhow::anyhow!("Manual rates lock poisoned"))?;
+        match rate {
+            Some(rate) if rate.is_finite() && rate > 0.0 => {
+                rates.insert(currency.to_lowercase(), rate);
+            }
+            Some(rate) => anyhow::bail!("Invalid rate: {}", rate),
+            None => {
+                rates.remove(&currency.to_lowercase());
+            }
+        }
+        Ok(())
+    }
 
-    // Check HTTP status
-    if !response.status().is_success() {
-        return Err(anyhow::anyhow!(
-            "CoinGecko API returned non-success status: {}",
-            response.status()
-        ));
+    /// Parse `{"usd": 150.0, "eur": 140.0}`
+    fn parse_file(body: &str) -> Result<SourceRates> {
+        let rates: SourceRates = serde_json::from_str(body)
+            .context("Manual rates file must be a JSON object of rates")?;
+        Ok(rates
+            .into_iter()
+            .map(|(currency, rate)| (currency.to_lowercase(), rate))
+            .collect())
     }
+}
 
-    // Parse JSON response
-    let data: CoinGeckoResponse = response
-        .json()
-        .await
-        .context("Failed to parse CoinGecko API response")?;
+impl PriceSource for ManualSource {
+    fn name(&self) -> &'static str {
+        "manual"
+    }
 
-    // Validate rate is reasonable (between $50 and $1000)
-    let rate = data.monero.usd;
-    if rate < 50.0 || rate > 1000.0 {
-        return Err(anyhow::anyhow!(
-            "Unreasonable XMR/USD rate from API: ${:.2}",
-            rate
-        ));
+    fn fetch<'a>(&'a self, currencies: &'a [String]) -> BoxFuture<'a, Result<SourceRates>> {
+        Box::pin(async move {
+            let mut rates = match &self.file {
+                Some(path) => Self::parse_file(
+                    &tokio::fs::read_to_string(path)
+                        .await
+                        .context(format!("Failed to read {}", path.display()))?,
+                )?,
+                None => SourceRates::new(),
+            };
+            let admin_rates = self
+                .rates
+                .read()
+                .map_err(|_| anyhow::anyhow!("Manual rates lock poisoned"))?;
+            rates.extend(admin_rates.iter().map(|(c, r)| (c.clone(), *r)));
+            rates.retain(|currency, _| currencies.contains(currency));
+            Ok(rates)
+        })
     }
+}
 
-    Ok(rate)
+/// Quotes of several sources combined into one rate
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Aggregate {
+    /// Median of the accepted quotes
+    pub rate: f64,
+    /// Sources in the median
+    pub sources: Vec<String>,
+    /// Sources rejected as outliers
+    pub rejected: Vec<String>,
 }
 
-/// Convert XMR atomic units (piconeros) to USD
-///
-/// # Arguments
-/// * `atomic_units` - Price in piconeros (1 XMR = 1,000,000,000,000 piconeros)
-///
-/// # Returns
-/// - USD amount as f64
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
+
+/// Median of `quotes` after rejecting those more than `max_deviation_pct`
+/// away from the median of all of them
 ///
-/// # Example
-/// ```
-/// let atomic_price: i64 = 240_000_000_000; // 0.24 XMR
-/// let usd_price = atomic_to_usd(atomic_price).await;
-/// // If rate is $155/XMR, returns ~$37.20
-/// ```
-pub async fn atomic_to_usd(atomic_units: i64) -> f64 {
-    const XMR_TO_ATOMIC: f64 = 1_000_000_000_000.0;
+/// Non-positive or non-finite quotes are rejected outright. Two sources that
+/// disagree are both rejected: neither can be trusted over the other.
+pub fn aggregate(quotes: &[(String, f64)], max_deviation_pct: f64) -> Option<Aggregate> {
+    let (valid, mut rejected): (Vec<_>, Vec<_>) = quotes
+        .iter()
+        .cloned()
+        .partition(|(_, rate)| rate.is_finite() && *rate > 0.0);
+
+    let center = median(&mut valid.iter().map(|(_, rate)| *rate).collect::<Vec<_>>())?;
+    let (accepted, outliers): (Vec<_>, Vec<_>) = valid
+        .into_iter()
+        .partition(|(_, rate)| ((rate - center) / center).abs() * 100.0 <= max_deviation_pct);
+    rejected.extend(outliers);
+
+    let rate = median(&mut accepted.iter().map(|(_, rate)| *rate).collect::<Vec<_>>())?;
+    Some(Aggregate {
+        rate,
+        sources: accepted.into_iter().map(|(name, _)| name).collect(),
+        rejected: rejected.into_iter().map(|(name, _)| name).collect(),
+    })
+}
 
-    let xmr = atomic_units as f64 / XMR_TO_ATOMIC;
-    let rate = get_xmr_usd_rate().await;
+/// Rate in use for one currency, with its age
+#[derive(Debug, Clone, Serialize)]
+pub struct CurrentRate {
+    pub currency: String,
+    /// Fiat units per 1 XMR
+    pub rate: f64,
+    /// Sources in the median
+    pub sources: Vec<String>,
+    /// When the rate was last refreshed
+    pub fetched_at: NaiveDateTime,
+    pub age_secs: i64,
+    /// Older than `max_age_secs`: no source produced a usable rate since
+    pub stale: bool,
+}
 
-    xmr * rate
+impl CurrentRate {
+    /// Fiat value of an amount in atomic units
+    pub fn convert_atomic(&self, atomic_units: i64) -> f64 {
+        atomic_units as f64 / ATOMIC_PER_XMR * self.rate
+    }
 }
 
-/// Convert XMR (as f64) to USD
-///
-/// # Arguments
-/// * `xmr` - Amount in XMR as f64
-///
-/// # Returns
-/// - USD amount as f64
-pub async fn xmr_to_usd(xmr: f64) -> f64 {
-    let rate = get_xmr_usd_rate().await;
-    xmr * rate
+/// Aggregates XMR exchange rates and keeps their history
+pub struct PriceOracle {
+    db: DbPool,
+    config: PriceOracleConfig,
+    sources: Vec<Arc<dyn PriceSource>>,
+    manual: Option<Arc<ManualSource>>,
 }
 
-/// Clear the rate cache (useful for testing)
-#[allow(dead_code)]
-pub fn clear_cache() {
-    if let Ok(mut cache) = RATE_CACHE.lock() {
-        *cache = None;
-        debug!("Rate cache cleared");
+impl PriceOracle {
+    /// Oracle querying the sources named in `config.sources`
+    pub fn new(db: DbPool, config: PriceOracleConfig) -> Result<Self> {
+        let client = http_client(config.proxy.as_deref())?;
+        let manual = config
+            .manual_enabled()
+            .then(|| Arc::new(ManualSource::new(config.manual_rates_file.clone())));
+
+        let mut sources: Vec<Arc<dyn PriceSource>> = Vec::new();
+        for name in &config.sources {
+            match name.as_str() {
+                "coingecko" => sources.push(Arc::new(CoinGeckoSource::new(client.clone()))),
+                "kraken" => sources.push(Arc::new(KrakenSource::new(client.clone()))),
+                "manual" => {
+                    if let Some(manual) = &manual {
+                        sources.push(manual.clone());
+                    }
+                }
+                other => warn!("Unknown price source '{}' ignored", other),
+            }
+        }
+
+        Ok(Self {
+            db,
+            config,
+            sources,
+            manual,
+        })
+    }
+
+    /// Oracle over explicitly provided sources (`config.sources` is ignored)
+    pub fn with_sources(
+        db: DbPool,
+        config: PriceOracleConfig,
+        sources: Vec<Arc<dyn PriceSource>>,
+    ) -> Self {
+        Self {
+            db,
+            config,
+            sources,
+            manual: None,
+        }
+    }
+
+    /// Currencies XMR is priced in
+    pub fn currencies(&self) -> &[String] {
+        &self.config.currencies
+    }
+
+    pub fn config(&self) -> &PriceOracleConfig {
+        &self.config
+    }
+
+    /// Refresh rates every `refresh_secs`
+    pub async fn start(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(self.config.refresh_interval());
+        loop {
+            interval.tick().await;
+            match self.refresh().await {
+                Ok(records) => {
+                    for record in &records {
+                        info!(
+                            "XMR/{} = {:.2} (sources: {})",
+                            record.currency.to_uppercase(),
+                            record.rate,
+                            record.sources
+                        );
+                    }
+                }
+                Err(e) => error!("Price refresh failed: {:#}", e),
+            }
+        }
+    }
+
+    /// Query every source and record a new rate for each currency they agree on
+    ///
+    /// Currencies without enough agreeing sources keep their previous rate,
+    /// which eventually becomes stale.
+    pub async fn refresh(&self) -> Result<Vec<PriceRecord>> {
+        let currencies = &self.config.currencies;
+        let results = join_all(self.sources.iter().map(|source| source.fetch(currencies))).await;
+
+        let mut quotes: HashMap<String, Vec<(String, f64)>> = HashMap::new();
+        for (source, result) in self.sources.iter().zip(results) {
+            match result {
+                Ok(rates) => {
+                    for (currency, rate) in rates {
+                        if currencies.contains(&currency) {
+                            quotes
+                                .entry(currency)
+                                .or_default()
+                                .push((source.name().to_string(), rate));
+                        }
+                    }
+                }
+                Err(e) => warn!("Price source {} failed: {:#}", source.name(), e),
+            }
+        }
+
+        let mut accepted = Vec::new();
+        for currency in currencies {
+            let quotes = quotes.remove(currency).unwrap_or_default();
+            let Some(aggregate) = aggregate(&quotes, self.config.max_deviation_pct) else {
+                warn!(
+                    "No usable XMR/{} rate from any source",
+                    currency.to_uppercase()
+                );
+                continue;
+            };
+            if !aggregate.rejected.is_empty() {
+                warn!(
+                    "XMR/{} outliers rejected: {}",
+                    currency.to_uppercase(),
+                    aggregate.rejected.join(", ")
+                );
+            }
+            if aggregate.sources.len() < self.config.min_sources {
+                warn!(
+                    "XMR/{} from {} source(s), {} required - keeping previous rate",
+                    currency.to_uppercase(),
+                    aggregate.sources.len(),
+                    self.config.min_sources
+                );
+                continue;
+            }
+            accepted.push((currency.clone(), aggregate));
+        }
+
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        tokio::task::spawn_blocking(move || {
+            accepted
+                .iter()
+                .map(|(currency, aggregate)| {
+                    PriceRecord::insert(
+                        &mut conn,
+                        currency,
+                        aggregate.rate,
+                        &aggregate.sources,
+                        &aggregate.rejected,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .await
+        .context("Task join error")?
+    }
+
+    /// Set an admin rate on the manual source and refresh right away
+    pub async fn set_manual_rate(
+        &self,
+        currency: &str,
+        rate: Option<f64>,
+    ) -> Result<Vec<PriceRecord>> {
+        let manual = self
+            .manual
+            .as_ref()
+            .context("Manual price source is not enabled (add `manual` to PRICE_SOURCES)")?;
+        let currency = currency.to_lowercase();
+        if !self.config.currencies.contains(&currency) {
+            anyhow::bail!("Currency {} is not priced by this marketplace", currency);
+        }
+        manual.set_rate(&currency, rate)?;
+        self.refresh().await
+    }
+
+    /// Rate in use for `currency`
+    ///
+    /// Fails when no rate was ever recorded: callers must not invent one.
+    pub async fn rate(&self, currency: &str) -> Result<CurrentRate> {
+        let currency = currency.to_lowercase();
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let record = {
+            let currency = currency.clone();
+            tokio::task::spawn_blocking(move || PriceRecord::latest(&mut conn, &currency))
+                .await
+                .context("Task join error")??
+        };
+        record.map(|record| self.current(record)).context(format!(
+            "No XMR/{} rate recorded yet",
+            currency.to_uppercase()
+        ))
+    }
+
+    /// Rates in use for every configured currency that has one
+    pub async fn rates(&self) -> Result<Vec<CurrentRate>> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let currencies = self.config.currencies.clone();
+        let records = tokio::task::spawn_blocking(move || {
+            currencies
+                .iter()
+                .filter_map(|currency| PriceRecord::latest(&mut conn, currency).transpose())
+                .collect::<Result<Vec<_>>>()
+        })
+        .await
+        .context("Task join error")??;
+        Ok(records
+            .into_iter()
+            .map(|record| self.current(record))
+            .collect())
+    }
+
+    /// Recorded rates for `currency`, newest first
+    pub async fn history(&self, currency: &str, limit: i64) -> Result<Vec<PriceRecord>> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let currency = currency.to_lowercase();
+        tokio::task::spawn_blocking(move || PriceRecord::list(&mut conn, &currency, limit))
+            .await
+            .context("Task join error")?
+    }
+
+    fn current(&self, record: PriceRecord) -> CurrentRate {
+        let age_secs = (Utc::now().naive_utc() - record.fetched_at)
+            .num_seconds()
+            .max(0);
+        CurrentRate {
+            sources: record
+                .source_names()
+                .into_iter()
+                .map(str::to_string)
+                .collect(),
+            currency: record.currency,
+            rate: record.rate,
+            fetched_at: record.fetched_at,
+            age_secs,
+            stale: age_secs as u64 > self.config.max_age_secs,
+        }
     }
 }
 
@@ -199,50 +575,76 @@ pub fn clear_cache() {
 mod tests {
     use super::*;
 
-    #[tokio::test]
-    async fn test_get_xmr_usd_rate() {
-        // This test will use fallback rate if Tor/API unavailable
-        let rate = get_xmr_usd_rate().await;
-        assert!(rate > 0.0, "Rate should be positive");
-        assert!(
-            rate >= 50.0 && rate <= 1000.0,
-            "Rate should be reasonable"
-        );
+    fn quotes(rates: &[(&str, f64)]) -> Vec<(String, f64)> {
+        rates
+            .iter()
+            .map(|(name, rate)| (name.to_string(), *rate))
+            .collect()
     }
 
-    #[tokio::test]
-    async fn test_atomic_to_usd() {
-        // Test with 1 XMR (1,000,000,000,000 atomic units)
-        let atomic = 1_000_000_000_000i64;
-        let usd = atomic_to_usd(atomic).await;
-
-        // Should be between $50 and $1000
-        assert!(
-            usd >= 50.0 && usd <= 1000.0,
-            "USD conversion should be reasonable"
-        );
+    #[test]
+    fn test_aggregate_rejects_outliers() {
+        let result = aggregate(
+            &quotes(&[("a", 150.0), ("b", 152.0), ("c", 151.0), ("d", 300.0)]),
+            5.0,
+        )
+        .expect("rate");
+        assert_eq!(result.rate, 151.0);
+        assert_eq!(result.sources, vec!["a", "b", "c"]);
+        assert_eq!(result.rejected, vec!["d"]);
     }
 
-    #[tokio::test]
-    async fn test_xmr_to_usd() {
-        let xmr = 2.5;
-        let usd = xmr_to_usd(xmr).await;
+    #[test]
+    fn test_aggregate_edge_cases() {
+        assert!(aggregate(&[], 5.0).is_none());
+
+        let single = aggregate(&quotes(&[("a", 150.0)]), 5.0).expect("rate");
+        assert_eq!(single.rate, 150.0);
 
-        // Should be between $125 and $2500
-        assert!(
-            usd >= 125.0 && usd <= 2500.0,
-            "USD conversion should be reasonable"
-        );
+        let even = aggregate(&quotes(&[("a", 150.0), ("b", 154.0)]), 5.0).expect("rate");
+        assert_eq!(even.rate, 152.0);
+
+        // Two sources that disagree cannot outvote each other
+        assert!(aggregate(&quotes(&[("a", 100.0), ("b", 200.0)]), 5.0).is_none());
+
+        let invalid =
+            aggregate(&quotes(&[("a", f64::NAN), ("b", -1.0), ("c", 150.0)]), 5.0).expect("rate");
+        assert_eq!(invalid.rate, 150.0);
+        assert_eq!(invalid.rejected, vec!["a", "b"]);
     }
 
     #[test]
-    fn test_cache_rate() {
-        clear_cache();
+    fn test_parse_coingecko() -> Result<()> {
+        let rates = CoinGeckoSource::parse(r#"{"monero":{"usd":155.43,"EUR":143.1}}"#)?;
+        assert_eq!(rates.get("usd"), Some(&155.43));
+        assert_eq!(rates.get("eur"), Some(&143.1));
+        assert!(CoinGeckoSource::parse(r#"{"error":"rate limited"}"#).is_err());
+        Ok(())
+    }
 
-        cache_rate(155.0);
+    #[test]
+    fn test_parse_kraken() -> Result<()> {
+        let body =
+            r#"{"error":[],"result":{"XXMRZUSD":{"a":["155.9","1","1.0"],"c":["155.81","0.2"]}}}"#;
+        assert_eq!(KrakenSource::parse(body)?, 155.81);
+        assert!(KrakenSource::parse(r#"{"error":["EQuery:Unknown asset pair"]}"#).is_err());
+        Ok(())
+    }
 
-        let cached = get_cached_rate();
-        assert!(cached.is_some(), "Rate should be cached");
-        assert_eq!(cached.unwrap(), 155.0, "Cached rate should match");
+    #[tokio::test]
+    async fn test_manual_source() -> Result<()> {
+        let source = ManualSource::new(None);
+        let currencies = vec!["usd".to_string()];
+        assert!(source.fetch(&currencies).await?.is_empty());
+
+        source.set_rate("USD", Some(160.0))?;
+        source.set_rate("eur", Some(150.0))?;
+        let rates = source.fetch(&currencies).await?;
+        assert_eq!(rates, SourceRates::from([("usd".to_string(), 160.0)]));
+
+        assert!(source.set_rate("usd", Some(0.0)).is_err());
+        source.set_rate("usd", None)?;
+        assert!(source.fetch(&currencies).await?.is_empty());
+        Ok(())
     }
 }
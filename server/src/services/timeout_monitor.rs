@@ -6,16 +6,29 @@
 
 use actix::Addr;
 use anyhow::{Context, Result};
+use monero_marketplace_common::clock::{SharedClock, SystemClock};
 use std::sync::Arc;
+use tokio::sync::Mutex;
 use tokio::time::interval;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
 use crate::config::TimeoutConfig;
-use crate::db::DbPool;
-use crate::models::escrow::Escrow;
+use crate::db::{
+    db_create_transaction, db_transition_escrow, db_transition_escrow_audited,
+    db_update_escrow_transaction_hash, DbPool,
+};
+use crate::models::audit_log::AuditEventType;
+use crate::models::escrow::{Escrow, EscrowStatus};
+use crate::models::escrow_event::{EscrowActor, EscrowEventRecord};
+use crate::models::transaction::NewTransaction;
+use crate::models::user::User;
 use crate::repositories::MultisigStateRepository;
+use crate::services::arbiter_assignment::ArbiterAssignmentService;
+use crate::services::escrow::validate_payout_address;
+use crate::wallet_manager::{WalletManager, PAYOUT_FEE_RESERVE};
 use crate::websocket::{NotifyUser, WebSocketServer, WsEvent};
+use monero_marketplace_common::types::TransferDestination;
 
 /// Timeout monitoring service
 ///
@@ -27,6 +40,9 @@ pub struct TimeoutMonitor {
     websocket: Addr<WebSocketServer>,
     config: TimeoutConfig,
     multisig_repo: Option<Arc<MultisigStateRepository>>,
+    wallet_manager: Option<Arc<Mutex<WalletManager>>>,
+    arbiter_assignment: Option<Arc<ArbiterAssignmentService>>,
+    clock: SharedClock,
 }
 
 impl TimeoutMonitor {
@@ -46,6 +62,9 @@ impl TimeoutMonitor {
             websocket,
             config,
             multisig_repo: None,
+            wallet_manager: None,
+            arbiter_assignment: None,
+            clock: SystemClock::shared(),
         }
     }
 
@@ -72,9 +91,38 @@ impl TimeoutMonitor {
             websocket,
             config,
             multisig_repo: Some(Arc::new(multisig_repo)),
+            wallet_manager: None,
+            arbiter_assignment: None,
+            clock: SystemClock::shared(),
         }
     }
 
+    /// Evaluate deadlines against `clock` instead of the system time
+    ///
+    /// Used by tests to drive expiry with a simulated chain.
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Co-sign refunds of partial deposits with `wallet_manager`
+    ///
+    /// Without it, a funding timeout on an escrow that received coins is
+    /// left expired (and retried on every poll) instead of cancelled.
+    pub fn with_wallet_manager(mut self, wallet_manager: Arc<Mutex<WalletManager>>) -> Self {
+        self.wallet_manager = Some(wallet_manager);
+        self
+    }
+
+    /// Hand timed-out disputes to another arbiter from the pool
+    ///
+    /// Without it, or once the escalation limit is reached, a dispute
+    /// timeout only notifies an admin.
+    pub fn with_arbiter_assignment(mut self, service: Arc<ArbiterAssignmentService>) -> Self {
+        self.arbiter_assignment = Some(service);
+        self
+    }
+
     /// Start monitoring in background
     ///
     /// This spawns a background task that periodically checks for:
@@ -90,22 +138,29 @@ impl TimeoutMonitor {
 
         loop {
             poll_timer.tick().await;
+            self.check_timeouts().await;
+        }
+    }
 
-            // Check for expired escrows first (highest priority)
-            if let Err(e) = self.check_expired_escrows().await {
-                error!("Error checking expired escrows: {}", e);
-            }
+    /// Run one pass of every timeout check
+    ///
+    /// Called on each tick of `start_monitoring`; tests call it directly
+    /// after advancing the clock. Errors are logged, not returned.
+    pub async fn check_timeouts(&self) {
+        // Check for expired escrows first (highest priority)
+        if let Err(e) = self.check_expired_escrows().await {
+            error!("Error checking expired escrows: {}", e);
+        }
 
-            // Check for escrows approaching expiration (send warnings)
-            if let Err(e) = self.check_expiring_escrows().await {
-                error!("Error checking expiring escrows: {}", e);
-            }
+        // Check for escrows approaching expiration (send warnings)
+        if let Err(e) = self.check_expiring_escrows().await {
+            error!("Error checking expiring escrows: {}", e);
+        }
 
-            // Check for stuck multisig setups (if persistence enabled)
-            if self.multisig_repo.is_some() {
-                if let Err(e) = self.check_stuck_multisig_setups().await {
-                    error!("Error checking stuck multisig setups: {}", e);
-                }
+        // Check for stuck multisig setups (if persistence enabled)
+        if self.multisig_repo.is_some() {
+            if let Err(e) = self.check_stuck_multisig_setups().await {
+                error!("Error checking stuck multisig setups: {}", e);
             }
         }
     }
@@ -114,14 +169,15 @@ impl TimeoutMonitor {
     ///
     /// Finds all escrows past their deadline and takes appropriate action:
     /// - "created" → Cancel (multisig setup incomplete)
-    /// - "funded" → Cancel (buyer never deposited funds)
+    /// - "funded"/"underfunded" → Refund any deposit to the buyer, then cancel
     /// - "releasing"/"refunding" → Alert admin (transaction stuck)
-    /// - "disputed" → Escalate (arbiter timeout)
+    /// - "disputed" → Reassign to another arbiter, else escalate (arbiter timeout)
     async fn check_expired_escrows(&self) -> Result<()> {
         let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let now = self.clock.now_naive();
 
         let expired_escrows = tokio::task::spawn_blocking(move || {
-            Escrow::find_expired(&mut conn)
+            Escrow::find_expired_at(&mut conn, now)
         })
         .await
         .context("Task join error")??;
@@ -144,17 +200,20 @@ impl TimeoutMonitor {
             );
 
             // Handle based on current status
-            match escrow.status.as_str() {
-                "created" => {
+            match escrow.get_status() {
+                Ok(EscrowStatus::Created) => {
                     self.handle_multisig_setup_timeout(escrow_id, escrow).await?;
                 }
-                "funded" => {
-                    self.handle_funding_timeout(escrow_id, escrow).await?;
+                Ok(EscrowStatus::Funded | EscrowStatus::Underfunded) => {
+                    // A failed refund must not hold up the other expired escrows
+                    if let Err(e) = self.handle_funding_timeout(escrow_id, escrow).await {
+                        error!("Funding timeout for escrow {} not handled: {:#}", escrow_id, e);
+                    }
                 }
-                "releasing" | "refunding" => {
+                Ok(EscrowStatus::Releasing | EscrowStatus::Refunding) => {
                     self.handle_transaction_timeout(escrow_id, escrow).await?;
                 }
-                "disputed" => {
+                Ok(EscrowStatus::Disputed) => {
                     self.handle_dispute_timeout(escrow_id, escrow).await?;
                 }
                 _ => {
@@ -173,9 +232,10 @@ impl TimeoutMonitor {
     async fn check_expiring_escrows(&self) -> Result<()> {
         let warning_threshold = self.config.warning_threshold_secs;
         let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let now = self.clock.now_naive();
 
         let expiring_escrows = tokio::task::spawn_blocking(move || {
-            Escrow::find_expiring_soon(&mut conn, warning_threshold as i64)
+            Escrow::find_expiring_soon_at(&mut conn, now, warning_threshold as i64)
         })
         .await
         .context("Task join error")??;
@@ -193,7 +253,7 @@ impl TimeoutMonitor {
                 .context("Failed to parse escrow_id")?;
 
             let expires_in_secs = escrow
-                .seconds_until_expiration()
+                .seconds_until_expiration_at(now)
                 .unwrap_or(0);
 
             info!(
@@ -222,7 +282,13 @@ impl TimeoutMonitor {
         let mut conn = self.db.get().context("Failed to get DB connection")?;
         let escrow_id_clone = escrow_id.to_string();
         tokio::task::spawn_blocking(move || {
-            Escrow::update_status(&mut conn, escrow_id_clone, "cancelled")
+            EscrowEventRecord::record_transition(
+                &mut conn,
+                &escrow_id_clone,
+                EscrowStatus::Cancelled,
+                &EscrowActor::System("timeout_monitor"),
+                Some("Multisig setup not completed within 1 hour"),
+            )
         })
         .await
         .context("Task join error")??;
@@ -231,42 +297,188 @@ impl TimeoutMonitor {
         self.websocket.do_send(WsEvent::EscrowAutoCancelled {
             escrow_id,
             reason: "Multisig setup not completed within 1 hour".to_string(),
-            cancelled_at_status: "created".to_string(),
+            cancelled_at_status: EscrowStatus::Created,
         });
 
         info!("Escrow {} auto-cancelled due to setup timeout", escrow_id);
         Ok(())
     }
 
-    /// Handle timeout for funding (status: "funded")
+    /// Handle timeout for funding (status: "funded" or "underfunded")
     ///
-    /// Action: Cancel the escrow (multisig ready but buyer never deposited)
-    async fn handle_funding_timeout(&self, escrow_id: Uuid, _escrow: Escrow) -> Result<()> {
+    /// Action: Cancel the escrow (multisig ready but buyer never deposited in full).
+    /// Coins that did land on the multisig address are first sent back to the
+    /// buyer's registered wallet; if that refund fails the escrow is not cancelled.
+    /// A refund broadcast on an earlier tick (escrow `transaction_hash` set) is
+    /// not sent again.
+    async fn handle_funding_timeout(&self, escrow_id: Uuid, escrow: Escrow) -> Result<()> {
+        let cancelled_at_status = escrow.get_status()?;
+
         info!(
             "Funding timeout for escrow {}: cancelling",
             escrow_id
         );
 
+        let mut reason = "Buyer did not fund escrow within 24 hours".to_string();
+        // Audit entry for a deposit refund, written with the cancellation
+        let mut refund_audit = None;
+        if let Some(tx_hash) = &escrow.transaction_hash {
+            info!(
+                "Deposit of expired escrow {} already refunded (tx {})",
+                escrow_id, tx_hash
+            );
+            reason = format!("{}; deposit refunded to buyer (tx {})", reason, tx_hash);
+            refund_audit = Some(serde_json::json!({ "tx_hash": tx_hash }));
+        } else if escrow.funded_amount > 0 {
+            match self.refund_partial_deposit(escrow_id, &escrow).await? {
+                Some((tx_hash, amount)) => {
+                    reason = format!(
+                        "{}; deposit of {} atomic units refunded to buyer (tx {})",
+                        reason, amount, tx_hash
+                    );
+                    refund_audit = Some(serde_json::json!({
+                        "amount": amount,
+                        "tx_hash": tx_hash,
+                    }));
+                }
+                None => {
+                    reason = format!("{}; deposit too small to cover the refund fee", reason);
+                }
+            }
+        }
+
         // Update status to cancelled
-        let mut conn = self.db.get().context("Failed to get DB connection")?;
-        let escrow_id_clone = escrow_id.to_string();
-        tokio::task::spawn_blocking(move || {
-            Escrow::update_status(&mut conn, escrow_id_clone, "cancelled")
-        })
-        .await
-        .context("Task join error")??;
+        let actor = EscrowActor::System("timeout_monitor");
+        match refund_audit {
+            Some(data) => {
+                db_transition_escrow_audited(
+                    &self.db,
+                    escrow_id,
+                    EscrowStatus::Cancelled,
+                    actor,
+                    Some(&reason),
+                    AuditEventType::Refund,
+                    data,
+                )
+                .await?;
+            }
+            None => {
+                db_transition_escrow(
+                    &self.db,
+                    escrow_id,
+                    EscrowStatus::Cancelled,
+                    actor,
+                    Some(&reason),
+                )
+                .await?;
+            }
+        }
 
         // Notify all parties
         self.websocket.do_send(WsEvent::EscrowAutoCancelled {
             escrow_id,
-            reason: "Buyer did not fund escrow within 24 hours".to_string(),
-            cancelled_at_status: "funded".to_string(),
+            reason,
+            cancelled_at_status,
         });
 
         info!("Escrow {} auto-cancelled due to funding timeout", escrow_id);
         Ok(())
     }
 
+    /// Send the deposits of an expired escrow back to the buyer
+    ///
+    /// The refund goes to the buyer's registered `wallet_address`, created and
+    /// co-signed with the vendor and arbiter keys, and is recorded in
+    /// `transactions`. It waits until every deposit is unlocked (`funded_amount`
+    /// also counts deposits still in the pool), then sends the unlocked balance
+    /// minus the fee reserve. Returns the refund tx hash and amount, or None
+    /// when the balance does not cover the fee.
+    async fn refund_partial_deposit(
+        &self,
+        escrow_id: Uuid,
+        escrow: &Escrow,
+    ) -> Result<Option<(String, u64)>> {
+        let wallet_manager = self.wallet_manager.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Escrow {} holds {} atomic units but no wallet manager is configured to refund them",
+                escrow_id,
+                escrow.funded_amount
+            )
+        })?;
+
+        let buyer_id = escrow.buyer_id.clone();
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let buyer = tokio::task::spawn_blocking(move || User::find_by_id(&mut conn, buyer_id))
+            .await
+            .context("Task join error")??;
+        let buyer_address = buyer.wallet_address.ok_or_else(|| {
+            anyhow::anyhow!(
+                "Escrow {} holds {} atomic units but the buyer has no registered wallet address",
+                escrow_id,
+                escrow.funded_amount
+            )
+        })?;
+        validate_payout_address(&buyer_address)?;
+
+        let mut wallet_manager = wallet_manager.lock().await;
+        let (unlocked_balance, total_balance) = wallet_manager
+            .sync_multisig_wallets(escrow_id)
+            .await
+            .context("Failed to sync escrow wallets")?;
+        if total_balance > unlocked_balance {
+            anyhow::bail!(
+                "Escrow {} has {} of {} atomic units still locked, refund postponed",
+                escrow_id,
+                total_balance - unlocked_balance,
+                total_balance
+            );
+        }
+        let amount = unlocked_balance.saturating_sub(PAYOUT_FEE_RESERVE);
+        if amount == 0 {
+            warn!(
+                "Expired escrow {} holds {} atomic units, not enough for the refund fee",
+                escrow_id, unlocked_balance
+            );
+            return Ok(None);
+        }
+
+        info!(
+            "Refunding deposit of {} atomic units from expired escrow {} to buyer address {}",
+            amount,
+            escrow_id,
+            &buyer_address[..10]
+        );
+
+        let destinations = vec![TransferDestination {
+            address: buyer_address,
+            amount,
+        }];
+        let tx_hash = wallet_manager.refund_funds(escrow_id, destinations).await?;
+        drop(wallet_manager);
+
+        db_update_escrow_transaction_hash(&self.db, escrow_id, &tx_hash).await?;
+        db_create_transaction(
+            &self.db,
+            NewTransaction {
+                id: Uuid::new_v4().to_string(),
+                escrow_id: escrow_id.to_string(),
+                tx_hash: Some(tx_hash.clone()),
+                amount_xmr: amount as i64,
+                confirmations: 0,
+                buyer_amount_xmr: None,
+                vendor_amount_xmr: None,
+            },
+        )
+        .await
+        .context("Failed to record funding timeout refund")?;
+
+        info!(
+            "Funding timeout refund for escrow {} submitted: tx={}",
+            escrow_id, tx_hash
+        );
+        Ok(Some((tx_hash, amount)))
+    }
+
     /// Handle timeout for transaction confirmation (status: "releasing"/"refunding")
     ///
     /// Action: Alert admin (transaction may be stuck in mempool)
@@ -283,7 +495,7 @@ impl TimeoutMonitor {
         );
 
         // Calculate hours pending
-        let secs_since_activity = (chrono::Utc::now().naive_utc()
+        let secs_since_activity = (self.clock.now_naive()
             - escrow.last_activity_at)
             .num_seconds();
         let hours_pending = (secs_since_activity / 3600) as u64;
@@ -309,7 +521,8 @@ impl TimeoutMonitor {
 
     /// Handle timeout for dispute resolution (status: "disputed")
     ///
-    /// Action: Escalate to admin (arbiter failed to resolve in time)
+    /// Action: Reassign to another arbiter from the pool (with a fresh deadline),
+    /// or escalate to admin when none is eligible or the escalation limit is reached
     /// Optionally: Auto-refund to buyer after escalation
     async fn handle_dispute_timeout(&self, escrow_id: Uuid, escrow: Escrow) -> Result<()> {
         let arbiter_id = escrow
@@ -318,7 +531,7 @@ impl TimeoutMonitor {
             .context("Failed to parse arbiter_id")?;
 
         // Calculate days in dispute
-        let secs_in_dispute = (chrono::Utc::now().naive_utc()
+        let secs_in_dispute = (self.clock.now_naive()
             - escrow.last_activity_at)
             .num_seconds();
         let days_in_dispute = (secs_in_dispute / 86400) as u64;
@@ -328,6 +541,41 @@ impl TimeoutMonitor {
             escrow_id, arbiter_id, days_in_dispute
         );
 
+        if let Some(service) = &self.arbiter_assignment {
+            match service.escalate(escrow_id).await {
+                Ok(reassignment) => {
+                    // The new arbiter gets a full dispute window
+                    let mut conn = self.db.get().context("Failed to get DB connection")?;
+                    let new_expires_at = self
+                        .config
+                        .deadline_for_status(EscrowStatus::Disputed, &*self.clock);
+                    let escrow_id_str = escrow_id.to_string();
+                    tokio::task::spawn_blocking(move || {
+                        Escrow::update_expiration(&mut conn, escrow_id_str, new_expires_at)
+                    })
+                    .await
+                    .context("Task join error")??;
+
+                    self.websocket.do_send(WsEvent::DisputeEscalated {
+                        escrow_id,
+                        arbiter_id,
+                        days_in_dispute,
+                        action_taken: format!(
+                            "Reassigned to arbiter {}",
+                            reassignment.escrow.arbiter_id
+                        ),
+                    });
+                    return Ok(());
+                }
+                Err(e) => {
+                    warn!(
+                        "Dispute {} not reassigned, escalating to admin: {:#}",
+                        escrow_id, e
+                    );
+                }
+            }
+        }
+
         // Send escalation notification
         self.websocket.do_send(WsEvent::DisputeEscalated {
             escrow_id,
@@ -354,11 +602,16 @@ impl TimeoutMonitor {
         escrow: &Escrow,
         expires_in_secs: u64,
     ) -> Result<()> {
-        let action_required = match escrow.status.as_str() {
-            "created" => "Complete multisig setup".to_string(),
-            "funded" => "Buyer: deposit funds to escrow address".to_string(),
-            "releasing" | "refunding" => "Wait for blockchain confirmation".to_string(),
-            "disputed" => "Arbiter: resolve dispute".to_string(),
+        let action_required = match escrow.get_status() {
+            Ok(EscrowStatus::Created) => "Complete multisig setup".to_string(),
+            Ok(EscrowStatus::Funded) => "Buyer: deposit funds to escrow address".to_string(),
+            Ok(EscrowStatus::Underfunded) => {
+                "Buyer: top up the partial deposit to the escrow address".to_string()
+            }
+            Ok(EscrowStatus::Releasing | EscrowStatus::Refunding) => {
+                "Wait for blockchain confirmation".to_string()
+            }
+            Ok(EscrowStatus::Disputed) => "Arbiter: resolve dispute".to_string(),
             _ => "No action required".to_string(),
         };
 
@@ -443,7 +696,7 @@ impl TimeoutMonitor {
             if let Some(escrow) = escrow_result {
                 match repo.load_snapshot(&escrow_id_str) {
                     Ok(Some(snapshot)) => {
-                        let minutes_stuck = (chrono::Utc::now().naive_utc().and_utc().timestamp()
+                        let minutes_stuck = (self.clock.now().timestamp()
                             - escrow.multisig_updated_at as i64) / 60;
 
                         warn!(
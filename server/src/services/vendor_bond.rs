@@ -0,0 +1,542 @@
+//! Vendor bond (security deposit) service
+//!
+//! A bond gets its own 2-of-3 multisig wallet, set up through the same
+//! `WalletManager` calls as an escrow. The bond UUID plays the escrow id and
+//! the three roles map to: buyer → marketplace, vendor → vendor, arbiter →
+//! arbiter, so payouts are signed by marketplace + arbiter exactly like
+//! `release_funds` for an escrow.
+//!
+//! # Lifecycle
+//! ```text
+//! created → awaiting_funding → active → released (vendor retires)
+//!                                   ↘ slashed   (nothing left after slashes)
+//! created → cancelled (multisig setup failed)
+//! ```
+
+use actix::Addr;
+use anyhow::{Context, Result};
+use diesel::prelude::*;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::config::BondPolicy;
+use crate::db::{db_load_escrow, DbPool};
+use crate::models::audit_log::{AuditEntry, AuditEventType};
+use crate::models::escrow::EscrowStatus;
+use crate::models::escrow_event::EscrowActor;
+use crate::models::listing::ListingStatus;
+use crate::models::user::User;
+use crate::models::vendor_bond::{NewVendorBond, NewVendorBondSlash, VendorBond, VendorBondStatus};
+use crate::schema::{listings, orders};
+use crate::services::escrow::validate_payout_address;
+use crate::wallet_manager::WalletManager;
+use crate::websocket::{NotifyUser, WebSocketServer, WsEvent};
+use monero_marketplace_common::types::TransferDestination;
+
+/// Escrow statuses in which the arbiter may slash the vendor's bond
+const SLASHABLE_ESCROW_STATUSES: [EscrowStatus; 4] = [
+    EscrowStatus::Disputed,
+    EscrowStatus::ResolvedBuyer,
+    EscrowStatus::ResolvedSplit,
+    EscrowStatus::Refunding,
+];
+
+/// Order statuses that block a vendor from retiring
+const OPEN_ORDER_STATUSES: [&str; 4] = ["pending", "funded", "shipped", "disputed"];
+
+/// Manages vendor bonds: multisig setup, funding, slashing and release
+pub struct VendorBondService {
+    /// Monero wallet manager (shared with the escrow orchestrator)
+    wallet_manager: Arc<Mutex<WalletManager>>,
+    /// Database connection pool
+    db: DbPool,
+    /// WebSocket server actor address for vendor notifications
+    websocket: Addr<WebSocketServer>,
+    /// Bond amounts and the listing limits they unlock
+    policy: BondPolicy,
+}
+
+impl VendorBondService {
+    /// Create a new VendorBondService
+    pub fn new(
+        wallet_manager: Arc<Mutex<WalletManager>>,
+        db: DbPool,
+        websocket: Addr<WebSocketServer>,
+        policy: BondPolicy,
+    ) -> Self {
+        Self {
+            wallet_manager,
+            db,
+            websocket,
+            policy,
+        }
+    }
+
+    /// Bond policy in use
+    pub fn policy(&self) -> &BondPolicy {
+        &self.policy
+    }
+
+    /// Open a bond for `vendor_id` and return it with its multisig address
+    ///
+    /// # Errors
+    /// - Amount below the policy minimum
+    /// - Vendor already has an open bond
+    /// - No arbiter available, or multisig setup failed (bond is cancelled)
+    pub async fn open_bond(&self, vendor_id: Uuid, amount_atomic: i64) -> Result<VendorBond> {
+        let minimum = self
+            .policy
+            .minimum_bond()
+            .ok_or_else(|| anyhow::anyhow!("Vendor bonds are disabled (no bond tiers configured)"))?;
+        if amount_atomic < minimum {
+            return Err(anyhow::anyhow!(
+                "Bond amount {} is below the minimum of {} atomic units",
+                amount_atomic,
+                minimum
+            ));
+        }
+
+        let bond_id = Uuid::new_v4();
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let bond = tokio::task::spawn_blocking(move || -> Result<VendorBond> {
+            let vendor = User::find_by_id(&mut conn, vendor_id.to_string())?;
+            if vendor.role != "vendor" {
+                return Err(anyhow::anyhow!("Only vendors can post a bond"));
+            }
+            if VendorBond::find_open_by_vendor(&mut conn, &vendor.id)?.is_some() {
+                return Err(anyhow::anyhow!("Vendor already has an open bond"));
+            }
+
+            let arbiter = User::find_by_role(&mut conn, "arbiter")?
+                .into_iter()
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("No arbiters available in the system"))?;
+
+            VendorBond::create(
+                &mut conn,
+                NewVendorBond {
+                    id: bond_id.to_string(),
+                    vendor_id: vendor.id,
+                    arbiter_id: arbiter.id,
+                    amount: amount_atomic,
+                    status: VendorBondStatus::Created.as_str().to_string(),
+                },
+            )
+        })
+        .await
+        .context("Task join error")??;
+
+        info!(
+            "🔄 Vendor bond {} created for vendor {} ({} atomic units)",
+            bond_id, vendor_id, amount_atomic
+        );
+
+        let multisig_address = match self.setup_bond_multisig(bond_id).await {
+            Ok(address) => address,
+            Err(e) => {
+                error!("❌ Multisig setup failed for vendor bond {}: {}", bond_id, e);
+                self.set_status(bond_id, VendorBondStatus::Cancelled).await?;
+                return Err(e.context("Failed to setup vendor bond multisig"));
+            }
+        };
+
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let bond = tokio::task::spawn_blocking(move || {
+            VendorBond::set_multisig_address(&mut conn, &bond.id, &multisig_address)?;
+            VendorBond::find_by_id(&mut conn, &bond.id)
+        })
+        .await
+        .context("Task join error")??;
+
+        self.notify_status(&bond, VendorBondStatus::AwaitingFunding);
+        info!("✅ Vendor bond {} awaiting funding", bond.id);
+
+        Ok(bond)
+    }
+
+    /// Check the bond wallet and activate the bond once the deposit is unlocked
+    ///
+    /// Returns the bond (unchanged unless it just became active).
+    pub async fn refresh_funding(&self, bond_id: Uuid) -> Result<VendorBond> {
+        let bond = self.load_bond(bond_id).await?;
+        if bond.status != VendorBondStatus::AwaitingFunding.as_str() {
+            return Ok(bond);
+        }
+
+        let mut wallet_manager = self.wallet_manager.lock().await;
+        // Same order as get_balance(): (unlocked, total)
+        let (unlocked_balance, _total_balance) = wallet_manager
+            .sync_multisig_wallets(bond_id)
+            .await
+            .context("Failed to sync vendor bond wallets")?;
+        drop(wallet_manager);
+
+        if (unlocked_balance as i64) < bond.amount {
+            info!(
+                "Vendor bond {} not funded yet: {}/{} atomic units unlocked",
+                bond_id, unlocked_balance, bond.amount
+            );
+            return Ok(bond);
+        }
+
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let bond = tokio::task::spawn_blocking(move || {
+            VendorBond::mark_funded(&mut conn, &bond.id)?;
+            VendorBond::find_by_id(&mut conn, &bond.id)
+        })
+        .await
+        .context("Task join error")??;
+
+        self.notify_status(&bond, VendorBondStatus::Active);
+        info!("✅ Vendor bond {} funded and active", bond.id);
+
+        Ok(bond)
+    }
+
+    /// Slash part of a vendor's bond into the payout of a disputed escrow
+    ///
+    /// The assigned arbiter of the escrow sends `amount_atomic` from the
+    /// vendor's bond wallet to the escrow buyer's registered wallet address.
+    /// The slashes of one escrow add up to at most the escrow amount.
+    /// Returns the transaction hash.
+    ///
+    /// # Errors
+    /// - Requester is not the escrow's arbiter, or escrow not in a dispute
+    /// - Buyer has no registered wallet address
+    /// - Vendor has no active bond, or amount exceeds the remaining bond or
+    ///   what is left to slash for the escrow
+    pub async fn slash_bond(
+        &self,
+        escrow_id: Uuid,
+        arbiter_id: Uuid,
+        amount_atomic: i64,
+        reason: String,
+    ) -> Result<String> {
+        let escrow = db_load_escrow(&self.db, escrow_id).await?;
+
+        if arbiter_id.to_string() != escrow.arbiter_id {
+            return Err(anyhow::anyhow!("Only assigned arbiter can slash a vendor bond"));
+        }
+        if !SLASHABLE_ESCROW_STATUSES.contains(&escrow.get_status()?) {
+            return Err(anyhow::anyhow!(
+                "Escrow not in a dispute (current: {})",
+                escrow.status
+            ));
+        }
+
+        // Held until the slash is recorded, so two slashes of the same
+        // escrow cannot both pass the per-escrow cap
+        let mut wallet_manager = self.wallet_manager.lock().await;
+
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let (buyer_id, vendor_id) = (escrow.buyer_id.clone(), escrow.vendor_id.clone());
+        let escrow_key = escrow.id.clone();
+        let (buyer, bond, slashable) = tokio::task::spawn_blocking(move || -> Result<_> {
+            let buyer = User::find_by_id(&mut conn, buyer_id)?;
+            let bond = VendorBond::find_open_by_vendor(&mut conn, &vendor_id)?;
+            let slashable = VendorBond::slashable_for_escrow(&mut conn, &escrow_key)?;
+            Ok((buyer, bond, slashable))
+        })
+        .await
+        .context("Task join error")??;
+
+        let buyer_address = buyer.wallet_address.ok_or_else(|| {
+            anyhow::anyhow!("Buyer of escrow {} has no registered wallet address", escrow.id)
+        })?;
+        validate_payout_address(&buyer_address)?;
+
+        let bond = bond
+            .filter(|bond| bond.status == VendorBondStatus::Active.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Vendor has no active bond"))?;
+
+        if amount_atomic <= 0 || amount_atomic > bond.remaining() {
+            return Err(anyhow::anyhow!(
+                "Invalid slash amount {} (remaining bond: {})",
+                amount_atomic,
+                bond.remaining()
+            ));
+        }
+        if amount_atomic > slashable {
+            return Err(anyhow::anyhow!(
+                "Invalid slash amount {} (left to slash for this escrow: {})",
+                amount_atomic,
+                slashable
+            ));
+        }
+
+        let bond_id = bond.id.parse::<Uuid>().context("Invalid vendor bond id")?;
+        let destinations = vec![TransferDestination {
+            address: buyer_address,
+            amount: amount_atomic as u64,
+        }];
+
+        let tx_hash = wallet_manager.release_funds(bond_id, destinations).await?;
+
+        info!(
+            "⚖️ Vendor bond {} slashed by {} atomic units for escrow {}: tx_hash={}",
+            bond_id, amount_atomic, escrow_id, tx_hash
+        );
+
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let audit_data = serde_json::json!({
+            "bond_id": bond.id,
+            "escrow_id": escrow.id,
+            "amount": amount_atomic,
+            "tx_hash": tx_hash,
+        });
+        let new_slash = NewVendorBondSlash {
+            id: Uuid::new_v4().to_string(),
+            bond_id: bond.id.clone(),
+            escrow_id: escrow.id.clone(),
+            arbiter_id: arbiter_id.to_string(),
+            amount: amount_atomic,
+            tx_hash: tx_hash.clone(),
+            reason,
+        };
+        let bond = tokio::task::spawn_blocking(move || {
+            // BEGIN IMMEDIATE: takes the audit chain head lock up front
+            conn.immediate_transaction::<_, anyhow::Error, _>(|conn| {
+                let bond = VendorBond::record_slash(conn, new_slash)?;
+                AuditEntry::append(
+                    conn,
+                    AuditEventType::BondSlash,
+                    &bond.id,
+                    &EscrowActor::user(arbiter_id),
+                    &audit_data,
+                )?;
+                Ok(bond)
+            })
+        })
+        .await
+        .context("Task join error")?
+        .context("Failed to record vendor bond slash")?;
+        drop(wallet_manager);
+
+        self.notify_vendor(
+            &bond,
+            WsEvent::VendorBondSlashed {
+                bond_id,
+                escrow_id,
+                amount: amount_atomic,
+                tx_hash: tx_hash.clone(),
+            },
+        );
+        if bond.status == VendorBondStatus::Slashed.as_str() {
+            self.notify_status(&bond, VendorBondStatus::Slashed);
+        }
+
+        Ok(tx_hash)
+    }
+
+    /// Retire a vendor: deactivate listings and pay back the remaining bond
+    ///
+    /// Listings are deactivated before the payout so nothing sells during
+    /// it, and reactivated if the payout fails. Returns the release
+    /// transaction hash.
+    ///
+    /// # Errors
+    /// - Vendor has no active bond
+    /// - Vendor still has open orders (pending, funded, shipped, disputed)
+    pub async fn retire_vendor(&self, vendor_id: Uuid, payout_address: String) -> Result<String> {
+        validate_payout_address(&payout_address)?;
+
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let (bond, deactivated) = tokio::task::spawn_blocking(move || -> Result<_> {
+            let vendor_id = vendor_id.to_string();
+            let bond = VendorBond::find_open_by_vendor(&mut conn, &vendor_id)?
+                .filter(|bond| bond.status == VendorBondStatus::Active.as_str())
+                .ok_or_else(|| anyhow::anyhow!("Vendor has no active bond"))?;
+
+            let open_orders: i64 = orders::table
+                .filter(orders::vendor_id.eq(&vendor_id))
+                .filter(orders::status.eq_any(OPEN_ORDER_STATUSES))
+                .count()
+                .get_result(&mut conn)
+                .context("Failed to count open orders")?;
+            if open_orders > 0 {
+                return Err(anyhow::anyhow!(
+                    "Vendor still has {} open order(s); complete them before retiring",
+                    open_orders
+                ));
+            }
+
+            let deactivated: Vec<String> = listings::table
+                .filter(listings::vendor_id.eq(&vendor_id))
+                .filter(listings::status.eq(ListingStatus::Active.as_str()))
+                .select(listings::id)
+                .load(&mut conn)
+                .context("Failed to load vendor listings")?;
+            set_listings_status(&mut conn, &deactivated, ListingStatus::Inactive)
+                .context("Failed to deactivate vendor listings")?;
+            info!(
+                "Vendor {} retiring: {} listing(s) deactivated",
+                vendor_id,
+                deactivated.len()
+            );
+
+            Ok((bond, deactivated))
+        })
+        .await
+        .context("Task join error")??;
+
+        let bond_id = bond.id.parse::<Uuid>().context("Invalid vendor bond id")?;
+        let mut audit_data = serde_json::json!({
+            "amount": bond.remaining(),
+            "payout_address": payout_address,
+            "deactivated_listings": deactivated.len(),
+        });
+        let destinations = vec![TransferDestination {
+            address: payout_address,
+            amount: bond.remaining() as u64,
+        }];
+
+        let mut wallet_manager = self.wallet_manager.lock().await;
+        let release_result = wallet_manager.release_funds(bond_id, destinations).await;
+        drop(wallet_manager);
+
+        let tx_hash = match release_result {
+            Ok(tx_hash) => tx_hash,
+            Err(e) => {
+                error!(
+                    "❌ Vendor bond {} release failed, reactivating {} listing(s) of vendor {}: {}",
+                    bond_id,
+                    deactivated.len(),
+                    vendor_id,
+                    e
+                );
+                let mut conn = self.db.get().context("Failed to get DB connection")?;
+                tokio::task::spawn_blocking(move || {
+                    set_listings_status(&mut conn, &deactivated, ListingStatus::Active)
+                })
+                .await
+                .context("Task join error")?
+                .context("Failed to reactivate vendor listings after a failed release")?;
+                return Err(anyhow::Error::from(e).context("Failed to release vendor bond"));
+            }
+        };
+
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        let release_hash = tx_hash.clone();
+        audit_data["tx_hash"] = serde_json::json!(tx_hash);
+        let bond = tokio::task::spawn_blocking(move || {
+            // BEGIN IMMEDIATE: takes the audit chain head lock up front
+            conn.immediate_transaction::<_, anyhow::Error, _>(|conn| {
+                VendorBond::mark_released(conn, &bond.id, &release_hash)?;
+                AuditEntry::append(
+                    conn,
+                    AuditEventType::BondRelease,
+                    &bond.id,
+                    &EscrowActor::user(vendor_id),
+                    &audit_data,
+                )?;
+                VendorBond::find_by_id(conn, &bond.id)
+            })
+        })
+        .await
+        .context("Task join error")??;
+
+        self.notify_status(&bond, VendorBondStatus::Released);
+        info!(
+            "✅ Vendor bond {} released to retiring vendor {}: tx_hash={}",
+            bond_id, vendor_id, tx_hash
+        );
+
+        Ok(tx_hash)
+    }
+
+    /// Custodial 2-of-3 setup for the bond wallet, returns the multisig address
+    async fn setup_bond_multisig(&self, bond_id: Uuid) -> Result<String> {
+        let bond_str = bond_id.to_string();
+        let mut wallet_manager = self.wallet_manager.lock().await;
+
+        let mut wallet_ids = Vec::with_capacity(3);
+        for role in ["buyer", "vendor", "arbiter"] {
+            #[allow(deprecated)]
+            let wallet_id = wallet_manager
+                .create_temporary_wallet(bond_id, role)
+                .await
+                .context(format!("Failed to create {} wallet for vendor bond", role))?;
+            wallet_ids.push(wallet_id);
+        }
+
+        let mut infos = Vec::with_capacity(3);
+        for wallet_id in wallet_ids {
+            infos.push(
+                wallet_manager
+                    .make_multisig(&bond_str, wallet_id, vec![])
+                    .await
+                    .context("Failed to prepare multisig for vendor bond")?,
+            );
+        }
+
+        wallet_manager
+            .exchange_multisig_info(bond_id, infos)
+            .await
+            .context("Failed to exchange multisig info")?;
+
+        let multisig_address = wallet_manager
+            .finalize_multisig(bond_id)
+            .await
+            .context("Failed to finalize multisig")?;
+
+        if let Some(pool) = wallet_manager.wallet_pool() {
+            pool.register_escrow_wallets(bond_id).await;
+        }
+
+        Ok(multisig_address)
+    }
+
+    async fn load_bond(&self, bond_id: Uuid) -> Result<VendorBond> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        tokio::task::spawn_blocking(move || VendorBond::find_by_id(&mut conn, &bond_id.to_string()))
+            .await
+            .context("Task join error")?
+    }
+
+    async fn set_status(&self, bond_id: Uuid, status: VendorBondStatus) -> Result<()> {
+        let mut conn = self.db.get().context("Failed to get DB connection")?;
+        tokio::task::spawn_blocking(move || {
+            VendorBond::update_status(&mut conn, &bond_id.to_string(), status)
+        })
+        .await
+        .context("Task join error")?
+    }
+
+    fn notify_status(&self, bond: &VendorBond, status: VendorBondStatus) {
+        let Ok(bond_id) = bond.id.parse::<Uuid>() else {
+            warn!("Vendor bond {} has an invalid id, skipping notification", bond.id);
+            return;
+        };
+        self.notify_vendor(
+            bond,
+            WsEvent::VendorBondStatusChanged {
+                bond_id,
+                new_status: status.as_str().to_string(),
+            },
+        );
+    }
+
+    fn notify_vendor(&self, bond: &VendorBond, event: WsEvent) {
+        match bond.vendor_id.parse::<Uuid>() {
+            Ok(user_id) => self.websocket.do_send(NotifyUser { user_id, event }),
+            Err(_) => warn!("Vendor bond {} has an invalid vendor id", bond.id),
+        }
+    }
+}
+
+/// Set the status of the given listings (the retiring vendor's)
+fn set_listings_status(
+    conn: &mut SqliteConnection,
+    listing_ids: &[String],
+    status: ListingStatus,
+) -> Result<usize> {
+    diesel::update(listings::table.filter(listings::id.eq_any(listing_ids)))
+        .set((
+            listings::status.eq(status.as_str()),
+            listings::updated_at.eq(diesel::dsl::now),
+        ))
+        .execute(conn)
+        .context("Failed to update listing status")
+}
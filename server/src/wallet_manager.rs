@@ -3,7 +3,7 @@
 use anyhow::Result;
 use monero_marketplace_common::{
     error::{Error as CommonError, MoneroError},
-    types::{MoneroConfig, MultisigInfo},
+    types::{MoneroConfig, MultisigInfo, TxProofCheck},
 };
 use monero_marketplace_wallet::MoneroClient;
 use std::collections::HashMap;
@@ -24,6 +24,10 @@ use crate::wallet_pool::WalletPool;
 use once_cell::sync::Lazy;
 static WALLET_CREATION_LOCK: Lazy<TokioMutex<()>> = Lazy::new(|| TokioMutex::new(()));
 
+/// Part of the unlocked balance kept for the network fee of a payout
+/// (0.0001 XMR = 100000000 atomic units)
+pub const PAYOUT_FEE_RESERVE: u64 = 100_000_000;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum WalletRole {
     Buyer,
@@ -2072,35 +2076,9 @@ impl WalletManager {
         info!("✅ Multisig outputs synchronized");
 
         // Get wallet balance and adjust amount for fees
-        let buyer_wallet = self.wallets.get(&buyer_id)
-            .ok_or(WalletManagerError::WalletNotFound(buyer_id))?;
-        let (total_balance, unlocked_balance) = buyer_wallet.rpc_client.rpc().get_balance().await
-            .map_err(|e| WalletManagerError::RpcError(convert_monero_error(e)))?;
-
-        info!("Wallet balance: total={}, unlocked={}", total_balance, unlocked_balance);
-
-        // Reserve space for transaction fees (0.0001 XMR = 100000000 atomic units)
-        const FEE_RESERVE: u64 = 100_000_000;
-
-        // Adjust destinations to account for fees
         let mut adjusted_destinations = destinations.clone();
-        if let Some(dest) = adjusted_destinations.first_mut() {
-            if unlocked_balance > FEE_RESERVE {
-                let max_sendable = unlocked_balance - FEE_RESERVE;
-                if dest.amount > max_sendable {
-                    info!(
-                        "Adjusting amount from {} to {} to reserve {} for fees",
-                        dest.amount, max_sendable, FEE_RESERVE
-                    );
-                    dest.amount = max_sendable;
-                }
-            } else {
-                return Err(WalletManagerError::InvalidState {
-                    expected: format!("balance > {}", FEE_RESERVE),
-                    actual: format!("balance = {}", unlocked_balance),
-                });
-            }
-        }
+        self.fit_to_unlocked_balance(buyer_id, &mut adjusted_destinations)
+            .await?;
 
         // 3. Create unsigned transaction using buyer wallet
         info!("Creating unsigned transaction with buyer wallet");
@@ -2219,6 +2197,11 @@ impl WalletManager {
             .await?;
         info!("✅ Reopened arbiter wallet: {}", arbiter_id);
 
+        // Adjust amounts to the unlocked balance, keeping the fee reserve
+        let mut adjusted_destinations = destinations.clone();
+        self.fit_to_unlocked_balance(vendor_id, &mut adjusted_destinations)
+            .await?;
+
         // Create unsigned transaction using vendor wallet
         info!("Creating unsigned refund transaction with vendor wallet");
         let vendor_wallet = self
@@ -2229,7 +2212,7 @@ impl WalletManager {
         let create_result = vendor_wallet
             .rpc_client
             .rpc()
-            .transfer_multisig(destinations.clone())
+            .transfer_multisig(adjusted_destinations)
             .await
             .map_err(|e| WalletManagerError::RpcError(convert_monero_error(e)))?;
 
@@ -2309,6 +2292,42 @@ impl WalletManager {
         Ok(tx_hash)
     }
 
+    /// Fit payout destinations to the unlocked balance of a multisig wallet
+    ///
+    /// Keeps [`PAYOUT_FEE_RESERVE`] for the network fee, see
+    /// [`fit_destinations_to_balance`]. Fails if the unlocked balance does
+    /// not even cover the fee reserve.
+    async fn fit_to_unlocked_balance(
+        &self,
+        wallet_id: Uuid,
+        destinations: &mut Vec<monero_marketplace_common::types::TransferDestination>,
+    ) -> Result<(), WalletManagerError> {
+        let wallet = self
+            .wallets
+            .get(&wallet_id)
+            .ok_or(WalletManagerError::WalletNotFound(wallet_id))?;
+        let (unlocked_balance, total_balance) = wallet
+            .rpc_client
+            .rpc()
+            .get_balance()
+            .await
+            .map_err(|e| WalletManagerError::RpcError(convert_monero_error(e)))?;
+
+        info!("Wallet balance: total={}, unlocked={}", total_balance, unlocked_balance);
+
+        if destinations.is_empty() {
+            return Ok(());
+        }
+        if unlocked_balance <= PAYOUT_FEE_RESERVE {
+            return Err(WalletManagerError::InvalidState {
+                expected: format!("balance > {}", PAYOUT_FEE_RESERVE),
+                actual: format!("balance = {}", unlocked_balance),
+            });
+        }
+        fit_destinations_to_balance(destinations, unlocked_balance - PAYOUT_FEE_RESERVE);
+        Ok(())
+    }
+
     /// Find two wallets by their roles
     fn find_wallets_for_escrow(
         &self,
@@ -2430,6 +2449,73 @@ impl WalletManager {
         })
     }
 
+    /// Generate a proof that a transaction paid `address`
+    ///
+    /// For an escrow wallet and its multisig address this is an `InProofV2`:
+    /// every participant holds the shared view key.
+    ///
+    /// # Errors
+    /// - WalletNotFound - Wallet ID not found
+    /// - RpcError - Transaction unknown to the wallet, RPC unreachable, etc.
+    pub async fn get_tx_proof(
+        &self,
+        wallet_id: Uuid,
+        tx_hash: &str,
+        address: &str,
+        message: &str,
+    ) -> Result<String, WalletManagerError> {
+        let wallet = self
+            .wallets
+            .get(&wallet_id)
+            .ok_or(WalletManagerError::WalletNotFound(wallet_id))?;
+
+        wallet
+            .rpc_client
+            .rpc()
+            .get_tx_proof(tx_hash, address, message)
+            .await
+            .map_err(|e| WalletManagerError::RpcError(convert_monero_error(e)))
+    }
+
+    /// Check a proof that a transaction paid `address`
+    ///
+    /// Any open wallet can check a proof; the wallet's daemon supplies the
+    /// transaction and its confirmations.
+    ///
+    /// # Errors
+    /// - WalletNotFound - Wallet ID not found
+    /// - RpcError - Malformed proof, unknown transaction, RPC unreachable, etc.
+    pub async fn check_tx_proof(
+        &self,
+        wallet_id: Uuid,
+        tx_hash: &str,
+        address: &str,
+        message: &str,
+        signature: &str,
+    ) -> Result<TxProofCheck, WalletManagerError> {
+        let wallet = self
+            .wallets
+            .get(&wallet_id)
+            .ok_or(WalletManagerError::WalletNotFound(wallet_id))?;
+
+        let check = wallet
+            .rpc_client
+            .rpc()
+            .check_tx_proof(tx_hash, address, message, signature)
+            .await
+            .map_err(|e| WalletManagerError::RpcError(convert_monero_error(e)))?;
+
+        info!(
+            "Transaction proof for {}: good={}, received={} atomic units, confirmations={}",
+            &tx_hash[..10.min(tx_hash.len())],
+            check.good,
+            check.received,
+            check.confirmations
+        );
+
+        Ok(check)
+    }
+
     /// Recover active escrows from database after server restart
     ///
     /// Queries the repository for all active escrows and reconstructs
@@ -2698,6 +2784,41 @@ pub struct TransferInfo {
     pub block_height: Option<u64>,
 }
 
+/// Shrink destinations so their total fits in `max_sendable`
+///
+/// Each amount is scaled by `max_sendable / total` (rounded down) and the
+/// rounding remainder is handed out one atomic unit at a time, so a split
+/// settlement shares the fee reserve between buyer and vendor. With a
+/// single destination this simply caps its amount at `max_sendable`.
+/// Destinations left at zero are dropped: the wallet RPC rejects them.
+fn fit_destinations_to_balance(
+    destinations: &mut Vec<monero_marketplace_common::types::TransferDestination>,
+    max_sendable: u64,
+) {
+    let total: u128 = destinations.iter().map(|d| d.amount as u128).sum();
+    if total > max_sendable as u128 {
+        let scaled: Vec<u64> = destinations
+            .iter()
+            .map(|d| (d.amount as u128 * max_sendable as u128 / total) as u64)
+            .collect();
+        let mut remainder = max_sendable - scaled.iter().sum::<u64>();
+
+        for (dest, mut adjusted) in destinations.iter_mut().zip(scaled) {
+            if remainder > 0 && adjusted < dest.amount {
+                adjusted += 1;
+                remainder -= 1;
+            }
+            info!(
+                "Adjusting amount from {} to {} to reserve fees",
+                dest.amount, adjusted
+            );
+            dest.amount = adjusted;
+        }
+    }
+
+    destinations.retain(|dest| dest.amount > 0);
+}
+
 /// Convert MoneroError to CommonError
 fn convert_monero_error(e: MoneroError) -> CommonError {
     match e {
@@ -2764,6 +2885,42 @@ impl WalletManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use monero_marketplace_common::types::TransferDestination;
+
+    fn destination(address: &str, amount: u64) -> TransferDestination {
+        TransferDestination {
+            address: address.to_string(),
+            amount,
+        }
+    }
+
+    /// Test fee adjustment on single and split destinations
+    #[test]
+    fn test_fit_destinations_to_balance() {
+        // Single destination: capped at max_sendable
+        let mut single = vec![destination("vendor", 1_000)];
+        fit_destinations_to_balance(&mut single, 900);
+        assert_eq!(single[0].amount, 900);
+
+        // Enough balance: untouched
+        let mut split = vec![destination("buyer", 600), destination("vendor", 400)];
+        fit_destinations_to_balance(&mut split, 1_000);
+        assert_eq!((split[0].amount, split[1].amount), (600, 400));
+
+        // Shortfall shared pro rata
+        fit_destinations_to_balance(&mut split, 900);
+        assert_eq!((split[0].amount, split[1].amount), (540, 360));
+
+        // Rounding remainder is handed out, total is exact; a share
+        // rounded down to zero is dropped
+        let mut uneven = vec![destination("buyer", 2), destination("vendor", 1)];
+        fit_destinations_to_balance(&mut uneven, 2);
+        assert_eq!(uneven.len(), 1);
+        assert_eq!((uneven[0].address.as_str(), uneven[0].amount), ("buyer", 2));
+        let mut uneven = vec![destination("buyer", 5), destination("vendor", 5)];
+        fit_destinations_to_balance(&mut uneven, 7);
+        assert_eq!((uneven[0].amount, uneven[1].amount), (4, 3));
+    }
 
     /// Test convert_monero_error covers all variants
     #[test]
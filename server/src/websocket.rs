@@ -3,6 +3,7 @@
 use actix::{Actor, ActorContext, Addr, AsyncContext, Context, Handler, Message, StreamHandler};
 use actix_web_actors::ws;
 use anyhow::Result;
+use monero_marketplace_common::types::EscrowStatus;
 use serde;
 use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
@@ -125,11 +126,15 @@ pub enum WsEvent {
     },
     EscrowStatusChanged {
         escrow_id: Uuid,
-        new_status: String,
+        new_status: EscrowStatus,
     },
+    /// Confirmation progress of a deposit, release or refund ("x of y")
     TransactionConfirmed {
+        escrow_id: Uuid,
         tx_hash: String,
         confirmations: u32,
+        /// Confirmations required for this escrow's amount tier
+        required_confirmations: u32,
     },
     NewMessage {
         from: Uuid,
@@ -180,7 +185,7 @@ pub enum WsEvent {
     EscrowAutoCancelled {
         escrow_id: Uuid,
         reason: String,
-        cancelled_at_status: String,
+        cancelled_at_status: EscrowStatus,
     },
     /// Notification that a dispute has been escalated due to timeout
     ///
@@ -192,6 +197,17 @@ pub enum WsEvent {
         days_in_dispute: u64,
         action_taken: String,
     },
+    /// Escrow handed to another arbiter (recusal, escalation or admin)
+    ///
+    /// With `multisig_restarted`, all three parties must register their
+    /// wallets again because the multisig setup had not completed.
+    ArbiterReassigned {
+        escrow_id: Uuid,
+        previous_arbiter_id: Uuid,
+        arbiter_id: Uuid,
+        reason: String,
+        multisig_restarted: bool,
+    },
     /// Alert that a transaction appears stuck (high confirmation timeout)
     ///
     /// Triggered when a "releasing" or "refunding" transaction has not confirmed
@@ -232,6 +248,32 @@ pub enum WsEvent {
         phase: String,
         recovered_at: i64, // Unix timestamp
     },
+    /// Vendor bond moved to a new status (awaiting_funding, active, released...)
+    VendorBondStatusChanged {
+        bond_id: Uuid,
+        new_status: String,
+    },
+    /// Part of a vendor bond was slashed into a dispute payout
+    VendorBondSlashed {
+        bond_id: Uuid,
+        escrow_id: Uuid,
+        amount: i64,
+        tx_hash: String,
+    },
+    /// Partial deposit on the escrow address: the buyer must send `missing_atomic` more
+    EscrowUnderfunded {
+        escrow_id: Uuid,
+        received_atomic: i64,
+        expected_atomic: i64,
+        missing_atomic: i64,
+    },
+    /// More than the escrow amount received: `surplus_atomic` goes back to the buyer on release
+    EscrowOverfunded {
+        escrow_id: Uuid,
+        received_atomic: i64,
+        expected_atomic: i64,
+        surplus_atomic: i64,
+    },
 }
 
 // --- Handlers ---
@@ -29,6 +29,8 @@ fn test_complete_airgap_workflow() {
         vendor_response: Some("Shipped in perfect condition".to_string()),
         dispute_opened_at: chrono::Utc::now().timestamp(),
         evidence_file_count: 3,
+        evidence: Vec::new(),
+        evidence_deadline: chrono::Utc::now().timestamp(),
         partial_tx_hex: "deadbeef".to_string(),
         nonce: hex::encode(random_bytes_32()),
     };
@@ -103,6 +105,8 @@ fn test_qr_size_constraints() {
         vendor_response: Some("B".repeat(500)), // Large response
         dispute_opened_at: chrono::Utc::now().timestamp(),
         evidence_file_count: 99,
+        evidence: Vec::new(),
+        evidence_deadline: chrono::Utc::now().timestamp(),
         partial_tx_hex: "C".repeat(200),
         nonce: hex::encode(random_bytes_32()),
     };
@@ -0,0 +1,237 @@
+//! Arbiter pool: workload balancing, conflicts, recusal and escalation
+//!
+//! Exercises the selection rules of `services::arbiter_assignment` and the
+//! reassignment of timed-out disputes by `TimeoutMonitor` on a simulated
+//! clock.
+//!
+//! Run with: cargo test --package server --test arbiter_assignment_test
+
+mod common;
+
+use anyhow::Result;
+use chrono::Duration;
+use common::{create_escrow_with_arbiter, create_order, create_user, setup_test_db};
+use diesel::prelude::*;
+use monero_marketplace_common::clock::ChainClock;
+use monero_marketplace_test_support::chain::SimulatedChain;
+use monero_marketplace_test_support::mock_rpc::MockNetwork;
+use server::config::{ArbiterPoolConfig, TimeoutConfig};
+use server::db::DbPool;
+use server::models::arbiter_assignment::{ArbiterAssignment, AssignmentReason};
+use server::models::escrow::{Escrow, EscrowStatus};
+use server::models::escrow_event::{EscrowActor, EscrowEventRecord};
+use server::schema::escrows;
+use server::services::arbiter_assignment::{
+    reassign_arbiter, select_arbiter, ArbiterAssignmentService,
+};
+use server::services::timeout_monitor::TimeoutMonitor;
+use server::websocket::WebSocketServer;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Move an escrow to `disputed` through `funded`
+fn dispute(conn: &mut SqliteConnection, escrow: &Escrow) -> Result<Escrow> {
+    diesel::update(escrows::table.filter(escrows::id.eq(&escrow.id)))
+        .set(escrows::multisig_address.eq("5escrowaddress"))
+        .execute(conn)?;
+
+    let system = EscrowActor::System("test");
+    EscrowEventRecord::record_transition(conn, &escrow.id, EscrowStatus::Funded, &system, None)?;
+    EscrowEventRecord::record_transition(
+        conn,
+        &escrow.id,
+        EscrowStatus::Disputed,
+        &EscrowActor::user(&escrow.buyer_id),
+        Some("Item not received"),
+    )
+}
+
+/// Test: new escrows go to the arbiter with the fewest open disputes
+#[test]
+fn test_selects_least_loaded_arbiter() -> Result<()> {
+    let (pool, db_path) = setup_test_db("arbiter_assignment")?;
+    let mut conn = pool.get()?;
+    let config = ArbiterPoolConfig::default();
+
+    let busy = create_user(&mut conn, "arbiter")?;
+    let idle = create_user(&mut conn, "arbiter")?;
+
+    // `busy` holds one dispute, `idle` two escrows without dispute
+    let escrow = create_escrow_with_arbiter(&mut conn, &busy, EscrowStatus::Created)?;
+    dispute(&mut conn, &escrow)?;
+    create_escrow_with_arbiter(&mut conn, &idle, EscrowStatus::Created)?;
+    create_escrow_with_arbiter(&mut conn, &idle, EscrowStatus::Created)?;
+
+    let buyer = create_user(&mut conn, "buyer")?;
+    let vendor = create_user(&mut conn, "vendor")?;
+    let selected = select_arbiter(&mut conn, &config, &buyer, &vendor, &[])?;
+    assert_eq!(selected.arbiter_id, idle);
+    assert_eq!(selected.open_disputes, 0);
+    assert_eq!(selected.open_escrows, 2);
+
+    // At capacity, the busy arbiter is out of the pool entirely
+    let full = ArbiterPoolConfig {
+        max_open_disputes: 1,
+        ..config
+    };
+    let excluded = vec![idle.clone()];
+    assert!(select_arbiter(&mut conn, &full, &buyer, &vendor, &excluded).is_err());
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: arbiters who traded with the buyer or the vendor are skipped
+#[test]
+fn test_excludes_arbiters_who_traded_with_parties() -> Result<()> {
+    let (pool, db_path) = setup_test_db("arbiter_assignment")?;
+    let mut conn = pool.get()?;
+    let config = ArbiterPoolConfig::default();
+
+    let bought_from_vendor = create_user(&mut conn, "arbiter")?;
+    let sold_to_buyer = create_user(&mut conn, "arbiter")?;
+    let independent = create_user(&mut conn, "arbiter")?;
+
+    let buyer = create_user(&mut conn, "buyer")?;
+    let vendor = create_user(&mut conn, "vendor")?;
+    create_order(&mut conn, &bought_from_vendor, &vendor, "pending")?;
+    create_order(&mut conn, &buyer, &sold_to_buyer, "pending")?;
+
+    // `independent` is the busiest but the only one without a conflict
+    let escrow = create_escrow_with_arbiter(&mut conn, &independent, EscrowStatus::Created)?;
+    dispute(&mut conn, &escrow)?;
+
+    let selected = select_arbiter(&mut conn, &config, &buyer, &vendor, &[])?;
+    assert_eq!(selected.arbiter_id, independent);
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: recusal before setup restarts the multisig and never reassigns a past arbiter
+#[actix_web::test]
+async fn test_recusal_restarts_unstarted_multisig() -> Result<()> {
+    let (pool, db_path) = setup_test_db("arbiter_assignment")?;
+    let config = ArbiterPoolConfig::default();
+
+    let (escrow, first, second, third) = {
+        let mut conn = pool.get()?;
+        let first = create_user(&mut conn, "arbiter")?;
+        let second = create_user(&mut conn, "arbiter")?;
+        let third = create_user(&mut conn, "arbiter")?;
+        // Make `third` the last choice so the order of reassignments is known
+        create_escrow_with_arbiter(&mut conn, &third, EscrowStatus::Created)?;
+
+        let escrow = create_escrow_with_arbiter(&mut conn, &first, EscrowStatus::Created)?;
+        diesel::update(escrows::table.filter(escrows::id.eq(&escrow.id)))
+            .set((
+                escrows::buyer_wallet_info.eq(Some(vec![1u8; 16])),
+                escrows::multisig_phase.eq("preparing"),
+            ))
+            .execute(&mut conn)?;
+        (escrow, first, second, third)
+    };
+
+    let service = ArbiterAssignmentService::new(pool.clone(), config.clone());
+    let escrow_id = escrow.id.parse::<Uuid>()?;
+
+    // Only the current arbiter may recuse
+    let other = second.parse::<Uuid>()?;
+    assert!(service.recuse(escrow_id, other, "Not mine").await.is_err());
+
+    let reassignment = service
+        .recuse(escrow_id, first.parse()?, "Know the vendor personally")
+        .await?;
+    assert_eq!(reassignment.previous_arbiter_id, first);
+    assert_eq!(reassignment.escrow.arbiter_id, second);
+    assert!(reassignment.multisig_restarted);
+    assert!(reassignment.escrow.buyer_wallet_info.is_none());
+    assert_eq!(reassignment.escrow.multisig_phase, "not_started");
+
+    // Admin move: `first` already held the escrow, so `third` takes it
+    let mut conn = pool.get()?;
+    let moved = reassign_arbiter(&mut conn, &config, &escrow.id, AssignmentReason::Admin, None)?;
+    assert_eq!(moved.escrow.arbiter_id, third);
+
+    let history = ArbiterAssignment::find_by_escrow(&mut conn, &escrow.id)?;
+    let held: Vec<_> = history.iter().map(|a| a.arbiter_id.as_str()).collect();
+    assert_eq!(held, [first.as_str(), second.as_str(), third.as_str()]);
+    assert_eq!(history[0].end_reason.as_deref(), Some("recused"));
+    assert_eq!(history[1].end_reason.as_deref(), Some("replaced"));
+    assert!(history[2].ended_at.is_none());
+
+    // Nobody left who has not held it
+    assert!(
+        reassign_arbiter(&mut conn, &config, &escrow.id, AssignmentReason::Admin, None).is_err()
+    );
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: a timed-out dispute is reassigned until the escalation limit, then left to admins
+#[actix_web::test]
+async fn test_dispute_timeout_reassigns_until_limit() -> Result<()> {
+    let (pool, db_path) = setup_test_db("arbiter_assignment")?;
+    let network = MockNetwork::new();
+    let chain = SimulatedChain::new(&network);
+    let timeouts = TimeoutConfig::default();
+    let pool_config = ArbiterPoolConfig {
+        max_escalation_reassignments: 1,
+        ..ArbiterPoolConfig::default()
+    };
+
+    let (escrow, first) = {
+        let mut conn = pool.get()?;
+        let first = create_user(&mut conn, "arbiter")?;
+        create_user(&mut conn, "arbiter")?;
+        create_user(&mut conn, "arbiter")?;
+
+        let escrow = create_escrow_with_arbiter(&mut conn, &first, EscrowStatus::Created)?;
+        let escrow = dispute(&mut conn, &escrow)?;
+        Escrow::update_expiration(
+            &mut conn,
+            escrow.id.clone(),
+            timeouts.deadline_for_status(EscrowStatus::Disputed, &chain),
+        )?;
+        (escrow, first)
+    };
+
+    let service = Arc::new(ArbiterAssignmentService::new(pool.clone(), pool_config));
+    let websocket = actix::Actor::start(WebSocketServer::default());
+    let monitor = TimeoutMonitor::new(pool.clone(), websocket, timeouts.clone())
+        .with_clock(chain.shared())
+        .with_arbiter_assignment(service);
+
+    let load = |pool: &DbPool| -> Result<Escrow> {
+        let mut conn = pool.get()?;
+        Escrow::find_by_id(&mut conn, escrow.id.clone())
+    };
+
+    monitor.check_timeouts().await;
+    assert_eq!(load(&pool)?.arbiter_id, first);
+
+    // First timeout: handed to the least loaded arbiter, with a fresh deadline
+    chain.advance(Duration::days(7) + Duration::seconds(1));
+    monitor.check_timeouts().await;
+    let reassigned = load(&pool)?;
+    assert_eq!(reassigned.status, "disputed");
+    assert_ne!(reassigned.arbiter_id, first);
+    assert!(!reassigned.is_expired_at(chain.now_naive()));
+    assert!(!reassigned.is_multisig_unstarted());
+
+    // Second timeout: limit reached, stays with the escalated arbiter for an admin
+    chain.advance(Duration::days(7) + Duration::seconds(1));
+    monitor.check_timeouts().await;
+    let escalated = load(&pool)?;
+    assert_eq!(escalated.arbiter_id, reassigned.arbiter_id);
+
+    let mut conn = pool.get()?;
+    assert_eq!(
+        ArbiterAssignment::count_by_reason(&mut conn, &escrow.id, AssignmentReason::Escalation)?,
+        1
+    );
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
@@ -0,0 +1,156 @@
+//! Signed audit checkpoints and offline verification of exports
+//!
+//! Run with: cargo test --package server --test audit_checkpoint_test
+
+mod common;
+
+use anyhow::Result;
+use common::setup_test_db;
+use server::config::AuditCheckpointPolicy;
+use server::crypto::signing::MarketplaceSigner;
+use server::models::audit_log::AuditEventType;
+use server::models::escrow_event::EscrowActor;
+use server::services::audit::AuditLogger;
+use server::services::audit_checkpoint::{
+    verify_export, AuditCheckpointer, AuditFinding, CHECKPOINT_FILE,
+};
+use uuid::Uuid;
+
+const SECRET: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+const OTHER_SECRET: &str = "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb";
+
+async fn record(audit: &AuditLogger, count: usize) -> Result<()> {
+    for _ in 0..count {
+        audit
+            .record(
+                AuditEventType::Release,
+                Uuid::new_v4(),
+                EscrowActor::System("test"),
+                serde_json::json!({ "amount": 1000 }),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// Test: checkpoints are signed only when the log grows and are published
+#[tokio::test]
+async fn test_checkpoints_are_signed_and_published() -> Result<()> {
+    let (pool, db_path) = setup_test_db("audit_checkpoint")?;
+    let audit = AuditLogger::new(pool.clone());
+    let publish_dir = std::env::temp_dir().join(format!("audit_publish_{}", Uuid::new_v4()));
+    let checkpointer = AuditCheckpointer::new(
+        pool.clone(),
+        MarketplaceSigner::from_hex(SECRET)?,
+        AuditCheckpointPolicy {
+            interval_secs: 60,
+            publish_dir: Some(publish_dir.clone()),
+        },
+    );
+
+    assert!(checkpointer.checkpoint().await?.is_none(), "empty log");
+
+    record(&audit, 3).await?;
+    let first = checkpointer.checkpoint().await?.expect("log grew");
+    assert_eq!(first.sequence, 3);
+    first.verify_signature(&checkpointer.public_key_hex())?;
+    assert!(checkpointer.checkpoint().await?.is_none(), "log unchanged");
+
+    record(&audit, 2).await?;
+    let second = checkpointer.checkpoint().await?.expect("log grew");
+    assert_eq!(second.sequence, 5);
+
+    let checkpoints = audit.checkpoints().await?;
+    assert_eq!(checkpoints, vec![first, second]);
+
+    let published = std::fs::read_to_string(publish_dir.join(CHECKPOINT_FILE))?;
+    assert_eq!(published.lines().count(), 2);
+
+    let entries = audit.export(0, 100).await?;
+    let report = verify_export(&entries, &checkpoints, &checkpointer.public_key_hex());
+    assert!(report.is_clean(), "{:?}", report.findings);
+    assert_eq!(report.checkpoints_matched, 2);
+
+    // Partial export anchored on the first checkpoint
+    let tail = audit.export(3, 100).await?;
+    let report = verify_export(&tail, &checkpoints, &checkpointer.public_key_hex());
+    assert!(report.is_clean(), "{:?}", report.findings);
+
+    let _ = std::fs::remove_dir_all(publish_dir);
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: gaps, edits, rewrites, truncation and foreign signatures are reported
+#[tokio::test]
+async fn test_verify_export_reports_tampering() -> Result<()> {
+    let (pool, db_path) = setup_test_db("audit_checkpoint")?;
+    let audit = AuditLogger::new(pool.clone());
+    let signer = MarketplaceSigner::from_hex(SECRET)?;
+    let public_key = signer.public_key_hex();
+    let checkpointer =
+        AuditCheckpointer::new(pool.clone(), signer, AuditCheckpointPolicy::default());
+
+    record(&audit, 4).await?;
+    checkpointer.checkpoint().await?;
+    let checkpoints = audit.checkpoints().await?;
+    let entries = audit.export(0, 100).await?;
+
+    // Entry removed from the middle
+    let mut gap = entries.clone();
+    gap.remove(1);
+    let report = verify_export(&gap, &checkpoints, &public_key);
+    assert!(report
+        .findings
+        .contains(&AuditFinding::Gap { from: 2, to: 2 }));
+
+    // Entry edited in place
+    let mut edited = entries.clone();
+    edited[2].data = r#"{"amount":1}"#.to_string();
+    let report = verify_export(&edited, &checkpoints, &public_key);
+    assert!(report
+        .findings
+        .contains(&AuditFinding::AlteredEntry { sequence: 3 }));
+
+    // Whole chain rehashed after an edit: internally consistent, but not what was signed
+    let mut rewritten = entries.clone();
+    rewritten[0].data = r#"{"amount":1}"#.to_string();
+    for i in 0..rewritten.len() {
+        if i > 0 {
+            rewritten[i].previous_hash = Some(rewritten[i - 1].entry_hash.clone());
+        }
+        rewritten[i].entry_hash = rewritten[i].expected_hash();
+    }
+    let report = verify_export(&rewritten, &checkpoints, &public_key);
+    assert_eq!(
+        report.findings,
+        vec![AuditFinding::CheckpointMismatch { sequence: 4 }]
+    );
+
+    // Last entries dropped
+    let report = verify_export(&entries[..2], &checkpoints, &public_key);
+    assert_eq!(
+        report.findings,
+        vec![AuditFinding::Truncated { sequence: 4 }]
+    );
+
+    // Checkpoint checked against another key
+    let other_key = MarketplaceSigner::from_hex(OTHER_SECRET)?.public_key_hex();
+    let report = verify_export(&entries, &checkpoints, &other_key);
+    assert!(matches!(
+        report.findings.as_slice(),
+        [AuditFinding::BadCheckpointSignature { sequence: 4, .. }]
+    ));
+
+    // Forged signature under the right key
+    let mut forged = checkpoints.clone();
+    forged[0].head_hash = rewritten[3].entry_hash.clone();
+    let report = verify_export(&rewritten, &forged, &public_key);
+    assert!(matches!(
+        report.findings.as_slice(),
+        [AuditFinding::BadCheckpointSignature { sequence: 4, .. }]
+    ));
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
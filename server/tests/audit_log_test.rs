@@ -0,0 +1,156 @@
+//! Hash-chained audit log: appends, append-only triggers and tamper detection
+//!
+//! Run with: cargo test --package server --test audit_log_test
+
+mod common;
+
+use anyhow::Result;
+use common::{create_escrow, setup_test_db};
+use diesel::prelude::*;
+use server::db::{db_load_escrow, db_transition_escrow_audited};
+use server::models::audit_log::{AuditEntry, AuditEventType};
+use server::models::escrow::EscrowStatus;
+use server::models::escrow_event::EscrowActor;
+use server::services::audit::AuditLogger;
+use uuid::Uuid;
+
+/// Test: concurrent writers extend a single intact chain
+#[tokio::test]
+async fn test_concurrent_appends_form_one_chain() -> Result<()> {
+    let (pool, db_path) = setup_test_db("audit_log")?;
+    let audit = AuditLogger::new(pool.clone());
+
+    let writers: Vec<_> = (0..8)
+        .map(|i| {
+            let audit = audit.clone();
+            tokio::spawn(async move {
+                audit
+                    .record(
+                        AuditEventType::Release,
+                        Uuid::new_v4(),
+                        EscrowActor::System("test"),
+                        serde_json::json!({ "writer": i }),
+                    )
+                    .await
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.await??;
+    }
+
+    let result = audit.verify().await?;
+    assert!(result.is_valid(), "{:?}", result);
+    assert_eq!(result.entries_verified, 8);
+
+    let page = audit.export(5, 10).await?;
+    assert_eq!(
+        page.iter().map(|e| e.sequence).collect::<Vec<_>>(),
+        [6, 7, 8]
+    );
+    let fifth = audit.export(4, 1).await?;
+    assert_eq!(page[0].previous_hash.as_deref(), Some(fifth[0].entry_hash.as_str()));
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: entries cannot be updated or deleted, and edits that bypass that are detected
+#[tokio::test]
+async fn test_tampering_is_rejected_or_detected() -> Result<()> {
+    let (pool, db_path) = setup_test_db("audit_log")?;
+    let audit = AuditLogger::new(pool.clone());
+
+    let escrow_id = Uuid::new_v4();
+    for event_type in [AuditEventType::DisputeResolution, AuditEventType::Refund] {
+        audit
+            .record(
+                event_type,
+                escrow_id,
+                EscrowActor::user(Uuid::new_v4()),
+                serde_json::json!({ "amount": 1_000_000_000_000i64 }),
+            )
+            .await?;
+    }
+    audit
+        .record(
+            AuditEventType::WalletAddressChange,
+            Uuid::new_v4(),
+            EscrowActor::user(Uuid::new_v4()),
+            serde_json::json!({ "new_address": "4..." }),
+        )
+        .await?;
+
+    let mut conn = pool.get()?;
+    assert_eq!(AuditEntry::find_by_entity(&mut conn, &escrow_id.to_string())?.len(), 2);
+
+    let update = diesel::sql_query("UPDATE audit_log SET data = '{}' WHERE sequence = 2")
+        .execute(&mut conn);
+    assert!(update.is_err(), "audit_log must reject updates");
+    let delete = diesel::sql_query("DELETE FROM audit_log WHERE sequence = 3").execute(&mut conn);
+    assert!(delete.is_err(), "audit_log must reject deletes");
+    assert!(AuditEntry::verify_chain(&mut conn)?.is_valid());
+
+    // Someone with raw file access drops the trigger and rewrites an amount
+    diesel::sql_query("DROP TRIGGER audit_log_no_update").execute(&mut conn)?;
+    diesel::sql_query(
+        "UPDATE audit_log SET data = '{\"amount\":1}' WHERE sequence = 2",
+    )
+    .execute(&mut conn)?;
+
+    let result = AuditEntry::verify_chain(&mut conn)?;
+    assert_eq!(result.broken_at, Some(2));
+    assert_eq!(result.entries_verified, 1);
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: a ruling whose audit entry cannot be written leaves the escrow untouched
+#[tokio::test]
+async fn test_transition_fails_without_audit_entry() -> Result<()> {
+    let (pool, db_path) = setup_test_db("audit_log")?;
+    let mut conn = pool.get()?;
+    let escrow = create_escrow(&mut conn, EscrowStatus::Disputed)?;
+    let escrow_id = Uuid::parse_str(&escrow.id)?;
+    let arbiter = EscrowActor::user(Uuid::parse_str(&escrow.arbiter_id)?);
+    let ruling = serde_json::json!({ "resolution": "buyer" });
+
+    diesel::sql_query(
+        "CREATE TRIGGER audit_log_full BEFORE INSERT ON audit_log \
+         BEGIN SELECT RAISE(ABORT, 'disk full'); END",
+    )
+    .execute(&mut conn)?;
+    let result = db_transition_escrow_audited(
+        &pool,
+        escrow_id,
+        EscrowStatus::ResolvedBuyer,
+        arbiter.clone(),
+        None,
+        AuditEventType::DisputeResolution,
+        ruling.clone(),
+    )
+    .await;
+    assert!(result.is_err(), "transition must fail with its audit entry");
+    assert_eq!(
+        db_load_escrow(&pool, escrow_id).await?.get_status()?,
+        EscrowStatus::Disputed
+    );
+
+    diesel::sql_query("DROP TRIGGER audit_log_full").execute(&mut conn)?;
+    let updated = db_transition_escrow_audited(
+        &pool,
+        escrow_id,
+        EscrowStatus::ResolvedBuyer,
+        arbiter,
+        None,
+        AuditEventType::DisputeResolution,
+        ruling,
+    )
+    .await?;
+    assert_eq!(updated.get_status()?, EscrowStatus::ResolvedBuyer);
+    assert_eq!(AuditEntry::find_by_entity(&mut conn, &escrow.id)?.len(), 1);
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
@@ -0,0 +1,173 @@
+//! Shared fixtures of the server integration tests
+//!
+//! Included by each test file with `mod common;`: encrypted test databases
+//! and the users, listings, orders and escrows most tests start from.
+//! Rows are written with plain inserts so the helpers also work on a
+//! database migrated only part of the way.
+
+#![allow(dead_code)]
+
+use anyhow::Result;
+use diesel::prelude::*;
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use server::db::{create_pool, DbPool};
+use server::models::escrow::{Escrow, EscrowStatus, NewEscrow};
+use server::models::listing::NewListing;
+use server::models::order::NewOrder;
+use server::models::user::{NewUser, User};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+pub const ONE_XMR: i64 = 1_000_000_000_000;
+
+/// Fresh encrypted database with all migrations applied
+///
+/// `name` prefixes the file created in the temp directory.
+pub fn setup_test_db(name: &str) -> Result<(DbPool, PathBuf)> {
+    setup_test_db_before(name, None)
+}
+
+/// Fresh database encrypted with `db_key`, with all migrations applied
+pub fn setup_test_db_with_key(name: &str, db_key: &str) -> Result<(DbPool, PathBuf)> {
+    let db_path = std::env::temp_dir().join(format!("{}_{}.db", name, Uuid::new_v4()));
+    let pool = create_pool(db_path.to_str().unwrap_or_default(), db_key)?;
+    run_pending_migrations(&mut *pool.get()?)?;
+    Ok((pool, db_path))
+}
+
+/// Fresh encrypted database, migrated up to (but not including) `stop_before`
+pub fn setup_test_db_before(name: &str, stop_before: Option<&str>) -> Result<(DbPool, PathBuf)> {
+    let db_path = std::env::temp_dir().join(format!("{}_{}.db", name, Uuid::new_v4()));
+    let pool = create_pool(db_path.to_str().unwrap_or_default(), "test_encryption_key")?;
+
+    let mut conn = pool.get()?;
+    loop {
+        let pending = conn
+            .pending_migrations(MIGRATIONS)
+            .map_err(|e| anyhow::anyhow!("Failed to list migrations: {}", e))?;
+        let Some(next) = pending.first() else { break };
+        if stop_before.is_some_and(|name| next.name().to_string().contains(name)) {
+            break;
+        }
+        conn.run_next_migration(MIGRATIONS)
+            .map_err(|e| anyhow::anyhow!("Failed to run migrations: {}", e))?;
+    }
+
+    Ok((pool, db_path))
+}
+
+/// Apply the migrations left out by [`setup_test_db_before`]
+pub fn run_pending_migrations(conn: &mut SqliteConnection) -> Result<()> {
+    conn.run_pending_migrations(MIGRATIONS)
+        .map_err(|e| anyhow::anyhow!("Failed to run migrations: {}", e))?;
+    Ok(())
+}
+
+/// New user with `role`, named after it
+pub fn create_user(conn: &mut SqliteConnection, role: &str) -> Result<String> {
+    let id = Uuid::new_v4().to_string();
+    User::create(
+        conn,
+        NewUser {
+            id: id.clone(),
+            username: format!("{}_{}", role, &id[..8]),
+            password_hash: "hashed_password".to_string(),
+            role: role.to_string(),
+            wallet_address: None,
+            wallet_id: None,
+        },
+    )?;
+    Ok(id)
+}
+
+/// Active listing of `vendor_id`
+pub fn create_listing(
+    conn: &mut SqliteConnection,
+    vendor_id: &str,
+    title: &str,
+    price_xmr: i64,
+    stock: i32,
+) -> Result<String> {
+    let id = Uuid::new_v4().to_string();
+    diesel::insert_into(server::schema::listings::table)
+        .values(&NewListing {
+            id: id.clone(),
+            vendor_id: vendor_id.to_string(),
+            title: title.to_string(),
+            description: "Integration test listing".to_string(),
+            price_xmr,
+            stock,
+            status: "active".to_string(),
+            images_ipfs_cids: None,
+            category: "other".to_string(),
+        })
+        .execute(conn)?;
+    Ok(id)
+}
+
+/// One-XMR order from `buyer_id` for a new listing of `vendor_id`
+pub fn create_order(
+    conn: &mut SqliteConnection,
+    buyer_id: &str,
+    vendor_id: &str,
+    status: &str,
+) -> Result<String> {
+    let listing_id = create_listing(conn, vendor_id, "Test Product", ONE_XMR, 10)?;
+
+    let id = Uuid::new_v4().to_string();
+    diesel::insert_into(server::schema::orders::table)
+        .values(&NewOrder {
+            id: id.clone(),
+            buyer_id: buyer_id.to_string(),
+            vendor_id: vendor_id.to_string(),
+            listing_id,
+            escrow_id: None,
+            status: status.to_string(),
+            total_xmr: ONE_XMR,
+            shipping_address: None,
+            shipping_notes: None,
+        })
+        .execute(conn)?;
+    Ok(id)
+}
+
+/// One-XMR escrow in `status` between a new buyer and vendor, held by a
+/// new arbiter
+pub fn create_escrow(conn: &mut SqliteConnection, status: EscrowStatus) -> Result<Escrow> {
+    let arbiter = create_user(conn, "arbiter")?;
+    create_escrow_with_arbiter(conn, &arbiter, status)
+}
+
+/// One-XMR escrow in `status` between a new buyer and vendor, held by
+/// `arbiter_id`
+///
+/// The order is `completed` along with a completed escrow, `pending` otherwise.
+pub fn create_escrow_with_arbiter(
+    conn: &mut SqliteConnection,
+    arbiter_id: &str,
+    status: EscrowStatus,
+) -> Result<Escrow> {
+    let buyer = create_user(conn, "buyer")?;
+    let vendor = create_user(conn, "vendor")?;
+    let order_status = if status == EscrowStatus::Completed {
+        "completed"
+    } else {
+        "pending"
+    };
+    let order_id = create_order(conn, &buyer, &vendor, order_status)?;
+
+    Escrow::create(
+        conn,
+        NewEscrow {
+            id: Uuid::new_v4().to_string(),
+            order_id,
+            buyer_id: buyer,
+            vendor_id: vendor,
+            arbiter_id: arbiter_id.to_string(),
+            amount: ONE_XMR,
+            status: status.as_str().to_string(),
+        },
+    )
+}
@@ -0,0 +1,335 @@
+//! Dispute evidence: submission window, encrypted storage and USB bundle
+//!
+//! Exercises `DisputeEvidenceService` with a local blob store and a
+//! simulated clock, and checks the exported hash list against the bundle
+//! the way the offline arbiter does.
+//!
+//! Run with: cargo test --package server --test dispute_evidence_test
+
+mod common;
+
+use anyhow::Result;
+use common::{create_escrow, setup_test_db, ONE_XMR};
+use monero_marketplace_test_support::chain::SimulatedChain;
+use monero_marketplace_test_support::mock_rpc::MockNetwork;
+use server::db::DbPool;
+use server::models::dispute_evidence::EvidenceKind;
+use server::models::escrow::{Escrow, EscrowStatus};
+use server::models::escrow_event::{EscrowActor, EscrowEventRecord};
+use server::services::airgap::{DisputeRequest, EvidenceHash};
+use server::services::dispute_evidence::{
+    DisputeEvidenceService, EvidenceStore, MAX_FILES_PER_PARTY,
+};
+use std::path::PathBuf;
+use std::time::Duration;
+use uuid::Uuid;
+
+const EVIDENCE_WINDOW: Duration = Duration::from_secs(72 * 3600);
+const PNG_HEADER: [u8; 16] = [
+    0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 0x0D, b'I', b'H', b'D', b'R',
+];
+
+/// New escrow moved to `disputed` by its buyer
+fn create_disputed_escrow(pool: &DbPool) -> Result<Escrow> {
+    let mut conn = pool.get()?;
+    let conn = &mut conn;
+    let escrow = create_escrow(conn, EscrowStatus::Created)?;
+
+    let system = EscrowActor::System("test");
+    EscrowEventRecord::record_transition(conn, &escrow.id, EscrowStatus::Funded, &system, None)?;
+    EscrowEventRecord::record_transition(
+        conn,
+        &escrow.id,
+        EscrowStatus::Disputed,
+        &EscrowActor::user(&escrow.buyer_id),
+        Some("Item not received"),
+    )
+}
+
+fn evidence_service(pool: &DbPool, chain: &SimulatedChain) -> (DisputeEvidenceService, PathBuf) {
+    let blob_dir = std::env::temp_dir().join(format!("evidence_blobs_{}", Uuid::new_v4()));
+    let service = DisputeEvidenceService::new(
+        pool.clone(),
+        EvidenceStore::Local(blob_dir.clone()),
+        vec![7u8; 32],
+        EVIDENCE_WINDOW,
+    )
+    .with_clock(chain.shared());
+    (service, blob_dir)
+}
+
+fn uuid(id: &str) -> Uuid {
+    id.parse().expect("valid uuid")
+}
+
+fn dispute_request(escrow: &Escrow, evidence: Vec<EvidenceHash>) -> DisputeRequest {
+    DisputeRequest {
+        escrow_id: uuid(&escrow.id),
+        buyer_id: uuid(&escrow.buyer_id),
+        vendor_id: uuid(&escrow.vendor_id),
+        amount: escrow.amount as u64,
+        buyer_claim: "Item not received".to_string(),
+        vendor_response: None,
+        dispute_opened_at: escrow.updated_at.and_utc().timestamp(),
+        evidence_file_count: evidence.len(),
+        evidence,
+        evidence_deadline: escrow.updated_at.and_utc().timestamp(),
+        partial_tx_hex: "abc123".to_string(),
+        nonce: "ab".repeat(32),
+    }
+}
+
+/// Test: both parties submit, files are encrypted at rest and the bundle matches the export
+#[tokio::test]
+async fn test_evidence_bundle_matches_exported_hashes() -> Result<()> {
+    let (pool, db_path) = setup_test_db("dispute_evidence")?;
+    let escrow = create_disputed_escrow(&pool)?;
+    let chain = SimulatedChain::new(&MockNetwork::new());
+    let (service, blob_dir) = evidence_service(&pool, &chain);
+    let escrow_id = uuid(&escrow.id);
+
+    let mut photo = PNG_HEADER.to_vec();
+    photo.extend_from_slice(b"parcel photo");
+    let tracking = b"Carrier: Swiss Post\nTracking: RR123456789CH".to_vec();
+
+    let buyer_photo = service
+        .submit(
+            escrow_id,
+            uuid(&escrow.buyer_id),
+            EvidenceKind::Photo,
+            Some("Empty parcel".to_string()),
+            photo.clone(),
+        )
+        .await?;
+    assert_eq!(buyer_photo.mime_type, "image/png");
+    assert_eq!(buyer_photo.file_name, "buyer-1.png");
+
+    let vendor_tracking = service
+        .submit(escrow_id, uuid(&escrow.vendor_id), EvidenceKind::Tracking, None, tracking.clone())
+        .await?;
+    assert_eq!(vendor_tracking.mime_type, "text/plain");
+    assert_eq!(vendor_tracking.file_name, "vendor-1.txt");
+
+    // Arbiter and outsiders cannot submit
+    assert!(service
+        .submit(escrow_id, uuid(&escrow.arbiter_id), EvidenceKind::Document, None, tracking.clone())
+        .await
+        .is_err());
+    assert!(service
+        .submit(escrow_id, Uuid::new_v4(), EvidenceKind::Document, None, tracking.clone())
+        .await
+        .is_err());
+
+    // Stored blob is not the plaintext
+    let blob = std::fs::read(blob_dir.join(&vendor_tracking.storage_ref))?;
+    assert!(!blob.windows(tracking.len()).any(|w| w == tracking.as_slice()));
+    assert_eq!(service.read(&vendor_tracking).await?, tracking);
+
+    // Hash list as exported to the offline arbiter
+    let hashes = service.evidence_hashes(&escrow).await?;
+    assert_eq!(hashes.len(), 2);
+    let by_name = |name: &str| hashes.iter().find(|h| h.file_name == name).cloned();
+    assert_eq!(by_name(&buyer_photo.file_name).map(|h| h.submitted_by), Some("buyer".to_string()));
+    assert_eq!(
+        by_name(&vendor_tracking.file_name).map(|h| h.submitted_by),
+        Some("vendor".to_string())
+    );
+
+    let request = dispute_request(&escrow, hashes);
+    request.validate()?;
+
+    // Bundle written for the USB stick verifies against the QR payload
+    let bundle_dir = std::env::temp_dir().join(format!("evidence_bundle_{}", Uuid::new_v4()));
+    assert_eq!(service.write_bundle(escrow_id, &bundle_dir).await?, 2);
+    request.verify_evidence_bundle(&bundle_dir)?;
+
+    // Tampered file is detected
+    let tampered = bundle_dir.join(&vendor_tracking.file_name);
+    std::fs::write(&tampered, b"Carrier: Swiss Post\nTracking: RR000000000CH")?;
+    assert!(request.verify_evidence_bundle(&bundle_dir).is_err());
+    std::fs::write(&tampered, &tracking)?;
+    request.verify_evidence_bundle(&bundle_dir)?;
+
+    // Unlisted file is detected
+    std::fs::write(bundle_dir.join("extra.txt"), b"not in the QR")?;
+    assert!(request.verify_evidence_bundle(&bundle_dir).is_err());
+    std::fs::remove_file(bundle_dir.join("extra.txt"))?;
+
+    // Missing file is detected
+    std::fs::remove_file(bundle_dir.join(&buyer_photo.file_name))?;
+    assert!(request.verify_evidence_bundle(&bundle_dir).is_err());
+
+    let _ = std::fs::remove_dir_all(bundle_dir);
+    let _ = std::fs::remove_dir_all(blob_dir);
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: evidence is frozen after the deadline and once the dispute is resolved
+#[tokio::test]
+async fn test_evidence_frozen_after_deadline() -> Result<()> {
+    let (pool, db_path) = setup_test_db("dispute_evidence")?;
+    let escrow = create_disputed_escrow(&pool)?;
+    let chain = SimulatedChain::new(&MockNetwork::new());
+    let (service, blob_dir) = evidence_service(&pool, &chain);
+    let escrow_id = uuid(&escrow.id);
+    let buyer = uuid(&escrow.buyer_id);
+    let note = b"Vendor stopped answering after payment".to_vec();
+
+    let mut conn = pool.get()?;
+    let opened = EscrowEventRecord::last_transition_to(&mut conn, &escrow.id, EscrowStatus::Disputed)?
+        .expect("dispute event");
+    assert_eq!(
+        service.deadline(escrow_id).await?,
+        opened.created_at + chrono::Duration::from_std(EVIDENCE_WINDOW)?
+    );
+
+    chain.advance(chrono::Duration::hours(71));
+    service
+        .submit(escrow_id, buyer, EvidenceKind::Message, None, note.clone())
+        .await?;
+
+    chain.advance(chrono::Duration::hours(2));
+    let late = service
+        .submit(escrow_id, buyer, EvidenceKind::Message, None, note.clone())
+        .await;
+    assert!(late.is_err(), "evidence accepted after the deadline");
+    assert_eq!(service.list(escrow_id).await?.len(), 1);
+
+    // A second dispute (fresh escrow) is closed by the arbiter's ruling
+    let resolved = create_disputed_escrow(&pool)?;
+    EscrowEventRecord::record_transition(
+        &mut conn,
+        &resolved.id,
+        EscrowStatus::ResolvedBuyer,
+        &EscrowActor::user(&resolved.arbiter_id),
+        None,
+    )?;
+    assert!(service
+        .submit(uuid(&resolved.id), uuid(&resolved.buyer_id), EvidenceKind::Message, None, note)
+        .await
+        .is_err());
+
+    let _ = std::fs::remove_dir_all(blob_dir);
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: blobs written under a retired key are moved to the new key
+#[tokio::test]
+async fn test_evidence_reencrypted_after_key_rotation() -> Result<()> {
+    use server::crypto::encryption::{is_encrypted_with, register_previous_key};
+
+    let (pool, db_path) = setup_test_db("dispute_evidence")?;
+    let escrow = create_disputed_escrow(&pool)?;
+    let chain = SimulatedChain::new(&MockNetwork::new());
+    let (old_service, blob_dir) = evidence_service(&pool, &chain);
+    let escrow_id = uuid(&escrow.id);
+    let note = b"Tracking number never activated".to_vec();
+    old_service
+        .submit(escrow_id, uuid(&escrow.buyer_id), EvidenceKind::Message, None, note.clone())
+        .await?;
+
+    let new_key = vec![9u8; 32];
+    let service = DisputeEvidenceService::new(
+        pool.clone(),
+        EvidenceStore::Local(blob_dir.clone()),
+        new_key.clone(),
+        EVIDENCE_WINDOW,
+    )
+    .with_clock(chain.shared());
+    register_previous_key(&[7u8; 32])?;
+
+    assert_eq!(service.reencrypt_all().await?, (1, 0));
+    let evidence = service.list(escrow_id).await?;
+    let blob = std::fs::read(blob_dir.join(&evidence[0].storage_ref))?;
+    assert!(is_encrypted_with(&blob, &new_key));
+    assert_eq!(service.read(&evidence[0]).await?, note);
+    assert_eq!(service.reencrypt_all().await?, (0, 0));
+
+    let _ = std::fs::remove_dir_all(blob_dir);
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: per-party file limit and rejected formats
+#[tokio::test]
+async fn test_evidence_limits() -> Result<()> {
+    let (pool, db_path) = setup_test_db("dispute_evidence")?;
+    let escrow = create_disputed_escrow(&pool)?;
+    let chain = SimulatedChain::new(&MockNetwork::new());
+    let (service, blob_dir) = evidence_service(&pool, &chain);
+    let escrow_id = uuid(&escrow.id);
+    let buyer = uuid(&escrow.buyer_id);
+
+    // Windows executable
+    assert!(service
+        .submit(escrow_id, buyer, EvidenceKind::Document, None, b"MZ\x90\x00\x03\x00\x00\x00".to_vec())
+        .await
+        .is_err());
+    assert!(service
+        .submit(escrow_id, buyer, EvidenceKind::Document, None, Vec::new())
+        .await
+        .is_err());
+
+    for i in 0..MAX_FILES_PER_PARTY {
+        service
+            .submit(escrow_id, buyer, EvidenceKind::Message, None, format!("message {}", i).into_bytes())
+            .await?;
+    }
+    assert!(service
+        .submit(escrow_id, buyer, EvidenceKind::Message, None, b"one too many".to_vec())
+        .await
+        .is_err());
+
+    // The vendor has their own quota
+    service
+        .submit(escrow_id, uuid(&escrow.vendor_id), EvidenceKind::Message, None, b"reply".to_vec())
+        .await?;
+
+    let _ = std::fs::remove_dir_all(blob_dir);
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: a full evidence list still fits in a single QR code
+#[test]
+fn test_full_evidence_list_fits_in_qr() -> Result<()> {
+    // Version 40, L error correction, byte mode
+    let max_qr_bytes = 2953;
+
+    let evidence = (0..MAX_FILES_PER_PARTY * 2)
+        .map(|i| EvidenceHash {
+            file_name: format!("{}-{}.jpg", if i % 2 == 0 { "buyer" } else { "vendor" }, i / 2 + 1),
+            sha256: "ab".repeat(32),
+            size_bytes: 10 * 1024 * 1024,
+            kind: "document".to_string(),
+            submitted_by: if i % 2 == 0 { "buyer" } else { "vendor" }.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    let request = DisputeRequest {
+        escrow_id: Uuid::new_v4(),
+        buyer_id: Uuid::new_v4(),
+        vendor_id: Uuid::new_v4(),
+        amount: ONE_XMR as u64,
+        buyer_claim: "A".repeat(250),
+        vendor_response: Some("B".repeat(250)),
+        dispute_opened_at: chrono::Utc::now().timestamp(),
+        evidence_file_count: evidence.len(),
+        evidence,
+        evidence_deadline: chrono::Utc::now().timestamp(),
+        partial_tx_hex: "c".repeat(200),
+        nonce: "ab".repeat(32),
+    };
+    request.validate()?;
+
+    let json = serde_json::to_string(&request)?;
+    assert!(
+        json.len() < max_qr_bytes,
+        "Dispute payload {} exceeds QR capacity {}",
+        json.len(),
+        max_qr_bytes
+    );
+    Ok(())
+}
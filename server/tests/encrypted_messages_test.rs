@@ -0,0 +1,138 @@
+//! Encrypted order messages: registered public keys and ciphertext-only storage
+//!
+//! Exercises `UserEncryptionKey` and `OrderMessage` against an encrypted
+//! database with all migrations applied.
+//!
+//! Run with: cargo test --package server --test encrypted_messages_test
+
+mod common;
+
+use anyhow::Result;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use common::{
+    create_order, create_user, run_pending_migrations, setup_test_db, setup_test_db_before,
+};
+use diesel::prelude::*;
+use diesel::sql_types::{Integer, Text};
+use server::crypto::message_envelope::{
+    EnvelopeRecipient, MessageEnvelope, ENVELOPE_ALGORITHM, ENVELOPE_VERSION,
+};
+use server::models::encryption_key::UserEncryptionKey;
+use server::models::message::{NewOrderMessage, OrderMessage};
+use uuid::Uuid;
+
+fn public_key(fill: u8) -> String {
+    let mut key = vec![0x04];
+    key.extend_from_slice(&[fill; 64]);
+    BASE64_STANDARD.encode(key)
+}
+
+fn envelope_for(user_ids: &[&str]) -> MessageEnvelope {
+    MessageEnvelope {
+        version: ENVELOPE_VERSION,
+        algorithm: ENVELOPE_ALGORITHM.to_string(),
+        ephemeral_public_key: public_key(9),
+        nonce: BASE64_STANDARD.encode([1u8; 12]),
+        ciphertext: BASE64_STANDARD.encode([2u8; 40]),
+        recipients: user_ids
+            .iter()
+            .map(|user_id| EnvelopeRecipient {
+                user_id: user_id.to_string(),
+                nonce: BASE64_STANDARD.encode([3u8; 12]),
+                wrapped_key: BASE64_STANDARD.encode([4u8; 48]),
+            })
+            .collect(),
+    }
+}
+
+/// Test: keys are registered, rotated and rejected when malformed
+#[test]
+fn test_register_and_rotate_encryption_key() -> Result<()> {
+    let (pool, db_path) = setup_test_db("encrypted_messages")?;
+    let mut conn = pool.get()?;
+    let buyer_id = create_user(&mut conn, "buyer")?;
+    let vendor_id = create_user(&mut conn, "vendor")?;
+
+    assert!(UserEncryptionKey::find_by_user_id(&mut conn, &buyer_id)?.is_none());
+
+    UserEncryptionKey::upsert(&mut conn, &buyer_id, &public_key(1))?;
+    let rotated = UserEncryptionKey::upsert(&mut conn, &buyer_id, &public_key(2))?;
+    assert_eq!(rotated.public_key, public_key(2));
+
+    assert!(UserEncryptionKey::upsert(&mut conn, &vendor_id, "not-a-key").is_err());
+
+    let keys = UserEncryptionKey::find_by_user_ids(&mut conn, &[&buyer_id, &vendor_id])?;
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0].user_id, buyer_id);
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: only the envelope is stored and it round-trips unchanged
+#[test]
+fn test_message_stores_envelope_only() -> Result<()> {
+    let (pool, db_path) = setup_test_db("encrypted_messages")?;
+    let mut conn = pool.get()?;
+    let buyer_id = create_user(&mut conn, "buyer")?;
+    let vendor_id = create_user(&mut conn, "vendor")?;
+    let order_id = create_order(&mut conn, &buyer_id, &vendor_id, "pending")?;
+
+    let envelope = envelope_for(&[&buyer_id, &vendor_id]);
+    envelope.validate()?;
+    OrderMessage::create(
+        NewOrderMessage::new(order_id.clone(), buyer_id.clone(), &envelope)?,
+        &mut conn,
+    )?;
+
+    let messages = OrderMessage::get_by_order_id(&order_id, &mut conn)?;
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].message, None);
+    assert_eq!(messages[0].envelope()?, Some(envelope.clone()));
+    assert!(envelope.is_addressed_to(&vendor_id));
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: plaintext messages sent before encryption survive the migration
+/// as legacy messages next to new encrypted ones
+#[test]
+fn test_migration_keeps_legacy_messages() -> Result<()> {
+    let (pool, db_path) =
+        setup_test_db_before("encrypted_messages", Some("add_user_encryption_keys"))?;
+    let mut conn = pool.get()?;
+    let buyer_id = create_user(&mut conn, "buyer")?;
+    let vendor_id = create_user(&mut conn, "vendor")?;
+    let order_id = create_order(&mut conn, &buyer_id, &vendor_id, "pending")?;
+
+    diesel::sql_query(
+        "INSERT INTO order_messages (id, order_id, sender_id, message, created_at) \
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind::<Text, _>(Uuid::new_v4().to_string())
+    .bind::<Text, _>(&order_id)
+    .bind::<Text, _>(&vendor_id)
+    .bind::<Text, _>("Shipped yesterday")
+    .bind::<Integer, _>(1)
+    .execute(&mut conn)?;
+
+    run_pending_migrations(&mut conn)?;
+
+    let envelope = envelope_for(&[&buyer_id, &vendor_id]);
+    OrderMessage::create(
+        NewOrderMessage::new(order_id.clone(), buyer_id.clone(), &envelope)?,
+        &mut conn,
+    )?;
+
+    let messages = OrderMessage::get_by_order_id(&order_id, &mut conn)?;
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0].message.as_deref(), Some("Shipped yesterday"));
+    assert_eq!(messages[0].envelope()?, None);
+    assert_eq!(messages[1].message, None);
+    assert_eq!(messages[1].envelope()?, Some(envelope));
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
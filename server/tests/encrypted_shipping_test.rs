@@ -0,0 +1,147 @@
+//! Encrypted shipping addresses: per-vendor envelopes and retention purge
+//!
+//! Exercises `Order::create_checkout` and `Order::purge_shipping_data`
+//! against an encrypted database with all migrations applied.
+//!
+//! Run with: cargo test --package server --test encrypted_shipping_test
+
+mod common;
+
+use anyhow::Result;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use common::{create_listing, create_user, setup_test_db, ONE_XMR};
+use server::crypto::message_envelope::{
+    EnvelopeRecipient, MessageEnvelope, ENVELOPE_ALGORITHM, ENVELOPE_VERSION,
+};
+use server::models::listing::Listing;
+use server::models::order::{CheckoutLine, Order, OrderStatus};
+
+fn line(listing_id: &str) -> CheckoutLine {
+    CheckoutLine {
+        listing_id: listing_id.to_string(),
+        quantity: 1,
+    }
+}
+
+fn envelope_for(user_ids: &[&str]) -> MessageEnvelope {
+    let mut ephemeral_key = vec![0x04];
+    ephemeral_key.extend_from_slice(&[9u8; 64]);
+    MessageEnvelope {
+        version: ENVELOPE_VERSION,
+        algorithm: ENVELOPE_ALGORITHM.to_string(),
+        ephemeral_public_key: BASE64_STANDARD.encode(ephemeral_key),
+        nonce: BASE64_STANDARD.encode([1u8; 12]),
+        ciphertext: BASE64_STANDARD.encode([2u8; 80]),
+        recipients: user_ids
+            .iter()
+            .map(|user_id| EnvelopeRecipient {
+                user_id: user_id.to_string(),
+                nonce: BASE64_STANDARD.encode([3u8; 12]),
+                wrapped_key: BASE64_STANDARD.encode([4u8; 48]),
+            })
+            .collect(),
+    }
+}
+
+fn stored_envelope(order: &Order) -> Result<MessageEnvelope> {
+    let raw = order
+        .shipping_address
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("Order {} has no shipping address", order.id))?;
+    Ok(serde_json::from_str(raw)?)
+}
+
+/// Test: each vendor's order stores a copy of the envelope only they can open
+#[test]
+fn test_checkout_stores_one_envelope_per_vendor() -> Result<()> {
+    let (pool, db_path) = setup_test_db("encrypted_shipping")?;
+    let mut conn = pool.get()?;
+    let buyer = create_user(&mut conn, "buyer")?;
+    let vendor_a = create_user(&mut conn, "vendor")?;
+    let vendor_b = create_user(&mut conn, "vendor")?;
+    let apples = create_listing(&mut conn, &vendor_a, "Test Product", ONE_XMR, 10)?;
+    let honey = create_listing(&mut conn, &vendor_b, "Test Product", ONE_XMR, 10)?;
+
+    let envelope = envelope_for(&[&vendor_a, &vendor_b]);
+    let created = Order::create_checkout(
+        &mut conn,
+        &buyer,
+        &[line(&apples), line(&honey)],
+        Some(&envelope),
+    )?;
+    assert_eq!(created.len(), 2);
+
+    for (order, _) in &created {
+        let stored = stored_envelope(order)?;
+        assert_eq!(stored.recipients.len(), 1);
+        assert!(stored.is_addressed_to(&order.vendor_id));
+        assert_eq!(stored.ciphertext, envelope.ciphertext);
+        assert!(order.shipping_notes.is_none());
+    }
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: an address not encrypted to every vendor rolls back the checkout
+#[test]
+fn test_checkout_rejects_missing_vendor() -> Result<()> {
+    let (pool, db_path) = setup_test_db("encrypted_shipping")?;
+    let mut conn = pool.get()?;
+    let buyer = create_user(&mut conn, "buyer")?;
+    let vendor_a = create_user(&mut conn, "vendor")?;
+    let vendor_b = create_user(&mut conn, "vendor")?;
+    let apples = create_listing(&mut conn, &vendor_a, "Test Product", ONE_XMR, 10)?;
+    let honey = create_listing(&mut conn, &vendor_b, "Test Product", ONE_XMR, 10)?;
+
+    let result = Order::create_checkout(
+        &mut conn,
+        &buyer,
+        &[line(&apples), line(&honey)],
+        Some(&envelope_for(&[&vendor_a])),
+    );
+    assert!(result.is_err());
+    assert!(Order::find_by_buyer(&mut conn, buyer)?.is_empty());
+    assert_eq!(Listing::find_by_id(&mut conn, apples)?.stock, 10);
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: only settled orders past the cutoff lose their address
+#[test]
+fn test_purge_shipping_data_of_settled_orders() -> Result<()> {
+    let (pool, db_path) = setup_test_db("encrypted_shipping")?;
+    let mut conn = pool.get()?;
+    let buyer = create_user(&mut conn, "buyer")?;
+    let vendor = create_user(&mut conn, "vendor")?;
+    let apples = create_listing(&mut conn, &vendor, "Test Product", ONE_XMR, 10)?;
+    let envelope = envelope_for(&[&vendor]);
+
+    let mut settled = Order::create_checkout(&mut conn, &buyer, &[line(&apples)], Some(&envelope))?;
+    let (settled, _) = settled.pop().expect("one order");
+    let mut open = Order::create_checkout(&mut conn, &buyer, &[line(&apples)], Some(&envelope))?;
+    let (open, _) = open.pop().expect("one order");
+
+    for status in [OrderStatus::Funded, OrderStatus::Shipped, OrderStatus::Completed] {
+        Order::update_status(&mut conn, settled.id.clone(), status)?;
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+
+    // Still within the retention period
+    assert_eq!(Order::purge_shipping_data(&mut conn, now - chrono::Duration::days(1))?, 0);
+    assert!(Order::find_by_id(&mut conn, settled.id.clone())?.shipping_address.is_some());
+
+    // Past it: the completed order is purged, the pending one is kept
+    assert_eq!(Order::purge_shipping_data(&mut conn, now + chrono::Duration::days(1))?, 1);
+    assert!(Order::find_by_id(&mut conn, settled.id.clone())?.shipping_address.is_none());
+    assert!(Order::find_by_id(&mut conn, open.id.clone())?.shipping_address.is_some());
+
+    // Nothing left to purge
+    assert_eq!(Order::purge_shipping_data(&mut conn, now + chrono::Duration::days(1))?, 0);
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
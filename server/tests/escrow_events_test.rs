@@ -0,0 +1,220 @@
+//! Escrow event log: recording, replay, append-only guard and admin timeline
+//!
+//! Run with: cargo test --package server --test escrow_events_test
+
+mod common;
+
+use actix_web::{test, web, App};
+use anyhow::Result;
+use common::{
+    create_order, create_user, run_pending_migrations, setup_test_db, setup_test_db_before, ONE_XMR,
+};
+use diesel::prelude::*;
+use server::db::db_transition_escrow;
+use server::handlers::monitoring;
+use server::models::escrow::{Escrow, EscrowStatus, NewEscrow};
+use server::models::escrow_event::{replay, EscrowActor, EscrowEventRecord};
+use uuid::Uuid;
+
+struct Parties {
+    buyer: String,
+    vendor: String,
+    arbiter: String,
+    order_id: String,
+}
+
+fn setup_parties(conn: &mut SqliteConnection) -> Result<Parties> {
+    let buyer = create_user(conn, "buyer")?;
+    let vendor = create_user(conn, "vendor")?;
+    let arbiter = create_user(conn, "arbiter")?;
+
+    let order_id = create_order(conn, &buyer, &vendor, "pending")?;
+
+    Ok(Parties {
+        buyer,
+        vendor,
+        arbiter,
+        order_id,
+    })
+}
+
+fn new_escrow(parties: &Parties) -> NewEscrow {
+    NewEscrow {
+        id: Uuid::new_v4().to_string(),
+        order_id: parties.order_id.clone(),
+        buyer_id: parties.buyer.clone(),
+        vendor_id: parties.vendor.clone(),
+        arbiter_id: parties.arbiter.clone(),
+        amount: ONE_XMR,
+        status: "created".to_string(),
+    }
+}
+
+/// Test: every transition is recorded and the replay matches escrows.status
+#[actix_web::test]
+async fn test_dispute_history_is_replayed() -> Result<()> {
+    let (pool, db_path) = setup_test_db("escrow_events")?;
+    let (parties, escrow) = {
+        let mut conn = pool.get()?;
+        let parties = setup_parties(&mut conn)?;
+        let escrow = Escrow::create(&mut conn, new_escrow(&parties))?;
+        (parties, escrow)
+    };
+    let escrow_id: Uuid = escrow.id.parse()?;
+
+    let orchestrator = EscrowActor::System("escrow_orchestrator");
+    let monitor = EscrowActor::System("blockchain_monitor");
+    db_transition_escrow(&pool, escrow_id, EscrowStatus::Funded, orchestrator, None).await?;
+    db_transition_escrow(
+        &pool,
+        escrow_id,
+        EscrowStatus::Active,
+        monitor.clone(),
+        None,
+    )
+    .await?;
+    // Same status again: nothing recorded
+    db_transition_escrow(&pool, escrow_id, EscrowStatus::Active, monitor, None).await?;
+    db_transition_escrow(
+        &pool,
+        escrow_id,
+        EscrowStatus::Disputed,
+        EscrowActor::user(&parties.buyer),
+        Some("Package never arrived"),
+    )
+    .await?;
+    let escrow = db_transition_escrow(
+        &pool,
+        escrow_id,
+        EscrowStatus::ResolvedBuyer,
+        EscrowActor::user(&parties.arbiter),
+        Some("Dispute resolved in favour of buyer"),
+    )
+    .await?;
+    assert_eq!(escrow.status, "resolved_buyer");
+
+    let records = EscrowEventRecord::find_by_escrow(&mut *pool.get()?, &escrow.id)?;
+    let kinds: Vec<&str> = records.iter().map(|r| r.event_type.as_str()).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            "created",
+            "status_changed",
+            "funded",
+            "disputed",
+            "status_changed"
+        ]
+    );
+    assert_eq!(records[0].actor, format!("user:{}", parties.buyer));
+    assert_eq!(records[3].from_status.as_deref(), Some("active"));
+    assert_eq!(records[3].reason.as_deref(), Some("Package never arrived"));
+    assert_eq!(records[4].actor, format!("user:{}", parties.arbiter));
+
+    let state = replay(&records)?;
+    assert_eq!(state.status.as_str(), escrow.status);
+    assert_eq!(state.vendor_id, parties.vendor);
+    assert_eq!(state.amount, ONE_XMR as u64);
+    assert_eq!(state.disputed_by, Some(parties.buyer.clone()));
+    assert_eq!(state.event_count, 5);
+
+    // Append-only: history cannot be rewritten
+    let mut conn = pool.get()?;
+    let rewrite =
+        diesel::sql_query("UPDATE escrow_events SET actor = 'user:nobody'").execute(&mut conn);
+    assert!(rewrite.is_err());
+    let erase = diesel::sql_query("DELETE FROM escrow_events").execute(&mut conn);
+    assert!(erase.is_err());
+    assert_eq!(
+        EscrowEventRecord::find_by_escrow(&mut conn, &escrow.id)?.len(),
+        5
+    );
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: escrows created before the event log get a backfilled history
+#[actix_web::test]
+async fn test_migration_backfills_existing_escrows() -> Result<()> {
+    let (pool, db_path) = setup_test_db_before("escrow_events", Some("add_escrow_events"))?;
+    let escrow_id = {
+        let mut conn = pool.get()?;
+        let parties = setup_parties(&mut conn)?;
+        let mut escrow = new_escrow(&parties);
+        escrow.status = "disputed".to_string();
+        diesel::insert_into(server::schema::escrows::table)
+            .values(&escrow)
+            .execute(&mut conn)?;
+
+        run_pending_migrations(&mut conn)?;
+        escrow.id
+    };
+
+    let records = EscrowEventRecord::find_by_escrow(&mut *pool.get()?, &escrow_id)?;
+    assert_eq!(records.len(), 2);
+    assert!(records.iter().all(|r| r.actor == "system:migration"));
+
+    let state = replay(&records)?;
+    assert_eq!(state.status, EscrowStatus::Disputed);
+    assert_eq!(state.amount, ONE_XMR as u64);
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: admin timeline flags a status changed behind the event log's back
+#[actix_web::test]
+async fn test_admin_timeline_reports_consistency() -> Result<()> {
+    let (pool, db_path) = setup_test_db("escrow_events")?;
+    let escrow = {
+        let mut conn = pool.get()?;
+        let parties = setup_parties(&mut conn)?;
+        Escrow::create(&mut conn, new_escrow(&parties))?
+    };
+    db_transition_escrow(
+        &pool,
+        escrow.id.parse()?,
+        EscrowStatus::Funded,
+        EscrowActor::System("escrow_orchestrator"),
+        Some("Multisig setup complete"),
+    )
+    .await?;
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .service(web::scope("/admin").service(monitoring::get_escrow_timeline)),
+    )
+    .await;
+    let uri = format!("/admin/escrows/{}/timeline", escrow.id);
+
+    let body: serde_json::Value =
+        test::call_and_read_body_json(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+    assert_eq!(body["consistent"], true);
+    assert_eq!(body["replayed"]["status"], "funded");
+    assert_eq!(body["events"].as_array().map(Vec::len), Some(2));
+    assert_eq!(body["events"][1]["reason"], "Multisig setup complete");
+    assert_eq!(body["events"][1]["event"]["type"], "status_changed");
+
+    // Status written directly, bypassing the event log
+    diesel::update(server::schema::escrows::table)
+        .set(server::schema::escrows::status.eq("completed"))
+        .execute(&mut *pool.get()?)?;
+
+    let body: serde_json::Value =
+        test::call_and_read_body_json(&app, test::TestRequest::get().uri(&uri).to_request()).await;
+    assert_eq!(body["consistent"], false);
+    assert_eq!(body["current_status"], "completed");
+
+    let resp = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri("/admin/escrows/not-a-uuid/timeline")
+            .to_request(),
+    )
+    .await;
+    assert_eq!(resp.status(), actix_web::http::StatusCode::BAD_REQUEST);
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
@@ -0,0 +1,519 @@
+//! Property tests for the escrow state machine
+//!
+//! Random sequences of status changes are pushed through
+//! `EscrowEventRecord::record_transition` (the path behind
+//! `db_update_escrow_status` and `db_transition_escrow`), and random escrows
+//! through the code that moves them: `release_funds` / `refund_funds`, the
+//! blockchain and timeout monitors and the air-gap decision import.
+//! Whatever the input, only transitions from the table in `EscrowStatus`
+//! may be stored.
+//!
+//! Run with: cargo test --package server --test escrow_state_machine_test
+
+mod common;
+
+use anyhow::Result;
+use chrono::Duration;
+use common::{create_escrow, setup_test_db, ONE_XMR};
+use diesel::prelude::*;
+use monero_marketplace_common::clock::ChainClock;
+use monero_marketplace_common::types::MoneroConfig;
+use monero_marketplace_test_support::mock_rpc::{create_multisig_wallets, MockWalletRpc};
+use monero_marketplace_test_support::{MockNetwork, SimulatedChain};
+use proptest::prelude::*;
+use proptest::test_runner::TestRunner;
+use server::config::TimeoutConfig;
+use server::db::{db_load_escrow, db_update_escrow_status, DbPool};
+use server::handlers::airgap_dispute::record_arbiter_decision;
+use server::models::audit_log::AuditEntry;
+use server::models::escrow::{Escrow, EscrowStatus};
+use server::models::escrow_event::{replay, EscrowActor, EscrowEventRecord};
+use server::schema::escrows;
+use server::services::airgap::{ArbiterDecision, ArbiterResolution};
+use server::services::blockchain_monitor::{BlockchainMonitor, MonitorConfig};
+use server::services::escrow::{EscrowOrchestrator, PAYOUT_NOT_ALLOWED_ERROR};
+use server::services::timeout_monitor::TimeoutMonitor;
+use server::services::wallet_session_manager::WalletSessionManager;
+use server::wallet_manager::WalletManager;
+use server::wallet_pool::WalletPool;
+use server::websocket::WebSocketServer;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+/// Payout address recorded with the arbiter's ruling
+const RULING_ADDRESS: &str = "9RulingAddressxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx";
+
+/// Any other well-formed payout address
+const OTHER_ADDRESS: &str = "9OtherAddressxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxxx";
+
+/// Who asks for a payout
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Caller {
+    Buyer,
+    Vendor,
+    Arbiter,
+    Stranger,
+}
+
+impl Caller {
+    fn id(self, escrow: &Escrow) -> Uuid {
+        let id = match self {
+            Caller::Buyer => &escrow.buyer_id,
+            Caller::Vendor => &escrow.vendor_id,
+            Caller::Arbiter => &escrow.arbiter_id,
+            Caller::Stranger => return Uuid::new_v4(),
+        };
+        id.parse().unwrap_or_default()
+    }
+}
+
+/// Buyer, vendor and arbiter wallet RPCs on one mock network
+async fn start_rpcs(network: &MockNetwork) -> Result<[MockWalletRpc; 3]> {
+    Ok([
+        MockWalletRpc::start("buyer", network).await?,
+        MockWalletRpc::start("vendor", network).await?,
+        MockWalletRpc::start("arbiter", network).await?,
+    ])
+}
+
+fn wallet_manager(rpcs: &[MockWalletRpc; 3]) -> Result<WalletManager> {
+    let configs = rpcs
+        .iter()
+        .map(|rpc| MoneroConfig {
+            rpc_url: rpc.url().to_string(),
+            rpc_user: None,
+            rpc_password: None,
+            timeout_seconds: 10,
+        })
+        .collect();
+    WalletManager::new(configs)
+}
+
+/// Escrow wallets as left by the multisig setup, returns the multisig address
+fn create_escrow_wallets(rpcs: &[MockWalletRpc; 3], escrow_id: &str) -> Result<String> {
+    create_multisig_wallets(
+        [&rpcs[0], &rpcs[1], &rpcs[2]],
+        [
+            &format!("buyer_temp_escrow_{}", escrow_id),
+            &format!("vendor_temp_escrow_{}", escrow_id),
+            &format!("arbiter_temp_escrow_{}", escrow_id),
+        ],
+    )
+}
+
+async fn stored_status(pool: &DbPool, escrow_id: Uuid) -> EscrowStatus {
+    db_load_escrow(pool, escrow_id)
+        .await
+        .and_then(|escrow| escrow.get_status())
+        .unwrap_or_else(|e| panic!("escrow {} unreadable: {:#}", escrow_id, e))
+}
+
+/// Every stored (from, to) pair is legal and the replay matches escrows.status
+fn assert_history_is_legal(conn: &mut SqliteConnection, escrow_id: &str) -> Result<()> {
+    let escrow = Escrow::find_by_id(conn, escrow_id.to_string())?;
+    let records = EscrowEventRecord::find_by_escrow(conn, escrow_id)?;
+
+    for record in records.iter().skip(1) {
+        let from: EscrowStatus = record.from_status.as_deref().unwrap_or_default().parse()?;
+        let to: EscrowStatus = record.to_status.parse()?;
+        assert!(
+            from.can_transition_to(to),
+            "illegal transition stored: {} -> {}",
+            from,
+            to
+        );
+    }
+
+    let state = replay(&records)?;
+    assert_eq!(state.status, escrow.get_status()?);
+    Ok(())
+}
+
+fn any_status() -> impl Strategy<Value = EscrowStatus> {
+    prop::sample::select(EscrowStatus::ALL.to_vec())
+}
+
+fn runner() -> TestRunner {
+    TestRunner::new(ProptestConfig::with_cases(64))
+}
+
+/// Mostly the statuses a payout starts from, plus any other
+fn payout_status() -> impl Strategy<Value = EscrowStatus> {
+    prop_oneof![
+        Just(EscrowStatus::Active),
+        Just(EscrowStatus::Overfunded),
+        Just(EscrowStatus::ResolvedBuyer),
+        Just(EscrowStatus::ResolvedVendor),
+        any_status(),
+    ]
+}
+
+fn any_caller() -> impl Strategy<Value = Caller> {
+    prop::sample::select(vec![
+        Caller::Buyer,
+        Caller::Vendor,
+        Caller::Arbiter,
+        Caller::Stranger,
+    ])
+}
+
+fn any_resolution() -> impl Strategy<Value = ArbiterResolution> {
+    prop_oneof![
+        Just(ArbiterResolution::Buyer),
+        Just(ArbiterResolution::Vendor),
+        (0..=ONE_XMR as u64, 0..=ONE_XMR as u64).prop_map(|(buyer_amount, vendor_amount)| {
+            ArbiterResolution::Split {
+                buyer_amount,
+                vendor_amount,
+            }
+        }),
+    ]
+}
+
+/// Test: arbitrary requested statuses, legal moves apply and illegal ones change nothing
+#[test]
+fn test_random_requests_never_store_illegal_transitions() -> Result<()> {
+    let (pool, db_path) = setup_test_db("escrow_state_machine")?;
+    let actor = EscrowActor::System("proptest");
+
+    runner()
+        .run(&prop::collection::vec(any_status(), 1..24), |targets| {
+            let mut conn = pool.get().unwrap();
+            let escrow = create_escrow(&mut conn, EscrowStatus::Created).unwrap();
+
+            let mut expected = EscrowStatus::Created;
+            for target in targets {
+                let result = EscrowEventRecord::record_transition(
+                    &mut conn, &escrow.id, target, &actor, None,
+                );
+                let stored = Escrow::find_by_id(&mut conn, escrow.id.clone())
+                    .unwrap()
+                    .get_status()
+                    .unwrap();
+
+                if target == expected || expected.can_transition_to(target) {
+                    prop_assert!(result.is_ok());
+                    expected = target;
+                } else {
+                    prop_assert!(result.is_err());
+                }
+                prop_assert_eq!(stored, expected);
+            }
+
+            assert_history_is_legal(&mut conn, &escrow.id).unwrap();
+            Ok(())
+        })
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: random walks over legal moves record one event per step
+#[test]
+fn test_legal_walks_are_fully_recorded() -> Result<()> {
+    let (pool, db_path) = setup_test_db("escrow_state_machine")?;
+    let actor = EscrowActor::System("proptest");
+
+    runner()
+        .run(
+            &prop::collection::vec(any::<prop::sample::Index>(), 1..16),
+            |choices| {
+                let mut conn = pool.get().unwrap();
+                let escrow = create_escrow(&mut conn, EscrowStatus::Created).unwrap();
+
+                let mut current = EscrowStatus::Created;
+                let mut steps = 0;
+                for choice in choices {
+                    let next = current.next_statuses();
+                    if next.is_empty() {
+                        prop_assert!(current.is_terminal());
+                        break;
+                    }
+                    current = *choice.get(&next);
+                    EscrowEventRecord::record_transition(
+                        &mut conn, &escrow.id, current, &actor, None,
+                    )
+                    .unwrap();
+                    steps += 1;
+                }
+
+                let records = EscrowEventRecord::find_by_escrow(&mut conn, &escrow.id).unwrap();
+                prop_assert_eq!(records.len(), steps + 1);
+                assert_history_is_legal(&mut conn, &escrow.id).unwrap();
+                Ok(())
+            },
+        )
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: db_update_escrow_status rejects a move outside the table
+#[tokio::test]
+async fn test_db_update_escrow_status_rejects_illegal_move() -> Result<()> {
+    let (pool, db_path) = setup_test_db("escrow_state_machine")?;
+    let escrow = create_escrow(&mut *pool.get()?, EscrowStatus::Created)?;
+    let escrow_id: Uuid = escrow.id.parse()?;
+
+    // Cannot skip from "created" straight to payout
+    let err = db_update_escrow_status(&pool, escrow_id, EscrowStatus::Completed)
+        .await
+        .expect_err("created -> completed must be rejected");
+    assert!(format!("{:#}", err).contains("Illegal escrow transition: created -> completed"));
+    assert_eq!(db_load_escrow(&pool, escrow_id).await?.status, "created");
+
+    db_update_escrow_status(&pool, escrow_id, EscrowStatus::Funded).await?;
+    db_update_escrow_status(&pool, escrow_id, EscrowStatus::Cancelled).await?;
+    assert!(
+        db_update_escrow_status(&pool, escrow_id, EscrowStatus::Funded)
+            .await
+            .is_err()
+    );
+
+    assert_history_is_legal(&mut *pool.get()?, &escrow.id)?;
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: the buyer releases and the vendor refunds a funded escrow, rulings
+/// are carried out by the arbiter to the ruling address, nothing else pays out
+#[test]
+fn test_payouts_only_by_entitled_caller() -> Result<()> {
+    let (pool, db_path) = setup_test_db("escrow_state_machine")?;
+    let system = actix_web::rt::System::new();
+    let network = MockNetwork::new();
+
+    let (rpcs, orchestrator) = system.block_on(async {
+        let rpcs = start_rpcs(&network).await?;
+        let session_manager = Arc::new(WalletSessionManager::new(Arc::new(WalletPool::new(
+            vec![],
+            std::env::temp_dir(),
+        ))));
+        let orchestrator = EscrowOrchestrator::new(
+            Arc::new(Mutex::new(wallet_manager(&rpcs)?)),
+            session_manager,
+            pool.clone(),
+            actix::Actor::start(WebSocketServer::default()),
+            vec![0u8; 32],
+        );
+        anyhow::Ok((rpcs, orchestrator))
+    })?;
+
+    let cases = (payout_status(), any_caller(), any::<bool>(), any::<bool>());
+    runner()
+        .run(&cases, |(status, caller, to_ruling, release)| {
+            system.block_on(async {
+                let escrow = create_escrow(&mut pool.get().unwrap(), status).unwrap();
+                let escrow_id: Uuid = escrow.id.parse().unwrap();
+
+                let multisig_address = create_escrow_wallets(&rpcs, &escrow.id).unwrap();
+                network.fund_unlocked(&multisig_address, 2 * ONE_XMR as u64);
+                diesel::update(escrows::table.filter(escrows::id.eq(&escrow.id)))
+                    .set((
+                        escrows::multisig_address.eq(&multisig_address),
+                        escrows::ruling_address.eq(RULING_ADDRESS),
+                    ))
+                    .execute(&mut pool.get().unwrap())
+                    .unwrap();
+
+                let address = if to_ruling {
+                    RULING_ADDRESS
+                } else {
+                    OTHER_ADDRESS
+                }
+                .to_string();
+                let (result, payout_status) = if release {
+                    (
+                        orchestrator
+                            .release_funds(escrow_id, caller.id(&escrow), address)
+                            .await,
+                        EscrowStatus::Releasing,
+                    )
+                } else {
+                    (
+                        orchestrator
+                            .refund_funds(escrow_id, caller.id(&escrow), address)
+                            .await,
+                        EscrowStatus::Refunding,
+                    )
+                };
+
+                let allowed = match (release, status, caller) {
+                    (true, EscrowStatus::Active | EscrowStatus::Overfunded, Caller::Buyer)
+                    | (false, EscrowStatus::Active | EscrowStatus::Overfunded, Caller::Vendor) => {
+                        true
+                    }
+                    (true, EscrowStatus::ResolvedVendor, Caller::Arbiter)
+                    | (false, EscrowStatus::ResolvedBuyer, Caller::Arbiter) => to_ruling,
+                    _ => false,
+                };
+
+                let stored = stored_status(&pool, escrow_id).await;
+                match result {
+                    Ok(_) => {
+                        prop_assert!(allowed, "{:?} paid out a {} escrow", caller, status);
+                        prop_assert_eq!(stored, payout_status);
+                    }
+                    Err(e) => {
+                        prop_assert!(!allowed, "allowed payout failed: {:#}", e);
+                        prop_assert!(
+                            e.to_string().starts_with(PAYOUT_NOT_ALLOWED_ERROR),
+                            "{:#}",
+                            e
+                        );
+                        prop_assert_eq!(stored, status);
+                    }
+                }
+
+                assert_history_is_legal(&mut pool.get().unwrap(), &escrow.id).unwrap();
+                Ok(())
+            })
+        })
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: the timeout and blockchain monitors only store legal transitions,
+/// whatever the status, deadline and funding of the escrow
+#[test]
+fn test_monitors_only_store_legal_transitions() -> Result<()> {
+    let (pool, db_path) = setup_test_db("escrow_state_machine")?;
+    let system = actix_web::rt::System::new();
+
+    let cases = (any_status(), any::<bool>(), any::<bool>(), 0u64..20);
+    runner()
+        .run(&cases, |(status, expired, funded, blocks)| {
+            system.block_on(async {
+                let network = MockNetwork::new();
+                let chain = SimulatedChain::new(&network);
+                let rpcs = start_rpcs(&network).await.unwrap();
+
+                let escrow = create_escrow(&mut pool.get().unwrap(), status).unwrap();
+                let escrow_id: Uuid = escrow.id.parse().unwrap();
+
+                // Funding doubles as the transaction a payout waits on
+                let multisig_address = create_escrow_wallets(&rpcs, &escrow.id).unwrap();
+                let tx_hash = if funded {
+                    network.fund(&multisig_address, ONE_XMR as u64)
+                } else {
+                    "0".repeat(64)
+                };
+                let deadline = if expired {
+                    chain.now_naive() - Duration::hours(1)
+                } else {
+                    chain.now_naive() + Duration::days(1)
+                };
+                diesel::update(escrows::table.filter(escrows::id.eq(&escrow.id)))
+                    .set((
+                        escrows::multisig_address.eq(&multisig_address),
+                        escrows::transaction_hash.eq(&tx_hash),
+                        escrows::expires_at.eq(deadline),
+                    ))
+                    .execute(&mut pool.get().unwrap())
+                    .unwrap();
+
+                let wallet_pool = Arc::new(WalletPool::new(
+                    rpcs.iter().map(MockWalletRpc::port).collect(),
+                    std::env::temp_dir(),
+                ));
+                let session_manager = Arc::new(WalletSessionManager::new(wallet_pool));
+                session_manager
+                    .get_or_create_session(escrow_id)
+                    .await
+                    .unwrap();
+
+                let websocket = actix::Actor::start(WebSocketServer::default());
+                let timeouts =
+                    TimeoutMonitor::new(pool.clone(), websocket.clone(), TimeoutConfig::default())
+                        .with_clock(chain.shared());
+                let blockchain = BlockchainMonitor::new(
+                    Arc::new(Mutex::new(wallet_manager(&rpcs).unwrap())),
+                    session_manager,
+                    pool.clone(),
+                    websocket,
+                    MonitorConfig::default(),
+                )
+                .with_clock(chain.shared());
+
+                chain.mine_blocks(blocks);
+                timeouts.check_timeouts().await;
+                blockchain.poll_escrows().await.unwrap();
+                chain.mine_blocks(blocks);
+                blockchain.poll_escrows().await.unwrap();
+
+                if status.is_terminal() {
+                    prop_assert_eq!(stored_status(&pool, escrow_id).await, status);
+                }
+                assert_history_is_legal(&mut pool.get().unwrap(), &escrow.id).unwrap();
+                Ok(())
+            })
+        })
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: an imported arbiter decision settles a disputed escrow within its
+/// amount and changes nothing otherwise
+#[test]
+fn test_arbiter_decisions_only_settle_disputes() -> Result<()> {
+    let (pool, db_path) = setup_test_db("escrow_state_machine")?;
+
+    let status = prop_oneof![Just(EscrowStatus::Disputed), any_status()];
+    runner()
+        .run(&(status, any_resolution()), |(status, resolution)| {
+            let mut conn = pool.get().unwrap();
+            let escrow = create_escrow(&mut conn, status).unwrap();
+            let escrow_id: Uuid = escrow.id.parse().unwrap();
+
+            let decision = ArbiterDecision {
+                escrow_id,
+                nonce: "proptest".to_string(),
+                decision: resolution.clone(),
+                reason: "Property test ruling".to_string(),
+                signed_tx_hex: "00".to_string(),
+                decision_signature: String::new(),
+                decided_at: 0,
+            };
+            let result = record_arbiter_decision(&mut conn, escrow_id, &decision, Uuid::new_v4());
+
+            let fits = match resolution {
+                ArbiterResolution::Split {
+                    buyer_amount,
+                    vendor_amount,
+                } => buyer_amount + vendor_amount <= ONE_XMR as u64,
+                _ => true,
+            };
+            let stored = Escrow::find_by_id(&mut conn, escrow.id.clone())
+                .unwrap()
+                .get_status()
+                .unwrap();
+
+            let audited = AuditEntry::find_by_entity(&mut conn, &escrow.id)
+                .unwrap()
+                .len();
+
+            if status == EscrowStatus::Disputed && fits {
+                prop_assert!(result.is_ok(), "{:?}", result.err());
+                prop_assert_eq!(stored, resolution.escrow_status());
+                prop_assert_eq!(audited, 1);
+            } else {
+                prop_assert!(result.is_err());
+                prop_assert_eq!(stored, status);
+                prop_assert_eq!(audited, 0);
+            }
+
+            assert_history_is_legal(&mut conn, &escrow.id).unwrap();
+            Ok(())
+        })
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
@@ -0,0 +1,328 @@
+//! Fiat-pegged listings: conversion at checkout, quote expiry and requote
+//!
+//! Run with: cargo test --package server --test fiat_pricing_test
+
+mod common;
+
+use anyhow::Result;
+use common::{create_user, setup_test_db, ONE_XMR};
+use diesel::prelude::*;
+use futures_util::future::BoxFuture;
+use monero_marketplace_common::clock::ChainClock;
+use monero_marketplace_test_support::{MockNetwork, SimulatedChain};
+use server::config::PriceOracleConfig;
+use server::db::DbPool;
+use server::models::escrow::{Escrow, NewEscrow};
+use server::models::listing::{FiatPrice, Listing};
+use server::models::order::{CheckoutLine, Order};
+use server::models::price_history::NewPriceRecord;
+use server::schema::{escrows, price_history};
+use server::services::fiat_pricing::FiatPricing;
+use server::services::price_conversion::{PriceOracle, PriceSource, SourceRates};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+/// Source quoting a USD rate the test can change
+struct UsdSource(Mutex<f64>);
+
+impl PriceSource for UsdSource {
+    fn name(&self) -> &'static str {
+        "test"
+    }
+
+    fn fetch<'a>(&'a self, _currencies: &'a [String]) -> BoxFuture<'a, Result<SourceRates>> {
+        Box::pin(async move {
+            let rate = *self.0.lock().map_err(|_| anyhow::anyhow!("poisoned"))?;
+            Ok(SourceRates::from([("usd".to_string(), rate)]))
+        })
+    }
+}
+
+struct Market {
+    pool: DbPool,
+    db_path: PathBuf,
+    source: Arc<UsdSource>,
+    oracle: Arc<PriceOracle>,
+    /// Pricing clock, a day behind the wall clock so quotes only expire
+    /// when it is advanced
+    chain: SimulatedChain,
+    pricing: FiatPricing,
+}
+
+impl Market {
+    fn new(usd_rate: f64) -> Result<Self> {
+        let (pool, db_path) = setup_test_db("fiat_pricing")?;
+        let source = Arc::new(UsdSource(Mutex::new(usd_rate)));
+        let oracle = Arc::new(PriceOracle::with_sources(
+            pool.clone(),
+            PriceOracleConfig {
+                proxy: None,
+                ..Default::default()
+            },
+            vec![source.clone()],
+        ));
+        let chain = SimulatedChain::starting_at(
+            &MockNetwork::new(),
+            chrono::Utc::now() - chrono::Duration::days(1),
+        );
+        let pricing = FiatPricing::new(pool.clone(), oracle.clone()).with_clock(chain.shared());
+        Ok(Self {
+            pool,
+            db_path,
+            source,
+            oracle,
+            chain,
+            pricing,
+        })
+    }
+
+    async fn set_usd_rate(&self, rate: f64) -> Result<()> {
+        *self
+            .source
+            .0
+            .lock()
+            .map_err(|_| anyhow::anyhow!("poisoned"))? = rate;
+        self.oracle.refresh().await?;
+        Ok(())
+    }
+}
+
+impl Drop for Market {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.db_path);
+    }
+}
+
+/// Listing priced in XMR, pegged to `usd_cents` if given
+fn create_listing(
+    conn: &mut SqliteConnection,
+    vendor_id: &str,
+    price_xmr: i64,
+    usd_cents: Option<i64>,
+) -> Result<String> {
+    let id = common::create_listing(conn, vendor_id, "Fiat pricing test listing", price_xmr, 10)?;
+    if let Some(amount) = usd_cents {
+        Listing::set_fiat_price(
+            conn,
+            id.clone(),
+            Some(FiatPrice {
+                currency: "usd".to_string(),
+                amount,
+            }),
+        )?;
+    }
+    Ok(id)
+}
+
+fn line(listing_id: &str, quantity: i32) -> CheckoutLine {
+    CheckoutLine {
+        listing_id: listing_id.to_string(),
+        quantity,
+    }
+}
+
+/// Let the 30-minute quote window of the default config pass
+fn expire_quotes(market: &Market) {
+    market.chain.advance(chrono::Duration::minutes(31));
+}
+
+/// Test: fiat items are converted at the current rate, which is locked in
+#[tokio::test]
+async fn test_checkout_locks_fiat_price() -> Result<()> {
+    let market = Market::new(150.0)?;
+    market.oracle.refresh().await?;
+    let quote = market.pricing.checkout_quote().await?;
+
+    let mut conn = market.pool.get()?;
+    let buyer = create_user(&mut conn, "buyer")?;
+    let vendor = create_user(&mut conn, "vendor")?;
+    let pegged = create_listing(&mut conn, &vendor, ONE_XMR, Some(30_000))?;
+    let plain = create_listing(&mut conn, &vendor, ONE_XMR, None)?;
+
+    // Without rates, a fiat-priced listing cannot be bought (stock untouched)
+    let err = Order::create_checkout(&mut conn, &buyer, &[line(&pegged, 1)], None).unwrap_err();
+    assert!(
+        err.to_string().starts_with("No current XMR/USD rate"),
+        "{}",
+        err
+    );
+    assert_eq!(Listing::find_by_id(&mut conn, pegged.clone())?.stock, 10);
+
+    let created = Order::create_checkout_quoted(
+        &mut conn,
+        &buyer,
+        &[line(&pegged, 2), line(&plain, 1)],
+        None,
+        &quote,
+    )?;
+    let (order, items) = &created[0];
+
+    // 2 × 300.00 USD at 150 USD/XMR + 1 XMR
+    assert_eq!(order.total_xmr, 4 * ONE_XMR + ONE_XMR);
+    let expires_at = order.quote_expires_at.expect("quote expiry");
+    assert_eq!(expires_at - market.chain.now_naive(), chrono::Duration::minutes(30));
+    assert!(!order.is_quote_expired(market.pricing.now()));
+
+    let fiat_item = items
+        .iter()
+        .find(|item| item.listing_id == pegged)
+        .expect("fiat item");
+    assert_eq!(fiat_item.unit_price_xmr, 2 * ONE_XMR);
+    assert_eq!(fiat_item.unit_price_fiat, Some(30_000));
+    assert_eq!(fiat_item.price_currency.as_deref(), Some("usd"));
+    assert_eq!(fiat_item.price_rate, Some(150.0));
+
+    let xmr_item = items
+        .iter()
+        .find(|item| item.listing_id == plain)
+        .expect("xmr item");
+    assert_eq!(xmr_item.unit_price_xmr, ONE_XMR);
+    assert!(xmr_item.fiat_price().is_none());
+
+    // An XMR-only order has no quote to expire
+    let created =
+        Order::create_checkout_quoted(&mut conn, &buyer, &[line(&plain, 1)], None, &quote)?;
+    assert!(created[0].0.quote_expires_at.is_none());
+
+    Ok(())
+}
+
+/// Test: an expired quote is requoted at the new rate, escrow amount included,
+/// until the escrow receives a deposit
+#[tokio::test]
+async fn test_expired_quote_is_requoted_until_funded() -> Result<()> {
+    let market = Market::new(150.0)?;
+    market.oracle.refresh().await?;
+    let quote = market.pricing.checkout_quote().await?;
+
+    let (order, escrow_id) = {
+        let mut conn = market.pool.get()?;
+        let buyer = create_user(&mut conn, "buyer")?;
+        let vendor = create_user(&mut conn, "vendor")?;
+        let arbiter = create_user(&mut conn, "arbiter")?;
+        let pegged = create_listing(&mut conn, &vendor, ONE_XMR, Some(15_000))?;
+        let (order, _) =
+            Order::create_checkout_quoted(&mut conn, &buyer, &[line(&pegged, 1)], None, &quote)?
+                .remove(0);
+        assert_eq!(order.total_xmr, ONE_XMR);
+
+        let escrow = Escrow::create(
+            &mut conn,
+            NewEscrow {
+                id: Uuid::new_v4().to_string(),
+                order_id: order.id.clone(),
+                buyer_id: buyer,
+                vendor_id: vendor,
+                arbiter_id: arbiter,
+                amount: order.total_xmr,
+                status: "created".to_string(),
+            },
+        )?;
+        let order = Order::set_escrow(&mut conn, order.id, escrow.id.clone())?;
+        (order, escrow.id)
+    };
+
+    // Quote still valid: nothing to do
+    assert!(market.pricing.requote_expired().await?.is_empty());
+
+    market.set_usd_rate(100.0).await?;
+    expire_quotes(&market);
+    assert!(order.is_quote_expired(market.pricing.now()));
+
+    let requoted = market.pricing.requote_expired().await?;
+    assert_eq!(requoted.len(), 1);
+    assert_eq!(requoted[0].total_xmr, 3 * ONE_XMR / 2);
+    assert!(!requoted[0].is_quote_expired(market.pricing.now()));
+
+    let mut conn = market.pool.get()?;
+    let escrow = Escrow::find_by_id(&mut conn, escrow_id.clone())?;
+    assert_eq!(escrow.amount, 3 * ONE_XMR / 2);
+    let items = requoted[0].items(&mut conn)?;
+    assert_eq!(items[0].price_rate, Some(100.0));
+    assert_eq!(items[0].unit_price_fiat, Some(15_000));
+
+    // A deposit locks the quote, even once it has expired
+    diesel::update(escrows::table.filter(escrows::id.eq(&escrow_id)))
+        .set(escrows::funded_amount.eq(ONE_XMR))
+        .execute(&mut conn)?;
+    drop(conn);
+    expire_quotes(&market);
+
+    market.set_usd_rate(120.0).await?;
+    assert!(market.pricing.requote_expired().await?.is_empty());
+    assert!(market.pricing.requote(&order.id).await.is_err());
+    let mut conn = market.pool.get()?;
+    assert_eq!(
+        Escrow::find_by_id(&mut conn, escrow_id)?.amount,
+        3 * ONE_XMR / 2
+    );
+
+    Ok(())
+}
+
+/// Test: a stale rate neither prices a checkout nor a requote
+#[tokio::test]
+async fn test_stale_rate_is_not_used() -> Result<()> {
+    let market = Market::new(150.0)?;
+    {
+        let mut conn = market.pool.get()?;
+        diesel::insert_into(price_history::table)
+            .values(&NewPriceRecord {
+                currency: "usd".to_string(),
+                rate: 150.0,
+                sources: "test".to_string(),
+                rejected_sources: String::new(),
+                fetched_at: (chrono::Utc::now() - chrono::Duration::hours(2)).naive_utc(),
+            })
+            .execute(&mut conn)?;
+    }
+
+    let quote = market.pricing.checkout_quote().await?;
+    assert!(quote.rates.is_empty());
+
+    let mut conn = market.pool.get()?;
+    let buyer = create_user(&mut conn, "buyer")?;
+    let vendor = create_user(&mut conn, "vendor")?;
+    let pegged = create_listing(&mut conn, &vendor, ONE_XMR, Some(15_000))?;
+    assert!(
+        Order::create_checkout_quoted(&mut conn, &buyer, &[line(&pegged, 1)], None, &quote)
+            .is_err()
+    );
+
+    Ok(())
+}
+
+/// Test: listing XMR prices follow the rate, XMR-priced listings are untouched
+#[tokio::test]
+async fn test_reprice_listings() -> Result<()> {
+    let market = Market::new(200.0)?;
+    market.oracle.refresh().await?;
+
+    let (pegged, plain) = {
+        let mut conn = market.pool.get()?;
+        let vendor = create_user(&mut conn, "vendor")?;
+        (
+            create_listing(&mut conn, &vendor, ONE_XMR, Some(50_000))?,
+            create_listing(&mut conn, &vendor, ONE_XMR, None)?,
+        )
+    };
+
+    assert_eq!(market.pricing.reprice_listings().await?, 1);
+    assert_eq!(market.pricing.reprice_listings().await?, 0);
+
+    let mut conn = market.pool.get()?;
+    let listing = Listing::find_by_id(&mut conn, pegged.clone())?;
+    assert_eq!(listing.price_xmr, 5 * ONE_XMR / 2);
+    assert_eq!(
+        listing.fiat_price().map(|price| price.display()).as_deref(),
+        Some("500.00 USD")
+    );
+    assert_eq!(Listing::find_by_id(&mut conn, plain)?.price_xmr, ONE_XMR);
+
+    // Back to a plain XMR price
+    let listing = Listing::set_fiat_price(&mut conn, pegged, None)?;
+    assert!(listing.fiat_price().is_none());
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+//! DB key rotation: PRAGMA rekey and re-encryption of field-encrypted columns
+//!
+//! Run with: cargo test --package server --test key_rotation_test
+
+mod common;
+
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use common::{setup_test_db, setup_test_db_with_key};
+use diesel::prelude::*;
+use server::crypto::encryption::{
+    decrypt_field, encrypt_field, generate_key, is_encrypted_with, register_previous_key,
+};
+use server::db::{create_pool, rekey_database};
+use server::models::wallet_rpc_config::WalletRpcConfig;
+use server::schema::wallet_rpc_configs;
+use server::services::key_rotation::reencrypt_database;
+use uuid::Uuid;
+
+/// Bare escrow row, optionally with an encrypted multisig state
+///
+/// Foreign keys are turned off on `conn`: users and orders are irrelevant here.
+fn insert_escrow(conn: &mut SqliteConnection, multisig_state: Option<&str>) -> Result<String> {
+    diesel::sql_query("PRAGMA foreign_keys = OFF;").execute(conn)?;
+    let escrow_id = Uuid::new_v4().to_string();
+    diesel::sql_query(
+        "INSERT INTO escrows (id, order_id, buyer_id, vendor_id, arbiter_id, amount, status, multisig_state_json) \
+         VALUES (?, 'order', 'buyer', 'vendor', 'arbiter', 1000, 'created', ?)",
+    )
+    .bind::<diesel::sql_types::Text, _>(&escrow_id)
+    .bind::<diesel::sql_types::Nullable<diesel::sql_types::Text>, _>(multisig_state)
+    .execute(conn)?;
+    Ok(escrow_id)
+}
+
+/// Test: the database opens only with the new key after rekey
+#[test]
+fn test_rekey_changes_database_key() -> Result<()> {
+    let old_key = hex::encode(generate_key());
+    let new_key = hex::encode(generate_key());
+    let (pool, db_path) = setup_test_db_with_key("key_rotation", &old_key)?;
+    let path = db_path.to_str().unwrap_or_default().to_string();
+
+    {
+        let mut conn = pool.get()?;
+        let escrow_id = insert_escrow(&mut conn, None)?;
+        WalletRpcConfig::save(
+            &mut conn,
+            "wallet-1",
+            &escrow_id,
+            "buyer",
+            "http://127.0.0.1:18082",
+            None,
+            None,
+            &hex::decode(&old_key)?,
+        )?;
+    }
+    drop(pool);
+
+    rekey_database(&path, &old_key, &new_key)?;
+
+    let mut conn = SqliteConnection::establish(&path)?;
+    diesel::sql_query(format!("PRAGMA key = '{}';", old_key)).execute(&mut conn)?;
+    assert!(diesel::sql_query("SELECT count(*) FROM sqlite_master;")
+        .execute(&mut conn)
+        .is_err());
+
+    let pool = create_pool(&path, &new_key)?;
+    let mut conn = pool.get()?;
+    let count: i64 = wallet_rpc_configs::table.count().get_result(&mut conn)?;
+    assert_eq!(count, 1);
+
+    assert!(rekey_database(&path, &old_key, &hex::encode(generate_key())).is_err());
+
+    drop(conn);
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: every encrypted column moves to the new key, idempotently
+#[test]
+fn test_reencrypt_database_moves_fields_to_new_key() -> Result<()> {
+    let old_field_key = generate_key();
+    let new_field_key = generate_key();
+    let (pool, db_path) = setup_test_db("key_rotation")?;
+    let mut conn = pool.get()?;
+
+    let old_state = BASE64.encode(encrypt_field(r#"{"phase":"ready"}"#, &old_field_key)?);
+    let escrow_id = insert_escrow(&mut conn, Some(&old_state))?;
+    WalletRpcConfig::save(
+        &mut conn,
+        "wallet-1",
+        &escrow_id,
+        "buyer",
+        "http://127.0.0.1:18082",
+        Some("rpc_user"),
+        None,
+        &old_field_key,
+    )?;
+    register_previous_key(&old_field_key)?;
+    let report = reencrypt_database(&mut conn, &new_field_key)?;
+    assert_eq!(report.reencrypted(), 3, "{:?}", report);
+    assert_eq!(report.failed(), 0);
+
+    let config: WalletRpcConfig = wallet_rpc_configs::table.first(&mut conn)?;
+    assert!(is_encrypted_with(&config.rpc_url_encrypted, &new_field_key));
+    assert_eq!(
+        config.decrypt_url(&new_field_key)?,
+        "http://127.0.0.1:18082"
+    );
+
+    let state: Option<String> = server::schema::escrows::table
+        .select(server::schema::escrows::multisig_state_json)
+        .first(&mut conn)?;
+    let state = BASE64.decode(state.unwrap_or_default())?;
+    assert!(is_encrypted_with(&state, &new_field_key));
+    assert_eq!(
+        decrypt_field(&state, &new_field_key)?,
+        r#"{"phase":"ready"}"#
+    );
+
+    let again = reencrypt_database(&mut conn, &new_field_key)?;
+    assert_eq!(again.reencrypted(), 0);
+
+    drop(conn);
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: rotate_key prints the new key even when `--reencrypt-fields` fails
+#[test]
+fn test_rotate_key_prints_new_key_when_reencryption_fails() -> Result<()> {
+    let old_key = hex::encode(generate_key());
+    let db_path = std::env::temp_dir().join(format!("key_rotation_{}.db", Uuid::new_v4()));
+    let path = db_path.to_str().unwrap_or_default().to_string();
+
+    // No migrations: the encrypted columns do not exist, so re-encryption fails
+    let pool = create_pool(&path, &old_key)?;
+    diesel::sql_query("CREATE TABLE placeholder (id INTEGER PRIMARY KEY);")
+        .execute(&mut pool.get()?)?;
+    drop(pool);
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_rotate_key"))
+        .arg("--reencrypt-fields")
+        .env("DB_ENCRYPTION_KEY", &old_key)
+        .env("DATABASE_URL", &path)
+        .output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(
+        output.status.success(),
+        "stdout: {}\nstderr: {}",
+        stdout,
+        stderr
+    );
+    assert!(stderr.contains("Field re-encryption failed"), "{}", stderr);
+    assert_eq!(stdout.matches("New share").count(), 5);
+
+    let new_key = stdout
+        .lines()
+        .skip_while(|line| !line.contains("New DB_ENCRYPTION_KEY"))
+        .nth(1)
+        .map(str::trim)
+        .unwrap_or_default();
+    assert_eq!(new_key.len(), 64, "{}", stdout);
+
+    let mut conn = SqliteConnection::establish(&path)?;
+    diesel::sql_query(format!("PRAGMA key = '{}';", new_key)).execute(&mut conn)?;
+    diesel::sql_query("SELECT count(*) FROM placeholder;").execute(&mut conn)?;
+
+    drop(conn);
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
@@ -11,12 +11,14 @@ use serde_json::json;
 use time::Duration;
 
 use server::{
+    config::{BondPolicy, PriceOracleConfig},
     db::create_pool,
     handlers::{auth, listings},
     middleware::{
         rate_limit::{auth_rate_limiter, global_rate_limiter},
         security_headers::SecurityHeaders,
     },
+    services::price_conversion::PriceOracle,
 };
 
 type DbPool = r2d2::Pool<ConnectionManager<SqliteConnection>>;
@@ -48,6 +50,12 @@ fn create_test_app(
                 .build(),
         )
         .app_data(web::Data::new(pool.clone()))
+        .app_data(web::Data::new(BondPolicy::default()))
+        .app_data(web::Data::new(PriceOracle::with_sources(
+            pool.clone(),
+            PriceOracleConfig::default(),
+            Vec::new(),
+        )))
         .service(
             web::scope("/api/auth")
                 .wrap(auth_rate_limiter())
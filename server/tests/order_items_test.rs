@@ -0,0 +1,132 @@
+//! Multi-item checkout: one order per vendor, line items, atomic stock
+//!
+//! Exercises `Order::create_checkout` against an encrypted database with
+//! all migrations applied.
+//!
+//! Run with: cargo test --package server --test order_items_test
+
+mod common;
+
+use anyhow::Result;
+use common::{create_listing, create_user, setup_test_db, ONE_XMR};
+use server::models::listing::Listing;
+use server::models::order::{CheckoutLine, Order};
+use server::models::order_item::OrderItem;
+
+fn line(listing_id: &str, quantity: i32) -> CheckoutLine {
+    CheckoutLine {
+        listing_id: listing_id.to_string(),
+        quantity,
+    }
+}
+
+/// Test: a two-vendor cart gives one order per vendor, each with its items
+#[test]
+fn test_checkout_groups_items_by_vendor() -> Result<()> {
+    let (pool, db_path) = setup_test_db("order_items")?;
+    let mut conn = pool.get()?;
+
+    let buyer = create_user(&mut conn, "buyer")?;
+    let vendor_a = create_user(&mut conn, "vendor")?;
+    let vendor_b = create_user(&mut conn, "vendor")?;
+    let apples = create_listing(&mut conn, &vendor_a, "Apples", ONE_XMR, 10)?;
+    let pears = create_listing(&mut conn, &vendor_a, "Pears", 2 * ONE_XMR, 5)?;
+    let honey = create_listing(&mut conn, &vendor_b, "Honey", 3 * ONE_XMR, 1)?;
+
+    let created = Order::create_checkout(
+        &mut conn,
+        &buyer,
+        &[line(&apples, 2), line(&honey, 1), line(&pears, 1), line(&apples, 1)],
+        None,
+    )?;
+
+    assert_eq!(created.len(), 2);
+
+    let (order_a, items_a) = &created[0];
+    assert_eq!(order_a.vendor_id, vendor_a);
+    assert_eq!(order_a.total_xmr, 3 * ONE_XMR + 2 * ONE_XMR);
+    assert_eq!(order_a.listing_id, apples);
+    let lines_a: Vec<(&str, i32)> = items_a
+        .iter()
+        .map(|item| (item.title.as_str(), item.quantity))
+        .collect();
+    assert_eq!(lines_a, vec![("Apples", 3), ("Pears", 1)]);
+
+    let (order_b, items_b) = &created[1];
+    assert_eq!(order_b.vendor_id, vendor_b);
+    assert_eq!(order_b.total_xmr, 3 * ONE_XMR);
+    assert_eq!(items_b.len(), 1);
+    assert_eq!(items_b[0].total_price(), 3 * ONE_XMR);
+
+    // Stock decreased for every item, sold out listing flagged
+    assert_eq!(Listing::find_by_id(&mut conn, apples.clone())?.stock, 7);
+    assert_eq!(Listing::find_by_id(&mut conn, pears.clone())?.stock, 4);
+    let honey_listing = Listing::find_by_id(&mut conn, honey.clone())?;
+    assert_eq!(honey_listing.stock, 0);
+    assert_eq!(honey_listing.status, "sold_out");
+
+    // Items are reloaded from the order
+    assert_eq!(order_a.items(&mut conn)?.len(), 2);
+
+    drop(conn);
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: one out-of-stock item rolls back the whole checkout
+#[test]
+fn test_checkout_is_atomic() -> Result<()> {
+    let (pool, db_path) = setup_test_db("order_items")?;
+    let mut conn = pool.get()?;
+
+    let buyer = create_user(&mut conn, "buyer")?;
+    let vendor_a = create_user(&mut conn, "vendor")?;
+    let vendor_b = create_user(&mut conn, "vendor")?;
+    let apples = create_listing(&mut conn, &vendor_a, "Apples", ONE_XMR, 10)?;
+    let honey = create_listing(&mut conn, &vendor_b, "Honey", 3 * ONE_XMR, 1)?;
+
+    let result = Order::create_checkout(
+        &mut conn,
+        &buyer,
+        &[line(&apples, 2), line(&honey, 2)],
+        None,
+    );
+    assert!(result.is_err());
+
+    assert_eq!(Listing::find_by_id(&mut conn, apples.clone())?.stock, 10);
+    assert_eq!(Listing::find_by_id(&mut conn, honey.clone())?.stock, 1);
+    assert!(Order::find_by_buyer(&mut conn, buyer.clone())?.is_empty());
+
+    // Buying from yourself is rejected the same way
+    let own = Order::create_checkout(&mut conn, &vendor_a, &[line(&apples, 1)], None);
+    assert!(own.is_err());
+    assert_eq!(Listing::find_by_id(&mut conn, apples)?.stock, 10);
+
+    drop(conn);
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: a single-listing checkout (Buy Now / POST /api/orders) has one line item
+#[test]
+fn test_single_listing_order_has_one_item() -> Result<()> {
+    let (pool, db_path) = setup_test_db("order_items")?;
+    let mut conn = pool.get()?;
+
+    let buyer = create_user(&mut conn, "buyer")?;
+    let vendor = create_user(&mut conn, "vendor")?;
+    let apples = create_listing(&mut conn, &vendor, "Apples", ONE_XMR, 10)?;
+
+    let mut created =
+        Order::create_checkout(&mut conn, &buyer, &[line(&apples, 4)], None)?;
+    let (order, items) = created.pop().expect("one order");
+
+    assert_eq!(order.total_xmr, 4 * ONE_XMR);
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].unit_price_xmr, ONE_XMR);
+    assert_eq!(OrderItem::find_by_order(&mut conn, &order.id)?.len(), 1);
+
+    drop(conn);
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
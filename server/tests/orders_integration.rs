@@ -11,13 +11,16 @@ use serde_json::json;
 use time::Duration;
 
 use server::{
+    config::PriceOracleConfig,
     db::create_pool,
     handlers::{auth, listings, orders},
     middleware::{
         rate_limit::{auth_rate_limiter, global_rate_limiter},
         security_headers::SecurityHeaders,
     },
+    services::{fiat_pricing::FiatPricing, price_conversion::PriceOracle},
 };
+use std::sync::Arc;
 
 type DbPool = r2d2::Pool<ConnectionManager<SqliteConnection>>;
 
@@ -34,6 +37,11 @@ fn create_test_app(
     >,
 > {
     let secret_key = Key::from(b"test_secret_key_at_least_64_bytes_long_for_security_purposes!!!!");
+    let oracle = Arc::new(PriceOracle::with_sources(
+        pool.clone(),
+        PriceOracleConfig::default(),
+        Vec::new(),
+    ));
 
     App::new()
         .wrap(SecurityHeaders)
@@ -48,6 +56,8 @@ fn create_test_app(
                 .build(),
         )
         .app_data(web::Data::new(pool.clone()))
+        .app_data(web::Data::from(oracle.clone()))
+        .app_data(web::Data::new(FiatPricing::new(pool.clone(), oracle)))
         .service(
             web::scope("/api/auth")
                 .wrap(auth_rate_limiter())
@@ -0,0 +1,149 @@
+//! Price oracle: median of several sources, outliers, staleness and history
+//!
+//! Run with: cargo test --package server --test price_oracle_test
+
+mod common;
+
+use anyhow::Result;
+use common::setup_test_db;
+use diesel::prelude::*;
+use futures_util::future::BoxFuture;
+use server::config::PriceOracleConfig;
+use server::models::price_history::NewPriceRecord;
+use server::schema::price_history;
+use server::services::price_conversion::{PriceOracle, PriceSource, SourceRates};
+use std::sync::Arc;
+
+/// Source quoting fixed rates, or failing when it has none
+struct FixedSource {
+    name: &'static str,
+    rates: Vec<(&'static str, f64)>,
+}
+
+impl PriceSource for FixedSource {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn fetch<'a>(&'a self, _currencies: &'a [String]) -> BoxFuture<'a, Result<SourceRates>> {
+        Box::pin(async move {
+            if self.rates.is_empty() {
+                anyhow::bail!("{} unreachable", self.name);
+            }
+            Ok(self
+                .rates
+                .iter()
+                .map(|(currency, rate)| (currency.to_string(), *rate))
+                .collect())
+        })
+    }
+}
+
+fn source(name: &'static str, rates: &[(&'static str, f64)]) -> Arc<dyn PriceSource> {
+    Arc::new(FixedSource {
+        name,
+        rates: rates.to_vec(),
+    })
+}
+
+fn offline_config(sources: &[&str]) -> PriceOracleConfig {
+    PriceOracleConfig {
+        sources: sources.iter().map(|s| s.to_string()).collect(),
+        proxy: None,
+        ..Default::default()
+    }
+}
+
+/// Test: the median of agreeing sources is recorded, outliers and failures are not
+#[tokio::test]
+async fn test_refresh_records_median_without_outliers() -> Result<()> {
+    let (pool, db_path) = setup_test_db("price_oracle")?;
+    let oracle = PriceOracle::with_sources(
+        pool.clone(),
+        offline_config(&[]),
+        vec![
+            source("a", &[("usd", 150.0), ("eur", 140.0)]),
+            source("b", &[("usd", 152.0), ("eur", 141.0)]),
+            source("c", &[("usd", 151.0)]),
+            source("broken", &[("usd", 15.0)]),
+            source("down", &[]),
+        ],
+    );
+
+    assert!(oracle.rate("usd").await.is_err(), "no rate recorded yet");
+
+    let records = oracle.refresh().await?;
+    assert_eq!(records.len(), 2);
+
+    let usd = oracle.rate("USD").await?;
+    assert_eq!(usd.rate, 151.0);
+    assert_eq!(usd.sources, vec!["a", "b", "c"]);
+    assert!(!usd.stale);
+    assert_eq!(usd.convert_atomic(2_000_000_000_000), 302.0);
+
+    let history = oracle.history("usd", 10).await?;
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].rejected_source_names(), vec!["broken"]);
+
+    let eur = oracle.rate("eur").await?;
+    assert_eq!(eur.rate, 140.5);
+    assert_eq!(oracle.rates().await?.len(), 2);
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: when no source answers, the last rate is served and reported as stale
+#[tokio::test]
+async fn test_old_rate_is_reported_stale() -> Result<()> {
+    let (pool, db_path) = setup_test_db("price_oracle")?;
+    {
+        let mut conn = pool.get()?;
+        diesel::insert_into(price_history::table)
+            .values(&NewPriceRecord {
+                currency: "usd".to_string(),
+                rate: 148.0,
+                sources: "coingecko".to_string(),
+                rejected_sources: String::new(),
+                fetched_at: (chrono::Utc::now() - chrono::Duration::hours(2)).naive_utc(),
+            })
+            .execute(&mut conn)?;
+    }
+
+    let oracle =
+        PriceOracle::with_sources(pool.clone(), offline_config(&[]), vec![source("down", &[])]);
+    assert!(oracle.refresh().await?.is_empty());
+
+    let usd = oracle.rate("usd").await?;
+    assert_eq!(usd.rate, 148.0);
+    assert!(usd.stale);
+    assert!(usd.age_secs >= 7200);
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: a manual rate is used offline and only for configured currencies
+#[tokio::test]
+async fn test_manual_rate() -> Result<()> {
+    let (pool, db_path) = setup_test_db("price_oracle")?;
+    let oracle = PriceOracle::new(pool.clone(), offline_config(&["manual"]))?;
+
+    let records = oracle.set_manual_rate("usd", Some(160.0)).await?;
+    assert_eq!(records.len(), 1);
+    let usd = oracle.rate("usd").await?;
+    assert_eq!(usd.rate, 160.0);
+    assert_eq!(usd.sources, vec!["manual"]);
+
+    assert!(oracle.set_manual_rate("jpy", Some(20000.0)).await.is_err());
+    assert!(oracle.set_manual_rate("usd", Some(-1.0)).await.is_err());
+
+    let without_manual = PriceOracle::new(pool.clone(), offline_config(&["coingecko"]))?;
+    assert!(without_manual
+        .set_manual_rate("usd", Some(160.0))
+        .await
+        .is_err());
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
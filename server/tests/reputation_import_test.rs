@@ -0,0 +1,228 @@
+//! Portable reputation: vendor reputation keys, signed files exported from
+//! one marketplace and imported on another
+//!
+//! Run with: cargo test --package server --test reputation_import_test
+
+mod common;
+
+use anyhow::Result;
+use common::{create_user, setup_test_db};
+use ed25519_dalek::SigningKey;
+use reputation_common::types::ScoringParams;
+use reputation_crypto::reputation::{
+    calculate_stats, public_key_b64, sign_bound_review, sign_message, sign_reputation,
+    sign_review, verify_message_signature,
+};
+use reputation_crypto::scoring::reputation_score;
+use server::config::ReviewPolicy;
+use server::db::reputation::{
+    db_get_imported_reputations, db_get_reputation_key, db_get_vendor_reputation,
+    db_insert_imported_reputation, db_register_reputation_key, ImportedReputation,
+};
+use server::db::DbPool;
+use server::handlers::reputation::reputation_key_message;
+use server::handlers::reputation_ipfs::verify_imported_reputation;
+use uuid::Uuid;
+
+fn create_vendor(pool: &DbPool) -> Result<Uuid> {
+    Ok(create_user(&mut *pool.get()?, "vendor")?.parse()?)
+}
+
+/// Test: a reputation key is registered once and belongs to one vendor
+#[actix_web::test]
+async fn test_register_reputation_key() -> Result<()> {
+    let (pool, db_path) = setup_test_db("reputation_import")?;
+    let vendor = create_vendor(&pool)?;
+    let other = create_vendor(&pool)?;
+    let scoring = ScoringParams::default();
+    let vendor_key = SigningKey::from_bytes(&[11u8; 32]);
+    let public_key = public_key_b64(&vendor_key);
+
+    // Proof of possession names the marketplace and the vendor
+    let message = reputation_key_message("localhost", &vendor.to_string());
+    let proof = sign_message(message.as_bytes(), &vendor_key);
+    assert!(verify_message_signature(
+        &public_key,
+        message.as_bytes(),
+        &proof
+    )?);
+    let replayed = reputation_key_message("localhost", &other.to_string());
+    assert!(!verify_message_signature(
+        &public_key,
+        replayed.as_bytes(),
+        &proof
+    )?);
+
+    // Before registration the file names the vendor UUID
+    assert_eq!(
+        db_get_reputation_key(&pool, &vendor.to_string()).await?,
+        None
+    );
+    assert_eq!(
+        db_get_vendor_reputation(&pool, vendor, &scoring)
+            .await?
+            .vendor_pubkey,
+        vendor.to_string()
+    );
+
+    db_register_reputation_key(&pool, &vendor.to_string(), &public_key).await?;
+    assert_eq!(
+        db_get_reputation_key(&pool, &vendor.to_string()).await?,
+        Some(public_key.clone())
+    );
+    assert_eq!(
+        db_get_vendor_reputation(&pool, vendor, &scoring)
+            .await?
+            .vendor_pubkey,
+        public_key
+    );
+
+    // Not replaced, not shared
+    let new_key = public_key_b64(&SigningKey::from_bytes(&[12u8; 32]));
+    assert!(
+        db_register_reputation_key(&pool, &vendor.to_string(), &new_key)
+            .await
+            .is_err()
+    );
+    assert!(
+        db_register_reputation_key(&pool, &other.to_string(), &public_key)
+            .await
+            .is_err()
+    );
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: a file signed on another marketplace is verified, stored apart
+/// from native reviews and replaced by a later import from there
+#[actix_web::test]
+async fn test_import_signed_reputation() -> Result<()> {
+    let (pool, db_path) = setup_test_db("reputation_import")?;
+    let vendor = create_vendor(&pool)?;
+    let vendor_key = SigningKey::from_bytes(&[13u8; 32]);
+    let public_key = public_key_b64(&vendor_key);
+    db_register_reputation_key(&pool, &vendor.to_string(), &public_key).await?;
+    let scoring = ScoringParams::default();
+
+    // Exported from another marketplace by the same vendor key
+    let elsewhere = ReviewPolicy {
+        marketplace_domain: "other.example.onion".to_string(),
+        ..ReviewPolicy::default()
+    };
+    let buyer_key = SigningKey::from_bytes(&[14u8; 32]);
+    let reviews = (0..3u8)
+        .map(|i| {
+            sign_bound_review(
+                format!("{:064x}", i),
+                5 - i,
+                None,
+                elsewhere.binding_for(&public_key, &Uuid::new_v4().to_string()),
+                &buyer_key,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut file = db_get_vendor_reputation(&pool, vendor, &scoring).await?;
+    file.stats = calculate_stats(&reviews);
+    file.reviews = reviews;
+    file.score = Some(reputation_score(&file, &scoring));
+    let file = sign_reputation(file, &vendor_key)?;
+
+    let policy = ReviewPolicy::default();
+    let summary = verify_imported_reputation(&file, &public_key, &policy)?;
+    assert_eq!(
+        summary.source_domains,
+        vec!["other.example.onion".to_string()]
+    );
+    assert_eq!(summary.unbound_reviews, 0);
+
+    // Re-signing with another key fails, a forged signature does not verify
+    assert!(sign_reputation(file.clone(), &buyer_key).is_err());
+    let mut forged = file.clone();
+    forged.reviews.pop();
+    forged.stats = calculate_stats(&forged.reviews);
+    assert!(verify_imported_reputation(&forged, &public_key, &policy).is_err());
+
+    let import = |cid: &str| ImportedReputation {
+        id: Uuid::new_v4().to_string(),
+        vendor_id: vendor.to_string(),
+        ipfs_cid: cid.to_string(),
+        vendor_pubkey: public_key.clone(),
+        source_domains: summary.source_domains.join(","),
+        total_reviews: file.stats.total_reviews as i32,
+        unbound_reviews: summary.unbound_reviews as i32,
+        average_rating: f64::from(file.stats.average_rating),
+        generated_at: file.generated_at.naive_utc(),
+        reputation_json: serde_json::to_string(&file).unwrap_or_default(),
+        imported_at: chrono::Utc::now().naive_utc(),
+    };
+    db_insert_imported_reputation(&pool, import("QmFirstExport")).await?;
+    db_insert_imported_reputation(&pool, import("QmSecondExport")).await?;
+
+    let imported = db_get_imported_reputations(&pool, vendor).await?;
+    assert_eq!(imported.len(), 1);
+    assert_eq!(imported[0].ipfs_cid, "QmSecondExport");
+    assert_eq!(imported[0].total_reviews, 3);
+
+    // Native reputation is untouched
+    assert!(db_get_vendor_reputation(&pool, vendor, &scoring)
+        .await?
+        .reviews
+        .is_empty());
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: a file carrying 1.0 reviews is only imported while the policy
+/// accepts unbound reviews
+#[actix_web::test]
+async fn test_import_rejects_unbound_reviews() -> Result<()> {
+    let (pool, db_path) = setup_test_db("reputation_import")?;
+    let vendor = create_vendor(&pool)?;
+    let vendor_key = SigningKey::from_bytes(&[15u8; 32]);
+    let public_key = public_key_b64(&vendor_key);
+    db_register_reputation_key(&pool, &vendor.to_string(), &public_key).await?;
+    let scoring = ScoringParams::default();
+
+    let elsewhere = ReviewPolicy {
+        marketplace_domain: "other.example.onion".to_string(),
+        ..ReviewPolicy::default()
+    };
+    let buyer_key = SigningKey::from_bytes(&[16u8; 32]);
+    let reviews = vec![
+        sign_bound_review(
+            "a".repeat(64),
+            5,
+            None,
+            elsewhere.binding_for(&public_key, &Uuid::new_v4().to_string()),
+            &buyer_key,
+        )?,
+        sign_review("b".repeat(64), 2, None, &buyer_key)?,
+    ];
+    let mut file = db_get_vendor_reputation(&pool, vendor, &scoring).await?;
+    file.stats = calculate_stats(&reviews);
+    file.reviews = reviews;
+    file.score = Some(reputation_score(&file, &scoring));
+    let file = sign_reputation(file, &vendor_key)?;
+
+    let policy = ReviewPolicy::default();
+    assert!(!policy.accept_unbound_reviews);
+    let err = verify_imported_reputation(&file, &public_key, &policy)
+        .expect_err("1.0 reviews must be refused by default");
+    assert!(err.to_string().contains("1.0 reviews"));
+
+    let lenient = ReviewPolicy {
+        accept_unbound_reviews: true,
+        ..ReviewPolicy::default()
+    };
+    let summary = verify_imported_reputation(&file, &public_key, &lenient)?;
+    assert_eq!(summary.unbound_reviews, 1);
+    assert_eq!(
+        summary.source_domains,
+        vec!["other.example.onion".to_string()]
+    );
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
@@ -13,6 +13,7 @@ use serde_json::json;
 use time::Duration;
 
 use server::{
+    config::ReviewPolicy,
     db::create_pool,
     handlers::{auth, reputation},
     middleware::{
@@ -50,6 +51,7 @@ fn create_test_app(
                 .build(),
         )
         .app_data(web::Data::new(pool.clone()))
+        .app_data(web::Data::new(ReviewPolicy::default()))
         .service(
             web::scope("/api/auth")
                 .wrap(auth_rate_limiter())
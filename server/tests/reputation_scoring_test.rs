@@ -0,0 +1,173 @@
+//! Vendor score: escrow outcomes counted from the escrow history, score
+//! carried by the reputation file and checked again on import
+//!
+//! Run with: cargo test --package server --test reputation_scoring_test
+
+mod common;
+
+use anyhow::Result;
+use common::{create_order, create_user, setup_test_db, ONE_XMR};
+use diesel::prelude::*;
+use ed25519_dalek::SigningKey;
+use monero_marketplace_common::types::EscrowStatus;
+use reputation_common::types::{EscrowOutcomes, ScoringParams};
+use reputation_crypto::reputation::sign_review;
+use reputation_crypto::scoring::verify_reputation_score;
+use server::config::ReviewPolicy;
+use server::db::reputation::{
+    db_get_escrow_outcomes, db_get_vendor_reputation, db_get_vendor_stats, db_insert_review,
+};
+use server::models::escrow::{Escrow, NewEscrow};
+use server::models::escrow_event::{EscrowActor, EscrowEventRecord};
+use uuid::Uuid;
+
+/// Escrow of a new order from `vendor`, taken through `path` from `created`
+fn create_escrow(conn: &mut SqliteConnection, vendor: Uuid, path: &[EscrowStatus]) -> Result<()> {
+    let buyer = create_user(conn, "buyer")?;
+    let arbiter = create_user(conn, "arbiter")?;
+    let order_id = create_order(conn, &buyer, &vendor.to_string(), "pending")?;
+
+    let escrow = Escrow::create(
+        conn,
+        NewEscrow {
+            id: Uuid::new_v4().to_string(),
+            order_id,
+            buyer_id: buyer,
+            vendor_id: vendor.to_string(),
+            arbiter_id: arbiter,
+            amount: ONE_XMR,
+            status: EscrowStatus::Created.as_str().to_string(),
+        },
+    )?;
+    for status in path {
+        EscrowEventRecord::record_transition(
+            conn,
+            &escrow.id,
+            *status,
+            &EscrowActor::System("reputation_scoring_test"),
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+/// Test: only funded escrows count; a dispute counts even once resolved
+#[actix_web::test]
+async fn test_escrow_outcomes() -> Result<()> {
+    use EscrowStatus::*;
+
+    let (pool, db_path) = setup_test_db("reputation_scoring")?;
+    let vendor: Uuid = create_user(&mut *pool.get()?, "vendor")?.parse()?;
+    assert_eq!(
+        db_get_escrow_outcomes(&pool, vendor).await?,
+        EscrowOutcomes::default()
+    );
+
+    let paths: [&[EscrowStatus]; 7] = [
+        &[],
+        &[Funded, Cancelled],
+        &[Funded, Underfunded],
+        &[Active, Releasing, Completed],
+        &[Active, Disputed, ResolvedVendor, Releasing, Completed],
+        &[Active, Disputed, ResolvedBuyer, Refunding, Refunded],
+        &[Active, Refunding],
+    ];
+    for path in paths {
+        create_escrow(&mut *pool.get()?, vendor, path)?;
+    }
+
+    let outcomes = db_get_escrow_outcomes(&pool, vendor).await?;
+    assert_eq!(
+        outcomes,
+        EscrowOutcomes {
+            funded: 4,
+            disputed: 2,
+            refunded: 2,
+        }
+    );
+
+    let params = ScoringParams::default();
+    let stats = db_get_vendor_stats(&pool, vendor, &params).await?;
+    assert_eq!(stats.total_reviews, 0);
+    assert_eq!(stats.score.score_milli, params.prior_rating_milli);
+    assert_eq!(stats.score.dispute_rate_bps, Some(5_000));
+    assert_eq!(stats.score.refund_rate_bps, Some(5_000));
+
+    // Another vendor's escrows are not counted
+    let other: Uuid = create_user(&mut *pool.get()?, "vendor")?.parse()?;
+    assert_eq!(
+        db_get_escrow_outcomes(&pool, other).await?,
+        EscrowOutcomes::default()
+    );
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: the reputation file carries a score that verifies, and the vendor
+/// profile shows it
+#[actix_web::test]
+async fn test_score_in_reputation_file() -> Result<()> {
+    let (pool, db_path) = setup_test_db("reputation_scoring")?;
+    let vendor: Uuid = create_user(&mut *pool.get()?, "vendor")?.parse()?;
+    create_escrow(
+        &mut *pool.get()?,
+        vendor,
+        &[EscrowStatus::Active, EscrowStatus::Disputed],
+    )?;
+
+    let buyer = create_user(&mut *pool.get()?, "buyer")?;
+    let buyer_key = SigningKey::from_bytes(&[31u8; 32]);
+    for (i, rating) in [5u8, 5, 4].into_iter().enumerate() {
+        let review = sign_review(format!("{:064x}", i), rating, None, &buyer_key)?;
+        db_insert_review(&pool, &review, &buyer, &vendor.to_string()).await?;
+    }
+
+    let policy = ReviewPolicy::default();
+    let reputation = db_get_vendor_reputation(&pool, vendor, &policy.scoring).await?;
+    let score = reputation
+        .score
+        .ok_or_else(|| anyhow::anyhow!("reputation file has no score"))?;
+    assert_eq!(score.params, policy.scoring);
+    assert_eq!(score.dispute_rate_bps, Some(10_000));
+    assert_eq!(score.refund_rate_bps, Some(0));
+    // Three reviews without payment proof, pulled towards the 3.5★ prior
+    assert!((3_500..4_667).contains(&score.score_milli));
+    assert!(verify_reputation_score(&reputation));
+
+    // A score that does not follow from the reviews is caught
+    let mut inflated = reputation.clone();
+    inflated.score = Some(reputation_common::types::ReputationScore {
+        score_milli: 5_000,
+        ..score
+    });
+    assert!(!verify_reputation_score(&inflated));
+    let mut hidden_dispute = reputation.clone();
+    hidden_dispute.outcomes = Some(EscrowOutcomes {
+        funded: 10,
+        ..EscrowOutcomes::default()
+    });
+    assert!(!verify_reputation_score(&hidden_dispute));
+
+    // Rendered on the vendor profile
+    let tera = tera::Tera::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../templates/**/*.html"
+    ))?;
+    let mut ctx = tera::Context::new();
+    ctx.insert("logged_in", &false);
+    ctx.insert("csrf_token", "token");
+    ctx.insert("vendor_id", &vendor.to_string());
+    ctx.insert("reputation", &reputation);
+    ctx.insert("reputation_json", &serde_json::to_string(&reputation)?);
+    ctx.insert("imported_reputations", &Vec::<String>::new());
+    ctx.insert("is_vendor", &false);
+    let html = tera.render("reputation/vendor_profile.html", &ctx)?;
+    let shown = format!("{:.2}", f64::from(score.score_milli) / 1000.0);
+    assert!(html.contains(&shown), "score {} not shown", shown);
+    assert!(html.contains("100% / 0%"));
+    assert!(html.contains("of 1 funded escrows"));
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
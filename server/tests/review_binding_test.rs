@@ -0,0 +1,152 @@
+//! Versioned reviews: 2.0 reviews bound to vendor, escrow and marketplace,
+//! 1.0 reviews stored before the migration still verifying
+//!
+//! Run with: cargo test --package server --test review_binding_test
+
+mod common;
+
+use anyhow::Result;
+use common::{create_escrow, run_pending_migrations, setup_test_db, setup_test_db_before};
+use diesel::prelude::*;
+use diesel::sql_types::{Integer, Text, Timestamp};
+use ed25519_dalek::SigningKey;
+use reputation_common::types::REVIEW_FORMAT_V1;
+use reputation_crypto::reputation::{
+    public_key_b64, sign_bound_review, sign_review, verify_review_for_vendor,
+};
+use server::config::ReviewPolicy;
+use server::db::reputation::{
+    db_escrow_review_exists, db_get_vendor_reviews, db_insert_review, db_register_reputation_key,
+};
+use server::handlers::reputation::check_review_binding;
+use server::models::escrow::EscrowStatus;
+use uuid::Uuid;
+
+/// Test: a 2.0 review survives storage, only verifies for its vendor, and
+/// an escrow takes a single review
+#[actix_web::test]
+async fn test_bound_review_roundtrip() -> Result<()> {
+    let (pool, db_path) = setup_test_db("review_binding")?;
+    let escrow = create_escrow(&mut *pool.get()?, EscrowStatus::Completed)?;
+    let policy = ReviewPolicy {
+        marketplace_domain: "market.example.onion".to_string(),
+        ..ReviewPolicy::default()
+    };
+
+    let buyer_key = SigningKey::from_bytes(&[3u8; 32]);
+    let review = sign_bound_review(
+        "a".repeat(64),
+        4,
+        Some("Arrived | well packed".to_string()),
+        policy.binding_for(&escrow.vendor_id, &escrow.id),
+        &buyer_key,
+    )?;
+
+    assert!(!db_escrow_review_exists(&pool, &escrow.id).await?);
+    let stored = db_insert_review(&pool, &review, &escrow.buyer_id, &escrow.vendor_id).await?;
+    assert_eq!(stored.format_version, "2.0");
+    assert_eq!(stored.escrow_id.as_deref(), Some(escrow.id.as_str()));
+    assert!(db_escrow_review_exists(&pool, &escrow.id).await?);
+
+    let loaded = db_get_vendor_reviews(&pool, escrow.vendor_id.parse()?).await?;
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].binding(), review.binding());
+    assert!(verify_review_for_vendor(&loaded[0], &escrow.vendor_id)?);
+    assert!(!verify_review_for_vendor(&loaded[0], &escrow.buyer_id)?);
+
+    // Same escrow, different txid: rejected by the unique escrow index
+    let again = sign_bound_review(
+        "b".repeat(64),
+        1,
+        None,
+        policy.binding_for(&escrow.vendor_id, &escrow.id),
+        &buyer_key,
+    )?;
+    assert!(
+        db_insert_review(&pool, &again, &escrow.buyer_id, &escrow.vendor_id)
+            .await
+            .is_err()
+    );
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: new reviews are only accepted bound to a settled escrow; unbound
+/// 1.0 submissions are rejected by default
+#[actix_web::test]
+async fn test_binding_requires_settled_escrow() -> Result<()> {
+    let (pool, db_path) = setup_test_db("review_binding")?;
+    let escrow = create_escrow(&mut *pool.get()?, EscrowStatus::Completed)?;
+    let vendor: Uuid = escrow.vendor_id.parse()?;
+    let vendor_pubkey = public_key_b64(&SigningKey::from_bytes(&[7u8; 32]));
+    db_register_reputation_key(&pool, &escrow.vendor_id, &vendor_pubkey).await?;
+
+    let policy = ReviewPolicy::default();
+    let buyer_key = SigningKey::from_bytes(&[6u8; 32]);
+    let review = sign_bound_review(
+        "d".repeat(64),
+        5,
+        None,
+        policy.binding_for(&vendor_pubkey, &escrow.id),
+        &buyer_key,
+    )?;
+    check_review_binding(&pool, &policy, &review, &escrow.buyer_id, &vendor).await?;
+
+    // Funds still held in escrow: too early to review
+    diesel::update(server::schema::escrows::table.find(&escrow.id))
+        .set(server::schema::escrows::status.eq(EscrowStatus::Active.as_str()))
+        .execute(&mut *pool.get()?)?;
+    let early = check_review_binding(&pool, &policy, &review, &escrow.buyer_id, &vendor).await;
+    assert!(early.is_err_and(|e| e.to_string().contains("not settled")));
+
+    let unbound = sign_review("d".repeat(64), 5, None, &buyer_key)?;
+    assert!(!policy.accept_unbound_reviews);
+    assert!(
+        check_review_binding(&pool, &policy, &unbound, &escrow.buyer_id, &vendor)
+            .await
+            .is_err()
+    );
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: reviews stored before the migration become 1.0 and still verify
+#[actix_web::test]
+async fn test_existing_reviews_migrate_as_v1() -> Result<()> {
+    let (pool, db_path) = setup_test_db_before("review_binding", Some("bind_reviews"))?;
+    let escrow = create_escrow(&mut *pool.get()?, EscrowStatus::Completed)?;
+
+    let buyer_key = SigningKey::from_bytes(&[5u8; 32]);
+    let review = sign_review("c".repeat(64), 5, Some("Legacy".to_string()), &buyer_key)?;
+    {
+        let mut conn = pool.get()?;
+        diesel::sql_query(
+            "INSERT INTO reviews (id, txid, reviewer_id, vendor_id, rating, comment, \
+             buyer_pubkey, signature, timestamp, verified) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, 0)",
+        )
+        .bind::<Text, _>(Uuid::new_v4().to_string())
+        .bind::<Text, _>(&review.txid)
+        .bind::<Text, _>(&escrow.buyer_id)
+        .bind::<Text, _>(&escrow.vendor_id)
+        .bind::<Integer, _>(i32::from(review.rating))
+        .bind::<Text, _>(review.comment.as_deref().unwrap_or_default())
+        .bind::<Text, _>(&review.buyer_pubkey)
+        .bind::<Text, _>(&review.signature)
+        .bind::<Timestamp, _>(review.timestamp.naive_utc())
+        .execute(&mut conn)?;
+
+        run_pending_migrations(&mut conn)?;
+    }
+
+    let loaded = db_get_vendor_reviews(&pool, escrow.vendor_id.parse()?).await?;
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].format_version, REVIEW_FORMAT_V1);
+    assert!(loaded[0].binding().is_none());
+    assert!(verify_review_for_vendor(&loaded[0], &escrow.vendor_id)?);
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//! Review amendments and vendor replies: signed revision history and
+//! replies stored with the review, exported and verifiable from the file
+//!
+//! Run with: cargo test --package server --test review_revision_test
+
+mod common;
+
+use anyhow::Result;
+use common::{create_escrow, setup_test_db};
+use ed25519_dalek::SigningKey;
+use reputation_common::types::ScoringParams;
+use reputation_crypto::reputation::{
+    public_key_b64, sign_bound_review, sign_reputation, verify_review_for_vendor,
+};
+use reputation_crypto::revision::{amend_review, sign_reply};
+use server::config::ReviewPolicy;
+use server::db::reputation::{
+    db_amend_review, db_get_review, db_get_vendor_reputation, db_get_vendor_review,
+    db_insert_review, db_register_reputation_key, db_set_review_reply,
+};
+use server::handlers::reputation_ipfs::verify_imported_reputation;
+use server::models::escrow::EscrowStatus;
+use uuid::Uuid;
+
+/// Test: an amendment replaces the stored version once, keeping the old
+/// one in a history that still verifies
+#[actix_web::test]
+async fn test_amendment_keeps_signed_history() -> Result<()> {
+    let (pool, db_path) = setup_test_db("review_revision")?;
+    let escrow = create_escrow(&mut *pool.get()?, EscrowStatus::Completed)?;
+    let vendor: Uuid = escrow.vendor_id.parse()?;
+    let vendor_key = SigningKey::from_bytes(&[51u8; 32]);
+    let vendor_pubkey = public_key_b64(&vendor_key);
+    db_register_reputation_key(&pool, &escrow.vendor_id, &vendor_pubkey).await?;
+
+    let buyer_key = SigningKey::from_bytes(&[52u8; 32]);
+    let policy = ReviewPolicy::default();
+    let txid = "e".repeat(64);
+    let original = sign_bound_review(
+        txid.clone(),
+        2,
+        Some("Still waiting".to_string()),
+        policy.binding_for(&vendor_pubkey, &escrow.id),
+        &buyer_key,
+    )?;
+    let stored = db_insert_review(&pool, &original, &escrow.buyer_id, &escrow.vendor_id).await?;
+    assert_eq!(stored.revisions, None);
+    assert!(policy.can_amend(stored.created_at, chrono::Utc::now().naive_utc()));
+
+    let amended = amend_review(&original, 5, Some("Arrived".to_string()), &buyer_key)?;
+    assert_eq!(db_amend_review(&pool, &stored.id, &amended).await?, 1);
+    // A second amendment of the same version lost the race
+    let stale = amend_review(&original, 1, None, &buyer_key)?;
+    assert_eq!(db_amend_review(&pool, &stored.id, &stale).await?, 0);
+
+    let current = db_get_review(&pool, &txid, &escrow.buyer_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("review not found"))?;
+    assert_eq!(current.id, stored.id);
+    assert_eq!(current.rating, 5);
+    assert_eq!(current.created_at, stored.created_at);
+
+    let reputation = db_get_vendor_reputation(&pool, vendor, &ScoringParams::default()).await?;
+    assert_eq!(reputation.stats.rating_distribution, [0, 0, 0, 0, 1]);
+    let review = &reputation.reviews[0];
+    assert_eq!(review.revisions.len(), 1);
+    assert_eq!(review.revisions[0].rating, 2);
+    assert_eq!(review.revisions[0].signature, original.signature);
+    assert!(verify_review_for_vendor(review, &vendor_pubkey)?);
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: the vendor's reply is stored with the review and exported in a
+/// file another marketplace verifies; a forged reply is rejected
+#[actix_web::test]
+async fn test_vendor_reply_exported() -> Result<()> {
+    let (pool, db_path) = setup_test_db("review_revision")?;
+    let escrow = create_escrow(&mut *pool.get()?, EscrowStatus::Completed)?;
+    let vendor: Uuid = escrow.vendor_id.parse()?;
+    let vendor_key = SigningKey::from_bytes(&[53u8; 32]);
+    let vendor_pubkey = public_key_b64(&vendor_key);
+    db_register_reputation_key(&pool, &escrow.vendor_id, &vendor_pubkey).await?;
+
+    let buyer_key = SigningKey::from_bytes(&[54u8; 32]);
+    let policy = ReviewPolicy::default();
+    let txid = "f".repeat(64);
+    let review = sign_bound_review(
+        txid.clone(),
+        3,
+        Some("Packaging damaged".to_string()),
+        policy.binding_for(&vendor_pubkey, &escrow.id),
+        &buyer_key,
+    )?;
+    db_insert_review(&pool, &review, &escrow.buyer_id, &escrow.vendor_id).await?;
+
+    // Found by the vendor it is about, not by anyone else
+    assert!(db_get_vendor_review(&pool, &txid, &escrow.buyer_id)
+        .await?
+        .is_none());
+    let stored = db_get_vendor_review(&pool, &txid, &escrow.vendor_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("review not found"))?;
+
+    let reply = sign_reply(&review, "Sorry, replacement sent".to_string(), &vendor_key)?;
+    assert_eq!(db_set_review_reply(&pool, &stored.id, &reply).await?, 1);
+
+    let params = ScoringParams::default();
+    let reputation = db_get_vendor_reputation(&pool, vendor, &params).await?;
+    assert_eq!(reputation.reviews[0].reply, Some(reply.clone()));
+    let file = sign_reputation(reputation, &vendor_key)?;
+
+    let elsewhere = ReviewPolicy {
+        marketplace_domain: "other.example.onion".to_string(),
+        ..ReviewPolicy::default()
+    };
+    let summary = verify_imported_reputation(&file, &vendor_pubkey, &elsewhere)?;
+    assert_eq!(summary.source_domains, vec![policy.marketplace_domain]);
+
+    // A reply signed with another key does not pass, even re-signed by the vendor
+    let mut forged = file.clone();
+    forged.reviews[0].reply = Some(sign_reply(&review, "Great buyer".to_string(), &buyer_key)?);
+    let forged = sign_reputation(forged, &vendor_key)?;
+    assert!(verify_imported_reputation(&forged, &vendor_pubkey, &elsewhere).is_err());
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
@@ -0,0 +1,571 @@
+//! BlockchainMonitor / TimeoutMonitor driven by a simulated chain
+//!
+//! Both monitors take their time and block height from a `SimulatedChain`
+//! (test-support) instead of the system clock, and the poll methods are
+//! called directly. Funding, confirmation counting and expiry are exercised
+//! in milliseconds instead of hours.
+//!
+//! Run with: cargo test --package server --test simulated_chain_monitor_test
+
+mod common;
+
+use anyhow::Result;
+use chrono::Duration;
+use common::{setup_test_db, ONE_XMR};
+use diesel::prelude::*;
+use monero_marketplace_common::clock::ChainClock;
+use monero_marketplace_common::types::MoneroConfig;
+use monero_marketplace_test_support::mock_rpc::{
+    create_multisig_wallets, MockWalletRpc, DEFAULT_UNLOCK_WINDOW,
+};
+use monero_marketplace_test_support::{MockNetwork, SimulatedChain};
+use serde_json::json;
+use server::config::confirmation::ConfirmationTier;
+use server::config::{ConfirmationPolicy, TimeoutConfig};
+use server::db::{db_load_escrow, DbPool};
+use server::models::audit_log::AuditEntry;
+use server::models::escrow::{EscrowStatus, NewEscrow};
+use server::models::listing::{Listing, NewListing};
+use server::models::order::{NewOrder, Order};
+use server::models::user::{NewUser, User};
+use server::schema::{escrows, transactions, users};
+use server::services::blockchain_monitor::{BlockchainMonitor, MonitorConfig};
+use server::services::timeout_monitor::TimeoutMonitor;
+use server::services::wallet_session_manager::WalletSessionManager;
+use server::wallet_manager::{WalletManager, PAYOUT_FEE_RESERVE};
+use server::wallet_pool::WalletPool;
+use server::websocket::WebSocketServer;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Insert buyer, vendor, arbiter, listing, order and an escrow in `status`
+fn insert_escrow(
+    conn: &mut SqliteConnection,
+    escrow_id: Uuid,
+    status: &str,
+    multisig_address: Option<&str>,
+) -> Result<Uuid> {
+    let mut party = |role: &str| -> Result<String> {
+        let id = Uuid::new_v4().to_string();
+        User::create(
+            conn,
+            NewUser {
+                id: id.clone(),
+                username: format!("{}_{}", role, &id[..8]),
+                password_hash: "hashed_password".to_string(),
+                role: role.to_string(),
+                wallet_address: None,
+                wallet_id: None,
+            },
+        )?;
+        Ok(id)
+    };
+    let buyer_id = party("buyer")?;
+    let vendor_id = party("vendor")?;
+    let arbiter_id = party("arbiter")?;
+
+    let listing_id = Uuid::new_v4().to_string();
+    Listing::create(
+        conn,
+        NewListing {
+            id: listing_id.clone(),
+            vendor_id: vendor_id.clone(),
+            title: "Test Product".to_string(),
+            description: "Simulated chain test listing".to_string(),
+            price_xmr: ONE_XMR,
+            stock: 10,
+            status: "active".to_string(),
+            images_ipfs_cids: None,
+            category: "other".to_string(),
+        },
+    )?;
+
+    let order_id = Uuid::new_v4().to_string();
+    Order::create(
+        conn,
+        NewOrder {
+            id: order_id.clone(),
+            buyer_id: buyer_id.clone(),
+            vendor_id: vendor_id.clone(),
+            listing_id,
+            escrow_id: None,
+            status: "pending".to_string(),
+            total_xmr: ONE_XMR,
+            shipping_address: None,
+            shipping_notes: None,
+        },
+    )?;
+
+    diesel::insert_into(escrows::table)
+        .values(&NewEscrow {
+            id: escrow_id.to_string(),
+            order_id,
+            buyer_id,
+            vendor_id,
+            arbiter_id,
+            amount: ONE_XMR,
+            status: status.to_string(),
+        })
+        .execute(conn)?;
+
+    if let Some(address) = multisig_address {
+        diesel::update(escrows::table.filter(escrows::id.eq(escrow_id.to_string())))
+            .set(escrows::multisig_address.eq(address))
+            .execute(conn)?;
+    }
+
+    Ok(escrow_id)
+}
+
+/// Set `expires_at` from the timeout policy of `status`, as of the chain clock
+fn set_deadline(
+    conn: &mut SqliteConnection,
+    escrow_id: Uuid,
+    status: EscrowStatus,
+    config: &TimeoutConfig,
+    chain: &SimulatedChain,
+) -> Result<()> {
+    diesel::update(escrows::table.filter(escrows::id.eq(escrow_id.to_string())))
+        .set((
+            escrows::expires_at.eq(config.deadline_for_status(status, chain)),
+            escrows::last_activity_at.eq(chain.now_naive()),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+fn config_for(rpc: &MockWalletRpc) -> MoneroConfig {
+    MoneroConfig {
+        rpc_url: rpc.url().to_string(),
+        rpc_user: None,
+        rpc_password: None,
+        timeout_seconds: 10,
+    }
+}
+
+async fn escrow_status(pool: &DbPool, escrow_id: Uuid) -> Result<String> {
+    Ok(db_load_escrow(pool, escrow_id).await?.status)
+}
+
+/// Escrow waiting for its deposit, with multisig wallets on `network` and a monitor
+async fn start_funding_escrow(
+    pool: &DbPool,
+    network: &MockNetwork,
+    chain: &SimulatedChain,
+    config: MonitorConfig,
+) -> Result<(BlockchainMonitor, [MockWalletRpc; 3], Uuid, String)> {
+    let buyer_rpc = MockWalletRpc::start("buyer", network).await?;
+    let vendor_rpc = MockWalletRpc::start("vendor", network).await?;
+    let arbiter_rpc = MockWalletRpc::start("arbiter", network).await?;
+
+    // Escrow wallets as left by the multisig setup
+    let escrow_id = Uuid::new_v4();
+    let multisig_address = create_multisig_wallets(
+        [&buyer_rpc, &vendor_rpc, &arbiter_rpc],
+        [
+            &format!("buyer_temp_escrow_{}", escrow_id),
+            &format!("vendor_temp_escrow_{}", escrow_id),
+            &format!("arbiter_temp_escrow_{}", escrow_id),
+        ],
+    )?;
+
+    insert_escrow(
+        &mut *pool.get()?,
+        escrow_id,
+        "created",
+        Some(&multisig_address),
+    )?;
+
+    // WalletPool hands out instances in order: buyer, vendor, arbiter
+    let wallet_pool = Arc::new(WalletPool::new(
+        vec![buyer_rpc.port(), vendor_rpc.port(), arbiter_rpc.port()],
+        std::env::temp_dir(),
+    ));
+    let session_manager = Arc::new(WalletSessionManager::new(wallet_pool));
+    session_manager.get_or_create_session(escrow_id).await?;
+
+    let wallet_manager = WalletManager::new(vec![
+        config_for(&buyer_rpc),
+        config_for(&vendor_rpc),
+        config_for(&arbiter_rpc),
+    ])?;
+    let websocket = actix::Actor::start(WebSocketServer::default());
+    let monitor = BlockchainMonitor::new(
+        Arc::new(tokio::sync::Mutex::new(wallet_manager)),
+        session_manager,
+        pool.clone(),
+        websocket,
+        config,
+    )
+    .with_clock(chain.shared());
+
+    Ok((
+        monitor,
+        [buyer_rpc, vendor_rpc, arbiter_rpc],
+        escrow_id,
+        multisig_address,
+    ))
+}
+
+/// Test: funding detected only once unlocked, release finalized after N confirmations
+#[actix_web::test]
+async fn test_funding_and_confirmations_on_simulated_chain() -> Result<()> {
+    let (pool, db_path) = setup_test_db("sim_chain")?;
+    let network = MockNetwork::new();
+    let chain = SimulatedChain::new(&network);
+
+    let config = MonitorConfig::default();
+    let (monitor, [buyer_rpc, vendor_rpc, arbiter_rpc], escrow_id, multisig_address) =
+        start_funding_escrow(&pool, &network, &chain, config.clone()).await?;
+
+    // Deposit in the mempool: visible but locked
+    network.fund(&multisig_address, ONE_XMR as u64);
+    monitor.poll_escrows().await?;
+    assert_eq!(escrow_status(&pool, escrow_id).await?, "created");
+
+    chain.mine_blocks(9);
+    monitor.poll_escrows().await?;
+    assert_eq!(escrow_status(&pool, escrow_id).await?, "created");
+
+    chain.mine_blocks(1);
+    monitor.poll_escrows().await?;
+    assert_eq!(escrow_status(&pool, escrow_id).await?, "active");
+
+    // Release: buyer creates, arbiter co-signs, buyer submits
+    let vendor_payout = vendor_rpc.call("get_address", json!({}))?["address"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+    let created = buyer_rpc.call(
+        "transfer",
+        json!({ "destinations": [{ "address": vendor_payout, "amount": ONE_XMR / 2 }] }),
+    )?;
+    let signed = arbiter_rpc.call(
+        "sign_multisig",
+        json!({ "tx_data_hex": created["multisig_txset"] }),
+    )?;
+    buyer_rpc.call(
+        "submit_multisig",
+        json!({ "tx_data_hex": signed["tx_data_hex"] }),
+    )?;
+    let tx_hash = created["tx_hash"].as_str().unwrap_or_default().to_string();
+
+    {
+        let mut conn = pool.get()?;
+        diesel::update(escrows::table.filter(escrows::id.eq(escrow_id.to_string())))
+            .set((
+                escrows::status.eq("releasing"),
+                escrows::transaction_hash.eq(&tx_hash),
+            ))
+            .execute(&mut conn)?;
+    }
+
+    monitor.poll_escrows().await?;
+    assert_eq!(escrow_status(&pool, escrow_id).await?, "releasing");
+
+    let required = config.confirmations.required_for(ONE_XMR as u64);
+    chain.mine_blocks(required as u64 - 1);
+    monitor.poll_escrows().await?;
+    assert_eq!(escrow_status(&pool, escrow_id).await?, "releasing");
+
+    chain.mine_blocks(1);
+    monitor.poll_escrows().await?;
+    assert_eq!(escrow_status(&pool, escrow_id).await?, "completed");
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: a higher amount tier holds funding back past the unlock window
+#[actix_web::test]
+async fn test_funding_waits_for_amount_tier_confirmations() -> Result<()> {
+    let (pool, db_path) = setup_test_db("sim_chain")?;
+    let network = MockNetwork::new();
+    let chain = SimulatedChain::new(&network);
+
+    // 1 XMR escrows need 15 confirmations instead of 10
+    let config = MonitorConfig {
+        confirmations: ConfirmationPolicy::new(
+            10,
+            vec![ConfirmationTier {
+                min_amount_atomic: ONE_XMR as u64,
+                required_confirmations: 15,
+            }],
+        ),
+        ..MonitorConfig::default()
+    };
+    let (monitor, _wallets, escrow_id, multisig_address) =
+        start_funding_escrow(&pool, &network, &chain, config).await?;
+
+    // Unlocked after 10 blocks, but not enough for this tier
+    network.fund_unlocked(&multisig_address, ONE_XMR as u64);
+    monitor.poll_escrows().await?;
+    assert_eq!(escrow_status(&pool, escrow_id).await?, "created");
+
+    chain.mine_blocks(4);
+    monitor.poll_escrows().await?;
+    assert_eq!(escrow_status(&pool, escrow_id).await?, "created");
+
+    chain.mine_blocks(1);
+    monitor.poll_escrows().await?;
+    assert_eq!(escrow_status(&pool, escrow_id).await?, "active");
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: partial deposit → underfunded, top-ups summed, surplus recorded
+#[actix_web::test]
+async fn test_underfunded_then_overfunded_by_top_up() -> Result<()> {
+    let (pool, db_path) = setup_test_db("sim_chain")?;
+    let network = MockNetwork::new();
+    let chain = SimulatedChain::new(&network);
+
+    let (monitor, _wallets, escrow_id, multisig_address) =
+        start_funding_escrow(&pool, &network, &chain, MonitorConfig::default()).await?;
+
+    // 60% paid: underfunded as soon as the deposit is seen
+    network.fund(&multisig_address, (ONE_XMR * 6 / 10) as u64);
+    monitor.poll_escrows().await?;
+    let escrow = db_load_escrow(&pool, escrow_id).await?;
+    assert_eq!(escrow.status, "underfunded");
+    assert_eq!(escrow.funded_amount, ONE_XMR * 6 / 10);
+
+    // Still short once confirmed
+    chain.mine_blocks(10);
+    monitor.poll_escrows().await?;
+    assert_eq!(escrow_status(&pool, escrow_id).await?, "underfunded");
+
+    // Top-up overshoots: both deposits counted once confirmed
+    network.fund(&multisig_address, (ONE_XMR / 2) as u64);
+    monitor.poll_escrows().await?;
+    let escrow = db_load_escrow(&pool, escrow_id).await?;
+    assert_eq!(escrow.status, "underfunded");
+    assert_eq!(escrow.funded_amount, ONE_XMR * 11 / 10);
+
+    chain.mine_blocks(10);
+    monitor.poll_escrows().await?;
+    let escrow = db_load_escrow(&pool, escrow_id).await?;
+    assert_eq!(escrow.status, "overfunded");
+    assert_eq!(escrow.surplus(), ONE_XMR / 10);
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: deadlines follow the simulated clock, not the wall clock
+#[actix_web::test]
+async fn test_expiry_on_simulated_chain() -> Result<()> {
+    let (pool, db_path) = setup_test_db("sim_chain")?;
+    let network = MockNetwork::new();
+    let chain = SimulatedChain::new(&network);
+    let config = TimeoutConfig::default();
+
+    let (setup_escrow, funding_escrow) = {
+        let mut conn = pool.get()?;
+        let setup_escrow = insert_escrow(&mut conn, Uuid::new_v4(), "created", None)?;
+        set_deadline(
+            &mut conn,
+            setup_escrow,
+            EscrowStatus::Created,
+            &config,
+            &chain,
+        )?;
+        let funding_escrow =
+            insert_escrow(&mut conn, Uuid::new_v4(), "funded", Some("5escrowaddress"))?;
+        set_deadline(
+            &mut conn,
+            funding_escrow,
+            EscrowStatus::Funded,
+            &config,
+            &chain,
+        )?;
+        (setup_escrow, funding_escrow)
+    };
+
+    let websocket = actix::Actor::start(WebSocketServer::default());
+    let monitor =
+        TimeoutMonitor::new(pool.clone(), websocket, config.clone()).with_clock(chain.shared());
+
+    monitor.check_timeouts().await;
+    assert_eq!(escrow_status(&pool, setup_escrow).await?, "created");
+    assert_eq!(escrow_status(&pool, funding_escrow).await?, "funded");
+
+    // 1h of blocks: multisig setup deadline passed, funding still open
+    chain.mine_for(Duration::hours(1));
+    chain.advance(Duration::seconds(1));
+    monitor.check_timeouts().await;
+    assert_eq!(escrow_status(&pool, setup_escrow).await?, "cancelled");
+    assert_eq!(escrow_status(&pool, funding_escrow).await?, "funded");
+
+    // Within the warning window the escrow is expiring, not expired
+    chain.advance(Duration::hours(22) + Duration::minutes(30));
+    let escrow = db_load_escrow(&pool, funding_escrow).await?;
+    let now = chain.now_naive();
+    assert!(!escrow.is_expired_at(now));
+    assert!(escrow.is_expiring_soon_at(now, config.warning_threshold_secs as i64));
+    // ...while the wall clock still sees a full day ahead
+    assert!(!escrow.is_expiring_soon(config.warning_threshold_secs as i64));
+
+    chain.advance(Duration::hours(1));
+    assert!(db_load_escrow(&pool, funding_escrow)
+        .await?
+        .is_expired_at(chain.now_naive()));
+    monitor.check_timeouts().await;
+    assert_eq!(escrow_status(&pool, funding_escrow).await?, "cancelled");
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: an expired escrow holding a partial deposit is not cancelled until it is refunded
+#[actix_web::test]
+async fn test_funding_timeout_keeps_partial_deposit_without_refund() -> Result<()> {
+    let (pool, db_path) = setup_test_db("sim_chain")?;
+    let network = MockNetwork::new();
+    let chain = SimulatedChain::new(&network);
+    let config = TimeoutConfig::default();
+
+    let escrow_id = {
+        let mut conn = pool.get()?;
+        let escrow_id =
+            insert_escrow(&mut conn, Uuid::new_v4(), "underfunded", Some("5escrowaddress"))?;
+        set_deadline(
+            &mut conn,
+            escrow_id,
+            EscrowStatus::Underfunded,
+            &config,
+            &chain,
+        )?;
+        diesel::update(escrows::table.filter(escrows::id.eq(escrow_id.to_string())))
+            .set(escrows::funded_amount.eq(ONE_XMR / 2))
+            .execute(&mut conn)?;
+        escrow_id
+    };
+
+    // No wallet manager to co-sign the refund: the deposit must not be stranded
+    let websocket = actix::Actor::start(WebSocketServer::default());
+    let monitor =
+        TimeoutMonitor::new(pool.clone(), websocket, config.clone()).with_clock(chain.shared());
+
+    chain.advance(Duration::hours(25));
+    monitor.check_timeouts().await;
+    let escrow = db_load_escrow(&pool, escrow_id).await?;
+    assert_eq!(escrow.status, "underfunded");
+    assert_eq!(escrow.funded_amount, ONE_XMR / 2);
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
+
+/// Test: an expired escrow's unlocked deposit, less the fee reserve, goes back
+/// to the buyer once; the escrow is cancelled and the refund is not repeated
+#[actix_web::test]
+async fn test_funding_timeout_refunds_partial_deposit() -> Result<()> {
+    let (pool, db_path) = setup_test_db("sim_chain")?;
+    let network = MockNetwork::new();
+    let chain = SimulatedChain::new(&network);
+    let config = TimeoutConfig::default();
+
+    let buyer_rpc = MockWalletRpc::start("buyer", &network).await?;
+    let vendor_rpc = MockWalletRpc::start("vendor", &network).await?;
+    let arbiter_rpc = MockWalletRpc::start("arbiter", &network).await?;
+    let escrow_id = Uuid::new_v4();
+    let multisig_address = create_multisig_wallets(
+        [&buyer_rpc, &vendor_rpc, &arbiter_rpc],
+        [
+            &format!("buyer_temp_escrow_{}", escrow_id),
+            &format!("vendor_temp_escrow_{}", escrow_id),
+            &format!("arbiter_temp_escrow_{}", escrow_id),
+        ],
+    )?;
+
+    let buyer_payout = format!("9{}", "b".repeat(94));
+    {
+        let mut conn = pool.get()?;
+        insert_escrow(&mut conn, escrow_id, "underfunded", Some(&multisig_address))?;
+        set_deadline(
+            &mut conn,
+            escrow_id,
+            EscrowStatus::Underfunded,
+            &config,
+            &chain,
+        )?;
+        let escrow = db_load_escrow(&pool, escrow_id).await?;
+        diesel::update(users::table.filter(users::id.eq(&escrow.buyer_id)))
+            .set(users::wallet_address.eq(&buyer_payout))
+            .execute(&mut conn)?;
+    }
+
+    // Half the order amount deposited, and more still in the pool
+    network.fund_unlocked(&multisig_address, (ONE_XMR / 2) as u64);
+    network.fund(&multisig_address, (ONE_XMR / 4) as u64);
+    diesel::update(escrows::table.filter(escrows::id.eq(escrow_id.to_string())))
+        .set(escrows::funded_amount.eq(3 * ONE_XMR / 4))
+        .execute(&mut *pool.get()?)?;
+
+    let wallet_manager = WalletManager::new(vec![
+        config_for(&buyer_rpc),
+        config_for(&vendor_rpc),
+        config_for(&arbiter_rpc),
+    ])?;
+    let websocket = actix::Actor::start(WebSocketServer::default());
+    let monitor = TimeoutMonitor::new(pool.clone(), websocket, config.clone())
+        .with_clock(chain.shared())
+        .with_wallet_manager(Arc::new(tokio::sync::Mutex::new(wallet_manager)));
+
+    // Expired, but part of the deposit is still locked: nothing is sent yet
+    chain.advance(Duration::hours(25));
+    monitor.check_timeouts().await;
+    assert_eq!(escrow_status(&pool, escrow_id).await?, "underfunded");
+    assert!(network.transfers_for(&buyer_payout).is_empty());
+
+    network.mine_blocks(DEFAULT_UNLOCK_WINDOW);
+    monitor.check_timeouts().await;
+    let escrow = db_load_escrow(&pool, escrow_id).await?;
+    assert_eq!(escrow.status, "cancelled");
+
+    let refunds = network.transfers_for(&buyer_payout);
+    assert_eq!(refunds.len(), 1);
+    let refunded = (3 * ONE_XMR / 4) as u64 - PAYOUT_FEE_RESERVE;
+    assert_eq!(
+        refunds[0].destinations,
+        vec![(buyer_payout.clone(), refunded)]
+    );
+    assert_eq!(
+        escrow.transaction_hash.as_deref(),
+        Some(refunds[0].txid.as_str())
+    );
+
+    // Recorded as an ordinary refund: no split amounts
+    let (amount, buyer_amount, vendor_amount): (i64, Option<i64>, Option<i64>) =
+        transactions::table
+            .filter(transactions::escrow_id.eq(escrow_id.to_string()))
+            .select((
+                transactions::amount_xmr,
+                transactions::buyer_amount_xmr,
+                transactions::vendor_amount_xmr,
+            ))
+            .first(&mut *pool.get()?)?;
+    assert_eq!((amount, buyer_amount, vendor_amount), (refunded as i64, None, None));
+
+    // Audited with the cancellation
+    let audit = AuditEntry::find_by_entity(&mut *pool.get()?, &escrow_id.to_string())?;
+    assert_eq!(audit.len(), 1);
+    assert_eq!(audit[0].event_type, "refund");
+    assert_eq!(audit[0].actor, "system:timeout_monitor");
+    assert!(audit[0].data.contains(&refunds[0].txid));
+
+    // Cancellation lost after the broadcast: the next tick cancels again
+    // without sending a second refund
+    diesel::update(escrows::table.filter(escrows::id.eq(escrow_id.to_string())))
+        .set(escrows::status.eq("underfunded"))
+        .execute(&mut *pool.get()?)?;
+    network.fund_unlocked(&multisig_address, (ONE_XMR / 2) as u64);
+    monitor.check_timeouts().await;
+    assert_eq!(escrow_status(&pool, escrow_id).await?, "cancelled");
+    assert_eq!(network.transfers_for(&buyer_payout).len(), 1);
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
@@ -0,0 +1,235 @@
+//! Vendor bond lifecycle against the in-process mock wallet RPC
+//!
+//! Opens a bond (custodial 2-of-3 setup through WalletManager), funds it on
+//! the mock chain, slashes part of it into a disputed escrow's payout and
+//! releases the rest when the vendor retires.
+//!
+//! Run with: cargo test --package server --test vendor_bond_test
+//! (takes ~20s: WalletManager waits 10s between make_multisig calls)
+
+mod common;
+
+use anyhow::Result;
+use common::{create_listing, create_user, setup_test_db, ONE_XMR};
+use diesel::prelude::*;
+use monero_marketplace_common::types::MoneroConfig;
+use monero_marketplace_test_support::mock_rpc::{MockNetwork, MockWalletRpc};
+use server::config::BondPolicy;
+use server::db::DbPool;
+use server::models::audit_log::AuditEntry;
+use server::models::escrow::NewEscrow;
+use server::models::listing::Listing;
+use server::models::order::{NewOrder, Order};
+use server::models::vendor_bond::VendorBond;
+use server::schema::{escrows, orders, users};
+use server::services::vendor_bond::VendorBondService;
+use server::wallet_manager::WalletManager;
+use server::websocket::WebSocketServer;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Listing, order and disputed escrow between `buyer` and `vendor`
+fn insert_disputed_escrow(
+    conn: &mut SqliteConnection,
+    buyer: Uuid,
+    vendor: Uuid,
+    arbiter: Uuid,
+) -> Result<(Uuid, String)> {
+    let listing_id = create_listing(conn, &vendor.to_string(), "Bonded Product", ONE_XMR, 10)?;
+
+    let order_id = Uuid::new_v4().to_string();
+    Order::create(
+        conn,
+        NewOrder {
+            id: order_id.clone(),
+            buyer_id: buyer.to_string(),
+            vendor_id: vendor.to_string(),
+            listing_id: listing_id.clone(),
+            escrow_id: None,
+            status: "disputed".to_string(),
+            total_xmr: ONE_XMR,
+            shipping_address: None,
+            shipping_notes: None,
+        },
+    )?;
+
+    let escrow_id = Uuid::new_v4();
+    diesel::insert_into(escrows::table)
+        .values(&NewEscrow {
+            id: escrow_id.to_string(),
+            order_id,
+            buyer_id: buyer.to_string(),
+            vendor_id: vendor.to_string(),
+            arbiter_id: arbiter.to_string(),
+            amount: ONE_XMR,
+            status: "disputed".to_string(),
+        })
+        .execute(conn)?;
+
+    Ok((escrow_id, listing_id))
+}
+
+fn config_for(rpc: &MockWalletRpc) -> MoneroConfig {
+    MoneroConfig {
+        rpc_url: rpc.url().to_string(),
+        rpc_user: None,
+        rpc_password: None,
+        timeout_seconds: 10,
+    }
+}
+
+/// Testnet-style payout address accepted by the payout validation
+fn payout_address(tag: char) -> String {
+    format!("9{}", tag.to_string().repeat(94))
+}
+
+fn load_bond(pool: &DbPool, bond_id: &str) -> Result<VendorBond> {
+    VendorBond::find_by_id(&mut *pool.get()?, bond_id)
+}
+
+/// Test: open → fund → slash into dispute payout → retire and release
+#[actix_web::test]
+async fn test_vendor_bond_lifecycle_with_mock_rpc() -> Result<()> {
+    let (pool, db_path) = setup_test_db("vendor_bond")?;
+    let policy = BondPolicy::default();
+
+    let (buyer, vendor, arbiter, escrow_id, listing_id) = {
+        let mut conn = pool.get()?;
+        let buyer: Uuid = create_user(&mut conn, "buyer")?.parse()?;
+        let vendor: Uuid = create_user(&mut conn, "vendor")?.parse()?;
+        let arbiter: Uuid = create_user(&mut conn, "arbiter")?.parse()?;
+        let (escrow_id, listing_id) = insert_disputed_escrow(&mut conn, buyer, vendor, arbiter)?;
+        (buyer, vendor, arbiter, escrow_id, listing_id)
+    };
+
+    let network = MockNetwork::new();
+    let marketplace_rpc = MockWalletRpc::start("marketplace", &network).await?;
+    let vendor_rpc = MockWalletRpc::start("vendor", &network).await?;
+    let arbiter_rpc = MockWalletRpc::start("arbiter", &network).await?;
+    let wallet_manager = WalletManager::new(vec![
+        config_for(&marketplace_rpc),
+        config_for(&vendor_rpc),
+        config_for(&arbiter_rpc),
+    ])?;
+
+    let websocket = actix::Actor::start(WebSocketServer::default());
+    let service = VendorBondService::new(
+        Arc::new(tokio::sync::Mutex::new(wallet_manager)),
+        pool.clone(),
+        websocket,
+        policy.clone(),
+    );
+
+    // Below the first tier, or from a non-vendor: rejected before any wallet work
+    assert!(service.open_bond(vendor, ONE_XMR / 2).await.is_err());
+    assert!(service.open_bond(buyer, 2 * ONE_XMR).await.is_err());
+
+    // Open: bond wallet ready, waiting for the deposit
+    let bond = service.open_bond(vendor, 2 * ONE_XMR).await?;
+    assert_eq!(bond.status, "awaiting_funding");
+    let bond_address = bond.multisig_address.clone().expect("multisig address");
+    assert_eq!(marketplace_rpc.address(), Some(bond_address.clone()));
+    assert!(service.open_bond(vendor, 2 * ONE_XMR).await.is_err());
+
+    // Not funded yet: no listing bonus
+    assert_eq!(service.refresh_funding(bond.id.parse()?).await?.status, "awaiting_funding");
+    let effective = VendorBond::effective_amount_for_vendor(&mut *pool.get()?, &vendor.to_string())?;
+    assert_eq!(policy.listing_limit(effective), Some(policy.unbonded_listing_limit));
+
+    network.fund_unlocked(&bond_address, 2 * ONE_XMR as u64);
+    let bond = service.refresh_funding(bond.id.parse()?).await?;
+    assert_eq!(bond.status, "active");
+    assert!(bond.funded_at.is_some());
+    let effective = VendorBond::effective_amount_for_vendor(&mut *pool.get()?, &vendor.to_string())?;
+    assert_eq!(effective, 2 * ONE_XMR);
+    assert_eq!(policy.listing_limit(effective), Some(25));
+
+    // Slash: only the escrow's arbiter, to the buyer's registered address,
+    // at most the remaining bond and the escrow amount
+    let reason = "Item never shipped".to_string();
+    assert!(service
+        .slash_bond(escrow_id, arbiter, ONE_XMR / 2, reason.clone())
+        .await
+        .is_err());
+    let buyer_payout = payout_address('b');
+    diesel::update(users::table.filter(users::id.eq(buyer.to_string())))
+        .set(users::wallet_address.eq(&buyer_payout))
+        .execute(&mut *pool.get()?)?;
+
+    assert!(service
+        .slash_bond(escrow_id, buyer, ONE_XMR / 2, reason.clone())
+        .await
+        .is_err());
+    assert!(service
+        .slash_bond(escrow_id, arbiter, 3 * ONE_XMR, reason.clone())
+        .await
+        .is_err());
+
+    let slash_tx = service
+        .slash_bond(escrow_id, arbiter, ONE_XMR / 2, reason.clone())
+        .await?;
+    let transfer = network.transfer(&slash_tx).expect("slash tx relayed");
+    assert_eq!(transfer.destinations, vec![(buyer_payout, (ONE_XMR / 2) as u64)]);
+
+    // The bond still covers it, but the escrow has only half its amount left
+    assert!(service
+        .slash_bond(escrow_id, arbiter, 3 * ONE_XMR / 4, reason)
+        .await
+        .is_err());
+
+    let bond = load_bond(&pool, &bond.id)?;
+    assert_eq!(bond.slashed_amount, ONE_XMR / 2);
+    assert_eq!(bond.remaining(), 3 * ONE_XMR / 2);
+    let slashes = bond.slashes(&mut *pool.get()?)?;
+    assert_eq!(slashes.len(), 1);
+    assert_eq!(slashes[0].escrow_id, escrow_id.to_string());
+    let audit = AuditEntry::find_by_entity(&mut *pool.get()?, &bond.id)?;
+    assert_eq!(
+        audit.iter().map(|e| e.event_type.as_str()).collect::<Vec<_>>(),
+        ["bond_slash"]
+    );
+    assert!(audit[0].data.contains(&slash_tx));
+
+    // Retire: blocked while the disputed order is open
+    let vendor_payout = payout_address('v');
+    assert!(service.retire_vendor(vendor, vendor_payout.clone()).await.is_err());
+
+    diesel::update(orders::table.filter(orders::vendor_id.eq(vendor.to_string())))
+        .set(orders::status.eq("refunded"))
+        .execute(&mut *pool.get()?)?;
+
+    // A failed payout leaves the vendor's listings active
+    marketplace_rpc.fail_next("transfer", "not enough money");
+    assert!(service.retire_vendor(vendor, vendor_payout.clone()).await.is_err());
+    assert_eq!(
+        Listing::find_by_id(&mut *pool.get()?, listing_id.clone())?.status,
+        "active"
+    );
+    assert_eq!(load_bond(&pool, &bond.id)?.status, "active");
+
+    let release_tx = service.retire_vendor(vendor, vendor_payout.clone()).await?;
+    let transfer = network.transfer(&release_tx).expect("release tx relayed");
+    assert_eq!(transfer.destinations.len(), 1);
+    assert_eq!(transfer.destinations[0].0, vendor_payout);
+
+    let bond = load_bond(&pool, &bond.id)?;
+    assert_eq!(bond.status, "released");
+    assert_eq!(bond.release_tx_hash.as_deref(), Some(release_tx.as_str()));
+    let audit = AuditEntry::find_by_entity(&mut *pool.get()?, &bond.id)?;
+    assert_eq!(
+        audit.iter().map(|e| e.event_type.as_str()).collect::<Vec<_>>(),
+        ["bond_slash", "bond_release"]
+    );
+    assert!(audit[1].data.contains(&release_tx));
+    assert_eq!(
+        Listing::find_by_id(&mut *pool.get()?, listing_id)?.status,
+        "inactive"
+    );
+    assert_eq!(
+        VendorBond::effective_amount_for_vendor(&mut *pool.get()?, &vendor.to_string())?,
+        0
+    );
+
+    let _ = std::fs::remove_file(db_path);
+    Ok(())
+}
@@ -0,0 +1,188 @@
+//! WalletManager 2-of-3 flow against the in-process mock wallet RPC
+//!
+//! Runs the custodial multisig setup and `release_funds` end to end with
+//! three `MockWalletRpc` instances (buyer, vendor, arbiter) sharing one
+//! `MockNetwork`, so no monero-wallet-rpc is needed.
+#![allow(deprecated)] // Exercises create_temporary_wallet() like the real-RPC E2E tests
+//!
+//! Run with: cargo test --test wallet_manager_mock_e2e
+//! (takes ~20s: WalletManager waits 10s between make_multisig calls)
+
+use monero_marketplace_common::types::{MoneroConfig, TransferDestination};
+use monero_marketplace_test_support::mock_rpc::{MockNetwork, MockWalletRpc, MOCK_TX_FEE};
+use server::wallet_manager::WalletManager;
+use uuid::Uuid;
+
+const ONE_XMR: u64 = 1_000_000_000_000;
+
+fn config_for(rpc: &MockWalletRpc) -> MoneroConfig {
+    MoneroConfig {
+        rpc_url: rpc.url().to_string(),
+        rpc_user: None,
+        rpc_password: None,
+        timeout_seconds: 10,
+    }
+}
+
+/// Custodial 2-of-3 setup through WalletManager, returns the multisig address
+async fn setup_multisig(manager: &mut WalletManager, escrow_id: Uuid) -> String {
+    let escrow_str = escrow_id.to_string();
+
+    let buyer_id = manager
+        .create_temporary_wallet(escrow_id, "buyer")
+        .await
+        .expect("buyer wallet");
+    let vendor_id = manager
+        .create_temporary_wallet(escrow_id, "vendor")
+        .await
+        .expect("vendor wallet");
+    let arbiter_id = manager
+        .create_temporary_wallet(escrow_id, "arbiter")
+        .await
+        .expect("arbiter wallet");
+
+    let mut infos = Vec::new();
+    for wallet_id in [buyer_id, vendor_id, arbiter_id] {
+        infos.push(
+            manager
+                .make_multisig(&escrow_str, wallet_id, vec![])
+                .await
+                .expect("prepare_multisig"),
+        );
+    }
+
+    manager
+        .exchange_multisig_info(escrow_id, infos)
+        .await
+        .expect("multisig exchange");
+    manager
+        .finalize_multisig(escrow_id)
+        .await
+        .expect("finalize multisig")
+}
+
+#[tokio::test]
+async fn test_wallet_manager_release_with_mock_rpc() {
+    let network = MockNetwork::new();
+    let buyer_rpc = MockWalletRpc::start("buyer", &network).await.unwrap();
+    let vendor_rpc = MockWalletRpc::start("vendor", &network).await.unwrap();
+    let arbiter_rpc = MockWalletRpc::start("arbiter", &network).await.unwrap();
+
+    // Role-based assignment: index % 3 == 0 buyer, 1 vendor, 2 arbiter
+    let mut manager = WalletManager::new(vec![
+        config_for(&buyer_rpc),
+        config_for(&vendor_rpc),
+        config_for(&arbiter_rpc),
+    ])
+    .expect("Failed to create WalletManager");
+
+    let escrow_id = Uuid::new_v4();
+    let multisig_address = setup_multisig(&mut manager, escrow_id).await;
+    assert_eq!(buyer_rpc.address(), Some(multisig_address.clone()));
+    assert_eq!(arbiter_rpc.address(), Some(multisig_address.clone()));
+
+    network.fund_unlocked(&multisig_address, 2 * ONE_XMR);
+
+    let vendor_payout = "9vendorpayoutaddress".to_string();
+    let tx_hash = manager
+        .release_funds(
+            escrow_id,
+            vec![TransferDestination {
+                address: vendor_payout.clone(),
+                amount: ONE_XMR,
+            }],
+        )
+        .await
+        .expect("release_funds");
+
+    let transfer = network.transfer(&tx_hash).expect("tx relayed");
+    assert_eq!(transfer.destinations, vec![(vendor_payout, ONE_XMR)]);
+    assert_eq!(
+        network.balance(&multisig_address).1,
+        ONE_XMR - MOCK_TX_FEE
+    );
+
+    // Arbiter co-signed: buyer created, arbiter signed, buyer submitted
+    assert_eq!(arbiter_rpc.call_count("sign_multisig"), 1);
+    assert_eq!(buyer_rpc.call_count("submit_multisig"), 1);
+    assert_eq!(vendor_rpc.call_count("sign_multisig"), 0);
+}
+
+#[tokio::test]
+async fn test_wallet_manager_split_release_with_mock_rpc() {
+    let network = MockNetwork::new();
+    let buyer_rpc = MockWalletRpc::start("buyer", &network).await.unwrap();
+    let vendor_rpc = MockWalletRpc::start("vendor", &network).await.unwrap();
+    let arbiter_rpc = MockWalletRpc::start("arbiter", &network).await.unwrap();
+
+    let mut manager = WalletManager::new(vec![
+        config_for(&buyer_rpc),
+        config_for(&vendor_rpc),
+        config_for(&arbiter_rpc),
+    ])
+    .expect("Failed to create WalletManager");
+
+    let escrow_id = Uuid::new_v4();
+    let multisig_address = setup_multisig(&mut manager, escrow_id).await;
+
+    // Escrow holds exactly the order amount: the fee reserve is shared
+    network.fund_unlocked(&multisig_address, ONE_XMR);
+
+    let buyer_payout = "9buyerrefundaddress".to_string();
+    let vendor_payout = "9vendorpayoutaddress".to_string();
+    let tx_hash = manager
+        .release_funds(
+            escrow_id,
+            vec![
+                TransferDestination {
+                    address: buyer_payout.clone(),
+                    amount: ONE_XMR * 6 / 10,
+                },
+                TransferDestination {
+                    address: vendor_payout.clone(),
+                    amount: ONE_XMR * 4 / 10,
+                },
+            ],
+        )
+        .await
+        .expect("release_funds (split)");
+
+    // One transaction, two destinations, fee reserve taken 60/40
+    let transfer = network.transfer(&tx_hash).expect("tx relayed");
+    const FEE_RESERVE: u64 = 100_000_000;
+    assert_eq!(
+        transfer.destinations,
+        vec![
+            (buyer_payout, (ONE_XMR - FEE_RESERVE) * 6 / 10),
+            (vendor_payout, (ONE_XMR - FEE_RESERVE) * 4 / 10),
+        ]
+    );
+    assert_eq!(arbiter_rpc.call_count("sign_multisig"), 1);
+    assert_eq!(buyer_rpc.call_count("submit_multisig"), 1);
+}
+
+#[tokio::test]
+async fn test_wallet_manager_skips_unhealthy_rpc() {
+    let network = MockNetwork::new();
+    let down = MockWalletRpc::start("buyer-down", &network).await.unwrap();
+    let vendor_rpc = MockWalletRpc::start("vendor", &network).await.unwrap();
+    let arbiter_rpc = MockWalletRpc::start("arbiter", &network).await.unwrap();
+    let buyer_rpc = MockWalletRpc::start("buyer", &network).await.unwrap();
+
+    down.fail_always("get_version", "RPC not responding");
+
+    // Indices 0 and 3 both serve the buyer role
+    let manager = WalletManager::new(vec![
+        config_for(&down),
+        config_for(&vendor_rpc),
+        config_for(&arbiter_rpc),
+        config_for(&buyer_rpc),
+    ])
+    .expect("Failed to create WalletManager");
+
+    let config = manager
+        .get_healthy_rpc_for_role(&server::wallet_manager::WalletRole::Buyer)
+        .await
+        .expect("healthy buyer RPC");
+    assert_eq!(config.rpc_url, buyer_rpc.url());
+}
@@ -87,6 +87,27 @@
             });
         }
 
+        // Message encryption key: generated here, only the public half is sent
+        const encryptionKeyInput = document.getElementById('signup-encryption-key');
+        if (signupForm && encryptionKeyInput && typeof MessageCrypto !== 'undefined') {
+            let signupKeyPair = null;
+            MessageCrypto.generateKeyPair()
+                .then(keyPair => {
+                    signupKeyPair = keyPair;
+                    encryptionKeyInput.value = keyPair.publicKey;
+                })
+                .catch(error => console.error('Failed to generate encryption key:', error));
+
+            signupForm.addEventListener('htmx:afterRequest', function(event) {
+                // Errors also come back as 200 HTML; only a redirect means the account exists
+                const registered = event.detail.xhr.getResponseHeader('HX-Redirect');
+                if (registered && signupKeyPair) {
+                    const username = document.getElementById('signup-username').value;
+                    MessageCrypto.saveKeyPair(username, signupKeyPair);
+                }
+            });
+        }
+
         // Debug info
         console.log('Login button:', loginTab);
         console.log('Signup button:', signupTab);
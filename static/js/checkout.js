@@ -128,6 +128,12 @@ class CheckoutFlow {
                 if (typeof lucide !== 'undefined') lucide.createIcons();
             }
 
+            // Encrypt address and notes to the vendors' keys: only they can read them
+            const encryptedAddress = await this.encryptShippingAddress({
+                ...shippingAddress,
+                notes: shippingNotes || null
+            });
+
             // Create order with shipping address
             const response = await fetch('/api/orders/create', {
                 method: 'POST',
@@ -137,8 +143,7 @@ class CheckoutFlow {
                 },
                 body: JSON.stringify({
                     checkout_mode: this.checkoutMode,
-                    shipping_address: JSON.stringify(shippingAddress),
-                    shipping_notes: shippingNotes || null
+                    shipping_address: encryptedAddress
                 })
             });
 
@@ -155,11 +160,20 @@ class CheckoutFlow {
                 // Show notification
                 this.showNotification('Informations enregistrées', 'success');
 
+                // Multi-vendor cart: one order (and one escrow) per vendor.
+                // This page funds the first one, the others are in "Mes commandes".
+                if (data.order_ids && data.order_ids.length > 1) {
+                    this.showNotification(
+                        `${data.order_ids.length} commandes créées (une par vendeur) - les autres sont dans Mes commandes`,
+                        'info'
+                    );
+                }
+
                 // Proceed to escrow initialization
                 await this.createOrderAndInitEscrow();
             } else {
                 console.error('[Checkout] Order creation failed:', data);
-                this.showNotification(data.message || 'Échec de la création de commande', 'error');
+                this.showNotification(data.error || data.message || 'Échec de la création de commande', 'error');
 
                 // Re-enable button
                 if (submitBtn) {
@@ -170,7 +184,7 @@ class CheckoutFlow {
             }
         } catch (error) {
             console.error('[Checkout] Shipping address submission error:', error);
-            this.showNotification('Erreur lors de l\'enregistrement de l\'adresse', 'error');
+            this.showNotification(error.message || 'Erreur lors de l\'enregistrement de l\'adresse', 'error');
 
             // Re-enable button
             const submitBtn = document.getElementById('submit-shipping-btn');
@@ -182,6 +196,26 @@ class CheckoutFlow {
         }
     }
 
+    /**
+     * Encrypt the shipping details to every vendor of the checkout
+     * @param {Object} shipping - Address fields and notes
+     * @returns {Promise<Object>} Envelope (see message-crypto.js)
+     */
+    async encryptShippingAddress(shipping) {
+        const response = await fetch(`/api/orders/shipping-keys?checkout_mode=${encodeURIComponent(this.checkoutMode)}`);
+        const data = await response.json();
+        if (!response.ok) {
+            throw new Error(data.error || 'Failed to load vendor keys');
+        }
+
+        const missing = data.vendors.filter(v => !v.public_key);
+        if (missing.length > 0) {
+            throw new Error('Vendor has not set up encrypted shipping yet');
+        }
+
+        return MessageCrypto.encrypt(JSON.stringify(shipping), data.vendors);
+    }
+
     /**
      * Check payment manually - forces blockchain verification
      */
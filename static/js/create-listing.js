@@ -30,6 +30,16 @@ document.addEventListener('DOMContentLoaded', () => {
         event.preventDefault();
 
         const formData = new FormData(form);
+
+        // Fiat price in cents; without it the listing is priced in XMR
+        const priceFiat = formData.get('price_fiat');
+        if (priceFiat) {
+            formData.set('price_fiat', Math.round(parseFloat(priceFiat) * 100));
+            formData.set('price_currency', formData.get('price_currency').trim().toLowerCase());
+        } else {
+            formData.delete('price_fiat');
+            formData.delete('price_currency');
+        }
         const resultDiv = document.getElementById('listing-result');
         const submitButton = form.querySelector('button[type="submit"]');
 
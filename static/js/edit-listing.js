@@ -21,6 +21,13 @@ document.addEventListener('DOMContentLoaded', () => {
             stock: parseInt(formData.get('stock'), 10),
         };
 
+        // Fiat price in cents; without it the listing is priced in XMR
+        const priceFiat = formData.get('price_fiat');
+        if (priceFiat) {
+            data.price_fiat = Math.round(parseFloat(priceFiat) * 100);
+            data.price_currency = formData.get('price_currency').trim().toLowerCase();
+        }
+
         resultDiv.innerHTML = '<p style="color: #888;">UPDATING...</p>';
         submitButton.disabled = true;
 
@@ -0,0 +1,194 @@
+/**
+ * Message Crypto
+ *
+ * End-to-end encryption of order messages with WebCrypto.
+ * Each user holds a P-256 key pair; the private key never leaves the browser
+ * (localStorage, per username) and only the public key is registered.
+ *
+ * Envelope (see server/src/crypto/message_envelope.rs):
+ * - random AES-256-GCM content key encrypts the body
+ * - per recipient: ECDH(ephemeral, recipient) → HKDF-SHA256 → AES-256-GCM
+ *   key wrapping the content key
+ *
+ * @module message-crypto
+ */
+
+const MessageCrypto = (() => {
+    const VERSION = 1;
+    const ALGORITHM = 'ECDH-P256+HKDF-SHA256+A256GCM';
+    const CURVE = { name: 'ECDH', namedCurve: 'P-256' };
+    const STORAGE_PREFIX = 'nexus-e2e-key:';
+
+    const encoder = new TextEncoder();
+    const decoder = new TextDecoder();
+
+    function toBase64(buffer) {
+        const bytes = new Uint8Array(buffer);
+        let binary = '';
+        bytes.forEach(b => { binary += String.fromCharCode(b); });
+        return btoa(binary);
+    }
+
+    function fromBase64(value) {
+        const binary = atob(value);
+        const bytes = new Uint8Array(binary.length);
+        for (let i = 0; i < binary.length; i++) {
+            bytes[i] = binary.charCodeAt(i);
+        }
+        return bytes;
+    }
+
+    function randomBytes(length) {
+        return crypto.getRandomValues(new Uint8Array(length));
+    }
+
+    async function importPublicKey(publicKeyB64) {
+        return crypto.subtle.importKey('raw', fromBase64(publicKeyB64), CURVE, true, []);
+    }
+
+    async function importPrivateKey(jwk) {
+        return crypto.subtle.importKey('jwk', jwk, CURVE, false, ['deriveBits']);
+    }
+
+    /**
+     * AES key wrapping the content key for one recipient
+     * @param {CryptoKey} privateKey - Our side of the ECDH
+     * @param {CryptoKey} publicKey - Their side of the ECDH
+     * @param {string} userId - Recipient, bound into the HKDF info
+     */
+    async function wrappingKey(privateKey, publicKey, userId) {
+        const shared = await crypto.subtle.deriveBits({ name: 'ECDH', public: publicKey }, privateKey, 256);
+        const hkdfKey = await crypto.subtle.importKey('raw', shared, 'HKDF', false, ['deriveKey']);
+        return crypto.subtle.deriveKey(
+            {
+                name: 'HKDF',
+                hash: 'SHA-256',
+                salt: new Uint8Array(0),
+                info: encoder.encode(`${ALGORITHM}:${userId}`)
+            },
+            hkdfKey,
+            { name: 'AES-GCM', length: 256 },
+            false,
+            ['encrypt', 'decrypt']
+        );
+    }
+
+    /**
+     * Generate a new key pair
+     * @returns {Promise<{publicKey: string, privateJwk: Object}>}
+     */
+    async function generateKeyPair() {
+        const pair = await crypto.subtle.generateKey(CURVE, true, ['deriveBits']);
+        const rawPublic = await crypto.subtle.exportKey('raw', pair.publicKey);
+        const privateJwk = await crypto.subtle.exportKey('jwk', pair.privateKey);
+        return { publicKey: toBase64(rawPublic), privateJwk };
+    }
+
+    function saveKeyPair(username, keyPair) {
+        localStorage.setItem(STORAGE_PREFIX + username.toLowerCase(), JSON.stringify(keyPair));
+    }
+
+    /**
+     * Key pair stored for `username` on this browser, or null
+     */
+    function loadKeyPair(username) {
+        const stored = localStorage.getItem(STORAGE_PREFIX + username.toLowerCase());
+        return stored ? JSON.parse(stored) : null;
+    }
+
+    /**
+     * Encrypt `plaintext` for every recipient that has a public key
+     * @param {string} plaintext - Message body
+     * @param {Array<{user_id: string, public_key: string}>} recipients
+     * @returns {Promise<Object>} Envelope ready to POST
+     */
+    async function encrypt(plaintext, recipients) {
+        const contentKeyBytes = randomBytes(32);
+        const contentKey = await crypto.subtle.importKey('raw', contentKeyBytes, 'AES-GCM', false, ['encrypt']);
+        const nonce = randomBytes(12);
+        const ciphertext = await crypto.subtle.encrypt({ name: 'AES-GCM', iv: nonce }, contentKey, encoder.encode(plaintext));
+
+        const ephemeral = await crypto.subtle.generateKey(CURVE, true, ['deriveBits']);
+        const ephemeralPublic = await crypto.subtle.exportKey('raw', ephemeral.publicKey);
+
+        const wrapped = [];
+        for (const recipient of recipients) {
+            const key = await wrappingKey(ephemeral.privateKey, await importPublicKey(recipient.public_key), recipient.user_id);
+            const wrapNonce = randomBytes(12);
+            const wrappedKey = await crypto.subtle.encrypt({ name: 'AES-GCM', iv: wrapNonce }, key, contentKeyBytes);
+            wrapped.push({
+                user_id: recipient.user_id,
+                nonce: toBase64(wrapNonce),
+                wrapped_key: toBase64(wrappedKey)
+            });
+        }
+
+        return {
+            version: VERSION,
+            algorithm: ALGORITHM,
+            ephemeral_public_key: toBase64(ephemeralPublic),
+            nonce: toBase64(nonce),
+            ciphertext: toBase64(ciphertext),
+            recipients: wrapped
+        };
+    }
+
+    /**
+     * Decrypt an envelope addressed to `userId`
+     * @returns {Promise<string|null>} Body, or null if not addressed to us
+     */
+    async function decrypt(envelope, userId, privateJwk) {
+        if (envelope.version !== VERSION || envelope.algorithm !== ALGORITHM) {
+            throw new Error('Unsupported message format');
+        }
+        const recipient = envelope.recipients.find(r => r.user_id === userId);
+        if (!recipient) {
+            return null;
+        }
+
+        const privateKey = await importPrivateKey(privateJwk);
+        const key = await wrappingKey(privateKey, await importPublicKey(envelope.ephemeral_public_key), userId);
+        const contentKeyBytes = await crypto.subtle.decrypt(
+            { name: 'AES-GCM', iv: fromBase64(recipient.nonce) }, key, fromBase64(recipient.wrapped_key)
+        );
+        const contentKey = await crypto.subtle.importKey('raw', contentKeyBytes, 'AES-GCM', false, ['decrypt']);
+        const plaintext = await crypto.subtle.decrypt(
+            { name: 'AES-GCM', iv: fromBase64(envelope.nonce) }, contentKey, fromBase64(envelope.ciphertext)
+        );
+        return decoder.decode(plaintext);
+    }
+
+    /**
+     * Key pair for `username`, created and registered if this browser has none
+     * @param {string} username - Current user
+     * @param {string} csrfToken - CSRF token for the registration request
+     */
+    async function ensureKeyPair(username, csrfToken) {
+        const existing = loadKeyPair(username);
+        if (existing) {
+            return existing;
+        }
+
+        const keyPair = await generateKeyPair();
+        const response = await fetch('/api/user/encryption-key', {
+            method: 'PUT',
+            headers: {
+                'Content-Type': 'application/json',
+                'X-CSRF-Token': csrfToken
+            },
+            body: JSON.stringify({ public_key: keyPair.publicKey })
+        });
+        if (!response.ok) {
+            throw new Error('Failed to register encryption key');
+        }
+        saveKeyPair(username, keyPair);
+        return keyPair;
+    }
+
+    return { generateKeyPair, saveKeyPair, loadKeyPair, ensureKeyPair, encrypt, decrypt };
+})();
+
+// Export for use in other scripts
+if (typeof module !== 'undefined' && module.exports) {
+    module.exports = { MessageCrypto };
+}
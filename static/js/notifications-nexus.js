@@ -118,6 +118,10 @@ class NexusNotificationManager {
             this.handleReviewInvitation(data.ReviewInvitation);
         } else if (data.DisputeResolved) {
             this.handleDisputeResolved(data.DisputeResolved);
+        } else if (data.EscrowUnderfunded) {
+            this.handleEscrowUnderfunded(data.EscrowUnderfunded);
+        } else if (data.EscrowOverfunded) {
+            this.handleEscrowOverfunded(data.EscrowOverfunded);
         }
     }
 
@@ -185,7 +189,7 @@ class NexusNotificationManager {
     handleTransactionConfirmed(data) {
         this.showToast(
             '⛓️ Transaction Confirmed',
-            `${data.confirmations} confirmation(s)`,
+            `${data.confirmations} of ${data.required_confirmations} confirmations`,
             'success',
             8000
         );
@@ -195,6 +199,34 @@ class NexusNotificationManager {
         }
     }
 
+    handleEscrowUnderfunded(data) {
+        this.showToast(
+            '⚠️ Partial Payment Received',
+            `Received ${this.formatXmr(data.received_atomic)} of ${this.formatXmr(data.expected_atomic)} XMR - send ${this.formatXmr(data.missing_atomic)} XMR more`,
+            'warning',
+            15000,
+            () => window.location.href = `/escrow/${data.escrow_id}`
+        );
+
+        if (this.notificationSound) {
+            this.playNotificationSound();
+        }
+    }
+
+    handleEscrowOverfunded(data) {
+        this.showToast(
+            '💰 Overpayment Received',
+            `${this.formatXmr(data.surplus_atomic)} XMR above the escrow amount will be returned on release`,
+            'info',
+            10000,
+            () => window.location.href = `/escrow/${data.escrow_id}`
+        );
+    }
+
+    formatXmr(atomic) {
+        return (atomic / 1e12).toFixed(12).replace(/\.?0+$/, '');
+    }
+
     handleNewMessage(data) {
         const preview = data.content.substring(0, 50) + (data.content.length > 50 ? '...' : '');
         this.showToast(
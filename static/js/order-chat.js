@@ -3,6 +3,8 @@
  *
  * Real-time vendor-buyer chat using HTMX polling.
  * Production-grade with auto-scroll, error handling, and accessibility.
+ * Messages are end-to-end encrypted with MessageCrypto (message-crypto.js);
+ * the server only ever sees envelopes.
  *
  * @module order-chat
  */
@@ -16,6 +18,7 @@ class OrderChat {
      * @param {string} options.currentUserId - Current user ID
      * @param {string} options.currentUsername - Current user username
      * @param {number} [options.pollInterval=3000] - Polling interval in ms
+     * @param {boolean} [options.shareWithArbiter=true] - Also encrypt to the escrow arbiter
      */
     constructor(options = {}) {
         this.orderId = options.orderId;
@@ -23,6 +26,11 @@ class OrderChat {
         this.currentUserId = options.currentUserId;
         this.currentUsername = options.currentUsername;
         this.pollInterval = options.pollInterval || 3000;
+        this.shareWithArbiter = options.shareWithArbiter !== false;
+
+        this.keyPair = null;
+        this.partyKeys = null;
+        this.decrypted = new Map();
 
         this.container = null;
         this.messagesContainer = null;
@@ -59,8 +67,15 @@ class OrderChat {
         }
 
         this.attachEventListeners();
-        this.loadMessages();
-        this.startPolling();
+        this.initEncryption()
+            .then(() => {
+                this.loadMessages();
+                this.startPolling();
+            })
+            .catch(error => {
+                console.error('Failed to set up message encryption:', error);
+                this.showError('Encrypted chat unavailable in this browser.');
+            });
 
         console.log('✅ Order chat initialized', {
             orderId: this.orderId,
@@ -90,6 +105,48 @@ class OrderChat {
         setTimeout(() => this.inputElement.focus(), 100);
     }
 
+    /**
+     * Load (or create and register) our key pair
+     */
+    async initEncryption() {
+        this.keyPair = await MessageCrypto.ensureKeyPair(this.currentUsername, this.csrfToken);
+    }
+
+    /**
+     * Fetch the public keys of the order parties
+     */
+    async loadPartyKeys() {
+        const response = await fetch(`/api/orders/${this.orderId}/message-keys`);
+        if (!response.ok) {
+            throw new Error(`HTTP ${response.status}`);
+        }
+        this.partyKeys = await response.json();
+        return this.partyKeys;
+    }
+
+    /**
+     * Plaintext of a message, decrypted once and cached
+     * @param {Object} message - Message with its envelope
+     * @returns {Promise<string>}
+     */
+    async decryptMessage(message) {
+        if (this.decrypted.has(message.id)) {
+            return this.decrypted.get(message.id);
+        }
+        let text;
+        try {
+            text = await MessageCrypto.decrypt(message.envelope, this.currentUserId, this.keyPair.privateJwk);
+            if (text === null) {
+                text = '[Message not encrypted for you]';
+            }
+        } catch (error) {
+            console.error('Failed to decrypt message:', error);
+            text = '[Unable to decrypt message on this device]';
+        }
+        this.decrypted.set(message.id, text);
+        return text;
+    }
+
     /**
      * Load messages from API
      */
@@ -106,6 +163,12 @@ class OrderChat {
             }
 
             const data = await response.json();
+            for (const message of data.messages) {
+                message.legacy = !message.envelope;
+                message.message = message.legacy
+                    ? message.legacy_message
+                    : await this.decryptMessage(message);
+            }
             this.renderMessages(data.messages);
 
         } catch (error) {
@@ -173,7 +236,7 @@ class OrderChat {
      */
     createMessageElement(message) {
         const messageEl = document.createElement('div');
-        messageEl.className = `chat-message ${message.is_current_user ? 'self' : 'other'}`;
+        messageEl.className = `chat-message ${message.is_current_user ? 'self' : 'other'}${message.legacy ? ' legacy' : ''}`;
         messageEl.setAttribute('data-message-id', message.id);
 
         const avatar = message.sender_username.charAt(0).toUpperCase();
@@ -185,6 +248,7 @@ class OrderChat {
                 <div class="chat-message-header">
                     <span class="chat-message-username">${this.escapeHtml(message.sender_username)}</span>
                     <span class="chat-message-time">${timestamp}</span>
+                    ${message.legacy ? '<span class="chat-message-legacy" title="Sent before end-to-end encryption; stored unencrypted and read-only">Unencrypted</span>' : ''}
                 </div>
                 <div class="chat-message-bubble">
                     ${this.escapeHtml(message.message)}
@@ -236,13 +300,25 @@ class OrderChat {
         this.sendButton.classList.add('loading');
 
         try {
+            const keys = await this.loadPartyKeys();
+            const counterparty = keys.buyer.user_id === this.currentUserId ? keys.vendor : keys.buyer;
+            if (!counterparty.public_key) {
+                throw new Error('The other party has not set up encrypted messaging yet');
+            }
+
+            const recipients = [counterparty, { user_id: this.currentUserId, public_key: this.keyPair.publicKey }];
+            if (this.shareWithArbiter && keys.arbiter && keys.arbiter.public_key) {
+                recipients.push(keys.arbiter);
+            }
+            const envelope = await MessageCrypto.encrypt(message, recipients);
+
             const response = await fetch(`/api/orders/${this.orderId}/messages`, {
                 method: 'POST',
                 headers: {
                     'Content-Type': 'application/json',
                     'X-CSRF-Token': this.csrfToken
                 },
-                body: JSON.stringify({ message })
+                body: JSON.stringify({ envelope })
             });
 
             if (!response.ok) {
@@ -251,6 +327,8 @@ class OrderChat {
             }
 
             const newMessage = await response.json();
+            this.decrypted.set(newMessage.id, message);
+            newMessage.message = message;
 
             // Clear input
             this.inputElement.value = '';
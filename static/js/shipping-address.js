@@ -0,0 +1,49 @@
+/**
+ * Shipping Address
+ *
+ * Decrypts the buyer's shipping address on the vendor's order page.
+ * The address is an envelope encrypted to the vendor's public key at
+ * checkout (see message-crypto.js); only this browser's private key opens it.
+ *
+ * @module shipping-address
+ */
+
+document.addEventListener('DOMContentLoaded', async () => {
+    const container = document.getElementById('shipping-envelope');
+    if (!container) {
+        return;
+    }
+
+    const addressEl = document.getElementById('shipping-envelope-address');
+    const notesTitleEl = document.getElementById('shipping-envelope-notes-title');
+    const notesEl = document.getElementById('shipping-envelope-notes');
+
+    const keyPair = MessageCrypto.loadKeyPair(container.dataset.username);
+    if (!keyPair) {
+        addressEl.textContent = '[No private key in this browser - open this order from the browser you registered your key with]';
+        return;
+    }
+
+    try {
+        const envelope = JSON.parse(container.dataset.envelope);
+        const plaintext = await MessageCrypto.decrypt(envelope, container.dataset.userId, keyPair.privateJwk);
+        if (plaintext === null) {
+            addressEl.textContent = '[Address not encrypted for your key]';
+            return;
+        }
+
+        const shipping = JSON.parse(plaintext);
+        addressEl.textContent = [shipping.street, `${shipping.postal_code} ${shipping.city}`, shipping.country]
+            .filter(Boolean)
+            .join('\n');
+
+        if (shipping.notes) {
+            notesEl.textContent = shipping.notes;
+            notesTitleEl.style.display = '';
+            notesEl.style.display = '';
+        }
+    } catch (error) {
+        console.error('[ShippingAddress] Decryption failed:', error);
+        addressEl.textContent = '[Unable to decrypt - this browser holds a different key]';
+    }
+});
@@ -1,17 +1,101 @@
 /* tslint:disable */
 /* eslint-disable */
+
+/**
+ * Result of reputation file verification
+ */
+export class VerificationResult {
+    private constructor();
+    free(): void;
+    [Symbol.dispose](): void;
+    /**
+     * Convert to JavaScript object for detailed inspection
+     */
+    toJSON(): any;
+    readonly amended_reviews: number;
+    readonly error_message: string | undefined;
+    readonly file_signature_valid: boolean;
+    readonly foreign_reviews: number;
+    readonly invalid_replies: number;
+    readonly invalid_revisions: number;
+    readonly invalid_signatures: number;
+    readonly is_valid: boolean;
+    readonly score_match: boolean;
+    readonly signed: boolean;
+    readonly stats_match: boolean;
+    readonly total_reviews: number;
+    readonly unbound_reviews: number;
+    readonly valid_signatures: number;
+    readonly vendor_replies: number;
+    readonly verified_payments: number;
+}
+
+/**
+ * Get version information
+ */
+export function get_version(): string;
+
 /**
  * Initialize WASM module with panic hook for better debugging
  */
 export function init(): void;
+
+/**
+ * Recalculate the weighted score of a reputation file
+ *
+ * Uses the parameters of the file's score when it has one, the default
+ * parameters otherwise. The result is bit-for-bit the server's.
+ *
+ * # Arguments
+ * * `reputation_json` - JSON string of VendorReputation file
+ *
+ * # Returns
+ * * ReputationScore as a JavaScript object (`score_milli`, `dispute_rate_bps`...),
+ *   or `null` if the file does not parse
+ *
+ * # Example (JavaScript)
+ * ```javascript
+ * const score = reputation_score(reputationJson);
+ * console.log(`${(score.score_milli / 1000).toFixed(2)} stars`);
+ * ```
+ */
+export function reputation_score(reputation_json: string): any;
+
+/**
+ * Verify a Monero payment proof attached to a review
+ *
+ * Re-runs the wallet's `check_tx_proof` from the proof string and the
+ * public transaction data it carries, without trusting the marketplace.
+ *
+ * # Arguments
+ * * `proof_json` - JSON string of PaymentProof
+ *
+ * # Returns
+ * * `true` if the proof verifies and the address received `amount`
+ *
+ * # Example (JavaScript)
+ * ```javascript
+ * const review = reputation.reviews[0];
+ * const paid = verify_payment_proof(JSON.stringify(review.payment_proof));
+ * ```
+ */
+export function verify_payment_proof(proof_json: string): boolean;
+
 /**
  * Verify a complete vendor reputation file
  *
  * This is the main entry point for client-side verification.
  * It verifies:
  * 1. All signatures are valid
- * 2. Statistics match calculated values
- * 3. No tampering has occurred
+ * 2. Every 2.0 review was signed for this vendor
+ * 3. Statistics match calculated values
+ * 4. The vendor signature covers the whole file (format 2.0)
+ * 5. Amended reviews carry their full signed revision chain, and vendor
+ *    replies are signed by the file's vendor key
+ *
+ * 1.0 reviews are still accepted; they are counted in `unbound_reviews`
+ * since nothing ties them to this vendor. Unsigned 1.0 files are still
+ * accepted too, with `signed` false.
  *
  * # Arguments
  * * `reputation_json` - JSON string of VendorReputation file
@@ -36,23 +120,49 @@ export function init(): void;
  * ```
  */
 export function verify_reputation_file(reputation_json: string): VerificationResult;
+
+/**
+ * Verify the vendor reply attached to a review
+ *
+ * # Arguments
+ * * `review_json` - JSON string of SignedReview (with its `reply`)
+ * * `vendor_pubkey` - Vendor reputation key (base64)
+ *
+ * # Returns
+ * * `true` if the reply is signed by `vendor_pubkey` and answers one of the
+ *   review's versions (also `true` when there is no reply)
+ *
+ * # Example (JavaScript)
+ * ```javascript
+ * const review = reputation.reviews[0];
+ * const ok = verify_review_reply(JSON.stringify(review), reputation.vendor_pubkey);
+ * ```
+ */
+export function verify_review_reply(review_json: string, vendor_pubkey: string): boolean;
+
 /**
  * Verify a single review signature
  *
+ * For an amended review, the whole revision history is checked too.
+ *
  * # Arguments
  * * `review_json` - JSON string of SignedReview
  *
  * # Returns
- * * `true` if signature is valid, `false` otherwise
+ * * `true` if signature (and history) is valid, `false` otherwise
  *
  * # Example (JavaScript)
  * ```javascript
  * const reviewJson = JSON.stringify({
+ *   format_version: "2.0",
  *   txid: "abc123",
  *   rating: 5,
  *   comment: "Great!",
  *   timestamp: "2025-10-22T12:00:00Z",
  *   buyer_pubkey: "base64_pubkey",
+ *   vendor_pubkey: "vendor_pubkey",
+ *   escrow_id: "escrow_uuid",
+ *   marketplace_domain: "market.example.onion",
  *   signature: "base64_signature"
  * });
  *
@@ -60,69 +170,61 @@ export function verify_reputation_file(reputation_json: string): VerificationRes
  * ```
  */
 export function verify_single_review(review_json: string): boolean;
-/**
- * Get version information
- */
-export function get_version(): string;
-/**
- * Result of reputation file verification
- */
-export class VerificationResult {
-  private constructor();
-  free(): void;
-  [Symbol.dispose](): void;
-  /**
-   * Convert to JavaScript object for detailed inspection
-   */
-  toJSON(): any;
-  readonly is_valid: boolean;
-  readonly total_reviews: number;
-  readonly valid_signatures: number;
-  readonly invalid_signatures: number;
-  readonly stats_match: boolean;
-  readonly error_message: string | undefined;
-}
 
 export type InitInput = RequestInfo | URL | Response | BufferSource | WebAssembly.Module;
 
 export interface InitOutput {
-  readonly memory: WebAssembly.Memory;
-  readonly __wbg_verificationresult_free: (a: number, b: number) => void;
-  readonly verificationresult_is_valid: (a: number) => number;
-  readonly verificationresult_total_reviews: (a: number) => number;
-  readonly verificationresult_valid_signatures: (a: number) => number;
-  readonly verificationresult_invalid_signatures: (a: number) => number;
-  readonly verificationresult_stats_match: (a: number) => number;
-  readonly verificationresult_error_message: (a: number, b: number) => void;
-  readonly verificationresult_toJSON: (a: number) => number;
-  readonly verify_reputation_file: (a: number, b: number) => number;
-  readonly verify_single_review: (a: number, b: number) => number;
-  readonly get_version: (a: number) => void;
-  readonly init: () => void;
-  readonly __wbindgen_export_0: (a: number, b: number, c: number) => void;
-  readonly __wbindgen_export_1: (a: number, b: number) => number;
-  readonly __wbindgen_export_2: (a: number, b: number, c: number, d: number) => number;
-  readonly __wbindgen_add_to_stack_pointer: (a: number) => number;
-  readonly __wbindgen_start: () => void;
+    readonly memory: WebAssembly.Memory;
+    readonly __wbg_verificationresult_free: (a: number, b: number) => void;
+    readonly get_version: (a: number) => void;
+    readonly init: () => void;
+    readonly reputation_score: (a: number, b: number) => number;
+    readonly verificationresult_amended_reviews: (a: number) => number;
+    readonly verificationresult_error_message: (a: number, b: number) => void;
+    readonly verificationresult_file_signature_valid: (a: number) => number;
+    readonly verificationresult_foreign_reviews: (a: number) => number;
+    readonly verificationresult_invalid_replies: (a: number) => number;
+    readonly verificationresult_invalid_revisions: (a: number) => number;
+    readonly verificationresult_invalid_signatures: (a: number) => number;
+    readonly verificationresult_is_valid: (a: number) => number;
+    readonly verificationresult_score_match: (a: number) => number;
+    readonly verificationresult_signed: (a: number) => number;
+    readonly verificationresult_stats_match: (a: number) => number;
+    readonly verificationresult_toJSON: (a: number) => number;
+    readonly verificationresult_total_reviews: (a: number) => number;
+    readonly verificationresult_unbound_reviews: (a: number) => number;
+    readonly verificationresult_valid_signatures: (a: number) => number;
+    readonly verificationresult_vendor_replies: (a: number) => number;
+    readonly verificationresult_verified_payments: (a: number) => number;
+    readonly verify_payment_proof: (a: number, b: number) => number;
+    readonly verify_reputation_file: (a: number, b: number) => number;
+    readonly verify_review_reply: (a: number, b: number, c: number, d: number) => number;
+    readonly verify_single_review: (a: number, b: number) => number;
+    readonly __wbindgen_export: (a: number, b: number, c: number) => void;
+    readonly __wbindgen_export2: (a: number, b: number) => number;
+    readonly __wbindgen_export3: (a: number, b: number, c: number, d: number) => number;
+    readonly __wbindgen_add_to_stack_pointer: (a: number) => number;
+    readonly __wbindgen_start: () => void;
 }
 
 export type SyncInitInput = BufferSource | WebAssembly.Module;
+
 /**
-* Instantiates the given `module`, which can either be bytes or
-* a precompiled `WebAssembly.Module`.
-*
-* @param {{ module: SyncInitInput }} module - Passing `SyncInitInput` directly is deprecated.
-*
-* @returns {InitOutput}
-*/
+ * Instantiates the given `module`, which can either be bytes or
+ * a precompiled `WebAssembly.Module`.
+ *
+ * @param {{ module: SyncInitInput }} module - Passing `SyncInitInput` directly is deprecated.
+ *
+ * @returns {InitOutput}
+ */
 export function initSync(module: { module: SyncInitInput } | SyncInitInput): InitOutput;
 
 /**
-* If `module_or_path` is {RequestInfo} or {URL}, makes a request and
-* for everything else, calls `WebAssembly.instantiate` directly.
-*
-* @param {{ module_or_path: InitInput | Promise<InitInput> }} module_or_path - Passing `InitInput` directly is deprecated.
-*
-* @returns {Promise<InitOutput>}
-*/
+ * If `module_or_path` is {RequestInfo} or {URL}, makes a request and
+ * for everything else, calls `WebAssembly.instantiate` directly.
+ *
+ * @param {{ module_or_path: InitInput | Promise<InitInput> }} module_or_path - Passing `InitInput` directly is deprecated.
+ *
+ * @returns {Promise<InitOutput>}
+ */
 export default function __wbg_init (module_or_path?: { module_or_path: InitInput | Promise<InitInput> } | InitInput | Promise<InitInput>): Promise<InitOutput>;
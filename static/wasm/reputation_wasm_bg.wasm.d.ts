@@ -2,19 +2,32 @@
 /* eslint-disable */
 export const memory: WebAssembly.Memory;
 export const __wbg_verificationresult_free: (a: number, b: number) => void;
-export const verificationresult_is_valid: (a: number) => number;
-export const verificationresult_total_reviews: (a: number) => number;
-export const verificationresult_valid_signatures: (a: number) => number;
+export const get_version: (a: number) => void;
+export const init: () => void;
+export const reputation_score: (a: number, b: number) => number;
+export const verificationresult_amended_reviews: (a: number) => number;
+export const verificationresult_error_message: (a: number, b: number) => void;
+export const verificationresult_file_signature_valid: (a: number) => number;
+export const verificationresult_foreign_reviews: (a: number) => number;
+export const verificationresult_invalid_replies: (a: number) => number;
+export const verificationresult_invalid_revisions: (a: number) => number;
 export const verificationresult_invalid_signatures: (a: number) => number;
+export const verificationresult_is_valid: (a: number) => number;
+export const verificationresult_score_match: (a: number) => number;
+export const verificationresult_signed: (a: number) => number;
 export const verificationresult_stats_match: (a: number) => number;
-export const verificationresult_error_message: (a: number, b: number) => void;
 export const verificationresult_toJSON: (a: number) => number;
+export const verificationresult_total_reviews: (a: number) => number;
+export const verificationresult_unbound_reviews: (a: number) => number;
+export const verificationresult_valid_signatures: (a: number) => number;
+export const verificationresult_vendor_replies: (a: number) => number;
+export const verificationresult_verified_payments: (a: number) => number;
+export const verify_payment_proof: (a: number, b: number) => number;
 export const verify_reputation_file: (a: number, b: number) => number;
+export const verify_review_reply: (a: number, b: number, c: number, d: number) => number;
 export const verify_single_review: (a: number, b: number) => number;
-export const get_version: (a: number) => void;
-export const init: () => void;
-export const __wbindgen_export_0: (a: number, b: number, c: number) => void;
-export const __wbindgen_export_1: (a: number, b: number) => number;
-export const __wbindgen_export_2: (a: number, b: number, c: number, d: number) => number;
+export const __wbindgen_export: (a: number, b: number, c: number) => void;
+export const __wbindgen_export2: (a: number, b: number) => number;
+export const __wbindgen_export3: (a: number, b: number, c: number, d: number) => number;
 export const __wbindgen_add_to_stack_pointer: (a: number) => number;
 export const __wbindgen_start: () => void;
@@ -0,0 +1,122 @@
+//! Simulated chain clock for the monitoring services
+//!
+//! `SimulatedChain` implements [`ChainClock`] on top of a [`MockNetwork`]:
+//! its block height is the mock chain height and its time only moves when a
+//! test mines blocks (120s each) or calls [`SimulatedChain::advance`]. Hand
+//! it to `BlockchainMonitor::with_clock` / `TimeoutMonitor::with_clock` and
+//! call their poll methods directly instead of waiting on real intervals.
+
+use crate::mock_rpc::MockNetwork;
+use chrono::{DateTime, Duration, Utc};
+use monero_marketplace_common::clock::{ChainClock, SharedClock};
+use std::sync::Arc;
+
+/// Test clock driven by a mock chain (cheap to clone)
+#[derive(Debug, Clone)]
+pub struct SimulatedChain {
+    network: MockNetwork,
+    /// Simulated time at `network_origin`
+    origin: DateTime<Utc>,
+    /// Mock chain timestamp when the clock was created
+    network_origin: u64,
+}
+
+impl SimulatedChain {
+    /// New clock over `network`, starting at the current wall-clock time
+    pub fn new(network: &MockNetwork) -> Self {
+        Self::starting_at(network, Utc::now())
+    }
+
+    /// New clock over `network`, starting at `origin`
+    pub fn starting_at(network: &MockNetwork, origin: DateTime<Utc>) -> Self {
+        Self {
+            network: network.clone(),
+            origin,
+            network_origin: network.timestamp(),
+        }
+    }
+
+    /// This clock as a shared handle for the monitors
+    pub fn shared(&self) -> SharedClock {
+        Arc::new(self.clone())
+    }
+
+    /// Underlying mock chain
+    pub fn network(&self) -> &MockNetwork {
+        &self.network
+    }
+
+    /// Let `duration` pass without mining (negative durations are ignored)
+    pub fn advance(&self, duration: Duration) {
+        self.network
+            .advance_time(duration.num_seconds().max(0) as u64);
+    }
+
+    /// Mine `count` blocks, advancing time by the block target each
+    pub fn mine_blocks(&self, count: u64) {
+        self.network.mine_blocks(count);
+    }
+
+    /// Mine blocks until at least `duration` has passed
+    pub fn mine_for(&self, duration: Duration) {
+        let block_time = monero_marketplace_common::clock::MONERO_BLOCK_TIME_SECS as i64;
+        let blocks = (duration.num_seconds().max(0) + block_time - 1) / block_time;
+        self.mine_blocks(blocks as u64);
+    }
+}
+
+impl ChainClock for SimulatedChain {
+    fn now(&self) -> DateTime<Utc> {
+        let elapsed = self.network.timestamp().saturating_sub(self.network_origin);
+        self.origin + Duration::seconds(elapsed as i64)
+    }
+
+    fn block_height(&self) -> Option<u64> {
+        Some(self.network.height())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mining_moves_time_and_height() {
+        let network = MockNetwork::new();
+        let chain = SimulatedChain::new(&network);
+        let start = chain.now();
+        let height = chain.block_height().unwrap();
+
+        chain.mine_blocks(5);
+        assert_eq!(chain.block_height(), Some(height + 5));
+        assert_eq!(chain.now() - start, Duration::seconds(600));
+
+        chain.advance(Duration::hours(1));
+        assert_eq!(chain.block_height(), Some(height + 5));
+        assert_eq!(chain.now() - start, Duration::seconds(600 + 3600));
+    }
+
+    #[test]
+    fn test_mine_for_rounds_up_to_whole_blocks() {
+        let network = MockNetwork::new();
+        let chain = SimulatedChain::new(&network);
+        let height = network.height();
+
+        chain.mine_for(Duration::seconds(121));
+        assert_eq!(network.height(), height + 2);
+    }
+
+    #[test]
+    fn test_confirmations_follow_mock_chain() {
+        let network = MockNetwork::new();
+        let chain = SimulatedChain::new(&network);
+
+        let txid = network.fund("9someaddress", 1);
+        chain.mine_blocks(3);
+        let tx_height = network.transfer(&txid).unwrap().height.unwrap();
+        assert_eq!(
+            chain.confirmations_at(tx_height),
+            network.confirmations(&txid)
+        );
+    }
+}
@@ -0,0 +1,11 @@
+//! Test support for Monero Marketplace
+//!
+//! This crate provides test doubles used by the integration tests of the
+//! `wallet` and `server` crates so that escrow flows can run in CI without
+//! a monerod node or `monero-wallet-rpc` instances.
+
+pub mod chain;
+pub mod mock_rpc;
+
+pub use chain::SimulatedChain;
+pub use mock_rpc::{MockNetwork, MockWalletRpc};
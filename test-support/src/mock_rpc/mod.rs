@@ -0,0 +1,291 @@
+//! In-process mock of `monero-wallet-rpc`
+//!
+//! Speaks the JSON-RPC subset used by `MoneroRpcClient`: wallet file
+//! management, the multisig setup rounds (prepare/make/exchange and
+//! export/import), `transfer` + `sign_multisig` + `submit_multisig`,
+//! `get_balance`, `get_transfers` (incoming) and `get_transfer_by_txid`.
+//!
+//! # Example
+//! ```no_run
+//! # use monero_marketplace_test_support::mock_rpc::{MockNetwork, MockWalletRpc};
+//! # async fn example() -> anyhow::Result<()> {
+//! let network = MockNetwork::new();
+//! let buyer = MockWalletRpc::start("buyer", &network).await?;
+//! let vendor = MockWalletRpc::start("vendor", &network).await?;
+//! let arbiter = MockWalletRpc::start("arbiter", &network).await?;
+//!
+//! // Point MoneroConfig::rpc_url at buyer.url(), vendor.url(), arbiter.url()
+//! // and drive the 2-of-3 flow, then fund the multisig address:
+//! # let multisig_address = String::new();
+//! network.fund_unlocked(&multisig_address, 1_000_000_000_000);
+//!
+//! // Make the next sign_multisig on the arbiter fail once
+//! arbiter.fail_next("sign_multisig", "wallet locked");
+//! # Ok(())
+//! # }
+//! ```
+
+mod network;
+mod wallet;
+
+pub use network::{MockNetwork, MockTransfer, DEFAULT_UNLOCK_WINDOW, MOCK_TX_FEE};
+pub use wallet::DEFAULT_WALLET;
+
+use actix_web::{dev::ServerHandle, web, App, HttpResponse, HttpServer};
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex, MutexGuard};
+use wallet::{InjectedFailure, RpcState};
+
+/// Generic JSON-RPC error code used for injected failures
+const INJECTED_ERROR_CODE: i32 = -1;
+
+type SharedState = Arc<Mutex<RpcState>>;
+
+/// A running mock `monero-wallet-rpc` bound to `127.0.0.1` on a random port
+///
+/// The server is stopped when the handle is dropped.
+pub struct MockWalletRpc {
+    url: String,
+    port: u16,
+    state: SharedState,
+    network: MockNetwork,
+    handle: ServerHandle,
+}
+
+impl MockWalletRpc {
+    /// Start a new instance attached to `network`
+    ///
+    /// `label` only serves to derive distinct wallet addresses per instance
+    /// (use "buyer", "vendor", "arbiter", ...).
+    pub async fn start(label: &str, network: &MockNetwork) -> Result<Self> {
+        let state: SharedState = Arc::new(Mutex::new(RpcState::new(label, network.clone())));
+        let app_state = web::Data::new(state.clone());
+
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(app_state.clone())
+                .default_service(web::post().to(handle_json_rpc))
+        })
+        .workers(1)
+        .disable_signals()
+        .bind(("127.0.0.1", 0))
+        .context("Failed to bind mock wallet RPC")?;
+
+        let addr = server
+            .addrs()
+            .first()
+            .copied()
+            .context("Mock wallet RPC has no bound address")?;
+
+        let server = server.run();
+        let handle = server.handle();
+        tokio::spawn(server);
+
+        tracing::debug!("Mock wallet RPC '{}' listening on {}", label, addr);
+
+        Ok(Self {
+            url: format!("http://{}", addr),
+            port: addr.port(),
+            state,
+            network: network.clone(),
+            handle,
+        })
+    }
+
+    /// Base URL to use as `MoneroConfig::rpc_url` (the client appends `/json_rpc`)
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Port bound on 127.0.0.1 (what `WalletPool` expects)
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Chain shared with the other instances
+    pub fn network(&self) -> &MockNetwork {
+        &self.network
+    }
+
+    fn lock(&self) -> MutexGuard<'_, RpcState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Address of the open wallet (the multisig address once finalized)
+    pub fn address(&self) -> Option<String> {
+        self.lock().current_address()
+    }
+
+    /// Fail the next call to `method` with an RPC error carrying `message`
+    ///
+    /// `MoneroRpcClient` maps messages by content, e.g. "wallet locked" becomes
+    /// `MoneroError::WalletLocked` and "busy" becomes `MoneroError::WalletBusy`.
+    pub fn fail_next(&self, method: &str, message: &str) {
+        self.fail_times(method, 1, message);
+    }
+
+    /// Fail the next `times` calls to `method`
+    ///
+    /// Note that prepare/make/export/import are retried up to three times by
+    /// the client, so use `times >= 4` to make them fail for good.
+    pub fn fail_times(&self, method: &str, times: usize, message: &str) {
+        self.lock().failures.insert(
+            method.to_string(),
+            InjectedFailure {
+                remaining: Some(times),
+                code: INJECTED_ERROR_CODE,
+                message: message.to_string(),
+            },
+        );
+    }
+
+    /// Fail every call to `method` until [`MockWalletRpc::clear_failures`]
+    pub fn fail_always(&self, method: &str, message: &str) {
+        self.lock().failures.insert(
+            method.to_string(),
+            InjectedFailure {
+                remaining: None,
+                code: INJECTED_ERROR_CODE,
+                message: message.to_string(),
+            },
+        );
+    }
+
+    /// Remove every scripted failure
+    pub fn clear_failures(&self) {
+        self.lock().failures.clear();
+    }
+
+    /// Methods received so far, in order
+    pub fn calls(&self) -> Vec<String> {
+        self.lock().calls.clone()
+    }
+
+    /// Number of times `method` was called
+    pub fn call_count(&self, method: &str) -> usize {
+        self.lock().calls.iter().filter(|m| *m == method).count()
+    }
+
+    /// Call `method` in-process, bypassing HTTP
+    ///
+    /// Goes through the same dispatch as JSON-RPC requests (including
+    /// scripted failures and the call log). Handy to seed wallet files
+    /// before the code under test opens them.
+    pub fn call(&self, method: &str, params: Value) -> Result<Value> {
+        self.lock()
+            .dispatch(method, &params)
+            .map_err(|(code, message)| anyhow!("{} failed ({}): {}", method, code, message))
+    }
+
+    /// Stop the HTTP server and wait for it to shut down
+    pub async fn shutdown(self) {
+        self.handle.stop(false).await;
+    }
+}
+
+/// Create `filenames[i]` on `rpcs[i]` and run the 2-of-3 key exchange on them
+///
+/// Leaves each wallet open and multisig-ready, as `WalletManager` does at the
+/// end of escrow setup. Returns the shared multisig address.
+pub fn create_multisig_wallets(rpcs: [&MockWalletRpc; 3], filenames: [&str; 3]) -> Result<String> {
+    for (rpc, filename) in rpcs.iter().zip(filenames) {
+        rpc.call(
+            "create_wallet",
+            json!({ "filename": filename, "password": "", "language": "English" }),
+        )?;
+    }
+
+    let infos_of = |results: &[Value]| -> Vec<String> {
+        results
+            .iter()
+            .map(|r| r["multisig_info"].as_str().unwrap_or_default().to_string())
+            .collect()
+    };
+    let others = |infos: &[String], i: usize| -> Vec<String> {
+        infos
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, info)| info.clone())
+            .collect()
+    };
+
+    let prepared = rpcs
+        .iter()
+        .map(|rpc| rpc.call("prepare_multisig", json!({})))
+        .collect::<Result<Vec<_>>>()?;
+    let prepared = infos_of(&prepared);
+
+    let mut made = Vec::new();
+    for (i, rpc) in rpcs.iter().enumerate() {
+        made.push(rpc.call(
+            "make_multisig",
+            json!({ "threshold": 2, "multisig_info": others(&prepared, i), "password": "" }),
+        )?);
+    }
+    let mut round = infos_of(&made);
+
+    let mut address = String::new();
+    for _ in 0..2 {
+        let mut results = Vec::new();
+        for (i, rpc) in rpcs.iter().enumerate() {
+            results.push(rpc.call(
+                "exchange_multisig_keys",
+                json!({ "multisig_info": others(&round, i), "password": "" }),
+            )?);
+        }
+        address = results[0]["address"].as_str().unwrap_or_default().to_string();
+        round = infos_of(&results);
+    }
+
+    if address.is_empty() {
+        return Err(anyhow!("Multisig key exchange did not finalize"));
+    }
+    Ok(address)
+}
+
+impl Drop for MockWalletRpc {
+    fn drop(&mut self) {
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let handle = self.handle.clone();
+            runtime.spawn(async move { handle.stop(false).await });
+        }
+    }
+}
+
+async fn handle_json_rpc(state: web::Data<SharedState>, body: web::Bytes) -> HttpResponse {
+    let request: Value = match serde_json::from_slice(&body) {
+        Ok(value) => value,
+        Err(_) => {
+            return HttpResponse::Ok().json(json!({
+                "jsonrpc": "2.0",
+                "id": "0",
+                "error": { "code": -32700, "message": "Parse error" },
+            }))
+        }
+    };
+
+    let id = request["id"].clone();
+    let method = request["method"].as_str().unwrap_or_default();
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+    let outcome = state
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .dispatch(method, &params);
+
+    let body = match outcome {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => {
+            tracing::debug!("Mock wallet RPC {} -> error {}: {}", method, code, message);
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            })
+        }
+    };
+
+    HttpResponse::Ok().json(body)
+}
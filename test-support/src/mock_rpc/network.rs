@@ -0,0 +1,309 @@
+//! Shared fake blockchain backing every mock wallet RPC instance
+//!
+//! Real `monero-wallet-rpc` processes all observe the same chain, so the
+//! buyer, vendor and arbiter instances of a test share one `MockNetwork`.
+//! It tracks block height, transfers (mempool and mined) and balances per
+//! address, which is enough for the escrow flows to agree on funding and
+//! on the outcome of a multisig spend.
+
+use monero_marketplace_common::clock::MONERO_BLOCK_TIME_SECS;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Default number of confirmations before an output becomes spendable
+pub const DEFAULT_UNLOCK_WINDOW: u64 = 10;
+
+/// Fee charged by the mock for every transfer (0.0001 XMR)
+pub const MOCK_TX_FEE: u64 = 100_000_000;
+
+/// Starting height of a fresh mock chain
+const GENESIS_HEIGHT: u64 = 1_000;
+
+/// A transaction known to the mock chain
+#[derive(Debug, Clone)]
+pub struct MockTransfer {
+    pub txid: String,
+    /// Spending address, `None` for coinbase-like funding injected by tests
+    pub source: Option<String>,
+    pub destinations: Vec<(String, u64)>,
+    pub fee: u64,
+    /// Block height, `None` while still in the mempool
+    pub height: Option<u64>,
+    pub timestamp: u64,
+}
+
+impl MockTransfer {
+    /// Sum of all outputs of this transfer
+    pub fn total_amount(&self) -> u64 {
+        self.destinations.iter().map(|(_, amount)| amount).sum()
+    }
+
+    /// Sum of outputs paying `address`
+    pub fn amount_to(&self, address: &str) -> u64 {
+        self.destinations
+            .iter()
+            .filter(|(dest, _)| dest == address)
+            .map(|(_, amount)| amount)
+            .sum()
+    }
+}
+
+#[derive(Debug)]
+struct NetworkState {
+    height: u64,
+    timestamp: u64,
+    unlock_window: u64,
+    transfers: Vec<MockTransfer>,
+    balance_overrides: HashMap<String, (u64, u64)>,
+    id_counter: u64,
+}
+
+/// Handle to the shared fake chain (cheap to clone)
+#[derive(Debug, Clone)]
+pub struct MockNetwork {
+    state: Arc<Mutex<NetworkState>>,
+}
+
+impl Default for MockNetwork {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockNetwork {
+    /// Create an empty chain at a fixed starting height
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(NetworkState {
+                height: GENESIS_HEIGHT,
+                timestamp: 1_700_000_000,
+                unlock_window: DEFAULT_UNLOCK_WINDOW,
+                transfers: Vec::new(),
+                balance_overrides: HashMap::new(),
+                id_counter: 0,
+            })),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, NetworkState> {
+        // A panicking test must not poison the chain for the other instances
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Current chain height
+    pub fn height(&self) -> u64 {
+        self.lock().height
+    }
+
+    /// Unix timestamp of the chain tip (advances 120s per mined block)
+    pub fn timestamp(&self) -> u64 {
+        self.lock().timestamp
+    }
+
+    /// Let `secs` of wall-clock time pass without mining a block
+    pub fn advance_time(&self, secs: u64) {
+        self.lock().timestamp += secs;
+    }
+
+    /// Change the number of confirmations needed to unlock outputs
+    pub fn set_unlock_window(&self, blocks: u64) {
+        self.lock().unlock_window = blocks;
+    }
+
+    /// Mine `count` blocks, including every mempool transaction in the first one
+    pub fn mine_blocks(&self, count: u64) {
+        let mut state = self.lock();
+        for _ in 0..count {
+            state.height += 1;
+            state.timestamp += MONERO_BLOCK_TIME_SECS;
+            let height = state.height;
+            for transfer in state.transfers.iter_mut().filter(|t| t.height.is_none()) {
+                transfer.height = Some(height);
+            }
+        }
+    }
+
+    /// Send `amount` to `address` from outside the marketplace (mempool)
+    ///
+    /// Returns the txid of the funding transaction.
+    pub fn fund(&self, address: &str, amount: u64) -> String {
+        self.push_transfer(None, vec![(address.to_string(), amount)], 0)
+    }
+
+    /// Send `amount` to `address` and mine enough blocks to unlock it
+    pub fn fund_unlocked(&self, address: &str, amount: u64) -> String {
+        let txid = self.fund(address, amount);
+        let window = self.lock().unlock_window;
+        self.mine_blocks(window);
+        txid
+    }
+
+    /// Force `get_balance` for `address` to return `(unlocked, total)`
+    pub fn set_balance(&self, address: &str, unlocked: u64, total: u64) {
+        self.lock()
+            .balance_overrides
+            .insert(address.to_string(), (unlocked, total));
+    }
+
+    /// Remove a balance override set with [`MockNetwork::set_balance`]
+    pub fn clear_balance(&self, address: &str) {
+        self.lock().balance_overrides.remove(address);
+    }
+
+    /// Balance of `address` as `(unlocked, total)` in atomic units
+    pub fn balance(&self, address: &str) -> (u64, u64) {
+        let state = self.lock();
+        if let Some(balance) = state.balance_overrides.get(address) {
+            return *balance;
+        }
+
+        let mut total: i128 = 0;
+        let mut unlocked: i128 = 0;
+        for transfer in &state.transfers {
+            let incoming = transfer.amount_to(address) as i128;
+            if incoming > 0 {
+                total += incoming;
+                let confirmations = transfer
+                    .height
+                    .map(|h| state.height - h + 1)
+                    .unwrap_or(0);
+                if confirmations >= state.unlock_window {
+                    unlocked += incoming;
+                }
+            }
+            if transfer.source.as_deref() == Some(address) {
+                let outgoing = (transfer.total_amount() + transfer.fee) as i128;
+                total -= outgoing;
+                unlocked -= outgoing;
+            }
+        }
+
+        (unlocked.max(0) as u64, total.max(0) as u64)
+    }
+
+    /// Look up a transaction by id
+    pub fn transfer(&self, txid: &str) -> Option<MockTransfer> {
+        self.lock().transfers.iter().find(|t| t.txid == txid).cloned()
+    }
+
+    /// All transactions touching `address` (incoming or outgoing)
+    pub fn transfers_for(&self, address: &str) -> Vec<MockTransfer> {
+        self.lock()
+            .transfers
+            .iter()
+            .filter(|t| t.source.as_deref() == Some(address) || t.amount_to(address) > 0)
+            .cloned()
+            .collect()
+    }
+
+    /// Number of confirmations of `txid` (0 while in the mempool)
+    pub fn confirmations(&self, txid: &str) -> Option<u64> {
+        let state = self.lock();
+        state
+            .transfers
+            .iter()
+            .find(|t| t.txid == txid)
+            .map(|t| t.height.map(|h| state.height - h + 1).unwrap_or(0))
+    }
+
+    /// Relay a spend from `source` to the mempool
+    pub(crate) fn relay(
+        &self,
+        txid: &str,
+        source: &str,
+        destinations: Vec<(String, u64)>,
+        fee: u64,
+    ) {
+        let mut state = self.lock();
+        let timestamp = state.timestamp;
+        state.transfers.push(MockTransfer {
+            txid: txid.to_string(),
+            source: Some(source.to_string()),
+            destinations,
+            fee,
+            height: None,
+            timestamp,
+        });
+    }
+
+    fn push_transfer(
+        &self,
+        source: Option<String>,
+        destinations: Vec<(String, u64)>,
+        fee: u64,
+    ) -> String {
+        let txid = self.next_hex_id("tx");
+        let mut state = self.lock();
+        let timestamp = state.timestamp;
+        state.transfers.push(MockTransfer {
+            txid: txid.clone(),
+            source,
+            destinations,
+            fee,
+            height: None,
+            timestamp,
+        });
+        txid
+    }
+
+    /// Deterministic, unique 64-char hex identifier
+    pub(crate) fn next_hex_id(&self, domain: &str) -> String {
+        let counter = {
+            let mut state = self.lock();
+            state.id_counter += 1;
+            state.id_counter
+        };
+        hex_digest(&[domain.as_bytes(), &counter.to_le_bytes()])
+    }
+}
+
+/// SHA256 over the concatenation of `parts`, hex encoded
+pub(crate) fn hex_digest(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hex::encode(hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_funding_unlocks_after_window() {
+        let network = MockNetwork::new();
+        network.fund("9addr", 5_000);
+        assert_eq!(network.balance("9addr"), (0, 5_000));
+
+        network.mine_blocks(DEFAULT_UNLOCK_WINDOW - 1);
+        assert_eq!(network.balance("9addr"), (0, 5_000));
+
+        network.mine_blocks(1);
+        assert_eq!(network.balance("9addr"), (5_000, 5_000));
+    }
+
+    #[test]
+    fn test_spend_debits_source_and_fee() {
+        let network = MockNetwork::new();
+        network.fund_unlocked("5multisig", 10_000);
+        network.relay("abc", "5multisig", vec![("9vendor".to_string(), 4_000)], 100);
+
+        assert_eq!(network.balance("5multisig"), (5_900, 5_900));
+        assert_eq!(network.balance("9vendor"), (0, 4_000));
+        assert_eq!(network.confirmations("abc"), Some(0));
+
+        network.mine_blocks(1);
+        assert_eq!(network.confirmations("abc"), Some(1));
+    }
+
+    #[test]
+    fn test_balance_override() {
+        let network = MockNetwork::new();
+        network.set_balance("9addr", 1, 2);
+        assert_eq!(network.balance("9addr"), (1, 2));
+        network.clear_balance("9addr");
+        assert_eq!(network.balance("9addr"), (0, 0));
+    }
+}
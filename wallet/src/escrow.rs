@@ -11,13 +11,21 @@ use tracing::info;
 
 use crate::client::MoneroClient;
 use monero_marketplace_common::{
-    Amount, Error, Escrow, EscrowData, EscrowId, EscrowResult, EscrowState, MoneroAddress,
+    Amount, Error, Escrow, EscrowData, EscrowId, EscrowResult, EscrowStatus, MoneroAddress,
     TransferDestination, TxHash, UserId,
 };
 
 /// In-memory storage for escrows (will be replaced with database in Phase 2)
 type EscrowStorage = Arc<RwLock<HashMap<EscrowId, Escrow>>>;
 
+/// Current Unix time in seconds (escrow `updated_at`)
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 /// Manages escrow operations and state transitions
 pub struct EscrowManager {
     /// In-memory storage for escrows
@@ -103,7 +111,7 @@ impl EscrowManager {
         let mut escrow = self.get_escrow(escrow_id).await?;
 
         // Validate escrow can be funded
-        if !escrow.can_be_funded() {
+        if !escrow.state.can_transition_to(EscrowStatus::Active) {
             return Err(Error::InvalidState(format!(
                 "Escrow {} cannot be funded in state {:?}",
                 escrow_id, escrow.state
@@ -117,9 +125,8 @@ impl EscrowManager {
             .context("Failed to verify funding transaction")?;
 
         // Update escrow state
-        escrow
-            .transition_to(EscrowState::Funded)
-            .map_err(Error::InvalidState)?;
+        escrow.state = EscrowStatus::Active;
+        escrow.updated_at = unix_now();
         escrow.funding_tx_hash = Some(funding_tx_hash.clone());
 
         // Store updated escrow
@@ -158,7 +165,7 @@ impl EscrowManager {
         }
 
         // Validate escrow can be released
-        if !escrow.can_be_released() {
+        if !escrow.state.can_transition_to(EscrowStatus::Releasing) {
             return Err(Error::InvalidState(format!(
                 "Escrow {} cannot be released in state {:?}",
                 escrow_id, escrow.state
@@ -174,9 +181,8 @@ impl EscrowManager {
 
         // Update escrow state
         let mut updated_escrow = escrow.clone();
-        updated_escrow
-            .transition_to(EscrowState::Released)
-            .map_err(Error::InvalidState)?;
+        updated_escrow.state = EscrowStatus::Releasing;
+        updated_escrow.updated_at = unix_now();
 
         // Store updated escrow
         {
@@ -214,7 +220,7 @@ impl EscrowManager {
         }
 
         // Validate escrow can be refunded
-        if !escrow.can_be_refunded() {
+        if !escrow.state.can_transition_to(EscrowStatus::Refunding) {
             return Err(Error::InvalidState(format!(
                 "Escrow {} cannot be refunded in state {:?}",
                 escrow_id, escrow.state
@@ -230,9 +236,8 @@ impl EscrowManager {
 
         // Update escrow state
         let mut updated_escrow = escrow.clone();
-        updated_escrow
-            .transition_to(EscrowState::Refunded)
-            .map_err(Error::InvalidState)?;
+        updated_escrow.state = EscrowStatus::Refunding;
+        updated_escrow.updated_at = unix_now();
         updated_escrow.refund_tx_hash = Some(refund_tx_hash.clone());
 
         // Store updated escrow
@@ -275,7 +280,7 @@ impl EscrowManager {
         }
 
         // Validate escrow can be disputed
-        if !escrow.can_be_disputed() {
+        if !escrow.state.can_transition_to(EscrowStatus::Disputed) {
             return Err(Error::InvalidState(format!(
                 "Escrow {} cannot be disputed in state {:?}",
                 escrow_id, escrow.state
@@ -285,9 +290,8 @@ impl EscrowManager {
 
         // Update escrow state
         let mut updated_escrow = escrow.clone();
-        updated_escrow
-            .transition_to(EscrowState::Disputed)
-            .map_err(Error::InvalidState)?;
+        updated_escrow.state = EscrowStatus::Disputed;
+        updated_escrow.updated_at = unix_now();
         updated_escrow.dispute_reason = Some(reason.clone());
         updated_escrow.disputed_by = Some(disputed_by.clone());
 
@@ -538,7 +542,7 @@ mod tests {
                 assert_eq!(escrow.data.seller, "seller1");
                 assert_eq!(escrow.data.arbiter, "arbiter1");
                 assert_eq!(escrow.data.amount, 1000000000000);
-                assert_eq!(escrow.state, EscrowState::Created);
+                assert_eq!(escrow.state, EscrowStatus::Created);
                 assert!(escrow.funding_tx_hash.is_none());
                 assert!(escrow.release_tx_hash.is_none());
                 assert!(escrow.refund_tx_hash.is_none());
@@ -582,7 +586,7 @@ mod tests {
         // Full funding tests are in integration tests with testnet
 
         let escrow = manager.get_escrow_status(&escrow_id).await?;
-        assert_eq!(escrow.state, EscrowState::Created);
+        assert_eq!(escrow.state, EscrowStatus::Created);
         assert!(escrow.funding_tx_hash.is_none());
 
         Ok(())
@@ -1673,6 +1673,207 @@ impl MoneroRpcClient {
             fee: transfer["fee"].as_u64().unwrap_or(0),
         })
     }
+
+    /// List incoming transfers, mined and still in the pool
+    ///
+    /// Wraps `get_transfers` with `in` and `pool`. Pool entries have
+    /// `block_height == 0` and no confirmations.
+    pub async fn get_incoming_transfers(
+        &self,
+    ) -> Result<Vec<monero_marketplace_common::types::TransactionInfo>, MoneroError> {
+        use monero_marketplace_common::types::TransactionInfo;
+
+        // Acquérir permit pour rate limiting
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .map_err(|_| MoneroError::NetworkError("Semaphore closed".to_string()))?;
+
+        // Acquérir lock pour sérialiser les appels RPC
+        let _guard = self.rpc_lock.lock().await;
+
+        let mut request = RpcRequest::new("get_transfers");
+        request.params = Some(serde_json::json!({
+            "in": true,
+            "pool": true,
+        }));
+
+        let response = self
+            .client
+            .post(format!("{}/json_rpc", self.url))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_connect() {
+                    MoneroError::RpcUnreachable
+                } else {
+                    MoneroError::NetworkError(e.to_string())
+                }
+            })?;
+
+        let rpc_response: RpcResponse<serde_json::Value> = response
+            .json()
+            .await
+            .map_err(|e| MoneroError::InvalidResponse(format!("JSON parse: {}", e)))?;
+
+        if let Some(error) = rpc_response.error {
+            return Err(MoneroError::RpcError(error.message));
+        }
+
+        let result = rpc_response
+            .result
+            .ok_or_else(|| MoneroError::InvalidResponse("Missing result field".to_string()))?;
+
+        // Empty categories are omitted from the response
+        let transfers = ["in", "pool"]
+            .iter()
+            .filter_map(|category| result[*category].as_array())
+            .flatten()
+            .map(|transfer| TransactionInfo {
+                tx_hash: transfer["txid"].as_str().unwrap_or("").to_string(),
+                confirmations: transfer["confirmations"].as_u64().unwrap_or(0),
+                block_height: transfer["height"].as_u64().unwrap_or(0),
+                timestamp: transfer["timestamp"].as_u64().unwrap_or(0),
+                amount: transfer["amount"].as_u64().unwrap_or(0),
+                fee: transfer["fee"].as_u64().unwrap_or(0),
+            })
+            .collect();
+
+        Ok(transfers)
+    }
+
+    /// Generate a proof that a transaction paid an address
+    ///
+    /// An `OutProofV2` if this wallet sent the transaction, an `InProofV2` if
+    /// `address` belongs to it. `message` is signed with the proof, so the
+    /// proof cannot be reused for another purpose.
+    ///
+    /// # Returns
+    /// The proof string, to be checked with `check_tx_proof`
+    pub async fn get_tx_proof(
+        &self,
+        txid: &str,
+        address: &str,
+        message: &str,
+    ) -> Result<String, MoneroError> {
+        // Acquérir permit pour rate limiting
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .map_err(|_| MoneroError::NetworkError("Semaphore closed".to_string()))?;
+
+        // Acquérir lock pour sérialiser les appels RPC
+        let _guard = self.rpc_lock.lock().await;
+
+        let mut request = RpcRequest::new("get_tx_proof");
+        request.params = Some(serde_json::json!({
+            "txid": txid,
+            "address": address,
+            "message": message,
+        }));
+
+        let response = self
+            .client
+            .post(format!("{}/json_rpc", self.url))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_connect() {
+                    MoneroError::RpcUnreachable
+                } else {
+                    MoneroError::NetworkError(e.to_string())
+                }
+            })?;
+
+        let rpc_response: RpcResponse<serde_json::Value> = response
+            .json()
+            .await
+            .map_err(|e| MoneroError::InvalidResponse(format!("JSON parse: {}", e)))?;
+
+        if let Some(error) = rpc_response.error {
+            return Err(MoneroError::RpcError(error.message));
+        }
+
+        let result = rpc_response
+            .result
+            .ok_or_else(|| MoneroError::InvalidResponse("Missing result field".to_string()))?;
+
+        result["signature"]
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| MoneroError::InvalidResponse("Missing signature".to_string()))
+    }
+
+    /// Check a proof that a transaction paid an address
+    ///
+    /// Works in any wallet: the proof carries the shared secret, so no key
+    /// of the sender or recipient is needed.
+    ///
+    /// # Returns
+    /// TxProofCheck with the amount received and confirmation status
+    pub async fn check_tx_proof(
+        &self,
+        txid: &str,
+        address: &str,
+        message: &str,
+        signature: &str,
+    ) -> Result<monero_marketplace_common::types::TxProofCheck, MoneroError> {
+        // Acquérir permit pour rate limiting
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .map_err(|_| MoneroError::NetworkError("Semaphore closed".to_string()))?;
+
+        // Acquérir lock pour sérialiser les appels RPC
+        let _guard = self.rpc_lock.lock().await;
+
+        let mut request = RpcRequest::new("check_tx_proof");
+        request.params = Some(serde_json::json!({
+            "txid": txid,
+            "address": address,
+            "message": message,
+            "signature": signature,
+        }));
+
+        let response = self
+            .client
+            .post(format!("{}/json_rpc", self.url))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| {
+                if e.is_connect() {
+                    MoneroError::RpcUnreachable
+                } else {
+                    MoneroError::NetworkError(e.to_string())
+                }
+            })?;
+
+        let rpc_response: RpcResponse<serde_json::Value> = response
+            .json()
+            .await
+            .map_err(|e| MoneroError::InvalidResponse(format!("JSON parse: {}", e)))?;
+
+        if let Some(error) = rpc_response.error {
+            return Err(MoneroError::RpcError(error.message));
+        }
+
+        let result = rpc_response
+            .result
+            .ok_or_else(|| MoneroError::InvalidResponse("Missing result field".to_string()))?;
+
+        Ok(monero_marketplace_common::types::TxProofCheck {
+            good: result["good"].as_bool().unwrap_or(false),
+            received: result["received"].as_u64().unwrap_or(0),
+            in_pool: result["in_pool"].as_bool().unwrap_or(false),
+            confirmations: result["confirmations"].as_u64().unwrap_or(0),
+        })
+    }
 }
 
 /// Validation stricte multisig_info
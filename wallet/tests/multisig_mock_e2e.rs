@@ -0,0 +1,213 @@
+//! 2-of-3 multisig flow against the in-process mock wallet RPC
+//!
+//! Same flow as `multisig_e2e.rs` but backed by
+//! `monero_marketplace_test_support::mock_rpc`, so it runs in CI without
+//! monerod or monero-wallet-rpc.
+//!
+//! Execute: cargo test --package monero-marketplace-wallet --test multisig_mock_e2e
+
+use anyhow::Result;
+use monero_marketplace_common::error::Error;
+use monero_marketplace_common::types::{MoneroConfig, TransferDestination};
+use monero_marketplace_test_support::mock_rpc::{MockNetwork, MockWalletRpc, MOCK_TX_FEE};
+use monero_marketplace_wallet::MoneroClient;
+
+const ONE_XMR: u64 = 1_000_000_000_000;
+
+fn create_client(rpc: &MockWalletRpc) -> MoneroClient {
+    let config = MoneroConfig {
+        rpc_url: rpc.url().to_string(),
+        rpc_user: None,
+        rpc_password: None,
+        timeout_seconds: 10,
+    };
+    MoneroClient::new(config).expect("Failed to create client")
+}
+
+/// Run prepare → make → exchange (x2) → export/import on the 3 clients
+///
+/// Returns the shared multisig address.
+async fn setup_multisig(clients: &[&MoneroClient; 3]) -> Result<String> {
+    let mut prepare = Vec::new();
+    for client in clients {
+        prepare.push(client.multisig().prepare_multisig().await?.multisig_info);
+    }
+
+    let others = |infos: &[String], i: usize| -> Vec<String> {
+        infos
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, info)| info.clone())
+            .collect()
+    };
+
+    let mut round1 = Vec::new();
+    let mut addresses = Vec::new();
+    for (i, client) in clients.iter().enumerate() {
+        let made = client
+            .multisig()
+            .make_multisig(2, others(&prepare, i))
+            .await?;
+        addresses.push(made.address);
+        round1.push(made.multisig_info);
+    }
+    assert!(addresses.iter().all(|a| a == &addresses[0]));
+
+    let mut round2 = Vec::new();
+    for (i, client) in clients.iter().enumerate() {
+        let result = client
+            .multisig()
+            .exchange_multisig_keys(others(&round1, i))
+            .await?;
+        round2.push(result.multisig_info);
+    }
+
+    for (i, client) in clients.iter().enumerate() {
+        let result = client
+            .multisig()
+            .exchange_multisig_keys(others(&round2, i))
+            .await?;
+        assert_eq!(result.address, addresses[0]);
+        assert!(client.multisig().is_multisig().await?);
+    }
+
+    let mut exports = Vec::new();
+    for client in clients {
+        exports.push(client.multisig().export_multisig_info().await?.info);
+    }
+    for (i, client) in clients.iter().enumerate() {
+        client
+            .multisig()
+            .import_multisig_info(others(&exports, i))
+            .await?;
+    }
+
+    Ok(addresses.remove(0))
+}
+
+#[tokio::test]
+async fn test_full_2of3_release_flow_with_mock_rpc() -> Result<()> {
+    let network = MockNetwork::new();
+    let buyer_rpc = MockWalletRpc::start("buyer", &network).await?;
+    let vendor_rpc = MockWalletRpc::start("vendor", &network).await?;
+    let arbiter_rpc = MockWalletRpc::start("arbiter", &network).await?;
+
+    let buyer = create_client(&buyer_rpc);
+    let vendor = create_client(&vendor_rpc);
+    let arbiter = create_client(&arbiter_rpc);
+
+    let vendor_payout_address = vendor.get_address().await?;
+    let multisig_address = setup_multisig(&[&buyer, &vendor, &arbiter]).await?;
+    assert!(multisig_address.starts_with('5'));
+
+    // Buyer funds the escrow; nothing is spendable until unlocked
+    let funding_txid = network.fund(&multisig_address, 2 * ONE_XMR);
+    let (unlocked, total) = buyer.rpc().get_balance().await?;
+    assert_eq!((unlocked, total), (0, 2 * ONE_XMR));
+    network.mine_blocks(10);
+    let (unlocked, _) = arbiter.rpc().get_balance().await?;
+    assert_eq!(unlocked, 2 * ONE_XMR);
+
+    let funding = buyer
+        .transaction()
+        .get_transaction_info(funding_txid.clone())
+        .await?;
+    assert_eq!(funding.amount, 2 * ONE_XMR);
+    assert_eq!(funding.confirmations, 10);
+
+    // Release: buyer creates, arbiter co-signs, buyer submits
+    let created = buyer
+        .transaction()
+        .create_transaction(vec![TransferDestination {
+            address: vendor_payout_address.clone(),
+            amount: ONE_XMR,
+        }])
+        .await?;
+    assert_eq!(created.fee, MOCK_TX_FEE);
+
+    // One signature is not enough
+    let premature = buyer
+        .transaction()
+        .finalize_and_broadcast_transaction(created.multisig_txset.clone())
+        .await;
+    assert!(premature.is_err());
+
+    let signed = arbiter
+        .transaction()
+        .sign_multisig_transaction(created.multisig_txset.clone())
+        .await?;
+    let submitted = buyer
+        .transaction()
+        .finalize_and_broadcast_transaction(signed.tx_data_hex)
+        .await?;
+    assert_eq!(submitted.tx_hash_list, vec![created.tx_hash.clone()]);
+
+    network.mine_blocks(1);
+    let release = vendor
+        .transaction()
+        .get_transaction_info(created.tx_hash.clone())
+        .await?;
+    assert_eq!(release.confirmations, 1);
+    assert_eq!(release.amount, ONE_XMR);
+
+    let (_, vendor_total) = network.balance(&vendor_payout_address);
+    assert_eq!(vendor_total, ONE_XMR);
+    let (_, escrow_total) = network.balance(&multisig_address);
+    assert_eq!(escrow_total, ONE_XMR - MOCK_TX_FEE);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transfer_rejected_when_balance_insufficient() -> Result<()> {
+    let network = MockNetwork::new();
+    let rpcs = [
+        MockWalletRpc::start("buyer", &network).await?,
+        MockWalletRpc::start("vendor", &network).await?,
+        MockWalletRpc::start("arbiter", &network).await?,
+    ];
+    let clients = [
+        create_client(&rpcs[0]),
+        create_client(&rpcs[1]),
+        create_client(&rpcs[2]),
+    ];
+    let multisig_address = setup_multisig(&[&clients[0], &clients[1], &clients[2]]).await?;
+
+    // Scripted balance: funds visible but still locked
+    network.set_balance(&multisig_address, 0, ONE_XMR);
+    let result = clients[0]
+        .transaction()
+        .create_transaction(vec![TransferDestination {
+            address: multisig_address.clone(),
+            amount: ONE_XMR / 2,
+        }])
+        .await;
+    assert!(matches!(result, Err(Error::MoneroRpc(msg)) if msg.contains("not enough money")));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_injected_failures_surface_as_typed_errors() -> Result<()> {
+    let network = MockNetwork::new();
+    let rpc = MockWalletRpc::start("buyer", &network).await?;
+    let client = create_client(&rpc);
+
+    // prepare_multisig is retried 3 times by the client: 4 failures exhaust it
+    rpc.fail_times("prepare_multisig", 4, "wallet locked");
+    let result = client.multisig().prepare_multisig().await;
+    assert!(matches!(result, Err(Error::Wallet(msg)) if msg == "Wallet locked"));
+    assert_eq!(rpc.call_count("prepare_multisig"), 4);
+
+    // A single transient failure is absorbed by the retry loop
+    rpc.fail_next("prepare_multisig", "wallet busy");
+    assert!(client.multisig().prepare_multisig().await.is_ok());
+
+    rpc.fail_always("get_balance", "internal error");
+    assert!(client.get_wallet_status().await.is_err());
+    rpc.clear_failures();
+    assert!(client.get_wallet_status().await.is_ok());
+
+    Ok(())
+}